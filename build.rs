@@ -80,7 +80,7 @@ fn generate_humane_code(out_dir: &str, entries: &[ErrorEntry]) {
     fs::write(Path::new(out_dir).join("humane_code.rs"), code).unwrap();
 }
 
-// ── normalize_code() + get_explanation() + run_explain() ──
+// ── normalize_code() + get_explanation() + EXPLAIN_CODES ──
 
 fn generate_explain(out_dir: &str, entries: &[ErrorEntry]) {
     let mut normalize = String::from(
@@ -100,20 +100,13 @@ fn generate_explain(out_dir: &str, entries: &[ErrorEntry]) {
     }
     expl.push_str("        _ => None,\n    }\n}\n");
 
-    let run = r#"
-pub fn run_explain(code: &str) -> Result<(), String> {
-    let normalized = normalize_code(code);
-    match get_explanation(normalized) {
-        Some(expl) => { println!("{}", expl); Ok(()) }
-        None => Err(format!(
-            "unknown diagnostic code `{}`.\n\nTry a code like `S1002`, `S2002`, or `SNASK-SEM-TYPE-MISMATCH`.",
-            code
-        )),
+    let mut codes = String::from("pub const EXPLAIN_CODES: &[&str] = &[\n");
+    for e in entries {
+        codes.push_str(&format!("    \"{}\",\n", e.code));
     }
-}
-"#;
+    codes.push_str("];\n");
 
-    fs::write(Path::new(out_dir).join("explain.rs"), format!("{}{}{}", normalize, expl, run)).unwrap();
+    fs::write(Path::new(out_dir).join("explain.rs"), format!("{}{}{}", normalize, expl, codes)).unwrap();
 }
 
 // ── SemanticErrorKind enum + impl ──
@@ -166,7 +159,15 @@ fn generate_semantic_kind(out_dir: &str, entries: &[ErrorEntry]) {
         let pat = wildcard_pattern(&e.params, &v);
         msg_impl.push_str(&format!("            SemanticErrorKind::{} => \"{}\",\n", pat, e.full_code));
     }
-    msg_impl.push_str("        }\n    }\n}\n");
+    msg_impl.push_str("        }\n    }\n\n");
+
+    // ── impl is_warning() ──
+    let warnings: Vec<String> = sem.iter()
+        .filter(|e| e.severity == "Warning")
+        .map(|e| format!("SemanticErrorKind::{}", wildcard_pattern(&e.params, &variant_name(e))))
+        .collect();
+    let is_warning = if warnings.is_empty() { "false".to_string() } else { format!("matches!(self, {})", warnings.join(" | ")) };
+    msg_impl.push_str(&format!("    pub fn is_warning(&self) -> bool {{\n        {}\n    }}\n}}\n", is_warning));
 
     fs::write(Path::new(out_dir).join("semantic_kind.rs"), format!("{}{}", en, msg_impl)).unwrap();
 }
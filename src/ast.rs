@@ -2,15 +2,17 @@ use crate::span::{Position, Span};
 use crate::types::Type;
 
 // Moved from parser.rs to be a central part of the AST
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Location {
     pub line: usize,
     pub column: usize,
+    /// Byte offset into the source, so `&source[offset..]` starts here.
+    pub offset: usize,
 }
 
 impl Location {
     pub fn to_span(&self) -> Span {
-        let p = Position::from_line_col(self.line, self.column);
+        let p = Position::new(self.line, self.column, self.offset);
         Span::single(p)
     }
 }
@@ -32,11 +34,13 @@ pub enum BinaryOp {
     And,
     Or,
     Modulo,
+    Power,
     BitAnd,
     BitOr,
     BitXor,
     ShiftLeft,
     ShiftRight,
+    In,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -92,19 +96,36 @@ pub enum ExprKind {
         callee: Box<Expr>,
         args: Vec<Expr>,
     },
+    /// `target.property`, or `target?.property` when `optional`: nil
+    /// instead of a lookup when the target is nil.
     PropertyAccess {
         target: Box<Expr>,
         property: String,
+        optional: bool,
     },
     IndexAccess {
         target: Box<Expr>,
         index: Box<Expr>,
     },
+    /// `target[start:end:step]`; omitted parts are `None`.
+    Slice {
+        target: Box<Expr>,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+    },
     New {
         class: String,
         args: Vec<Expr>,
         strategy: MemoryStrategy,
     },
+    /// `Point { x: 1, y: 2 }`: a new object with each property given by name.
+    StructLiteral {
+        class: String,
+        fields: Vec<(String, Expr)>,
+    },
+    /// `a, b` in `return a, b;` or on the right of a destructuring assignment.
+    Tuple(Vec<Expr>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -183,6 +204,9 @@ pub struct FuncDecl {
     pub return_type: Option<Type>,
     pub body: Vec<Stmt>,
     pub is_unsafe: bool,
+    /// From the `fun` keyword to the end of the body; methods keep it even
+    /// though they are not statements of their own.
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -206,19 +230,45 @@ pub struct ConditionalStmt {
     pub else_block: Option<Vec<Stmt>>,
 }
 
+/// `while`/`for` loops. `else_block` runs when the loop ends because its
+/// condition failed or the iterable ran out, not when it is left early.
 #[derive(Debug, PartialEq, Clone)]
 pub enum LoopStmt {
     While {
         condition: Expr,
         body: Vec<Stmt>,
+        else_block: Option<Vec<Stmt>>,
     },
     For {
         iterator: String,
         iterable: Expr,
         body: Vec<Stmt>,
+        else_block: Option<Vec<Stmt>>,
     },
 }
 
+/// `print(args..., sep: expr, end: expr)`. Without `sep`/`end` the arguments
+/// are written back to back and followed by a newline.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrintStmt {
+    pub args: Vec<Expr>,
+    pub sep: Option<Expr>,
+    pub end: Option<Expr>,
+}
+
+impl PrintStmt {
+    pub fn exprs(&self) -> impl Iterator<Item = &Expr> {
+        self.args.iter().chain(self.sep.iter()).chain(self.end.iter())
+    }
+
+    pub fn exprs_mut(&mut self) -> impl Iterator<Item = &mut Expr> {
+        self.args
+            .iter_mut()
+            .chain(self.sep.iter_mut())
+            .chain(self.end.iter_mut())
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct ListDecl {
     pub name: String,
@@ -274,7 +324,18 @@ pub enum StmtKind {
     VarAssignment(VarSet),
     PropertyAssignment(PropertyAssignment),
     IndexAssignment(IndexAssignment),
-    Print(Vec<Expr>),
+    /// `let a, b = value;` (or `mut a, b = value;`).
+    TupleDeclaration {
+        names: Vec<String>,
+        mutable: bool,
+        value: Expr,
+    },
+    /// `a, b = value;` with the value fully evaluated before any store.
+    TupleAssignment {
+        names: Vec<String>,
+        value: Expr,
+    },
+    Print(PrintStmt),
     Input {
         name: String,
         var_type: Type,
@@ -289,7 +350,10 @@ pub enum StmtKind {
     ListPush(ListPush),
     DictDeclaration(DictDecl),
     DictSet(DictSet),
-    Import(String),
+    Import {
+        path: String,
+        alias: Option<String>,
+    },
     ImportCOm {
         header: String,
         alias: String,
@@ -298,6 +362,7 @@ pub enum StmtKind {
         from: Vec<String>,
         is_current_dir: bool,
         module: String,
+        alias: Option<String>,
     },
     UnsafeBlock(Vec<Stmt>),
     Promote {
@@ -312,6 +377,23 @@ pub enum StmtKind {
         name: String,
         body: Vec<Stmt>,
     },
+    TryCatch {
+        body: Vec<Stmt>,
+        err_name: String,
+        handler: Vec<Stmt>,
+    },
+    /// `enum Color: Red, Green, Blue`; variants are the integers 0, 1, 2 and
+    /// are referenced as `Color::Red`.
+    EnumDeclaration {
+        name: String,
+        variants: Vec<String>,
+    },
+    /// `type UserId = int`; uses of the alias are expanded before analysis
+    /// (`SemanticAnalyzer::expand_type_aliases`).
+    TypeAlias {
+        name: String,
+        target: Type,
+    },
     Entangle {
         target: String,
         anchor: String,
@@ -0,0 +1,124 @@
+use crate::compiler::{build_file, BuildOptions};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+/// Wall-clock statistics for a set of runs, in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchStats {
+    pub runs: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub max: f64,
+    pub std_dev: f64,
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let n = sorted.len();
+        let median = if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Some(BenchStats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean,
+            max: sorted[n - 1],
+            std_dev: variance.sqrt(),
+        })
+    }
+}
+
+/// Builds `file` once with `-O<opt_level>` and times `warmup + runs` executions
+/// of the binary. Warmup runs are executed but not measured.
+pub fn run_bench(file: &str, runs: usize, warmup: usize, opt_level: u8) -> Result<(), String> {
+    if runs == 0 {
+        return Err("bench: --runs must be at least 1".to_string());
+    }
+    if opt_level > 3 {
+        return Err("bench: --opt-level must be between 0 and 3".to_string());
+    }
+
+    build_file(
+        file,
+        BuildOptions {
+            opt_level,
+            ..Default::default()
+        },
+    )?;
+
+    let binary = file.replace(".snask", "");
+    let binary_path = if binary.starts_with('/') || binary.starts_with("./") {
+        binary
+    } else {
+        format!("./{}", binary)
+    };
+
+    let mut samples = Vec::with_capacity(runs);
+    for i in 0..warmup + runs {
+        let start = Instant::now();
+        let status = Command::new(&binary_path)
+            .stdout(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to run binary: {}", e))?;
+        let elapsed = start.elapsed();
+        if !status.success() {
+            return Err(format!(
+                "bench: run {} of {} exited with {}",
+                i + 1,
+                warmup + runs,
+                status
+            ));
+        }
+        if i >= warmup {
+            samples.push(elapsed.as_secs_f64() * 1000.0);
+        }
+    }
+
+    let stats = BenchStats::from_samples(&samples).expect("runs > 0");
+    println!(
+        "bench: {} ({} runs, {} warmup, -O{})",
+        file, stats.runs, warmup, opt_level
+    );
+    println!("  min      {:>10.3} ms", stats.min);
+    println!("  median   {:>10.3} ms", stats.median);
+    println!("  mean     {:>10.3} ms", stats.mean);
+    println!("  max      {:>10.3} ms", stats.max);
+    println!("  std-dev  {:>10.3} ms", stats.std_dev);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BenchStats;
+
+    #[test]
+    fn stats_on_fixed_sample() {
+        let stats = BenchStats::from_samples(&[4.0, 2.0, 8.0, 6.0]).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, 2.0);
+        assert_eq!(stats.max, 8.0);
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.mean, 5.0);
+        assert!((stats.std_dev - 5.0_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn stats_median_of_odd_sample_and_empty_input() {
+        let stats = BenchStats::from_samples(&[3.0, 1.0, 2.0]).unwrap();
+        assert_eq!(stats.median, 2.0);
+        assert!(BenchStats::from_samples(&[]).is_none());
+    }
+}
@@ -5,18 +5,33 @@ use std::sync::Arc;
 
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result as JsonResult;
+use tower_lsp::lsp_types::SymbolKind as LspSymbolKind;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
 use snask::ast::{Program, StmtKind};
+use snask::builtins::{find_builtin, BuiltinSig, BUILTINS};
+use snask::compiler::parser_fixit;
+use snask::hds::FixItApply;
 use snask::parser::Parser;
 use snask::parser::Token as SnaskToken;
 use snask::semantic_analyzer::SemanticAnalyzer;
+use snask::snask_fmt::format_snask;
 use snask::snif_fmt::format_snif;
 use snask::snif_parser::parse_snif;
 use snask::snif_schema::validate_snask_manifest;
 use snask::span as snask_span;
 
+// Semantic token legend indices, in the order `initialize` declares them.
+const TOKEN_KEYWORD: u32 = 0;
+const TOKEN_FUNCTION: u32 = 1;
+const TOKEN_VARIABLE: u32 = 2;
+const TOKEN_TYPE: u32 = 3;
+const TOKEN_STRING: u32 = 4;
+const TOKEN_NUMBER: u32 = 5;
+const TOKEN_OPERATOR: u32 = 6;
+const TOKEN_NAMESPACE: u32 = 7;
+
 #[derive(Default, Clone)]
 struct Document {
     text: String,
@@ -29,6 +44,8 @@ enum SymbolKind {
     Variable,
     Constant,
     Class,
+    Method,
+    Property,
     Parameter,
     Import,
     Module,
@@ -40,6 +57,8 @@ struct Symbol {
     kind: SymbolKind,
     span: snask_span::Span,
     uri: Url,
+    /// Class a method or property belongs to.
+    container: Option<String>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -97,6 +116,35 @@ impl Backend {
         None
     }
 
+    /// Text of every `.snask` file the server knows: open documents as
+    /// edited, the rest of the workspace from disk.
+    async fn workspace_sources(&self) -> Vec<(Url, String)> {
+        let (mut out, roots) = {
+            let st = self.state.read().await;
+            let open: Vec<(Url, String)> = st
+                .docs
+                .iter()
+                .filter(|(uri, _)| uri.path().ends_with(".snask"))
+                .map(|(uri, doc)| (uri.clone(), doc.text.clone()))
+                .collect();
+            (open, st.workspace_roots.clone())
+        };
+        for root in roots {
+            for file in Self::scan_snask_files(&root) {
+                let Ok(uri) = Url::from_file_path(&file) else {
+                    continue;
+                };
+                if out.iter().any(|(open, _)| *open == uri) {
+                    continue;
+                }
+                if let Ok(text) = fs::read_to_string(&file) {
+                    out.push((uri, text));
+                }
+            }
+        }
+        out
+    }
+
     fn should_skip_dir(name: &str) -> bool {
         matches!(
             name,
@@ -137,21 +185,28 @@ impl Backend {
 
         for root in roots {
             for file in Self::scan_snask_files(&root) {
-                let Ok(text) = fs::read_to_string(&file) else {
-                    continue;
-                };
-                let Ok(uri) = Url::from_file_path(&file) else {
-                    continue;
-                };
-                if let Ok(program) = Parser::new(&text).and_then(|mut p| p.parse_program()) {
-                    let symbols = Self::collect_symbols(&uri, &program);
-                    let mut st = state.write().await;
-                    st.symbols.insert(uri, symbols);
-                }
+                Self::reindex_file_static(&state, &file).await;
             }
         }
     }
 
+    /// Re-reads one file from disk and replaces its symbols. A file that is
+    /// gone or does not parse has none.
+    async fn reindex_file_static(state: &RwLock<State>, path: &Path) {
+        let Ok(uri) = Url::from_file_path(path) else {
+            return;
+        };
+        let symbols = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| Parser::new(&text).and_then(|mut p| p.parse_program()).ok())
+            .map(|program| Self::collect_symbols(&uri, &program));
+        let mut st = state.write().await;
+        match symbols {
+            Some(symbols) => st.symbols.insert(uri, symbols),
+            None => st.symbols.remove(&uri),
+        };
+    }
+
     fn identifier_at(text: &str, pos: Position) -> Option<String> {
         let line = text.lines().nth(pos.line as usize)?;
         let bytes = line.as_bytes();
@@ -179,6 +234,33 @@ impl Backend {
         Some(line[start..end].to_string())
     }
 
+    /// Builtin called `name`: by its own name or a native alias
+    /// (`gui::init`), or a plain builtin reached through one of the stdlib
+    /// modules (`string::upper`, `math_sqrt`).
+    fn builtin_named(name: &str) -> Option<&'static BuiltinSig> {
+        if let Some(builtin) = BUILTINS
+            .iter()
+            .find(|b| b.names().iter().any(|n| n == name))
+        {
+            return Some(builtin);
+        }
+        ["math", "string", "collections"].iter().find_map(|module| {
+            let rest = name.strip_prefix(module)?;
+            let rest = rest.strip_prefix("::").or_else(|| rest.strip_prefix('_'))?;
+            find_builtin(rest)
+        })
+    }
+
+    fn builtin_hover(name: &str) -> Option<String> {
+        let builtin = Self::builtin_named(name)?;
+        Some(format!(
+            "```snask\n{}\n```\n\n{}",
+            builtin.signature(),
+            builtin.doc
+        ))
+    }
+
+    /// Span columns are already UTF-16 code units, as LSP expects.
     fn span_to_range(span: &snask_span::Span) -> Range {
         let start_line = span.start.line.saturating_sub(1) as u32;
         let start_col = span.start.column.saturating_sub(1) as u32;
@@ -210,25 +292,40 @@ impl Backend {
                     kind: SymbolKind::Variable,
                     span: stmt.span.clone(),
                     uri: uri.clone(),
+                    container: None,
                 }),
                 StmtKind::MutDeclaration(d) => out.symbols.push(Symbol {
                     name: d.name.clone(),
                     kind: SymbolKind::Variable,
                     span: stmt.span.clone(),
                     uri: uri.clone(),
+                    container: None,
                 }),
                 StmtKind::ConstDeclaration(d) => out.symbols.push(Symbol {
                     name: d.name.clone(),
                     kind: SymbolKind::Constant,
                     span: stmt.span.clone(),
                     uri: uri.clone(),
+                    container: None,
                 }),
+                StmtKind::EnumDeclaration { name, variants } => {
+                    for variant in variants {
+                        out.symbols.push(Symbol {
+                            name: format!("{name}::{variant}"),
+                            kind: SymbolKind::Constant,
+                            span: stmt.span.clone(),
+                            uri: uri.clone(),
+                            container: None,
+                        });
+                    }
+                }
                 StmtKind::FuncDeclaration(f) => {
                     out.symbols.push(Symbol {
                         name: f.name.clone(),
                         kind: SymbolKind::Function,
                         span: stmt.span.clone(),
                         uri: uri.clone(),
+                        container: None,
                     });
                     for (param, _ty) in &f.params {
                         out.symbols.push(Symbol {
@@ -236,26 +333,54 @@ impl Backend {
                             kind: SymbolKind::Parameter,
                             span: stmt.span.clone(),
                             uri: uri.clone(),
+                            container: None,
                         });
                     }
                 }
-                StmtKind::ClassDeclaration(c) => out.symbols.push(Symbol {
-                    name: c.name.clone(),
-                    kind: SymbolKind::Class,
-                    span: stmt.span.clone(),
-                    uri: uri.clone(),
-                }),
-                StmtKind::Import(path) => out.symbols.push(Symbol {
-                    name: path.clone(),
+                StmtKind::ClassDeclaration(c) => {
+                    let span = c
+                        .methods
+                        .iter()
+                        .fold(stmt.span, |span, m| span.merge(&m.span));
+                    out.symbols.push(Symbol {
+                        name: c.name.clone(),
+                        kind: SymbolKind::Class,
+                        span,
+                        uri: uri.clone(),
+                        container: None,
+                    });
+                    for prop in &c.properties {
+                        out.symbols.push(Symbol {
+                            name: prop.name.clone(),
+                            kind: SymbolKind::Property,
+                            span: prop.value.span,
+                            uri: uri.clone(),
+                            container: Some(c.name.clone()),
+                        });
+                    }
+                    for method in &c.methods {
+                        out.symbols.push(Symbol {
+                            name: method.name.clone(),
+                            kind: SymbolKind::Method,
+                            span: method.span,
+                            uri: uri.clone(),
+                            container: Some(c.name.clone()),
+                        });
+                    }
+                }
+                StmtKind::Import { path, alias } => out.symbols.push(Symbol {
+                    name: alias.clone().unwrap_or_else(|| path.clone()),
                     kind: SymbolKind::Import,
                     span: stmt.span.clone(),
                     uri: uri.clone(),
+                    container: None,
                 }),
-                StmtKind::FromImport { module, .. } => out.symbols.push(Symbol {
-                    name: module.clone(),
+                StmtKind::FromImport { module, alias, .. } => out.symbols.push(Symbol {
+                    name: alias.clone().unwrap_or_else(|| module.clone()),
                     kind: SymbolKind::Module,
                     span: stmt.span.clone(),
                     uri: uri.clone(),
+                    container: None,
                 }),
                 _ => {}
             }
@@ -263,6 +388,52 @@ impl Backend {
         out
     }
 
+    /// The outline of a file: classes with their properties and methods,
+    /// then functions, variables and constants. Parameters and imports are
+    /// left out.
+    #[allow(deprecated)]
+    fn document_symbols(symbols: &FileSymbols) -> Vec<DocumentSymbol> {
+        let to_doc_symbol = |sym: &Symbol, children: Option<Vec<DocumentSymbol>>| {
+            let kind = match sym.kind {
+                SymbolKind::Function => LspSymbolKind::FUNCTION,
+                SymbolKind::Variable => LspSymbolKind::VARIABLE,
+                SymbolKind::Constant => LspSymbolKind::CONSTANT,
+                SymbolKind::Class => LspSymbolKind::CLASS,
+                SymbolKind::Method => LspSymbolKind::METHOD,
+                SymbolKind::Property => LspSymbolKind::PROPERTY,
+                SymbolKind::Parameter | SymbolKind::Import | SymbolKind::Module => return None,
+            };
+            let range = Self::span_to_range(&sym.span);
+            Some(DocumentSymbol {
+                name: sym.name.clone(),
+                detail: None,
+                kind,
+                tags: None,
+                deprecated: None,
+                range,
+                selection_range: range,
+                children,
+            })
+        };
+
+        symbols
+            .symbols
+            .iter()
+            .filter(|sym| sym.container.is_none())
+            .filter_map(|sym| {
+                let children = matches!(sym.kind, SymbolKind::Class).then(|| {
+                    symbols
+                        .symbols
+                        .iter()
+                        .filter(|member| member.container.as_deref() == Some(sym.name.as_str()))
+                        .filter_map(|member| to_doc_symbol(member, None))
+                        .collect()
+                });
+                to_doc_symbol(sym, children)
+            })
+            .collect()
+    }
+
     fn span_contains(span: &snask_span::Span, pos: Position) -> bool {
         let line = (pos.line as usize).saturating_add(1);
         let col = (pos.character as usize).saturating_add(1);
@@ -279,6 +450,11 @@ impl Backend {
                 StmtKind::ConstDeclaration(d) => {
                     out.push((d.name.clone(), SymbolKind::Constant));
                 }
+                StmtKind::TupleDeclaration { names, .. } => {
+                    for name in names {
+                        out.push((name.clone(), SymbolKind::Variable));
+                    }
+                }
                 StmtKind::Conditional(c) => {
                     Self::collect_locals_in_stmts(&c.if_block.body, out);
                     for b in &c.elif_blocks {
@@ -288,15 +464,29 @@ impl Backend {
                         Self::collect_locals_in_stmts(b, out);
                     }
                 }
-                StmtKind::Loop(l) => match l {
-                    snask::ast::LoopStmt::While { body, .. } => {
-                        Self::collect_locals_in_stmts(body, out)
-                    }
-                    snask::ast::LoopStmt::For { iterator, body, .. } => {
-                        out.push((iterator.clone(), SymbolKind::Variable));
-                        Self::collect_locals_in_stmts(body, out)
+                StmtKind::Loop(l) => {
+                    let else_block = match l {
+                        snask::ast::LoopStmt::While {
+                            body, else_block, ..
+                        } => {
+                            Self::collect_locals_in_stmts(body, out);
+                            else_block
+                        }
+                        snask::ast::LoopStmt::For {
+                            iterator,
+                            body,
+                            else_block,
+                            ..
+                        } => {
+                            out.push((iterator.clone(), SymbolKind::Variable));
+                            Self::collect_locals_in_stmts(body, out);
+                            else_block
+                        }
+                    };
+                    if let Some(b) = else_block {
+                        Self::collect_locals_in_stmts(b, out);
                     }
-                },
+                }
                 _ => {}
             }
         }
@@ -318,6 +508,586 @@ impl Backend {
         Vec::new()
     }
 
+    /// Legend index for `tok`, or `None` for layout tokens. Identifiers are
+    /// classified by their neighbours: `ns` in `ns::f` is a namespace, names
+    /// after `fun` or before `(` are functions, names after `class`/`new` and
+    /// built-in type names are types.
+    fn semantic_token_type(
+        tok: &SnaskToken,
+        prev: Option<&SnaskToken>,
+        next: Option<&SnaskToken>,
+    ) -> Option<u32> {
+        let ty = match tok {
+            SnaskToken::Let(_)
+            | SnaskToken::Mut(_)
+            | SnaskToken::Const(_)
+            | SnaskToken::Print(_)
+            | SnaskToken::Input(_)
+            | SnaskToken::Fun(_)
+            | SnaskToken::Class(_)
+            | SnaskToken::SelfKw(_)
+            | SnaskToken::Return(_)
+            | SnaskToken::New(_)
+            | SnaskToken::If(_)
+            | SnaskToken::Elif(_)
+            | SnaskToken::Else(_)
+            | SnaskToken::While(_)
+            | SnaskToken::For(_)
+            | SnaskToken::Try(_)
+            | SnaskToken::Catch(_)
+            | SnaskToken::Enum(_)
+            | SnaskToken::In(_)
+            | SnaskToken::Promote(_)
+            | SnaskToken::To(_)
+            | SnaskToken::Scope(_)
+            | SnaskToken::Zone(_)
+            | SnaskToken::Entangle(_)
+            | SnaskToken::With(_)
+            | SnaskToken::List(_)
+            | SnaskToken::Dict(_)
+            | SnaskToken::Import(_)
+            | SnaskToken::ImportCOm(_)
+            | SnaskToken::From(_)
+            | SnaskToken::True(_)
+            | SnaskToken::False(_)
+            | SnaskToken::Nil(_)
+            | SnaskToken::And(_)
+            | SnaskToken::Or(_)
+            | SnaskToken::Not(_)
+            | SnaskToken::Unsafe(_) => TOKEN_KEYWORD,
+
+            SnaskToken::Identifier(name, _) => {
+                if matches!(next, Some(SnaskToken::DoubleColon(_))) {
+                    TOKEN_NAMESPACE
+                } else if matches!(
+                    prev,
+                    Some(SnaskToken::Import(_) | SnaskToken::ImportCOm(_) | SnaskToken::From(_))
+                ) {
+                    TOKEN_NAMESPACE
+                } else if matches!(prev, Some(SnaskToken::Fun(_)))
+                    || matches!(next, Some(SnaskToken::LeftParen(_)))
+                {
+                    TOKEN_FUNCTION
+                } else if matches!(prev, Some(SnaskToken::Class(_) | SnaskToken::New(_)))
+                    || name.parse::<snask::types::Type>().is_ok()
+                {
+                    TOKEN_TYPE
+                } else {
+                    TOKEN_VARIABLE
+                }
+            }
+            SnaskToken::Number(..) => TOKEN_NUMBER,
+            SnaskToken::String(..) | SnaskToken::FString(..) => TOKEN_STRING,
+
+            SnaskToken::Indent(_)
+            | SnaskToken::Dedent(_)
+            | SnaskToken::Newline(_)
+            | SnaskToken::Eof(_) => return None,
+
+            // Operators, delimiters and `@`.
+            _ => TOKEN_OPERATOR,
+        };
+        Some(ty)
+    }
+
+    /// Delta-encoded semantic tokens for a `.snask` document; empty when it
+    /// does not tokenize.
+    fn snask_semantic_tokens(text: &str) -> Vec<SemanticToken> {
+        let Ok(tokens) = snask::parser::tokenize(text) else {
+            return Vec::new();
+        };
+        let mut data: Vec<SemanticToken> = Vec::new();
+        let mut prev_line: u32 = 0;
+        let mut prev_col: u32 = 0;
+        for (i, t) in tokens.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|j| &tokens[j]);
+            let Some(tok_type) = Self::semantic_token_type(t, prev, tokens.get(i + 1)) else {
+                continue;
+            };
+            let loc = t.get_location();
+            let line = loc.line.saturating_sub(1) as u32;
+            let col = loc.column.saturating_sub(1) as u32;
+
+            let delta_line = line.saturating_sub(prev_line);
+            let delta_start = if delta_line == 0 {
+                col.saturating_sub(prev_col)
+            } else {
+                col
+            };
+            data.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: snask::parser::token_width(t) as u32,
+                token_type: tok_type,
+                token_modifiers_bitset: 0,
+            });
+            prev_line = line;
+            prev_col = col;
+        }
+        data
+    }
+
+    /// Each function and method in `program` with the names it declares
+    /// itself: parameters, `let`/`mut`/`const` and loop variables.
+    fn function_scopes(program: &Program) -> Vec<(snask_span::Span, Vec<String>)> {
+        let scope = |f: &snask::ast::FuncDecl| {
+            let mut locals: Vec<(String, SymbolKind)> = f
+                .params
+                .iter()
+                .map(|(p, _ty)| (p.clone(), SymbolKind::Parameter))
+                .collect();
+            Self::collect_locals_in_stmts(&f.body, &mut locals);
+            (f.span, locals.into_iter().map(|(name, _)| name).collect())
+        };
+        let mut out = Vec::new();
+        for stmt in program {
+            match &stmt.kind {
+                StmtKind::FuncDeclaration(f) => out.push(scope(f)),
+                StmtKind::ClassDeclaration(c) => out.extend(c.methods.iter().map(scope)),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn token_range(tok: &SnaskToken) -> Range {
+        let loc = tok.get_location();
+        let line = loc.line.saturating_sub(1) as u32;
+        let character = loc.column.saturating_sub(1) as u32;
+        Range {
+            start: Position { line, character },
+            end: Position {
+                line,
+                character: character + snask::parser::token_width(tok) as u32,
+            },
+        }
+    }
+
+    /// Index of the renameable identifier under `pos`. A cursor right after
+    /// the name still counts; members reached through `.` or `::` do not.
+    fn renameable_token_at(tokens: &[SnaskToken], pos: Position) -> Option<usize> {
+        let on = |r: Range, end_inclusive: bool| {
+            r.start.line == pos.line
+                && r.start.character <= pos.character
+                && (pos.character < r.end.character
+                    || (end_inclusive && pos.character == r.end.character))
+        };
+        let ident_at = |end_inclusive| {
+            (0..tokens.len()).find(|&i| {
+                matches!(tokens[i], SnaskToken::Identifier(..))
+                    && on(Self::token_range(&tokens[i]), end_inclusive)
+            })
+        };
+        let i = ident_at(false).or_else(|| ident_at(true))?;
+        let is_member = i > 0
+            && matches!(
+                tokens[i - 1],
+                SnaskToken::Dot(_) | SnaskToken::QuestionDot(_) | SnaskToken::DoubleColon(_)
+            );
+        (!is_member).then_some(i)
+    }
+
+    /// Every occurrence in the file of the binding named at `pos`. A name
+    /// declared by the function under the cursor is only renamed inside it;
+    /// any other name is renamed everywhere except in functions that declare
+    /// their own.
+    fn rename_ranges(text: &str, program: &Program, pos: Position) -> Option<Vec<Range>> {
+        let tokens = snask::parser::tokenize(text).ok()?;
+        let target = Self::renameable_token_at(&tokens, pos)?;
+        let SnaskToken::Identifier(name, _) = &tokens[target] else {
+            return None;
+        };
+
+        let scopes = Self::function_scopes(program);
+        let declares = |scope: &&(snask_span::Span, Vec<String>)| scope.1.contains(name);
+        let local = scopes
+            .iter()
+            .filter(declares)
+            .find(|scope| Self::span_contains(&scope.0, pos));
+        let same_binding = |r: &Range| match local {
+            Some(scope) => Self::span_contains(&scope.0, r.start),
+            None => !scopes
+                .iter()
+                .filter(declares)
+                .any(|scope| Self::span_contains(&scope.0, r.start)),
+        };
+
+        let ranges = tokens
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| {
+                matches!(t, SnaskToken::Identifier(n, _) if n == name)
+                    && Self::renameable_token_at(&tokens, Self::token_range(t).start) == Some(*i)
+            })
+            .map(|(_, t)| Self::token_range(t))
+            .filter(same_binding)
+            .collect();
+        Some(ranges)
+    }
+
+    /// Names `program` can use to qualify members of `module`: the module
+    /// itself and any alias it was imported under.
+    fn module_qualifiers(program: &Program, module: &str) -> Vec<String> {
+        let stem = |path: &str| {
+            Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        };
+        let mut out = vec![module.to_string()];
+        for stmt in program {
+            let (path, alias) = match &stmt.kind {
+                StmtKind::Import { path, alias } => (path, alias),
+                StmtKind::FromImport { module, alias, .. } => (module, alias),
+                _ => continue,
+            };
+            if let (Some(alias), Some(imported)) = (alias, stem(path)) {
+                if imported == module {
+                    out.push(alias.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Spans of the expressions in `stmts` that `is_ref` accepts.
+    fn collect_refs_in_stmts(
+        stmts: &[snask::ast::Stmt],
+        is_ref: &dyn Fn(&snask::ast::Expr) -> bool,
+        out: &mut Vec<snask_span::Span>,
+    ) {
+        for stmt in stmts {
+            let mut expr = |e: &snask::ast::Expr| Self::collect_refs_in_expr(e, is_ref, out);
+            match &stmt.kind {
+                StmtKind::Expression(e) | StmtKind::FuncCall(e) | StmtKind::Return(e) => expr(e),
+                StmtKind::VarDeclaration(d) => expr(&d.value),
+                StmtKind::MutDeclaration(d) => expr(&d.value),
+                StmtKind::ConstDeclaration(d) => expr(&d.value),
+                StmtKind::VarAssignment(set) => expr(&set.value),
+                StmtKind::PropertyAssignment(set) => {
+                    expr(&set.target);
+                    expr(&set.value);
+                }
+                StmtKind::IndexAssignment(set) => {
+                    expr(&set.target);
+                    expr(&set.index);
+                    expr(&set.value);
+                }
+                StmtKind::TupleDeclaration { value, .. }
+                | StmtKind::TupleAssignment { value, .. } => expr(value),
+                StmtKind::Print(print) => print.exprs().for_each(expr),
+                StmtKind::FuncDeclaration(f) => Self::collect_refs_in_stmts(&f.body, is_ref, out),
+                StmtKind::ClassDeclaration(c) => {
+                    for prop in &c.properties {
+                        expr(&prop.value);
+                    }
+                    for method in &c.methods {
+                        Self::collect_refs_in_stmts(&method.body, is_ref, out);
+                    }
+                }
+                StmtKind::Conditional(c) => {
+                    expr(&c.if_block.condition);
+                    Self::collect_refs_in_stmts(&c.if_block.body, is_ref, out);
+                    for b in &c.elif_blocks {
+                        Self::collect_refs_in_expr(&b.condition, is_ref, out);
+                        Self::collect_refs_in_stmts(&b.body, is_ref, out);
+                    }
+                    if let Some(b) = &c.else_block {
+                        Self::collect_refs_in_stmts(b, is_ref, out);
+                    }
+                }
+                StmtKind::Loop(snask::ast::LoopStmt::While {
+                    condition,
+                    body,
+                    else_block,
+                }) => {
+                    expr(condition);
+                    Self::collect_refs_in_stmts(body, is_ref, out);
+                    if let Some(b) = else_block {
+                        Self::collect_refs_in_stmts(b, is_ref, out);
+                    }
+                }
+                StmtKind::Loop(snask::ast::LoopStmt::For {
+                    iterable,
+                    body,
+                    else_block,
+                    ..
+                }) => {
+                    expr(iterable);
+                    Self::collect_refs_in_stmts(body, is_ref, out);
+                    if let Some(b) = else_block {
+                        Self::collect_refs_in_stmts(b, is_ref, out);
+                    }
+                }
+                StmtKind::ListDeclaration(d) => expr(&d.value),
+                StmtKind::ListPush(push) => expr(&push.value),
+                StmtKind::DictDeclaration(d) => expr(&d.value),
+                StmtKind::DictSet(set) => {
+                    expr(&set.key);
+                    expr(&set.value);
+                }
+                StmtKind::UnsafeBlock(body)
+                | StmtKind::Scope { body, .. }
+                | StmtKind::Zone { body, .. } => Self::collect_refs_in_stmts(body, is_ref, out),
+                StmtKind::TryCatch { body, handler, .. } => {
+                    Self::collect_refs_in_stmts(body, is_ref, out);
+                    Self::collect_refs_in_stmts(handler, is_ref, out);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_refs_in_expr(
+        expr: &snask::ast::Expr,
+        is_ref: &dyn Fn(&snask::ast::Expr) -> bool,
+        out: &mut Vec<snask_span::Span>,
+    ) {
+        use snask::ast::{ExprKind, LiteralValue};
+        if is_ref(expr) {
+            out.push(expr.span);
+            return;
+        }
+        let mut walk = |e: &snask::ast::Expr| Self::collect_refs_in_expr(e, is_ref, out);
+        match &expr.kind {
+            ExprKind::Literal(LiteralValue::List(items)) => items.iter().for_each(walk),
+            ExprKind::Literal(LiteralValue::Dict(pairs)) => {
+                for (key, value) in pairs {
+                    walk(key);
+                    walk(value);
+                }
+            }
+            ExprKind::Literal(_) | ExprKind::Variable(_) => {}
+            ExprKind::Unary { expr, .. } => walk(expr),
+            ExprKind::Binary { left, right, .. } => {
+                walk(left);
+                walk(right);
+            }
+            ExprKind::FunctionCall { callee, args } => {
+                walk(callee);
+                args.iter().for_each(walk);
+            }
+            ExprKind::PropertyAccess { target, .. } => walk(target),
+            ExprKind::IndexAccess { target, index } => {
+                walk(target);
+                walk(index);
+            }
+            ExprKind::Slice {
+                target,
+                start,
+                end,
+                step,
+            } => {
+                walk(target);
+                [start, end, step]
+                    .into_iter()
+                    .flatten()
+                    .for_each(|bound| walk(bound));
+            }
+            ExprKind::New { args, .. } | ExprKind::Tuple(args) => args.iter().for_each(walk),
+            ExprKind::StructLiteral { fields, .. } => {
+                fields.iter().for_each(|(_, value)| walk(value))
+            }
+        }
+    }
+
+    /// Range of `decl`'s own name inside its declaration, or the whole
+    /// declaration when the name cannot be found.
+    fn declaration_range(text: &str, decl: &Symbol) -> Range {
+        snask::parser::tokenize(text)
+            .ok()
+            .and_then(|tokens| {
+                tokens
+                    .iter()
+                    .filter(|t| matches!(t, SnaskToken::Identifier(n, _) if *n == decl.name))
+                    .map(Self::token_range)
+                    .find(|r| Self::span_contains(&decl.span, r.start))
+            })
+            .unwrap_or_else(|| Self::span_to_range(&decl.span))
+    }
+
+    /// Every use of `decl` across `files`. Plain names only count in the file
+    /// that declares it; other files must qualify it with the module name
+    /// (`util::helper`, `util.helper`) or the alias it was imported under.
+    fn references_in(
+        files: &[(Url, String)],
+        decl: &Symbol,
+        include_declaration: bool,
+    ) -> Vec<Location> {
+        use snask::ast::ExprKind;
+        let module = decl
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_default();
+        let name = decl.name.as_str();
+        let width = name.encode_utf16().count() as u32;
+
+        let mut out = Vec::new();
+        for (uri, text) in files {
+            if include_declaration && *uri == decl.uri {
+                out.push(Location {
+                    uri: uri.clone(),
+                    range: Self::declaration_range(text, decl),
+                });
+            }
+            let Ok(program) = Parser::new(text).and_then(|mut p| p.parse_program()) else {
+                continue;
+            };
+            let same_file = *uri == decl.uri;
+            let qualifiers = Self::module_qualifiers(&program, &module);
+            let qualified = |q: &str| qualifiers.iter().any(|m| m == q);
+            let is_ref = |e: &snask::ast::Expr| match &e.kind {
+                ExprKind::Variable(v) => match v.rsplit_once("::") {
+                    Some((q, n)) => n == name && qualified(q),
+                    None => same_file && v == name,
+                },
+                ExprKind::PropertyAccess {
+                    target, property, ..
+                } => {
+                    property == name
+                        && matches!(&target.kind, ExprKind::Variable(q) if qualified(q))
+                }
+                _ => false,
+            };
+            let mut spans = Vec::new();
+            Self::collect_refs_in_stmts(&program, &is_ref, &mut spans);
+            for span in spans {
+                let mut range = Self::span_to_range(&span);
+                range.start = Position {
+                    line: range.end.line,
+                    character: range.end.character.saturating_sub(width),
+                };
+                out.push(Location {
+                    uri: uri.clone(),
+                    range,
+                });
+            }
+        }
+        out.sort_by_key(|l| {
+            (
+                l.uri.to_string(),
+                l.range.start.line,
+                l.range.start.character,
+            )
+        });
+        out
+    }
+
+    fn is_valid_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_')
+            && matches!(
+                snask::parser::tokenize(name).ok().as_deref(),
+                Some([SnaskToken::Identifier(..), ..])
+            )
+    }
+
+    /// Byte offset of an LSP position. `character` counts UTF-16 code units;
+    /// positions past the end of a line or of the text are clamped to it.
+    fn byte_offset(text: &str, pos: Position) -> usize {
+        let mut line_start = 0;
+        for _ in 0..pos.line {
+            match text[line_start..].find('\n') {
+                Some(i) => line_start += i + 1,
+                None => return text.len(),
+            }
+        }
+        let line_end = text[line_start..]
+            .find('\n')
+            .map_or(text.len(), |i| line_start + i);
+        let line = &text[line_start..line_end];
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units >= pos.character as usize {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+        line_start + line.len()
+    }
+
+    /// Replaces `range` in `text` with `new_text`.
+    fn apply_change(text: &mut String, range: Range, new_text: &str) {
+        let start = Self::byte_offset(text, range.start);
+        let end = Self::byte_offset(text, range.end).max(start);
+        text.replace_range(start..end, new_text);
+    }
+
+    /// Quick fixes for a parse error diagnostic, built from the same fix-its
+    /// the CLI prints: plausible ones are offered, safe ones preferred.
+    fn parser_fix_actions(uri: &Url, text: &str, diag: &Diagnostic) -> Vec<CodeAction> {
+        let Some(NumberOrString::String(code)) = &diag.code else {
+            return Vec::new();
+        };
+        let errors = match Parser::new(text) {
+            Ok(mut p) => p.parse_program_recovering(10).1,
+            Err(err) => vec![err],
+        };
+        errors
+            .iter()
+            .filter(|err| err.code == code.as_str() && Self::span_to_range(&err.span) == diag.range)
+            .filter_map(parser_fixit)
+            .filter(|fix| fix.is_plausible())
+            .filter_map(|fix| {
+                let Some(FixItApply::Insert { at, text }) = &fix.apply else {
+                    return None;
+                };
+                let pos = Self::span_to_range(at).start;
+                let edit = TextEdit {
+                    range: Range {
+                        start: pos,
+                        end: pos,
+                    },
+                    new_text: text.clone(),
+                };
+                Some(CodeAction {
+                    title: fix.title.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diag.clone()]),
+                    edit: Some(Self::mk_workspace_edit(uri.clone(), vec![edit])),
+                    is_preferred: Some(fix.is_safe()),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// Range covering all of `text`.
+    fn full_range(text: &str) -> Range {
+        let last = text.split('\n').last().unwrap_or("");
+        Range {
+            start: Position {
+                line: 0,
+                character: 0,
+            },
+            end: Position {
+                line: text.matches('\n').count() as u32,
+                character: last.encode_utf16().count() as u32,
+            },
+        }
+    }
+
+    /// A single edit replacing the document with its formatted text, no edits
+    /// when it is already formatted, or `None` when it does not parse.
+    fn format_edits(uri: &Url, text: &str) -> Option<Vec<TextEdit>> {
+        let formatted = if uri.path().ends_with(".snif") {
+            format_snif(&parse_snif(text).ok()?)
+        } else {
+            format_snask(text).ok()?
+        };
+        if formatted == text {
+            return Some(Vec::new());
+        }
+        Some(vec![TextEdit {
+            range: Self::full_range(text),
+            new_text: formatted,
+        }])
+    }
+
     fn line_indent(s: &str) -> String {
         s.chars().take_while(|c| *c == ' ' || *c == '\t').collect()
     }
@@ -418,11 +1188,12 @@ impl Backend {
                                 data: None,
                             });
                         }
-                    } else if let Some(program) = program_opt {
+                    } else if let Some(mut program) = program_opt {
                         let symbols = Self::collect_symbols(&uri, &program);
                         self.set_symbols(uri.clone(), symbols).await;
 
                         let mut analyzer = SemanticAnalyzer::new();
+                        analyzer.expand_type_aliases(&mut program);
                         analyzer.analyze(&program);
                         for err in analyzer.errors {
                             let mut message = err.message();
@@ -477,6 +1248,7 @@ impl Backend {
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> JsonResult<InitializeResult> {
+        // Keep in sync with the `TOKEN_*` indices.
         let legend = SemanticTokensLegend {
             token_types: vec![
                 SemanticTokenType::KEYWORD,
@@ -513,8 +1285,13 @@ impl LanguageServer for Backend {
 
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
-                text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::INCREMENTAL,
+                text_document_sync: Some(TextDocumentSyncCapability::Options(
+                    TextDocumentSyncOptions {
+                        open_close: Some(true),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
+                        save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                        ..Default::default()
+                    },
                 )),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
@@ -527,6 +1304,13 @@ impl LanguageServer for Backend {
                 }),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
                 code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
@@ -548,6 +1332,27 @@ impl LanguageServer for Backend {
     }
 
     async fn initialized(&self, _: InitializedParams) {
+        // Files created, changed or deleted outside the editor are re-indexed
+        // one at a time (see `did_change_watched_files`).
+        let watchers = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String("**/*.snask".to_string()),
+                kind: None,
+            }],
+        };
+        let registration = Registration {
+            id: "snask-watched-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(watchers).ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            self.client
+                .log_message(
+                    MessageType::WARNING,
+                    format!("snask-lsp: file watching unavailable: {}", e),
+                )
+                .await;
+        }
         self.client
             .log_message(MessageType::INFO, "snask-lsp initialized")
             .await;
@@ -575,22 +1380,11 @@ impl LanguageServer for Backend {
             String::new()
         };
 
+        // Changes apply in order, each against the result of the previous one.
         for change in params.content_changes {
-            if let Some(range) = change.range {
-                // Minimal incremental apply (UTF-16 positions are complex; this is best-effort).
-                // If it fails, fallback to full text.
-                if range.start.line == 0
-                    && range.start.character == 0
-                    && range.end.line == 0
-                    && range.end.character == 0
-                {
-                    text = change.text;
-                } else {
-                    // Fallback: treat as full text (VS Code usually sends full text if configured).
-                    text = change.text;
-                }
-            } else {
-                text = change.text;
+            match change.range {
+                Some(range) => Self::apply_change(&mut text, range, &change.text),
+                None => text = change.text,
             }
         }
 
@@ -598,6 +1392,35 @@ impl LanguageServer for Backend {
         self.publish_diagnostics(uri, text, version).await;
     }
 
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let uri = params.text_document.uri;
+        if !uri.path().ends_with(".snask") {
+            return;
+        }
+        if let Ok(path) = uri.to_file_path() {
+            Self::reindex_file_static(&self.state, &path).await;
+        }
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        for change in params.changes {
+            if !change.uri.path().ends_with(".snask") {
+                continue;
+            }
+            if change.typ == FileChangeType::DELETED {
+                self.state.write().await.symbols.remove(&change.uri);
+                continue;
+            }
+            // Open documents are indexed from the editor's buffer instead.
+            if self.get_doc(&change.uri).await.is_some() {
+                continue;
+            }
+            if let Ok(path) = change.uri.to_file_path() {
+                Self::reindex_file_static(&self.state, &path).await;
+            }
+        }
+    }
+
     async fn hover(&self, params: HoverParams) -> JsonResult<Option<Hover>> {
         let uri = params.text_document_position_params.text_document.uri;
         let pos = params.text_document_position_params.position;
@@ -614,6 +1437,8 @@ impl LanguageServer for Backend {
                     SymbolKind::Variable => "variable",
                     SymbolKind::Constant => "constant",
                     SymbolKind::Class => "class",
+                    SymbolKind::Method => "method",
+                    SymbolKind::Property => "property",
                     SymbolKind::Parameter => "parameter",
                     SymbolKind::Import => "import",
                     SymbolKind::Module => "module",
@@ -621,6 +1446,8 @@ impl LanguageServer for Backend {
                 let line = sym.span.start.line;
                 let col = sym.span.start.column;
                 format!("**{}** `{}`\n\nDefined at {}:{}", kind, sym.name, line, col)
+            } else if let Some(doc) = Self::builtin_hover(&name) {
+                doc
             } else {
                 format!("`{}`", name)
             }
@@ -666,6 +1493,104 @@ impl LanguageServer for Backend {
         })))
     }
 
+    async fn references(&self, params: ReferenceParams) -> JsonResult<Option<Vec<Location>>> {
+        let uri = params.text_document_position.text_document.uri;
+        let pos = params.text_document_position.position;
+        let doc = match self.get_doc(&uri).await {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let ident = match Self::identifier_at(&doc.text, pos) {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        let name = ident.rsplit("::").next().unwrap_or(&ident);
+        let decl = match self.find_symbol(name, &uri).await {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        let files = self.workspace_sources().await;
+        Ok(Some(Self::references_in(
+            &files,
+            &decl,
+            params.context.include_declaration,
+        )))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> JsonResult<Option<DocumentSymbolResponse>> {
+        let uri = params.text_document.uri;
+        let doc = match self.get_doc(&uri).await {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let Ok(program) = Parser::new(&doc.text).and_then(|mut p| p.parse_program()) else {
+            return Ok(None);
+        };
+        let symbols = Self::collect_symbols(&uri, &program);
+        Ok(Some(DocumentSymbolResponse::Nested(
+            Self::document_symbols(&symbols),
+        )))
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> JsonResult<Option<PrepareRenameResponse>> {
+        let doc = match self.get_doc(&params.text_document.uri).await {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let Ok(tokens) = snask::parser::tokenize(&doc.text) else {
+            return Ok(None);
+        };
+        Ok(Self::renameable_token_at(&tokens, params.position)
+            .map(|i| PrepareRenameResponse::Range(Self::token_range(&tokens[i]))))
+    }
+
+    async fn rename(&self, params: RenameParams) -> JsonResult<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let pos = params.text_document_position.position;
+        if !Self::is_valid_identifier(&params.new_name) {
+            return Err(tower_lsp::jsonrpc::Error::invalid_params(format!(
+                "'{}' is not a valid identifier",
+                params.new_name
+            )));
+        }
+        let doc = match self.get_doc(&uri).await {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let Ok(program) = Parser::new(&doc.text).and_then(|mut p| p.parse_program()) else {
+            return Ok(None);
+        };
+        let Some(ranges) = Self::rename_ranges(&doc.text, &program, pos) else {
+            return Ok(None);
+        };
+        let edits = ranges
+            .into_iter()
+            .map(|range| TextEdit {
+                range,
+                new_text: params.new_name.clone(),
+            })
+            .collect();
+        Ok(Some(Self::mk_workspace_edit(uri, edits)))
+    }
+
+    async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> JsonResult<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri;
+        let doc = match self.get_doc(&uri).await {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        Ok(Self::format_edits(&uri, &doc.text))
+    }
+
     async fn completion(&self, params: CompletionParams) -> JsonResult<Option<CompletionResponse>> {
         let uri = params.text_document_position.text_document.uri;
         let pos = params.text_document_position.position;
@@ -693,6 +1618,10 @@ impl LanguageServer for Backend {
                 ("else", "else", CompletionItemKind::KEYWORD),
                 ("while", "while ", CompletionItemKind::KEYWORD),
                 ("for", "for ", CompletionItemKind::KEYWORD),
+                ("try", "try:", CompletionItemKind::KEYWORD),
+                ("catch", "catch err:", CompletionItemKind::KEYWORD),
+                ("enum", "enum ", CompletionItemKind::KEYWORD),
+                ("type", "type ", CompletionItemKind::KEYWORD),
                 ("import", "import \"\";\n", CompletionItemKind::KEYWORD),
                 (
                     "from / import",
@@ -718,6 +1647,8 @@ impl LanguageServer for Backend {
                     SymbolKind::Variable => CompletionItemKind::VARIABLE,
                     SymbolKind::Constant => CompletionItemKind::CONSTANT,
                     SymbolKind::Class => CompletionItemKind::CLASS,
+                    SymbolKind::Method => CompletionItemKind::METHOD,
+                    SymbolKind::Property => CompletionItemKind::PROPERTY,
                     SymbolKind::Parameter => CompletionItemKind::VARIABLE,
                     SymbolKind::Import => CompletionItemKind::MODULE,
                     SymbolKind::Module => CompletionItemKind::MODULE,
@@ -739,6 +1670,8 @@ impl LanguageServer for Backend {
                     SymbolKind::Variable => CompletionItemKind::VARIABLE,
                     SymbolKind::Constant => CompletionItemKind::CONSTANT,
                     SymbolKind::Class => CompletionItemKind::CLASS,
+                    SymbolKind::Method => CompletionItemKind::METHOD,
+                    SymbolKind::Property => CompletionItemKind::PROPERTY,
                     SymbolKind::Parameter => CompletionItemKind::VARIABLE,
                     SymbolKind::Import => CompletionItemKind::MODULE,
                     SymbolKind::Module => CompletionItemKind::MODULE,
@@ -983,139 +1916,9 @@ impl LanguageServer for Backend {
             })));
         }
 
-        let tokens = match snask::parser::tokenize(&doc.text) {
-            Ok(t) => t,
-            Err(_) => {
-                return Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
-                    result_id: None,
-                    data: vec![],
-                })))
-            }
-        };
-
-        let mut data: Vec<SemanticToken> = Vec::new();
-        let mut prev_line: u32 = 0;
-        let mut prev_col: u32 = 0;
-
-        let mut expect_ident_as: Option<u32> = None; // token_type index
-
-        for t in tokens {
-            let loc = t.get_location().clone();
-            let line = loc.line.saturating_sub(1) as u32;
-            let col = loc.column.saturating_sub(1) as u32;
-
-            let (len, tok_type): (u32, Option<u32>) = match t.clone() {
-                // Keywords
-                SnaskToken::Let(_) => (3, Some(0)),
-                SnaskToken::Mut(_) => (3, Some(0)),
-                SnaskToken::Const(_) => (5, Some(0)),
-                SnaskToken::Print(_) => (5, Some(0)),
-                SnaskToken::Input(_) => (5, Some(0)),
-                SnaskToken::Fun(_) => (3, Some(0)),
-                SnaskToken::Class(_) => (5, Some(0)),
-                SnaskToken::SelfKw(_) => (4, Some(0)),
-                SnaskToken::Return(_) => (6, Some(0)),
-                SnaskToken::If(_) => (2, Some(0)),
-                SnaskToken::Elif(_) => (4, Some(0)),
-                SnaskToken::Else(_) => (4, Some(0)),
-                SnaskToken::While(_) => (5, Some(0)),
-                SnaskToken::For(_) => (3, Some(0)),
-                SnaskToken::In(_) => (2, Some(0)),
-                SnaskToken::Import(_) => (6, Some(0)),
-                SnaskToken::From(_) => (4, Some(0)),
-                SnaskToken::True(_) => (4, Some(0)),
-                SnaskToken::False(_) => (5, Some(0)),
-                SnaskToken::Nil(_) => (3, Some(0)),
-                SnaskToken::And(_) => (3, Some(0)),
-                SnaskToken::Or(_) => (2, Some(0)),
-                SnaskToken::Not(_) => (3, Some(0)),
-
-                // Identifiers / literals
-                SnaskToken::Identifier(name, _) => {
-                    let ty = expect_ident_as.take().unwrap_or(2); // default variable
-                    (name.len().max(1) as u32, Some(ty))
-                }
-                SnaskToken::Number(n, _) => (format!("{}", n).len().max(1) as u32, Some(5)),
-                SnaskToken::String(s, _) => ((s.len() + 2).max(1) as u32, Some(4)),
-
-                // Operators/delims
-                SnaskToken::Plus(_)
-                | SnaskToken::Minus(_)
-                | SnaskToken::Star(_)
-                | SnaskToken::Slash(_)
-                | SnaskToken::Equal(_)
-                | SnaskToken::Less(_)
-                | SnaskToken::Greater(_)
-                | SnaskToken::Comma(_)
-                | SnaskToken::Dot(_)
-                | SnaskToken::Colon(_)
-                | SnaskToken::Semicolon(_) => (1, Some(6)),
-                SnaskToken::DoubleSlash(_)
-                | SnaskToken::PlusEqual(_)
-                | SnaskToken::MinusEqual(_)
-                | SnaskToken::StarEqual(_)
-                | SnaskToken::SlashEqual(_)
-                | SnaskToken::EqualEqual(_)
-                | SnaskToken::BangEqual(_)
-                | SnaskToken::LessEqual(_)
-                | SnaskToken::GreaterEqual(_)
-                | SnaskToken::DoubleColon(_) => (2, Some(6)),
-                SnaskToken::TripleEqual(_) => (3, Some(6)),
-
-                // Brackets/parens
-                SnaskToken::LeftParen(_)
-                | SnaskToken::RightParen(_)
-                | SnaskToken::LeftBrace(_)
-                | SnaskToken::RightBrace(_)
-                | SnaskToken::LeftBracket(_)
-                | SnaskToken::RightBracket(_) => (1, Some(6)),
-
-                // Whitespace / structural
-                SnaskToken::Indent(_)
-                | SnaskToken::Dedent(_)
-                | SnaskToken::Newline(_)
-                | SnaskToken::Eof(_) => (0, None),
-                SnaskToken::List(_) | SnaskToken::Dict(_) => (4, Some(0)),
-                _ => (1, None),
-            };
-
-            // Update "expect next identifier" state based on current token
-            match t {
-                SnaskToken::Fun(_) => expect_ident_as = Some(1), // function
-                SnaskToken::Class(_) => expect_ident_as = Some(3), // type
-                SnaskToken::Let(_) | SnaskToken::Mut(_) => expect_ident_as = Some(2), // variable
-                SnaskToken::Const(_) => expect_ident_as = Some(2),
-                SnaskToken::From(_) | SnaskToken::Import(_) => expect_ident_as = Some(7), // namespace/module-ish
-                _ => {}
-            }
-
-            let Some(tok_type) = tok_type else { continue };
-            if len == 0 {
-                continue;
-            }
-
-            let delta_line = line.saturating_sub(prev_line);
-            let delta_start = if delta_line == 0 {
-                col.saturating_sub(prev_col)
-            } else {
-                col
-            };
-
-            data.push(SemanticToken {
-                delta_line,
-                delta_start,
-                length: len,
-                token_type: tok_type,
-                token_modifiers_bitset: 0,
-            });
-
-            prev_line = line;
-            prev_col = col;
-        }
-
         Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
             result_id: None,
-            data,
+            data: Self::snask_semantic_tokens(&doc.text),
         })))
     }
 
@@ -1220,77 +2023,9 @@ impl LanguageServer for Backend {
         for diag in params.context.diagnostics {
             let msg = diag.message.clone();
 
-            // Quickfix: missing semicolon (parser hint)
-            let is_missing_semicolon = match &diag.code {
-                Some(NumberOrString::String(s)) => s == "SNASK-PARSE-SEMICOLON",
-                _ => false,
-            };
-            if is_missing_semicolon || msg.contains("missed a ';'") {
-                let line_idx = diag.range.start.line as usize;
-                if let Some(line) = doc.text.lines().nth(line_idx) {
-                    if !line.trim_end().ends_with(';') {
-                        let insert_col = line.len() as u32;
-                        let edit = TextEdit {
-                            range: Range {
-                                start: Position {
-                                    line: diag.range.start.line,
-                                    character: insert_col,
-                                },
-                                end: Position {
-                                    line: diag.range.start.line,
-                                    character: insert_col,
-                                },
-                            },
-                            new_text: ";".to_string(),
-                        };
-                        let ca = CodeAction {
-                            title: "Insert ';'".to_string(),
-                            kind: Some(CodeActionKind::QUICKFIX),
-                            diagnostics: Some(vec![diag.clone()]),
-                            edit: Some(Self::mk_workspace_edit(uri.clone(), vec![edit])),
-                            is_preferred: Some(true),
-                            ..Default::default()
-                        };
-                        actions.push(CodeActionOrCommand::CodeAction(ca));
-                    }
-                }
-            }
-
-            // Quickfix: missing closing delimiters.
-            let missing_closer = match &diag.code {
-                Some(NumberOrString::String(s)) if s == "SNASK-PARSE-MISSING-RPAREN" => Some(")"),
-                Some(NumberOrString::String(s)) if s == "SNASK-PARSE-MISSING-RBRACKET" => Some("]"),
-                Some(NumberOrString::String(s)) if s == "SNASK-PARSE-MISSING-RBRACE" => Some("}"),
-                _ => None,
-            };
-            if let Some(ch) = missing_closer {
-                // Insert at end of the line where the error is reported (best-effort).
-                let line_idx = diag.range.start.line as usize;
-                if let Some(line) = doc.text.lines().nth(line_idx) {
-                    let insert_col = line.len() as u32;
-                    let edit = TextEdit {
-                        range: Range {
-                            start: Position {
-                                line: diag.range.start.line,
-                                character: insert_col,
-                            },
-                            end: Position {
-                                line: diag.range.start.line,
-                                character: insert_col,
-                            },
-                        },
-                        new_text: ch.to_string(),
-                    };
-                    let ca = CodeAction {
-                        title: format!("Insert '{ch}'"),
-                        kind: Some(CodeActionKind::QUICKFIX),
-                        diagnostics: Some(vec![diag.clone()]),
-                        edit: Some(Self::mk_workspace_edit(uri.clone(), vec![edit])),
-                        is_preferred: Some(true),
-                        ..Default::default()
-                    };
-                    actions.push(CodeActionOrCommand::CodeAction(ca));
-                }
+            // Quickfix: insert the token a parse error says is missing.
+            for ca in Self::parser_fix_actions(&uri, &doc.text, &diag) {
+                actions.push(CodeActionOrCommand::CodeAction(ca));
             }
 
             // Quickfix: create variable when "Variable 'x' not found."
@@ -1373,3 +2108,347 @@ async fn main() {
     let (service, socket) = LspService::new(|client| Backend::new(client));
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(line, column, length, type)` of each token, decoded from deltas.
+    fn decode(data: &[SemanticToken]) -> Vec<(u32, u32, u32, u32)> {
+        let (mut line, mut col) = (0, 0);
+        data.iter()
+            .map(|t| {
+                line += t.delta_line;
+                col = if t.delta_line == 0 {
+                    col + t.delta_start
+                } else {
+                    t.delta_start
+                };
+                (line, col, t.length, t.token_type)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn let_statement_tokens_are_keyword_variable_operator_number() {
+        let tokens = decode(&Backend::snask_semantic_tokens("let x = 1;\n"));
+        assert_eq!(
+            tokens,
+            vec![
+                (0, 0, 3, TOKEN_KEYWORD),
+                (0, 4, 1, TOKEN_VARIABLE),
+                (0, 6, 1, TOKEN_OPERATOR),
+                (0, 8, 1, TOKEN_NUMBER),
+                (0, 9, 1, TOKEN_OPERATOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn outline_nests_class_members_under_the_class() {
+        let source = "fun add(a, b)\n    return a + b\n\
+fun twice(n)\n    return n * 2\n\
+class Point\n    let x = 0\n    fun moved(dx)\n        return self.x + dx\n";
+        let program = Parser::new(source)
+            .and_then(|mut p| p.parse_program())
+            .expect("source should parse");
+        let uri = Url::parse("file:///outline.snask").unwrap();
+        let outline = Backend::document_symbols(&Backend::collect_symbols(&uri, &program));
+
+        let shape: Vec<(&str, LspSymbolKind, Vec<&str>)> = outline
+            .iter()
+            .map(|sym| {
+                let children = sym
+                    .children
+                    .iter()
+                    .flatten()
+                    .map(|child| child.name.as_str())
+                    .collect();
+                (sym.name.as_str(), sym.kind, children)
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("add", LspSymbolKind::FUNCTION, vec![]),
+                ("twice", LspSymbolKind::FUNCTION, vec![]),
+                ("Point", LspSymbolKind::CLASS, vec!["x", "moved"]),
+            ]
+        );
+
+        assert_eq!(outline[0].range.start.line, 0);
+        assert_eq!(outline[0].range.end.line, 1);
+        let point = &outline[2];
+        assert_eq!(point.range.start.line, 4);
+        assert_eq!(point.range.end.line, 7);
+        let moved = &point.children.as_ref().unwrap()[1];
+        assert_eq!(moved.kind, LspSymbolKind::METHOD);
+        assert_eq!(moved.range.start.line, 6);
+    }
+
+    #[test]
+    fn rename_edits_every_call_site_and_respects_local_scopes() {
+        let source = "fun add(a, b)\n    let x = a + b\n    return x\n\
+fun twice(n)\n    let x = n * 2\n    return add(x, 0)\n\
+class main\n    fun start()\n        print(add(1, 2))\n        print(twice(add(3, 4)))\n";
+        let program = Parser::new(source)
+            .and_then(|mut p| p.parse_program())
+            .expect("source should parse");
+        let starts = |line, character| {
+            Backend::rename_ranges(source, &program, Position { line, character })
+                .expect("cursor should be on a name")
+                .iter()
+                .map(|r| (r.start.line, r.start.character, r.end.character))
+                .collect::<Vec<_>>()
+        };
+
+        let calls = vec![(0, 4, 7), (5, 11, 14), (8, 14, 17), (9, 20, 23)];
+        assert_eq!(starts(8, 14), calls);
+        assert_eq!(starts(0, 7), calls);
+        assert_eq!(starts(1, 8), vec![(1, 8, 9), (2, 11, 12)]);
+        assert_eq!(starts(5, 15), vec![(4, 8, 9), (5, 15, 16)]);
+
+        assert!(Backend::is_valid_identifier("sum"));
+        assert!(!Backend::is_valid_identifier("fun"));
+        assert!(!Backend::is_valid_identifier("2x"));
+    }
+
+    #[test]
+    fn references_follow_qualified_calls_into_other_files() {
+        let util_uri = Url::parse("file:///ws/util.snask").unwrap();
+        let main_uri = Url::parse("file:///ws/main.snask").unwrap();
+        let util = "fun helper(n)\n    return n + 1\nfun twice(n)\n    return helper(helper(n))\n";
+        let main = r#"import "util"
+class main
+    fun start()
+        print(util::helper(1))
+        let helper = 2
+        print(helper)
+"#;
+        let files = vec![
+            (util_uri.clone(), util.to_string()),
+            (main_uri.clone(), main.to_string()),
+        ];
+        let program = Parser::new(util)
+            .and_then(|mut p| p.parse_program())
+            .expect("util should parse");
+        let decl = Backend::collect_symbols(&util_uri, &program)
+            .symbols
+            .into_iter()
+            .find(|s| s.name == "helper")
+            .expect("helper should be declared");
+
+        let found = |include_declaration| {
+            Backend::references_in(&files, &decl, include_declaration)
+                .into_iter()
+                .map(|l| {
+                    let file = l.uri.path().rsplit('/').next().unwrap().to_string();
+                    (
+                        file,
+                        l.range.start.line,
+                        l.range.start.character,
+                        l.range.end.character,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let uses = vec![
+            ("main.snask".to_string(), 3, 20, 26),
+            ("util.snask".to_string(), 3, 11, 17),
+            ("util.snask".to_string(), 3, 18, 24),
+        ];
+        assert_eq!(found(false), uses);
+
+        let mut with_decl = uses;
+        with_decl.insert(1, ("util.snask".to_string(), 0, 4, 10));
+        assert_eq!(found(true), with_decl);
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range {
+            start: Position {
+                line: start.0,
+                character: start.1,
+            },
+            end: Position {
+                line: end.0,
+                character: end.1,
+            },
+        }
+    }
+
+    #[test]
+    fn incremental_changes_apply_in_order_with_utf16_columns() {
+        let mut text = "let nome = \"ação\"\nprint(nome)\n".to_string();
+        // Replace `ação` (4 UTF-16 units, 6 bytes) with `olá`.
+        Backend::apply_change(&mut text, range((0, 12), (0, 16)), "olá");
+        assert_eq!(text, "let nome = \"olá\"\nprint(nome)\n");
+        // Insert after the closing quote, then rename on the next line.
+        Backend::apply_change(&mut text, range((0, 16), (0, 16)), ";");
+        Backend::apply_change(&mut text, range((1, 6), (1, 10)), "n");
+        assert_eq!(text, "let nome = \"olá\";\nprint(n)\n");
+        // Join the two lines and append at the end of the document.
+        Backend::apply_change(&mut text, range((0, 17), (1, 0)), " ");
+        Backend::apply_change(&mut text, range((1, 0), (1, 0)), "// fim\n");
+        assert_eq!(text, "let nome = \"olá\"; print(n)\n// fim\n");
+
+        // An emoji is two UTF-16 units; columns past the line end clamp to it.
+        let mut text = "x = \"😀\"\r\ny\n".to_string();
+        Backend::apply_change(&mut text, range((0, 8), (0, 8)), "!");
+        Backend::apply_change(&mut text, range((0, 99), (0, 99)), ";");
+        assert_eq!(text, "x = \"😀\"!;\r\ny\n");
+    }
+
+    #[test]
+    fn missing_semicolon_offers_an_insert_quick_fix() {
+        let uri = Url::parse("file:///fix.snask").unwrap();
+        let text = "class main\n    fun start()\n        let x = 1 print(x)\n";
+        let (_, mut errors) = Parser::new(text)
+            .expect("source should tokenize")
+            .parse_program_recovering(10);
+        let err = errors.remove(0);
+        assert_eq!(err.code, "SNASK-PARSE-SEMICOLON");
+        let diag = Diagnostic {
+            range: Backend::span_to_range(&err.span),
+            code: Some(NumberOrString::String(err.code.to_string())),
+            ..Default::default()
+        };
+
+        let actions = Backend::parser_fix_actions(&uri, text, &diag);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].title, "Insert `;`");
+        assert_eq!(actions[0].is_preferred, Some(true));
+        let changes = actions[0].edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let edit = &changes[&uri][0];
+        assert_eq!(edit.new_text, ";");
+        assert_eq!(
+            edit.range.start,
+            Position {
+                line: 2,
+                character: 18
+            }
+        );
+        assert_eq!(edit.range.end, edit.range.start);
+    }
+
+    #[test]
+    fn formatting_replaces_the_whole_snask_document() {
+        let uri = Url::parse("file:///fmt.snask").unwrap();
+        let text = "class main\n  fun start()  \n      print(1)\n\n\n";
+        let edits = Backend::format_edits(&uri, text).expect("source should parse");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range, Backend::full_range(text));
+        assert_eq!(edits[0].range.end.line, 5);
+        assert_eq!(
+            edits[0].new_text,
+            "class main\n    fun start()\n        print(1)\n"
+        );
+
+        assert_eq!(
+            Backend::format_edits(&uri, &edits[0].new_text),
+            Some(Vec::new())
+        );
+        assert_eq!(Backend::format_edits(&uri, "fun broken(\n"), None);
+    }
+
+    #[test]
+    fn formatting_routes_snif_documents_to_the_snif_formatter() {
+        let uri = Url::parse("file:///pkg/snask.snif").unwrap();
+        let text = "{package:{name:\"demo\",version:\"0.1.0\"}}";
+        let expected = format_snif(&parse_snif(text).unwrap());
+        assert_ne!(expected, text);
+        let edits = Backend::format_edits(&uri, text).expect("snif should parse");
+        assert_eq!(edits[0].new_text, expected);
+        assert_eq!(edits[0].range.end.character, text.len() as u32);
+        assert_eq!(Backend::format_edits(&uri, "{package:"), None);
+    }
+
+    #[test]
+    fn hovering_a_builtin_shows_its_signature_and_doc() {
+        let text = "let r = sqrt(2.0);\nlet s = string::upper(\"a\");\n";
+        let name = Backend::identifier_at(text, Position::new(0, 10)).unwrap();
+        let hover = Backend::builtin_hover(&name).expect("sqrt is a builtin");
+        assert!(hover.contains("sqrt(float) -> float"), "{hover}");
+        assert!(hover.contains("Square root."), "{hover}");
+
+        let name = Backend::identifier_at(text, Position::new(1, 17)).unwrap();
+        assert_eq!(name, "string::upper");
+        assert!(Backend::builtin_hover(&name)
+            .unwrap()
+            .contains("upper(str) -> str"));
+        assert!(Backend::builtin_hover("gui::init")
+            .unwrap()
+            .contains("gui_init() -> bool"));
+        assert_eq!(Backend::builtin_hover("not_a_builtin"), None);
+    }
+
+    #[tokio::test]
+    async fn saved_and_deleted_files_update_the_index() {
+        let dir = std::env::temp_dir().join(format!("snask_lsp_index_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("greeter.snask");
+        let uri = Url::from_file_path(&path).unwrap();
+        let other = Url::from_file_path(dir.join("main.snask")).unwrap();
+        let (service, _socket) = LspService::new(Backend::new);
+        let backend = service.inner();
+        assert!(backend.find_symbol("greet", &other).await.is_none());
+
+        fs::write(&path, "fun greet(name)\n    print(name)\n").unwrap();
+        backend
+            .did_save(DidSaveTextDocumentParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                text: None,
+            })
+            .await;
+        let sym = backend
+            .find_symbol("greet", &other)
+            .await
+            .expect("greet is indexed");
+        assert_eq!(sym.uri, uri);
+
+        fs::remove_file(&path).unwrap();
+        backend
+            .did_change_watched_files(DidChangeWatchedFilesParams {
+                changes: vec![FileEvent {
+                    uri,
+                    typ: FileChangeType::DELETED,
+                }],
+            })
+            .await;
+        assert!(backend.find_symbol("greet", &other).await.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn namespaced_calls_color_the_namespace() {
+        let source = "fun twice(n: int) : int\n    return math::mul(n, 2)\n";
+        let types: Vec<u32> = decode(&Backend::snask_semantic_tokens(source))
+            .into_iter()
+            .map(|(_, _, _, ty)| ty)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                TOKEN_KEYWORD,
+                TOKEN_FUNCTION,
+                TOKEN_OPERATOR,
+                TOKEN_VARIABLE,
+                TOKEN_OPERATOR,
+                TOKEN_TYPE,
+                TOKEN_OPERATOR,
+                TOKEN_OPERATOR,
+                TOKEN_TYPE,
+                TOKEN_KEYWORD,
+                TOKEN_NAMESPACE,
+                TOKEN_OPERATOR,
+                TOKEN_FUNCTION,
+                TOKEN_OPERATOR,
+                TOKEN_VARIABLE,
+                TOKEN_OPERATOR,
+                TOKEN_NUMBER,
+                TOKEN_OPERATOR,
+            ]
+        );
+    }
+}
@@ -0,0 +1,1453 @@
+//! Signatures of the builtin functions, shared by the semantic analyzer and
+//! the language server.
+//!
+//! The analyzer registers every entry of [`BUILTINS`] as a function symbol;
+//! the LSP reads the same entries for completion and hover, so a builtin is
+//! described in exactly one place.
+
+use crate::semantic_analyzer::display_type;
+use crate::types::Type;
+use crate::types::Type::{
+    Any, Bool, Dict, Float, Int, Isize, List, Ptr, String as Str, Usize, Void, I16, I32, I64, I8,
+    U16, U32, U64, U8,
+};
+
+/// One builtin function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinSig {
+    pub name: &'static str,
+    pub params: &'static [Type],
+    pub ret: Type,
+    /// Accepts any number of arguments after `params`.
+    pub variadic: bool,
+    /// Native runtime function: also reachable as `__name` and through its
+    /// module alias (`gui_init` as `gui::init`).
+    pub native: bool,
+    /// One-line description shown by the LSP.
+    pub doc: &'static str,
+}
+
+const fn sig(
+    name: &'static str,
+    params: &'static [Type],
+    ret: Type,
+    doc: &'static str,
+) -> BuiltinSig {
+    BuiltinSig {
+        name,
+        params,
+        ret,
+        variadic: false,
+        native: false,
+        doc,
+    }
+}
+
+const fn variadic(
+    name: &'static str,
+    params: &'static [Type],
+    ret: Type,
+    doc: &'static str,
+) -> BuiltinSig {
+    BuiltinSig {
+        name,
+        params,
+        ret,
+        variadic: true,
+        native: false,
+        doc,
+    }
+}
+
+const fn native(
+    name: &'static str,
+    params: &'static [Type],
+    ret: Type,
+    doc: &'static str,
+) -> BuiltinSig {
+    BuiltinSig {
+        name,
+        params,
+        ret,
+        variadic: false,
+        native: true,
+        doc,
+    }
+}
+
+impl BuiltinSig {
+    /// Every name the builtin is callable by: its own name, plus `__name` and
+    /// the module alias for natives.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![self.name.to_string()];
+        if self.native {
+            names.push(format!("__{}", self.name));
+            names.extend(native_module_aliases(self.name));
+        }
+        names
+    }
+
+    /// The signature as shown to users, e.g. `sqrt(float) -> float`.
+    pub fn signature(&self) -> String {
+        let mut params: Vec<String> = self.params.iter().map(display_type).collect();
+        if self.variadic {
+            params.push("...".to_string());
+        }
+        format!(
+            "{}({}) -> {}",
+            self.name,
+            params.join(", "),
+            display_type(&self.ret)
+        )
+    }
+}
+
+/// Module-qualified names of a native builtin, e.g. `gui_init` → `gui::init`.
+pub fn native_module_aliases(name: &str) -> Vec<String> {
+    let mappings = [
+        ("gui_", "gui"),
+        ("os_", "os"),
+        ("sfs_", "sfs"),
+        ("path_", "sfs"),
+        ("json_", "json"),
+        ("sjson_", "sjson"),
+        ("snif_", "snif"),
+        ("string_", "string"),
+        ("regex_", "regex"),
+        ("dt_", "datetime"),
+        ("csv_", "csv"),
+        ("base64_", "base64"),
+        ("hash_", "hash"),
+        ("sb_", "sb"),
+        ("sqlite_", "sqlite"),
+        ("zlib_", "zlib"),
+        ("skia_", "snask_skia"),
+        ("blaze_", "blaze"),
+        ("auth_", "blaze_auth"),
+        ("thread_", "os"),
+        ("snaskgui_", "snaskgui"),
+    ];
+
+    if let Some(rest) = name.strip_prefix("s_http_") {
+        return vec![format!("requests::{}", rest)];
+    }
+
+    for (prefix, module) in mappings {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            return vec![format!("{}::{}", module, rest)];
+        }
+    }
+
+    Vec::new()
+}
+
+/// The builtin called `name`, looked up by its plain name.
+pub fn find_builtin(name: &str) -> Option<&'static BuiltinSig> {
+    BUILTINS.iter().find(|b| b.name == name)
+}
+
+/// Every builtin, in registration order.
+pub const BUILTINS: &[BuiltinSig] = &[
+    // Runtime intrinsics — inline LLVM IR, no C dependency
+    sig("__snask_type", &[Any], Int, "Runtime type tag of a value."),
+    sig(
+        "__snask_get_str",
+        &[Any],
+        Str,
+        "String payload of a value, without conversion.",
+    ),
+    sig(
+        "__snask_get_num",
+        &[Any],
+        Float,
+        "Numeric payload of a value, without conversion.",
+    ),
+    sig(
+        "__snask_write",
+        &[Int, Str, Int],
+        Int,
+        "Writes `len` bytes of a string to a file descriptor.",
+    ),
+    // assert(cond[, message]); arity is checked by `assert_call_type`.
+    sig(
+        "assert",
+        &[Any, Str],
+        Void,
+        "Fails the program (or the current test) when `cond` is false.",
+    ),
+    // cfg("KEY"); replaced by a bool literal before analysis in builds.
+    sig(
+        "cfg",
+        &[Str],
+        Bool,
+        "Whether the compile-time define `KEY` is set (`--define KEY[=VAL]`, `build.defines`).",
+    ),
+    // Math
+    sig("abs", &[Float], Float, "Absolute value."),
+    sig(
+        "floor",
+        &[Float],
+        Float,
+        "Largest whole number not greater than `x`.",
+    ),
+    sig(
+        "ceil",
+        &[Float],
+        Float,
+        "Smallest whole number not less than `x`.",
+    ),
+    sig(
+        "round",
+        &[Float],
+        Float,
+        "`x` rounded to the nearest whole number.",
+    ),
+    sig("pow", &[Float, Float], Float, "`base` raised to `exp`."),
+    sig("sqrt", &[Float], Float, "Square root."),
+    variadic("min", &[], Any, "Smallest of the arguments."),
+    variadic("max", &[], Any, "Largest of the arguments."),
+    sig("sin", &[Float], Float, "Sine of an angle in radians."),
+    sig("cos", &[Float], Float, "Cosine of an angle in radians."),
+    sig(
+        "gcd",
+        &[Int, Int],
+        Int,
+        "Greatest common divisor, never negative.",
+    ),
+    sig(
+        "lcm",
+        &[Int, Int],
+        Int,
+        "Least common multiple; an error when it does not fit in an int.",
+    ),
+    sig(
+        "factorial",
+        &[Int],
+        Int,
+        "`n!`; an error for negative `n` or when it does not fit in an int.",
+    ),
+    sig(
+        "isqrt",
+        &[Int],
+        Int,
+        "Largest integer whose square is at most `n`; an error for negative `n`.",
+    ),
+    sig("random", &[], Float, "A pseudo-random float in [0, 1)."),
+    sig(
+        "random_range",
+        &[Float, Float],
+        Float,
+        "A pseudo-random float in [min, max); an error unless `min < max`.",
+    ),
+    sig(
+        "random_seed",
+        &[Int],
+        Void,
+        "Seeds `random`/`random_range` so the same seed gives the same sequence.",
+    ),
+    // Strings and collections
+    sig("len", &[Any], Float, "Length of a string, list or dict."),
+    sig("upper", &[Str], Str, "The string in upper case."),
+    sig("lower", &[Str], Str, "The string in lower case."),
+    sig(
+        "trim",
+        &[Str],
+        Str,
+        "The string without leading and trailing whitespace.",
+    ),
+    sig(
+        "split",
+        &[Str, Str],
+        List,
+        "Splits a string on a separator.",
+    ),
+    sig(
+        "join",
+        &[List, Str],
+        Str,
+        "Joins a list of strings with a separator.",
+    ),
+    sig(
+        "replace",
+        &[Str, Str, Str],
+        Str,
+        "Replaces every occurrence of `from` with `to`.",
+    ),
+    sig(
+        "contains",
+        &[Str, Str],
+        Bool,
+        "Whether the string contains `needle`.",
+    ),
+    sig(
+        "starts_with",
+        &[Str, Str],
+        Bool,
+        "Whether the string starts with `prefix`.",
+    ),
+    sig(
+        "ends_with",
+        &[Str, Str],
+        Bool,
+        "Whether the string ends with `suffix`.",
+    ),
+    sig(
+        "chars",
+        &[Str],
+        List,
+        "The characters of a string, as a list.",
+    ),
+    sig(
+        "substring",
+        &[Str, Float, Float],
+        Str,
+        "Characters from `start` up to, not including, `end`.",
+    ),
+    variadic(
+        "format",
+        &[Str],
+        Str,
+        "Fills the `{}` placeholders of a template with the arguments.",
+    ),
+    sig("range", &[Float], List, "The list `0, 1, ..., n - 1`."),
+    sig("sort", &[List], List, "A sorted copy of the list."),
+    sig("reverse", &[List], List, "A reversed copy of the list."),
+    sig(
+        "unique",
+        &[List],
+        List,
+        "The list without repeated items, first occurrence kept.",
+    ),
+    sig("flatten", &[List], List, "Concatenates a list of lists."),
+    sig(
+        "zip",
+        &[List, List],
+        List,
+        "Pairs `[a[i], b[i]]`, as long as the shorter list.",
+    ),
+    sig(
+        "enumerate",
+        &[List],
+        List,
+        "Pairs `[index, item]` for every item of the list.",
+    ),
+    sig(
+        "chunk",
+        &[List, Int],
+        List,
+        "Sublists of `n` items; the last one is shorter if needed.",
+    ),
+    sig(
+        "sum",
+        &[List],
+        Float,
+        "Sum of a list of numbers; 0 when empty.",
+    ),
+    sig(
+        "mean",
+        &[List],
+        Float,
+        "Average of a list of numbers; an error when empty.",
+    ),
+    sig(
+        "median",
+        &[List],
+        Float,
+        "Middle value of a list of numbers; an error when empty.",
+    ),
+    sig(
+        "stddev",
+        &[List],
+        Float,
+        "Population standard deviation of a list of numbers; an error when empty.",
+    ),
+    sig(
+        "mode",
+        &[List],
+        Float,
+        "Most frequent number in a list, the first one on a tie; an error when empty.",
+    ),
+    sig(
+        "dict_keys",
+        &[Dict],
+        List,
+        "Keys of a dict, in insertion order.",
+    ),
+    sig(
+        "dict_values",
+        &[Dict],
+        List,
+        "Values of a dict, in the order of its keys.",
+    ),
+    sig(
+        "dict_items",
+        &[Dict],
+        List,
+        "Pairs `[key, value]` of a dict, in insertion order.",
+    ),
+    sig(
+        "dict_merge",
+        &[Dict, Dict],
+        Dict,
+        "A new dict with the entries of `a` and `b`; `b` wins on duplicate keys.",
+    ),
+    sig("is_nil", &[Any], Bool, "Whether the value is `nil`."),
+    sig("is_str", &[Any], Bool, "Whether the value is a string."),
+    sig("is_obj", &[Any], Bool, "Whether the value is an object."),
+    // Files, network and process
+    sig("read_file", &[Str], Str, "Contents of a file as a string."),
+    sig(
+        "write_file",
+        &[Str, Str],
+        Void,
+        "Writes a string to a file, replacing it.",
+    ),
+    sig(
+        "append_file",
+        &[Str, Str],
+        Void,
+        "Appends a string to a file.",
+    ),
+    sig("exists", &[Str], Bool, "Whether a path exists."),
+    sig("delete", &[Str], Void, "Deletes a file."),
+    sig(
+        "read_dir",
+        &[Str],
+        List,
+        "Names of the entries in a directory.",
+    ),
+    sig(
+        "is_file",
+        &[Str],
+        Bool,
+        "Whether the path is a regular file.",
+    ),
+    sig("is_dir", &[Str], Bool, "Whether the path is a directory."),
+    sig("create_dir", &[Str], Void, "Creates a directory."),
+    sig(
+        "http_get",
+        &[Str],
+        Dict,
+        "Performs an HTTP GET and returns the response.",
+    ),
+    sig(
+        "http_post",
+        &[Str, Str],
+        Void,
+        "Performs an HTTP POST with a body.",
+    ),
+    sig("time", &[], Float, "Seconds since the Unix epoch."),
+    sig(
+        "sleep",
+        &[Float],
+        Void,
+        "Pauses for the given number of milliseconds.",
+    ),
+    sig(
+        "sleep_ms",
+        &[Float],
+        Void,
+        "Pauses for the given number of milliseconds (fractions allowed).",
+    ),
+    sig(
+        "time_ns",
+        &[],
+        Int,
+        "Nanoseconds on a monotonic clock; use differences to time code.",
+    ),
+    sig(
+        "time_ms",
+        &[],
+        Int,
+        "Milliseconds on a monotonic clock; use differences to time code.",
+    ),
+    sig(
+        "exit",
+        &[Float],
+        Void,
+        "Ends the program with a status code.",
+    ),
+    sig(
+        "__s_call_by_name",
+        &[Str, Any, Any, Any],
+        Any,
+        "Calls a function by name with up to three arguments.",
+    ),
+    sig("args", &[], List, "Command-line arguments."),
+    sig(
+        "env",
+        &[Str],
+        Str,
+        "Value of an environment variable, or an empty string.",
+    ),
+    sig(
+        "set_env",
+        &[Str, Str],
+        Void,
+        "Sets an environment variable.",
+    ),
+    sig("cwd", &[], Str, "Current working directory."),
+    sig("platform", &[], Str, "Operating system name, e.g. `linux`."),
+    sig("arch", &[], Str, "CPU architecture, e.g. `x86_64`."),
+    sig(
+        "str_to_num",
+        &[Str],
+        Float,
+        "Parses a number from a string.",
+    ),
+    sig("num_to_str", &[Float], Str, "Formats a number as a string."),
+    sig(
+        "calc_eval",
+        &[Str],
+        Float,
+        "Evaluates an arithmetic expression; `nil` when it is invalid.",
+    ),
+    sig(
+        "to_int",
+        &[Any],
+        Int,
+        "Converts to an integer; invalid input raises a catchable error.",
+    ),
+    sig(
+        "to_float",
+        &[Any],
+        Float,
+        "Converts to a float; invalid input raises a catchable error.",
+    ),
+    sig(
+        "to_str",
+        &[Any],
+        Str,
+        "Converts any value to its string form.",
+    ),
+    sig(
+        "to_bool",
+        &[Any],
+        Bool,
+        "Converts to a bool using Snask's truthiness rules.",
+    ),
+    sig(
+        "wrapping_add",
+        &[Any, Any],
+        Any,
+        "Integer addition that wraps around on overflow.",
+    ),
+    sig(
+        "wrapping_sub",
+        &[Any, Any],
+        Any,
+        "Integer subtraction that wraps around on overflow.",
+    ),
+    sig(
+        "wrapping_mul",
+        &[Any, Any],
+        Any,
+        "Integer multiplication that wraps around on overflow.",
+    ),
+    sig(
+        "saturating_add",
+        &[Any, Any],
+        Any,
+        "Integer addition clamped to the type's range.",
+    ),
+    // Low-level systems builtins
+    sig("as_u8", &[Any], U8, "Converts to `u8`, truncating."),
+    sig("as_u16", &[Any], U16, "Converts to `u16`, truncating."),
+    sig("as_u32", &[Any], U32, "Converts to `u32`, truncating."),
+    sig("as_u64", &[Any], U64, "Converts to `u64`."),
+    sig("as_i8", &[Any], I8, "Converts to `i8`, truncating."),
+    sig("as_i16", &[Any], I16, "Converts to `i16`, truncating."),
+    sig("as_i32", &[Any], I32, "Converts to `i32`, truncating."),
+    sig("as_i64", &[Any], I64, "Converts to `i64`."),
+    sig("as_usize", &[Any], Usize, "Converts to `usize`."),
+    sig("as_isize", &[Any], Isize, "Converts to `isize`."),
+    sig("lo_u8", &[Any], U8, "Low byte of a 16-bit value."),
+    sig("hi_u8", &[Any], U8, "High byte of a 16-bit value."),
+    sig("is_zero_u8", &[Any], Bool, "Whether the byte is zero."),
+    sig(
+        "is_negative_u8",
+        &[Any],
+        Bool,
+        "Whether bit 7 of the byte is set.",
+    ),
+    sig("null_ptr", &[], Ptr, "The null pointer."),
+    sig(
+        "make_u16",
+        &[Any, Any],
+        Any,
+        "Builds a `u16` from its low and high bytes.",
+    ),
+    sig("bit_set", &[Any, Any], Any, "`x` with bit `n` set."),
+    sig("bit_clear", &[Any, Any], Any, "`x` with bit `n` cleared."),
+    sig("bit_toggle", &[Any, Any], Any, "`x` with bit `n` flipped."),
+    sig(
+        "bit_write",
+        &[Any, Any],
+        Any,
+        "`x` with bit `n` set or cleared by `on`.",
+    ),
+    sig("flag_set", &[Any, Any], Any, "`flags` with `flag` set."),
+    sig(
+        "flag_clear",
+        &[Any, Any],
+        Any,
+        "`flags` with `flag` cleared.",
+    ),
+    sig(
+        "flag_write",
+        &[Any, Any],
+        Any,
+        "`flags` with `flag` set or cleared by `on`.",
+    ),
+    sig(
+        "wrapping_inc",
+        &[Any, Any],
+        Any,
+        "`x + 1`, wrapping around on overflow.",
+    ),
+    sig(
+        "wrapping_dec",
+        &[Any, Any],
+        Any,
+        "`x - 1`, wrapping around on overflow.",
+    ),
+    sig(
+        "bit_test",
+        &[Any, Any],
+        Bool,
+        "Whether bit `n` of `x` is set.",
+    ),
+    sig(
+        "flag_has",
+        &[Any, Any],
+        Bool,
+        "Whether `flag` is set in `flags`.",
+    ),
+    sig(
+        "carry_add_u8",
+        &[Any, Any, Any],
+        Bool,
+        "Whether `a + b + carry` overflows a byte.",
+    ),
+    sig(
+        "borrow_sub_u8",
+        &[Any, Any, Any],
+        Bool,
+        "Whether `a - b - borrow` needs a borrow.",
+    ),
+    sig(
+        "overflow_add_i8",
+        &[Any, Any, Any],
+        Bool,
+        "Whether `a + b + carry` overflows a signed byte.",
+    ),
+    sig(
+        "overflow_sub_i8",
+        &[Any, Any, Any],
+        Bool,
+        "Whether `a - b - borrow` overflows a signed byte.",
+    ),
+    sig("mem_alloc", &[Any], Ptr, "Allocates `size` bytes."),
+    sig(
+        "mem_alloc_zero",
+        &[Any],
+        Ptr,
+        "Allocates `size` zeroed bytes.",
+    ),
+    sig("mem_free", &[Ptr], Void, "Frees memory from `mem_alloc`."),
+    sig(
+        "ptr_add",
+        &[Ptr, Any],
+        Ptr,
+        "Pointer moved forward by `offset` bytes.",
+    ),
+    sig(
+        "mem_read_u8",
+        &[Ptr, Any],
+        U8,
+        "Reads a `u8` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_read_u16",
+        &[Ptr, Any],
+        U16,
+        "Reads a little-endian `u16` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_read_u32",
+        &[Ptr, Any],
+        U32,
+        "Reads a little-endian `u32` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_write_u8",
+        &[Ptr, Any, Any],
+        Void,
+        "Writes a `u8` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_write_u16",
+        &[Ptr, Any, Any],
+        Void,
+        "Writes a little-endian `u16` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_write_u32",
+        &[Ptr, Any, Any],
+        Void,
+        "Writes a little-endian `u32` at `ptr + offset`.",
+    ),
+    sig(
+        "mem_fill_u8",
+        &[Ptr, Any, Any],
+        Void,
+        "Fills `len` bytes at `ptr` with a byte.",
+    ),
+    sig(
+        "mem_copy",
+        &[Ptr, Ptr, Any],
+        Void,
+        "Copies `len` bytes from `src` to `dst`.",
+    ),
+    // Core natives aliased
+    native("os_cwd", &[], Str, "Current working directory."),
+    native("string_len", &[Any], Float, "Length of a string."),
+    native(
+        "string_slice",
+        &[Str, Float, Float, Float],
+        Str,
+        "Slice of a string from `start` to `end` taking every `step`th character.",
+    ),
+    // Regex (via `import "regex"`; POSIX extended syntax in the runtime)
+    native(
+        "regex_match",
+        &[Str, Str],
+        Bool,
+        "Whether the pattern matches anywhere in the text.",
+    ),
+    native(
+        "regex_find",
+        &[Str, Str],
+        Str,
+        "First match of the pattern, or an empty string.",
+    ),
+    native(
+        "regex_find_all",
+        &[Str, Str],
+        List,
+        "Every match of the pattern.",
+    ),
+    native(
+        "regex_replace",
+        &[Str, Str, Str],
+        Str,
+        "Replaces every match of the pattern.",
+    ),
+    // Datetime (via `import "datetime"`; epoch seconds, always UTC)
+    native("dt_now", &[], Float, "Current time in epoch seconds (UTC)."),
+    native(
+        "dt_format",
+        &[Float, Str],
+        Str,
+        "Formats epoch seconds with a `strftime` pattern.",
+    ),
+    native(
+        "dt_parse",
+        &[Str, Str],
+        Float,
+        "Parses a date with a `strptime` pattern into epoch seconds.",
+    ),
+    native(
+        "dt_add_days",
+        &[Float, Float],
+        Float,
+        "Epoch seconds moved by a number of days.",
+    ),
+    // CSV (via `import "csv"`; RFC 4180 quoting, rows are lists of strings)
+    native(
+        "csv_parse",
+        &[Str],
+        List,
+        "Parses comma-separated text into a list of rows.",
+    ),
+    native(
+        "csv_parse_delim",
+        &[Str, Str],
+        List,
+        "Parses delimited text into a list of rows using a one-character delimiter.",
+    ),
+    native(
+        "csv_write",
+        &[List],
+        Str,
+        "Writes a list of rows as comma-separated text, quoting fields as needed.",
+    ),
+    native(
+        "csv_write_delim",
+        &[List, Str],
+        Str,
+        "Writes a list of rows as delimited text using a one-character delimiter.",
+    ),
+    // Base64 (via `import "base64"`; RFC 4648, invalid input is a catchable error)
+    native(
+        "base64_encode",
+        &[Str],
+        Str,
+        "Encodes a string as padded standard base64.",
+    ),
+    native(
+        "base64_decode",
+        &[Str],
+        Str,
+        "Decodes padded standard base64.",
+    ),
+    native(
+        "base64_encode_url",
+        &[Str],
+        Str,
+        "Encodes a string as URL-safe base64 without padding.",
+    ),
+    native(
+        "base64_decode_url",
+        &[Str],
+        Str,
+        "Decodes URL-safe base64, with or without padding.",
+    ),
+    // Hashes (via `import "hash"`; lowercase hex digests, no crypto library needed)
+    native(
+        "hash_sha256_hex",
+        &[Str],
+        Str,
+        "SHA-256 digest of a string as lowercase hex.",
+    ),
+    native(
+        "hash_sha1_hex",
+        &[Str],
+        Str,
+        "SHA-1 digest of a string as lowercase hex (not for security).",
+    ),
+    native(
+        "hash_md5_hex",
+        &[Str],
+        Str,
+        "MD5 digest of a string as lowercase hex (not for security).",
+    ),
+    // String builders (via `import "sb"`; handle-based, appends are amortized O(1))
+    native(
+        "sb_create",
+        &[],
+        Str,
+        "Creates an empty string builder; returns its handle.",
+    ),
+    native(
+        "sb_append",
+        &[Str, Str],
+        Bool,
+        "Appends a string to the end of a builder.",
+    ),
+    native(
+        "sb_to_string",
+        &[Str],
+        Str,
+        "Everything appended to a builder so far, as one string.",
+    ),
+    native(
+        "sb_free",
+        &[Str],
+        Bool,
+        "Releases a builder; its handle is invalid afterwards.",
+    ),
+    // JSON (via `import "json"`; module calls compile to `__json_*`)
+    native("json_parse", &[Str], Any, "Parses JSON text into a value."),
+    native(
+        "json_stringify",
+        &[Any],
+        Str,
+        "Serializes a value as compact JSON.",
+    ),
+    native(
+        "json_stringify_pretty",
+        &[Any],
+        Str,
+        "Serializes a value as indented JSON.",
+    ),
+    native(
+        "json_stringify_opts",
+        &[Any, Int, Bool],
+        Str,
+        "Serializes a value with `indent` spaces per level (0 is compact), keys sorted if asked.",
+    ),
+    native(
+        "json_get",
+        &[Any, Str],
+        Any,
+        "Field of a JSON object, or `nil`.",
+    ),
+    native(
+        "json_has",
+        &[Any, Str],
+        Bool,
+        "Whether a JSON object has the field.",
+    ),
+    native(
+        "json_len",
+        &[Any],
+        Float,
+        "Number of items in a JSON array or object.",
+    ),
+    native(
+        "json_index",
+        &[Any, Float],
+        Any,
+        "Item of a JSON array, or `nil`.",
+    ),
+    native(
+        "json_set",
+        &[Any, Str, Any],
+        Bool,
+        "Sets a field of a JSON object.",
+    ),
+    native("json_keys", &[Any], Any, "Field names of a JSON object."),
+    native(
+        "json_parse_ex",
+        &[Str],
+        Any,
+        "Parses JSON text, reporting where it is invalid.",
+    ),
+    // SJSON (native helpers)
+    native("sjson_type", &[Any], Str, "Type name of a JSON value."),
+    native("sjson_new_object", &[], Any, "A new empty JSON object."),
+    native("sjson_new_array", &[], Any, "A new empty JSON array."),
+    native("sjson_arr_len", &[Any], Float, "Length of a JSON array."),
+    native("sjson_arr_get", &[Any, Float], Any, "Item of a JSON array."),
+    native(
+        "sjson_arr_set",
+        &[Any, Float, Any],
+        Bool,
+        "Replaces an item of a JSON array.",
+    ),
+    native(
+        "sjson_arr_push",
+        &[Any, Any],
+        Bool,
+        "Appends to a JSON array.",
+    ),
+    native(
+        "sjson_path_get",
+        &[Any, Str],
+        Any,
+        "Value at a dotted path such as `a.b.0`.",
+    ),
+    sig("is_0", &[Any], Bool, "Whether the value is zero."),
+    // SNIF (library surface over SNIF runtime)
+    native("snif_new_object", &[], Any, "A new empty SNIF object."),
+    native("snif_new_array", &[], Any, "A new empty SNIF array."),
+    native(
+        "snif_parse_ex",
+        &[Str],
+        Any,
+        "Parses SNIF text, reporting where it is invalid.",
+    ),
+    native("snif_type", &[Any], Str, "Type name of a SNIF value."),
+    native("snif_arr_len", &[Any], Float, "Length of a SNIF array."),
+    native("snif_arr_get", &[Any, Float], Any, "Item of a SNIF array."),
+    native(
+        "snif_arr_set",
+        &[Any, Float, Any],
+        Bool,
+        "Replaces an item of a SNIF array.",
+    ),
+    native(
+        "snif_arr_push",
+        &[Any, Any],
+        Bool,
+        "Appends to a SNIF array.",
+    ),
+    native(
+        "snif_path_get",
+        &[Any, Str],
+        Any,
+        "Value at a dotted path such as `a.b.0`.",
+    ),
+    // Auth (used by blaze_auth)
+    native(
+        "auth_random_hex",
+        &[Float],
+        Str,
+        "Random hex string of `n` bytes.",
+    ),
+    native("auth_now", &[], Float, "Current time in epoch seconds."),
+    native(
+        "auth_const_time_eq",
+        &[Str, Str],
+        Bool,
+        "Compares two strings in constant time.",
+    ),
+    native(
+        "auth_hash_password",
+        &[Str],
+        Str,
+        "Salted hash of a password.",
+    ),
+    native(
+        "auth_verify_password",
+        &[Str, Str],
+        Bool,
+        "Whether a password matches a stored hash.",
+    ),
+    native("auth_session_id", &[], Str, "A new random session id."),
+    native("auth_csrf_token", &[], Str, "A new random CSRF token."),
+    native(
+        "auth_cookie_kv",
+        &[Str, Str],
+        Str,
+        "A `Set-Cookie` value for a key and value.",
+    ),
+    native(
+        "auth_cookie_session",
+        &[Str],
+        Str,
+        "A `Set-Cookie` value for a session id.",
+    ),
+    native(
+        "auth_cookie_delete",
+        &[Str],
+        Str,
+        "A `Set-Cookie` value that expires a cookie.",
+    ),
+    native(
+        "auth_bearer_header",
+        &[Str],
+        Str,
+        "An `Authorization: Bearer` header value.",
+    ),
+    native(
+        "auth_ok",
+        &[],
+        Bool,
+        "Always true; checks the auth library is linked.",
+    ),
+    native(
+        "auth_fail",
+        &[],
+        Bool,
+        "Always false; placeholder for a failed check.",
+    ),
+    native("auth_version", &[], Str, "Version of the auth library."),
+    // SFS (filesystem)
+    native("sfs_read", &[Str], Str, "Contents of a file as a string."),
+    native("binfile_size", &[Str], Float, "Size of a file in bytes."),
+    native(
+        "binfile_read_into",
+        &[Str, Ptr, Float],
+        Float,
+        "Reads up to `max` bytes of a file into a buffer; returns the count.",
+    ),
+    native(
+        "sfs_write",
+        &[Str, Str],
+        Bool,
+        "Writes a string to a file, replacing it.",
+    ),
+    native(
+        "sfs_append",
+        &[Str, Str],
+        Bool,
+        "Appends a string to a file.",
+    ),
+    native(
+        "sfs_write_mb",
+        &[Str, Float],
+        Float,
+        "Writes a file of `mb` megabytes; used by benchmarks.",
+    ),
+    native(
+        "sfs_count_bytes",
+        &[Str],
+        Float,
+        "Reads a file and returns how many bytes it has.",
+    ),
+    native("sfs_delete", &[Str], Bool, "Deletes a file."),
+    native("sfs_exists", &[Str], Bool, "Whether a path exists."),
+    native("sfs_copy", &[Str, Str], Bool, "Copies a file."),
+    native("sfs_move", &[Str, Str], Bool, "Moves or renames a file."),
+    native("sfs_mkdir", &[Str], Bool, "Creates a directory."),
+    native(
+        "sfs_is_file",
+        &[Str],
+        Bool,
+        "Whether the path is a regular file.",
+    ),
+    native(
+        "sfs_is_dir",
+        &[Str],
+        Bool,
+        "Whether the path is a directory.",
+    ),
+    native(
+        "sfs_listdir",
+        &[Str],
+        Any,
+        "Names of the entries in a directory.",
+    ),
+    native(
+        "sfs_bench_create_small_files",
+        &[Str, Float, Float],
+        Float,
+        "Creates `count` files of `size` bytes; used by benchmarks.",
+    ),
+    native(
+        "sfs_bench_count_entries",
+        &[Str],
+        Float,
+        "Counts directory entries; used by benchmarks.",
+    ),
+    native(
+        "sfs_bench_delete_small_files",
+        &[Str, Float],
+        Float,
+        "Deletes files made by `sfs_bench_create_small_files`.",
+    ),
+    native("sfs_size", &[Str], Float, "Size of a file in bytes."),
+    native(
+        "sfs_mtime",
+        &[Str],
+        Float,
+        "Modification time in epoch seconds.",
+    ),
+    native("sfs_rmdir", &[Str], Bool, "Removes an empty directory."),
+    native(
+        "sfs_open",
+        &[Str, Str],
+        Str,
+        "Opens a file for streaming (`\"r\"`, `\"w\"` or `\"a\"`) and returns its handle.",
+    ),
+    native(
+        "sfs_read_line",
+        &[Str],
+        Str,
+        "Next line of an open file without the line break, or nil at the end.",
+    ),
+    native(
+        "sfs_write_line",
+        &[Str, Str],
+        Bool,
+        "Writes a string and a line break to an open file.",
+    ),
+    native(
+        "sfs_close",
+        &[Str],
+        Bool,
+        "Closes a file opened with `sfs_open`.",
+    ),
+    // Blaze (Web Server)
+    native(
+        "blaze_run",
+        &[Float, Any],
+        Bool,
+        "Serves HTTP on `port` using a dict of routes.",
+    ),
+    native(
+        "blaze_qs_get",
+        &[Str, Str],
+        Str,
+        "Value of a query-string parameter.",
+    ),
+    native(
+        "blaze_cookie_get",
+        &[Str, Str],
+        Str,
+        "Value of a cookie from a `Cookie` header.",
+    ),
+    // OS
+    native(
+        "os_platform",
+        &[],
+        Str,
+        "Operating system name, e.g. `linux`.",
+    ),
+    native("os_arch", &[], Str, "CPU architecture, e.g. `x86_64`."),
+    native(
+        "os_getenv",
+        &[Str],
+        Str,
+        "Value of an environment variable, or an empty string.",
+    ),
+    native(
+        "os_setenv",
+        &[Str, Str],
+        Bool,
+        "Sets an environment variable.",
+    ),
+    native(
+        "os_random_hex",
+        &[Float],
+        Str,
+        "Random hex string of `n` bytes.",
+    ),
+    // Misc "s_*" core helpers (runtime)
+    native("s_time", &[], Float, "Seconds since the Unix epoch."),
+    native(
+        "s_sleep",
+        &[Float],
+        Void,
+        "Pauses for the given number of milliseconds.",
+    ),
+    // Path helpers
+    native("path_basename", &[Str], Str, "Last component of a path."),
+    native(
+        "path_dirname",
+        &[Str],
+        Str,
+        "Path without its last component.",
+    ),
+    native(
+        "path_extname",
+        &[Str],
+        Str,
+        "Extension of a path, including the dot.",
+    ),
+    native("path_join", &[Str, Str], Str, "Joins two path components."),
+    // GUI (GTK runtime surface via `import "snask_gtk"`)
+    native("gui_init", &[], Bool, "Initializes the GUI toolkit."),
+    native("gui_run", &[], Void, "Runs the GUI main loop."),
+    native("gui_quit", &[], Void, "Stops the GUI main loop."),
+    native(
+        "gui_window",
+        &[Str, Float, Float],
+        Any,
+        "A new window with a title and size.",
+    ),
+    native("gui_set_title", &[Any, Str], Bool, "Sets a window's title."),
+    native(
+        "gui_set_resizable",
+        &[Any, Bool],
+        Bool,
+        "Sets whether a window can be resized.",
+    ),
+    native(
+        "gui_autosize",
+        &[Any],
+        Bool,
+        "Sizes a window to its content.",
+    ),
+    native("gui_vbox", &[], Any, "A vertical box container."),
+    native("gui_hbox", &[], Any, "A horizontal box container."),
+    native("gui_scrolled", &[], Any, "A scrollable container."),
+    native(
+        "gui_eventbox",
+        &[],
+        Any,
+        "A container that receives clicks.",
+    ),
+    native(
+        "gui_flowbox",
+        &[],
+        Any,
+        "A container that wraps its children.",
+    ),
+    native(
+        "gui_flow_add",
+        &[Any, Any],
+        Bool,
+        "Adds a widget to a flow box.",
+    ),
+    native("gui_frame", &[], Any, "A framed container."),
+    native(
+        "gui_set_margin",
+        &[Any, Float],
+        Bool,
+        "Sets a widget's margin on every side.",
+    ),
+    native(
+        "gui_icon",
+        &[Any, Str],
+        Bool,
+        "Sets a widget's icon by name.",
+    ),
+    native("gui_listbox", &[], Any, "A selectable list."),
+    native(
+        "gui_list_add_text",
+        &[Any, Str],
+        Bool,
+        "Adds a text row to a list.",
+    ),
+    native(
+        "gui_on_select_ctx",
+        &[Any, Any, Any],
+        Bool,
+        "Calls a handler by name, with a context string, when a row is selected.",
+    ),
+    native(
+        "gui_set_child",
+        &[Any, Any],
+        Bool,
+        "Sets the single child of a container.",
+    ),
+    native(
+        "gui_add",
+        &[Any, Any],
+        Bool,
+        "Adds a widget to a container.",
+    ),
+    native(
+        "gui_add_expand",
+        &[Any, Any],
+        Bool,
+        "Adds a widget that takes the free space.",
+    ),
+    native("gui_label", &[Str], Any, "A text label."),
+    native("gui_entry", &[], Any, "A single-line text input."),
+    native("gui_textview", &[], Any, "A multi-line text input."),
+    native(
+        "gui_set_placeholder",
+        &[Any, Str],
+        Bool,
+        "Sets an input's placeholder text.",
+    ),
+    native(
+        "gui_set_editable",
+        &[Any, Bool],
+        Bool,
+        "Sets whether an input can be edited.",
+    ),
+    native("gui_button", &[Str], Any, "A button with a label."),
+    native(
+        "gui_set_enabled",
+        &[Any, Bool],
+        Bool,
+        "Enables or disables a widget.",
+    ),
+    native(
+        "gui_set_visible",
+        &[Any, Bool],
+        Bool,
+        "Shows or hides a widget.",
+    ),
+    native(
+        "gui_show_all",
+        &[Any],
+        Bool,
+        "Shows a widget and its children.",
+    ),
+    native(
+        "gui_set_text",
+        &[Any, Str],
+        Bool,
+        "Sets the text of a label or input.",
+    ),
+    native("gui_get_text", &[Any], Str, "Text of a label or input."),
+    native(
+        "gui_on_click",
+        &[Any, Any],
+        Bool,
+        "Calls a handler by name when the widget is clicked.",
+    ),
+    native(
+        "gui_on_click_ctx",
+        &[Any, Any, Any],
+        Bool,
+        "Calls a handler by name, with a context string, on click.",
+    ),
+    native(
+        "gui_on_tap_ctx",
+        &[Any, Any, Any],
+        Bool,
+        "Calls a handler by name, with a context string, on tap.",
+    ),
+    native("gui_separator_h", &[], Any, "A horizontal separator."),
+    native("gui_separator_v", &[], Any, "A vertical separator."),
+    native(
+        "gui_css",
+        &[Str],
+        Bool,
+        "Loads a CSS stylesheet for the application.",
+    ),
+    native(
+        "gui_add_class",
+        &[Any, Str],
+        Bool,
+        "Adds a CSS class to a widget.",
+    ),
+    native(
+        "gui_msg_info",
+        &[Str, Str],
+        Bool,
+        "Shows an information dialog.",
+    ),
+    native("gui_msg_error", &[Str, Str], Bool, "Shows an error dialog."),
+    // snaskgui: framebuffer-first native surface for emulators/games.
+    native(
+        "snaskgui_init",
+        &[],
+        Bool,
+        "Initializes the framebuffer GUI.",
+    ),
+    native(
+        "snaskgui_window",
+        &[Str, Float, Float, Float],
+        Any,
+        "A new framebuffer window with a title, size and scale.",
+    ),
+    native(
+        "snaskgui_present_rgba",
+        &[Any, Ptr, Float, Float],
+        Bool,
+        "Draws an RGBA pixel buffer to the window.",
+    ),
+    native(
+        "snaskgui_poll",
+        &[Any],
+        Bool,
+        "Processes pending window events.",
+    ),
+    native(
+        "snaskgui_key_down",
+        &[Any, Float],
+        Bool,
+        "Whether a key is held.",
+    ),
+    native(
+        "snaskgui_mouse_x",
+        &[Any],
+        Float,
+        "Mouse x position in the window.",
+    ),
+    native(
+        "snaskgui_mouse_y",
+        &[Any],
+        Float,
+        "Mouse y position in the window.",
+    ),
+    native(
+        "snaskgui_mouse_down",
+        &[Any, Float],
+        Bool,
+        "Whether a mouse button is held.",
+    ),
+    native(
+        "snaskgui_should_close",
+        &[Any],
+        Bool,
+        "Whether the window was asked to close.",
+    ),
+    native(
+        "snaskgui_delay",
+        &[Float],
+        Void,
+        "Pauses for the given number of milliseconds.",
+    ),
+    native("snaskgui_close", &[Any], Void, "Closes the window."),
+];
+
+/// Calls emitted by the `snask test` driver. They are registered only while
+/// analyzing a test build, so user code cannot call them.
+pub const TEST_DRIVER_BUILTINS: &[BuiltinSig] = &[
+    sig(
+        "snask_test_run",
+        &[Str],
+        Bool,
+        "Runs one test function by name.",
+    ),
+    sig("snask_test_summary", &[], Void, "Prints the test summary."),
+];
@@ -0,0 +1,175 @@
+use crate::sps;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project-local directory for cached build outputs, removed by `--cache`.
+pub const BUILD_CACHE_DIR: &str = ".snask-cache";
+
+/// Removes the build outputs of the project in the current directory.
+pub fn run_clean(dist: bool, cache: bool) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let removed = clean_project(&cwd, dist, cache)?;
+    if removed.is_empty() {
+        println!("✨ Nothing to clean.");
+        return Ok(());
+    }
+    for path in &removed {
+        let shown = path.strip_prefix(&cwd).unwrap_or(path);
+        println!("🧹 removed {}", shown.display());
+    }
+    Ok(())
+}
+
+/// Deletes the project binary (from `snask.snif` name/entry), stray
+/// `temp_snask.*` files and, if asked, `dist/` and the build cache. Every
+/// target is checked to be inside `dir` before anything is deleted.
+pub fn clean_project(dir: &Path, dist: bool, cache: bool) -> Result<Vec<PathBuf>, String> {
+    let root = dir
+        .canonicalize()
+        .map_err(|e| format!("clean: cannot resolve {}: {}", dir.display(), e))?;
+
+    // (path, may be a directory)
+    let mut candidates: Vec<(PathBuf, bool)> = Vec::new();
+    if let Ok((m, _)) = sps::load_manifest_from(&root) {
+        let entry_bin = m.package.entry.replace(".snask", "");
+        for base in [m.package.name.clone(), entry_bin] {
+            candidates.push((root.join(&base), false));
+            candidates.push((root.join(format!("{}.wasm", base)), false));
+        }
+    }
+    let entries = fs::read_dir(&root).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with("temp_snask.") {
+            candidates.push((entry.path(), false));
+        }
+    }
+    if dist {
+        candidates.push((root.join("dist"), true));
+    }
+    if cache {
+        candidates.push((root.join(BUILD_CACHE_DIR), true));
+    }
+
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for (path, dir_ok) in candidates {
+        let Ok(resolved) = path.canonicalize() else {
+            continue;
+        };
+        // A directory that happens to share the binary's name is not ours.
+        if resolved.is_dir() && !dir_ok {
+            continue;
+        }
+        if !resolved.starts_with(&root) || resolved == root {
+            return Err(format!(
+                "clean: refusing to delete {} because it is outside the project directory {}",
+                resolved.display(),
+                root.display()
+            ));
+        }
+        if is_source_file(&resolved) || targets.contains(&resolved) {
+            continue;
+        }
+        targets.push(resolved);
+    }
+
+    for path in &targets {
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        result.map_err(|e| format!("clean: failed to remove {}: {}", path.display(), e))?;
+    }
+    Ok(targets)
+}
+
+fn is_source_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("snask") | Some("snif") | Some("lock")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_project, BUILD_CACHE_DIR};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("snask_clean_test_{name}_{nonce}"));
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        dir
+    }
+
+    #[test]
+    fn clean_removes_binary_and_dist_but_keeps_sources() {
+        let dir = temp_dir("project");
+        fs::write(
+            dir.join("snask.snif"),
+            r#"{ package: { name: "demo", version: "0.1.0", entry: "main.snask" } }"#,
+        )
+        .unwrap();
+        let source = "class main\n    fun start()\n        print(1)\n";
+        fs::write(dir.join("main.snask"), source).unwrap();
+        fs::write(dir.join("demo"), b"\x7fELF").unwrap();
+        fs::write(dir.join("temp_snask.ll"), "; ir").unwrap();
+        fs::create_dir_all(dir.join("dist")).unwrap();
+        fs::write(dir.join("dist").join("demo-x86_64"), b"bin").unwrap();
+
+        let removed = clean_project(&dir, true, false).expect("clean should succeed");
+
+        assert_eq!(removed.len(), 3, "{removed:?}");
+        assert!(!dir.join("demo").exists());
+        assert!(!dir.join("temp_snask.ll").exists());
+        assert!(!dir.join("dist").exists());
+        assert!(dir.join("main.snask").exists());
+        assert!(dir.join("snask.snif").exists());
+    }
+
+    #[test]
+    fn clean_removes_the_build_cache_only_with_cache() {
+        let dir = temp_dir("cache");
+        fs::write(
+            dir.join("snask.snif"),
+            r#"{ package: { name: "demo", version: "0.1.0", entry: "main.snask" } }"#,
+        )
+        .unwrap();
+        let source = "class main\n    fun start()\n        print(1)\n";
+        fs::write(dir.join("main.snask"), source).unwrap();
+        let cache = dir.join(BUILD_CACHE_DIR);
+        fs::create_dir_all(cache.join("objects")).unwrap();
+        fs::write(cache.join("objects").join("main.o"), b"obj").unwrap();
+
+        let removed = clean_project(&dir, false, false).expect("clean should succeed");
+        assert!(removed.is_empty(), "{removed:?}");
+        assert!(cache.exists());
+
+        let removed = clean_project(&dir, false, true).expect("clean should succeed");
+        assert_eq!(removed.len(), 1, "{removed:?}");
+        assert!(!cache.exists());
+        assert!(dir.join("main.snask").exists());
+    }
+
+    #[test]
+    fn clean_refuses_to_delete_outside_the_project() {
+        let outer = temp_dir("outer");
+        let dir = outer.join("project");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("snask.snif"),
+            r#"{ package: { name: "demo", version: "0.1.0", entry: "../elsewhere.snask" } }"#,
+        )
+        .unwrap();
+        fs::write(outer.join("elsewhere"), b"bin").unwrap();
+
+        let err = clean_project(&dir, false, false).expect_err("outside target must be refused");
+        assert!(err.contains("outside the project directory"), "{err}");
+        assert!(outer.join("elsewhere").exists());
+    }
+}
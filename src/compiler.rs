@@ -1,9 +1,12 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use inkwell::context::Context;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use crate::ast::{
     ClassDecl, ConditionalStmt, ConstDecl, DictDecl, DictSet, Expr, ExprKind, FuncDecl, IfBlock,
@@ -11,10 +14,12 @@ use crate::ast::{
     PropertyAssignment, Stmt, StmtKind, VarDecl, VarSet,
 };
 use crate::diagnostics::{humane_code, Annotation, Diagnostic, DiagnosticBag};
+use crate::hds::{with_fixit_help, FixIt, FixItApply, FixItKind};
 use crate::llvm_generator::LLVMGenerator;
 use crate::modules::is_native_module;
 use crate::om_contract::{load_builtin_om_contract, load_om_contract, OmContract};
 use crate::om_scan::{scan_header, ScanOptions};
+use crate::optimizer::eliminate_dead_functions;
 use crate::parser::{ParseError, Parser};
 use crate::semantic_analyzer::{SemanticAnalyzer, SemanticError};
 use crate::sps::SnifFeatureValue;
@@ -28,6 +33,8 @@ pub struct BuildOptions {
     pub output_name: Option<String>,
     pub target: Option<String>,
     pub profile: BuildProfile,
+    /// `-O` level passed to `llc`; at 2 and above unreachable user functions
+    /// are also dropped before code generation.
     pub opt_level: u8,
     pub lto: bool,
     pub release_size: bool,
@@ -35,8 +42,152 @@ pub struct BuildOptions {
     pub tiny: bool,
     pub extreme: bool,
     pub strip: bool,
+    /// Emit DWARF function and line info (`snask build --debug`/`-g`);
+    /// the binary is never stripped then.
+    pub debug: bool,
     pub opt_override: Option<String>,
     pub features: BTreeMap<String, SnifFeatureValue>,
+    /// Compile-time values read by `cfg("KEY")`: the manifest's
+    /// `build.defines` overlaid with `--define KEY=VAL`.
+    pub defines: BTreeMap<String, String>,
+    /// Workspace members this build can import by package name, mapped to
+    /// their entry file. Resolved before the registry packages.
+    pub path_deps: BTreeMap<String, PathBuf>,
+    pub verbosity: Verbosity,
+    /// Compile `assert(...)` calls away (`snask build --no-assert`).
+    pub no_assert: bool,
+    /// Build a module object without `class main` (`snask build --no-main`).
+    pub no_main: bool,
+    /// Replace `class main` with a driver that runs every `test_*`
+    /// function (`snask test`).
+    pub test_mode: bool,
+    /// Only run tests whose name contains this string.
+    pub test_filter: Option<String>,
+    /// Count function entries (`snask test --coverage`). Off by default,
+    /// as every call pays for the counter.
+    pub coverage: bool,
+    /// Fail the build when there are warnings (`--deny-warnings`). They are
+    /// still printed as warnings.
+    pub deny_warnings: bool,
+    /// Deepest chain of nested imports; `None` uses
+    /// [`DEFAULT_MAX_IMPORT_DEPTH`].
+    pub max_import_depth: Option<usize>,
+    /// Keep resolving the other imports after a module fails and report
+    /// every failure together (`snask build --keep-going`).
+    pub keep_going: bool,
+}
+
+/// Parses a `--define` argument: `KEY=VAL`, or a bare `KEY` meaning `true`.
+pub fn parse_define(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg.split_once('=').unwrap_or((arg, "true"));
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("invalid --define `{arg}`: expected KEY or KEY=VAL"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// How many files deep `import`s may nest before the build gives up.
+pub const DEFAULT_MAX_IMPORT_DEPTH: usize = 64;
+
+/// Nesting level of the file whose imports are being resolved. Import cycles
+/// are cut by the resolved-module set; this stops legitimate but absurdly
+/// deep chains before they overflow the stack.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportDepth {
+    level: usize,
+    max: usize,
+}
+
+impl ImportDepth {
+    /// The entry file, allowing `max` nested imports below it.
+    pub fn root(max: Option<usize>) -> Self {
+        ImportDepth {
+            level: 0,
+            max: max.unwrap_or(DEFAULT_MAX_IMPORT_DEPTH),
+        }
+    }
+
+    fn enter(self, module_name: &str, module_path: &str) -> Result<Self, String> {
+        if self.level >= self.max {
+            return Err(format!(
+                "Import depth limit of {} exceeded at module '{}' ({}).\nhelp: flatten the import chain; each file in it imports the next.",
+                self.max, module_name, module_path
+            ));
+        }
+        Ok(ImportDepth {
+            level: self.level + 1,
+            ..self
+        })
+    }
+}
+
+/// Modules that failed to load while resolving imports. The first failure
+/// stops the build unless `keep_going` is set, in which case each one is
+/// recorded and the remaining imports are still resolved.
+#[derive(Debug, Default)]
+pub struct ImportErrors {
+    keep_going: bool,
+    errors: Vec<String>,
+}
+
+impl ImportErrors {
+    pub fn new(keep_going: bool) -> Self {
+        ImportErrors {
+            keep_going,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Passes `result` through, or records its error when keeping going.
+    fn record(&mut self, result: Result<(), String>) -> Result<(), String> {
+        match result {
+            Err(e) if self.keep_going => {
+                self.errors.push(e);
+                Ok(())
+            }
+            other => other,
+        }
+    }
+
+    /// Fails with every recorded error, in the order they were found.
+    pub fn finish(self) -> Result<(), String> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let count = self.errors.len();
+        let mut out = self.errors.join("\n");
+        out.push_str(&format!(
+            "\n\nerror: {} module{} failed to load\n",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+        Err(out)
+    }
+}
+
+/// How much the build pipeline prints (`-q`, default, `-v`, `-vv`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// No progress bar.
+    Quiet,
+    #[default]
+    Normal,
+    /// Also print the resolved toolchain and the output path.
+    Verbose,
+    /// Also print every `llc`/`clang` command line with its link args.
+    Trace,
+}
+
+impl Verbosity {
+    pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,28 +237,113 @@ pub fn resolve_entry_file(cli_file: Option<String>) -> Result<String, String> {
     Err("SPS: no input file provided and `snask.snif` was not found in the current directory.\n\nHow to fix:\n- Build a file directly: `snask build main.snask`\n- Or create an SPS project: `snask init` and then `snask build`\n".to_string())
 }
 
+/// Entry-file argument meaning "read the program from stdin".
+pub const STDIN_FILE: &str = "-";
+/// File name shown in diagnostics for a program read from stdin.
+pub const STDIN_NAME: &str = "<stdin>";
+
+fn read_stdin() -> Result<String, String> {
+    let mut source = String::new();
+    std::io::stdin()
+        .read_to_string(&mut source)
+        .map_err(|e| format!("Failed to read source from stdin: {}", e))?;
+    Ok(source)
+}
+
 pub fn build_file(file_path: &str, options: BuildOptions) -> Result<(), String> {
-    let pb = ProgressBar::new(7);
+    if file_path == STDIN_FILE {
+        if options.output_name.is_none() {
+            return Err(
+                "a program read from stdin has no file name; pass an output: `snask build - -o <name>`"
+                    .to_string(),
+            );
+        }
+        let source = read_stdin()?;
+        return build_source(STDIN_NAME, &source, Path::new("."), options);
+    }
+    let source = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+    let entry_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    build_source(file_path, &source, entry_dir, options)
+}
+
+/// Runs the whole pipeline on `source`. `file_path` is only used for
+/// diagnostics, the LLVM module name and the default output name.
+pub fn build_source(
+    file_path: &str,
+    source: &str,
+    entry_dir: &Path,
+    options: BuildOptions,
+) -> Result<(), String> {
+    let pb = build_progress_bar(options.verbosity);
+    let GeneratedIr {
+        ir,
+        program: resolved_program,
+        link_tiny_runtime,
+    } = generate_ir(file_path, source, entry_dir, &options, &pb)?;
+
+    let size_link = options.release_size || options.tiny || options.extreme;
+    if options.strip && size_link && uses_name_dispatch(&resolved_program) {
+        eprintln!(
+            "warning: `--strip` with a size profile: GUI/blaze handlers are found by name with `dlsym`, but size profiles link without `--export-dynamic`, so they will not resolve.\nhelp: build with the default profile to keep the handlers exported."
+        );
+    }
+
+    let extra_pkgs = get_imported_pkgs(&resolved_program);
+    link_binary(
+        file_path,
+        ir.into_bytes(),
+        options,
+        link_tiny_runtime,
+        &pb,
+        extra_pkgs,
+    )?;
+
+    pb.finish_with_message("OK");
+    Ok(())
+}
+
+pub fn build_progress_bar(verbosity: Verbosity) -> ProgressBar {
+    let pb = if verbosity == Verbosity::Quiet || !std::io::stderr().is_terminal() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(7)
+    };
     pb.set_style(
         ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} {msg}")
             .unwrap()
             .progress_chars("=>-"),
     );
+    pb
+}
+
+/// LLVM IR of a program, with the resolved program it was generated from.
+pub struct GeneratedIr {
+    pub ir: String,
+    pub program: Program,
+    pub link_tiny_runtime: bool,
+}
 
+/// Every stage of the pipeline up to LLVM IR (everything but linking).
+pub fn generate_ir(
+    file_path: &str,
+    source: &str,
+    entry_dir: &Path,
+    options: &BuildOptions,
+    pb: &ProgressBar,
+) -> Result<GeneratedIr, String> {
     pb.set_message("Reading file");
-    let source = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
     pb.inc(1);
 
     pb.set_message("Parser (tokens/AST)");
-    let mut parser = Parser::new(&source).map_err(|e| {
+    let mut parser = Parser::new(source).map_err(|e| {
         pb.finish_and_clear();
-        render_parser_diagnostic(file_path, &source, &e)
+        render_parser_diagnostic(file_path, source, &e)
     })?;
 
     let (program_opt, parse_errors) = parser.parse_program_recovering(10);
     if !parse_errors.is_empty() {
         pb.finish_and_clear();
-        return Err(render_parser_diagnostics(file_path, &source, &parse_errors));
+        return Err(render_parser_diagnostics(file_path, source, &parse_errors));
     }
     let mut program = program_opt.unwrap_or_default();
 
@@ -116,41 +352,59 @@ pub fn build_file(file_path: &str, options: BuildOptions) -> Result<(), String>
     pb.inc(1);
 
     // Auto-import stdlib modules
-    let loc = Location { line: 0, column: 0 };
+    let loc = Location::default();
     let span = loc.to_span();
     program.push(Stmt::with_span(
-        StmtKind::Import("stdio".to_string()),
+        StmtKind::Import {
+            path: "stdio".to_string(),
+            alias: None,
+        },
         loc,
         span,
     ));
 
     // Validate entrypoint
-    validate_entrypoint(&program)?;
+    check_entrypoint(&program, options)?;
 
     pb.set_message("Resolving imports");
     let mut resolved_program = Vec::new();
     let mut resolved_modules = HashSet::new();
     resolved_modules.insert(file_path.to_string());
-    let entry_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    let mut import_errors = ImportErrors::new(options.keep_going);
     resolve_imports(
         &mut program,
         entry_dir,
+        &options.path_deps,
         &mut resolved_program,
         &mut resolved_modules,
+        ImportDepth::root(options.max_import_depth),
+        &mut import_errors,
     )?;
+    // Analyzing without the failed modules would only add unresolved names.
+    import_errors.finish()?;
     pb.inc(1);
 
     pb.set_message("Expanding inheritance");
     expand_inheritance(&mut resolved_program)?;
+    // Before analysis, so branches that `cfg` turns off are never checked.
+    crate::optimizer::resolve_cfg(&mut resolved_program, &options.defines);
     pb.inc(1);
 
+    if options.test_mode {
+        let filter = options.test_filter.as_deref();
+        if let Err(e) = crate::test_runner::synthesize_test_driver(&mut resolved_program, filter) {
+            pb.finish_and_clear();
+            return Err(e);
+        }
+    }
+
     if options.profile == BuildProfile::Baremetal {
         let restrictions = find_baremetal_restrictions(&resolved_program);
         if !restrictions.is_empty() {
             pb.finish_and_clear();
             return Err(render_baremetal_restrictions(
                 file_path,
-                &source,
+                source,
                 &restrictions,
             ));
         }
@@ -167,49 +421,200 @@ pub fn build_file(file_path: &str, options: BuildOptions) -> Result<(), String>
             "`--min-runtime` cannot be used with GUI/SQLite/Skia/Web imports.\n".to_string(),
         );
     }
+    if needs_full_runtime && is_wasm_target(options.target.as_deref()) {
+        pb.finish_and_clear();
+        return Err(format!(
+            "GUI/SQLite/zlib/Skia/Web imports and `import_c_om` are not available for `{}`.\n",
+            options.target.as_deref().unwrap_or_default()
+        ));
+    }
     let link_tiny_runtime = options.tiny || (options.min_runtime && !needs_full_runtime);
 
     pb.set_message("Semantic analysis");
     let mut analyzer = SemanticAnalyzer::new();
     analyzer.set_tiny_mode(options.tiny);
+    analyzer.set_test_mode(options.test_mode);
+    analyzer.expand_type_aliases(&mut resolved_program);
     analyzer.analyze(&resolved_program);
     if !analyzer.errors.is_empty() {
         pb.finish_and_clear();
         return Err(render_semantic_diagnostics(
             file_path,
-            &source,
+            source,
             &analyzer.errors,
         ));
     }
+    if let Err(e) = pb.suspend(|| {
+        report_semantic_warnings(file_path, source, &analyzer.warnings, options.deny_warnings)
+    }) {
+        pb.finish_and_clear();
+        return Err(e);
+    }
     pb.inc(1);
 
+    if options.opt_level >= 2 {
+        eliminate_dead_functions(&mut resolved_program);
+    }
+
     pb.set_message("Generating LLVM IR");
     let context = Context::create();
     let mut generator = LLVMGenerator::new(&context, file_path);
     generator.set_om_contracts(load_om_contracts(&resolved_program)?);
+    generator.set_asserts_enabled(!options.no_assert);
+    generator.set_module_mode(options.no_main);
+    generator.set_coverage(options.coverage);
+    generator.set_debug_info(options.debug);
     let ir = generator.generate(resolved_program.clone())?;
     pb.inc(1);
 
-    let extra_pkgs = get_imported_pkgs(&resolved_program);
-    link_binary(
-        file_path,
-        ir.into_bytes(),
-        options,
+    Ok(GeneratedIr {
+        ir,
+        program: resolved_program,
         link_tiny_runtime,
-        &pb,
-        extra_pkgs,
+    })
+}
+
+/// `snask check`: parse, resolve imports and run semantic analysis without
+/// generating code. Reads stdin when `file_path` is `-`.
+pub fn check_file(
+    file_path: &str,
+    deny_warnings: bool,
+    max_import_depth: Option<usize>,
+) -> Result<(), String> {
+    let (name, source, entry_dir) = if file_path == STDIN_FILE {
+        (STDIN_NAME, read_stdin()?, PathBuf::from("."))
+    } else {
+        let source = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+        let dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+        (file_path, source, dir.to_path_buf())
+    };
+    check_source(name, &source, &entry_dir, deny_warnings, max_import_depth)?;
+    println!("✅ {}: no problems found", name);
+    Ok(())
+}
+
+pub fn check_source(
+    file_path: &str,
+    source: &str,
+    entry_dir: &Path,
+    deny_warnings: bool,
+    max_import_depth: Option<usize>,
+) -> Result<(), String> {
+    let mut parser =
+        Parser::new(source).map_err(|e| render_parser_diagnostic(file_path, source, &e))?;
+    let (program_opt, parse_errors) = parser.parse_program_recovering(10);
+    if !parse_errors.is_empty() {
+        return Err(render_parser_diagnostics(file_path, source, &parse_errors));
+    }
+    let mut program = program_opt.unwrap_or_default();
+
+    let loc = Location::default();
+    let span = loc.to_span();
+    program.push(Stmt::with_span(
+        StmtKind::Import {
+            path: "stdio".to_string(),
+            alias: None,
+        },
+        loc,
+        span,
+    ));
+
+    let mut resolved_program = Vec::new();
+    let mut resolved_modules = HashSet::new();
+    resolved_modules.insert(file_path.to_string());
+    resolve_imports(
+        &mut program,
+        entry_dir,
+        &BTreeMap::new(),
+        &mut resolved_program,
+        &mut resolved_modules,
+        ImportDepth::root(max_import_depth),
+        &mut ImportErrors::new(false),
     )?;
+    expand_inheritance(&mut resolved_program)?;
 
-    pb.finish_with_message("OK");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.expand_type_aliases(&mut resolved_program);
+    analyzer.analyze(&resolved_program);
+    if !analyzer.errors.is_empty() {
+        return Err(render_semantic_diagnostics(
+            file_path,
+            source,
+            &analyzer.errors,
+        ));
+    }
+    report_semantic_warnings(file_path, source, &analyzer.warnings, deny_warnings)
+}
+
+/// Every `.snask` file a build of `file_path` reads: the entry and each
+/// module it imports, directly or not. Modules that fail to load are still
+/// listed, and the imports of an entry that does not parse are skipped.
+pub fn source_files(file_path: &Path) -> Vec<PathBuf> {
+    let mut files = vec![file_path.to_path_buf()];
+    let Ok(source) = fs::read_to_string(file_path) else {
+        return files;
+    };
+    let Ok(mut program) = Parser::new(&source).and_then(|mut p| p.parse_program()) else {
+        return files;
+    };
+    let entry_dir = file_path.parent().unwrap_or(Path::new("."));
+    let mut resolved_modules = HashSet::new();
+    let _ = resolve_imports(
+        &mut program,
+        entry_dir,
+        &BTreeMap::new(),
+        &mut Vec::new(),
+        &mut resolved_modules,
+        ImportDepth::root(None),
+        &mut ImportErrors::new(true),
+    );
+    // Aliased imports are recorded as `path#alias`.
+    files.extend(
+        resolved_modules
+            .into_iter()
+            .map(|key| match key.rsplit_once('#') {
+                Some((path, _)) if Path::new(path).exists() => PathBuf::from(path),
+                _ => PathBuf::from(key),
+            }),
+    );
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Prints the analyzer's warnings. With `deny` they are returned as the
+/// build error instead, still rendered as warnings.
+fn report_semantic_warnings(
+    file_path: &str,
+    source: &str,
+    warnings: &[SemanticError],
+    deny: bool,
+) -> Result<(), String> {
+    if warnings.is_empty() {
+        return Ok(());
+    }
+    let rendered = render_semantic_diagnostics(file_path, source, warnings);
+    if deny {
+        return Err(format!(
+            "{}\nerror: aborting because of {} warning(s) (`--deny-warnings`)\n",
+            rendered,
+            warnings.len()
+        ));
+    }
+    eprint!("{}", rendered);
     Ok(())
 }
 
-fn get_imported_pkgs(program: &[Stmt]) -> Vec<String> {
+/// Native packages (`sqlite`, `zlib`) that need extra link arguments.
+pub fn get_imported_pkgs(program: &[Stmt]) -> Vec<String> {
     let mut pkgs = Vec::new();
     let mut seen = HashSet::new();
     for stmt in program {
         match &stmt.kind {
-            StmtKind::Import(module_name) => {
+            StmtKind::Import {
+                path: module_name,
+                ..
+            } => {
                 if (module_name == "sqlite" || module_name == "zlib")
                     && seen.insert(module_name.clone())
                 {
@@ -238,7 +643,10 @@ fn load_om_contracts(program: &[Stmt]) -> Result<Vec<OmContract>, String> {
 
     for stmt in program {
         match &stmt.kind {
-            StmtKind::Import(module_name) => {
+            StmtKind::Import {
+                path: module_name,
+                ..
+            } => {
                 if (module_name == "sqlite" || module_name == "zlib")
                     && seen.insert(module_name.clone())
                 {
@@ -385,7 +793,11 @@ fn inject_features(program: &mut Program, features: BTreeMap<String, SnifFeature
             SnifFeatureValue::String(s) => LiteralValue::String(s.clone()),
             SnifFeatureValue::Number(n) => LiteralValue::Number(n),
         };
-        let loc = Location { line: 1, column: 1 };
+        let loc = Location {
+            line: 1,
+            column: 1,
+            offset: 0,
+        };
         let span = loc.to_span();
         let const_decl = ConstDecl {
             name,
@@ -420,6 +832,17 @@ fn validate_entrypoint(program: &Program) -> Result<(), String> {
     )
 }
 
+/// Applications need `class main`; `--no-main` builds (libraries) and
+/// `snask test` builds, which generate their own, skip it.
+fn check_entrypoint(program: &Program, options: &BuildOptions) -> Result<(), String> {
+    if options.no_main || options.test_mode {
+        return Ok(());
+    }
+    validate_entrypoint(program).map_err(|e| {
+        format!("{e}\n\nhelp: to build a library file without an entry point, pass `--no-main`.")
+    })
+}
+
 fn expand_inheritance(program: &mut Program) -> Result<(), String> {
     let mut classes: std::collections::HashMap<String, crate::ast::ClassDecl> =
         std::collections::HashMap::new();
@@ -490,7 +913,7 @@ fn uses_full_runtime(program: &[Stmt]) -> bool {
     }
     for st in program {
         match &st.kind {
-            StmtKind::Import(m) => {
+            StmtKind::Import { path: m, .. } => {
                 if is_heavy_module(m) {
                     return true;
                 }
@@ -526,7 +949,7 @@ fn find_baremetal_restrictions(program: &[Stmt]) -> Vec<BaremetalRestriction> {
 
 fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<BaremetalRestriction>) {
     match &stmt.kind {
-        StmtKind::Print(exprs) => {
+        StmtKind::Print(print) => {
             restrictions.push(BaremetalRestriction {
                 span: stmt.span,
                 message: "print requires std runtime".to_string(),
@@ -536,11 +959,11 @@ fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<Barem
                     "`baremetal` starts without Snask std/runtime services by default.".to_string(),
                 ),
             });
-            for expr in exprs {
+            for expr in print.exprs() {
                 collect_baremetal_expr_restrictions(expr, restrictions);
             }
         }
-        StmtKind::Import(lib) => {
+        StmtKind::Import { path: lib, .. } => {
             if baremetal_runtime_module(lib) {
                 restrictions.push(BaremetalRestriction {
                     span: stmt.span,
@@ -571,7 +994,9 @@ fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<Barem
         | StmtKind::ConstDeclaration(ConstDecl { value: expr, .. })
         | StmtKind::VarAssignment(VarSet { value: expr, .. })
         | StmtKind::ListDeclaration(ListDecl { value: expr, .. })
-        | StmtKind::ListPush(ListPush { value: expr, .. }) => {
+        | StmtKind::ListPush(ListPush { value: expr, .. })
+        | StmtKind::TupleDeclaration { value: expr, .. }
+        | StmtKind::TupleAssignment { value: expr, .. } => {
             collect_baremetal_expr_restrictions(expr, restrictions);
         }
         StmtKind::PropertyAssignment(PropertyAssignment { target, value, .. }) => {
@@ -627,20 +1052,36 @@ fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<Barem
                 }
             }
         }
-        StmtKind::Loop(loop_stmt) => match loop_stmt {
-            LoopStmt::While { condition, body } => {
-                collect_baremetal_expr_restrictions(condition, restrictions);
-                for child in body {
-                    collect_baremetal_stmt_restrictions(child, restrictions);
+        StmtKind::Loop(loop_stmt) => {
+            let else_block = match loop_stmt {
+                LoopStmt::While {
+                    condition,
+                    body,
+                    else_block,
+                } => {
+                    collect_baremetal_expr_restrictions(condition, restrictions);
+                    for child in body {
+                        collect_baremetal_stmt_restrictions(child, restrictions);
+                    }
+                    else_block
                 }
-            }
-            LoopStmt::For { iterable, body, .. } => {
-                collect_baremetal_expr_restrictions(iterable, restrictions);
-                for child in body {
-                    collect_baremetal_stmt_restrictions(child, restrictions);
+                LoopStmt::For {
+                    iterable,
+                    body,
+                    else_block,
+                    ..
+                } => {
+                    collect_baremetal_expr_restrictions(iterable, restrictions);
+                    for child in body {
+                        collect_baremetal_stmt_restrictions(child, restrictions);
+                    }
+                    else_block
                 }
+            };
+            for child in else_block.iter().flatten() {
+                collect_baremetal_stmt_restrictions(child, restrictions);
             }
-        },
+        }
         StmtKind::UnsafeBlock(body)
         | StmtKind::Scope { body, .. }
         | StmtKind::Zone { body, .. } => {
@@ -648,6 +1089,11 @@ fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<Barem
                 collect_baremetal_stmt_restrictions(child, restrictions);
             }
         }
+        StmtKind::TryCatch { body, handler, .. } => {
+            for child in body.iter().chain(handler) {
+                collect_baremetal_stmt_restrictions(child, restrictions);
+            }
+        }
         StmtKind::Input { .. } => restrictions.push(BaremetalRestriction {
             span: stmt.span,
             message: "input requires std runtime".to_string(),
@@ -668,7 +1114,10 @@ fn collect_baremetal_stmt_restrictions(stmt: &Stmt, restrictions: &mut Vec<Barem
                 });
             }
         }
-        StmtKind::Promote { .. } | StmtKind::Entangle { .. } => {}
+        StmtKind::Promote { .. }
+        | StmtKind::Entangle { .. }
+        | StmtKind::EnumDeclaration { .. }
+        | StmtKind::TypeAlias { .. } => {}
     }
 }
 
@@ -705,11 +1154,27 @@ fn collect_baremetal_expr_restrictions(expr: &Expr, restrictions: &mut Vec<Barem
             collect_baremetal_expr_restrictions(target, restrictions);
             collect_baremetal_expr_restrictions(index, restrictions);
         }
-        ExprKind::New { args, .. } => {
+        ExprKind::Slice {
+            target,
+            start,
+            end,
+            step,
+        } => {
+            collect_baremetal_expr_restrictions(target, restrictions);
+            for bound in [start, end, step].into_iter().flatten() {
+                collect_baremetal_expr_restrictions(bound, restrictions);
+            }
+        }
+        ExprKind::New { args, .. } | ExprKind::Tuple(args) => {
             for arg in args {
                 collect_baremetal_expr_restrictions(arg, restrictions);
             }
         }
+        ExprKind::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                collect_baremetal_expr_restrictions(value, restrictions);
+            }
+        }
         ExprKind::Literal(LiteralValue::List(items)) => {
             for item in items {
                 collect_baremetal_expr_restrictions(item, restrictions);
@@ -728,7 +1193,9 @@ fn collect_baremetal_expr_restrictions(expr: &Expr, restrictions: &mut Vec<Barem
 fn baremetal_runtime_call_name(callee: &Expr) -> Option<String> {
     match &callee.kind {
         ExprKind::Variable(name) if baremetal_runtime_builtin(name) => Some(name.clone()),
-        ExprKind::PropertyAccess { target, property } => {
+        ExprKind::PropertyAccess {
+            target, property, ..
+        } => {
             if let ExprKind::Variable(module) = &target.kind {
                 if baremetal_runtime_module(module) {
                     return Some(format!("{module}.{property}"));
@@ -766,6 +1233,7 @@ fn baremetal_runtime_builtin(name: &str) -> bool {
             | "json_parse"
             | "json_stringify"
             | "json_stringify_pretty"
+            | "json_stringify_opts"
             | "json_get"
             | "json_has"
     )
@@ -811,22 +1279,91 @@ fn render_baremetal_restrictions(
 #[cfg(test)]
 mod tests {
     use super::{
-        find_baremetal_restrictions, namespace_imported_module, render_baremetal_restrictions,
-        render_parser_diagnostics, render_semantic_diagnostics, validate_entrypoint,
+        check_entrypoint, check_source, eliminate_dead_functions, find_baremetal_restrictions,
+        imported_module_files, module_key, namespace_imported_module, parse_define,
+        render_baremetal_restrictions, render_parser_diagnostics, render_semantic_diagnostics,
+        resolve_imports, resolve_module_path, validate_entrypoint, BuildOptions, ImportDepth,
+        ImportErrors, STDIN_NAME,
     };
     use crate::ast::{ClassDecl, Location, Stmt, StmtKind};
     use crate::parser::Parser;
-    use crate::semantic_analyzer::{SemanticError, SemanticErrorKind};
+    use crate::semantic_analyzer::{SemanticAnalyzer, SemanticError, SemanticErrorKind};
     use crate::span::{Position, Span};
+    use std::collections::{BTreeMap, HashSet};
+    use std::fs;
+    use std::path::{Path, PathBuf};
 
     fn loc() -> Location {
-        Location { line: 1, column: 1 }
+        Location {
+            line: 1,
+            column: 1,
+            offset: 0,
+        }
     }
 
     fn span() -> Span {
         loc().to_span()
     }
 
+    fn module_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snask-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp module dir");
+        fs::write(
+            dir.join("strings.snask"),
+            "fun shout(text: str) : str\n    return text\n",
+        )
+        .expect("write module");
+        fs::write(
+            dir.join("numbers.snask"),
+            "fun twice(n: int) : int\n    return n * 2\n",
+        )
+        .expect("write module");
+        dir
+    }
+
+    fn resolve_source(dir: &Path, source: &str) -> Result<Vec<Stmt>, String> {
+        resolve_source_with_depth(dir, source, None)
+    }
+
+    fn resolve_source_with_depth(
+        dir: &Path,
+        source: &str,
+        max_depth: Option<usize>,
+    ) -> Result<Vec<Stmt>, String> {
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+        let mut resolved = Vec::new();
+        resolve_imports(
+            &mut program,
+            dir,
+            &BTreeMap::new(),
+            &mut resolved,
+            &mut HashSet::new(),
+            ImportDepth::root(max_depth),
+            &mut ImportErrors::new(false),
+        )?;
+        Ok(resolved)
+    }
+
+    /// Parses `source` and generates its LLVM IR.
+    fn generate_ir(source: &str) -> String {
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let program = parser.parse_program().expect("source should parse");
+
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        generator.generate(program).expect("IR should be generated")
+    }
+
+    /// Returns the body of `main::start` in `ir`.
+    fn start_body(ir: &str) -> &str {
+        ir.split("define void @f_main_NS_start(")
+            .nth(1)
+            .and_then(|body| body.split("\n}\n").next())
+            .expect("main::start should be defined")
+    }
+
     #[test]
     fn validate_entrypoint_rejects_missing_main_class() {
         let program = Vec::new();
@@ -852,6 +1389,23 @@ mod tests {
         assert!(err.contains("at least one method"));
     }
 
+    #[test]
+    fn define_arguments_default_to_true() {
+        assert_eq!(
+            parse_define("platform=linux"),
+            Ok(("platform".to_string(), "linux".to_string()))
+        );
+        assert_eq!(
+            parse_define("debug"),
+            Ok(("debug".to_string(), "true".to_string()))
+        );
+        assert_eq!(
+            parse_define("empty="),
+            Ok(("empty".to_string(), String::new()))
+        );
+        assert!(parse_define("=1").is_err());
+    }
+
     #[test]
     fn humane_parser_diagnostic_points_to_missing_paren() {
         let source = "class main\n    fun start()\n        print(\"Hello\"\n";
@@ -863,6 +1417,7 @@ mod tests {
         assert!(rendered.contains("print(\"Hello\""));
         assert!(rendered.contains("^ expected `)` here"));
         assert!(!rendered.contains("ParseError"));
+        assert!(rendered.contains("safe fix (95%): Insert `)`"));
     }
 
     #[test]
@@ -874,17 +1429,48 @@ mod tests {
             SemanticErrorKind::VariableNotFound("mesage".to_string()),
             span,
         )
-        .with_help("Did you mean 'message'?".to_string());
+        .with_help("Did you mean `message`?".to_string());
 
         let rendered = render_semantic_diagnostics("name.snask", source, &[error]);
 
         assert!(rendered.contains("error[S2002]: variable `mesage` was not found"));
         assert!(rendered.contains("print(mesage)"));
         assert!(rendered.contains("^^^^^^ unknown name"));
-        assert!(rendered.contains("help: Did you mean 'message'?"));
+        assert!(rendered.contains("help: Did you mean `message`?"));
         assert!(!rendered.contains("SemanticError"));
     }
 
+    #[test]
+    fn semantic_diagnostics_are_sorted_by_position_and_deduplicated() {
+        let source = "class main\n    fun start()\n        print(aaa)\n        print(bbb)\n        print(ccc)\n";
+        let at = |line: usize| Span::new(Position::new(line, 15, 0), Position::new(line, 18, 0));
+        let missing = |name: &str, line: usize| {
+            SemanticError::new(
+                SemanticErrorKind::VariableNotFound(name.to_string()),
+                at(line),
+            )
+        };
+        let errors = vec![
+            missing("ccc", 5),
+            missing("aaa", 3),
+            missing("ccc", 5),
+            missing("bbb", 4),
+        ];
+
+        let rendered = render_semantic_diagnostics("order.snask", source, &errors);
+
+        let positions: Vec<usize> = ["`aaa`", "`bbb`", "`ccc`"]
+            .iter()
+            .map(|name| rendered.find(name).expect("every error is rendered"))
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]), "{rendered}");
+        assert_eq!(
+            rendered.matches("`ccc` was not found").count(),
+            1,
+            "{rendered}"
+        );
+    }
+
     #[test]
     fn humane_semantic_type_mismatch_uses_public_type_names() {
         let source = "class main\n    fun start()\n        let age: int = \"18\"\n";
@@ -933,44 +1519,634 @@ mod tests {
     }
 
     #[test]
-    fn baremetal_diagnostic_explains_std_runtime_requirement() {
-        let source = "class main\n    fun start()\n        print(\"Hello\")\n";
-        let mut parser = Parser::new(source).expect("source should tokenize");
-        let program = parser.parse_program().expect("source should parse");
-        let restrictions = find_baremetal_restrictions(&program);
-        let rendered = render_baremetal_restrictions("kernel.snask", source, &restrictions);
+    fn import_alias_is_used_as_namespace_prefix() {
+        let dir = module_dir("import-alias");
+        let source = "import \"strings\" as m\nfrom / import numbers as n\n\nclass main\n    fun start()\n        print(m::shout(\"hi\"), n::twice(2))\n";
+        let program = resolve_source(&dir, source).expect("aliased imports should resolve");
+        let _ = fs::remove_dir_all(&dir);
 
-        assert!(rendered.contains("error[S8001]: print requires std runtime"));
-        assert!(rendered.contains("print(\"Hello\")"));
-        assert!(rendered.contains("std output is not available in baremetal"));
-        assert!(rendered.contains("help: use a serial/VGA driver or build with `--profile humane`"));
+        let names: Vec<&str> = program
+            .iter()
+            .filter_map(|stmt| match &stmt.kind {
+                StmtKind::FuncDeclaration(func) => Some(func.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(names.contains(&"m::shout"));
+        assert!(names.contains(&"n::twice"));
+        assert!(!names.contains(&"strings::shout"));
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.errors.is_empty(),
+            "unexpected semantic errors: {:?}",
+            analyzer.errors
+        );
     }
-}
 
-pub fn link_binary(
-    file_path: &str,
-    ir: Vec<u8>,
-    options: BuildOptions,
-    link_tiny_runtime: bool,
-    pb: &ProgressBar,
-    extra_pkgs: Vec<String>,
-) -> Result<(), String> {
-    let ir_file = "temp_snask.ll";
-    fs::write(ir_file, ir).map_err(|e| e.to_string())?;
+    #[test]
+    fn unused_imported_function_is_omitted_from_ir() {
+        let dir = module_dir("dead-functions");
+        let source = "import \"strings\"\nimport \"numbers\"\n\nclass main\n    fun start()\n        print(numbers::twice(2))\n";
+        let mut program = resolve_source(&dir, source).expect("imports should resolve");
+        let _ = fs::remove_dir_all(&dir);
 
-    let size_link = options.release_size || options.tiny || options.extreme;
-    let clang_opt = if let Some(o) = options.opt_override.as_deref() {
-        format!("-{}", o)
-    } else if options.extreme || options.tiny {
-        "-Oz".to_string()
-    } else if options.release_size {
-        "-Os".to_string()
-    } else {
-        format!("-O{}", options.opt_level)
-    };
+        let removed = eliminate_dead_functions(&mut program);
+        assert_eq!(removed, vec!["strings::shout".to_string()]);
 
-    let mut extra_libs = Vec::new();
-    for pkg in extra_pkgs {
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        let ir = generator.generate(program).expect("IR should be generated");
+        assert!(ir.contains("f_numbers_NS_twice"), "{ir}");
+        assert!(!ir.contains("f_strings_NS_shout"), "{ir}");
+    }
+
+    #[test]
+    fn construction_initializes_fields_and_calls_init() {
+        let source = "class Point\n    let x: int = 0\n    fun init(x: int)\n        self.x = x\n\
+class main\n    fun start()\n        let p: Point = Point(4)\n        print(p.x)\n";
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        assert!(start.contains("call void @s_alloc_obj("), "{start}");
+        assert!(start.contains("call void @json_set("), "{start}");
+        assert!(start.contains("call void @f_Point_NS_init("), "{start}");
+    }
+
+    #[test]
+    fn string_plus_chain_becomes_one_concat_call() {
+        let source = r#"class main
+    fun start()
+        let a = "a"
+        let b = "b"
+        let c = 3
+        let d = "d"
+        print(a + b + c + d)
+        print(a + b)
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let chains = start.matches("call void @s_concat_n(").count();
+        assert_eq!(chains, 1, "{start}");
+        assert!(start.contains("i32 4)"), "{start}");
+        // A single `+` keeps the two-operand call.
+        let pairs = start.matches("call void @s_concat(").count();
+        assert_eq!(pairs, 1, "{start}");
+    }
+
+    #[test]
+    fn slices_call_the_runtime_with_nil_for_omitted_bounds() {
+        let source = r#"class main
+    fun start()
+        let xs = [1, 2, 3, 4]
+        print(xs[1:3])
+        print("hello"[::-1])
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let slices = start.matches("call void @snask_slice(").count();
+        assert_eq!(slices, 2, "{start}");
+        assert!(!start.contains("@snask_index_get"), "{start}");
+    }
+
+    #[test]
+    fn user_functions_shadow_builtins_with_the_same_name() {
+        let source = r#"fun gcd(a, b)
+    return "mine"
+
+fun sum(xs: list) : str
+    return "also mine"
+
+class main
+    fun start()
+        print(gcd(4, 6))
+        print(sum([1, 2]))
+        print(lcm(4, 6))
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        assert!(start.contains("call void @f_gcd("), "{start}");
+        assert!(start.contains("call void @f_sum("), "{start}");
+        assert!(!start.contains("call void @s_gcd("), "{start}");
+        assert!(!start.contains("call void @s_sum("), "{start}");
+        // Builtins nobody redefines still resolve to the runtime.
+        assert!(start.contains("call void @s_lcm("), "{start}");
+    }
+
+    #[test]
+    fn in_calls_the_runtime_membership_check() {
+        let source = r#"class main
+    fun start()
+        let xs = [1, 2]
+        let ages = {"ana": 30}
+        print(2 in xs, "ana" in ages, "na" in "snask")
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let checks = start.matches("call void @snask_in(").count();
+        assert_eq!(checks, 3, "{start}");
+    }
+
+    #[test]
+    fn modulo_and_division_check_for_a_zero_divisor() {
+        let source = r#"class main
+    fun start()
+        let a = 10
+        let b = 3
+        let x = 7.5
+        print(a % b, x % 2.0)
+        print(x / 0.0)
+        try:
+            print(a / b)
+        catch err:
+            print(err)
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        assert!(start.contains(" = srem i64 "), "{start}");
+        assert!(start.contains(" = frem double "), "{start}");
+        assert!(start.contains(" = fdiv double "), "{start}");
+        // Outside a `try` a zero divisor panics; inside it is catchable. Float
+        // `/` is left to IEEE inf/nan.
+        let panics = start
+            .matches("call void @snask_division_by_zero(i32 0)")
+            .count();
+        assert_eq!(panics, 2, "{start}");
+        let catchable = start
+            .matches("call void @snask_division_by_zero(i32 1)")
+            .count();
+        assert_eq!(catchable, 1, "{start}");
+    }
+
+    #[test]
+    fn bitwise_ops_on_any_values_use_integer_instructions() {
+        let source = r#"class main
+    fun start()
+        let xs = [12]
+        print(xs[0] & 4, xs[0] << 1, ~xs[0])
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        assert!(start.contains(" = and i64 "), "{start}");
+        assert!(start.contains(" = shl i64 "), "{start}");
+        assert!(start.contains(" = xor i64 "), "{start}");
+    }
+
+    #[test]
+    fn and_or_only_evaluate_the_right_side_when_needed() {
+        let source = "fun loud() : bool\n    print(\"ran\")\n    return true\n\n\
+class main\n    fun start()\n        let ok = false\n        print(ok and loud())\n";
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let branch = start
+            .find("label %sc_rhs, label %sc_end")
+            .expect("`and` should branch on its left side");
+        let rhs = start.find("\nsc_rhs:").expect("right side block");
+        let call = start.find("@f_loud(").expect("loud() should be called");
+        assert!(branch < rhs && rhs < call, "{start}");
+        assert!(start.contains("phi i1 [ false, %"), "{start}");
+    }
+
+    #[test]
+    fn optional_chaining_skips_the_lookup_and_the_call_on_nil() {
+        let source = "class Point\n    let x: int = 1\n    fun twice() : int\n        return self.x * 2\n\n\
+class main\n    fun start()\n        let p: Point = Point()\n        print(p?.x)\n        print(p?.twice())\n";
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let guards: Vec<usize> = start
+            .match_indices("br i1 %is_nil")
+            .map(|(at, _)| at)
+            .collect();
+        assert_eq!(guards.len(), 2, "{start}");
+        let get = start.find("@json_get(").expect("p?.x should look up x");
+        let call = start
+            .find("@f_Point_NS_twice(")
+            .expect("p?.twice() should call the method");
+        assert!(
+            guards[0] < get && get < guards[1] && guards[1] < call,
+            "{start}"
+        );
+    }
+
+    #[test]
+    fn index_assignment_is_bounds_checked_and_catchable() {
+        let source = r#"class main
+    fun start()
+        let xs = [1, 2]
+        try:
+            xs[5] = 3
+        catch err:
+            print(err)
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let set = start
+            .find("call void @snask_index_set(")
+            .expect("index assignment should call snask_index_set");
+        assert!(!start.contains("@json_set("), "{start}");
+        assert!(start[set..].contains(", i32 1)"), "{start}");
+        assert!(
+            start[set..].contains("call i32 @snask_error_pending()"),
+            "{start}"
+        );
+    }
+
+    #[test]
+    fn index_assignment_outside_try_panics_at_its_line() {
+        let source = r#"class main
+    fun start()
+        let xs = [1, 2]
+        xs[5] = 3
+"#;
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        let set = start
+            .find("call void @snask_index_set(")
+            .expect("index assignment should call snask_index_set");
+        let location = start
+            .rfind("call void @snask_set_location(")
+            .expect("index assignment should record its location");
+        assert!(location < set, "{start}");
+        assert!(start[set..].contains(", i32 0)"), "{start}");
+        assert!(ir.contains("c\"main.snask:4:"), "{ir}");
+    }
+
+    #[test]
+    fn loop_else_runs_on_normal_exit_only() {
+        let source = "class main
+    fun start()
+        mut i = 0
+        while i < 3
+            i = i + 1
+        else
+            print(\"while done\")
+        for x in [1, 2]
+            print(x)
+        else
+            print(\"for done\")
+        while i < 5
+            i = i + 1
+";
+        let ir = generate_ir(source);
+        let start = start_body(&ir);
+        // A failed condition leads into `else`, which then falls through to
+        // the end block; a loop without `else` exits straight to its end.
+        assert!(
+            start.contains("label %while_body, label %while_else"),
+            "{start}"
+        );
+        assert!(
+            start.contains("label %for_body, label %for_else"),
+            "{start}"
+        );
+        assert_eq!(start.matches("\nwhile_else").count(), 1, "{start}");
+        let while_else = start
+            .split("\nwhile_else:")
+            .nth(1)
+            .and_then(|block| block.split("\n\n").next())
+            .expect("while_else block should exist");
+        assert!(while_else.contains("br label %while_end"), "{while_else}");
+    }
+
+    #[test]
+    fn index_access_records_its_source_line_for_runtime_panics() {
+        let source = r#"class main
+    fun start()
+        let xs = [1, 2]
+        print(xs[0])
+        print(xs[5])
+"#;
+        let ir = generate_ir(source);
+        assert!(ir.contains("c\"main.snask:5:17\\00\""), "{ir}");
+        let start = start_body(&ir);
+        let calls: Vec<&str> = start
+            .lines()
+            .filter_map(|line| {
+                ["snask_set_location", "snask_index_get"]
+                    .into_iter()
+                    .find(|name| line.contains(&format!("call void @{}(", name)))
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                "snask_set_location",
+                "snask_index_get",
+                "snask_set_location",
+                "snask_index_get",
+            ]
+        );
+    }
+
+    #[test]
+    fn import_chains_deeper_than_the_limit_name_the_deepest_module() {
+        let dir = module_dir("import-depth");
+        for i in 0..6 {
+            let import = if i < 5 {
+                format!("import \"chain{}\"\n", i + 1)
+            } else {
+                String::new()
+            };
+            let body = format!("{import}fun f{i}() : int\n    return {i}\n");
+            fs::write(dir.join(format!("chain{}.snask", i)), body).expect("write module");
+        }
+        let shallow = resolve_source_with_depth(&dir, "import \"chain0\"\n", Some(3));
+        let deep = resolve_source_with_depth(&dir, "import \"chain0\"\n", Some(6));
+        let _ = fs::remove_dir_all(&dir);
+
+        let err = shallow.expect_err("a chain of six modules exceeds a limit of three");
+        assert!(err.contains("Import depth limit of 3"), "{err}");
+        assert!(err.contains("module 'chain3'"), "{err}");
+        deep.expect("the whole chain fits in a limit of six");
+    }
+
+    #[test]
+    fn keep_going_reports_every_module_that_fails() {
+        let dir = module_dir("keep-going");
+        fs::write(dir.join("broken_a.snask"), "fun a(\n    return 1\n").expect("write module");
+        fs::write(dir.join("broken_b.snask"), "let = 2\n").expect("write module");
+        let source = "import \"broken_a\"\nimport \"strings\"\nimport \"broken_b\"\n";
+        let resolve = |keep_going: bool| {
+            let mut parser = Parser::new(source).expect("source should tokenize");
+            let mut program = parser.parse_program().expect("source should parse");
+            let mut resolved = Vec::new();
+            let mut errors = ImportErrors::new(keep_going);
+            resolve_imports(
+                &mut program,
+                &dir,
+                &BTreeMap::new(),
+                &mut resolved,
+                &mut HashSet::new(),
+                ImportDepth::root(None),
+                &mut errors,
+            )
+            .and_then(|()| errors.finish())
+            .map(|()| resolved.len())
+            .map_err(|e| (e, resolved.len()))
+        };
+        let first_only = resolve(false);
+        let all = resolve(true);
+        let _ = fs::remove_dir_all(&dir);
+
+        let (err, _) = first_only.expect_err("a broken module fails the build");
+        assert!(err.contains("broken_a.snask"), "{err}");
+        assert!(!err.contains("broken_b.snask"), "{err}");
+
+        let (err, resolved) = all.expect_err("keep going still fails the build");
+        assert!(err.contains("broken_a.snask"), "{err}");
+        assert!(err.contains("broken_b.snask"), "{err}");
+        assert!(err.contains("2 modules failed to load"), "{err}");
+        assert_eq!(resolved, 1, "the module between them is still resolved");
+    }
+
+    #[test]
+    fn modules_parsed_in_parallel_resolve_in_import_order() {
+        let dir = module_dir("parallel-parse");
+        let mut source = String::new();
+        let mut expected = vec!["numbers::twice".to_string()];
+        for i in 0..16 {
+            let import = if i % 2 == 0 {
+                "import \"numbers\"\n"
+            } else {
+                ""
+            };
+            fs::write(
+                dir.join(format!("m{i}.snask")),
+                format!("{import}fun f{i}() : int\n    return {i}\n"),
+            )
+            .expect("write module");
+            source.push_str(&format!("import \"m{i}\"\n"));
+            expected.push(format!("m{i}::f{i}"));
+        }
+        source.push_str("from / import strings\n");
+        expected.push("strings::shout".to_string());
+
+        let first = resolve_source(&dir, &source).expect("modules should resolve");
+        let repeated: Vec<_> = (0..8)
+            .map(|_| resolve_source(&dir, &source).expect("modules should resolve"))
+            .collect();
+        let _ = fs::remove_dir_all(&dir);
+
+        let names: Vec<String> = first
+            .iter()
+            .filter_map(|stmt| match &stmt.kind {
+                StmtKind::FuncDeclaration(func) => Some(func.name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, expected);
+        for program in repeated {
+            assert_eq!(
+                program, first,
+                "resolution must not depend on thread timing"
+            );
+        }
+    }
+
+    #[test]
+    fn already_resolved_modules_are_not_parsed_again() {
+        let dir = module_dir("resolved-not-reparsed");
+        let source = "import \"numbers\"\nimport \"strings\"\nimport \"numbers\" as n\n";
+        let program = Parser::new(source)
+            .expect("source should tokenize")
+            .parse_program()
+            .expect("source should parse");
+        let deps = BTreeMap::new();
+        let numbers = resolve_module_path(&dir, "numbers", &deps).expect("numbers resolves");
+        let strings = resolve_module_path(&dir, "strings", &deps).expect("strings resolves");
+
+        let mut resolved = HashSet::new();
+        resolved.insert(module_key(&numbers, None));
+        let files = imported_module_files(&program, &dir, &deps, &resolved);
+        assert_eq!(
+            files,
+            vec![
+                ("strings".to_string(), strings.clone()),
+                ("numbers".to_string(), numbers.clone()),
+            ]
+        );
+
+        resolved.insert(module_key(&numbers, Some("n")));
+        let files = imported_module_files(&program, &dir, &deps, &resolved);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(files, vec![("strings".to_string(), strings)]);
+    }
+
+    #[test]
+    fn import_alias_collision_is_an_error() {
+        let dir = module_dir("import-alias-collision");
+        let source = "import \"strings\" as m\nimport \"numbers\" as m\n";
+        let err = resolve_source(&dir, source).expect_err("duplicate alias must fail");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(err.contains("Import alias 'm'"));
+        assert!(err.contains("strings"));
+        assert!(err.contains("numbers"));
+    }
+
+    #[test]
+    fn mainless_file_needs_no_main_flag() {
+        let source = "fun helper(n: int) : int\n    return n + 1\n";
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let program = parser.parse_program().expect("source should parse");
+
+        let err = check_entrypoint(&program, &BuildOptions::default())
+            .expect_err("an application needs class main");
+        assert!(err.contains("class main"), "{err}");
+        assert!(err.contains("--no-main"), "{err}");
+
+        let options = BuildOptions {
+            no_main: true,
+            ..Default::default()
+        };
+        check_entrypoint(&program, &options).expect("--no-main skips the check");
+
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "lib.snask");
+        generator.set_module_mode(true);
+        let ir = generator.generate(program).expect("module IR should be generated");
+        assert!(ir.contains("@snask_module_init"), "{ir}");
+        assert!(!ir.contains("define i32 @main"), "{ir}");
+        assert!(ir.contains("f_helper"), "{ir}");
+    }
+
+    #[test]
+    fn check_reports_stdin_source_as_stdin() {
+        let cwd = Path::new(".");
+        let source = "class main\n    fun start()\n        print(missing_name)\n";
+        let err = check_source(STDIN_NAME, source, cwd, false, None)
+            .expect_err("undefined name must fail");
+        assert!(err.contains("<stdin>:3"), "{err}");
+
+        let err = check_source(STDIN_NAME, "class main\n    fun start(\n", cwd, false, None)
+            .expect_err("parse error must fail");
+        assert!(err.contains("<stdin>"), "{err}");
+
+        let ok = "class main\n    fun start()\n        print(\"hi\")\n";
+        check_source(STDIN_NAME, ok, cwd, false, None).expect("valid program should check");
+    }
+
+    #[test]
+    fn type_aliases_resolve_across_modules() {
+        let dir = module_dir("type-alias");
+        let ids = "fun next(id: UserId) : UserId\n    return id + 1\ntype UserId = int\n";
+        fs::write(dir.join("ids.snask"), ids).expect("write module");
+        let source = "import \"ids\"\nclass main\n    fun start()\n        \
+let id: ids::UserId = ids::next(1)\n        let bad: ids::UserId = \"x\"\n";
+        let file = dir.join("main.snask");
+        let file = file.to_str().expect("utf-8 path");
+        let result = check_source(file, source, &dir, false, None);
+        let _ = fs::remove_dir_all(&dir);
+
+        let err = result.expect_err("a string is not an ids::UserId");
+        assert!(err.contains("expected `int`, found `str`"), "{err}");
+        assert!(!err.contains("is not defined"), "{err}");
+    }
+
+    #[test]
+    fn check_honours_the_import_depth_limit() {
+        let dir = module_dir("check-depth");
+        let outer = "import \"inner\"\nfun o() : int\n    return 1\n";
+        fs::write(dir.join("outer.snask"), outer).expect("write module");
+        fs::write(dir.join("inner.snask"), "fun i() : int\n    return 2\n").expect("write module");
+        let source = "import \"outer\"\nclass main\n    fun start()\n        print(1)\n";
+        let file = dir.join("main.snask");
+        let file = file.to_str().expect("utf-8 path");
+        let limited = check_source(file, source, &dir, false, Some(1));
+        let unlimited = check_source(file, source, &dir, false, None);
+        let _ = fs::remove_dir_all(&dir);
+
+        let err = limited.expect_err("two nested imports exceed a limit of one");
+        assert!(err.contains("Import depth limit of 1"), "{err}");
+        unlimited.expect("the default limit allows two nested imports");
+    }
+
+    #[test]
+    fn caret_lands_on_the_right_glyph_after_accents_and_emoji() {
+        let cwd = Path::new(".");
+        let source = "class main\n    fun start()\n        let s = \"é😀\" + missing\n";
+        let err = check_source(STDIN_NAME, source, cwd, false, None).expect_err("unknown name");
+
+        // `😀` is two UTF-16 units, so `missing` starts at column 25.
+        assert!(err.contains("<stdin>:3:25"), "{err}");
+        let caret_line = err
+            .lines()
+            .find(|l| l.contains('^'))
+            .expect("a caret line")
+            .split_once("| ")
+            .expect("a gutter")
+            .1;
+        let prefix = "        let s = \"é😀\" + ";
+        assert_eq!(caret_line.find('^'), Some(prefix.chars().count()), "{err}");
+        assert!(caret_line.trim_start().starts_with("^^^^^^^ "), "{err}");
+    }
+
+    #[test]
+    fn deny_warnings_turns_a_warning_only_program_into_a_failure() {
+        let cwd = Path::new(".");
+        let source = "fun f() : int\n    return 1\n    print(\"never\")\n\
+class main\n    fun start()\n        print(f())\n";
+        check_source(STDIN_NAME, source, cwd, false, None).expect("warnings alone do not fail");
+
+        let err =
+            check_source(STDIN_NAME, source, cwd, true, None).expect_err("--deny-warnings fails");
+        assert!(err.contains("warning[S2007]: unreachable code"), "{err}");
+        assert!(!err.contains("error[S2007]"), "{err}");
+        assert!(err.contains("aborting because of 1 warning(s)"), "{err}");
+    }
+
+    #[test]
+    fn baremetal_diagnostic_explains_std_runtime_requirement() {
+        let source = "class main\n    fun start()\n        print(\"Hello\")\n";
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let program = parser.parse_program().expect("source should parse");
+        let restrictions = find_baremetal_restrictions(&program);
+        let rendered = render_baremetal_restrictions("kernel.snask", source, &restrictions);
+
+        assert!(rendered.contains("error[S8001]: print requires std runtime"));
+        assert!(rendered.contains("print(\"Hello\")"));
+        assert!(rendered.contains("std output is not available in baremetal"));
+        assert!(rendered.contains("help: use a serial/VGA driver or build with `--profile humane`"));
+    }
+}
+
+pub fn link_binary(
+    file_path: &str,
+    ir: Vec<u8>,
+    options: BuildOptions,
+    link_tiny_runtime: bool,
+    pb: &ProgressBar,
+    extra_pkgs: Vec<String>,
+) -> Result<(), String> {
+    let ir_file = "temp_snask.ll";
+    fs::write(ir_file, ir).map_err(|e| e.to_string())?;
+
+    let target = options.target.as_deref();
+    let wasm = is_wasm_target(target);
+    if wasm && !extra_pkgs.is_empty() {
+        return Err(format!(
+            "native packages ({}) cannot be linked into a `{}` binary.",
+            extra_pkgs.join(", "),
+            target.unwrap_or_default()
+        ));
+    }
+    if options.no_main {
+        return emit_module_object(file_path, ir_file, &options, pb);
+    }
+
+    let size_link = options.release_size || options.tiny || options.extreme;
+    let clang_opt = if let Some(o) = options.opt_override.as_deref() {
+        format!("-{}", o)
+    } else if options.extreme || options.tiny {
+        "-Oz".to_string()
+    } else if options.release_size {
+        "-Os".to_string()
+    } else {
+        format!("-O{}", options.opt_level)
+    };
+
+    let mut extra_libs = Vec::new();
+    for pkg in extra_pkgs {
         if let Ok(libs) = get_pkg_libs(&pkg) {
             extra_libs.extend(libs);
         }
@@ -980,7 +2156,9 @@ pub fn link_binary(
     let clang_path = toolchain::clang();
     let llc_path = toolchain::llc();
 
+    // clang drives wasm-ld itself for wasm triples.
     let have_lld = size_link
+        && !wasm
         && lld
             .as_ref()
             .map(|path| {
@@ -999,9 +2177,26 @@ pub fn link_binary(
         None
     };
 
-    let final_output = options
-        .output_name
-        .unwrap_or_else(|| file_path.replace(".snask", ""));
+    let final_output = options.output_name.clone().unwrap_or_else(|| {
+        let base = file_path.replace(".snask", "");
+        if wasm {
+            format!("{}.wasm", base)
+        } else {
+            base
+        }
+    });
+
+    if options.verbosity >= Verbosity::Verbose {
+        pb.suspend(|| {
+            eprintln!(
+                "snask: clang {}, llc {}{}",
+                toolchain::tool_display(&clang_path),
+                toolchain::tool_display(&llc_path),
+                if have_lld { " (ld.lld)" } else { "" }
+            );
+            eprintln!("snask: output {}", final_output);
+        });
+    }
 
     if options.lto {
         pb.set_message(format!(
@@ -1054,30 +2249,16 @@ pub fn link_binary(
             args.push(runtime_path);
         }
 
-        let status = clang
+        clang
             .args(&args)
             .arg("-o")
             .arg(&final_output)
             .args(&extra_libs)
-            .args(if options.extreme {
-                vec![]
-            } else if options.tiny {
-                vec!["-lc".to_string(), "-lgcc".to_string()]
-            } else {
-                vec!["-ldl".to_string()]
-            })
-            .args(if link_tiny_runtime {
-                vec![]
-            } else {
-                vec!["-lm".to_string()]
-            })
-            .args(get_link_flags(size_link, have_lld))
-            .args(get_runtime_linkargs_for(
-                options.target.as_deref(),
-                link_tiny_runtime,
-            ))
-            .status()
-            .map_err(|e| e.to_string())?;
+            .args(system_lib_args(&options, link_tiny_runtime))
+            .args(get_link_flags(size_link, have_lld, target))
+            .args(get_runtime_linkargs_for(target, link_tiny_runtime));
+        trace_command(options.verbosity, pb, &clang);
+        let status = clang.status().map_err(|e| e.to_string())?;
 
         if !status.success() {
             return Err("Final link step failed (LTO path).".to_string());
@@ -1092,18 +2273,9 @@ pub fn link_binary(
             toolchain::tool_display(&llc_path),
             options.opt_level
         ));
-        let mut llc = Command::new(&llc_path);
-        llc.arg(format!("-O{}", options.opt_level))
-            .arg("-relocation-model=pic")
-            .arg("-filetype=obj");
-        if let Some(t) = &options.target {
-            llc.arg(format!("-mtriple={}", t));
-        }
-        llc.arg(ir_file)
-            .arg("-o")
-            .arg(obj_file)
-            .status()
-            .map_err(|e| e.to_string())?;
+        let mut llc = llc_command(&llc_path, options.opt_level, target, ir_file, obj_file);
+        trace_command(options.verbosity, pb, &llc);
+        llc.status().map_err(|e| e.to_string())?;
 
         pb.set_message(format!(
             "Linking ({})",
@@ -1166,30 +2338,16 @@ pub fn link_binary(
             args.push(runtime_path);
         }
 
-        let status = clang
+        clang
             .args(&args)
             .arg("-o")
             .arg(&final_output)
             .args(&extra_libs)
-            .args(if options.extreme {
-                vec![]
-            } else if options.tiny {
-                vec!["-lc".to_string(), "-lgcc".to_string()]
-            } else {
-                vec!["-ldl".to_string()]
-            })
-            .args(if link_tiny_runtime {
-                vec![]
-            } else {
-                vec!["-lm".to_string()]
-            })
-            .args(get_link_flags(size_link, have_lld))
-            .args(get_runtime_linkargs_for(
-                options.target.as_deref(),
-                link_tiny_runtime,
-            ))
-            .status()
-            .map_err(|e| e.to_string())?;
+            .args(system_lib_args(&options, link_tiny_runtime))
+            .args(get_link_flags(size_link, have_lld, target))
+            .args(get_runtime_linkargs_for(target, link_tiny_runtime));
+        trace_command(options.verbosity, pb, &clang);
+        let status = clang.status().map_err(|e| e.to_string())?;
 
         if !status.success() {
             return Err("Final link step failed.".to_string());
@@ -1200,14 +2358,168 @@ pub fn link_binary(
         }
     }
 
-    if options.strip && options.target.is_none() {
-        strip_binary(&final_output);
+    if options.strip && !options.debug && !strip_binary(&final_output, target) {
+        eprintln!(
+            "warning: `--strip` skipped for {}: no strip tool for this target (install llvm-strip)",
+            final_output
+        );
     }
 
     Ok(())
 }
 
-fn get_link_flags(size_link: bool, have_lld: bool) -> Vec<String> {
+/// `--no-main`: stops after `llc` and leaves `<name>.o`, whose
+/// `snask_module_init` runs the module's top-level statements.
+fn emit_module_object(
+    file_path: &str,
+    ir_file: &str,
+    options: &BuildOptions,
+    pb: &ProgressBar,
+) -> Result<(), String> {
+    let obj_file = options
+        .output_name
+        .clone()
+        .unwrap_or_else(|| format!("{}.o", file_path.replace(".snask", "")));
+    let llc_path = toolchain::llc();
+    pb.set_message(format!(
+        "Compiling module ({} -O{})",
+        toolchain::tool_display(&llc_path),
+        options.opt_level
+    ));
+    let target = options.target.as_deref();
+    let mut llc = llc_command(&llc_path, options.opt_level, target, ir_file, &obj_file);
+    trace_command(options.verbosity, pb, &llc);
+    let status = llc.status().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("Compiling the module object failed.".to_string());
+    }
+    if std::env::var("SNASK_KEEP_TEMPS").ok().as_deref() != Some("1") {
+        fs::remove_file(ir_file).ok();
+    }
+    if options.verbosity >= Verbosity::Verbose {
+        pb.suspend(|| eprintln!("snask: output {}", obj_file));
+    }
+    Ok(())
+}
+
+/// `wasm32-*`/`wasm64-*` triples: no dynamic loader, no host pkg-config
+/// libraries and a `.wasm` output.
+pub fn is_wasm_target(target: Option<&str>) -> bool {
+    target.is_some_and(|t| t.starts_with("wasm32") || t.starts_with("wasm64"))
+}
+
+fn llc_command(
+    llc_path: &Path,
+    opt_level: u8,
+    target: Option<&str>,
+    ir_file: &str,
+    obj_file: &str,
+) -> Command {
+    let mut llc = Command::new(llc_path);
+    llc.args(llc_args(opt_level, target))
+        .arg(ir_file)
+        .arg("-o")
+        .arg(obj_file);
+    llc
+}
+
+/// Runs `llc` on `ir` and returns the assembly text instead of an object
+/// (`snask disasm`).
+pub fn emit_assembly(ir: &str, opt_level: u8, target: Option<&str>) -> Result<String, String> {
+    let base = std::env::temp_dir().join(format!("snask_disasm_{}", std::process::id()));
+    let ir_file = base.with_extension("ll");
+    let asm_file = base.with_extension("s");
+    fs::write(&ir_file, ir).map_err(|e| format!("disasm: cannot write the IR: {}", e))?;
+    let args = llc_args(opt_level, target).into_iter().map(|arg| {
+        if arg == "-filetype=obj" {
+            "-filetype=asm".to_string()
+        } else {
+            arg
+        }
+    });
+    let llc_path = toolchain::llc();
+    let output = Command::new(&llc_path)
+        .args(args)
+        .arg(&ir_file)
+        .arg("-o")
+        .arg(&asm_file)
+        .output();
+    let asm = fs::read_to_string(&asm_file);
+    let _ = fs::remove_file(&ir_file);
+    let _ = fs::remove_file(&asm_file);
+    let output = output.map_err(|e| format!("disasm: cannot run {}: {}", llc_path.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "disasm: llc failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    asm.map_err(|e| format!("disasm: cannot read the assembly: {}", e))
+}
+
+/// The line `-vv` prints for a toolchain invocation, if any.
+fn trace_line(verbosity: Verbosity, cmd: &Command) -> Option<String> {
+    if verbosity < Verbosity::Trace {
+        return None;
+    }
+    let mut line = cmd.get_program().to_string_lossy().to_string();
+    for arg in cmd.get_args() {
+        let arg = arg.to_string_lossy();
+        if arg.is_empty() || arg.contains(char::is_whitespace) {
+            line.push_str(&format!(" '{}'", arg));
+        } else {
+            line.push(' ');
+            line.push_str(&arg);
+        }
+    }
+    Some(line)
+}
+
+fn trace_command(verbosity: Verbosity, pb: &ProgressBar, cmd: &Command) {
+    if let Some(line) = trace_line(verbosity, cmd) {
+        pb.suspend(|| eprintln!("$ {}", line));
+    }
+}
+
+fn llc_args(opt_level: u8, target: Option<&str>) -> Vec<String> {
+    let mut args = vec![format!("-O{}", opt_level)];
+    if !is_wasm_target(target) {
+        args.push("-relocation-model=pic".to_string());
+    }
+    args.push("-filetype=obj".to_string());
+    if let Some(t) = target {
+        args.push(format!("-mtriple={}", t));
+    }
+    args
+}
+
+fn system_lib_args(options: &BuildOptions, link_tiny_runtime: bool) -> Vec<String> {
+    let wasm = is_wasm_target(options.target.as_deref());
+    let mut args = Vec::new();
+    if !options.extreme {
+        if options.tiny {
+            args.push("-lc".to_string());
+            if !wasm {
+                args.push("-lgcc".to_string());
+            }
+        } else if !wasm {
+            args.push("-ldl".to_string());
+        }
+    }
+    if !link_tiny_runtime {
+        args.push("-lm".to_string());
+    }
+    args
+}
+
+fn get_link_flags(size_link: bool, have_lld: bool, target: Option<&str>) -> Vec<String> {
+    if is_wasm_target(target) {
+        return if size_link {
+            vec!["-Wl,--gc-sections".to_string(), "-Wl,-O1".to_string()]
+        } else {
+            Vec::new()
+        };
+    }
     if size_link {
         let mut v = vec![
             "-Wl,--gc-sections".to_string(),
@@ -1239,6 +2551,12 @@ pub fn get_runtime_linkargs_for(target: Option<&str>, tiny: bool) -> Vec<String>
             format!("{}/.snask/lib/runtime.linkargs", home)
         }
     };
+    if is_wasm_target(target) {
+        // Host pkg-config libraries never apply to wasm; only trust the setup output.
+        return std::fs::read_to_string(&p)
+            .map(|s| s.split_whitespace().map(|x| x.to_string()).collect())
+            .unwrap_or_default();
+    }
     if let Ok(s) = std::fs::read_to_string(&p) {
         let mut args: Vec<String> = s.split_whitespace().map(|x| x.to_string()).collect();
         for arg in fallback_runtime_linkargs() {
@@ -1273,67 +2591,101 @@ fn fallback_runtime_linkargs() -> Vec<String> {
     args
 }
 
-fn strip_binary(path: &str) {
+/// Removes the static symbol table and debug info. The dynamic symbol table
+/// is kept, so `f_*` handlers exported with `-rdynamic` still resolve through
+/// `dlsym`. Cross targets need `llvm-strip`; the host `strip` only knows the
+/// host object format. Returns whether the binary was stripped.
+fn strip_binary(path: &str, target: Option<&str>) -> bool {
     let strip_tool = if let Some(path) = toolchain::llvm_strip() {
         Some(path)
-    } else if Command::new("strip").arg("--version").output().is_ok() {
+    } else if target.is_none() && Command::new("strip").arg("--version").output().is_ok() {
         Some("strip".into())
     } else {
         None
     };
-    if let Some(strip_tool) = strip_tool {
-        let _ = Command::new(strip_tool).arg(path).status();
-    }
+    let Some(strip_tool) = strip_tool else {
+        return false;
+    };
+    Command::new(strip_tool)
+        .arg(path)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Programs whose GUI callbacks or blaze routes are looked up by name with
+/// `dlsym` at runtime.
+pub fn uses_name_dispatch(program: &[Stmt]) -> bool {
+    program.iter().any(|st| match &st.kind {
+        StmtKind::Import { path: m, .. } | StmtKind::FromImport { module: m, .. } => {
+            matches!(m.as_str(), "gui" | "blaze" | "blaze_auth")
+        }
+        _ => false,
+    })
 }
 
 pub fn resolve_imports(
     program: &mut Program,
     entry_dir: &Path,
+    path_deps: &BTreeMap<String, PathBuf>,
     resolved_program: &mut Program,
     resolved_modules: &mut HashSet<String>,
+    depth: ImportDepth,
+    errors: &mut ImportErrors,
 ) -> Result<(), String> {
+    // Reading and parsing is independent per file, so this file's imports are
+    // parsed up front in parallel. Namespacing and splicing below stay in
+    // import order, which keeps the resolved program the same on every run.
+    let mut parsed = parse_modules(&imported_module_files(
+        program,
+        entry_dir,
+        path_deps,
+        resolved_modules,
+    ));
+    // alias -> module it was bound to, per importing file.
+    let mut aliases: HashMap<String, String> = HashMap::new();
     for stmt in program.drain(..) {
         match stmt.kind {
-            StmtKind::Import(ref module_name) => {
+            StmtKind::Import {
+                path: ref module_name,
+                ref alias,
+            } => {
+                if let Some(alias) = alias {
+                    register_import_alias(&mut aliases, alias, module_name)?;
+                }
                 if is_native_module(module_name) {
+                    if let Some(alias) = alias {
+                        return Err(format!(
+                            "Native module '{}' cannot be imported as '{}'; use '{}::' directly.",
+                            module_name, alias, module_name
+                        ));
+                    }
                     resolved_program.push(stmt);
                     continue;
                 }
-                match resolve_module_path(entry_dir, module_name) {
+                match resolve_module_path(entry_dir, module_name, path_deps) {
                     Ok(module_path) => {
-                        if !resolved_modules.contains(&module_path) {
-                            resolved_modules.insert(module_path.clone());
-                            let source = fs::read_to_string(&module_path).map_err(|e| {
-                                format!("Failed to read module {}: {}", module_name, e)
-                            })?;
-                            let mut parser = Parser::new(&source)
-                                .map_err(|e| render_parser_diagnostic(&module_path, &source, &e))?;
-                            let (module_program, errors) = parser.parse_program_recovering(10);
-                            if !errors.is_empty() {
-                                return Err(render_parser_diagnostics(
-                                    &module_path,
-                                    &source,
-                                    &errors,
-                                ));
-                            }
-                            if let Some(mut prog) = module_program {
-                                let prefix = Path::new(module_name)
-                                    .file_stem()
-                                    .and_then(|stem| stem.to_str())
-                                    .unwrap_or_else(|| {
-                                        module_name.split('/').last().unwrap_or(module_name)
-                                    });
-                                if prefix != "prelude" {
-                                    namespace_imported_module(&mut prog, prefix);
-                                }
-                                resolve_imports(
-                                    &mut prog,
-                                    Path::new(&module_path).parent().unwrap(),
-                                    resolved_program,
-                                    resolved_modules,
-                                )?;
-                            }
-                        }
+                        let prefix = alias.clone().unwrap_or_else(|| {
+                            Path::new(module_name)
+                                .file_stem()
+                                .and_then(|stem| stem.to_str())
+                                .unwrap_or_else(|| {
+                                    module_name.split('/').last().unwrap_or(module_name)
+                                })
+                                .to_string()
+                        });
+                        let imported = import_snask_module(
+                            module_name,
+                            &module_path,
+                            &prefix,
+                            alias.is_some(),
+                            path_deps,
+                            &mut parsed,
+                            resolved_program,
+                            resolved_modules,
+                            depth,
+                            errors,
+                        );
+                        errors.record(imported)?;
                     }
                     Err(e) => {
                         // If not found as .snask, check if it's a C package
@@ -1342,10 +2694,40 @@ pub fn resolve_imports(
                             resolved_program.push(stmt);
                             continue;
                         }
-                        return Err(e);
+                        errors.record(Err(e))?;
                     }
                 }
             }
+            StmtKind::FromImport {
+                ref from,
+                is_current_dir,
+                ref module,
+                ref alias,
+            } => {
+                if let Some(alias) = alias {
+                    register_import_alias(&mut aliases, alias, module)?;
+                }
+                let module_path = from_import_path(entry_dir, from, is_current_dir, module);
+                if !module_path.exists() {
+                    // Native/stdlib modules keep the file-level semantics they had before.
+                    resolved_program.push(stmt);
+                    continue;
+                }
+                let prefix = alias.clone().unwrap_or_else(|| module.clone());
+                let imported = import_snask_module(
+                    module,
+                    &module_path.to_string_lossy(),
+                    &prefix,
+                    alias.is_some(),
+                    path_deps,
+                    &mut parsed,
+                    resolved_program,
+                    resolved_modules,
+                    depth,
+                    errors,
+                );
+                errors.record(imported)?;
+            }
             StmtKind::ImportCOm { .. } => resolved_program.push(stmt),
             _ => resolved_program.push(stmt),
         }
@@ -1353,6 +2735,184 @@ pub fn resolve_imports(
     Ok(())
 }
 
+/// `from a/b import m` looks for `m.snask` under `a/b`, relative to the
+/// importing file; `from / import m` next to it.
+fn from_import_path(
+    entry_dir: &Path,
+    from: &[String],
+    is_current_dir: bool,
+    module: &str,
+) -> PathBuf {
+    let dir = if is_current_dir {
+        entry_dir.to_path_buf()
+    } else {
+        from.iter()
+            .fold(entry_dir.to_path_buf(), |acc, s| acc.join(s))
+    };
+    dir.join(format!("{}.snask", module))
+}
+
+/// A module file after reading and parsing, before namespacing.
+type ParsedModule = Result<Option<Program>, String>;
+
+/// The `.snask` files `program` imports directly, as `(module name, path)`
+/// in import order and without repeats. Imports already in
+/// `resolved_modules` are skipped, since `import_snask_module` would drop
+/// them anyway.
+fn imported_module_files(
+    program: &Program,
+    entry_dir: &Path,
+    path_deps: &BTreeMap<String, PathBuf>,
+    resolved_modules: &HashSet<String>,
+) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for stmt in program {
+        let file = match &stmt.kind {
+            StmtKind::Import {
+                path: module_name,
+                alias,
+            } if !is_native_module(module_name) => {
+                resolve_module_path(entry_dir, module_name, path_deps)
+                    .ok()
+                    .map(|path| (module_name.clone(), path, alias))
+            }
+            StmtKind::FromImport {
+                from,
+                is_current_dir,
+                module,
+                alias,
+            } => {
+                let path = from_import_path(entry_dir, from, *is_current_dir, module);
+                path.exists()
+                    .then(|| (module.clone(), path.to_string_lossy().to_string(), alias))
+            }
+            _ => None,
+        };
+        let file = file
+            .filter(|(_, path, alias)| {
+                !resolved_modules.contains(&module_key(path, alias.as_deref()))
+            })
+            .map(|(name, path, _)| (name, path));
+        if let Some(file) = file {
+            if !files.iter().any(|(_, path)| *path == file.1) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+/// Parses `files` on up to `available_parallelism` threads. Results are
+/// keyed by path, so the order the threads finish in does not matter.
+fn parse_modules(files: &[(String, String)]) -> HashMap<String, ParsedModule> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(files.len());
+    if workers <= 1 {
+        return files
+            .iter()
+            .map(|(name, path)| (path.clone(), parse_module_file(name, path)))
+            .collect();
+    }
+    let next = AtomicUsize::new(0);
+    let parsed = Mutex::new(HashMap::new());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some((name, path)) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let module = parse_module_file(name, path);
+                    parsed
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(path.clone(), module);
+                }
+            });
+        }
+    });
+    parsed.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+fn parse_module_file(module_name: &str, module_path: &str) -> ParsedModule {
+    let source = fs::read_to_string(module_path)
+        .map_err(|e| format!("Failed to read module {}: {}", module_name, e))?;
+    let mut parser =
+        Parser::new(&source).map_err(|e| render_parser_diagnostic(module_path, &source, &e))?;
+    let (module_program, parse_errors) = parser.parse_program_recovering(10);
+    if !parse_errors.is_empty() {
+        return Err(render_parser_diagnostics(
+            module_path,
+            &source,
+            &parse_errors,
+        ));
+    }
+    Ok(module_program)
+}
+
+fn register_import_alias(
+    aliases: &mut HashMap<String, String>,
+    alias: &str,
+    module_name: &str,
+) -> Result<(), String> {
+    if let Some(previous) = aliases.get(alias) {
+        if previous != module_name {
+            return Err(format!(
+                "Import alias '{}' is used for both '{}' and '{}'.",
+                alias, previous, module_name
+            ));
+        }
+        return Ok(());
+    }
+    aliases.insert(alias.to_string(), module_name.to_string());
+    Ok(())
+}
+
+/// How `resolved_modules` names an import. An aliased import defines its
+/// symbols under a different prefix, so it must not be deduplicated against a
+/// plain import of the same file.
+fn module_key(module_path: &str, alias: Option<&str>) -> String {
+    match alias {
+        Some(alias) => format!("{}#{}", module_path, alias),
+        None => module_path.to_string(),
+    }
+}
+
+fn import_snask_module(
+    module_name: &str,
+    module_path: &str,
+    prefix: &str,
+    aliased: bool,
+    path_deps: &BTreeMap<String, PathBuf>,
+    parsed: &mut HashMap<String, ParsedModule>,
+    resolved_program: &mut Program,
+    resolved_modules: &mut HashSet<String>,
+    depth: ImportDepth,
+    errors: &mut ImportErrors,
+) -> Result<(), String> {
+    if !resolved_modules.insert(module_key(module_path, aliased.then_some(prefix))) {
+        return Ok(());
+    }
+    let depth = depth.enter(module_name, module_path)?;
+    // A file imported twice under different aliases is parsed again.
+    let module_program = parsed
+        .remove(module_path)
+        .unwrap_or_else(|| parse_module_file(module_name, module_path))?;
+    if let Some(mut prog) = module_program {
+        if prefix != "prelude" {
+            namespace_imported_module(&mut prog, prefix);
+        }
+        resolve_imports(
+            &mut prog,
+            Path::new(module_path).parent().unwrap(),
+            path_deps,
+            resolved_program,
+            resolved_modules,
+            depth,
+            errors,
+        )?;
+    }
+    Ok(())
+}
+
 fn namespace_imported_module(program: &mut Program, prefix: &str) {
     let local_symbols: HashSet<String> = program
         .iter()
@@ -1362,6 +2922,9 @@ fn namespace_imported_module(program: &mut Program, prefix: &str) {
             StmtKind::VarDeclaration(v) => Some(v.name.clone()),
             StmtKind::MutDeclaration(v) => Some(v.name.clone()),
             StmtKind::ConstDeclaration(v) => Some(v.name.clone()),
+            StmtKind::EnumDeclaration { name, .. } | StmtKind::TypeAlias { name, .. } => {
+                Some(name.clone())
+            }
             _ => None,
         })
         .collect();
@@ -1374,6 +2937,9 @@ fn namespace_imported_module(program: &mut Program, prefix: &str) {
             StmtKind::VarDeclaration(v) => v.name = namespaced(prefix, &v.name),
             StmtKind::MutDeclaration(v) => v.name = namespaced(prefix, &v.name),
             StmtKind::ConstDeclaration(v) => v.name = namespaced(prefix, &v.name),
+            StmtKind::EnumDeclaration { name, .. } | StmtKind::TypeAlias { name, .. } => {
+                *name = namespaced(prefix, name)
+            }
             _ => {}
         }
     }
@@ -1401,6 +2967,11 @@ fn rewrite_type_for_namespace(ty: &mut Type, prefix: &str, local_symbols: &HashS
             }
             rewrite_type_for_namespace(ret, prefix, local_symbols);
         }
+        Type::Tuple(items) => {
+            for item in items {
+                rewrite_type_for_namespace(item, prefix, local_symbols);
+            }
+        }
         _ => {}
     }
 }
@@ -1473,6 +3044,9 @@ fn collect_stmt_local_names(stmts: &[Stmt], names: &mut HashSet<String>) {
             StmtKind::DictDeclaration(v) => {
                 names.insert(v.name.clone());
             }
+            StmtKind::TupleDeclaration { names: declared, .. } => {
+                names.extend(declared.iter().cloned());
+            }
             StmtKind::Conditional(c) => {
                 collect_stmt_local_names(&c.if_block.body, names);
                 for block in &c.elif_blocks {
@@ -1482,16 +3056,40 @@ fn collect_stmt_local_names(stmts: &[Stmt], names: &mut HashSet<String>) {
                     collect_stmt_local_names(body, names);
                 }
             }
-            StmtKind::Loop(LoopStmt::While { body, .. }) => collect_stmt_local_names(body, names),
-            StmtKind::Loop(LoopStmt::For { iterator, body, .. }) => {
+            StmtKind::Loop(LoopStmt::While {
+                body, else_block, ..
+            }) => {
+                collect_stmt_local_names(body, names);
+                if let Some(body) = else_block {
+                    collect_stmt_local_names(body, names);
+                }
+            }
+            StmtKind::Loop(LoopStmt::For {
+                iterator,
+                body,
+                else_block,
+                ..
+            }) => {
                 names.insert(iterator.clone());
                 collect_stmt_local_names(body, names);
+                if let Some(body) = else_block {
+                    collect_stmt_local_names(body, names);
+                }
             }
             StmtKind::UnsafeBlock(body)
             | StmtKind::Scope { body, .. }
             | StmtKind::Zone { body, .. } => {
                 collect_stmt_local_names(body, names);
             }
+            StmtKind::TryCatch {
+                body,
+                err_name,
+                handler,
+            } => {
+                names.insert(err_name.clone());
+                collect_stmt_local_names(body, names);
+                collect_stmt_local_names(handler, names);
+            }
             _ => {}
         }
     }
@@ -1558,6 +3156,17 @@ fn rewrite_stmt_for_namespace(stmt: &mut Stmt, prefix: &str, local_symbols: &Has
             }
             rewrite_expr_for_namespace(&mut v.value, prefix, local_symbols)
         }
+        StmtKind::TupleDeclaration { value, .. } => {
+            rewrite_expr_for_namespace(value, prefix, local_symbols)
+        }
+        StmtKind::TupleAssignment { names, value } => {
+            for name in names.iter_mut() {
+                if local_symbols.contains(name) {
+                    *name = namespaced(prefix, name);
+                }
+            }
+            rewrite_expr_for_namespace(value, prefix, local_symbols)
+        }
         StmtKind::PropertyAssignment(a) => {
             rewrite_expr_for_namespace(&mut a.target, prefix, local_symbols);
             rewrite_expr_for_namespace(&mut a.value, prefix, local_symbols);
@@ -1567,24 +3176,39 @@ fn rewrite_stmt_for_namespace(stmt: &mut Stmt, prefix: &str, local_symbols: &Has
             rewrite_expr_for_namespace(&mut a.index, prefix, local_symbols);
             rewrite_expr_for_namespace(&mut a.value, prefix, local_symbols);
         }
-        StmtKind::Print(exprs) => {
-            for e in exprs {
+        StmtKind::Print(print) => {
+            for e in print.exprs_mut() {
                 rewrite_expr_for_namespace(e, prefix, local_symbols);
             }
         }
-        StmtKind::Input { var_type, .. } => {
+        StmtKind::Input { var_type, .. } | StmtKind::TypeAlias { target: var_type, .. } => {
             rewrite_type_for_namespace(var_type, prefix, local_symbols);
         }
         StmtKind::FuncDeclaration(f) => rewrite_func_decl_for_namespace(f, prefix, local_symbols),
         StmtKind::ClassDeclaration(c) => rewrite_class_decl_for_namespace(c, prefix, local_symbols),
         StmtKind::Conditional(c) => rewrite_conditional_for_namespace(c, prefix, local_symbols),
-        StmtKind::Loop(LoopStmt::While { condition, body }) => {
+        StmtKind::Loop(LoopStmt::While {
+            condition,
+            body,
+            else_block,
+        }) => {
             rewrite_expr_for_namespace(condition, prefix, local_symbols);
             rewrite_stmts_for_namespace(body, prefix, local_symbols);
+            if let Some(body) = else_block {
+                rewrite_stmts_for_namespace(body, prefix, local_symbols);
+            }
         }
-        StmtKind::Loop(LoopStmt::For { iterable, body, .. }) => {
+        StmtKind::Loop(LoopStmt::For {
+            iterable,
+            body,
+            else_block,
+            ..
+        }) => {
             rewrite_expr_for_namespace(iterable, prefix, local_symbols);
             rewrite_stmts_for_namespace(body, prefix, local_symbols);
+            if let Some(body) = else_block {
+                rewrite_stmts_for_namespace(body, prefix, local_symbols);
+            }
         }
         StmtKind::ListDeclaration(v) => {
             rewrite_opt_type_for_namespace(&mut v.var_type, prefix, local_symbols);
@@ -1612,6 +3236,10 @@ fn rewrite_stmt_for_namespace(stmt: &mut Stmt, prefix: &str, local_symbols: &Has
         | StmtKind::Zone { body, .. } => {
             rewrite_stmts_for_namespace(body, prefix, local_symbols);
         }
+        StmtKind::TryCatch { body, handler, .. } => {
+            rewrite_stmts_for_namespace(body, prefix, local_symbols);
+            rewrite_stmts_for_namespace(handler, prefix, local_symbols);
+        }
         _ => {}
     }
 }
@@ -1643,6 +3271,14 @@ fn rewrite_expr_for_namespace(expr: &mut Expr, prefix: &str, local_symbols: &Has
         ExprKind::Variable(name) if local_symbols.contains(name) => {
             *name = namespaced(prefix, name)
         }
+        // `Color::Red` where `Color` is an enum declared in this module.
+        ExprKind::Variable(name)
+            if name
+                .split_once("::")
+                .is_some_and(|(owner, _)| local_symbols.contains(owner)) =>
+        {
+            *name = format!("{prefix}::{name}")
+        }
         ExprKind::Unary { expr, .. } => rewrite_expr_for_namespace(expr, prefix, local_symbols),
         ExprKind::Binary { left, right, .. } => {
             rewrite_expr_for_namespace(left, prefix, local_symbols);
@@ -1661,6 +3297,17 @@ fn rewrite_expr_for_namespace(expr: &mut Expr, prefix: &str, local_symbols: &Has
             rewrite_expr_for_namespace(target, prefix, local_symbols);
             rewrite_expr_for_namespace(index, prefix, local_symbols);
         }
+        ExprKind::Slice {
+            target,
+            start,
+            end,
+            step,
+        } => {
+            rewrite_expr_for_namespace(target, prefix, local_symbols);
+            for bound in [start, end, step].into_iter().flatten() {
+                rewrite_expr_for_namespace(bound, prefix, local_symbols);
+            }
+        }
         ExprKind::New { class, args, .. } => {
             if local_symbols.contains(class) {
                 *class = namespaced(prefix, class);
@@ -1669,11 +3316,28 @@ fn rewrite_expr_for_namespace(expr: &mut Expr, prefix: &str, local_symbols: &Has
                 rewrite_expr_for_namespace(arg, prefix, local_symbols);
             }
         }
+        ExprKind::StructLiteral { class, fields } => {
+            if local_symbols.contains(class) {
+                *class = namespaced(prefix, class);
+            }
+            for (_, value) in fields {
+                rewrite_expr_for_namespace(value, prefix, local_symbols);
+            }
+        }
+        ExprKind::Tuple(items) => {
+            for item in items {
+                rewrite_expr_for_namespace(item, prefix, local_symbols);
+            }
+        }
         _ => {}
     }
 }
 
-fn resolve_module_path(entry_dir: &Path, module_name: &str) -> Result<String, String> {
+fn resolve_module_path(
+    entry_dir: &Path,
+    module_name: &str,
+    path_deps: &BTreeMap<String, PathBuf>,
+) -> Result<String, String> {
     let name_with_ext = if module_name.ends_with(".snask") {
         module_name.to_string()
     } else {
@@ -1692,7 +3356,12 @@ fn resolve_module_path(entry_dir: &Path, module_name: &str) -> Result<String, St
         return Ok(raw.to_string_lossy().to_string());
     }
 
-    // 2. Compiler stdlib path (src/stdlib/)
+    // 2. Workspace path dependencies (package name -> entry file)
+    if let Some(entry) = path_deps.get(module_name) {
+        return Ok(entry.to_string_lossy().to_string());
+    }
+
+    // 3. Compiler stdlib path (src/stdlib/)
     let stdlib = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("src/stdlib")
         .join(&name_with_ext);
@@ -1700,7 +3369,7 @@ fn resolve_module_path(entry_dir: &Path, module_name: &str) -> Result<String, St
         return Ok(stdlib.to_string_lossy().to_string());
     }
 
-    // 3. Stdlib / Packages (MVP: check both direct and nested structure)
+    // 4. Stdlib / Packages (MVP: check both direct and nested structure)
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
     let pkg_direct = Path::new(&home)
         .join(".snask/packages")
@@ -1735,9 +3404,11 @@ pub fn render_parser_diagnostics(filename: &str, source: &str, errs: &[ParseErro
         for note in &err.notes {
             diagnostic = diagnostic.with_note(note.clone());
         }
-        if let Some(help) = &err.help {
-            diagnostic = diagnostic.with_help(help.clone());
-        }
+        diagnostic = match (parser_fixit(err), &err.help) {
+            (Some(fix), help) => with_fixit_help(diagnostic, &fix, help.as_deref()),
+            (None, Some(help)) => diagnostic.with_help(help.clone()),
+            (None, None) => diagnostic,
+        };
         bag.add(diagnostic);
     }
     let mut rendered = bag.render_all(filename, source);
@@ -1755,10 +3426,16 @@ pub fn render_semantic_diagnostics(
     source: &str,
     errors: &[SemanticError],
 ) -> String {
+    let errors = sorted_semantic_errors(errors);
     let mut bag = DiagnosticBag::new();
     let shown = errors.len().min(5);
     for error in errors.iter().take(shown) {
-        let mut diagnostic = Diagnostic::error(error.message())
+        let diagnostic = if error.is_warning() {
+            Diagnostic::warning(error.message())
+        } else {
+            Diagnostic::error(error.message())
+        };
+        let mut diagnostic = diagnostic
             .with_code(humane_code(error.code()).to_string())
             .with_annotation(Annotation::primary(
                 error.span,
@@ -1782,6 +3459,46 @@ pub fn render_semantic_diagnostics(
     rendered
 }
 
+/// Errors top-to-bottom by their start position, whatever order the analyzer
+/// found them in. The same error reported twice at one span is shown once.
+fn sorted_semantic_errors(errors: &[SemanticError]) -> Vec<&SemanticError> {
+    let mut sorted: Vec<&SemanticError> = errors.iter().collect();
+    sorted.sort_by_key(|e| (e.span.start.line, e.span.start.column));
+    let mut unique: Vec<&SemanticError> = Vec::with_capacity(sorted.len());
+    for error in sorted {
+        let seen = unique.iter().any(|u| {
+            u.span == error.span && u.code() == error.code() && u.message() == error.message()
+        });
+        if !seen {
+            unique.push(error);
+        }
+    }
+    unique
+}
+
+/// The token a parse error is missing, as a fix that inserts it where the
+/// error points. Shared with the language server so both offer the same fixes
+/// at the same confidence.
+pub fn parser_fixit(err: &ParseError) -> Option<FixIt> {
+    let (text, confidence) = match err.code {
+        "SNASK-PARSE-SEMICOLON" => (";", 90),
+        "SNASK-PARSE-MISSING-RPAREN" => (")", 95),
+        "SNASK-PARSE-MISSING-RBRACKET" => ("]", 95),
+        // Where a block should close is a guess; only suggest it.
+        "SNASK-PARSE-MISSING-RBRACE" => ("}", 75),
+        _ => return None,
+    };
+    Some(FixIt {
+        title: format!("Insert `{text}`"),
+        confidence,
+        kind: FixItKind::QuickFix,
+        apply: Some(FixItApply::Insert {
+            at: err.span,
+            text: text.to_string(),
+        }),
+    })
+}
+
 fn parser_message(err: &ParseError) -> String {
     match err.code {
         "SNASK-PARSE-MISSING-RPAREN" => "missing closing `)`".to_string(),
@@ -1820,19 +3537,31 @@ fn semantic_annotation(error: &SemanticError) -> &'static str {
         ImmutableAssignment(_) => "cannot assign to immutable binding",
         ReturnOutsideFunction => "`return` is only valid inside a function",
         WrongNumberOfArguments { .. } => "wrong number of arguments",
+        TupleArityMismatch { .. } => "names and tuple elements differ in count",
+        UnknownEnumVariant { .. } => "unknown enum variant",
+        CfgKeyNotLiteral => "expected a key written as a string literal",
+        MissingStructFields { .. } => "every property needs a value here",
         IndexAccessOnNonIndexable(_) => "cannot index this value",
         InvalidIndexType(_) => "invalid index type",
         PropertyNotFound(_) => "unknown property",
         NotCallable(_) => "this value is not callable",
         RestrictedNativeFunction { .. } => "reserved native function",
         TinyDisallowedLib(_) => "not available in tiny mode",
+        UnreachableCode => "this statement never runs",
         _ => "error",
     }
 }
 
 #[cfg(test)]
 mod build_profile_tests {
-    use super::BuildProfile;
+    use super::{
+        build_source, get_link_flags, is_wasm_target, llc_args, llc_command, system_lib_args,
+        trace_line, BuildOptions, BuildProfile, Verbosity,
+    };
+    use crate::toolchain;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
 
     #[test]
     fn parses_language_profiles() {
@@ -1858,4 +3587,161 @@ mod build_profile_tests {
         }
         assert_eq!(BuildProfile::parse("unknown"), None);
     }
+
+    #[test]
+    fn wasm_target_changes_llc_and_link_flags() {
+        let wasm = Some("wasm32-wasi");
+        assert!(is_wasm_target(wasm));
+        assert!(!is_wasm_target(None));
+        assert!(!is_wasm_target(Some("x86_64-unknown-linux-gnu")));
+
+        let native = llc_args(2, None);
+        assert!(native.contains(&"-relocation-model=pic".to_string()));
+        let llc = llc_args(2, wasm);
+        assert!(!llc.contains(&"-relocation-model=pic".to_string()));
+        assert!(llc.contains(&"-mtriple=wasm32-wasi".to_string()));
+
+        assert!(get_link_flags(false, false, None).contains(&"-rdynamic".to_string()));
+        assert!(get_link_flags(false, false, wasm).is_empty());
+
+        let native_opts = BuildOptions::default();
+        assert!(system_lib_args(&native_opts, false).contains(&"-ldl".to_string()));
+        let wasm_opts = BuildOptions {
+            target: wasm.map(str::to_string),
+            ..Default::default()
+        };
+        let libs = system_lib_args(&wasm_opts, false);
+        assert!(!libs.contains(&"-ldl".to_string()));
+        assert!(libs.contains(&"-lm".to_string()));
+    }
+
+    #[test]
+    fn strip_makes_the_binary_smaller_and_it_still_runs() {
+        if !crate::jit::shared_runtime_path().is_file() {
+            eprintln!("skipping: no shared runtime (run `snask setup`)");
+            return;
+        }
+        let source = "class main\n    fun start()\n        print(\"hi\")\n";
+        let bin = std::env::temp_dir().join(format!("snask-strip-{}", std::process::id()));
+        let build = |strip: bool| {
+            let options = BuildOptions {
+                output_name: Some(bin.to_string_lossy().to_string()),
+                verbosity: Verbosity::Quiet,
+                strip,
+                ..Default::default()
+            };
+            build_source("main.snask", source, Path::new("."), options).expect("build");
+            fs::metadata(&bin).expect("binary should exist").len()
+        };
+        let before = build(false);
+        let after = build(true);
+        let output = Command::new(&bin).output().expect("stripped binary should run");
+        let _ = fs::remove_file(&bin);
+
+        if after == before {
+            eprintln!("skipping: no strip tool available");
+            return;
+        }
+        assert!(after < before, "{after} should be smaller than {before}");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hi\n");
+    }
+
+    #[test]
+    fn debug_build_object_has_a_line_table() {
+        let source = "fun twice(n: int) : int\n    return n * 2\n\n\
+class main\n    fun start()\n        print(twice(2))\n";
+        let program = crate::parser::parse_program(source).expect("source should parse");
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        generator.set_debug_info(true);
+        let ir = generator.generate(program).expect("IR should be generated");
+        assert!(ir.contains("!DISubprogram(name: \"main::start\""), "{ir}");
+        assert!(ir.contains("!DILocation(line: 6, column: 9"), "{ir}");
+
+        let llc = toolchain::llc();
+        let version = Command::new(&llc).arg("--version").output();
+        if !version.is_ok_and(|o| o.status.success()) {
+            eprintln!("skipping: no llc available");
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("snask-debug-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let ir_file = dir.join("main.ll").to_string_lossy().to_string();
+        let obj_file = dir.join("main.o").to_string_lossy().to_string();
+        fs::write(&ir_file, &ir).unwrap();
+        let compiled = llc_command(&llc, 0, None, &ir_file, &obj_file).status();
+        let object = fs::read(&obj_file).unwrap_or_default();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(
+            compiled.is_ok_and(|s| s.success()),
+            "llc should accept the IR"
+        );
+        let section: &[u8] = b".debug_line";
+        assert!(
+            object.windows(section.len()).any(|w| w == section),
+            "the object should have a .debug_line section"
+        );
+    }
+
+    #[test]
+    fn only_programs_that_read_args_link_against_s_set_args() {
+        let llc = toolchain::llc();
+        let version = Command::new(&llc).arg("--version").output();
+        if !version.is_ok_and(|o| o.status.success()) {
+            eprintln!("skipping: no llc available");
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("snask-set-args-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // The extreme and nano runtimes don't define `s_set_args`, so an
+        // object that never calls `args()` must not reference it.
+        let object_for = |name: &str, source: &str| {
+            let program = crate::parser::parse_program(source).expect("source should parse");
+            let context = inkwell::context::Context::create();
+            let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+            let ir = generator.generate(program).expect("IR should be generated");
+            let ir_file = dir.join(format!("{name}.ll")).to_string_lossy().to_string();
+            let obj_file = dir.join(format!("{name}.o")).to_string_lossy().to_string();
+            fs::write(&ir_file, &ir).unwrap();
+            let compiled = llc_command(&llc, 0, None, &ir_file, &obj_file).status();
+            assert!(
+                compiled.is_ok_and(|s| s.success()),
+                "llc should accept {name}"
+            );
+            fs::read(&obj_file).unwrap_or_default()
+        };
+        let hello = object_for(
+            "hello",
+            "class main\n    fun start()\n        print(\"hi\")\n",
+        );
+        let echo = object_for(
+            "echo",
+            "class main\n    fun start()\n        print(args())\n",
+        );
+        let _ = fs::remove_dir_all(&dir);
+
+        let symbol: &[u8] = b"s_set_args";
+        let references = |object: &[u8]| object.windows(symbol.len()).any(|w| w == symbol);
+        assert!(!references(&hello), "hello should not need s_set_args");
+        assert!(references(&echo), "args() should need s_set_args");
+    }
+
+    #[test]
+    fn trace_verbosity_surfaces_llc_command_line() {
+        let llc = llc_command(Path::new("llc-18"), 2, None, "temp_snask.ll", "temp_snask.o");
+        assert_eq!(
+            trace_line(Verbosity::Trace, &llc).as_deref(),
+            Some("llc-18 -O2 -relocation-model=pic -filetype=obj temp_snask.ll -o temp_snask.o")
+        );
+        assert_eq!(trace_line(Verbosity::Verbose, &llc), None);
+        assert_eq!(trace_line(Verbosity::Normal, &llc), None);
+
+        assert_eq!(Verbosity::from_flags(0, true), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(1, false), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(3, false), Verbosity::Trace);
+    }
 }
@@ -155,16 +155,17 @@ fn render_source_annotation(out: &mut String, source: &str, ann: &Annotation) {
         width = gutter_width
     ));
 
-    let start_col = ann.span.start.column.max(1);
+    // Columns are UTF-16 units; the caret line is padded per character.
+    let start_char = utf16_column_to_char(line, ann.span.start.column);
     let mut caret_len = if ann.span.start.line == ann.span.end.line {
-        ann.span.end.column.saturating_sub(ann.span.start.column)
+        utf16_column_to_char(line, ann.span.end.column).saturating_sub(start_char)
     } else {
         1
     };
     caret_len = caret_len.max(1);
 
     let marker = if ann.is_primary { "^" } else { "-" }.repeat(caret_len);
-    let padding = " ".repeat(start_col.saturating_sub(1));
+    let padding = " ".repeat(start_char);
     if ann.message.is_empty() {
         out.push_str(&format!(
             "{:>width$} | {}{}\n",
@@ -185,6 +186,20 @@ fn render_source_annotation(out: &mut String, source: &str, ann: &Annotation) {
     }
 }
 
+/// Characters of `line` before the 1-based UTF-16 `column`. Columns past the
+/// end of the line keep counting one character per unit.
+fn utf16_column_to_char(line: &str, column: usize) -> usize {
+    let target = column.saturating_sub(1);
+    let mut units = 0;
+    for (chars, ch) in line.chars().enumerate() {
+        if units >= target {
+            return chars;
+        }
+        units += ch.len_utf16();
+    }
+    line.chars().count() + target.saturating_sub(units)
+}
+
 include!(concat!(env!("OUT_DIR"), "/humane_code.rs"));
 
 pub struct DiagnosticReporter {
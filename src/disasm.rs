@@ -0,0 +1,223 @@
+//! `snask disasm`: the assembly `llc` generates for a program, optionally
+//! limited to one function and interleaved with the source lines it came
+//! from. Interleaving builds with debug info, so the `.loc` directives of the
+//! line table say which line each instruction belongs to.
+
+use crate::compiler::{emit_assembly, generate_ir, BuildOptions};
+use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run_disasm(
+    file: &str,
+    function: Option<&str>,
+    source: bool,
+    opt_level: u8,
+) -> Result<(), String> {
+    print!("{}", disassemble(file, function, source, opt_level)?);
+    Ok(())
+}
+
+/// Assembly for `file` at `-O<opt_level>`. `function` is a Snask name
+/// (`twice`, `main::start`) or a symbol (`f_main_NS_start`).
+pub fn disassemble(
+    file: &str,
+    function: Option<&str>,
+    source: bool,
+    opt_level: u8,
+) -> Result<String, String> {
+    if opt_level > 3 {
+        return Err("disasm: --opt-level must be between 0 and 3".to_string());
+    }
+    let text =
+        fs::read_to_string(file).map_err(|e| format!("disasm: cannot read {}: {}", file, e))?;
+    let entry_dir = Path::new(file).parent().unwrap_or(Path::new("."));
+    let options = BuildOptions {
+        opt_level,
+        debug: source,
+        ..Default::default()
+    };
+    let generated = generate_ir(file, &text, entry_dir, &options, &ProgressBar::hidden())?;
+    let asm = emit_assembly(&generated.ir, opt_level, None)?;
+    let files = source_files(&asm);
+    let asm = match function {
+        Some(name) => function_asm(&asm, name)
+            .ok_or_else(|| format!("disasm: no function `{}` in {}", name, file))?,
+        None => asm,
+    };
+    Ok(if source {
+        interleave_source(&asm, &files)
+    } else {
+        asm
+    })
+}
+
+/// The lines of one function, from its label to `.Lfunc_end`. Snask names
+/// are mangled the way the code generator does it.
+fn function_asm(asm: &str, name: &str) -> Option<String> {
+    let symbols = [
+        name.to_string(),
+        format!("f_{}", name.replace("::", "_NS_")),
+    ];
+    symbols.iter().find_map(|symbol| {
+        // Mach-O prefixes symbols with `_`.
+        let labels = [format!("{}:", symbol), format!("_{}:", symbol)];
+        let mut lines = asm.lines().skip_while(|line| {
+            let label = line.split_whitespace().next();
+            !labels.iter().any(|l| label == Some(l.as_str()))
+        });
+        let mut out = format!("{}\n", lines.next()?);
+        for line in lines {
+            out.push_str(line);
+            out.push('\n');
+            if line.starts_with(".Lfunc_end") || line.starts_with("Lfunc_end") {
+                break;
+            }
+        }
+        Some(out)
+    })
+}
+
+/// The line table's file numbers: `.file 1 "dir" "main.snask"`.
+fn source_files(asm: &str) -> HashMap<u32, PathBuf> {
+    asm.lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix(".file")?;
+            let number = rest.split_whitespace().next()?.parse().ok()?;
+            // Quoted strings sit at the odd positions between quotes.
+            let quoted: Vec<&str> = rest.split('"').skip(1).step_by(2).collect();
+            let path = match quoted.as_slice() {
+                [dir, name, ..] => Path::new(dir).join(name),
+                [name] => PathBuf::from(name),
+                [] => return None,
+            };
+            Some((number, path))
+        })
+        .collect()
+}
+
+/// Puts `# file:line  source` before the instructions of every new line.
+fn interleave_source(asm: &str, files: &HashMap<u32, PathBuf>) -> String {
+    let mut sources: HashMap<u32, Vec<String>> = HashMap::new();
+    let mut last = None;
+    let mut out = String::new();
+    for line in asm.lines() {
+        let mut loc = line
+            .trim_start()
+            .strip_prefix(".loc")
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .map(|n| n.parse::<u32>().ok());
+        if let (Some(Some(file)), Some(Some(number))) = (loc.next(), loc.next()) {
+            if number > 0 && last != Some((file, number)) {
+                last = Some((file, number));
+                let text = sources.entry(file).or_insert_with(|| {
+                    files
+                        .get(&file)
+                        .and_then(|path| fs::read_to_string(path).ok())
+                        .map(|text| text.lines().map(str::to_string).collect())
+                        .unwrap_or_default()
+                });
+                let name = files
+                    .get(&file)
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| format!("file {}", file));
+                let code = text.get(number as usize - 1).map_or("", |l| l.trim());
+                out.push_str(&format!("# {}:{}  {}\n", name, number, code));
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disassemble, function_asm, interleave_source, source_files};
+    use crate::toolchain;
+    use std::fs;
+    use std::process::Command;
+
+    const ASM: &str = "\t.text
+\t.globl\tf_twice                         # -- Begin function f_twice
+f_twice:                                # @f_twice
+.Lfunc_begin0:
+\t.file\t1 \"DIR\" \"main.snask\"
+\t.loc\t1 1 0                           # main.snask:1:0
+\tpushq\t%rbx
+\t.loc\t1 2 5 prologue_end              # main.snask:2:5
+\taddq\t%rax, %rax
+.Lfunc_end0:
+\t.size\tf_twice, .Lfunc_end0-f_twice
+f_main_NS_start:                        # @f_main_NS_start
+\t.loc\t1 5 0
+\tretq
+.Lfunc_end1:
+";
+
+    #[test]
+    fn function_filter_and_source_lines_follow_the_line_table() {
+        let dir = std::env::temp_dir().join(format!("snask_disasm_lines_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("main.snask"),
+            "fun twice(n: int) : int\n    return n * 2\n",
+        )
+        .unwrap();
+        let asm = ASM.replace("DIR", &dir.to_string_lossy());
+
+        let files = source_files(&asm);
+        let twice = function_asm(&asm, "twice").expect("twice should be found");
+        let shown = interleave_source(&twice, &files);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(twice.starts_with("f_twice:"), "{twice}");
+        assert!(twice.ends_with(".Lfunc_end0:\n"), "{twice}");
+        assert_eq!(
+            function_asm(&asm, "f_main_NS_start"),
+            function_asm(&asm, "main::start")
+        );
+        assert_eq!(function_asm(&asm, "missing"), None);
+        let comments: Vec<&str> = shown.lines().filter(|l| l.starts_with('#')).collect();
+        assert_eq!(
+            comments,
+            vec![
+                "# main.snask:1  fun twice(n: int) : int",
+                "# main.snask:2  return n * 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn disasm_shows_the_mangled_entry_function() {
+        let version = Command::new(toolchain::llc()).arg("--version").output();
+        if !version.is_ok_and(|o| o.status.success()) {
+            eprintln!("skipping: no llc available");
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("snask_disasm_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("main.snask");
+        fs::write(
+            &file,
+            "class main\n    fun start()\n        print(\"hi\")\n",
+        )
+        .unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let all = disassemble(&file, None, false, 0);
+        let start = disassemble(&file, Some("main::start"), true, 0);
+        let _ = fs::remove_dir_all(&dir);
+
+        let all = all.expect("disasm should succeed");
+        assert!(all.contains("f_main_NS_start:"), "{all}");
+        let start = start.expect("disasm --function should succeed");
+        assert!(start.starts_with("f_main_NS_start:"), "{start}");
+        assert!(start.contains("print(\"hi\")"), "{start}");
+    }
+}
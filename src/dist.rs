@@ -1,6 +1,7 @@
 use crate::compiler::{self, BuildOptions, BuildProfile};
 use crate::sps;
 use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -12,6 +13,8 @@ pub fn run_dist(
     appimage: bool,
     name: Option<String>,
     linux_user: bool,
+    archive: bool,
+    strip: bool,
     out_dir_str: String,
 ) -> Result<(), String> {
     let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
@@ -63,6 +66,8 @@ pub fn run_dist(
         targets.push(None);
     }
 
+    check_target_runtimes(&snask_lib_dir(), &targets)?;
+
     println!("📦 dist: entry = {}", file_path);
     println!("📁 dist: out_dir = {}", out_dir.display());
 
@@ -76,6 +81,8 @@ pub fn run_dist(
         }
         if t.as_deref() == Some("x86_64-pc-windows-gnu") {
             out_path.set_extension("exe");
+        } else if compiler::is_wasm_target(t.as_deref()) {
+            out_path.set_extension("wasm");
         }
 
         // opt level: do SPS se existir, senão O2
@@ -93,7 +100,7 @@ pub fn run_dist(
                 .unwrap_or_else(|| "release-size".to_string());
             let tiny = p == "tiny";
             let release_size = p == "release-size" || (!tiny && p == "release");
-            let do_strip = m.build.strip.unwrap_or(release_size || tiny);
+            let do_strip = strip || m.build.strip.unwrap_or(release_size || tiny);
             let opt_override = m.build.opt.clone().or_else(|| {
                 if tiny {
                     Some("Oz".to_string())
@@ -130,11 +137,33 @@ pub fn run_dist(
             tiny,
             extreme: false,
             strip: do_strip,
+            debug: false,
             opt_override,
             features: features.clone(),
+            defines: manifest
+                .as_ref()
+                .map(|m| m.build.defines.clone())
+                .unwrap_or_default(),
+            path_deps: Default::default(),
+            verbosity: Default::default(),
+            no_assert: false,
+            no_main: false,
+            test_mode: false,
+            test_filter: None,
+            coverage: false,
+            deny_warnings: false,
+            max_import_depth: manifest.as_ref().and_then(|m| m.build.max_import_depth),
+            keep_going: false,
         };
 
         compiler::build_file(&file_path, build_opts)?;
+
+        if archive {
+            if let Some(kind) = t.as_deref().and_then(ArchiveKind::for_target) {
+                let path = make_archive(kind, &out_dir, &cwd, &out_path, &base_name, &triple)?;
+                println!("✅ archive: {}", path.display());
+            }
+        }
     }
 
     // Linux user install (best-effort)
@@ -162,7 +191,7 @@ pub fn run_dist(
             if !bin_path.exists() {
                 return Err(format!("Para gerar .deb, preciso do binário Linux nativo em '{}'. Rode `snask dist --deb` sem targets de cross ou inclua o host.", bin_path.display()));
             }
-            let deb_path = make_deb(&out_dir, &base_name, &bin_path)?;
+            let deb_path = make_deb(&out_dir, manifest.as_ref(), &base_name, &bin_path)?;
             println!("✅ .deb: {}", deb_path.display());
         }
 
@@ -174,7 +203,7 @@ pub fn run_dist(
                     bin_path.display()
                 ));
             }
-            let app = make_appimage(&out_dir, &base_name, &bin_path)?;
+            let app = make_appimage(&out_dir, &cwd, manifest.as_ref(), &base_name, &bin_path)?;
             println!("✅ .AppImage: {}", app.display());
         }
     }
@@ -188,6 +217,217 @@ pub fn run_dist(
     Ok(())
 }
 
+/// Archive format used by `snask dist --archive` for non-Linux targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// `.zip` for Windows, `.tar.gz` for macOS; other targets are not archived.
+    pub fn for_target(triple: &str) -> Option<Self> {
+        if triple.contains("windows") {
+            Some(ArchiveKind::Zip)
+        } else if triple.contains("apple-darwin") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ArchiveKind::Zip => "zip",
+            ArchiveKind::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// Packs `bin_path` plus any README/LICENSE found in `project_dir` into
+/// `<out_dir>/<name>-<triple>.<zip|tar.gz>`, under a `<name>-<triple>/` folder.
+pub fn make_archive(
+    kind: ArchiveKind,
+    out_dir: &Path,
+    project_dir: &Path,
+    bin_path: &Path,
+    name: &str,
+    triple: &str,
+) -> Result<PathBuf, String> {
+    let folder = format!("{name}-{triple}");
+    let mut entries: Vec<(String, PathBuf, u32)> = Vec::new();
+    let bin_name = bin_path.file_name().unwrap_or_default().to_string_lossy();
+    entries.push((format!("{folder}/{bin_name}"), bin_path.to_path_buf(), 0o755));
+    for extra in archive_extras(project_dir) {
+        let file_name = extra.file_name().unwrap_or_default().to_string_lossy();
+        entries.push((format!("{folder}/{file_name}"), extra.to_path_buf(), 0o644));
+    }
+
+    let archive_path = out_dir.join(format!("{folder}.{}", kind.extension()));
+    let err = |e: &dyn std::fmt::Display| {
+        format!("dist: failed to write {}: {e}", archive_path.display())
+    };
+    let file = std::fs::File::create(&archive_path).map_err(|e| err(&e))?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipWriter::new(file);
+            for (entry, path, mode) in &entries {
+                let data = std::fs::read(path).map_err(|e| err(&e))?;
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .unix_permissions(*mode);
+                zip.start_file(entry.as_str(), options).map_err(|e| err(&e))?;
+                zip.write_all(&data).map_err(|e| err(&e))?;
+            }
+            zip.finish().map_err(|e| err(&e))?;
+        }
+        ArchiveKind::TarGz => {
+            let gz = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut tar = tar::Builder::new(gz);
+            for (entry, path, mode) in &entries {
+                let data = std::fs::read(path).map_err(|e| err(&e))?;
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(*mode);
+                header.set_cksum();
+                tar.append_data(&mut header, entry, data.as_slice())
+                    .map_err(|e| err(&e))?;
+            }
+            let gz = tar.into_inner().map_err(|e| err(&e))?;
+            gz.finish().map_err(|e| err(&e))?;
+        }
+    }
+    Ok(archive_path)
+}
+
+/// README and LICENSE/COPYING files at the top of the project, sorted.
+fn archive_extras(project_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(project_dir) else {
+        return Vec::new();
+    };
+    let mut extras: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let upper = p
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_uppercase();
+            ["README", "LICENSE", "LICENCE", "COPYING"]
+                .iter()
+                .any(|prefix| upper.starts_with(prefix))
+        })
+        .collect();
+    extras.sort();
+    extras
+}
+
+/// A target with a prepared runtime under `~/.snask/lib`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledTarget {
+    pub triple: String,
+    /// The host runtime, used when `--targets` is not given.
+    pub is_host: bool,
+}
+
+/// `snask dist --target-list`: prints the targets `snask dist` can build for.
+pub fn run_target_list() -> Result<(), String> {
+    let lib_dir = snask_lib_dir();
+    let targets = installed_targets(&lib_dir);
+    if targets.is_empty() {
+        println!("No runtime found in {}.", lib_dir.display());
+        println!("Run `snask setup` for the host or `snask setup --target <triple>`.");
+        return Ok(());
+    }
+    println!("Installed targets ({}):", lib_dir.display());
+    for t in &targets {
+        if t.is_host {
+            println!("  {} (host, default)", t.triple);
+        } else {
+            println!("  {}", t.triple);
+        }
+    }
+    Ok(())
+}
+
+fn snask_lib_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".snask/lib")
+}
+
+/// The host runtime lives at `lib_dir/runtime.o`, cross targets at
+/// `lib_dir/<triple>/runtime.o`. The host comes first, the rest sorted.
+pub fn installed_targets(lib_dir: &Path) -> Vec<InstalledTarget> {
+    let host = host_triple();
+    let mut cross: Vec<String> = std::fs::read_dir(lib_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().join("runtime.o").is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    cross.sort();
+
+    let mut targets = Vec::new();
+    if lib_dir.join("runtime.o").is_file() || cross.contains(&host) {
+        targets.push(InstalledTarget {
+            triple: host.clone(),
+            is_host: true,
+        });
+    }
+    targets.extend(
+        cross
+            .into_iter()
+            .filter(|t| *t != host)
+            .map(|triple| InstalledTarget {
+                triple,
+                is_host: false,
+            }),
+    );
+    targets
+}
+
+fn host_triple() -> String {
+    let arch = std::env::consts::ARCH;
+    match std::env::consts::OS {
+        "macos" => format!("{arch}-apple-darwin"),
+        "windows" => format!("{arch}-pc-windows-gnu"),
+        os => format!("{arch}-unknown-{os}-gnu"),
+    }
+}
+
+/// Fails before any build starts when a cross target has no runtime, instead
+/// of letting the link step fail on a missing `runtime.o`.
+fn check_target_runtimes(lib_dir: &Path, targets: &[Option<String>]) -> Result<(), String> {
+    let missing: Vec<&str> = targets
+        .iter()
+        .flatten()
+        .filter(|t| !lib_dir.join(t.as_str()).join("runtime.o").is_file())
+        .map(|t| t.as_str())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut msg = String::from("dist: no prepared runtime for ");
+    msg.push_str(
+        &missing
+            .iter()
+            .map(|t| format!("`{t}`"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    msg.push_str(&format!(" in {}\n", lib_dir.display()));
+    for t in &missing {
+        msg.push_str(&format!("help: run `snask setup --target {t}`\n"));
+    }
+    msg.push_str("help: `snask dist --target-list` shows the installed targets\n");
+    Err(msg)
+}
+
 #[cfg(target_os = "linux")]
 fn install_linux_user(
     project_dir: &Path,
@@ -206,16 +446,9 @@ fn install_linux_user(
     std::fs::create_dir_all(&apps).map_err(|e| e.to_string())?;
     std::fs::create_dir_all(&icons).map_err(|e| e.to_string())?;
 
-    let app = manifest.and_then(|m| m.app.clone());
-    let app_id = app.as_ref().map(|a| a.id.as_str()).unwrap_or(base_name);
-    let app_name = app.as_ref().map(|a| a.name.as_str()).unwrap_or(base_name);
-    let comment = app.as_ref().map(|a| a.comment.as_str()).unwrap_or("");
-    let categories = app
-        .as_ref()
-        .map(|a| a.categories.as_str())
-        .unwrap_or("Utility;");
-    let terminal = app.as_ref().map(|a| a.terminal).unwrap_or(false);
-    let icon_field = app.as_ref().map(|a| a.icon.as_str()).unwrap_or("");
+    let app = manifest.and_then(|m| m.app.as_ref());
+    let app_id = app.map(|a| a.id.as_str()).unwrap_or(base_name);
+    let icon_field = app.map(|a| a.icon.as_str()).unwrap_or("");
 
     let dest_bin = local_bin.join(base_name);
     std::fs::copy(bin_path, &dest_bin).map_err(|e| e.to_string())?;
@@ -240,15 +473,7 @@ fn install_linux_user(
     };
 
     let desktop_path = apps.join(format!("{}.desktop", app_id));
-    let desktop = format!(
-        "[Desktop Entry]\nType=Application\nName={}\nComment={}\nExec={}\nIcon={}\nTerminal={}\nCategories={}\n",
-        app_name,
-        comment,
-        base_name,
-        icon_name,
-        if terminal { "true" } else { "false" },
-        categories
-    );
+    let desktop = desktop_entry(app, base_name, &icon_name);
     std::fs::write(&desktop_path, desktop).map_err(|e| e.to_string())?;
 
     if which("update-desktop-database").is_ok() {
@@ -261,33 +486,37 @@ fn install_linux_user(
     Ok(())
 }
 
+/// Renders the `.desktop` entry shared by `--linux-user` and `--appimage`
+/// from the manifest `app` section, falling back to the binary name.
 #[cfg(target_os = "linux")]
-fn make_deb(out_dir: &Path, name: &str, bin_path: &Path) -> Result<PathBuf, String> {
-    // Layout mínimo: package_root/usr/bin/<name> + DEBIAN/control
-    let root = out_dir.join(format!("{}_debroot", name));
-    let _ = std::fs::remove_dir_all(&root);
-    std::fs::create_dir_all(root.join("usr/bin")).map_err(|e| e.to_string())?;
-    std::fs::create_dir_all(root.join("DEBIAN")).map_err(|e| e.to_string())?;
-
-    let dest_bin = root.join("usr/bin").join(name);
-    std::fs::copy(bin_path, &dest_bin).map_err(|e| e.to_string())?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&dest_bin)
-            .map_err(|e| e.to_string())?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&dest_bin, perms).map_err(|e| e.to_string())?;
-    }
+fn desktop_entry(app: Option<&sps::AppSection>, base_name: &str, icon: &str) -> String {
+    let app_name = app.map(|a| a.name.as_str()).unwrap_or(base_name);
+    let comment = app.map(|a| a.comment.as_str()).unwrap_or("");
+    let categories = app.map(|a| a.categories.as_str()).unwrap_or("Utility;");
+    let terminal = app.map(|a| a.terminal).unwrap_or(false);
+    format!(
+        "[Desktop Entry]\nType=Application\nName={}\nComment={}\nExec={}\nIcon={}\nTerminal={}\nCategories={}\n",
+        app_name,
+        comment,
+        base_name,
+        icon,
+        if terminal { "true" } else { "false" },
+        categories
+    )
+}
 
-    let control = format!(
-        "Package: {name}\nVersion: 0.1.0\nSection: utils\nPriority: optional\nArchitecture: amd64\nMaintainer: Snask\nDescription: Snask app packaged by snask dist\n",
-    );
-    std::fs::write(root.join("DEBIAN/control"), control).map_err(|e| e.to_string())?;
+#[cfg(target_os = "linux")]
+fn make_deb(
+    out_dir: &Path,
+    manifest: Option<&sps::SpsManifest>,
+    name: &str,
+    bin_path: &Path,
+) -> Result<PathBuf, String> {
+    let root = prepare_deb_root(out_dir, manifest, name, bin_path)?;
 
     // dpkg-deb
-    let deb_name = format!("{name}_0.1.0_amd64.deb");
+    let version = deb_version(manifest);
+    let deb_name = format!("{name}_{version}_amd64.deb");
     let deb_path = out_dir.join(deb_name);
     let status = Command::new("dpkg-deb")
         .arg("--build")
@@ -305,44 +534,60 @@ fn make_deb(out_dir: &Path, name: &str, bin_path: &Path) -> Result<PathBuf, Stri
 }
 
 #[cfg(target_os = "linux")]
-fn make_appimage(out_dir: &Path, name: &str, bin_path: &Path) -> Result<PathBuf, String> {
-    // Layout mínimo AppDir + appimagetool.
-    let tool = which("appimagetool")?;
-    let appdir = out_dir.join(format!("{}.AppDir", name));
-    let _ = std::fs::remove_dir_all(&appdir);
-    std::fs::create_dir_all(appdir.join("usr/bin")).map_err(|e| e.to_string())?;
+fn deb_version(manifest: Option<&sps::SpsManifest>) -> &str {
+    manifest.map(|m| m.package.version.as_str()).unwrap_or("0.1.0")
+}
 
-    let dest_bin = appdir.join("usr/bin").join(name);
-    std::fs::copy(bin_path, &dest_bin).map_err(|e| e.to_string())?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&dest_bin)
-            .map_err(|e| e.to_string())?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&dest_bin, perms).map_err(|e| e.to_string())?;
-    }
+/// Layout mínimo: package_root/usr/bin/<name> + DEBIAN/control, with the
+/// version, maintainer and description taken from the manifest.
+#[cfg(target_os = "linux")]
+fn prepare_deb_root(
+    out_dir: &Path,
+    manifest: Option<&sps::SpsManifest>,
+    name: &str,
+    bin_path: &Path,
+) -> Result<PathBuf, String> {
+    use std::os::unix::fs::PermissionsExt;
 
-    // AppRun simples: executa o binário
-    let apprun = format!("#!/bin/sh\nHERE=\"$(dirname \"$(readlink -f \"$0\")\")\"\nexec \"$HERE/usr/bin/{name}\" \"$@\"\n");
-    let apprun_path = appdir.join("AppRun");
-    std::fs::write(&apprun_path, apprun).map_err(|e| e.to_string())?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&apprun_path)
-            .map_err(|e| e.to_string())?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&apprun_path, perms).map_err(|e| e.to_string())?;
-    }
+    let root = out_dir.join(format!("{}_debroot", name));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("usr/bin")).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(root.join("DEBIAN")).map_err(|e| e.to_string())?;
+
+    let dest_bin = root.join("usr/bin").join(name);
+    std::fs::copy(bin_path, &dest_bin).map_err(|e| e.to_string())?;
+    let mut perms = std::fs::metadata(&dest_bin)
+        .map_err(|e| e.to_string())?
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&dest_bin, perms).map_err(|e| e.to_string())?;
 
-    // .desktop mínimo (sem ícone)
-    let desktop = format!(
-        "[Desktop Entry]\nType=Application\nName={name}\nExec={name}\nCategories=Utility;\nTerminal=true\n",
+    let maintainer = manifest
+        .and_then(|m| m.package.maintainer.as_deref())
+        .unwrap_or("Snask");
+    let description = match manifest.and_then(|m| m.app.as_ref()) {
+        Some(app) if !app.comment.is_empty() => format!("{} - {}", app.name, app.comment),
+        Some(app) => app.name.clone(),
+        None => "Snask app packaged by snask dist".to_string(),
+    };
+    let control = format!(
+        "Package: {name}\nVersion: {}\nSection: utils\nPriority: optional\nArchitecture: amd64\nMaintainer: {maintainer}\nDescription: {description}\n",
+        deb_version(manifest),
     );
-    std::fs::write(appdir.join(format!("{}.desktop", name)), desktop).map_err(|e| e.to_string())?;
+    std::fs::write(root.join("DEBIAN/control"), control).map_err(|e| e.to_string())?;
+    Ok(root)
+}
+
+#[cfg(target_os = "linux")]
+fn make_appimage(
+    out_dir: &Path,
+    project_dir: &Path,
+    manifest: Option<&sps::SpsManifest>,
+    name: &str,
+    bin_path: &Path,
+) -> Result<PathBuf, String> {
+    let tool = which("appimagetool")?;
+    let appdir = prepare_appdir(out_dir, project_dir, manifest, name, bin_path)?;
 
     let out_path = out_dir.join(format!("{}.AppImage", name));
     let status = Command::new(tool)
@@ -356,6 +601,68 @@ fn make_appimage(out_dir: &Path, name: &str, bin_path: &Path) -> Result<PathBuf,
     Ok(out_path)
 }
 
+/// Lays out `<name>.AppDir` for appimagetool: the binary, `AppRun`, the
+/// desktop entry built from `manifest.app` and, when `app.icon` points to a
+/// file in the project, that icon (also as `.DirIcon`).
+#[cfg(target_os = "linux")]
+fn prepare_appdir(
+    out_dir: &Path,
+    project_dir: &Path,
+    manifest: Option<&sps::SpsManifest>,
+    name: &str,
+    bin_path: &Path,
+) -> Result<PathBuf, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let appdir = out_dir.join(format!("{}.AppDir", name));
+    let _ = std::fs::remove_dir_all(&appdir);
+    std::fs::create_dir_all(appdir.join("usr/bin")).map_err(|e| e.to_string())?;
+
+    let dest_bin = appdir.join("usr/bin").join(name);
+    std::fs::copy(bin_path, &dest_bin).map_err(|e| e.to_string())?;
+    let mut perms = std::fs::metadata(&dest_bin)
+        .map_err(|e| e.to_string())?
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&dest_bin, perms).map_err(|e| e.to_string())?;
+
+    // AppRun simples: executa o binário
+    let apprun = format!("#!/bin/sh\nHERE=\"$(dirname \"$(readlink -f \"$0\")\")\"\nexec \"$HERE/usr/bin/{name}\" \"$@\"\n");
+    let apprun_path = appdir.join("AppRun");
+    std::fs::write(&apprun_path, apprun).map_err(|e| e.to_string())?;
+    let mut perms = std::fs::metadata(&apprun_path)
+        .map_err(|e| e.to_string())?
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&apprun_path, perms).map_err(|e| e.to_string())?;
+
+    let app = manifest.and_then(|m| m.app.as_ref());
+    let app_id = app.map(|a| a.id.as_str()).unwrap_or(name);
+    let icon_field = app.map(|a| a.icon.as_str()).unwrap_or("");
+
+    // appimagetool looks for `<Icon>.png`/`.svg` next to the desktop file.
+    let icon_src = project_dir.join(icon_field);
+    let icon_name = if !icon_field.trim().is_empty() && icon_src.is_file() {
+        let ext = icon_src
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_else(|| "png".to_string());
+        let dest = appdir.join(format!("{}.{}", app_id, ext));
+        std::fs::copy(&icon_src, &dest).map_err(|e| e.to_string())?;
+        std::fs::copy(&icon_src, appdir.join(".DirIcon")).map_err(|e| e.to_string())?;
+        app_id.to_string()
+    } else if !icon_field.trim().is_empty() {
+        icon_field.to_string()
+    } else {
+        app_id.to_string()
+    };
+
+    let desktop = desktop_entry(app, name, &icon_name);
+    std::fs::write(appdir.join(format!("{}.desktop", app_id)), desktop)
+        .map_err(|e| e.to_string())?;
+    Ok(appdir)
+}
+
 fn which(cmd: &str) -> Result<String, String> {
     let out = Command::new("sh")
         .arg("-lc")
@@ -367,3 +674,244 @@ fn which(cmd: &str) -> Result<String, String> {
     }
     Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_target_runtimes, host_triple, installed_targets, make_archive, ArchiveKind,
+        InstalledTarget,
+    };
+    use std::fs;
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    fn fake_lib_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snask-dist-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp lib dir");
+        dir
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn appdir_has_icon_and_desktop_entry_from_manifest() {
+        let project = fake_lib_dir("appdir");
+        fs::write(
+            project.join("snask.snif"),
+            r#"{
+  package: { name: "vault", version: "1.0.0", entry: "main.snask" },
+  app: {
+    id: "snask-vault",
+    name: "Snask Vault",
+    comment: "Password vault",
+    icon: "assets/vault.svg",
+    categories: "Utility;Security;",
+  },
+}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(project.join("assets")).unwrap();
+        fs::write(project.join("assets/vault.svg"), "<svg/>").unwrap();
+        fs::write(project.join("vault"), b"\x7fELF").unwrap();
+        let (manifest, _) = crate::sps::load_manifest_from(&project).expect("manifest");
+        let out_dir = project.join("dist");
+        fs::create_dir_all(&out_dir).unwrap();
+
+        let appdir = super::prepare_appdir(
+            &out_dir,
+            &project,
+            Some(&manifest),
+            "vault",
+            &project.join("vault"),
+        )
+        .expect("AppDir should be prepared");
+        let icon = fs::read_to_string(appdir.join("snask-vault.svg"));
+        let desktop = fs::read_to_string(appdir.join("snask-vault.desktop"));
+        let has_bin = appdir.join("usr/bin/vault").is_file();
+        let _ = fs::remove_dir_all(&project);
+
+        assert_eq!(icon.expect("icon copied into the AppDir"), "<svg/>");
+        let desktop = desktop.expect("desktop entry written");
+        assert!(desktop.contains("Name=Snask Vault\n"), "{desktop}");
+        assert!(desktop.contains("Icon=snask-vault\n"), "{desktop}");
+        assert!(desktop.contains("Comment=Password vault\n"), "{desktop}");
+        assert!(desktop.contains("Exec=vault\n"), "{desktop}");
+        assert!(has_bin);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn deb_control_uses_manifest_version_and_maintainer() {
+        let project = fake_lib_dir("deb");
+        fs::write(
+            project.join("snask.snif"),
+            r#"{
+  package: {
+    name: "vault",
+    version: "1.2.3",
+    entry: "main.snask",
+    maintainer: "Jane Doe <jane@example.com>",
+  },
+  app: { id: "snask-vault", name: "Snask Vault", comment: "Password vault" },
+}"#,
+        )
+        .unwrap();
+        fs::write(project.join("vault"), b"\x7fELF").unwrap();
+        let (manifest, _) = crate::sps::load_manifest_from(&project).expect("manifest");
+
+        let root =
+            super::prepare_deb_root(&project, Some(&manifest), "vault", &project.join("vault"))
+                .expect("deb root should be prepared");
+        let control = fs::read_to_string(root.join("DEBIAN/control"));
+        let version = super::deb_version(Some(&manifest)).to_string();
+        let _ = fs::remove_dir_all(&project);
+
+        let control = control.expect("control file written");
+        assert!(control.contains("Version: 1.2.3\n"), "{control}");
+        assert!(control.contains("Maintainer: Jane Doe <jane@example.com>\n"), "{control}");
+        assert!(control.contains("Description: Snask Vault - Password vault\n"), "{control}");
+        assert!(control.contains("Architecture: amd64\n"), "{control}");
+        assert_eq!(version, "1.2.3");
+    }
+
+    fn archive_project(name: &str) -> PathBuf {
+        let project = fake_lib_dir(name);
+        fs::write(project.join("README.md"), "# Vault\n").unwrap();
+        fs::write(project.join("main.snask"), "class main\n").unwrap();
+        fs::create_dir_all(project.join("dist")).unwrap();
+        project
+    }
+
+    #[test]
+    fn windows_archive_is_a_zip_with_the_binary() {
+        let project = archive_project("zip");
+        let bin = project.join("dist/vault-x86_64-pc-windows-gnu.exe");
+        fs::write(&bin, b"MZ binary").unwrap();
+
+        let kind = ArchiveKind::for_target("x86_64-pc-windows-gnu").expect("windows archives");
+        let path = make_archive(
+            kind,
+            &project.join("dist"),
+            &project,
+            &bin,
+            "vault",
+            "x86_64-pc-windows-gnu",
+        )
+        .expect("zip should be written");
+        let file = fs::File::open(&path).expect("zip exists");
+        let mut zip = zip::ZipArchive::new(file).expect("valid zip");
+        let mut names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        let mut binary = String::new();
+        zip.by_name("vault-x86_64-pc-windows-gnu/vault-x86_64-pc-windows-gnu.exe")
+            .expect("binary entry")
+            .read_to_string(&mut binary)
+            .unwrap();
+        let _ = fs::remove_dir_all(&project);
+
+        assert!(path.ends_with("vault-x86_64-pc-windows-gnu.zip"));
+        assert_eq!(
+            names,
+            vec![
+                "vault-x86_64-pc-windows-gnu/README.md",
+                "vault-x86_64-pc-windows-gnu/vault-x86_64-pc-windows-gnu.exe",
+            ]
+        );
+        assert_eq!(binary, "MZ binary");
+    }
+
+    #[test]
+    fn macos_archive_is_a_tar_gz_with_the_binary() {
+        let project = archive_project("targz");
+        let bin = project.join("dist/vault-x86_64-apple-darwin");
+        fs::write(&bin, b"macho").unwrap();
+
+        let kind = ArchiveKind::for_target("x86_64-apple-darwin").expect("macOS archives");
+        let path = make_archive(
+            kind,
+            &project.join("dist"),
+            &project,
+            &bin,
+            "vault",
+            "x86_64-apple-darwin",
+        )
+        .expect("tar.gz should be written");
+        let file = fs::File::open(&path).expect("tar.gz exists");
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut found = Vec::new();
+        for entry in archive.entries().expect("valid tar.gz") {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().to_string();
+            let mode = entry.header().mode().unwrap();
+            let mut body = String::new();
+            entry.read_to_string(&mut body).unwrap();
+            found.push((name, mode, body));
+        }
+        let _ = fs::remove_dir_all(&project);
+
+        assert!(path.ends_with("vault-x86_64-apple-darwin.tar.gz"));
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert_eq!(
+            found[0],
+            (
+                "vault-x86_64-apple-darwin/vault-x86_64-apple-darwin".to_string(),
+                0o755,
+                "macho".to_string()
+            )
+        );
+        assert_eq!(found[1].0, "vault-x86_64-apple-darwin/README.md");
+        assert!(ArchiveKind::for_target("aarch64-unknown-linux-gnu").is_none());
+    }
+
+    #[test]
+    fn target_list_scans_runtime_subdirs_and_marks_host() {
+        let lib = fake_lib_dir("targets");
+        fs::write(lib.join("runtime.o"), b"host").unwrap();
+        for t in ["x86_64-pc-windows-gnu", "aarch64-unknown-linux-gnu"] {
+            fs::create_dir_all(lib.join(t)).unwrap();
+            fs::write(lib.join(t).join("runtime.o"), b"obj").unwrap();
+        }
+        // A target directory whose setup never finished is not listed.
+        fs::create_dir_all(lib.join("wasm32-wasi")).unwrap();
+
+        let targets = installed_targets(&lib);
+        let _ = fs::remove_dir_all(&lib);
+
+        let host = host_triple();
+        assert_eq!(
+            targets[0],
+            InstalledTarget {
+                triple: host.clone(),
+                is_host: true,
+            }
+        );
+        let cross: Vec<&str> = targets[1..].iter().map(|t| t.triple.as_str()).collect();
+        let mut expected = vec!["aarch64-unknown-linux-gnu", "x86_64-pc-windows-gnu"];
+        expected.retain(|t| *t != host);
+        assert_eq!(cross, expected);
+        assert!(targets[1..].iter().all(|t| !t.is_host));
+    }
+
+    #[test]
+    fn dist_fails_early_for_targets_without_runtime() {
+        let lib = fake_lib_dir("precheck");
+        fs::create_dir_all(lib.join("x86_64-pc-windows-gnu")).unwrap();
+        fs::write(lib.join("x86_64-pc-windows-gnu/runtime.o"), b"obj").unwrap();
+
+        let ok = check_target_runtimes(&lib, &[None, Some("x86_64-pc-windows-gnu".to_string())]);
+        let err = check_target_runtimes(
+            &lib,
+            &[
+                Some("x86_64-pc-windows-gnu".to_string()),
+                Some("x86_64-apple-darwin".to_string()),
+            ],
+        );
+        let _ = fs::remove_dir_all(&lib);
+
+        assert!(ok.is_ok());
+        let err = err.expect_err("missing runtime must fail");
+        assert!(err.contains("`x86_64-apple-darwin`"), "{err}");
+        assert!(err.contains("run `snask setup --target x86_64-apple-darwin`"), "{err}");
+        assert!(!err.contains("setup --target x86_64-pc-windows-gnu"), "{err}");
+    }
+}
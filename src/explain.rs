@@ -1,8 +1,51 @@
 include!(concat!(env!("OUT_DIR"), "/explain.rs"));
 
+/// `snask explain <CODE>`, like `rustc --explain`. Accepts the short code
+/// (`S2002`, any case) or the full one (`SNASK-SEM-VAR-NOT-FOUND`).
+pub fn run_explain(code: &str) -> Result<(), String> {
+    println!("{}", explain_text(code)?);
+    Ok(())
+}
+
+/// The explanation with its first line as an underlined title, or an error
+/// listing the closest and all known codes.
+pub fn explain_text(code: &str) -> Result<String, String> {
+    let upper = code.trim().to_ascii_uppercase();
+    let text = get_explanation(normalize_code(&upper)).ok_or_else(|| unknown_code(code))?;
+    let (title, body) = text.split_once("\n\n").unwrap_or((text, ""));
+    Ok(format!(
+        "{}\n{}\n\n{}",
+        title,
+        "=".repeat(title.chars().count()),
+        body
+    ))
+}
+
+fn unknown_code(code: &str) -> String {
+    let upper = code.trim().to_ascii_uppercase();
+    // Codes of the same group share the letter and first digit (`S2xxx`).
+    let group: String = upper.chars().take(2).collect();
+    let mut close: Vec<&str> = EXPLAIN_CODES
+        .iter()
+        .copied()
+        .filter(|c| group.len() == 2 && c.starts_with(&group))
+        .collect();
+    close.truncate(5);
+    let mut all: Vec<&str> = EXPLAIN_CODES.to_vec();
+    all.sort();
+    all.dedup();
+
+    let mut msg = format!("unknown diagnostic code `{}`.\n", code.trim());
+    if !close.is_empty() {
+        msg.push_str(&format!("\nsimilar codes: {}\n", close.join(", ")));
+    }
+    msg.push_str(&format!("\navailable codes: {}\n", all.join(", ")));
+    msg
+}
+
 #[cfg(test)]
 mod tests {
-    use super::get_explanation;
+    use super::{explain_text, get_explanation};
 
     #[test]
     fn explain_has_entry_for_missing_paren() {
@@ -22,4 +65,22 @@ mod tests {
     fn explain_returns_none_for_unknown_code() {
         assert!(get_explanation("S9999").is_none());
     }
+
+    #[test]
+    fn explain_text_has_a_title_and_lists_codes_when_unknown() {
+        let text = explain_text("s2002").expect("lowercase codes are accepted");
+        let mut lines = text.lines();
+        let title = lines.next().unwrap();
+        assert!(title.starts_with("S2002: "), "{text}");
+        assert_eq!(lines.next().unwrap(), "=".repeat(title.chars().count()));
+        assert!(text.contains("severity"), "{text}");
+
+        let err = explain_text("S2999").expect_err("S2999 is not a code");
+        assert!(err.contains("unknown diagnostic code `S2999`"), "{err}");
+        assert!(err.contains("similar codes: S2"), "{err}");
+        assert!(
+            err.contains("available codes:") && err.contains("S1002"),
+            "{err}"
+        );
+    }
 }
@@ -32,6 +32,8 @@ pub enum FixItApply {
     CliSteps(Vec<String>),
     /// Placeholder for editor-driven edits (LSP). The compiler only renders this as text.
     WorkspaceEditHint(String),
+    /// Insert `text` where `at` starts. Editors apply it as an edit; the CLI only prints it.
+    Insert { at: Span, text: String },
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -42,6 +44,56 @@ pub struct FixIt {
     pub apply: Option<FixItApply>,
 }
 
+impl FixIt {
+    /// Confident enough to apply as is: `help` in the CLI, a preferred quick fix in editors.
+    pub fn is_safe(&self) -> bool {
+        self.confidence >= QUICKFIX_THRESHOLD
+    }
+
+    /// Worth mentioning at all; less likely fixes are neither shown nor offered.
+    pub fn is_plausible(&self) -> bool {
+        self.confidence >= MAYBE_THRESHOLD
+    }
+}
+
+/// Adds `fix` to `d` the way every diagnostic shows one: a safe fix becomes
+/// the help (ahead of `help`), a plausible one a note, anything else is
+/// dropped.
+pub fn with_fixit_help(mut d: Diagnostic, fix: &FixIt, help: Option<&str>) -> Diagnostic {
+    if fix.is_safe() {
+        let mut text = format!("safe fix ({}%): {}", fix.confidence, fix.title);
+        if let Some(apply) = &fix.apply {
+            match apply {
+                FixItApply::CliSteps(steps) => {
+                    for s in steps {
+                        text.push_str(&format!("\n  - {s}"));
+                    }
+                }
+                FixItApply::WorkspaceEditHint(h) => {
+                    text.push_str(&format!("\n  - {h}"));
+                }
+                FixItApply::Insert { at, text: insert } => {
+                    text.push_str(&format!(
+                        "\n  - insert `{insert}` at {}:{}",
+                        at.start.line, at.start.column
+                    ));
+                }
+            }
+        }
+        if let Some(existing) = help {
+            text.push_str(&format!("\n\n{existing}"));
+        }
+        return d.with_help(text);
+    }
+    if fix.is_plausible() {
+        d = d.with_note(format!("possible fix ({}%): {}", fix.confidence, fix.title));
+    }
+    match help {
+        Some(existing) => d.with_help(existing.to_string()),
+        None => d,
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Trace {
     pub code: String,
@@ -153,33 +205,7 @@ impl HyperDiagnostic {
         if !self.fixits.is_empty() {
             let mut fixes = self.fixits.clone();
             fixes.sort_by_key(|f| std::cmp::Reverse(f.confidence));
-            let top = &fixes[0];
-            if top.confidence >= QUICKFIX_THRESHOLD {
-                let mut help = format!("safe fix ({}%): {}", top.confidence, top.title);
-                if let Some(apply) = &top.apply {
-                    match apply {
-                        FixItApply::CliSteps(steps) => {
-                            for s in steps {
-                                help.push_str(&format!("\n  - {s}"));
-                            }
-                        }
-                        FixItApply::WorkspaceEditHint(h) => {
-                            help.push_str(&format!("\n  - {h}"));
-                        }
-                    }
-                }
-                if let Some(existing) = &self.help {
-                    help.push_str(&format!("\n\n{existing}"));
-                }
-                d = d.with_help(help);
-            } else if top.confidence >= MAYBE_THRESHOLD {
-                d = d.with_note(format!("possible fix ({}%): {}", top.confidence, top.title));
-                if let Some(existing) = &self.help {
-                    d = d.with_help(existing.clone());
-                }
-            } else if let Some(existing) = &self.help {
-                d = d.with_help(existing.clone());
-            }
+            d = with_fixit_help(d, &fixes[0], self.help.as_deref());
         } else if let Some(existing) = &self.help {
             d = d.with_help(existing.clone());
         }
@@ -0,0 +1,759 @@
+use crate::compiler::{
+    build_progress_bar, generate_ir, get_imported_pkgs, uses_name_dispatch, BuildOptions,
+};
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::targets::{InitializationConfig, Target};
+use inkwell::OptimizationLevel;
+use std::ffi::{c_char, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shared build of the host runtime, written by `snask setup` next to
+/// `runtime.o`. The JIT loads it so the generated code can call the natives.
+pub fn shared_runtime_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".snask/lib/runtime.so")
+}
+
+/// `snask run --jit`: compiles `file_path` to IR and runs `main` in-process
+/// with `args` as the program arguments. Returns the exit code, or `None` when the program cannot be JIT-compiled
+/// (the reason is printed) and the caller should build and run it instead.
+pub fn run_file(
+    file_path: &str,
+    options: &BuildOptions,
+    args: &[String],
+) -> Result<Option<i32>, String> {
+    let source = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+    let entry_dir = Path::new(file_path).parent().unwrap_or(Path::new("."));
+    let pb = build_progress_bar(options.verbosity);
+    let generated = generate_ir(file_path, &source, entry_dir, options, &pb)?;
+    pb.finish_and_clear();
+
+    // Handlers looked up with `dlsym` and native packages need a real link.
+    if uses_name_dispatch(&generated.program) || !get_imported_pkgs(&generated.program).is_empty() {
+        eprintln!("note: this program needs the linker; running the AOT build instead of --jit");
+        return Ok(None);
+    }
+    match run_ir(&generated.ir, file_path, &shared_runtime_path(), args) {
+        Ok(code) => Ok(Some(code)),
+        Err(e) => {
+            eprintln!(
+                "warning: --jit unavailable ({}); falling back to the AOT build",
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Parses `ir`, loads `runtime` into the process and calls the module's
+/// `main` with LLVM's JIT. `argv[0]` is `module_name`, followed by `args`.
+pub fn run_ir(ir: &str, module_name: &str, runtime: &Path, args: &[String]) -> Result<i32, String> {
+    if !runtime.is_file() {
+        return Err(format!(
+            "{} not found; run `snask setup` to build it",
+            runtime.display()
+        ));
+    }
+    // Returns true on failure.
+    if inkwell::support::load_library_permanently(runtime) {
+        return Err(format!("could not load {}", runtime.display()));
+    }
+    Target::initialize_native(&InitializationConfig::default())?;
+
+    let context = Context::create();
+    let buffer = MemoryBuffer::create_from_memory_range_copy(ir.as_bytes(), module_name);
+    let module = context
+        .create_module_from_ir(buffer)
+        .map_err(|e| e.to_string())?;
+    let engine = module
+        .create_jit_execution_engine(OptimizationLevel::Default)
+        .map_err(|e| e.to_string())?;
+    let argv = std::iter::once(module_name)
+        .chain(args.iter().map(String::as_str))
+        .map(|a| CString::new(a).map_err(|_| format!("argument {:?} contains a NUL byte", a)))
+        .collect::<Result<Vec<CString>, String>>()?;
+    let mut argv_ptrs: Vec<*const c_char> = argv.iter().map(|a| a.as_ptr()).collect();
+    argv_ptrs.push(std::ptr::null());
+    type Main = unsafe extern "C" fn(i32, *const *const c_char) -> i32;
+    let main = unsafe { engine.get_function::<Main>("main") }.map_err(|e| e.to_string())?;
+    Ok(unsafe { main.call(argv.len() as i32, argv_ptrs.as_ptr()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_ir, shared_runtime_path};
+    use crate::compiler::{build_source, generate_ir, BuildOptions, Verbosity};
+    use indicatif::ProgressBar;
+    use std::fs;
+    use std::io::Read;
+    use std::os::unix::io::FromRawFd;
+    use std::path::Path;
+    use std::process::Command;
+
+    const SOURCE: &str = "fun twice(n: int) : int\n    return n * 2\n\
+class main\n    fun start()\n        print(\"hello\")\n        print(twice(21))\n";
+
+    /// Runs the JIT in a child process with stdout redirected into a pipe.
+    fn jit_stdout(ir: &str, args: &[String]) -> Option<String> {
+        let (code, out, _) = jit_output(ir, args)?;
+        (code == 0).then_some(out)
+    }
+
+    /// Like `jit_stdout`, but also captures stderr and hands back the exit
+    /// code whatever it is.
+    fn jit_output(ir: &str, args: &[String]) -> Option<(i32, String, String)> {
+        let mut out_fds = [0; 2];
+        let mut err_fds = [0; 2];
+        unsafe {
+            if libc::pipe(out_fds.as_mut_ptr()) != 0 || libc::pipe(err_fds.as_mut_ptr()) != 0 {
+                return None;
+            }
+            let pid = libc::fork();
+            if pid == 0 {
+                libc::close(out_fds[0]);
+                libc::close(err_fds[0]);
+                libc::dup2(out_fds[1], 1);
+                libc::dup2(err_fds[1], 2);
+                let code = run_ir(ir, "main.snask", &shared_runtime_path(), args).unwrap_or(127);
+                libc::fflush(std::ptr::null_mut());
+                libc::_exit(code);
+            }
+            libc::close(out_fds[1]);
+            libc::close(err_fds[1]);
+            let mut out = String::new();
+            std::fs::File::from_raw_fd(out_fds[0])
+                .read_to_string(&mut out)
+                .ok()?;
+            let mut err = String::new();
+            std::fs::File::from_raw_fd(err_fds[0])
+                .read_to_string(&mut err)
+                .ok()?;
+            let mut status = 0;
+            libc::waitpid(pid, &mut status, 0);
+            libc::WIFEXITED(status).then(|| (libc::WEXITSTATUS(status), out, err))
+        }
+    }
+
+    /// Generates the IR of a standalone `main.snask` program.
+    fn source_ir(source: &str) -> String {
+        generate_ir(
+            "main.snask",
+            source,
+            Path::new("."),
+            &BuildOptions::default(),
+            &ProgressBar::hidden(),
+        )
+        .expect("IR should be generated")
+        .ir
+    }
+
+    /// Whether the shared runtime the JIT loads is missing, in which case the
+    /// calling test should return early.
+    fn no_shared_runtime() -> bool {
+        let missing = !shared_runtime_path().is_file();
+        if missing {
+            eprintln!("skipping: no shared runtime (run `snask setup`)");
+        }
+        missing
+    }
+
+    #[test]
+    fn jit_prints_the_same_as_the_aot_binary() {
+        if no_shared_runtime() {
+            return;
+        }
+        let binary = std::env::temp_dir().join(format!("snask_jit_aot_{}", std::process::id()));
+        let options = BuildOptions {
+            output_name: Some(binary.to_string_lossy().to_string()),
+            verbosity: Verbosity::Quiet,
+            ..Default::default()
+        };
+        let ir = generate_ir(
+            "main.snask",
+            SOURCE,
+            Path::new("."),
+            &options,
+            &ProgressBar::hidden(),
+        )
+        .expect("IR should be generated")
+        .ir;
+        build_source("main.snask", SOURCE, Path::new("."), options).expect("AOT build");
+        let aot = Command::new(&binary)
+            .output()
+            .expect("AOT binary should run");
+        let _ = std::fs::remove_file(&binary);
+
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, String::from_utf8_lossy(&aot.stdout));
+        assert!(jit.contains("hello"), "{jit}");
+    }
+
+    #[test]
+    fn forwarded_arguments_reach_args_in_both_backends() {
+        const ECHO: &str =
+            "class main\n    fun start()\n        for a in args()\n            print(a)\n";
+        let args: Vec<String> = ["one", "--flag", "two words"].map(String::from).to_vec();
+        let binary = std::env::temp_dir().join(format!("snask_jit_args_{}", std::process::id()));
+        let options = BuildOptions {
+            output_name: Some(binary.to_string_lossy().to_string()),
+            verbosity: Verbosity::Quiet,
+            ..Default::default()
+        };
+        let ir = generate_ir(
+            "main.snask",
+            ECHO,
+            Path::new("."),
+            &options,
+            &ProgressBar::hidden(),
+        )
+        .expect("IR should be generated")
+        .ir;
+        assert!(ir.contains("define i32 @main(i32"), "{ir}");
+        assert!(ir.contains("call void @s_set_args"), "{ir}");
+        assert!(ir.contains("call void @s_args"), "{ir}");
+
+        if no_shared_runtime() {
+            return;
+        }
+        build_source("main.snask", ECHO, Path::new("."), options).expect("AOT build");
+        let aot = Command::new(&binary)
+            .args(&args)
+            .output()
+            .expect("AOT binary should run");
+        let _ = std::fs::remove_file(&binary);
+        assert_eq!(
+            String::from_utf8_lossy(&aot.stdout),
+            "one\n--flag\ntwo words\n"
+        );
+
+        let jit = jit_stdout(&ir, &args).expect("JIT run should succeed");
+        assert_eq!(jit, "one\n--flag\ntwo words\n");
+    }
+
+    #[test]
+    fn power_evaluates_right_to_left() {
+        const POWER: &str = r#"class main
+    fun start()
+        print(2 ** 3 ** 2 == 512)
+        print((2 ** 3) ** 2 == 64)
+        print(2 ** -1 == 0.5)
+"#;
+        let ir = source_ir(POWER);
+        assert!(ir.contains("call void @s_pow("), "{ir}");
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "true\ntrue\ntrue\n");
+    }
+
+    #[test]
+    fn and_or_skip_the_right_side_once_the_left_decides() {
+        const SHORT: &str = r#"fun loud(v: bool) : bool
+    print("ran")
+    return v
+
+class main
+    fun start()
+        print(false and loud(true))
+        print(true or loud(false))
+        print(true and loud(false))
+        print(false or loud(true))
+"#;
+        let ir = source_ir(SHORT);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "false\ntrue\nran\nfalse\nran\ntrue\n");
+    }
+
+    #[test]
+    fn bitwise_ops_work_on_ints() {
+        const BITS: &str = r#"class main
+    fun start()
+        let xs = [12]
+        print(0xF0 & 0x0F == 0)
+        print(0xF0 | 0x0F == 255)
+        print(6 ^ 3 == 5)
+        print(1 << 4 == 16)
+        print(-16 >> 2 == -4)
+        print(~0 == -1)
+        print(xs[0] | 1 == 13)
+"#;
+        let ir = source_ir(BITS);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "true\n".repeat(7));
+    }
+
+    #[test]
+    fn modulo_follows_the_dividend_and_zero_divisors_are_catchable() {
+        const MODULO: &str = r#"class main
+    fun start()
+        let zero = 0
+        print(10 % 3 == 1)
+        print(-7 % 3 == -1)
+        print(7.5 % 2 == 1.5)
+        print(1.5 / 0.0 > 1000.0)
+        try:
+            print(10 % zero)
+        catch err:
+            print(err)
+"#;
+        let ir = source_ir(MODULO);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "true\ntrue\ntrue\ntrue\ndivision by zero\n");
+    }
+
+    #[test]
+    fn optional_chaining_gives_nil_for_a_nil_base() {
+        const OPTIONAL: &str = r#"class Point
+    let x: int = 3
+    fun twice() : int
+        return self.x * 2
+
+fun find(found: bool) : Point
+    if found
+        return Point()
+    return nil
+
+class main
+    fun start()
+        let p: Point = find(true)
+        let q: Point = find(false)
+        print(p?.x)
+        print(p?.twice())
+        print(q?.x)
+        print(q?.twice())
+        print(q?.x?.y)
+"#;
+        let ir = source_ir(OPTIONAL);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "3\n6\nnil\nnil\nnil\n");
+    }
+
+    #[test]
+    fn struct_literal_sets_the_given_fields_without_init() {
+        const LITERAL: &str = r#"class Point
+    let x: int = 0
+    let y: int = 0
+    fun init()
+        print("init")
+    fun sum() : int
+        return self.x + self.y
+
+class main
+    fun start()
+        let p: Point = Point { y: 2, x: 1 }
+        print(p.x)
+        print(p.y)
+        print(p.sum())
+"#;
+        let ir = source_ir(LITERAL);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "1\n2\n3\n");
+    }
+
+    #[test]
+    fn methods_dispatch_on_the_class_the_object_was_made_from() {
+        const DISPATCH: &str = r#"class Shape
+    let name: str = "shape"
+    fun area() : int
+        return 0
+    fun describe() : str
+        return self.name + " " + to_str(self.area())
+
+class Circle extends Shape
+    let r: int = 2
+    fun area() : int
+        return 3 * self.r * self.r
+
+class main
+    fun start()
+        let s: Shape = Circle()
+        print(s.area())
+        print(s.describe())
+        let shapes = [Shape(), Circle()]
+        print(shapes[0].area())
+        print(shapes[1].area())
+"#;
+        let ir = source_ir(DISPATCH);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(jit, "12\nshape 12\n0\n12\n");
+    }
+
+    #[test]
+    fn out_of_range_index_assignment_panics_outside_try() {
+        const INDEX_SET: &str = r#"class main
+    fun start()
+        let xs = [1, 2]
+        try:
+            xs[5] = 3
+        catch err:
+            print(err)
+        print("before")
+        xs[7] = 3
+        print("not reached")
+"#;
+        let ir = source_ir(INDEX_SET);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let (code, out, err) = jit_output(&ir, &[]).expect("JIT run should finish");
+        assert_eq!(code, 1, "{err}");
+        assert_eq!(out, "index 5 out of bounds for length 2\nbefore\n");
+        assert!(err.contains("index 7 out of bounds for length 2"), "{err}");
+        assert!(err.contains("main.snask:9:"), "{err}");
+    }
+
+    #[test]
+    fn failing_assert_exits_with_its_message_and_location() {
+        let testes = Path::new(env!("CARGO_MANIFEST_DIR")).join("Testes");
+        let source = fs::read_to_string(testes.join("assert.snask")).expect("read assert.snask");
+        let generate = |options: &BuildOptions| {
+            generate_ir(
+                "Testes/assert.snask",
+                &source,
+                &testes,
+                options,
+                &ProgressBar::hidden(),
+            )
+            .expect("IR should be generated")
+        };
+        let checked = generate(&BuildOptions::default());
+        let unchecked = generate(&BuildOptions {
+            no_assert: true,
+            ..Default::default()
+        });
+
+        if no_shared_runtime() {
+            return;
+        }
+        let (code, out, err) = jit_output(&checked.ir, &[]).expect("JIT run should finish");
+        assert_eq!(code, 1, "{err}");
+        assert_eq!(out, "ok\n");
+        assert!(err.contains("assertion failed: two is not three"), "{err}");
+        assert!(err.contains("--> Testes/assert.snask:11:"), "{err}");
+
+        let (code, out, err) = jit_output(&unchecked.ir, &[]).expect("JIT run should finish");
+        assert_eq!((code, out.as_str()), (0, "ok\nend\n"), "{err}");
+    }
+
+    #[test]
+    fn list_index_out_of_range_panics_at_its_line_but_dicts_give_nil() {
+        const INDEX_GET: &str = r#"class main
+    fun start()
+        let ages: dict = {"ana": 30}
+        print(ages[3])
+        let xs = [1, 2]
+        print(xs[4])
+        print("not reached")
+"#;
+        let ir = source_ir(INDEX_GET);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let (code, out, err) = jit_output(&ir, &[]).expect("JIT run should finish");
+        assert_eq!(code, 1, "{err}");
+        assert_eq!(out, "nil\n");
+        assert!(err.contains("index 4 out of bounds for length 2"), "{err}");
+        assert!(err.contains("main.snask:6:"), "{err}");
+    }
+
+    #[test]
+    fn regex_matches_captures_and_replaces() {
+        const REGEX: &str = r#"import "regex"
+
+class main
+    fun start()
+        print(regex::match("b+", "abbc"))
+        print(regex::match("^b", "abbc"))
+        print(regex::find("([a-z]+)@", "mail: ana@host"))
+        print(regex::find("[0-9]+", "room 101, floor 3"))
+        let keys = regex::find_all("([a-z])=", "a=1 b=2")
+        print(len(keys), keys[0], keys[1], sep: " ")
+        print(regex::replace("([a-z]+)@([a-z]+)", "ana@host", "\\2 at \\1"))
+        print(regex::replace("o", "foo", "[\\0]"))
+        print(regex::replace("x*", "ab", "-"))
+"#;
+        let ir = source_ir(REGEX);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let jit = jit_stdout(&ir, &[]).expect("JIT run should succeed");
+        assert_eq!(
+            jit,
+            "true\nfalse\nana\n101\n2 a b\nhost at ana\nf[o][o]\n-a-b-\n"
+        );
+    }
+
+    /// The `// Expected output:` comment block a `Testes/` program starts with.
+    fn expected_output(source: &str) -> String {
+        source
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| !line.starts_with("// Expected output"))
+            .skip(1)
+            .take_while(|line| line.starts_with("//"))
+            .map(|line| {
+                let text = line.strip_prefix("// ").unwrap_or(&line[2..]);
+                format!("{text}\n")
+            })
+            .collect()
+    }
+
+    /// Runs `Testes/<name>.snask` in the JIT and checks that it prints the
+    /// output its header documents.
+    fn assert_testes_output(name: &str) {
+        let testes = Path::new(env!("CARGO_MANIFEST_DIR")).join("Testes");
+        let path = testes.join(format!("{name}.snask"));
+        let source = fs::read_to_string(&path).expect("Testes program should exist");
+        let expected = expected_output(&source);
+        assert!(!expected.is_empty(), "{name} documents no output");
+        let generated = generate_ir(
+            "main.snask",
+            &source,
+            &testes,
+            &BuildOptions::default(),
+            &ProgressBar::hidden(),
+        )
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        if no_shared_runtime() {
+            return;
+        }
+        let (code, out, err) = jit_output(&generated.ir, &[]).expect("JIT run should finish");
+        assert_eq!(code, 0, "{name}: {err}");
+        assert_eq!(out, expected, "{name}");
+    }
+
+    #[test]
+    fn testes_int_math_prints_its_expected_output() {
+        assert_testes_output("int_math");
+    }
+
+    #[test]
+    fn testes_tuples_prints_its_expected_output() {
+        assert_testes_output("tuples");
+    }
+
+    #[test]
+    fn testes_stats_prints_its_expected_output() {
+        assert_testes_output("stats");
+    }
+
+    #[test]
+    fn testes_list_utils_prints_its_expected_output() {
+        assert_testes_output("list_utils");
+    }
+
+    #[test]
+    fn testes_dict_utils_prints_its_expected_output() {
+        assert_testes_output("dict_utils");
+    }
+
+    #[test]
+    fn testes_json_opts_prints_its_expected_output() {
+        assert_testes_output("json_opts");
+    }
+
+    #[test]
+    fn testes_csv_prints_its_expected_output() {
+        assert_testes_output("csv");
+    }
+
+    #[test]
+    fn testes_base64_prints_its_expected_output() {
+        assert_testes_output("base64");
+    }
+
+    #[test]
+    fn testes_hash_prints_its_expected_output() {
+        assert_testes_output("hash");
+    }
+
+    #[test]
+    fn testes_sfs_stream_prints_its_expected_output() {
+        assert_testes_output("sfs_stream");
+    }
+
+    #[test]
+    fn testes_random_seed_prints_its_expected_output() {
+        assert_testes_output("random_seed");
+    }
+
+    #[test]
+    fn testes_timing_prints_its_expected_output() {
+        assert_testes_output("timing");
+    }
+
+    #[test]
+    fn testes_membership_prints_its_expected_output() {
+        assert_testes_output("membership");
+    }
+
+    #[test]
+    fn testes_string_slice_prints_its_expected_output() {
+        assert_testes_output("string_slice");
+    }
+
+    #[test]
+    fn testes_datetime_prints_its_expected_output() {
+        assert_testes_output("datetime");
+    }
+
+    #[test]
+    fn testes_try_catch_prints_its_expected_output() {
+        assert_testes_output("try_catch");
+    }
+
+    #[test]
+    fn testes_enum_prints_its_expected_output() {
+        assert_testes_output("enum");
+    }
+
+    #[test]
+    fn testes_conversions_prints_its_expected_output() {
+        assert_testes_output("conversions");
+    }
+
+    #[test]
+    fn testes_loop_else_prints_its_expected_output() {
+        assert_testes_output("loop_else");
+    }
+
+    #[test]
+    fn testes_power_prints_its_expected_output() {
+        assert_testes_output("power");
+    }
+
+    #[test]
+    fn testes_modulo_prints_its_expected_output() {
+        assert_testes_output("modulo");
+    }
+
+    #[test]
+    fn testes_bitwise_prints_its_expected_output() {
+        assert_testes_output("bitwise");
+    }
+
+    #[test]
+    fn testes_short_circuit_prints_its_expected_output() {
+        assert_testes_output("short_circuit");
+    }
+
+    #[test]
+    fn testes_optional_chaining_prints_its_expected_output() {
+        assert_testes_output("optional_chaining");
+    }
+
+    #[test]
+    fn testes_struct_literal_prints_its_expected_output() {
+        assert_testes_output("struct_literal");
+    }
+
+    #[test]
+    fn testes_slicing_prints_its_expected_output() {
+        assert_testes_output("slicing");
+    }
+
+    #[test]
+    fn testes_index_assign_prints_its_expected_output() {
+        assert_testes_output("index_assign");
+    }
+
+    #[test]
+    fn testes_string_builder_prints_its_expected_output() {
+        assert_testes_output("string_builder");
+    }
+
+    #[test]
+    fn testes_regex_prints_its_expected_output() {
+        assert_testes_output("regex");
+    }
+
+    #[test]
+    fn testes_cfg_follows_the_defines() {
+        let testes = Path::new(env!("CARGO_MANIFEST_DIR")).join("Testes");
+        let source = fs::read_to_string(testes.join("cfg.snask")).expect("read cfg.snask");
+        let generate = |defines: &[(&str, &str)]| {
+            let options = BuildOptions {
+                defines: defines
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                ..Default::default()
+            };
+            generate_ir(
+                "main.snask",
+                &source,
+                &testes,
+                &options,
+                &ProgressBar::hidden(),
+            )
+            .expect("IR should be generated")
+            .ir
+        };
+        let plain = generate(&[]);
+        let debug = generate(&[("debug", "true")]);
+
+        if no_shared_runtime() {
+            return;
+        }
+        let out = jit_stdout(&plain, &[]).expect("JIT run should succeed");
+        assert_eq!(out, "default build\ndebug: false\n");
+        let out = jit_stdout(&debug, &[]).expect("JIT run should succeed");
+        assert_eq!(out, "debug build\ndebug: true\n");
+    }
+
+    #[test]
+    fn testes_index_panic_exits_at_the_bad_index() {
+        let testes = Path::new(env!("CARGO_MANIFEST_DIR")).join("Testes");
+        let source =
+            fs::read_to_string(testes.join("index_panic.snask")).expect("read index_panic.snask");
+        let generated = generate_ir(
+            "Testes/index_panic.snask",
+            &source,
+            &testes,
+            &BuildOptions::default(),
+            &ProgressBar::hidden(),
+        )
+        .expect("IR should be generated");
+
+        if no_shared_runtime() {
+            return;
+        }
+        let (code, out, err) = jit_output(&generated.ir, &[]).expect("JIT run should finish");
+        assert_eq!(code, 1, "{err}");
+        assert_eq!(out, "uva\n");
+        assert!(err.contains("index 3 out of bounds for length 3"), "{err}");
+        assert!(err.contains("--> Testes/index_panic.snask:8:"), "{err}");
+    }
+}
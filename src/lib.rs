@@ -1,20 +1,29 @@
 pub mod ast;
+pub mod bench;
+pub mod builtins;
+pub mod clean;
 pub mod compiler;
 pub mod errors;
 pub mod diagnostics;
+pub mod disasm;
 pub mod dist;
 pub mod explain;
 pub mod fetch;
 pub mod hds;
+pub mod jit;
 pub mod lib_tool;
 pub mod llvm_generator;
 pub mod modules;
 pub mod om_contract;
 pub mod om_scan;
+pub mod optimizer;
 pub mod packages;
 pub mod parser;
+pub mod repl;
 pub mod semantic_analyzer;
+pub mod snask_fmt;
 pub mod snif_fmt;
+pub mod snif_json;
 pub mod snif_parser;
 pub mod snif_schema;
 pub mod snif_tooling;
@@ -22,7 +31,9 @@ pub mod span;
 pub mod sps;
 pub mod stdlib;
 pub mod symbol_table;
+pub mod test_runner;
 pub mod toolchain;
 pub mod tools;
 pub mod types;
 pub mod value;
+pub mod watch;
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -87,6 +88,16 @@ struct PackageJson {
 }
 
 pub fn lib_init(opts: NewLibOpts) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    lib_init_in(&cwd, &opts)?;
+    println!("✅ Created: {}.snask, package.json, README.md", opts.name.trim());
+    println!("📦 Next step: `snask lib publish {}`.", opts.name.trim());
+    Ok(())
+}
+
+/// Writes `<name>.snask`, `package.json` and `README.md` into `dir`, the
+/// files `snask lib publish` reads.
+pub fn lib_init_in(dir: &Path, opts: &NewLibOpts) -> Result<(), String> {
     let name = opts.name.trim();
     if name.is_empty() {
         return Err("Invalid name.".to_string());
@@ -101,9 +112,9 @@ pub fn lib_init(opts: NewLibOpts) -> Result<(), String> {
     let snask_file = format!("{}.snask", name);
     let json_file = "package.json";
     let md_file = "README.md";
-    if Path::new(&snask_file).exists()
-        || Path::new(json_file).exists()
-        || Path::new(md_file).exists()
+    if dir.join(&snask_file).exists()
+        || dir.join(json_file).exists()
+        || dir.join(md_file).exists()
     {
         return Err(format!(
             "One of these files already exists in the current directory: '{}', '{}' or '{}'.",
@@ -117,7 +128,7 @@ pub fn lib_init(opts: NewLibOpts) -> Result<(), String> {
         version = opts.version,
         desc = opts.description.replace('\"', "\\\"")
     );
-    fs::write(&snask_file, content)
+    fs::write(dir.join(&snask_file), content)
         .map_err(|e| format!("Failed to create '{}': {}", snask_file, e))?;
 
     let pkg_json = format!(
@@ -126,7 +137,7 @@ pub fn lib_init(opts: NewLibOpts) -> Result<(), String> {
         version = opts.version,
         desc = opts.description.replace('\"', "\\\"")
     );
-    fs::write(json_file, pkg_json)
+    fs::write(dir.join(json_file), pkg_json)
         .map_err(|e| format!("Failed to create '{}': {}", json_file, e))?;
 
     let readme = format!(
@@ -134,10 +145,8 @@ pub fn lib_init(opts: NewLibOpts) -> Result<(), String> {
         name = name,
         desc = opts.description
     );
-    fs::write(md_file, readme).map_err(|e| format!("Failed to create '{}': {}", md_file, e))?;
-
-    println!("✅ Created: {}, {}, {}", snask_file, json_file, md_file);
-    println!("📦 Next step: `snask lib publish {}`.", name);
+    fs::write(dir.join(md_file), readme)
+        .map_err(|e| format!("Failed to create '{}': {}", md_file, e))?;
     Ok(())
 }
 
@@ -151,17 +160,77 @@ pub struct PublishOpts {
     pub pr: bool,
     pub fork: Option<String>,
     pub branch: Option<String>,
+    /// Validate and print the plan without touching git or the registry.
+    pub dry_run: bool,
+}
+
+/// What `lib publish` writes into the registry repo, worked out before
+/// anything is touched so `--dry-run` can show it.
+#[derive(Debug, Clone)]
+pub struct PublishPlan {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// Library files and where they go, relative to the registry repo.
+    pub files: Vec<(PathBuf, PathBuf)>,
+    /// `index/<letter>/<name>.json`, relative to the registry repo.
+    pub index_path: PathBuf,
+    pub index_json: String,
+    pub message: String,
+    /// Branch pushed to the fork with `--pr`.
+    pub branch: Option<String>,
+    /// `canonical_sha256` of the library file, recorded in the index.
+    pub sha256: String,
+    /// This exact version and content is already in the registry.
+    pub already_published: bool,
+}
+
+/// sha256 of a library file with CRLF line endings normalized, so the same
+/// source checked out on Windows and Unix hashes the same.
+pub fn canonical_sha256(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes).replace("\r\n", "\n");
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Hash of `name` at `version` as published in the `registry` checkout:
+/// the index entry's `sha256` when it describes that version, otherwise the
+/// archived source in `packages_src/`.
+fn published_sha256(
+    registry: &Path,
+    name: &str,
+    version: &str,
+    index_path: &Path,
+) -> Option<String> {
+    let entry: Option<JsonValue> = fs::read(registry.join(index_path))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    if let Some(entry) = entry {
+        if entry.get("version").and_then(|v| v.as_str()) == Some(version) {
+            if let Some(sha) = entry.get("sha256").and_then(|v| v.as_str()) {
+                return Some(sha.to_string());
+            }
+        }
+    }
+    let src_dir = registry.join("packages_src").join(name).join(version);
+    if !src_dir.exists() {
+        return None;
+    }
+    let bytes = fs::read(src_dir.join(format!("{}.snask", name))).unwrap_or_default();
+    Some(canonical_sha256(&bytes))
 }
 
-fn read_required_package_files(lib_name: &str) -> Result<(PackageJson, String, PathBuf), String> {
-    let snask_path = PathBuf::from(format!("{}.snask", lib_name));
+fn read_required_package_files(
+    dir: &Path,
+    lib_name: &str,
+) -> Result<(PackageJson, String, PathBuf), String> {
+    let snask_path = dir.join(format!("{}.snask", lib_name));
     if !snask_path.exists() {
         return Err(format!(
             "Arquivo obrigatório não encontrado: '{}'.",
             snask_path.display()
         ));
     }
-    let json_bytes = fs::read("package.json")
+    let json_bytes = fs::read(dir.join("package.json"))
         .map_err(|e| format!("Arquivo obrigatório não encontrado: package.json ({})", e))?;
     let pkg: PackageJson =
         serde_json::from_slice(&json_bytes).map_err(|e| format!("package.json inválido: {}", e))?;
@@ -171,7 +240,7 @@ fn read_required_package_files(lib_name: &str) -> Result<(PackageJson, String, P
             pkg.name, lib_name
         ));
     }
-    let md = fs::read_to_string("README.md")
+    let md = fs::read_to_string(dir.join("README.md"))
         .map_err(|e| format!("Arquivo obrigatório não encontrado: README.md ({})", e))?;
     if md.trim().is_empty() {
         return Err("README.md está vazio.".to_string());
@@ -179,19 +248,283 @@ fn read_required_package_files(lib_name: &str) -> Result<(PackageJson, String, P
     Ok((pkg, md, snask_path))
 }
 
-pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
+/// Always left out of a published library, before `.snaskignore` rules.
+const DEFAULT_IGNORES: &[&str] = &["dist/", "target/", "*.o", "*.ll"];
+
+/// One `.snaskignore` line: gitignore-style globs where `*`/`?` stay within
+/// a path component, `**` crosses them, a trailing `/` only matches
+/// directories, a `/` elsewhere anchors the pattern to the library root and
+/// `!` re-includes what an earlier line excluded.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        Some(IgnoreRule {
+            pattern: line.trim_start_matches('/').to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// `rel` uses `/` separators and is relative to the library root.
+    fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            glob_matches(self.pattern.as_bytes(), rel.as_bytes())
+        } else {
+            let base = rel.rsplit('/').next().unwrap_or(rel);
+            glob_matches(self.pattern.as_bytes(), base.as_bytes())
+        }
+    }
+}
+
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            rest.is_empty()
+                || (0..=text.len())
+                    .any(|i| (i == 0 || text[i - 1] == b'/') && glob_matches(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob_matches(rest, &text[i..])),
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(c) if *c != b'/') && glob_matches(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_matches(rest, &text[1..]),
+    }
+}
+
+/// `DEFAULT_IGNORES` followed by the rules of `.snaskignore`, if any.
+fn ignore_rules(lib_dir: &Path) -> Vec<IgnoreRule> {
+    let custom = fs::read_to_string(lib_dir.join(".snaskignore")).unwrap_or_default();
+    DEFAULT_IGNORES
+        .iter()
+        .copied()
+        .chain(custom.lines())
+        .filter_map(IgnoreRule::parse)
+        .collect()
+}
+
+fn is_ignored(rules: &[IgnoreRule], rel: &str, is_dir: bool) -> bool {
+    rules.iter().fold(false, |ignored, rule| {
+        if rule.matches(rel, is_dir) {
+            !rule.negated
+        } else {
+            ignored
+        }
+    })
+}
+
+/// Files of the library in `lib_dir` to publish, relative and sorted. Skips
+/// `.git/`, `.snaskignore` itself and whatever the ignore rules exclude;
+/// like git, a file inside an ignored directory cannot be re-included. The
+/// required `<name>.snask`, `package.json` and `README.md` are always kept.
+fn collect_library_files(lib_dir: &Path, name: &str) -> Result<Vec<PathBuf>, String> {
+    let rules = ignore_rules(lib_dir);
+    let required = [
+        format!("{}.snask", name),
+        "package.json".to_string(),
+        "README.md".to_string(),
+    ];
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(rel_dir) = stack.pop() {
+        let dir = lib_dir.join(&rel_dir);
+        for entry in
+            fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let rel = rel_dir.join(entry.file_name());
+            let rel_str = rel.to_string_lossy().replace('\\', "/");
+            let is_dir = entry.path().is_dir();
+            if rel_str == ".git" || rel_str == ".snaskignore" {
+                continue;
+            }
+            let keep = required.contains(&rel_str) || !is_ignored(&rules, &rel_str, is_dir);
+            if !keep {
+                continue;
+            }
+            if is_dir {
+                stack.push(rel);
+            } else {
+                files.push(rel);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Validates the library in `lib_dir` against the registry checkout in
+/// `registry` (which may not exist yet) and returns what publishing it
+/// would write. Reads only.
+pub fn plan_publish(
+    lib_dir: &Path,
+    registry: &Path,
+    opts: &PublishOpts,
+) -> Result<PublishPlan, String> {
     let name = opts.name.trim();
     if name.is_empty() {
         return Err("Nome inválido.".to_string());
     }
-    let (pkg, _readme_md, local_file) = read_required_package_files(name)?;
+    let (pkg, _readme_md, local_file) = read_required_package_files(lib_dir, name)?;
     let version = opts.version.clone().unwrap_or_else(|| pkg.version.clone());
     let description = opts
         .description
         .clone()
         .unwrap_or_else(|| pkg.description.clone());
+    if opts.pr && opts.fork.is_none() {
+        return Err("Modo PR exige `--fork <URL-do-seu-fork>`.".to_string());
+    }
+
+    let src_dir = Path::new("packages_src").join(name).join(&version);
+    let snask_file = format!("{}.snask", name);
+    let bytes = fs::read(&local_file)
+        .map_err(|e| format!("Failed to read {}: {}", local_file.display(), e))?;
+    let sha256 = canonical_sha256(&bytes);
+    // A lib em packages/ e a fonte versionada (sem o que o .snaskignore exclui) em packages_src/
+    let mut files = vec![(local_file, Path::new("packages").join(&snask_file))];
+    for rel in collect_library_files(lib_dir, name)? {
+        files.push((lib_dir.join(&rel), src_dir.join(&rel)));
+    }
+
+    // Metadados do índice (formato simples v1)
+    let index_path = Path::new("index")
+        .join(
+            name.chars()
+                .next()
+                .unwrap()
+                .to_ascii_lowercase()
+                .to_string(),
+        )
+        .join(format!("{}.json", name));
+
+    // Republicar a mesma versão só é permitido com o mesmo conteúdo.
+    let already_published = match published_sha256(registry, name, &version, &index_path) {
+        Some(published) if published == sha256 => true,
+        Some(published) => {
+            return Err(format!(
+                "{} v{} já foi publicado com outro conteúdo (sha256 {} no registry, {} local).\nhelp: aumente a versão em package.json (ou passe outra versão) e publique de novo.",
+                name, version, published, sha256
+            ));
+        }
+        None => false,
+    };
+
+    let index_json = format!(
+        "{{\n  \"version\": \"{version}\",\n  \"url\": \"{url}\",\n  \"description\": \"{desc}\",\n  \"sha256\": \"{sha256}\"\n}}\n",
+        version = version,
+        url = snask_file,
+        desc = description.replace('\"', "\\\""),
+        sha256 = sha256
+    );
+
+    let message = opts
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("pkg: publish {} v{}", name, version));
+    let branch = opts.pr.then(|| {
+        opts.branch
+            .clone()
+            .unwrap_or_else(|| format!("pkg/{}-v{}", name, version))
+    });
+
+    Ok(PublishPlan {
+        name: name.to_string(),
+        version,
+        description,
+        files,
+        index_path,
+        index_json,
+        message,
+        branch,
+        sha256,
+        already_published,
+    })
+}
+
+/// The `--dry-run` report: every file that would be written, the commit
+/// and where it would be pushed.
+pub fn describe_publish(plan: &PublishPlan, registry: &Path, opts: &PublishOpts) -> String {
+    if plan.already_published {
+        return format!(
+            "🔎 Dry run: {} v{} is already in '{}' with the same content; nothing to publish.\n",
+            plan.name,
+            plan.version,
+            registry.display()
+        );
+    }
+    let mut out = format!(
+        "🔎 Dry run: {} v{} is ready to publish to '{}'.\n",
+        plan.name,
+        plan.version,
+        registry.display()
+    );
+    if !registry.join(".git").exists() {
+        out.push_str("ℹ️  The registry is not cloned yet; it would be cloned first.\n");
+    }
+    out.push_str("Files:\n");
+    for (src, dest) in &plan.files {
+        out.push_str(&format!("  {} <- {}\n", dest.display(), src.display()));
+    }
+    out.push_str(&format!("  {} (index entry)\n", plan.index_path.display()));
+    out.push_str("  registry.json (legacy index, updated)\n");
+    out.push_str(&format!("Commit: \"{}\"\n", plan.message));
+    match (&plan.branch, &opts.fork) {
+        (Some(branch), Some(fork)) => {
+            out.push_str(&format!("Push: branch '{}' to fork {}\n", branch, fork))
+        }
+        _ if opts.push => out.push_str("Push: origin main\n"),
+        _ => out.push_str("Push: none (local commit only; pass --push or --pr)\n"),
+    }
+    out.push_str("Nothing was written.\n");
+    out
+}
+
+pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    if opts.dry_run {
+        let repo = registry_dir()?;
+        let plan = plan_publish(&cwd, &repo, &opts)?;
+        print!("{}", describe_publish(&plan, &repo, &opts));
+        return Ok(());
+    }
 
     let repo = ensure_registry_repo()?;
+    let plan = plan_publish(&cwd, &repo, &opts)?;
+    let name = plan.name.as_str();
+    if plan.already_published {
+        println!(
+            "✅ {} v{} já está publicado com o mesmo conteúdo; nada a fazer.",
+            name, plan.version
+        );
+        return Ok(());
+    }
 
     // Evita bagunçar o repo do registry caso esteja "sujo"
     let out = Command::new("git")
@@ -210,56 +543,28 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
     }
 
     // Se for PR, cria uma branch e envia para o fork
-    let target_branch = opts
-        .branch
-        .clone()
-        .unwrap_or_else(|| format!("pkg/{}-v{}", name, version));
-    if opts.pr {
+    if let Some(target_branch) = &plan.branch {
         // Garante que estamos na main antes de criar branch
         run_git(&["checkout", "main"], &repo)?;
-        run_git(&["checkout", "-B", &target_branch], &repo)?;
+        run_git(&["checkout", "-B", target_branch], &repo)?;
     }
 
-    let packages_dir = repo.join("packages");
-    let packages_src = repo.join("packages_src").join(name).join(&version);
-    let index_dir = repo.join("index").join(
-        name.chars()
-            .next()
-            .unwrap()
-            .to_ascii_lowercase()
-            .to_string(),
-    );
-    fs::create_dir_all(&packages_dir)
-        .map_err(|e| format!("Failed to create {}: {}", packages_dir.display(), e))?;
-    fs::create_dir_all(&packages_src)
-        .map_err(|e| format!("Failed to create {}: {}", packages_src.display(), e))?;
-    fs::create_dir_all(&index_dir)
-        .map_err(|e| format!("Failed to create {}: {}", index_dir.display(), e))?;
-
-    // Copia o arquivo da lib para o repo do registry
-    let dest_pkg = packages_dir.join(format!("{}.snask", name));
-    fs::copy(&local_file, &dest_pkg)
-        .map_err(|e| format!("Failed to copy to {}: {}", dest_pkg.display(), e))?;
-
-    // Fonte versionada (com package.json e README.md obrigatórios)
-    fs::copy(&local_file, packages_src.join(format!("{}.snask", name)))
-        .map_err(|e| format!("Failed to copy sources: {}", e))?;
-    fs::copy("package.json", packages_src.join("package.json"))
-        .map_err(|e| format!("Failed to copy package.json: {}", e))?;
-    fs::copy("README.md", packages_src.join("README.md"))
-        .map_err(|e| format!("Failed to copy README.md: {}", e))?;
-
-    // Escreve metadados do índice (formato simples v1)
-    let desc = description.replace('\"', "\\\"");
-    let url = format!("{}.snask", name);
-    let index_path = index_dir.join(format!("{}.json", name));
-    let index_json = format!(
-        "{{\n  \"version\": \"{version}\",\n  \"url\": \"{url}\",\n  \"description\": \"{desc}\"\n}}\n",
-        version = version,
-        url = url,
-        desc = desc
-    );
-    fs::write(&index_path, index_json)
+    // Copia a lib e a fonte versionada (com package.json e README.md) para o repo do registry
+    for (src, dest) in &plan.files {
+        let dest = repo.join(dest);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(src, &dest).map_err(|e| format!("Failed to copy to {}: {}", dest.display(), e))?;
+    }
+
+    let index_path = repo.join(&plan.index_path);
+    if let Some(parent) = index_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&index_path, &plan.index_json)
         .map_err(|e| format!("Failed to write {}: {}", index_path.display(), e))?;
 
     // Compatibilidade: também atualiza registry.json legado, para ferramentas antigas que ainda leem um arquivo único.
@@ -281,9 +586,10 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
         legacy_obj["packages"] = serde_json::json!({});
     }
     legacy_obj["packages"][name] = serde_json::json!({
-        "version": version,
+        "version": plan.version,
         "url": format!("{name}.snask"),
-        "description": description,
+        "description": plan.description,
+        "sha256": plan.sha256,
     });
     let legacy_pretty =
         serde_json::to_string_pretty(&legacy_obj).map_err(|e| e.to_string())? + "\n";
@@ -291,20 +597,17 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
         .map_err(|e| format!("Failed to write {}: {}", legacy_registry_path.display(), e))?;
 
     // Stage + commit
-    run_git(
-        &[
-            "add",
-            dest_pkg.to_string_lossy().as_ref(),
-            index_path.to_string_lossy().as_ref(),
-            packages_src.to_string_lossy().as_ref(),
-            legacy_registry_path.to_string_lossy().as_ref(),
-        ],
-        &repo,
-    )?;
-    let msg = opts
-        .message
-        .unwrap_or_else(|| format!("pkg: publish {} v{}", name, version));
-    run_git(&["commit", "-m", &msg], &repo).map_err(|e| {
+    let mut add_args: Vec<String> = vec!["add".to_string()];
+    add_args.extend(
+        plan.files
+            .iter()
+            .map(|(_, dest)| repo.join(dest).to_string_lossy().to_string()),
+    );
+    add_args.push(index_path.to_string_lossy().to_string());
+    add_args.push(legacy_registry_path.to_string_lossy().to_string());
+    let add_args: Vec<&str> = add_args.iter().map(String::as_str).collect();
+    run_git(&add_args, &repo)?;
+    run_git(&["commit", "-m", &plan.message], &repo).map_err(|e| {
         // se nada mudou, commit falha; dá uma msg melhor
         if e.contains("nothing to commit") {
             "Nada para commitar (pacote/index já estavam iguais).".to_string()
@@ -313,7 +616,7 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
         }
     })?;
 
-    if opts.pr {
+    if let Some(target_branch) = &plan.branch {
         // Usa remote "fork" para push
         let fork_url = opts
             .fork
@@ -323,7 +626,7 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
         // cria/atualiza remote fork
         let _ = run_git(&["remote", "remove", "fork"], &repo);
         run_git(&["remote", "add", "fork", &fork_url], &repo)?;
-        run_git(&["push", "-u", "fork", &target_branch], &repo)?;
+        run_git(&["push", "-u", "fork", target_branch], &repo)?;
 
         // volta para main para não confundir o usuário
         let _ = run_git(&["checkout", "main"], &repo);
@@ -355,3 +658,192 @@ pub fn lib_publish(opts: PublishOpts) -> Result<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        canonical_sha256, describe_publish, lib_init_in, plan_publish, NewLibOpts, PublishOpts,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snask_lib_{}_{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        dir
+    }
+
+    fn publish_opts(name: &str) -> PublishOpts {
+        PublishOpts {
+            name: name.to_string(),
+            version: None,
+            description: None,
+            message: None,
+            push: true,
+            pr: false,
+            fork: None,
+            branch: None,
+            dry_run: true,
+        }
+    }
+
+    /// Every path under `dir` with its contents, to compare before/after.
+    fn snapshot(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut out = Vec::new();
+        let mut stack = vec![dir.to_path_buf()];
+        while let Some(d) = stack.pop() {
+            for entry in fs::read_dir(&d).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let bytes = fs::read(&path).unwrap_or_default();
+                    out.push((path, bytes));
+                }
+            }
+        }
+        out.sort();
+        out
+    }
+
+    fn scaffold_mathx(lib: &Path) {
+        lib_init_in(
+            lib,
+            &NewLibOpts {
+                name: "mathx".to_string(),
+                description: "Math helpers".to_string(),
+                version: "0.2.0".to_string(),
+            },
+        )
+        .expect("library should be scaffolded");
+    }
+
+    #[test]
+    fn dry_run_reports_the_plan_and_writes_nothing() {
+        let lib = temp_dir("dry_run");
+        scaffold_mathx(&lib);
+        let registry = temp_dir("dry_run_registry").join("registry");
+        let before = snapshot(&lib);
+
+        let opts = publish_opts("mathx");
+        let plan = plan_publish(&lib, &registry, &opts).expect("library should validate");
+        let report = describe_publish(&plan, &registry, &opts);
+
+        assert_eq!(snapshot(&lib), before);
+        assert!(!registry.exists());
+        assert_eq!(plan.version, "0.2.0");
+        assert!(
+            report.contains("mathx v0.2.0 is ready to publish"),
+            "{report}"
+        );
+        assert!(report.contains("packages/mathx.snask"), "{report}");
+        assert!(
+            report.contains("packages_src/mathx/0.2.0/README.md"),
+            "{report}"
+        );
+        assert!(report.contains("index/m/mathx.json"), "{report}");
+        assert!(report.contains("Push: origin main"), "{report}");
+
+        fs::remove_file(lib.join("README.md")).unwrap();
+        let err = plan_publish(&lib, &registry, &opts).expect_err("README.md is required");
+        assert!(err.contains("README.md"), "{err}");
+        let _ = fs::remove_dir_all(&lib);
+        let _ = fs::remove_dir_all(registry.parent().unwrap());
+    }
+
+    #[test]
+    fn republishing_a_version_needs_identical_content() {
+        let lib = temp_dir("republish");
+        scaffold_mathx(&lib);
+        let registry = temp_dir("republish_registry");
+        let opts = publish_opts("mathx");
+        let source = fs::read(lib.join("mathx.snask")).unwrap();
+
+        // A new version.
+        let plan = plan_publish(&lib, &registry, &opts).expect("new version should be accepted");
+        assert!(!plan.already_published);
+        assert!(plan.index_json.contains(&plan.sha256));
+
+        // The same version with the same content (CRLF on the registry side).
+        let published = registry.join("packages_src/mathx/0.2.0");
+        fs::create_dir_all(&published).unwrap();
+        let crlf = String::from_utf8_lossy(&source).replace('\n', "\r\n");
+        fs::write(published.join("mathx.snask"), &crlf).unwrap();
+        assert_eq!(canonical_sha256(crlf.as_bytes()), plan.sha256);
+        let plan = plan_publish(&lib, &registry, &opts).expect("identical re-publish is allowed");
+        assert!(plan.already_published);
+        let report = describe_publish(&plan, &registry, &opts);
+        assert!(report.contains("nothing to publish"), "{report}");
+
+        // The same version with different content.
+        fs::write(
+            lib.join("mathx.snask"),
+            "fun hello(name)\n    return name\n",
+        )
+        .unwrap();
+        let err = plan_publish(&lib, &registry, &opts).expect_err("changed content needs a bump");
+        assert!(err.contains("mathx v0.2.0"), "{err}");
+        assert!(err.contains("aumente a versão"), "{err}");
+
+        // The index entry's sha256 wins for the version it describes.
+        fs::remove_dir_all(&published).unwrap();
+        fs::create_dir_all(registry.join("index/m")).unwrap();
+        let sha = canonical_sha256(&fs::read(lib.join("mathx.snask")).unwrap());
+        fs::write(
+            registry.join("index/m/mathx.json"),
+            format!("{{\"version\": \"0.2.0\", \"sha256\": \"{sha}\"}}"),
+        )
+        .unwrap();
+        let plan = plan_publish(&lib, &registry, &opts).unwrap();
+        assert!(plan.already_published);
+        let _ = fs::remove_dir_all(&lib);
+        let _ = fs::remove_dir_all(&registry);
+    }
+
+    #[test]
+    fn snaskignore_and_default_patterns_filter_published_files() {
+        let lib = temp_dir("snaskignore");
+        scaffold_mathx(&lib);
+        for file in [
+            "helpers/util.snask",
+            "dist/mathx",
+            "target/debug/mathx.o",
+            "build.o",
+            "out.ll",
+            "tests/math_test.snask",
+            "debug.log",
+            "keep.log",
+        ] {
+            let path = lib.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "x").unwrap();
+        }
+        fs::write(
+            lib.join(".snaskignore"),
+            "# local only\ntests/\n*.log\n!keep.log\n",
+        )
+        .unwrap();
+
+        let registry = temp_dir("snaskignore_registry");
+        let plan = plan_publish(&lib, &registry, &publish_opts("mathx")).unwrap();
+        let published: Vec<String> = plan
+            .files
+            .iter()
+            .filter_map(|(_, dest)| dest.strip_prefix("packages_src/mathx/0.2.0").ok())
+            .map(|rel| rel.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            published,
+            vec![
+                "README.md",
+                "helpers/util.snask",
+                "keep.log",
+                "mathx.snask",
+                "package.json",
+            ]
+        );
+        let _ = fs::remove_dir_all(&lib);
+        let _ = fs::remove_dir_all(&registry);
+    }
+}
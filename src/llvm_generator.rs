@@ -4,12 +4,17 @@ use crate::ast::{
 use crate::om_contract::{OmContract, OmFunctionContract, OmResourceContract};
 use inkwell::builder::Builder;
 use inkwell::context::Context;
-use inkwell::module::Module;
+use inkwell::debug_info::{
+    AsDIScope, DICompileUnit, DIFlags, DIFlagsConstants, DIScope, DWARFEmissionKind,
+    DWARFSourceLanguage, DebugInfoBuilder,
+};
+use inkwell::module::{FlagBehavior, Module};
 use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, IntType, StructType};
 use inkwell::values::{
     BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue, StructValue,
 };
 use std::collections::HashMap;
+use std::path::Path;
 
 // Snask ABI version — must match SNASK_ABI_VERSION in src/runtime/rt_abi.h
 const SNASK_ABI_VERSION: i32 = 1;
@@ -47,6 +52,12 @@ fn is_library_native(name: &str) -> bool {
         || name.starts_with("sjson_")
         || name.starts_with("snif_")
         || name.starts_with("string_")
+        || name.starts_with("regex_")
+        || name.starts_with("dt_")
+        || name.starts_with("csv_")
+        || name.starts_with("base64_")
+        || name.starts_with("hash_")
+        || name.starts_with("sb_")
 }
 
 fn library_native_help(name: &str) -> String {
@@ -78,6 +89,18 @@ fn library_native_help(name: &str) -> String {
         "snif"
     } else if name.starts_with("string_") {
         "string"
+    } else if name.starts_with("regex_") {
+        "regex"
+    } else if name.starts_with("dt_") {
+        "datetime"
+    } else if name.starts_with("csv_") {
+        "csv"
+    } else if name.starts_with("base64_") {
+        "base64"
+    } else if name.starts_with("hash_") {
+        "hash"
+    } else if name.starts_with("sb_") {
+        "sb"
     } else {
         "a library"
     };
@@ -105,6 +128,12 @@ fn native_symbol_for_module_alias(name: &str) -> Option<String> {
         "sjson" => "sjson_",
         "snif" => "snif_",
         "string" => "string_",
+        "regex" => "regex_",
+        "datetime" => "dt_",
+        "csv" => "csv_",
+        "base64" => "base64_",
+        "hash" => "hash_",
+        "sb" => "sb_",
         "sqlite" => "sqlite_",
         "zlib" => "zlib_",
         "snask_skia" => "skia_",
@@ -135,8 +164,35 @@ pub struct LLVMGenerator<'ctx> {
     active_zone_depth: usize,
     om_contracts: HashMap<String, OmContract>,
     function_return_types: HashMap<String, crate::types::Type>,
+    try_handlers: Vec<inkwell::basic_block::BasicBlock<'ctx>>,
+    asserts_enabled: bool,
+    module_mode: bool,
+    /// One label per instrumented function when coverage is on.
+    coverage_sites: Option<Vec<String>>,
+    module_name: String,
+    /// DWARF function and line info when building with `--debug`.
+    debug_info: Option<DebugInfo<'ctx>>,
+    /// Set once a call to `args()` is generated; only then does `main` hand
+    /// argc/argv to the runtime, which the extreme and nano runtimes lack.
+    uses_args: std::cell::Cell<bool>,
+}
+
+/// State of `--debug` builds: statements are attributed to `scope`, the
+/// subprogram of the function being generated.
+struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    unit: DICompileUnit<'ctx>,
+    scope: Option<DIScope<'ctx>>,
 }
 
+/// Called from `main` in coverage builds to hand the site labels to the
+/// runtime (`snask test --coverage`).
+const COVERAGE_REGISTER_SYMBOL: &str = "__snask_cov_register";
+
+/// Entry symbol used instead of `main` when compiling a module object
+/// (`snask build --no-main`); it runs the top-level statements.
+pub const MODULE_INIT_SYMBOL: &str = "snask_module_init";
+
 impl<'ctx> LLVMGenerator<'ctx> {
     pub fn new(context: &'ctx Context, module_name: &str) -> Self {
         let module = context.create_module(module_name);
@@ -167,6 +223,13 @@ impl<'ctx> LLVMGenerator<'ctx> {
             active_zone_depth: 0,
             om_contracts: HashMap::new(),
             function_return_types: HashMap::new(),
+            try_handlers: Vec::new(),
+            asserts_enabled: true,
+            module_mode: false,
+            coverage_sites: None,
+            module_name: module_name.to_string(),
+            debug_info: None,
+            uses_args: std::cell::Cell::new(false),
         }
     }
 
@@ -182,7 +245,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
             Type::F32 => self.context.f32_type().into(),
             Type::Float | Type::F64 => self.f64_type.into(),
             Type::Bool => self.bool_type.into(),
-            Type::String | Type::Ptr | Type::User(_) => self.ptr_type.into(),
+            Type::String | Type::Ptr => self.ptr_type.into(),
             _ => self.value_type.into(), // Fallback para Any/Complexos
         }
     }
@@ -294,18 +357,45 @@ impl<'ctx> LLVMGenerator<'ctx> {
         match name {
             "is_nil" | "is_str" | "is_obj" => Some(crate::types::Type::Bool),
             "len" | "time" | "s_time" => Some(crate::types::Type::Float),
-            "num_to_str" => Some(crate::types::Type::String),
-            "str_to_num" => Some(crate::types::Type::Float),
+            "random" | "s_random" | "random_range" => Some(crate::types::Type::Float),
+            "time_ns" | "time_ms" => Some(crate::types::Type::Int),
+            "num_to_str" | "string_slice" => Some(crate::types::Type::String),
+            "regex_match" => Some(crate::types::Type::Bool),
+            "regex_find" | "regex_replace" => Some(crate::types::Type::String),
+            "regex_find_all" => Some(crate::types::Type::Any),
+            "dt_now" | "dt_parse" | "dt_add_days" => Some(crate::types::Type::Float),
+            "dt_format" => Some(crate::types::Type::String),
+            "csv_parse" | "csv_parse_delim" => Some(crate::types::Type::List),
+            "csv_write" | "csv_write_delim" => Some(crate::types::Type::String),
+            "base64_encode" | "base64_decode" | "base64_encode_url" | "base64_decode_url" => {
+                Some(crate::types::Type::String)
+            }
+            "hash_sha256_hex" | "hash_sha1_hex" | "hash_md5_hex" => {
+                Some(crate::types::Type::String)
+            }
+            "sb_create" | "sb_to_string" => Some(crate::types::Type::String),
+            "sb_append" | "sb_free" => Some(crate::types::Type::Bool),
+            "str_to_num" | "to_float" => Some(crate::types::Type::Float),
+            "sum" | "mean" | "median" | "stddev" | "mode" => Some(crate::types::Type::Float),
+            "to_int" | "gcd" | "lcm" | "factorial" | "isqrt" => Some(crate::types::Type::Int),
+            "to_str" => Some(crate::types::Type::String),
+            "to_bool" => Some(crate::types::Type::Bool),
+            "snask_test_run" => Some(crate::types::Type::Bool),
             "binfile_size" | "binfile_read_into" => Some(crate::types::Type::Float),
             "os_cwd" | "os_platform" | "os_arch" | "os_getenv" | "os_random_hex" => {
                 Some(crate::types::Type::String)
             }
             "os_setenv" => Some(crate::types::Type::Bool),
-            "sfs_read" => Some(crate::types::Type::String),
+            "sfs_read" | "sfs_open" | "sfs_read_line" => Some(crate::types::Type::String),
             "sfs_count_bytes" | "sfs_size" | "sfs_mtime" => Some(crate::types::Type::Float),
             "sfs_delete" | "sfs_exists" | "sfs_copy" | "sfs_move" | "sfs_mkdir" | "sfs_is_file"
-            | "sfs_is_dir" | "sfs_rmdir" => Some(crate::types::Type::Bool),
+            | "sfs_is_dir" | "sfs_rmdir" | "sfs_write_line" | "sfs_close" => {
+                Some(crate::types::Type::Bool)
+            }
             "sfs_listdir" => Some(crate::types::Type::Any),
+            "args" | "s_args" | "zip" | "enumerate" | "chunk" => Some(crate::types::Type::List),
+            "dict_keys" | "dict_values" | "dict_items" => Some(crate::types::Type::List),
+            "dict_merge" => Some(crate::types::Type::Dict),
             "gui_init" => Some(crate::types::Type::Bool),
             "gui_run" | "gui_quit" => Some(crate::types::Type::Void),
             "gui_window" | "gui_vbox" | "gui_hbox" | "gui_scrolled" | "gui_eventbox"
@@ -999,6 +1089,127 @@ impl<'ctx> LLVMGenerator<'ctx> {
         }
     }
 
+    pub fn set_asserts_enabled(&mut self, enabled: bool) {
+        self.asserts_enabled = enabled;
+    }
+
+    /// Emits `snask_module_init` instead of `main` and never calls
+    /// `main::start`, so the object can be linked into another program.
+    pub fn set_module_mode(&mut self, module: bool) {
+        self.module_mode = module;
+    }
+
+    /// Counts every function entry except `test_*` functions; the labels are
+    /// `file\tline\tname`, where `file` is the module for `mod::fn` and the
+    /// entry file otherwise, and `line` is where the function is declared.
+    pub fn set_coverage(&mut self, enabled: bool) {
+        self.coverage_sites = enabled.then(Vec::new);
+    }
+
+    /// Emits DWARF debug info for functions and statement lines, so `gdb` and
+    /// `lldb` can break on and step through Snask source. Variable locations
+    /// are not described yet.
+    pub fn set_debug_info(&mut self, enabled: bool) {
+        if !enabled {
+            self.debug_info = None;
+            return;
+        }
+        let path = Path::new(&self.module_name);
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.module_name.clone());
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let version = inkwell::debug_info::debug_metadata_version();
+        self.module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            self.i32_type.const_int(version as u64, false),
+        );
+        self.module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            self.i32_type.const_int(4, false),
+        );
+        let (builder, unit) = self.module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &file,
+            &dir.to_string_lossy(),
+            "snask",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        self.debug_info = Some(DebugInfo {
+            builder,
+            unit,
+            scope: None,
+        });
+    }
+
+    /// Gives `function` a subprogram declared at `line` and attributes the
+    /// code emitted next to it. Every function with a body needs one in debug
+    /// builds, or calls would carry a location from another function.
+    fn debug_function(&mut self, function: FunctionValue<'ctx>, name: &str, line: usize) {
+        let Some(debug) = &mut self.debug_info else {
+            return;
+        };
+        let builder = &debug.builder;
+        let file = debug.unit.get_file();
+        let line = line as u32;
+        let ty = builder.create_subroutine_type(file, None, &[], DIFlags::ZERO);
+        let subprogram = builder.create_function(
+            debug.unit.as_debug_info_scope(),
+            name,
+            function.get_name().to_str().ok(),
+            file,
+            line,
+            ty,
+            false,
+            true,
+            line,
+            DIFlags::ZERO,
+            false,
+        );
+        function.set_subprogram(subprogram);
+        let scope = subprogram.as_debug_info_scope();
+        let location = builder.create_debug_location(self.context, line, 0, scope, None);
+        debug.scope = Some(scope);
+        self.builder.set_current_debug_location(location);
+    }
+
+    /// Attributes the code emitted next to `loc` in debug builds.
+    fn set_debug_location(&self, loc: &Location) {
+        let Some(DebugInfo {
+            builder,
+            scope: Some(scope),
+            ..
+        }) = &self.debug_info
+        else {
+            return;
+        };
+        let location = builder.create_debug_location(
+            self.context,
+            loc.line as u32,
+            loc.column as u32,
+            *scope,
+            None,
+        );
+        self.builder.set_current_debug_location(location);
+    }
+
     pub fn set_om_contracts(&mut self, contracts: Vec<OmContract>) {
         self.om_contracts = contracts
             .into_iter()
@@ -1111,6 +1322,9 @@ impl<'ctx> LLVMGenerator<'ctx> {
 
         // Declara funções globais e preenche mapa de classes
         for stmt in &program {
+            if let StmtKind::EnumDeclaration { name, variants } = &stmt.kind {
+                self.declare_enum(name, variants);
+            }
             if let StmtKind::FuncDeclaration(func) = &stmt.kind {
                 self.declare_function(func)?;
             }
@@ -1119,9 +1333,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 for method in &mut c.methods {
                     // Adiciona 'self' como primeiro parâmetro se não existir
                     if !method.params.iter().any(|p| p.0 == "self") {
-                        method
-                            .params
-                            .insert(0, ("self".to_string(), crate::types::Type::Any));
+                        method.params.insert(
+                            0,
+                            ("self".to_string(), crate::types::Type::User(c.name.clone())),
+                        );
                     }
                     let mut m = method.clone();
                     m.name = format!("{}::{}", c.name, m.name);
@@ -1132,12 +1347,34 @@ impl<'ctx> LLVMGenerator<'ctx> {
         }
 
         let i32_type = self.context.i32_type();
-        let main_func = self
-            .module
-            .add_function("main", i32_type.fn_type(&[], false), None);
+        let entry_name = if self.module_mode {
+            MODULE_INIT_SYMBOL
+        } else {
+            "main"
+        };
+        // Only the program entry receives argc/argv; module initializers take nothing.
+        let main_type = if self.module_mode {
+            i32_type.fn_type(&[], false)
+        } else {
+            i32_type.fn_type(&[i32_type.into(), self.ptr_type.into()], false)
+        };
+        let main_func = self.module.add_function(entry_name, main_type, None);
         let entry = self.context.append_basic_block(main_func, "entry");
         self.builder.position_at_end(entry);
         self.current_func = Some(main_func);
+        self.debug_function(main_func, entry_name, 0);
+
+        let coverage_register = self.coverage_sites.is_some().then(|| {
+            let register = self.module.add_function(
+                COVERAGE_REGISTER_SYMBOL,
+                self.context.void_type().fn_type(&[], false),
+                None,
+            );
+            self.builder
+                .build_call(register, &[], "cov_register")
+                .unwrap();
+            register
+        });
 
         // ABI version check — aborta se runtime e compiler não combinam
         if let Some(abi_check) = self.module.get_function("s_check_abi") {
@@ -1216,60 +1453,63 @@ impl<'ctx> LLVMGenerator<'ctx> {
 
         // Se houver uma class main, chama o ponto de entrada (prioriza 'start' ou o primeiro método se start não existir)
         let mut entry_point_found = false;
-        for stmt in &program {
-            if let StmtKind::ClassDeclaration(class) = &stmt.kind {
-                if class.name == "main" && !class.methods.is_empty() {
-                    // Busca 'start' ou pega o primeiro método
-                    let method = class
-                        .methods
-                        .iter()
-                        .find(|m| m.name == "start")
-                        .or_else(|| class.methods.get(0))
-                        .unwrap();
+        if !self.module_mode {
+            for stmt in &program {
+                if let StmtKind::ClassDeclaration(class) = &stmt.kind {
+                    if class.name == "main" && !class.methods.is_empty() {
+                        // Busca 'start' ou pega o primeiro método
+                        let method = class
+                            .methods
+                            .iter()
+                            .find(|m| m.name == "start")
+                            .or_else(|| class.methods.get(0))
+                            .unwrap();
 
-                    let f_name = format!("main::{}", method.name);
-                    if let Some(f) = self.functions.get(&f_name) {
-                        let mut l_args = Vec::new();
-                        let r_a = self.create_entry_block_alloca(self.value_type, "ra");
-                        l_args.push(r_a.into());
-                        let self_a = self.create_entry_block_alloca(self.value_type, "entry_self");
-                        let mut self_v = self.value_type.get_undef();
-                        self_v = self
-                            .builder
-                            .build_insert_value(
-                                self_v,
-                                self.context.f64_type().const_float(TYPE_NIL as f64),
-                                0,
-                                "entry_self_tag",
-                            )
-                            .unwrap()
-                            .into_struct_value();
-                        self_v = self
-                            .builder
-                            .build_insert_value(
-                                self_v,
-                                self.context.f64_type().const_float(0.0),
-                                1,
-                                "entry_self_num",
-                            )
-                            .unwrap()
-                            .into_struct_value();
-                        self_v = self
-                            .builder
-                            .build_insert_value(
-                                self_v,
-                                self.ptr_type.const_null(),
-                                2,
-                                "entry_self_ptr",
-                            )
-                            .unwrap()
-                            .into_struct_value();
-                        self.builder.build_store(self_a, self_v).unwrap();
-                        l_args.push(self_a.into());
-                        self.builder.build_call(*f, &l_args, "call_entry").unwrap();
-                        entry_point_found = true;
+                        let f_name = format!("main::{}", method.name);
+                        if let Some(f) = self.functions.get(&f_name) {
+                            let mut l_args = Vec::new();
+                            let r_a = self.create_entry_block_alloca(self.value_type, "ra");
+                            l_args.push(r_a.into());
+                            let self_a =
+                                self.create_entry_block_alloca(self.value_type, "entry_self");
+                            let mut self_v = self.value_type.get_undef();
+                            self_v = self
+                                .builder
+                                .build_insert_value(
+                                    self_v,
+                                    self.context.f64_type().const_float(TYPE_NIL as f64),
+                                    0,
+                                    "entry_self_tag",
+                                )
+                                .unwrap()
+                                .into_struct_value();
+                            self_v = self
+                                .builder
+                                .build_insert_value(
+                                    self_v,
+                                    self.context.f64_type().const_float(0.0),
+                                    1,
+                                    "entry_self_num",
+                                )
+                                .unwrap()
+                                .into_struct_value();
+                            self_v = self
+                                .builder
+                                .build_insert_value(
+                                    self_v,
+                                    self.ptr_type.const_null(),
+                                    2,
+                                    "entry_self_ptr",
+                                )
+                                .unwrap()
+                                .into_struct_value();
+                            self.builder.build_store(self_a, self_v).unwrap();
+                            l_args.push(self_a.into());
+                            self.builder.build_call(*f, &l_args, "call_entry").unwrap();
+                            entry_point_found = true;
+                        }
+                        break;
                     }
-                    break;
                 }
             }
         }
@@ -1290,17 +1530,49 @@ impl<'ctx> LLVMGenerator<'ctx> {
         // Gera o corpo das funções
         for stmt in program {
             if let StmtKind::FuncDeclaration(func) = &stmt.kind {
-                self.generate_function_body(func.clone())?;
+                self.generate_function_body(func.clone(), stmt.loc.line)?;
             }
             if let StmtKind::ClassDeclaration(class) = &stmt.kind {
                 // Pega a versão atualizada da classe (com o self injetado)
                 let c = self.classes.get(&class.name).unwrap().clone();
                 for mut method in c.methods {
                     method.name = format!("{}::{}", c.name, method.name);
-                    self.generate_function_body(method)?;
+                    self.generate_function_body(method, stmt.loc.line)?;
+                }
+            }
+        }
+        if let Some(register) = coverage_register {
+            self.generate_coverage_register(register);
+        }
+        if self.uses_args.get() {
+            if let (Some(set_args), Some(argc), Some(argv)) = (
+                self.module.get_function("s_set_args"),
+                main_func.get_nth_param(0),
+                main_func.get_nth_param(1),
+            ) {
+                match entry.get_first_instruction() {
+                    Some(first) => self.builder.position_before(&first),
+                    None => self.builder.position_at_end(entry),
+                }
+                // The builder still carries the last function body's location.
+                if let (Some(debug), Some(subprogram)) =
+                    (&self.debug_info, main_func.get_subprogram())
+                {
+                    let scope = subprogram.as_debug_info_scope();
+                    let location =
+                        debug
+                            .builder
+                            .create_debug_location(self.context, 0, 0, scope, None);
+                    self.builder.set_current_debug_location(location);
                 }
+                self.builder
+                    .build_call(set_args, &[argc.into(), argv.into()], "set_args")
+                    .unwrap();
             }
         }
+        if let Some(debug) = &self.debug_info {
+            debug.builder.finalize();
+        }
         Ok(self.module.print_to_string().to_string())
     }
 
@@ -1315,6 +1587,16 @@ impl<'ctx> LLVMGenerator<'ctx> {
             None,
         );
 
+        // argc/argv do processo, guardados para `args()`
+        self.module.add_function(
+            "s_set_args",
+            void_type.fn_type(
+                &[self.context.i32_type().into(), self.ptr_type.into()],
+                false,
+            ),
+            None,
+        );
+
         // s_write — única função C que o runtime Snask precisa (write syscall)
         let i64_type = self.context.i64_type();
         self.module.add_function(
@@ -1469,6 +1751,21 @@ impl<'ctx> LLVMGenerator<'ctx> {
         let f_concat = self.module.add_function("s_concat", fn_2, None);
         self.functions.insert("s_concat".to_string(), f_concat);
         self.functions.insert("concat".to_string(), f_concat);
+        self.functions.insert(
+            "s_concat_n".to_string(),
+            self.module.add_function(
+                "s_concat_n",
+                void_type.fn_type(
+                    &[
+                        self.ptr_type.into(),
+                        self.ptr_type.into(),
+                        self.i32_type.into(),
+                    ],
+                    false,
+                ),
+                None,
+            ),
+        );
 
         let f_abs = self.module.add_function("s_abs", fn_1, None);
         self.functions.insert("s_abs".to_string(), f_abs);
@@ -1546,12 +1843,39 @@ impl<'ctx> LLVMGenerator<'ctx> {
         self.functions.insert("s_time".to_string(), f_time);
         self.functions.insert("time".to_string(), f_time);
 
+        let f_random = self.module.add_function(
+            "s_random",
+            void_type.fn_type(&[self.ptr_type.into()], false),
+            None,
+        );
+        self.functions.insert("s_random".to_string(), f_random);
+        self.functions.insert("random".to_string(), f_random);
+
         let f_sleep = self.module.add_function("s_sleep", fn_1, None);
         self.functions.insert("s_sleep".to_string(), f_sleep);
         self.functions.insert("sleep".to_string(), f_sleep);
+        let f_sleep_ms = self.module.add_function("s_sleep_ms", fn_1, None);
+        self.functions.insert("s_sleep_ms".to_string(), f_sleep_ms);
+        self.functions.insert("sleep_ms".to_string(), f_sleep_ms);
+        for name in ["time_ns", "time_ms"] {
+            let symbol = format!("s_{}", name);
+            let f = self.module.add_function(
+                &symbol,
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            );
+            self.functions.insert(symbol, f);
+            self.functions.insert(name.to_string(), f);
+        }
 
         let f_exit = self.module.add_function("s_exit", fn_1, None);
         self.functions.insert("exit".to_string(), f_exit);
+        let f_args = self.module.add_function(
+            "s_args",
+            void_type.fn_type(&[self.ptr_type.into()], false),
+            None,
+        );
+        self.functions.insert("args".to_string(), f_args);
         self.functions.insert(
             "s_system".to_string(),
             self.module.add_function("s_system", fn_1, None),
@@ -1617,6 +1941,22 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "sfs_rmdir".to_string(),
             self.module.add_function("sfs_rmdir", fn_1, None),
         );
+        self.functions.insert(
+            "sfs_open".to_string(),
+            self.module.add_function("sfs_open", fn_2, None),
+        );
+        self.functions.insert(
+            "sfs_read_line".to_string(),
+            self.module.add_function("sfs_read_line", fn_1, None),
+        );
+        self.functions.insert(
+            "sfs_write_line".to_string(),
+            self.module.add_function("sfs_write_line", fn_2, None),
+        );
+        self.functions.insert(
+            "sfs_close".to_string(),
+            self.module.add_function("sfs_close", fn_1, None),
+        );
 
         self.functions.insert(
             "path_basename".to_string(),
@@ -2214,6 +2554,36 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "calc_eval".to_string(),
             self.module.add_function("calc_eval", fn_1, None),
         );
+        // Runtime symbols carry the `s_` prefix so they cannot clash with libc.
+        for (name, ty) in [
+            ("to_int", fn_1),
+            ("to_float", fn_1),
+            ("to_str", fn_1),
+            ("to_bool", fn_1),
+            ("gcd", fn_2),
+            ("lcm", fn_2),
+            ("factorial", fn_1),
+            ("isqrt", fn_1),
+            ("random_range", fn_2),
+            ("random_seed", fn_1),
+            ("sum", fn_1),
+            ("mean", fn_1),
+            ("median", fn_1),
+            ("stddev", fn_1),
+            ("mode", fn_1),
+            ("zip", fn_2),
+            ("enumerate", fn_1),
+            ("chunk", fn_2),
+            ("dict_keys", fn_1),
+            ("dict_values", fn_1),
+            ("dict_items", fn_1),
+            ("dict_merge", fn_2),
+        ] {
+            let symbol = format!("s_{}", name);
+            let f = self.module.add_function(&symbol, ty, None);
+            self.functions.insert(symbol, f);
+            self.functions.insert(name.to_string(), f);
+        }
 
         // SQLite (optional at link time)
         self.functions.insert(
@@ -2332,6 +2702,92 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "string_substring".to_string(),
             self.module.add_function("string_substring", fn_3, None),
         );
+        self.functions.insert(
+            "string_slice".to_string(),
+            self.module.add_function("string_slice", fn_4, None),
+        );
+        self.functions.insert(
+            "snask_slice".to_string(),
+            self.module.add_function("snask_slice", fn_4, None),
+        );
+        self.functions.insert(
+            "regex_match".to_string(),
+            self.module.add_function("regex_match", fn_2, None),
+        );
+        self.functions.insert(
+            "regex_find".to_string(),
+            self.module.add_function("regex_find", fn_2, None),
+        );
+        self.functions.insert(
+            "regex_find_all".to_string(),
+            self.module.add_function("regex_find_all", fn_2, None),
+        );
+        self.functions.insert(
+            "regex_replace".to_string(),
+            self.module.add_function("regex_replace", fn_3, None),
+        );
+        self.functions.insert(
+            "dt_now".to_string(),
+            self.module.add_function(
+                "dt_now",
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "dt_format".to_string(),
+            self.module.add_function("dt_format", fn_2, None),
+        );
+        self.functions.insert(
+            "dt_parse".to_string(),
+            self.module.add_function("dt_parse", fn_2, None),
+        );
+        self.functions.insert(
+            "dt_add_days".to_string(),
+            self.module.add_function("dt_add_days", fn_2, None),
+        );
+        self.functions.insert(
+            "csv_parse".to_string(),
+            self.module.add_function("csv_parse", fn_1, None),
+        );
+        self.functions.insert(
+            "csv_parse_delim".to_string(),
+            self.module.add_function("csv_parse_delim", fn_2, None),
+        );
+        self.functions.insert(
+            "csv_write".to_string(),
+            self.module.add_function("csv_write", fn_1, None),
+        );
+        self.functions.insert(
+            "csv_write_delim".to_string(),
+            self.module.add_function("csv_write_delim", fn_2, None),
+        );
+        for name in [
+            "base64_encode",
+            "base64_decode",
+            "base64_encode_url",
+            "base64_decode_url",
+            "hash_sha256_hex",
+            "hash_sha1_hex",
+            "hash_md5_hex",
+            "sb_to_string",
+            "sb_free",
+        ] {
+            self.functions
+                .insert(name.to_string(), self.module.add_function(name, fn_1, None));
+        }
+        self.functions.insert(
+            "sb_create".to_string(),
+            self.module.add_function(
+                "sb_create",
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "sb_append".to_string(),
+            self.module.add_function("sb_append", fn_2, None),
+        );
         self.functions.insert(
             "string_format".to_string(),
             self.module.add_function(
@@ -2457,6 +2913,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
             self.module
                 .add_function("json_stringify_pretty", fn_1, None),
         );
+        self.functions.insert(
+            "json_stringify_opts".to_string(),
+            self.module.add_function("json_stringify_opts", fn_3, None),
+        );
         self.functions.insert(
             "json_parse".to_string(),
             self.module.add_function("json_parse", fn_1, None),
@@ -2465,6 +2925,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "json_get".to_string(),
             self.module.add_function("json_get", fn_2, None),
         );
+        self.functions.insert(
+            "snask_index_get".to_string(),
+            self.module.add_function("snask_index_get", fn_2, None),
+        );
         self.functions.insert(
             "snask_iter_get".to_string(),
             self.module.add_function("snask_iter_get", fn_2, None),
@@ -2473,6 +2937,27 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "json_set".to_string(),
             self.module.add_function("json_set", fn_3, None),
         );
+        self.functions.insert(
+            "snask_index_set".to_string(),
+            self.module.add_function(
+                "snask_index_set",
+                void_type.fn_type(
+                    &[
+                        self.ptr_type.into(),
+                        self.ptr_type.into(),
+                        self.ptr_type.into(),
+                        self.ptr_type.into(),
+                        self.i32_type.into(),
+                    ],
+                    false,
+                ),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_in".to_string(),
+            self.module.add_function("snask_in", fn_2, None),
+        );
         self.functions.insert(
             "json_keys".to_string(),
             self.module.add_function("json_keys", fn_1, None),
@@ -2604,6 +3089,88 @@ impl<'ctx> LLVMGenerator<'ctx> {
             self.module
                 .add_function("s_arena_reset", void_type.fn_type(&[], false), None),
         );
+        self.functions.insert(
+            "snask_error_pending".to_string(),
+            self.module.add_function(
+                "snask_error_pending",
+                self.i32_type.fn_type(&[], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_error_clear".to_string(),
+            self.module
+                .add_function("snask_error_clear", void_type.fn_type(&[], false), None),
+        );
+        self.functions.insert(
+            "snask_assert_fail".to_string(),
+            self.module.add_function(
+                "snask_assert_fail",
+                void_type.fn_type(&[self.ptr_type.into(), self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_set_location".to_string(),
+            self.module.add_function(
+                "snask_set_location",
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_division_by_zero".to_string(),
+            self.module.add_function(
+                "snask_division_by_zero",
+                void_type.fn_type(&[self.i32_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_no_method".to_string(),
+            self.module.add_function(
+                "snask_no_method",
+                void_type.fn_type(&[self.i32_type.into(), self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_error_take".to_string(),
+            self.module.add_function(
+                "snask_error_take",
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            ),
+        );
+        // `snask test` driver and `--coverage` counters (see test_runner.rs)
+        self.functions.insert(
+            "snask_cov_init".to_string(),
+            self.module.add_function(
+                "snask_cov_init",
+                void_type.fn_type(&[self.ptr_type.into(), self.i32_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_cov_hit".to_string(),
+            self.module.add_function(
+                "snask_cov_hit",
+                void_type.fn_type(&[self.i32_type.into()], false),
+                None,
+            ),
+        );
+        self.functions.insert(
+            "snask_test_run".to_string(),
+            self.module.add_function("snask_test_run", fn_1, None),
+        );
+        self.functions.insert(
+            "snask_test_summary".to_string(),
+            self.module.add_function(
+                "snask_test_summary",
+                void_type.fn_type(&[self.ptr_type.into()], false),
+                None,
+            ),
+        );
         self.functions.insert(
             "s_zone_enter".to_string(),
             self.module.add_function(
@@ -2677,6 +3244,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "sfs_size",
             "sfs_mtime",
             "sfs_rmdir",
+            "sfs_open",
+            "sfs_read_line",
+            "sfs_write_line",
+            "sfs_close",
             "path_basename",
             "path_dirname",
             "path_extname",
@@ -2791,6 +3362,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
             // JSON / SNIF
             "json_stringify",
             "json_stringify_pretty",
+            "json_stringify_opts",
             "json_parse",
             "json_get",
             "json_has",
@@ -2821,6 +3393,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "string_ends_with",
             "string_chars",
             "string_substring",
+            "string_slice",
             "string_format",
             "string_index_of",
             "string_last_index_of",
@@ -2841,6 +3414,35 @@ impl<'ctx> LLVMGenerator<'ctx> {
             "string_from_char_code",
             "string_to_char_code",
             "string_reverse",
+            // Regex
+            "regex_match",
+            "regex_find",
+            "regex_find_all",
+            "regex_replace",
+            // Datetime
+            "dt_now",
+            "dt_format",
+            "dt_parse",
+            "dt_add_days",
+            // CSV
+            "csv_parse",
+            "csv_parse_delim",
+            "csv_write",
+            "csv_write_delim",
+            // Base64
+            "base64_encode",
+            "base64_decode",
+            "base64_encode_url",
+            "base64_decode_url",
+            // Hash
+            "hash_sha256_hex",
+            "hash_sha1_hex",
+            "hash_md5_hex",
+            // String builder
+            "sb_create",
+            "sb_append",
+            "sb_to_string",
+            "sb_free",
         ] {
             if let Some(f) = self.module.get_function(n) {
                 let alias = format!("__{}", n);
@@ -2855,19 +3457,106 @@ impl<'ctx> LLVMGenerator<'ctx> {
         name.replace("::", "_NS_")
     }
 
-    fn expr_path(expr: &Expr) -> Option<Vec<String>> {
+    /// Operands of a left-nested `+` chain: `a + b + c` gives `[a, b, c]`.
+    fn add_chain<'e>(left: &'e Expr, right: &'e Expr) -> Vec<&'e Expr> {
+        let mut operands = vec![right];
+        let mut next = left;
+        while let ExprKind::Binary {
+            op: BinaryOp::Add,
+            left,
+            right,
+        } = &next.kind
+        {
+            operands.push(right);
+            next = left;
+        }
+        operands.push(next);
+        operands.reverse();
+        operands
+    }
+
+    /// Whether `expr` is known to evaluate to a `str` before generating it:
+    /// string literals and variables declared as one.
+    fn is_string_expr(&self, expr: &Expr) -> bool {
         match &expr.kind {
-            ExprKind::Variable(name) => Some(vec![name.clone()]),
-            ExprKind::PropertyAccess { target, property } => {
-                let mut parts = Self::expr_path(target)?;
-                parts.push(property.clone());
-                Some(parts)
+            ExprKind::Literal(LiteralValue::String(_)) => true,
+            ExprKind::Variable(name) => {
+                let var = self
+                    .local_vars
+                    .get(name)
+                    .or_else(|| self.variables.get(name));
+                matches!(var, Some((_, crate::types::Type::String)))
             }
-            _ => None,
+            _ => false,
         }
     }
 
-    fn om_resource_for_surface(
+    /// A chain of `+` that starts with a string is concatenation all the way,
+    /// so it becomes a single `s_concat_n` call that allocates the result
+    /// once instead of once per `+`.
+    fn build_concat_chain(
+        &self,
+        operands: &[&Expr],
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let parts_type = self.value_type.array_type(operands.len() as u32);
+        let parts = self.create_entry_block_alloca(parts_type, "concat_parts");
+        for (i, operand) in operands.iter().enumerate() {
+            let (v, ty) = self.evaluate_expression((*operand).clone())?;
+            let boxed = self.box_value(v, ty);
+            let index = self.i32_type.const_int(i as u64, false);
+            let slot = unsafe {
+                self.builder
+                    .build_in_bounds_gep(
+                        parts_type,
+                        parts,
+                        &[self.i32_type.const_zero(), index],
+                        "concat_part",
+                    )
+                    .unwrap()
+            };
+            self.builder.build_store(slot, boxed).unwrap();
+        }
+        let concat_fn = self
+            .functions
+            .get("s_concat_n")
+            .ok_or("s_concat_n not declared")?;
+        let count = self.i32_type.const_int(operands.len() as u64, false);
+        let out_p = self.create_entry_block_alloca(self.value_type, "concat_out");
+        self.builder
+            .build_call(
+                *concat_fn,
+                &[out_p.into(), parts.into(), count.into()],
+                "concat",
+            )
+            .unwrap();
+        let res_v = self
+            .builder
+            .build_load(self.value_type, out_p, "concat_res")
+            .unwrap()
+            .into_struct_value();
+        Ok((
+            self.unbox_value(res_v, crate::types::Type::String),
+            crate::types::Type::String,
+        ))
+    }
+
+    fn expr_path(expr: &Expr) -> Option<Vec<String>> {
+        match &expr.kind {
+            ExprKind::Variable(name) => Some(vec![name.clone()]),
+            ExprKind::PropertyAccess {
+                target,
+                property,
+                optional: false,
+            } => {
+                let mut parts = Self::expr_path(target)?;
+                parts.push(property.clone());
+                Some(parts)
+            }
+            _ => None,
+        }
+    }
+
+    fn om_resource_for_surface(
         &self,
         library: &str,
         surface_type: &str,
@@ -3131,9 +3820,13 @@ impl<'ctx> LLVMGenerator<'ctx> {
 
         if c_type.contains('*') {
             let ptr = match value_ty {
-                crate::types::Type::String
-                | crate::types::Type::Ptr
-                | crate::types::Type::User(_) => value.into_pointer_value(),
+                crate::types::Type::String | crate::types::Type::Ptr => value.into_pointer_value(),
+                // Objects stay boxed; C gets the object itself.
+                crate::types::Type::User(_) => self
+                    .builder
+                    .build_extract_value(value.into_struct_value(), PTR_IDX, "obj_ptr")
+                    .unwrap()
+                    .into_pointer_value(),
                 crate::types::Type::Any => {
                     let value_ptr = self.create_entry_block_alloca(self.value_type, "om_res_arg");
                     self.builder
@@ -3360,6 +4053,184 @@ impl<'ctx> LLVMGenerator<'ctx> {
         }
     }
 
+    /// `assert(cond[, message])`: a false condition makes the runtime print the
+    /// message with the call's location and exit with status 1. The message is
+    /// only evaluated on failure; `--no-assert` emits nothing at all.
+    fn emit_assert(
+        &self,
+        args: &[Expr],
+        loc: &Location,
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let nil = (self.ptr_type.const_null().into(), crate::types::Type::Void);
+        if !self.asserts_enabled {
+            return Ok(nil);
+        }
+        let cond = args.first().ok_or("assert expects a condition.")?;
+        let (cond_val, cond_ty) = self.evaluate_expression(cond.clone())?;
+        let is_true = match cond_ty {
+            crate::types::Type::Bool => cond_val.into_int_value(),
+            _ => {
+                let boxed = self.box_value(cond_val, cond_ty);
+                let n = self
+                    .builder
+                    .build_extract_value(boxed, NUM_IDX, "an")
+                    .unwrap()
+                    .into_float_value();
+                self.builder
+                    .build_float_compare(
+                        inkwell::FloatPredicate::ONE,
+                        n,
+                        self.f64_type.const_float(0.0),
+                        "atrue",
+                    )
+                    .unwrap()
+            }
+        };
+
+        let parent = self.current_func.unwrap();
+        let fail_bb = self.context.append_basic_block(parent, "assert_fail");
+        let ok_bb = self.context.append_basic_block(parent, "assert_ok");
+        self.builder
+            .build_conditional_branch(is_true, ok_bb, fail_bb)
+            .unwrap();
+
+        self.builder.position_at_end(fail_bb);
+        let msg = match args.get(1) {
+            Some(m) => {
+                let (v, ty) = self.evaluate_expression(m.clone())?;
+                self.box_value(v, ty)
+            }
+            None => self.value_type.const_zero(),
+        };
+        let msg_p = self.create_entry_block_alloca(self.value_type, "assert_msg");
+        self.builder.build_store(msg_p, msg).unwrap();
+        let location = format!(
+            "{}:{}:{}",
+            self.module.get_name().to_string_lossy(),
+            loc.line,
+            loc.column
+        );
+        let location_p = self
+            .builder
+            .build_global_string_ptr(&location, "assert_loc")
+            .unwrap();
+        let fail_fn = self
+            .functions
+            .get("snask_assert_fail")
+            .ok_or("snask_assert_fail not declared")?;
+        self.builder
+            .build_call(
+                *fail_fn,
+                &[msg_p.into(), location_p.as_pointer_value().into()],
+                "assert_fail",
+            )
+            .unwrap();
+        self.builder.build_unreachable().unwrap();
+
+        self.builder.position_at_end(ok_bb);
+        Ok(nil)
+    }
+
+    /// Records `loc` as the operation a runtime panic should point at. Each
+    /// site gets its own "file:line:col" constant; together they form the
+    /// line table the runtime reports from.
+    fn emit_set_location(&self, loc: &Location) {
+        let Some(set_fn) = self.functions.get("snask_set_location") else {
+            return;
+        };
+        let location = format!(
+            "{}:{}:{}",
+            self.module.get_name().to_string_lossy(),
+            loc.line,
+            loc.column
+        );
+        let location_p = self
+            .builder
+            .build_global_string_ptr(&location, "snask_loc")
+            .unwrap();
+        self.builder
+            .build_call(*set_fn, &[location_p.as_pointer_value().into()], "set_loc")
+            .unwrap();
+    }
+
+    /// Inside a `try` body, branches to the innermost handler when the call
+    /// that was just emitted left a pending runtime error.
+    fn emit_try_check(&self) {
+        let Some(&catch_bb) = self.try_handlers.last() else {
+            return;
+        };
+        let Some(pending_fn) = self.functions.get("snask_error_pending") else {
+            return;
+        };
+        let pending = self
+            .builder
+            .build_call(*pending_fn, &[], "err_pending")
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+        let failed = self
+            .builder
+            .build_int_compare(
+                inkwell::IntPredicate::NE,
+                pending,
+                self.i32_type.const_zero(),
+                "err_failed",
+            )
+            .unwrap();
+        let cont_bb = self
+            .context
+            .append_basic_block(self.current_func.unwrap(), "try_cont");
+        self.builder
+            .build_conditional_branch(failed, catch_bb, cont_bb)
+            .unwrap();
+        self.builder.position_at_end(cont_bb);
+    }
+
+    /// Guards `/`, `//` and `%`: when `is_zero` holds, reports a division by
+    /// zero instead of dividing. Inside a `try` that jumps to the handler;
+    /// elsewhere the runtime panics at `loc`.
+    fn emit_zero_divisor_check(&self, is_zero: IntValue<'ctx>, loc: &Location) {
+        let func = self.current_func.unwrap();
+        let zero_bb = self.context.append_basic_block(func, "div_zero");
+        let ok_bb = self.context.append_basic_block(func, "div_ok");
+        self.builder
+            .build_conditional_branch(is_zero, zero_bb, ok_bb)
+            .unwrap();
+
+        self.builder.position_at_end(zero_bb);
+        self.emit_runtime_failure("snask_division_by_zero", &[], loc);
+
+        self.builder.position_at_end(ok_bb);
+    }
+
+    /// Ends the current block with a failure at `loc`: `fail_fn(recoverable,
+    /// extra...)` records a catchable error and jumps to the handler inside a
+    /// `try`, and panics otherwise.
+    fn emit_runtime_failure(
+        &self,
+        fail_fn: &str,
+        extra: &[BasicMetadataValueEnum<'ctx>],
+        loc: &Location,
+    ) {
+        self.emit_set_location(loc);
+        let catch_bb = self.try_handlers.last().copied();
+        let recoverable = self
+            .i32_type
+            .const_int(u64::from(catch_bb.is_some()), false);
+        let mut fail_args: Vec<BasicMetadataValueEnum> = vec![recoverable.into()];
+        fail_args.extend_from_slice(extra);
+        let fail_fn = self.functions.get(fail_fn).unwrap();
+        self.builder
+            .build_call(*fail_fn, &fail_args, "runtime_failure")
+            .unwrap();
+        match catch_bb {
+            Some(catch_bb) => self.builder.build_unconditional_branch(catch_bb).unwrap(),
+            None => self.builder.build_unreachable().unwrap(),
+        };
+    }
+
     fn emit_active_zone_cleanups(&self) {
         for _ in 0..self.active_zone_depth {
             self.emit_zone_leave();
@@ -3387,21 +4258,378 @@ impl<'ctx> LLVMGenerator<'ctx> {
         Ok(properties)
     }
 
+    /// The `init` method that constructs `class_name`, its own or the closest
+    /// parent's, as declared in `self.functions`.
+    fn constructor_for(&self, class_name: &str) -> Option<String> {
+        self.method_for(class_name, "init")
+    }
+
+    /// Whether `child` is `parent` or inherits from it.
+    fn is_subclass_of(&self, child: &str, parent: &str) -> bool {
+        let mut visited: Vec<&str> = Vec::new();
+        let mut current = Some(child);
+        while let Some(name) = current {
+            if name == parent {
+                return true;
+            }
+            if visited.contains(&name) {
+                return false;
+            }
+            visited.push(name);
+            current = self.classes.get(name).and_then(|c| c.parent.as_deref());
+        }
+        false
+    }
+
+    /// The method `name` of `class_name`, its own or the closest parent's, as
+    /// declared in `self.functions`.
+    fn method_for(&self, class_name: &str, name: &str) -> Option<String> {
+        let mut current = self.classes.get(class_name)?;
+        let mut visited = vec![current.name.clone()];
+        loop {
+            if current.methods.iter().any(|m| m.name == name) {
+                return Some(format!("{}::{}", current.name, name));
+            }
+            let parent = self.classes.get(current.parent.as_deref()?)?;
+            if visited.contains(&parent.name) {
+                return None;
+            }
+            visited.push(parent.name.clone());
+            current = parent;
+        }
+    }
+
+    /// `new Class(args)`: allocates the object, stores every property's
+    /// initial value, then calls `init(self, args)` when the class has one.
+    fn emit_new(
+        &self,
+        class: String,
+        args: &[Expr],
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let out_p = self.emit_object(&class, &[])?;
+
+        if let Some(init_name) = self.constructor_for(&class) {
+            let init = *self.functions.get(&init_name).unwrap();
+            let r_a = self.create_entry_block_alloca(self.value_type, "init_ret");
+            let mut l_args: Vec<BasicMetadataValueEnum> = vec![r_a.into(), out_p.into()];
+            for arg in args {
+                let (v, ty) = self.evaluate_expression(arg.clone())?;
+                let boxed = self.box_value(v, ty);
+                let arg_a = self.create_entry_block_alloca(self.value_type, "init_arg");
+                self.builder.build_store(arg_a, boxed).unwrap();
+                l_args.push(arg_a.into());
+            }
+            self.builder.build_call(init, &l_args, "init").unwrap();
+            self.emit_try_check();
+        }
+
+        let res_v = self
+            .builder
+            .build_load(self.value_type, out_p, "obj")
+            .unwrap()
+            .into_struct_value();
+        Ok((res_v.into(), crate::types::Type::User(class)))
+    }
+
+    /// A new `class` object. Properties named in `fields` take the given
+    /// values, evaluated in source order before the allocation; the rest
+    /// take their declared initial values.
+    fn emit_object(
+        &self,
+        class: &str,
+        fields: &[(String, Expr)],
+    ) -> Result<PointerValue<'ctx>, String> {
+        let out_p = self.create_entry_block_alloca(self.value_type, "alloc_out");
+        let fn_alloc = self.functions.get("s_alloc_obj").unwrap();
+        let properties = self.collect_class_properties(class)?;
+        let mut given = Vec::new();
+        for (name, value) in fields {
+            let (val, val_ty) = self.evaluate_expression(value.clone())?;
+            given.push((name, self.box_value(val, val_ty)));
+        }
+
+        let size_boxed = self.box_value(
+            self.f64_type.const_float(properties.len() as f64).into(),
+            crate::types::Type::Float,
+        );
+        let size_p = self.create_entry_block_alloca(self.value_type, "szp");
+        self.builder.build_store(size_p, size_boxed).unwrap();
+
+        let names_arg = self.build_class_names_arg(class, &properties)?;
+        self.builder
+            .build_call(
+                *fn_alloc,
+                &[out_p.into(), size_p.into(), names_arg.into()],
+                "alloc",
+            )
+            .unwrap();
+
+        let set_f = *self.functions.get("json_set").unwrap();
+        for prop in &properties {
+            let val_boxed = match given.iter().find(|(name, _)| **name == prop.name) {
+                Some((_, boxed)) => *boxed,
+                None => {
+                    let (val, val_ty) = self.evaluate_expression(prop.value.clone())?;
+                    self.box_value(val, val_ty)
+                }
+            };
+            let key_boxed = self.box_value(
+                self.builder
+                    .build_global_string_ptr(&prop.name, "field_key")
+                    .unwrap()
+                    .as_pointer_value()
+                    .into(),
+                crate::types::Type::String,
+            );
+            let key_p = self.create_entry_block_alloca(self.value_type, "field_key_ptr");
+            self.builder.build_store(key_p, key_boxed).unwrap();
+            let val_p = self.create_entry_block_alloca(self.value_type, "field_val");
+            self.builder.build_store(val_p, val_boxed).unwrap();
+            let set_out = self.create_entry_block_alloca(self.value_type, "field_set_out");
+            self.builder
+                .build_call(
+                    set_f,
+                    &[set_out.into(), out_p.into(), key_p.into(), val_p.into()],
+                    "field_init",
+                )
+                .unwrap();
+        }
+        Ok(out_p)
+    }
+
+    /// `obj.property`, looked up by name at run time.
+    fn emit_property_get(
+        &self,
+        obj: BasicValueEnum<'ctx>,
+        obj_ty: crate::types::Type,
+        property: &str,
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let get_f = self.functions.get("json_get").unwrap();
+
+        let obj_boxed = self.box_value(obj, obj_ty);
+        let key_boxed = self.box_value(
+            self.builder
+                .build_global_string_ptr(property, "prop_lookup")
+                .unwrap()
+                .as_pointer_value()
+                .into(),
+            crate::types::Type::String,
+        );
+
+        let obj_p = self.create_entry_block_alloca(self.value_type, "objp");
+        self.builder.build_store(obj_p, obj_boxed).unwrap();
+        let idx_p = self.create_entry_block_alloca(self.value_type, "idxp");
+        self.builder.build_store(idx_p, key_boxed).unwrap();
+
+        let res_p = self.create_entry_block_alloca(self.value_type, "rp");
+        self.builder
+            .build_call(*get_f, &[res_p.into(), obj_p.into(), idx_p.into()], "get")
+            .unwrap();
+
+        let res_v = self
+            .builder
+            .build_load(self.value_type, res_p, "r")
+            .unwrap()
+            .into_struct_value();
+        Ok((res_v.into(), crate::types::Type::Any))
+    }
+
+    /// `obj.method(args)`: `Class::method(obj, args)` for the class `obj` was
+    /// created from. The classes that could be behind `obj` (subclasses that
+    /// override the method of its static class, or every class with the
+    /// method when `obj` is untyped) are told apart at run time by the
+    /// object's property-name table. Anything else gets the static class's
+    /// method, or a runtime error when `obj` is untyped.
+    fn emit_method_call(
+        &self,
+        obj: BasicValueEnum<'ctx>,
+        obj_ty: crate::types::Type,
+        method: &str,
+        args: &[Expr],
+        loc: &Location,
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let static_method = match &obj_ty {
+            crate::types::Type::User(class) => Some(
+                self.method_for(class, method)
+                    .ok_or_else(|| format!("Class {} has no method `{}`.", class, method))?,
+            ),
+            crate::types::Type::Any => None,
+            other => {
+                return Err(format!(
+                    "Cannot call method `{}` on a value of type {:?}.",
+                    method, other
+                ))
+            }
+        };
+        // The return pointer and `self` come before the arguments.
+        let arity = args.len() + 2;
+        let mut class_names: Vec<&String> = self.classes.keys().collect();
+        class_names.sort();
+        let candidates: Vec<(String, String)> = class_names
+            .into_iter()
+            .filter(|class| match &obj_ty {
+                crate::types::Type::User(base) => {
+                    *class != base && self.is_subclass_of(class, base)
+                }
+                _ => true,
+            })
+            .filter_map(|class| Some((class.clone(), self.method_for(class, method)?)))
+            .filter(|(_, name)| Some(name) != static_method.as_ref())
+            .filter(|(_, name)| self.functions[name].count_params() as usize == arity)
+            .collect();
+        if static_method.is_none() && candidates.is_empty() {
+            return Err(format!(
+                "No class has a method `{}` taking {} argument(s).",
+                method,
+                args.len()
+            ));
+        }
+
+        let r_a = self.create_entry_block_alloca(self.value_type, "method_ret");
+        let self_p = self.create_entry_block_alloca(self.value_type, "method_self");
+        let self_boxed = self.box_value(obj, obj_ty.clone());
+        self.builder.build_store(self_p, self_boxed).unwrap();
+        let mut l_args: Vec<BasicMetadataValueEnum> = vec![r_a.into(), self_p.into()];
+        for arg in args {
+            let (v, ty) = self.evaluate_expression(arg.clone())?;
+            let boxed = self.box_value(v, ty);
+            let arg_a = self.create_entry_block_alloca(self.value_type, "method_arg");
+            self.builder.build_store(arg_a, boxed).unwrap();
+            l_args.push(arg_a.into());
+        }
+
+        let call = |name: &str| {
+            let f = *self.functions.get(name).unwrap();
+            self.builder.build_call(f, &l_args, "method").unwrap();
+        };
+        if candidates.is_empty() {
+            call(static_method.as_deref().unwrap());
+        } else {
+            let func = self.current_func.unwrap();
+            let end_bb = self.context.append_basic_block(func, "method_end");
+            let table = self.emit_class_table(self_boxed);
+            for (class, name) in &candidates {
+                let properties = self.collect_class_properties(class)?;
+                let class_table = self.build_class_names_arg(class, &properties)?;
+                let is_class = self
+                    .builder
+                    .build_int_compare(inkwell::IntPredicate::EQ, table, class_table, "is_class")
+                    .unwrap();
+                let call_bb = self.context.append_basic_block(func, "method_of_class");
+                let next_bb = self.context.append_basic_block(func, "method_next");
+                self.builder
+                    .build_conditional_branch(is_class, call_bb, next_bb)
+                    .unwrap();
+                self.builder.position_at_end(call_bb);
+                call(name);
+                self.builder.build_unconditional_branch(end_bb).unwrap();
+                self.builder.position_at_end(next_bb);
+            }
+            match &static_method {
+                Some(name) => {
+                    call(name);
+                    self.builder.build_unconditional_branch(end_bb).unwrap();
+                }
+                None => {
+                    let method_name = self
+                        .builder
+                        .build_global_string_ptr(method, "method_name")
+                        .unwrap()
+                        .as_pointer_value();
+                    self.emit_runtime_failure("snask_no_method", &[method_name.into()], loc);
+                }
+            }
+            self.builder.position_at_end(end_bb);
+        }
+        self.emit_try_check();
+
+        let res_v = self
+            .builder
+            .build_load(self.value_type, r_a, "r")
+            .unwrap()
+            .into_struct_value();
+        // Only a return type every possible method agrees on is known here.
+        let mut called = static_method
+            .iter()
+            .chain(candidates.iter().map(|(_, name)| name));
+        let first = called
+            .next()
+            .and_then(|name| self.function_return_types.get(name).cloned());
+        let return_ty = called.try_fold(first, |ty, name| {
+            (self.function_return_types.get(name) == ty.as_ref()).then_some(ty)
+        });
+        match return_ty.flatten() {
+            Some(return_ty)
+                if return_ty != crate::types::Type::Void
+                    && return_ty != crate::types::Type::Any =>
+            {
+                Ok((self.unbox_value(res_v, return_ty.clone()), return_ty))
+            }
+            _ => Ok((res_v.into(), crate::types::Type::Any)),
+        }
+    }
+
+    /// The property-name table of a boxed object, which identifies its
+    /// class, or null for anything that is not an object.
+    fn emit_class_table(&self, boxed: StructValue<'ctx>) -> PointerValue<'ctx> {
+        let tag = self
+            .builder
+            .build_extract_value(boxed, TAG_IDX, "tag")
+            .unwrap()
+            .into_float_value();
+        let is_obj = self
+            .builder
+            .build_float_compare(
+                inkwell::FloatPredicate::OEQ,
+                tag,
+                self.f64_type.const_float(TYPE_OBJ as f64),
+                "is_obj",
+            )
+            .unwrap();
+        let func = self.current_func.unwrap();
+        let from_bb = self.builder.get_insert_block().unwrap();
+        let obj_bb = self.context.append_basic_block(func, "obj_table");
+        let join_bb = self.context.append_basic_block(func, "obj_table_end");
+        self.builder
+            .build_conditional_branch(is_obj, obj_bb, join_bb)
+            .unwrap();
+
+        self.builder.position_at_end(obj_bb);
+        let obj_ptr = self
+            .builder
+            .build_extract_value(boxed, PTR_IDX, "obj_ptr")
+            .unwrap()
+            .into_pointer_value();
+        // `names` is the first field of SnaskObject.
+        let names = self
+            .builder
+            .build_load(self.ptr_type, obj_ptr, "obj_names")
+            .unwrap()
+            .into_pointer_value();
+        self.builder.build_unconditional_branch(join_bb).unwrap();
+
+        self.builder.position_at_end(join_bb);
+        let phi = self
+            .builder
+            .build_phi(self.ptr_type, "class_table")
+            .unwrap();
+        phi.add_incoming(&[(&self.ptr_type.const_null(), from_bb), (&names, obj_bb)]);
+        phi.as_basic_value().into_pointer_value()
+    }
+
     fn build_class_names_arg(
         &self,
         class_name: &str,
         properties: &[VarDecl],
     ) -> Result<PointerValue<'ctx>, String> {
-        if properties.is_empty() {
-            return Ok(self.ptr_type.const_null());
-        }
-
         let global_name = format!("__snask_class_names_{}", self.sanitize_name(class_name));
         let names_global = if let Some(g) = self.module.get_global(&global_name) {
             g
         } else {
             let i8_ptr = self.context.ptr_type(inkwell::AddressSpace::from(0));
-            let arr_ty = i8_ptr.array_type(properties.len() as u32);
+            // A null after the names keeps every class's table non-empty and
+            // distinct, so its address identifies the class for method calls.
+            let arr_ty = i8_ptr.array_type(properties.len() as u32 + 1);
             let g = self.module.add_global(arr_ty, None, &global_name);
             let mut elems = Vec::new();
             for prop in properties {
@@ -3411,6 +4639,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     .unwrap();
                 elems.push(sp.as_pointer_value());
             }
+            elems.push(i8_ptr.const_null());
             let init = i8_ptr.const_array(&elems);
             g.set_initializer(&init);
             g.set_constant(true);
@@ -3435,6 +4664,35 @@ impl<'ctx> LLVMGenerator<'ctx> {
             .unwrap())
     }
 
+    /// Body of `__snask_cov_register`: passes the label table to
+    /// `snask_cov_init`, which also installs the dump at exit.
+    fn generate_coverage_register(&mut self, register: FunctionValue<'ctx>) {
+        let sites = self.coverage_sites.clone().unwrap_or_default();
+        let entry = self.context.append_basic_block(register, "entry");
+        self.builder.position_at_end(entry);
+        self.debug_function(register, COVERAGE_REGISTER_SYMBOL, 0);
+        let labels: Vec<PointerValue<'ctx>> = sites
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                self.builder
+                    .build_global_string_ptr(label, &format!("cov_label_{}", i))
+                    .unwrap()
+                    .as_pointer_value()
+            })
+            .collect();
+        let table_type = self.ptr_type.array_type(labels.len() as u32);
+        let table = self.module.add_global(table_type, None, "__snask_cov_labels");
+        table.set_initializer(&self.ptr_type.const_array(&labels));
+        table.set_constant(true);
+        let init = *self.functions.get("snask_cov_init").unwrap();
+        let count = self.i32_type.const_int(labels.len() as u64, false);
+        self.builder
+            .build_call(init, &[table.as_pointer_value().into(), count.into()], "")
+            .unwrap();
+        self.builder.build_return(None).unwrap();
+    }
+
     fn declare_function(&mut self, func: &FuncDecl) -> Result<(), String> {
         let mut p_types: Vec<inkwell::types::BasicMetadataTypeEnum> = vec![self.ptr_type.into()];
         for _ in &func.params {
@@ -3454,11 +4712,30 @@ impl<'ctx> LLVMGenerator<'ctx> {
         Ok(())
     }
 
-    fn generate_function_body(&mut self, func: FuncDecl) -> Result<(), String> {
+    fn generate_function_body(&mut self, func: FuncDecl, line: usize) -> Result<(), String> {
         let function = *self.functions.get(&func.name).unwrap();
         let entry = self.context.append_basic_block(function, "entry");
         self.builder.position_at_end(entry);
         self.current_func = Some(function);
+        let decl_line = match func.span.start.line {
+            0 => line,
+            start => start,
+        };
+        self.debug_function(function, &func.name, decl_line);
+        // Tests exercise the code; they are not what coverage measures.
+        let is_test = func.name.starts_with(crate::test_runner::TEST_PREFIX);
+        if let Some(sites) = self.coverage_sites.as_mut().filter(|_| !is_test) {
+            let file = match func.name.split_once("::") {
+                Some((module, _)) if !self.classes.contains_key(module) => module.to_string(),
+                _ => self.module_name.clone(),
+            };
+            let id = sites.len() as u64;
+            sites.push(format!("{}\t{}\t{}", file, decl_line, func.name));
+            let hit = *self.functions.get("snask_cov_hit").unwrap();
+            self.builder
+                .build_call(hit, &[self.i32_type.const_int(id, false).into()], "cov_hit")
+                .unwrap();
+        }
         let old_vars = self.variables.clone();
         let old_zone_depth = self.active_zone_depth;
         self.active_zone_depth = 0;
@@ -3522,7 +4799,41 @@ impl<'ctx> LLVMGenerator<'ctx> {
         Ok(())
     }
 
+    /// A loop's `else` block. The loop branches to `else_bb` when it runs out
+    /// and to `end_bb`, past the block, when it is left early.
+    fn generate_loop_else(
+        &mut self,
+        else_bb: inkwell::basic_block::BasicBlock<'ctx>,
+        body: Vec<Stmt>,
+        end_bb: inkwell::basic_block::BasicBlock<'ctx>,
+    ) -> Result<(), String> {
+        self.builder.position_at_end(else_bb);
+        for s in body {
+            self.generate_statement(s)?;
+            if self
+                .builder
+                .get_insert_block()
+                .unwrap()
+                .get_terminator()
+                .is_some()
+            {
+                break;
+            }
+        }
+        if self
+            .builder
+            .get_insert_block()
+            .unwrap()
+            .get_terminator()
+            .is_none()
+        {
+            self.builder.build_unconditional_branch(end_bb).unwrap();
+        }
+        Ok(())
+    }
+
     fn generate_statement(&mut self, stmt: Stmt) -> Result<(), String> {
+        self.set_debug_location(&stmt.loc);
         match stmt.kind {
             StmtKind::VarDeclaration(d) => {
                 let (v, ty) = self.evaluate_expression(d.value)?;
@@ -3594,6 +4905,37 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 let stored_v = self.cast_basic_value(v, ty, target_ty);
                 self.builder.build_store(*p, stored_v).unwrap();
             }
+            StmtKind::TupleDeclaration { names, value, .. } => {
+                let items = self.load_tuple_items(value, names.len())?;
+                let in_main = self.current_func.unwrap().get_name().to_str().unwrap() == "main";
+                for (name, (v, ty)) in names.into_iter().zip(items) {
+                    let llvm_ty = self.snask_type_to_llvm(&ty);
+                    if in_main {
+                        let gv = self.module.add_global(llvm_ty, None, &format!("g_{}", name));
+                        gv.set_initializer(&llvm_ty.const_zero());
+                        let p = gv.as_pointer_value();
+                        self.builder.build_store(p, v).unwrap();
+                        self.variables.insert(name, (p, ty));
+                    } else {
+                        let a = self.create_entry_block_alloca(llvm_ty, &name);
+                        self.builder.build_store(a, v).unwrap();
+                        self.local_vars.insert(name, (a, ty));
+                    }
+                }
+            }
+            StmtKind::TupleAssignment { names, value } => {
+                // Every element is loaded before the first store, so `a, b = b, a` swaps.
+                let items = self.load_tuple_items(value, names.len())?;
+                for (name, (v, ty)) in names.iter().zip(items) {
+                    let (p, target_ty) = self
+                        .local_vars
+                        .get(name)
+                        .or_else(|| self.variables.get(name))
+                        .ok_or_else(|| format!("Var {} not found.", name))?;
+                    let stored_v = self.cast_basic_value(v, ty, target_ty);
+                    self.builder.build_store(*p, stored_v).unwrap();
+                }
+            }
             StmtKind::PropertyAssignment(p) => {
                 let (obj, obj_ty) = self.evaluate_expression(p.target)?;
                 let (val, val_ty) = self.evaluate_expression(p.value)?;
@@ -3636,7 +4978,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 let idx_boxed = self.box_value(idx, idx_ty);
                 let val_boxed = self.box_value(val, val_ty);
 
-                let set_f = self.functions.get("json_set").unwrap();
+                let set_f = self.functions.get("snask_index_set").unwrap();
 
                 let obj_p = self.create_entry_block_alloca(self.value_type, "objp");
                 self.builder.build_store(obj_p, obj_boxed).unwrap();
@@ -3646,47 +4988,81 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 self.builder.build_store(val_p, val_boxed).unwrap();
 
                 let out_p = self.create_entry_block_alloca(self.value_type, "outp");
+                self.emit_set_location(&stmt.loc);
+                let recoverable = self
+                    .i32_type
+                    .const_int(u64::from(!self.try_handlers.is_empty()), false);
                 self.builder
                     .build_call(
                         *set_f,
-                        &[out_p.into(), obj_p.into(), idx_p.into(), val_p.into()],
+                        &[
+                            out_p.into(),
+                            obj_p.into(),
+                            idx_p.into(),
+                            val_p.into(),
+                            recoverable.into(),
+                        ],
                         "idx_set",
                     )
                     .unwrap();
+                self.emit_try_check();
             }
-            StmtKind::Print(exprs) => {
-                let loc = || Location { line: 0, column: 0 };
+            StmtKind::Print(print) => {
+                let loc = || Location::default();
                 let span = || loc().to_span();
-                for expr in exprs {
-                    let callee = Expr {
-                        kind: ExprKind::Variable("stdio::_val".to_string()),
-                        loc: loc(),
-                        span: span(),
-                    };
-                    let call = Expr {
-                        kind: ExprKind::FunctionCall {
-                            callee: Box::new(callee),
-                            args: vec![expr.clone()],
-                        },
-                        loc: loc(),
-                        span: span(),
-                    };
-                    self.evaluate_expression(call)?;
-                }
-                let nl_callee = Expr {
-                    kind: ExprKind::Variable("stdio::println".to_string()),
-                    loc: loc(),
-                    span: span(),
-                };
-                let nl_call = Expr {
+                let stdio_call = |name: &str, args: Vec<Expr>| Expr {
                     kind: ExprKind::FunctionCall {
-                        callee: Box::new(nl_callee),
-                        args: vec![],
+                        callee: Box::new(Expr {
+                            kind: ExprKind::Variable(name.to_string()),
+                            loc: loc(),
+                            span: span(),
+                        }),
+                        args,
                     },
                     loc: loc(),
                     span: span(),
                 };
-                self.evaluate_expression(nl_call)?;
+
+                // `sep:` is evaluated once and reused between arguments.
+                let sep = match print.sep {
+                    Some(sep_expr) => {
+                        let (v, ty) = self.evaluate_expression(sep_expr)?;
+                        let boxed = self.box_value(v, ty);
+                        let sep_ptr = self.create_entry_block_alloca(self.value_type, "print_sep");
+                        self.builder.build_store(sep_ptr, boxed).unwrap();
+                        self.local_vars.insert(
+                            "__print_sep".to_string(),
+                            (sep_ptr, crate::types::Type::Any),
+                        );
+                        Some(Expr {
+                            kind: ExprKind::Variable("__print_sep".to_string()),
+                            loc: loc(),
+                            span: span(),
+                        })
+                    }
+                    None => None,
+                };
+
+                for (i, expr) in print.args.into_iter().enumerate() {
+                    if i > 0 {
+                        if let Some(sep) = &sep {
+                            self.evaluate_expression(stdio_call("stdio::_val", vec![sep.clone()]))?;
+                        }
+                    }
+                    self.evaluate_expression(stdio_call("stdio::_val", vec![expr]))?;
+                }
+                if sep.is_some() {
+                    self.local_vars.remove("__print_sep");
+                }
+
+                match print.end {
+                    Some(end) => {
+                        self.evaluate_expression(stdio_call("stdio::_val", vec![end]))?;
+                    }
+                    None => {
+                        self.evaluate_expression(stdio_call("stdio::println", vec![]))?;
+                    }
+                }
             }
             StmtKind::Return(expr) => {
                 let (v, ty) = self.evaluate_expression(expr)?;
@@ -3716,34 +5092,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 let merge_bb = self.context.append_basic_block(parent, "merge");
 
                 let (cond_val, cond_ty) = self.evaluate_expression(c.if_block.condition)?;
-                let is_true = match cond_ty {
-                    crate::types::Type::Bool => cond_val.into_int_value(),
-                    crate::types::Type::Float => self
-                        .builder
-                        .build_float_compare(
-                            inkwell::FloatPredicate::ONE,
-                            cond_val.into_float_value(),
-                            self.f64_type.const_float(0.0),
-                            "is_true",
-                        )
-                        .unwrap(),
-                    _ => {
-                        let boxed = self.box_value(cond_val, cond_ty);
-                        let n = self
-                            .builder
-                            .build_extract_value(boxed, NUM_IDX, "n")
-                            .unwrap()
-                            .into_float_value();
-                        self.builder
-                            .build_float_compare(
-                                inkwell::FloatPredicate::ONE,
-                                n,
-                                self.f64_type.const_float(0.0),
-                                "is_true",
-                            )
-                            .unwrap()
-                    }
-                };
+                let is_true = self.is_truthy(cond_val, cond_ty);
 
                 self.builder
                     .build_conditional_branch(is_true, then_bb, else_bb)
@@ -3784,37 +5133,26 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 self.builder.position_at_end(merge_bb);
             }
             StmtKind::Loop(l) => match l {
-                crate::ast::LoopStmt::While { condition, body } => {
-                    let parent = self.current_func.unwrap();
-                    let cond_bb = self.context.append_basic_block(parent, "while_cond");
-                    let body_bb = self.context.append_basic_block(parent, "while_body");
-                    let end_bb = self.context.append_basic_block(parent, "while_end");
-
-                    self.builder.build_unconditional_branch(cond_bb).unwrap();
-
-                    self.builder.position_at_end(cond_bb);
-                    let (cond_val, cond_ty) = self.evaluate_expression(condition)?;
-                    let is_true = match cond_ty {
-                        crate::types::Type::Bool => cond_val.into_int_value(),
-                        _ => {
-                            let boxed = self.box_value(cond_val, cond_ty);
-                            let n = self
-                                .builder
-                                .build_extract_value(boxed, NUM_IDX, "wn")
-                                .unwrap()
-                                .into_float_value();
-                            self.builder
-                                .build_float_compare(
-                                    inkwell::FloatPredicate::ONE,
-                                    n,
-                                    self.context.f64_type().const_float(0.0),
-                                    "wtrue",
-                                )
-                                .unwrap()
-                        }
-                    };
+                crate::ast::LoopStmt::While {
+                    condition,
+                    body,
+                    else_block,
+                } => {
+                    let parent = self.current_func.unwrap();
+                    let cond_bb = self.context.append_basic_block(parent, "while_cond");
+                    let body_bb = self.context.append_basic_block(parent, "while_body");
+                    let else_bb = else_block
+                        .as_ref()
+                        .map(|_| self.context.append_basic_block(parent, "while_else"));
+                    let end_bb = self.context.append_basic_block(parent, "while_end");
+
+                    self.builder.build_unconditional_branch(cond_bb).unwrap();
+
+                    self.builder.position_at_end(cond_bb);
+                    let (cond_val, cond_ty) = self.evaluate_expression(condition)?;
+                    let is_true = self.is_truthy(cond_val, cond_ty);
                     self.builder
-                        .build_conditional_branch(is_true, body_bb, end_bb)
+                        .build_conditional_branch(is_true, body_bb, else_bb.unwrap_or(end_bb))
                         .unwrap();
 
                     self.builder.position_at_end(body_bb);
@@ -3840,17 +5178,24 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         self.builder.build_unconditional_branch(cond_bb).unwrap();
                     }
 
+                    if let (Some(else_bb), Some(else_body)) = (else_bb, else_block) {
+                        self.generate_loop_else(else_bb, else_body, end_bb)?;
+                    }
                     self.builder.position_at_end(end_bb);
                 }
                 crate::ast::LoopStmt::For {
                     iterator,
                     iterable,
                     body,
+                    else_block,
                 } => {
                     let parent = self.current_func.unwrap();
                     let cond_bb = self.context.append_basic_block(parent, "for_cond");
                     let body_bb = self.context.append_basic_block(parent, "for_body");
                     let step_bb = self.context.append_basic_block(parent, "for_step");
+                    let else_bb = else_block
+                        .as_ref()
+                        .map(|_| self.context.append_basic_block(parent, "for_else"));
                     let end_bb = self.context.append_basic_block(parent, "for_end");
 
                     let (iterable_raw, iterable_ty) = self.evaluate_expression(iterable)?;
@@ -3939,7 +5284,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         )
                         .unwrap();
                     self.builder
-                        .build_conditional_branch(has_more, body_bb, end_bb)
+                        .build_conditional_branch(has_more, body_bb, else_bb.unwrap_or(end_bb))
                         .unwrap();
 
                     self.builder.position_at_end(body_bb);
@@ -4024,12 +5369,15 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     self.builder.build_store(index_ptr, next_index).unwrap();
                     self.builder.build_unconditional_branch(cond_bb).unwrap();
 
-                    self.builder.position_at_end(end_bb);
                     if let Some(prev) = previous_iter {
                         self.local_vars.insert(iterator, prev);
                     } else {
                         self.local_vars.remove(&iterator);
                     }
+                    if let (Some(else_bb), Some(else_body)) = (else_bb, else_block) {
+                        self.generate_loop_else(else_bb, else_body, end_bb)?;
+                    }
+                    self.builder.position_at_end(end_bb);
                 }
             },
             StmtKind::FuncCall(expr) => {
@@ -4094,6 +5442,102 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     self.emit_zone_leave();
                 }
             }
+            StmtKind::TryCatch {
+                body,
+                err_name,
+                handler,
+            } => {
+                let parent = self.current_func.unwrap();
+                let catch_bb = self.context.append_basic_block(parent, "try_catch");
+                let end_bb = self.context.append_basic_block(parent, "try_end");
+
+                // An error left pending by code outside any `try` must not
+                // trigger this handler.
+                if let Some(clear_fn) = self.functions.get("snask_error_clear") {
+                    self.builder
+                        .build_call(*clear_fn, &[], "err_clear")
+                        .unwrap();
+                }
+
+                // TRY block: every runtime call is followed by `emit_try_check`.
+                self.try_handlers.push(catch_bb);
+                for s in body {
+                    self.generate_statement(s)?;
+                    if self
+                        .builder
+                        .get_insert_block()
+                        .unwrap()
+                        .get_terminator()
+                        .is_some()
+                    {
+                        break;
+                    }
+                }
+                self.try_handlers.pop();
+                if self
+                    .builder
+                    .get_insert_block()
+                    .unwrap()
+                    .get_terminator()
+                    .is_none()
+                {
+                    self.builder.build_unconditional_branch(end_bb).unwrap();
+                }
+
+                // CATCH block: bind the error message, then run the handler.
+                self.builder.position_at_end(catch_bb);
+                let err_out = self.create_entry_block_alloca(self.value_type, "err_out");
+                let take_fn = *self
+                    .functions
+                    .get("snask_error_take")
+                    .ok_or("snask_error_take not declared")?;
+                self.builder
+                    .build_call(take_fn, &[err_out.into()], "err_take")
+                    .unwrap();
+                let err_v = self
+                    .builder
+                    .build_load(self.value_type, err_out, "err")
+                    .unwrap()
+                    .into_struct_value();
+                let err_raw = self.unbox_value(err_v, crate::types::Type::String);
+                let err_llvm_ty = self.snask_type_to_llvm(&crate::types::Type::String);
+                let err_a = self.create_entry_block_alloca(err_llvm_ty, &err_name);
+                self.builder.build_store(err_a, err_raw).unwrap();
+                let shadowed = self
+                    .local_vars
+                    .insert(err_name.clone(), (err_a, crate::types::Type::String));
+                for s in handler {
+                    self.generate_statement(s)?;
+                    if self
+                        .builder
+                        .get_insert_block()
+                        .unwrap()
+                        .get_terminator()
+                        .is_some()
+                    {
+                        break;
+                    }
+                }
+                match shadowed {
+                    Some(prev) => {
+                        self.local_vars.insert(err_name, prev);
+                    }
+                    None => {
+                        self.local_vars.remove(&err_name);
+                    }
+                }
+                if self
+                    .builder
+                    .get_insert_block()
+                    .unwrap()
+                    .get_terminator()
+                    .is_none()
+                {
+                    self.builder.build_unconditional_branch(end_bb).unwrap();
+                }
+
+                self.builder.position_at_end(end_bb);
+            }
             StmtKind::Scope { name: _, body } => {
                 // Similar to zones
                 for s in body {
@@ -4140,11 +5584,72 @@ impl<'ctx> LLVMGenerator<'ctx> {
             StmtKind::Entangle { .. } => {
                 // Future OM feature: static anchoring
             }
+            StmtKind::EnumDeclaration { name, variants } => self.declare_enum(&name, &variants),
             _ => {}
         }
         Ok(())
     }
 
+    /// Variants become constant `int` globals numbered in declaration order.
+    fn declare_enum(&mut self, name: &str, variants: &[String]) {
+        for (i, variant) in variants.iter().enumerate() {
+            let full_name = format!("{}::{}", name, variant);
+            if self.variables.contains_key(&full_name) {
+                continue;
+            }
+            let gv = self
+                .module
+                .add_global(self.i64_type, None, &format!("g_{}", full_name));
+            gv.set_initializer(&self.i64_type.const_int(i as u64, false));
+            gv.set_constant(true);
+            self.variables
+                .insert(full_name, (gv.as_pointer_value(), crate::types::Type::Int));
+        }
+    }
+
+    /// Evaluates `value` once and reads its first `count` elements back out,
+    /// unboxed to the tuple's element types when they are known.
+    fn load_tuple_items(
+        &self,
+        value: Expr,
+        count: usize,
+    ) -> Result<Vec<(BasicValueEnum<'ctx>, crate::types::Type)>, String> {
+        let (v, ty) = self.evaluate_expression(value)?;
+        let item_types = match &ty {
+            crate::types::Type::Tuple(items) => items.clone(),
+            _ => vec![crate::types::Type::Any; count],
+        };
+        let tuple_p = self.create_entry_block_alloca(self.value_type, "tuplep");
+        self.builder
+            .build_store(tuple_p, self.box_value(v, ty))
+            .unwrap();
+
+        let get_f = *self.functions.get("json_get").unwrap();
+        let mut items = Vec::with_capacity(count);
+        for (i, item_ty) in item_types.into_iter().take(count).enumerate() {
+            let idx = self.i64_type.const_int(i as u64, false).into();
+            let idx_p = self.create_entry_block_alloca(self.value_type, "tuple_idx");
+            self.builder
+                .build_store(idx_p, self.box_value(idx, crate::types::Type::Int))
+                .unwrap();
+            let out_p = self.create_entry_block_alloca(self.value_type, "tuple_item");
+            self.builder
+                .build_call(
+                    get_f,
+                    &[out_p.into(), tuple_p.into(), idx_p.into()],
+                    "tuple_get",
+                )
+                .unwrap();
+            let item_v = self
+                .builder
+                .build_load(self.value_type, out_p, "item")
+                .unwrap()
+                .into_struct_value();
+            items.push((self.unbox_value(item_v, item_ty.clone()), item_ty));
+        }
+        Ok(items)
+    }
+
     fn box_value(&self, val: BasicValueEnum<'ctx>, ty: crate::types::Type) -> StructValue<'ctx> {
         let f64_type = self.f64_type;
         let mut s = self.value_type.get_undef();
@@ -4280,6 +5785,8 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     .unwrap()
                     .into_struct_value();
             }
+            // `nil`: an all-zero value carries the nil tag.
+            crate::types::Type::Void => return self.value_type.const_zero(),
             _ => {
                 if val.is_struct_value() {
                     return val.into_struct_value();
@@ -4289,6 +5796,138 @@ impl<'ctx> LLVMGenerator<'ctx> {
         s
     }
 
+    /// Whether a condition value (`if`, `while`, `and`/`or`) counts as true:
+    /// a `bool` as is, anything else when its number is not zero.
+    fn is_truthy(&self, value: BasicValueEnum<'ctx>, ty: crate::types::Type) -> IntValue<'ctx> {
+        let n = match ty {
+            crate::types::Type::Bool => return value.into_int_value(),
+            crate::types::Type::Float => value.into_float_value(),
+            _ => {
+                let boxed = self.box_value(value, ty);
+                self.builder
+                    .build_extract_value(boxed, NUM_IDX, "n")
+                    .unwrap()
+                    .into_float_value()
+            }
+        };
+        self.builder
+            .build_float_compare(
+                inkwell::FloatPredicate::ONE,
+                n,
+                self.f64_type.const_float(0.0),
+                "is_true",
+            )
+            .unwrap()
+    }
+
+    /// `and`/`or`: the right side only runs when the left one does not
+    /// already decide the result (true for `and`, false for `or`).
+    fn emit_short_circuit(
+        &self,
+        op: BinaryOp,
+        left: Expr,
+        right: Expr,
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let (lhs, lty) = self.evaluate_expression(left)?;
+        let lhs_true = self.is_truthy(lhs, lty);
+        let lhs_bb = self.builder.get_insert_block().unwrap();
+        let func = self.current_func.unwrap();
+        let rhs_bb = self.context.append_basic_block(func, "sc_rhs");
+        let end_bb = self.context.append_basic_block(func, "sc_end");
+        let is_and = op == BinaryOp::And;
+        if is_and {
+            self.builder
+                .build_conditional_branch(lhs_true, rhs_bb, end_bb)
+                .unwrap();
+        } else {
+            self.builder
+                .build_conditional_branch(lhs_true, end_bb, rhs_bb)
+                .unwrap();
+        }
+
+        self.builder.position_at_end(rhs_bb);
+        let (rhs, rty) = self.evaluate_expression(right)?;
+        let rhs_true = self.is_truthy(rhs, rty);
+        // The right side may have ended in a block of its own (a nested
+        // `and`, a call inside a `try`).
+        let rhs_end_bb = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(end_bb).unwrap();
+
+        self.builder.position_at_end(end_bb);
+        let phi = self.builder.build_phi(self.bool_type, "sc").unwrap();
+        let decided = self.bool_type.const_int(u64::from(!is_and), false);
+        phi.add_incoming(&[(&decided, lhs_bb), (&rhs_true, rhs_end_bb)]);
+        Ok((phi.as_basic_value(), crate::types::Type::Bool))
+    }
+
+    /// An `Any` value read as an `int` (its number, truncated).
+    fn any_as_int(
+        &self,
+        value: BasicValueEnum<'ctx>,
+    ) -> (BasicValueEnum<'ctx>, crate::types::Type) {
+        let boxed = self.box_value(value, crate::types::Type::Any);
+        (
+            self.unbox_value(boxed, crate::types::Type::Int),
+            crate::types::Type::Int,
+        )
+    }
+
+    /// Whether a value is nil.
+    fn is_nil(&self, value: BasicValueEnum<'ctx>, ty: crate::types::Type) -> IntValue<'ctx> {
+        if ty == crate::types::Type::Void {
+            return self.bool_type.const_int(1, false);
+        }
+        let boxed = self.box_value(value, ty);
+        let tag = self
+            .builder
+            .build_extract_value(boxed, TAG_IDX, "tag")
+            .unwrap()
+            .into_float_value();
+        self.builder
+            .build_float_compare(
+                inkwell::FloatPredicate::OEQ,
+                tag,
+                self.f64_type.const_float(TYPE_NIL as f64),
+                "is_nil",
+            )
+            .unwrap()
+    }
+
+    /// `a?.b` and `a?.m()`: `access` only runs when `obj` is not nil, and
+    /// the result is nil otherwise.
+    fn emit_nil_guarded(
+        &self,
+        obj: BasicValueEnum<'ctx>,
+        obj_ty: crate::types::Type,
+        access: impl FnOnce() -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String>,
+    ) -> Result<(BasicValueEnum<'ctx>, crate::types::Type), String> {
+        let res_p = self.create_entry_block_alloca(self.value_type, "opt_res");
+        self.builder
+            .build_store(res_p, self.value_type.const_zero())
+            .unwrap();
+        let is_nil = self.is_nil(obj, obj_ty);
+        let func = self.current_func.unwrap();
+        let some_bb = self.context.append_basic_block(func, "opt_some");
+        let end_bb = self.context.append_basic_block(func, "opt_end");
+        self.builder
+            .build_conditional_branch(is_nil, end_bb, some_bb)
+            .unwrap();
+
+        self.builder.position_at_end(some_bb);
+        let (value, ty) = access()?;
+        let boxed = self.box_value(value, ty);
+        self.builder.build_store(res_p, boxed).unwrap();
+        self.builder.build_unconditional_branch(end_bb).unwrap();
+
+        self.builder.position_at_end(end_bb);
+        let res_v = self
+            .builder
+            .build_load(self.value_type, res_p, "opt")
+            .unwrap()
+            .into_struct_value();
+        Ok((res_v.into(), crate::types::Type::Any))
+    }
+
     fn unbox_value(
         &self,
         val: StructValue<'ctx>,
@@ -4351,7 +5990,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     .unwrap();
                 b.into()
             }
-            crate::types::Type::String | crate::types::Type::Ptr | crate::types::Type::User(_) => {
+            crate::types::Type::String | crate::types::Type::Ptr => {
                 self.builder.build_extract_value(val, PTR_IDX, "p").unwrap()
             }
             _ => val.into(),
@@ -4471,6 +6110,38 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 }
                 _ => Err(format!("Lit not supported: {:?}", lit)),
             },
+            ExprKind::Tuple(items) => {
+                // A tuple is a fixed-size runtime array, so `t[0]` and print work on it too.
+                let fn_new = *self.functions.get("snif_new_array").unwrap();
+                let fn_push = *self.functions.get("snif_arr_push").unwrap();
+                let tuple_p = self.create_entry_block_alloca(self.value_type, "tuple");
+                self.builder
+                    .build_call(fn_new, &[tuple_p.into()], "tuple_new")
+                    .unwrap();
+
+                let mut item_types = Vec::with_capacity(items.len());
+                for item in items {
+                    let (v, ty) = self.evaluate_expression(item)?;
+                    let v_p = self.create_entry_block_alloca(self.value_type, "tuple_item");
+                    self.builder
+                        .build_store(v_p, self.box_value(v, ty.clone()))
+                        .unwrap();
+                    let push_out_p = self.create_entry_block_alloca(self.value_type, "push_out");
+                    self.builder
+                        .build_call(
+                            fn_push,
+                            &[push_out_p.into(), tuple_p.into(), v_p.into()],
+                            "tuple_push",
+                        )
+                        .unwrap();
+                    item_types.push(ty);
+                }
+                let tuple_v = self
+                    .builder
+                    .build_load(self.value_type, tuple_p, "tuple_val")
+                    .unwrap();
+                Ok((tuple_v, crate::types::Type::Tuple(item_types)))
+            }
             ExprKind::Variable(name) => {
                 if let Some((p, ty)) = self
                     .local_vars
@@ -4486,9 +6157,67 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 Err(format!("Var {} not found.", name))
             }
             ExprKind::Binary { op, left, right } => {
+                if matches!(op, BinaryOp::And | BinaryOp::Or) {
+                    return self.emit_short_circuit(op, *left, *right);
+                }
+                if matches!(op, BinaryOp::Add) {
+                    let operands = Self::add_chain(&left, &right);
+                    if operands.len() > 2
+                        && (self.is_string_expr(operands[0]) || self.is_string_expr(operands[1]))
+                    {
+                        return self.build_concat_chain(&operands);
+                    }
+                }
                 let (lhs, lty) = self.evaluate_expression(*left)?;
                 let (rhs, rty) = self.evaluate_expression(*right)?;
 
+                // `in` and `**` are runtime calls on the boxed operands.
+                if let Some((helper_name, result_ty)) = match op {
+                    BinaryOp::In => Some(("snask_in", crate::types::Type::Bool)),
+                    BinaryOp::Power => Some(("s_pow", crate::types::Type::Float)),
+                    _ => None,
+                } {
+                    let helper = *self.functions.get(helper_name).unwrap();
+                    let lhs_boxed = self.box_value(lhs, lty);
+                    let rhs_boxed = self.box_value(rhs, rty);
+                    let lhs_p = self.create_entry_block_alloca(self.value_type, "rt_op_l");
+                    let rhs_p = self.create_entry_block_alloca(self.value_type, "rt_op_r");
+                    let out_p = self.create_entry_block_alloca(self.value_type, "rt_op_out");
+                    self.builder.build_store(lhs_p, lhs_boxed).unwrap();
+                    self.builder.build_store(rhs_p, rhs_boxed).unwrap();
+                    self.builder
+                        .build_call(helper, &[out_p.into(), lhs_p.into(), rhs_p.into()], "rt_op")
+                        .unwrap();
+                    self.emit_try_check();
+                    let out_v = self
+                        .builder
+                        .build_load(self.value_type, out_p, "rt_op_v")
+                        .unwrap()
+                        .into_struct_value();
+                    return Ok((self.unbox_value(out_v, result_ty.clone()), result_ty));
+                }
+
+                // Bitwise operands typed `Any` (list items, untyped parameters)
+                // are numbers at runtime; they take the integer path as `int`.
+                let bitwise = matches!(
+                    op,
+                    BinaryOp::BitAnd
+                        | BinaryOp::BitOr
+                        | BinaryOp::BitXor
+                        | BinaryOp::ShiftLeft
+                        | BinaryOp::ShiftRight
+                );
+                let (lhs, lty) = if bitwise && lty == crate::types::Type::Any {
+                    self.any_as_int(lhs)
+                } else {
+                    (lhs, lty)
+                };
+                let (rhs, rty) = if bitwise && rty == crate::types::Type::Any {
+                    self.any_as_int(rhs)
+                } else {
+                    (rhs, rty)
+                };
+
                 if matches!(op, BinaryOp::Add)
                     && (lty == crate::types::Type::String || rty == crate::types::Type::String)
                 {
@@ -4528,8 +6257,6 @@ impl<'ctx> LLVMGenerator<'ctx> {
                             .build_int_compare(inkwell::IntPredicate::NE, li, ri, "bool_ne")
                             .unwrap()
                             .into(),
-                        BinaryOp::And => self.builder.build_and(li, ri, "bool_and").unwrap().into(),
-                        BinaryOp::Or => self.builder.build_or(li, ri, "bool_or").unwrap().into(),
                         _ => {
                             return Err(format!(
                                 "Operation {:?} not supported for bool values",
@@ -4584,6 +6311,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                             | BinaryOp::Subtract
                             | BinaryOp::Multiply
                             | BinaryOp::Divide
+                            | BinaryOp::Modulo
                             | BinaryOp::LessThan
                             | BinaryOp::LessThanOrEquals
                             | BinaryOp::GreaterThan
@@ -4602,6 +6330,19 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         .build_extract_value(rhs_boxed, NUM_IDX, "any_rnum")
                         .unwrap()
                         .into_float_value();
+                    // Float `/` keeps IEEE inf/nan; only `%` reports a zero divisor.
+                    if matches!(op, BinaryOp::Modulo) {
+                        let is_zero = self
+                            .builder
+                            .build_float_compare(
+                                inkwell::FloatPredicate::OEQ,
+                                rf,
+                                self.f64_type.const_zero(),
+                                "any_rem_zero",
+                            )
+                            .unwrap();
+                        self.emit_zero_divisor_check(is_zero, &expr.loc);
+                    }
                     let res = match op {
                         BinaryOp::Add => self
                             .builder
@@ -4623,6 +6364,11 @@ impl<'ctx> LLVMGenerator<'ctx> {
                             .build_float_div(lf, rf, "any_div")
                             .unwrap()
                             .into(),
+                        BinaryOp::Modulo => self
+                            .builder
+                            .build_float_rem(lf, rf, "any_rem")
+                            .unwrap()
+                            .into(),
                         BinaryOp::LessThan => self
                             .builder
                             .build_float_compare(inkwell::FloatPredicate::OLT, lf, rf, "any_lt")
@@ -4729,6 +6475,21 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         let ri = self
                             .cast_basic_value(rhs, rty.clone(), &op_ty)
                             .into_int_value();
+                        if matches!(
+                            op,
+                            BinaryOp::Divide | BinaryOp::IntDivide | BinaryOp::Modulo
+                        ) {
+                            let is_zero = self
+                                .builder
+                                .build_int_compare(
+                                    inkwell::IntPredicate::EQ,
+                                    ri,
+                                    ri.get_type().const_zero(),
+                                    "div_zero",
+                                )
+                                .unwrap();
+                            self.emit_zero_divisor_check(is_zero, &expr.loc);
+                        }
                         let res = match op {
                             BinaryOp::Add => {
                                 self.builder.build_int_add(li, ri, "add").unwrap().into()
@@ -4879,6 +6640,18 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         } else {
                             rhs.into_float_value()
                         };
+                        if matches!(op, BinaryOp::Modulo) {
+                            let is_zero = self
+                                .builder
+                                .build_float_compare(
+                                    inkwell::FloatPredicate::OEQ,
+                                    rf,
+                                    self.f64_type.const_zero(),
+                                    "frem_zero",
+                                )
+                                .unwrap();
+                            self.emit_zero_divisor_check(is_zero, &expr.loc);
+                        }
                         let res = match op {
                             BinaryOp::Add => {
                                 self.builder.build_float_add(lf, rf, "fadd").unwrap().into()
@@ -4892,6 +6665,9 @@ impl<'ctx> LLVMGenerator<'ctx> {
                             BinaryOp::Divide => {
                                 self.builder.build_float_div(lf, rf, "fdiv").unwrap().into()
                             }
+                            BinaryOp::Modulo => {
+                                self.builder.build_float_rem(lf, rf, "frem").unwrap().into()
+                            }
                             BinaryOp::LessThan => self
                                 .builder
                                 .build_float_compare(inkwell::FloatPredicate::OLT, lf, rf, "flt")
@@ -4934,7 +6710,11 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     op, lty, rty, expr.loc.line, expr.loc.column
                 ))
             }
-            ExprKind::PropertyAccess { target, property } => {
+            ExprKind::PropertyAccess {
+                target,
+                property,
+                optional,
+            } => {
                 if let ExprKind::Variable(library) = &target.kind {
                     let surface = format!("{}.{}", library, property);
                     if let Some(value) = self.om_constant_for_surface(library, &surface) {
@@ -4946,40 +6726,19 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 }
 
                 let (obj, obj_ty) = self.evaluate_expression(*target)?;
-                let get_f = self.functions.get("json_get").unwrap();
-
-                let obj_boxed = self.box_value(obj, obj_ty);
-                let key_boxed = self.box_value(
-                    self.builder
-                        .build_global_string_ptr(&property, "prop_lookup")
-                        .unwrap()
-                        .as_pointer_value()
-                        .into(),
-                    crate::types::Type::String,
-                );
-
-                let obj_p = self.create_entry_block_alloca(self.value_type, "objp");
-                self.builder.build_store(obj_p, obj_boxed).unwrap();
-                let idx_p = self.create_entry_block_alloca(self.value_type, "idxp");
-                self.builder.build_store(idx_p, key_boxed).unwrap();
-
-                let res_p = self.create_entry_block_alloca(self.value_type, "rp");
-                self.builder
-                    .build_call(*get_f, &[res_p.into(), obj_p.into(), idx_p.into()], "get")
-                    .unwrap();
-
-                let res_v = self
-                    .builder
-                    .build_load(self.value_type, res_p, "r")
-                    .unwrap()
-                    .into_struct_value();
-                Ok((res_v.into(), crate::types::Type::Any))
+                if optional {
+                    return self.emit_nil_guarded(obj, obj_ty.clone(), || {
+                        self.emit_property_get(obj, obj_ty, &property)
+                    });
+                }
+                self.emit_property_get(obj, obj_ty, &property)
             }
             ExprKind::IndexAccess { target, index } => {
                 let (obj, obj_ty) = self.evaluate_expression(*target)?;
                 let (idx, idx_ty) = self.evaluate_expression(*index)?;
 
-                let get_f = self.functions.get("json_get").unwrap();
+                self.emit_set_location(&expr.loc);
+                let get_f = self.functions.get("snask_index_get").unwrap();
 
                 let obj_boxed = self.box_value(obj, obj_ty);
                 let idx_boxed = self.box_value(idx, idx_ty);
@@ -5005,7 +6764,63 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     .into_struct_value();
                 Ok((res_v.into(), crate::types::Type::Any))
             }
+            ExprKind::Slice {
+                target,
+                start,
+                end,
+                step,
+            } => {
+                let (obj, obj_ty) = self.evaluate_expression(*target)?;
+                let obj_p = self.create_entry_block_alloca(self.value_type, "slice_objp");
+                let obj_boxed = self.box_value(obj, obj_ty);
+                self.builder.build_store(obj_p, obj_boxed).unwrap();
+                let out_p = self.create_entry_block_alloca(self.value_type, "slice_outp");
+                // Omitted bounds are passed as nil.
+                let mut args: Vec<BasicMetadataValueEnum> = vec![out_p.into(), obj_p.into()];
+                for bound in [start, end, step] {
+                    let bound = bound.map_or_else(
+                        || Expr::new(ExprKind::Literal(LiteralValue::Nil), expr.loc.clone()),
+                        |bound| *bound,
+                    );
+                    let (val, ty) = self.evaluate_expression(bound)?;
+                    let boxed = self.box_value(val, ty);
+                    let p = self.create_entry_block_alloca(self.value_type, "slice_boundp");
+                    self.builder.build_store(p, boxed).unwrap();
+                    args.push(p.into());
+                }
+
+                let slice_f = *self.functions.get("snask_slice").unwrap();
+                self.builder.build_call(slice_f, &args, "slice").unwrap();
+
+                let res_v = self
+                    .builder
+                    .build_load(self.value_type, out_p, "r")
+                    .unwrap()
+                    .into_struct_value();
+                Ok((res_v.into(), crate::types::Type::Any))
+            }
             ExprKind::FunctionCall { callee, args } => {
+                if let ExprKind::PropertyAccess {
+                    target,
+                    property,
+                    optional,
+                } = &callee.kind
+                {
+                    // `lib.f()` with no variable named `lib` is an OM call;
+                    // anything else calls a method on the target's value.
+                    let om_call = matches!(&target.kind, ExprKind::Variable(name)
+                        if !self.local_vars.contains_key(name)
+                            && !self.variables.contains_key(name));
+                    if *optional || !om_call {
+                        let (obj, obj_ty) = self.evaluate_expression((**target).clone())?;
+                        if *optional {
+                            return self.emit_nil_guarded(obj, obj_ty.clone(), || {
+                                self.emit_method_call(obj, obj_ty, property, &args, &expr.loc)
+                            });
+                        }
+                        return self.emit_method_call(obj, obj_ty, property, &args, &expr.loc);
+                    }
+                }
                 if let Some(path) = Self::expr_path(&callee) {
                     if path.len() == 2 {
                         let library = &path[0];
@@ -5098,6 +6913,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
                 l_args.push(r_a.into());
 
                 if let ExprKind::Variable(name) = &callee.kind {
+                    // `Point(1, 2)` is the same as `new Point(1, 2)`.
+                    if self.classes.contains_key(name) {
+                        return self.emit_new(name.clone(), &args);
+                    }
                     if let Some(result) = self.emit_systems_low_level_builtin(name, &args)? {
                         return Ok(result);
                     }
@@ -5107,6 +6926,10 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         return self.emit_snask_intrinsic(name, &args);
                     }
 
+                    if name == "assert" {
+                        return self.emit_assert(&args, &expr.loc);
+                    }
+
                     if matches!(
                         name.as_str(),
                         "wrapping_add" | "wrapping_sub" | "wrapping_mul" | "saturating_add"
@@ -5158,15 +6981,22 @@ impl<'ctx> LLVMGenerator<'ctx> {
 
                     let runtime_name =
                         native_symbol_for_module_alias(name).unwrap_or_else(|| name.clone());
-                    let f = self
+                    // A function the program declares wins over a runtime builtin
+                    // with the same plain name (`gcd`, `sum`, `zip`, ...).
+                    let user_fn = self
                         .module
-                        .get_function(&runtime_name)
+                        .get_function(&format!("f_{}", self.sanitize_name(name)));
+                    let f = user_fn
+                        .or_else(|| self.module.get_function(&runtime_name))
                         .or_else(|| self.module.get_function(&format!("f_{}", runtime_name)))
                         .or_else(|| self.functions.get(&runtime_name).cloned())
                         .or_else(|| self.module.get_function(name))
                         .or_else(|| self.module.get_function(&format!("f_{}", name)))
                         .or_else(|| self.functions.get(name).cloned())
                         .ok_or_else(|| format!("Função {} não encontrada.", name))?;
+                    if f.get_name().to_bytes() == b"s_args" {
+                        self.uses_args.set(true);
+                    }
 
                     for arg in args {
                         let (v, ty) = self.evaluate_expression(arg.clone())?;
@@ -5177,6 +7007,7 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     }
 
                     self.builder.build_call(f, &l_args, "c").unwrap();
+                    self.emit_try_check();
                     let res_v = self
                         .builder
                         .build_load(self.value_type, r_a, "r")
@@ -5195,10 +7026,13 @@ impl<'ctx> LLVMGenerator<'ctx> {
                             return Ok((raw, return_ty));
                         }
                     }
-                    if let Some(return_ty) = self
-                        .runtime_function_return_type(&runtime_name)
-                        .or_else(|| self.runtime_function_return_type(name))
-                    {
+                    let runtime_return_ty = match user_fn {
+                        Some(_) => None,
+                        None => self
+                            .runtime_function_return_type(&runtime_name)
+                            .or_else(|| self.runtime_function_return_type(name)),
+                    };
+                    if let Some(return_ty) = runtime_return_ty {
                         if return_ty != crate::types::Type::Void
                             && return_ty != crate::types::Type::Any
                         {
@@ -5252,6 +7086,11 @@ impl<'ctx> LLVMGenerator<'ctx> {
                         }
                     },
                     crate::ast::UnaryOp::BitNot => {
+                        let (raw, ty) = if ty == crate::types::Type::Any {
+                            self.any_as_int(raw)
+                        } else {
+                            (raw, ty)
+                        };
                         if ty.is_integer() {
                             Ok((
                                 self.builder
@@ -5291,32 +7130,9 @@ impl<'ctx> LLVMGenerator<'ctx> {
                     }
                 }
             }
-            ExprKind::New {
-                class,
-                args,
-                strategy,
-            } => {
-                // Instanciação de classe (simplificada para o novo modelo)
-                let out_p = self.create_entry_block_alloca(self.value_type, "alloc_out");
-                let fn_alloc = self.functions.get("s_alloc_obj").unwrap();
-                let properties = self.collect_class_properties(&class)?;
-
-                let size_boxed = self.box_value(
-                    self.f64_type.const_float(properties.len() as f64).into(),
-                    crate::types::Type::Float,
-                );
-                let size_p = self.create_entry_block_alloca(self.value_type, "szp");
-                self.builder.build_store(size_p, size_boxed).unwrap();
-
-                let names_arg = self.build_class_names_arg(&class, &properties)?;
-                self.builder
-                    .build_call(
-                        *fn_alloc,
-                        &[out_p.into(), size_p.into(), names_arg.into()],
-                        "alloc",
-                    )
-                    .unwrap();
-
+            ExprKind::New { class, args, .. } => self.emit_new(class, &args),
+            ExprKind::StructLiteral { class, fields } => {
+                let out_p = self.emit_object(&class, &fields)?;
                 let res_v = self
                     .builder
                     .build_load(self.value_type, out_p, "obj")
@@ -1,10 +1,17 @@
-use clap::{Parser as ClapParser, Subcommand};
-use std::process::Command;
+use clap::{ArgAction, Parser as ClapParser, Subcommand};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
 
-use snask::compiler::{build_file, resolve_entry_file, BuildOptions, BuildProfile};
+use snask::compiler::{
+    build_file, check_file, parse_define, resolve_entry_file, BuildOptions, BuildProfile,
+    Verbosity, STDIN_FILE,
+};
 use snask::dist;
+use snask::lib_tool;
 use snask::om_scan::{run_scan, ScanOptions};
 use snask::packages;
+use snask::snif_tooling;
 use snask::sps;
 use snask::tools;
 
@@ -14,6 +21,15 @@ use snask::tools;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Print more build details (-v: toolchain and output, -vv: llc/clang command lines)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Hide progress bars
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Fetch the package registry again instead of using the cached copy
+    #[arg(long, global = true)]
+    refresh: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,6 +39,9 @@ enum Commands {
         name: Option<String>,
         #[arg(long)]
         zenith: bool,
+        /// Scaffold a library (no `class main`) ready for `snask lib publish`
+        #[arg(long, conflicts_with = "zenith")]
+        lib: bool,
     },
     /// Build a Snask program or project
     Build {
@@ -43,6 +62,61 @@ enum Commands {
         tiny: bool,
         #[arg(long)]
         extreme: bool,
+        /// Build every member of the workspace in the current directory
+        #[arg(long)]
+        workspace: bool,
+        /// Read the program from stdin (same as `-` as the file); needs `-o`
+        #[arg(long)]
+        stdin: bool,
+        /// Leave `assert(...)` checks out of the binary
+        #[arg(long)]
+        no_assert: bool,
+        /// Strip symbols and debug info from the linked binary
+        #[arg(long)]
+        strip: bool,
+        /// Emit DWARF debug info (functions and lines) for gdb/lldb
+        #[arg(short = 'g', long, conflicts_with = "strip")]
+        debug: bool,
+        /// Build a module object without `class main` (implied by `package.lib`)
+        #[arg(long)]
+        no_main: bool,
+        /// Fail when there are warnings (`-W error` does the same)
+        #[arg(long)]
+        deny_warnings: bool,
+        /// `-W error` treats warnings as errors
+        #[arg(short = 'W', value_name = "LEVEL", value_parser = ["error"])]
+        warnings: Option<String>,
+        /// Skip the `prebuild`/`postbuild` scripts
+        #[arg(long)]
+        no_hooks: bool,
+        /// Keep resolving imports after a module fails and report every failure
+        #[arg(long)]
+        keep_going: bool,
+        /// Fail when imports nest deeper than N files (default 64)
+        #[arg(long, value_name = "N")]
+        max_import_depth: Option<usize>,
+        /// Set a value read by `cfg("KEY")`; a bare KEY means `true` (repeatable)
+        #[arg(long = "define", short = 'D', value_name = "KEY[=VAL]")]
+        defines: Vec<String>,
+        /// Rebuild whenever the entry file, an imported module or `snask.snif` changes
+        #[arg(long, conflicts_with_all = ["stdin", "workspace"])]
+        watch: bool,
+    },
+    /// Parse and type-check a program without building it
+    Check {
+        file: Option<String>,
+        /// Read the program from stdin (same as `-` as the file)
+        #[arg(long)]
+        stdin: bool,
+        /// Fail when there are warnings (`-W error` does the same)
+        #[arg(long)]
+        deny_warnings: bool,
+        /// `-W error` treats warnings as errors
+        #[arg(short = 'W', value_name = "LEVEL", value_parser = ["error"])]
+        warnings: Option<String>,
+        /// Fail when imports nest deeper than N files (default 64)
+        #[arg(long, value_name = "N")]
+        max_import_depth: Option<usize>,
     },
     /// Distribute/package the application
     Dist {
@@ -59,15 +133,97 @@ enum Commands {
         name: Option<String>,
         #[arg(long)]
         linux_user: bool,
+        /// Pack Windows builds into a `.zip` and macOS builds into a `.tar.gz`
+        #[arg(long)]
+        archive: bool,
+        /// Strip every binary, even when the manifest sets `build.strip: false`
+        #[arg(long)]
+        strip: bool,
         #[arg(long, default_value = "dist")]
         out_dir: String,
+        /// List the targets with a prepared runtime and exit
+        #[arg(long)]
+        target_list: bool,
     },
     /// Run a Snask program or project script
-    Run { file: Option<String> },
+    Run {
+        file: Option<String>,
+        /// Run in-process with LLVM's JIT instead of linking a binary
+        #[arg(long)]
+        jit: bool,
+        /// Skip the `prerun`/`postrun` scripts
+        #[arg(long)]
+        no_hooks: bool,
+        /// Rebuild and restart the program whenever its sources change
+        #[arg(long, conflicts_with = "jit")]
+        watch: bool,
+        /// Set a value read by `cfg("KEY")`; a bare KEY means `true` (repeatable)
+        #[arg(long = "define", short = 'D', value_name = "KEY[=VAL]")]
+        defines: Vec<String>,
+        /// Arguments for the program or script (`snask run app.snask a b`)
+        args: Vec<String>,
+        /// Arguments after a bare `--`, passed on as they are, even when they
+        /// start with `-` (`snask run dev -- --port 8080`, `snask run -- a b`)
+        #[arg(last = true)]
+        forwarded: Vec<String>,
+    },
+    /// Evaluate Snask code interactively
+    Repl,
+    /// Run every top-level `test_*` function and report pass/fail
+    Test {
+        file: Option<String>,
+        /// Only run tests whose name contains this string
+        #[arg(long)]
+        filter: Option<String>,
+        /// Count function calls and print coverage per file
+        #[arg(long)]
+        coverage: bool,
+    },
+    /// Build a program with optimizations and time several runs of it
+    Bench {
+        file: Option<String>,
+        #[arg(long, default_value_t = 10)]
+        runs: usize,
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        #[arg(long, default_value_t = 3)]
+        opt_level: u8,
+    },
+    /// Print the assembly generated for a program
+    Disasm {
+        file: Option<String>,
+        /// Only show this function (`twice`, `main::start` or a symbol name)
+        #[arg(long)]
+        function: Option<String>,
+        /// Interleave the source line each group of instructions comes from
+        #[arg(short = 'S', long)]
+        source: bool,
+        #[arg(long, default_value_t = 2)]
+        opt_level: u8,
+    },
+    /// Remove build artifacts of the current project
+    Clean {
+        /// Also remove the `dist/` directory
+        #[arg(long)]
+        dist: bool,
+        /// Also remove the build cache
+        #[arg(long)]
+        cache: bool,
+    },
     /// Add a dependency to the project
     Add {
         name: String,
+        /// A registry version, `git+<url>#<ref>` or `path:<dir>`
         version: Option<String>,
+        /// Depend on a git repository instead of the registry
+        #[arg(long, conflicts_with_all = ["version", "path"])]
+        git: Option<String>,
+        /// Branch, tag or commit to check out with `--git`
+        #[arg(long, requires = "git")]
+        rev: Option<String>,
+        /// Depend on a local library directory
+        #[arg(long, conflicts_with = "version")]
+        path: Option<String>,
     },
     /// Remove a dependency from the project
     Remove { name: String },
@@ -82,6 +238,26 @@ enum Commands {
     Uninstall { name: Option<String> },
     /// Update packages or Snask itself
     Update { name: Option<String> },
+    /// Show registry details of a package
+    Info {
+        name: String,
+        /// Print the details as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Search the package registry
+    Search {
+        query: String,
+        /// Print the matches as a JSON array
+        #[arg(long)]
+        json: bool,
+        /// Show at most this many matches
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Skip this many matches first
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+    },
     /// Manage Snask libraries
     Lib {
         #[command(subcommand)]
@@ -109,7 +285,14 @@ enum Commands {
     /// Explain a Snask diagnostic code
     Explain { code: String },
     /// System health check
-    Doctor,
+    Doctor {
+        /// Repair what can be repaired (runtime, package registry)
+        #[arg(long)]
+        fix: bool,
+        /// Print the report as JSON
+        #[arg(long, conflicts_with = "fix")]
+        json: bool,
+    },
     /// Measure binary size
     Size { path: String },
     /// Show Snask system information
@@ -137,9 +320,12 @@ enum LibCommands {
         #[arg(long)]
         pr: bool,
         #[arg(long)]
-        fork: bool,
+        fork: Option<String>,
         #[arg(long)]
         branch: Option<String>,
+        /// Validate and show what would be committed and pushed, without doing it
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -155,6 +341,19 @@ enum SnifCommands {
     Hash,
     Check,
     Fmt,
+    /// Compare two SNIF files key by key, ignoring formatting and key order
+    Diff {
+        a: String,
+        b: String,
+    },
+    /// Print a SNIF file as JSON; typed literals become `{"$date": ...}` objects
+    ToJson {
+        file: String,
+    },
+    /// Print a JSON file as canonical SNIF
+    FromJson {
+        file: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -173,11 +372,17 @@ enum OmCommands {
 
 fn main() {
     let cli = Cli::parse();
+    let verbosity = Verbosity::from_flags(cli.verbose, cli.quiet);
+    packages::set_force_registry_refresh(
+        cli.refresh || matches!(cli.command, Commands::Update { .. }),
+    );
 
     let result = match &cli.command {
-        Commands::Init { name, zenith } => {
+        Commands::Init { name, zenith, lib } => {
             if *zenith {
                 tools::init_zenith_project(name.clone())
+            } else if *lib {
+                sps::init_library(name.clone())
             } else {
                 sps::init_project(name.clone())
             }
@@ -192,19 +397,128 @@ fn main() {
             min_runtime,
             tiny,
             extreme,
-        } => run_build(
+            workspace,
+            stdin,
+            no_assert,
+            strip,
+            debug,
+            no_main,
+            deny_warnings,
+            warnings,
+            no_hooks,
+            keep_going,
+            max_import_depth,
+            defines,
+            watch,
+        } => {
+            let build = || {
+                run_build(
+                    &if *stdin {
+                        Some(STDIN_FILE.to_string())
+                    } else {
+                        file.clone()
+                    },
+                    output,
+                    target,
+                    profile,
+                    *lto,
+                    *release_size,
+                    *min_runtime,
+                    *tiny,
+                    *extreme,
+                    *workspace,
+                    *no_assert,
+                    *strip,
+                    *debug,
+                    *no_main,
+                    *deny_warnings || warnings.is_some(),
+                    *no_hooks,
+                    *keep_going,
+                    *max_import_depth,
+                    defines,
+                    verbosity,
+                )
+            };
+            if *watch {
+                watch_build(file, build)
+            } else {
+                build()
+            }
+        }
+        Commands::Check {
             file,
-            output,
-            target,
-            profile,
-            *lto,
-            *release_size,
-            *min_runtime,
-            *tiny,
-            *extreme,
-        ),
-        Commands::Run { file } => run_program(file),
-        Commands::Add { name, version } => sps::add_dependency(name, version.clone()),
+            stdin,
+            deny_warnings,
+            warnings,
+            max_import_depth,
+        } => {
+            let file = if *stdin {
+                Ok(STDIN_FILE.to_string())
+            } else {
+                resolve_entry_file(file.clone())
+            };
+            let max_import_depth = max_import_depth.or_else(|| {
+                let cwd = std::env::current_dir().ok()?;
+                sps::load_manifest_from(&cwd).ok()?.0.build.max_import_depth
+            });
+            file.and_then(|f| {
+                check_file(&f, *deny_warnings || warnings.is_some(), max_import_depth)
+            })
+        }
+        Commands::Run {
+            file,
+            jit,
+            no_hooks,
+            watch,
+            defines,
+            args,
+            forwarded,
+        } => {
+            let args: Vec<String> = args.iter().chain(forwarded).cloned().collect();
+            run_program(file, *jit, *watch, *no_hooks, defines, &args, verbosity)
+        }
+        Commands::Repl => snask::repl::run_repl(),
+        Commands::Test {
+            file,
+            filter,
+            coverage,
+        } => snask::test_runner::run_tests(file.clone(), filter.clone(), *coverage, verbosity),
+        Commands::Bench {
+            file,
+            runs,
+            warmup,
+            opt_level,
+        } => resolve_entry_file(file.clone())
+            .and_then(|entry| snask::bench::run_bench(&entry, *runs, *warmup, *opt_level)),
+        Commands::Disasm {
+            file,
+            function,
+            source,
+            opt_level,
+        } => resolve_entry_file(file.clone()).and_then(|entry| {
+            snask::disasm::run_disasm(&entry, function.as_deref(), *source, *opt_level)
+        }),
+        Commands::Clean { dist, cache } => snask::clean::run_clean(*dist, *cache),
+        Commands::Add {
+            name,
+            version,
+            git,
+            rev,
+            path,
+        } => {
+            let spec = match (git, path) {
+                (Some(url), _) => {
+                    let url = url.strip_prefix("git+").unwrap_or(url);
+                    Some(match rev {
+                        Some(rev) => format!("git+{}#{}", url, rev),
+                        None => format!("git+{}", url),
+                    })
+                }
+                (None, Some(path)) => Some(format!("path:{}", path)),
+                (None, None) => version.clone(),
+            };
+            sps::add_dependency(name, spec)
+        }
         Commands::Remove { name } => sps::remove_dependency(name),
         Commands::Setup { target } => tools::run_setup(target.clone()),
         Commands::Install { name } => packages::install_package(name),
@@ -222,6 +536,14 @@ fn main() {
                 tools::self_update()
             }
         }
+        Commands::Info { name, json } => packages::show_package_info(name, *json),
+        Commands::Search {
+            query,
+            json,
+            limit,
+            offset,
+        } => packages::search_packages(query, *json, *limit, *offset),
+        Commands::Dist { target_list: true, .. } => dist::run_target_list(),
         Commands::Dist {
             file,
             targets,
@@ -230,7 +552,10 @@ fn main() {
             appimage,
             name,
             linux_user,
+            archive,
+            strip,
             out_dir,
+            target_list: false,
         } => dist::run_dist(
             file.clone(),
             targets.clone(),
@@ -239,9 +564,11 @@ fn main() {
             *appimage,
             name.clone(),
             *linux_user,
+            *archive,
+            *strip,
             out_dir.clone(),
         ),
-        Commands::Doctor => tools::doctor(),
+        Commands::Doctor { fix, json } => tools::doctor(*fix, *json),
         Commands::Size { path } => tools::cmd_size(path),
         Commands::Fetch => {
             snask::fetch::run_fetch();
@@ -260,20 +587,57 @@ fn main() {
                 extra_cflags: cflags.clone(),
             }),
         },
+        Commands::Lib { cmd } => match cmd {
+            LibCommands::Init {
+                name,
+                version,
+                description,
+            } => lib_tool::lib_init(lib_tool::NewLibOpts {
+                name: name.clone(),
+                description: description.clone().unwrap_or_default(),
+                version: version.clone().unwrap_or_else(|| "0.1.0".to_string()),
+            }),
+            LibCommands::Publish {
+                name,
+                version,
+                description,
+                message,
+                push,
+                pr,
+                fork,
+                branch,
+                dry_run,
+            } => lib_tool::lib_publish(lib_tool::PublishOpts {
+                name: name.clone(),
+                version: Some(version.clone()),
+                description: description.clone(),
+                message: message.clone(),
+                push: *push,
+                pr: *pr,
+                fork: fork.clone(),
+                branch: branch.clone(),
+                dry_run: *dry_run,
+            }),
+        },
         Commands::Explain { code } => snask::explain::run_explain(code),
+        Commands::Snif { cmd, .. } => run_snif(cmd),
         _ => Err("Command not implemented yet in this refactor.".to_string()),
     };
 
     if let Err(e) = result {
-        if e.starts_with("error[") || e.starts_with("warning[") {
-            eprintln!("{}", e);
-        } else {
-            eprintln!("error: {}", e);
-        }
+        report_error(&e);
         std::process::exit(1);
     }
 }
 
+fn report_error(e: &str) {
+    if e.starts_with("error[") || e.starts_with("warning[") {
+        eprintln!("{}", e);
+    } else {
+        eprintln!("error: {}", e);
+    }
+}
+
 fn run_build(
     file: &Option<String>,
     output: &Option<String>,
@@ -284,37 +648,100 @@ fn run_build(
     min_runtime: bool,
     tiny: bool,
     extreme: bool,
+    workspace: bool,
+    no_assert: bool,
+    strip: bool,
+    debug: bool,
+    no_main: bool,
+    deny_warnings: bool,
+    no_hooks: bool,
+    keep_going: bool,
+    max_import_depth: Option<usize>,
+    defines: &[String],
+    verbosity: Verbosity,
 ) -> Result<(), String> {
+    if file.as_deref() == Some(STDIN_FILE) && output.is_none() {
+        return Err(
+            "building from stdin needs an explicit output: `snask build - -o <name>`".to_string(),
+        );
+    }
     let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
-    let (file_path, options) = if let Ok((m, _)) = sps::load_manifest_from(&cwd) {
-        sps::pin_from_lock(&cwd, &m)?;
-        sps::resolve_deps_and_lock(&cwd, &m)?;
-        let entry = file.clone().unwrap_or_else(|| m.package.entry.clone());
-        let profile_name = cli_profile
-            .as_deref()
-            .or(m.build.profile.as_deref())
-            .unwrap_or("default");
-        let profile = parse_build_profile(profile_name)?;
+    let cli_defines = parse_defines(defines)?;
+    let manifest_options =
+        |m: &sps::SpsManifest, output_name: String| -> Result<BuildOptions, String> {
+            let profile_name = cli_profile
+                .as_deref()
+                .or(m.build.profile.as_deref())
+                .unwrap_or("default");
+            let profile = parse_build_profile(profile_name)?;
 
-        let is_extreme = extreme || profile_name == "extreme";
-        let is_tiny = tiny || profile_name == "tiny" || is_extreme;
-        let is_release_size = release_size || profile_name == "release-size";
+            let is_extreme = extreme || profile_name == "extreme";
+            let is_tiny = tiny || profile_name == "tiny" || is_extreme;
+            let is_release_size = release_size || profile_name == "release-size";
+            let is_module = no_main || m.package.lib;
+            let output_name = if is_module && Path::new(&output_name).extension().is_none() {
+                format!("{}.o", output_name)
+            } else {
+                output_name
+            };
 
-        let opt = BuildOptions {
-            output_name: output.clone().or_else(|| Some(m.package.name.clone())),
-            target: target.clone(),
-            profile,
-            opt_level: m.build.opt_level,
-            lto: lto || m.build.lto.as_deref() == Some("thin") || is_release_size || is_tiny,
-            release_size: is_release_size,
-            min_runtime,
-            tiny: is_tiny,
-            extreme: is_extreme,
-            strip: m.build.strip.unwrap_or(is_release_size || is_tiny),
-            opt_override: m.build.opt.clone(),
-            features: m.build.features.clone(),
+            Ok(BuildOptions {
+                output_name: Some(output_name),
+                target: target.clone(),
+                profile,
+                opt_level: m.build.opt_level,
+                lto: lto || m.build.lto.as_deref() == Some("thin") || is_release_size || is_tiny,
+                release_size: is_release_size,
+                min_runtime,
+                tiny: is_tiny,
+                extreme: is_extreme,
+                strip: strip || m.build.strip.unwrap_or(is_release_size || is_tiny),
+                debug,
+                opt_override: m.build.opt.clone(),
+                features: m.build.features.clone(),
+                defines: with_defines(&m.build.defines, &cli_defines),
+                path_deps: Default::default(),
+                verbosity,
+                no_assert,
+                no_main: is_module,
+                test_mode: false,
+                test_filter: None,
+                coverage: false,
+                deny_warnings,
+                max_import_depth: max_import_depth.or(m.build.max_import_depth),
+                keep_going,
+            })
         };
-        (entry, opt)
+
+    if workspace {
+        if file.is_some() || output.is_some() {
+            return Err(
+                "`--workspace` builds every member; it cannot be combined with a file or `--output`."
+                    .to_string(),
+            );
+        }
+        let ws = sps::load_workspace_from(&cwd)?;
+        sps::resolve_workspace_deps_and_lock(&ws)?;
+        for member in &ws.members {
+            let m = &member.manifest;
+            println!("📦 Building workspace member '{}'", m.package.name);
+            let output_name = member.dir.join(&m.package.name);
+            let mut opt = manifest_options(m, output_name.to_string_lossy().to_string())?;
+            opt.path_deps = ws.path_deps(member);
+            let hooks = Some(m).filter(|_| !no_hooks);
+            sps::with_hooks(hooks, &member.dir, "build", || {
+                build_file(&ws.entry_path(member).to_string_lossy(), opt)
+            })?;
+        }
+        return Ok(());
+    }
+
+    let (file_path, options, manifest) = if let Ok((m, _)) = sps::load_manifest_from(&cwd) {
+        sps::pin_from_lock(&cwd, &m)?;
+        sps::resolve_deps_and_lock(&cwd, &m)?;
+        let entry = file.clone().unwrap_or_else(|| m.package.entry.clone());
+        let opt = manifest_options(&m, output.clone().unwrap_or_else(|| m.package.name.clone()))?;
+        (entry, opt, Some(m))
     } else {
         let entry = resolve_entry_file(file.clone())?;
         let profile = parse_build_profile(cli_profile.as_deref().unwrap_or("default"))?;
@@ -327,12 +754,63 @@ fn run_build(
             min_runtime,
             tiny,
             extreme,
+            verbosity,
+            no_assert,
+            strip,
+            debug,
+            no_main,
+            deny_warnings,
+            keep_going,
+            max_import_depth,
+            defines: cli_defines.clone(),
             ..Default::default()
         };
-        (entry, opt)
+        (entry, opt, None)
     };
 
-    build_file(&file_path, options)
+    let hooks = manifest.as_ref().filter(|_| !no_hooks);
+    sps::with_hooks(hooks, &cwd, "build", || build_file(&file_path, options))
+}
+
+fn run_snif(cmd: &SnifCommands) -> Result<(), String> {
+    match cmd {
+        SnifCommands::Schema { json: true, .. } => {
+            print!("{}", snif_tooling::schema_json());
+            Ok(())
+        }
+        SnifCommands::Schema { .. } => {
+            print!("{}", snif_tooling::schema_md());
+            Ok(())
+        }
+        SnifCommands::Diff { a, b } => {
+            let report = snif_tooling::snif_diff_files(Path::new(a), Path::new(b))?;
+            print!("{}", report);
+            Ok(())
+        }
+        SnifCommands::ToJson { file } => {
+            print!("{}", snif_tooling::snif_file_to_json(Path::new(file))?);
+            Ok(())
+        }
+        SnifCommands::FromJson { file } => {
+            print!("{}", snif_tooling::json_file_to_snif(Path::new(file))?);
+            Ok(())
+        }
+        _ => Err("Command not implemented yet in this refactor.".to_string()),
+    }
+}
+
+fn parse_defines(args: &[String]) -> Result<BTreeMap<String, String>, String> {
+    args.iter().map(|arg| parse_define(arg)).collect()
+}
+
+/// The manifest's `build.defines` with the `--define` values on top.
+fn with_defines(
+    manifest: &BTreeMap<String, String>,
+    cli: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut defines = manifest.clone();
+    defines.extend(cli.clone());
+    defines
 }
 
 fn parse_build_profile(profile: &str) -> Result<BuildProfile, String> {
@@ -344,54 +822,157 @@ fn parse_build_profile(profile: &str) -> Result<BuildProfile, String> {
     })
 }
 
-fn run_program(file: &Option<String>) -> Result<(), String> {
+fn run_program(
+    file: &Option<String>,
+    jit: bool,
+    watch: bool,
+    no_hooks: bool,
+    defines: &[String],
+    args: &[String],
+    verbosity: Verbosity,
+) -> Result<(), String> {
     let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let cli_defines = parse_defines(defines)?;
 
     // Handle scripts
     if let Some(arg) = file {
         if !arg.ends_with(".snask") {
             if let Ok((m, _)) = sps::load_manifest_from(&cwd) {
                 if let Some(cmdline) = m.scripts.get(arg) {
-                    let status = Command::new("sh")
-                        .arg("-lc")
-                        .arg(cmdline)
-                        .status()
-                        .map_err(|e| e.to_string())?;
-                    if !status.success() {
-                        return Err(format!("Script '{}' failed.", arg));
-                    }
-                    return Ok(());
+                    return sps::run_script(&cwd, arg, cmdline, args);
                 }
             }
         }
     }
 
-    // Resolve entry file
-    let (file_path, options) = if let Ok((m, _)) = sps::load_manifest_from(&cwd) {
-        sps::pin_from_lock(&cwd, &m)?;
-        sps::resolve_deps_and_lock(&cwd, &m)?;
+    let (file_path, options, manifest) = run_options(file, &cli_defines, verbosity, &cwd)?;
+    let hooks = manifest.as_ref().filter(|_| !no_hooks);
+    sps::with_hooks(hooks, &cwd, "run", || {
+        if watch {
+            watch_and_run(file, &cli_defines, verbosity, args)
+        } else {
+            build_and_run(&file_path, options, jit, args)
+        }
+    })
+}
+
+/// Entry file and build options for `snask run`, from `snask.snif` when the
+/// current directory has one.
+fn run_options(
+    file: &Option<String>,
+    cli_defines: &BTreeMap<String, String>,
+    verbosity: Verbosity,
+    cwd: &Path,
+) -> Result<(String, BuildOptions, Option<sps::SpsManifest>), String> {
+    if let Ok((m, _)) = sps::load_manifest_from(cwd) {
+        sps::pin_from_lock(cwd, &m)?;
+        sps::resolve_deps_and_lock(cwd, &m)?;
         let entry = file.clone().unwrap_or_else(|| m.package.entry.clone());
         let opt = BuildOptions {
             opt_level: m.opt_level_for(true),
             features: m.build.features.clone(),
+            defines: with_defines(&m.build.defines, cli_defines),
+            verbosity,
             ..Default::default()
         };
-        (entry, opt)
+        Ok((entry, opt, Some(m)))
     } else {
-        (resolve_entry_file(file.clone())?, BuildOptions::default())
-    };
+        let opt = BuildOptions {
+            defines: cli_defines.clone(),
+            verbosity,
+            ..Default::default()
+        };
+        Ok((resolve_entry_file(file.clone())?, opt, None))
+    }
+}
 
-    // Build
-    build_file(&file_path, options)?;
+/// Entry file and manifest path followed by a `--watch` session.
+fn watch_target(file: &Option<String>) -> Result<(String, Option<PathBuf>), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    match sps::load_manifest_from(&cwd) {
+        Ok((m, path)) => Ok((file.clone().unwrap_or(m.package.entry), Some(path))),
+        Err(_) => Ok((resolve_entry_file(file.clone())?, None)),
+    }
+}
 
-    // Run
+/// `snask build --watch`: builds now and after every change, reporting
+/// failures instead of stopping.
+fn watch_build(
+    file: &Option<String>,
+    build: impl Fn() -> Result<(), String>,
+) -> Result<(), String> {
+    let (entry, manifest) = watch_target(file)?;
+    snask::watch::watch(Path::new(&entry), manifest.as_deref(), || {
+        if let Err(e) = build() {
+            report_error(&e);
+        }
+    })
+}
+
+/// `snask run --watch`: rebuilds after every change and restarts the
+/// program once the build succeeds. The options are reloaded each time, so
+/// edits to `snask.snif` apply as well.
+fn watch_and_run(
+    file: &Option<String>,
+    cli_defines: &BTreeMap<String, String>,
+    verbosity: Verbosity,
+    args: &[String],
+) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (entry, manifest) = watch_target(file)?;
+    let mut running: Option<Child> = None;
+    snask::watch::watch(Path::new(&entry), manifest.as_deref(), || {
+        // Stop the old instance first: the build replaces its binary.
+        if let Some(mut child) = running.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        let started =
+            run_options(file, cli_defines, verbosity, &cwd).and_then(|(file_path, options, _)| {
+                build_file(&file_path, options)?;
+                Command::new(binary_path(&file_path))
+                    .args(args)
+                    .spawn()
+                    .map_err(|e| format!("Failed to run binary: {}", e))
+            });
+        match started {
+            Ok(child) => running = Some(child),
+            Err(e) => report_error(&e),
+        }
+    })
+}
+
+/// Path of the binary `build_file` writes for `file_path`, runnable as is.
+fn binary_path(file_path: &str) -> String {
     let binary = file_path.replace(".snask", "");
-    let binary_path = if binary.starts_with('/') || binary.starts_with("./") {
+    if binary.starts_with('/') || binary.starts_with("./") {
         binary
     } else {
         format!("./{}", binary)
-    };
-    let status = Command::new(&binary_path)
+    }
+}
+
+fn build_and_run(
+    file_path: &str,
+    options: BuildOptions,
+    jit: bool,
+    args: &[String],
+) -> Result<(), String> {
+    if jit {
+        if let Some(code) = snask::jit::run_file(file_path, &options, args)? {
+            if code != 0 {
+                return Err("Program execution failed.".to_string());
+            }
+            return Ok(());
+        }
+    }
+
+    // Build
+    build_file(file_path, options)?;
+
+    // Run
+    let status = Command::new(binary_path(file_path))
+        .args(args)
         .status()
         .map_err(|e| format!("Failed to run binary: {}", e))?;
     if !status.success() {
@@ -399,3 +980,50 @@ fn run_program(file: &Option<String>) -> Result<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Cli, Commands};
+    use clap::{CommandFactory, Parser as ClapParser};
+
+    /// The file, plain arguments and `--` arguments of a `snask run` line.
+    fn parse_run(argv: &[&str]) -> (Option<String>, Vec<String>, Vec<String>) {
+        let cli = Cli::try_parse_from(argv).expect("arguments should parse");
+        let Commands::Run {
+            file,
+            args,
+            forwarded,
+            ..
+        } = cli.command
+        else {
+            panic!("expected the run command");
+        };
+        (file, args, forwarded)
+    }
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn run_passes_everything_after_a_bare_double_dash_to_the_program() {
+        let (file, args, forwarded) = parse_run(&["snask", "run", "--", "foo", "bar"]);
+        assert_eq!(file, None);
+        assert!(args.is_empty());
+        assert_eq!(forwarded, ["foo", "bar"]);
+
+        let (file, _, forwarded) = parse_run(&["snask", "run", "--", "--port"]);
+        assert_eq!(file, None);
+        assert_eq!(forwarded, ["--port"]);
+
+        let (file, _, forwarded) = parse_run(&["snask", "run", "dev", "--", "--port", "8080"]);
+        assert_eq!(file.as_deref(), Some("dev"));
+        assert_eq!(forwarded, ["--port", "8080"]);
+
+        let (file, args, forwarded) = parse_run(&["snask", "run", "app.snask", "a", "b"]);
+        assert_eq!(file.as_deref(), Some("app.snask"));
+        assert_eq!(args, ["a", "b"]);
+        assert!(forwarded.is_empty());
+    }
+}
@@ -11,6 +11,12 @@ pub fn is_native_module(name: &str) -> bool {
             | "os"
             | "sfs"
             | "string"
+            | "regex"
+            | "datetime"
+            | "csv"
+            | "base64"
+            | "hash"
+            | "sb"
             | "math"
             | "json"
             | "http"
@@ -0,0 +1,659 @@
+use crate::ast::{BinaryOp, ExprKind, LiteralValue, LoopStmt, Program, Stmt, StmtKind, UnaryOp};
+use crate::ast::{ConditionalStmt, Expr, FuncDecl, IfBlock};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Removes top-level `FuncDeclaration`s that cannot be reached from the
+/// program roots: every method of every class (starting with `class main`)
+/// and every top-level statement that is not itself a function.
+///
+/// A function counts as used whenever its name appears as a variable, not
+/// only as a callee, so functions passed around as values survive. String
+/// literals naming a function are kept too, since GUI callbacks such as
+/// `gui::on_click(button, "on_save")` look handlers up by name at runtime.
+/// Returns the names of the removed functions.
+pub fn eliminate_dead_functions(program: &mut Program) -> Vec<String> {
+    let functions: HashMap<String, &FuncDecl> = program
+        .iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::FuncDeclaration(func) => Some((func.name.clone(), func)),
+            _ => None,
+        })
+        .collect();
+    let has_main = program.iter().any(
+        |stmt| matches!(&stmt.kind, StmtKind::ClassDeclaration(class) if class.name == "main"),
+    );
+    if functions.is_empty() || !has_main {
+        return Vec::new();
+    }
+
+    let mut refs = HashSet::new();
+    for stmt in program.iter() {
+        match &stmt.kind {
+            StmtKind::FuncDeclaration(_) => {}
+            StmtKind::ClassDeclaration(class) => {
+                for prop in &class.properties {
+                    collect_expr_refs(&prop.value, &mut refs);
+                }
+                for method in &class.methods {
+                    collect_block_refs(&method.body, &mut refs);
+                }
+            }
+            _ => collect_stmt_refs(stmt, &mut refs),
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut pending: Vec<String> = refs.into_iter().collect();
+    while let Some(name) = pending.pop() {
+        let Some(func) = functions.get(&name) else {
+            continue;
+        };
+        if !reachable.insert(name) {
+            continue;
+        }
+        let mut inner = HashSet::new();
+        collect_block_refs(&func.body, &mut inner);
+        pending.extend(inner);
+    }
+
+    let removed: Vec<String> = functions
+        .keys()
+        .filter(|name| !reachable.contains(*name))
+        .cloned()
+        .collect();
+    program.retain(|stmt| match &stmt.kind {
+        StmtKind::FuncDeclaration(func) => reachable.contains(&func.name),
+        _ => true,
+    });
+    removed
+}
+
+fn collect_block_refs(body: &[Stmt], refs: &mut HashSet<String>) {
+    for stmt in body {
+        collect_stmt_refs(stmt, refs);
+    }
+}
+
+fn collect_stmt_refs(stmt: &Stmt, refs: &mut HashSet<String>) {
+    match &stmt.kind {
+        StmtKind::Expression(expr) | StmtKind::FuncCall(expr) | StmtKind::Return(expr) => {
+            collect_expr_refs(expr, refs)
+        }
+        StmtKind::VarDeclaration(d) => collect_expr_refs(&d.value, refs),
+        StmtKind::MutDeclaration(d) => collect_expr_refs(&d.value, refs),
+        StmtKind::ConstDeclaration(d) => collect_expr_refs(&d.value, refs),
+        StmtKind::VarAssignment(set) => collect_expr_refs(&set.value, refs),
+        StmtKind::PropertyAssignment(set) => {
+            collect_expr_refs(&set.target, refs);
+            collect_expr_refs(&set.value, refs);
+        }
+        StmtKind::IndexAssignment(set) => {
+            collect_expr_refs(&set.target, refs);
+            collect_expr_refs(&set.index, refs);
+            collect_expr_refs(&set.value, refs);
+        }
+        StmtKind::TupleDeclaration { value, .. } | StmtKind::TupleAssignment { value, .. } => {
+            collect_expr_refs(value, refs)
+        }
+        StmtKind::Print(print) => {
+            for expr in print.exprs() {
+                collect_expr_refs(expr, refs);
+            }
+        }
+        // Nested functions are kept by their enclosing body, so whatever they
+        // call is treated as reachable as well.
+        StmtKind::FuncDeclaration(func) => collect_block_refs(&func.body, refs),
+        StmtKind::ClassDeclaration(class) => {
+            for prop in &class.properties {
+                collect_expr_refs(&prop.value, refs);
+            }
+            for method in &class.methods {
+                collect_block_refs(&method.body, refs);
+            }
+        }
+        StmtKind::Conditional(cond) => {
+            collect_expr_refs(&cond.if_block.condition, refs);
+            collect_block_refs(&cond.if_block.body, refs);
+            for elif in &cond.elif_blocks {
+                collect_expr_refs(&elif.condition, refs);
+                collect_block_refs(&elif.body, refs);
+            }
+            if let Some(body) = &cond.else_block {
+                collect_block_refs(body, refs);
+            }
+        }
+        StmtKind::Loop(LoopStmt::While {
+            condition,
+            body,
+            else_block,
+        }) => {
+            collect_expr_refs(condition, refs);
+            collect_block_refs(body, refs);
+            if let Some(else_body) = else_block {
+                collect_block_refs(else_body, refs);
+            }
+        }
+        StmtKind::Loop(LoopStmt::For {
+            iterable,
+            body,
+            else_block,
+            ..
+        }) => {
+            collect_expr_refs(iterable, refs);
+            collect_block_refs(body, refs);
+            if let Some(else_body) = else_block {
+                collect_block_refs(else_body, refs);
+            }
+        }
+        StmtKind::ListDeclaration(d) => collect_expr_refs(&d.value, refs),
+        StmtKind::ListPush(push) => collect_expr_refs(&push.value, refs),
+        StmtKind::DictDeclaration(d) => collect_expr_refs(&d.value, refs),
+        StmtKind::DictSet(set) => {
+            collect_expr_refs(&set.key, refs);
+            collect_expr_refs(&set.value, refs);
+        }
+        StmtKind::UnsafeBlock(body)
+        | StmtKind::Scope { body, .. }
+        | StmtKind::Zone { body, .. } => collect_block_refs(body, refs),
+        StmtKind::TryCatch { body, handler, .. } => {
+            collect_block_refs(body, refs);
+            collect_block_refs(handler, refs);
+        }
+        StmtKind::Input { .. }
+        | StmtKind::Import { .. }
+        | StmtKind::ImportCOm { .. }
+        | StmtKind::FromImport { .. }
+        | StmtKind::Promote { .. }
+        | StmtKind::EnumDeclaration { .. }
+        | StmtKind::TypeAlias { .. }
+        | StmtKind::Entangle { .. } => {}
+    }
+}
+
+fn collect_expr_refs(expr: &Expr, refs: &mut HashSet<String>) {
+    match &expr.kind {
+        ExprKind::Literal(LiteralValue::String(s)) => {
+            refs.insert(s.clone());
+        }
+        ExprKind::Literal(LiteralValue::List(items)) => {
+            for item in items {
+                collect_expr_refs(item, refs);
+            }
+        }
+        ExprKind::Literal(LiteralValue::Dict(pairs)) => {
+            for (key, value) in pairs {
+                collect_expr_refs(key, refs);
+                collect_expr_refs(value, refs);
+            }
+        }
+        ExprKind::Literal(_) => {}
+        ExprKind::Variable(name) => {
+            refs.insert(name.clone());
+        }
+        ExprKind::Unary { expr, .. } => collect_expr_refs(expr, refs),
+        ExprKind::Binary { left, right, .. } => {
+            collect_expr_refs(left, refs);
+            collect_expr_refs(right, refs);
+        }
+        ExprKind::FunctionCall { callee, args } => {
+            collect_expr_refs(callee, refs);
+            for arg in args {
+                collect_expr_refs(arg, refs);
+            }
+        }
+        ExprKind::PropertyAccess { target, .. } => {
+            // `util.helper` is resolved as `util::helper` by the generator.
+            if let Some(path) = property_path(expr) {
+                refs.insert(path.join("::"));
+            }
+            collect_expr_refs(target, refs);
+        }
+        ExprKind::IndexAccess { target, index } => {
+            collect_expr_refs(target, refs);
+            collect_expr_refs(index, refs);
+        }
+        ExprKind::Slice {
+            target,
+            start,
+            end,
+            step,
+        } => {
+            collect_expr_refs(target, refs);
+            for bound in [start, end, step].into_iter().flatten() {
+                collect_expr_refs(bound, refs);
+            }
+        }
+        ExprKind::New { args, .. } | ExprKind::Tuple(args) => {
+            for arg in args {
+                collect_expr_refs(arg, refs);
+            }
+        }
+        ExprKind::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                collect_expr_refs(value, refs);
+            }
+        }
+    }
+}
+
+fn property_path(expr: &Expr) -> Option<Vec<String>> {
+    match &expr.kind {
+        ExprKind::Variable(name) => Some(vec![name.clone()]),
+        ExprKind::PropertyAccess {
+            target, property, ..
+        } => {
+            let mut parts = property_path(target)?;
+            parts.push(property.clone());
+            Some(parts)
+        }
+        _ => None,
+    }
+}
+
+/// Replaces every `cfg("KEY")` with a boolean literal: `true` when `KEY` is
+/// defined to anything but `false`, `0` or an empty string. `if`/`elif`
+/// branches whose `cfg` condition becomes constant are pruned, so code for
+/// other configurations is neither analyzed nor generated. Conditions without
+/// a `cfg` call are left alone, even when they are already constant.
+pub fn resolve_cfg(program: &mut Program, defines: &BTreeMap<String, String>) {
+    resolve_cfg_block(program, defines);
+}
+
+fn cfg_enabled(defines: &BTreeMap<String, String>, key: &str) -> bool {
+    defines
+        .get(key)
+        .is_some_and(|value| !matches!(value.as_str(), "false" | "0" | ""))
+}
+
+fn resolve_cfg_block(body: &mut Vec<Stmt>, defines: &BTreeMap<String, String>) {
+    body.retain_mut(|stmt| resolve_cfg_stmt(stmt, defines));
+}
+
+/// Resolves `cfg` inside `stmt`; `false` when the statement was an `if`
+/// whose every branch is compiled out.
+fn resolve_cfg_stmt(stmt: &mut Stmt, defines: &BTreeMap<String, String>) -> bool {
+    match &mut stmt.kind {
+        StmtKind::Expression(expr) | StmtKind::FuncCall(expr) | StmtKind::Return(expr) => {
+            resolve_cfg_expr(expr, defines);
+        }
+        StmtKind::VarDeclaration(d) => {
+            resolve_cfg_expr(&mut d.value, defines);
+        }
+        StmtKind::MutDeclaration(d) => {
+            resolve_cfg_expr(&mut d.value, defines);
+        }
+        StmtKind::ConstDeclaration(d) => {
+            resolve_cfg_expr(&mut d.value, defines);
+        }
+        StmtKind::VarAssignment(set) => {
+            resolve_cfg_expr(&mut set.value, defines);
+        }
+        StmtKind::PropertyAssignment(set) => {
+            resolve_cfg_expr(&mut set.target, defines);
+            resolve_cfg_expr(&mut set.value, defines);
+        }
+        StmtKind::IndexAssignment(set) => {
+            resolve_cfg_expr(&mut set.target, defines);
+            resolve_cfg_expr(&mut set.index, defines);
+            resolve_cfg_expr(&mut set.value, defines);
+        }
+        StmtKind::TupleDeclaration { value, .. } | StmtKind::TupleAssignment { value, .. } => {
+            resolve_cfg_expr(value, defines);
+        }
+        StmtKind::Print(print) => {
+            for expr in print.exprs_mut() {
+                resolve_cfg_expr(expr, defines);
+            }
+        }
+        StmtKind::FuncDeclaration(func) => resolve_cfg_block(&mut func.body, defines),
+        StmtKind::ClassDeclaration(class) => {
+            for prop in &mut class.properties {
+                resolve_cfg_expr(&mut prop.value, defines);
+            }
+            for method in &mut class.methods {
+                resolve_cfg_block(&mut method.body, defines);
+            }
+        }
+        StmtKind::Conditional(cond) => return resolve_cfg_conditional(cond, defines),
+        StmtKind::Loop(LoopStmt::While {
+            condition,
+            body,
+            else_block,
+        }) => {
+            resolve_cfg_expr(condition, defines);
+            resolve_cfg_block(body, defines);
+            if let Some(else_body) = else_block {
+                resolve_cfg_block(else_body, defines);
+            }
+        }
+        StmtKind::Loop(LoopStmt::For {
+            iterable,
+            body,
+            else_block,
+            ..
+        }) => {
+            resolve_cfg_expr(iterable, defines);
+            resolve_cfg_block(body, defines);
+            if let Some(else_body) = else_block {
+                resolve_cfg_block(else_body, defines);
+            }
+        }
+        StmtKind::ListDeclaration(d) => {
+            resolve_cfg_expr(&mut d.value, defines);
+        }
+        StmtKind::ListPush(push) => {
+            resolve_cfg_expr(&mut push.value, defines);
+        }
+        StmtKind::DictDeclaration(d) => {
+            resolve_cfg_expr(&mut d.value, defines);
+        }
+        StmtKind::DictSet(set) => {
+            resolve_cfg_expr(&mut set.key, defines);
+            resolve_cfg_expr(&mut set.value, defines);
+        }
+        StmtKind::UnsafeBlock(body)
+        | StmtKind::Scope { body, .. }
+        | StmtKind::Zone { body, .. } => resolve_cfg_block(body, defines),
+        StmtKind::TryCatch { body, handler, .. } => {
+            resolve_cfg_block(body, defines);
+            resolve_cfg_block(handler, defines);
+        }
+        StmtKind::Input { .. }
+        | StmtKind::Import { .. }
+        | StmtKind::ImportCOm { .. }
+        | StmtKind::FromImport { .. }
+        | StmtKind::Promote { .. }
+        | StmtKind::EnumDeclaration { .. }
+        | StmtKind::TypeAlias { .. }
+        | StmtKind::Entangle { .. } => {}
+    }
+    true
+}
+
+/// Drops the branches a `cfg` condition turned off. A branch that is always
+/// taken becomes the `else` and ends the chain. When only that branch is
+/// left it stays an `if true`, keeping its own scope.
+fn resolve_cfg_conditional(cond: &mut ConditionalStmt, defines: &BTreeMap<String, String>) -> bool {
+    let blocks = std::iter::once(cond.if_block.clone()).chain(cond.elif_blocks.drain(..));
+    let mut kept: Vec<IfBlock> = Vec::new();
+    let mut else_block = cond.else_block.take();
+    for mut block in blocks {
+        let has_cfg = resolve_cfg_expr(&mut block.condition, defines);
+        match const_bool(&block.condition).filter(|_| has_cfg) {
+            Some(false) => {}
+            Some(true) => {
+                else_block = Some(block.body);
+                break;
+            }
+            None => kept.push(block),
+        }
+    }
+    if let Some(body) = &mut else_block {
+        resolve_cfg_block(body, defines);
+    }
+    for block in &mut kept {
+        resolve_cfg_block(&mut block.body, defines);
+    }
+
+    let mut kept = kept.into_iter();
+    match kept.next() {
+        Some(first) => {
+            cond.if_block = first;
+            cond.elif_blocks = kept.collect();
+            cond.else_block = else_block;
+            true
+        }
+        None => match else_block {
+            Some(body) => {
+                let condition = &cond.if_block.condition;
+                cond.if_block = IfBlock {
+                    condition: Expr::with_span(
+                        ExprKind::Literal(LiteralValue::Boolean(true)),
+                        condition.loc.clone(),
+                        condition.span,
+                    ),
+                    body,
+                };
+                true
+            }
+            None => false,
+        },
+    }
+}
+
+/// Value of a condition made only of boolean literals, `not`, `and` and
+/// `or`. The right side of `and`/`or` is only folded once the left side is
+/// known, so a call on the left is never dropped.
+fn const_bool(expr: &Expr) -> Option<bool> {
+    match &expr.kind {
+        ExprKind::Literal(LiteralValue::Boolean(b)) => Some(*b),
+        ExprKind::Unary {
+            op: UnaryOp::Not,
+            expr,
+        } => const_bool(expr).map(|b| !b),
+        ExprKind::Binary {
+            op: BinaryOp::And,
+            left,
+            right,
+        } => match const_bool(left)? {
+            false => Some(false),
+            true => const_bool(right),
+        },
+        ExprKind::Binary {
+            op: BinaryOp::Or,
+            left,
+            right,
+        } => match const_bool(left)? {
+            true => Some(true),
+            false => const_bool(right),
+        },
+        _ => None,
+    }
+}
+
+/// Resolves the `cfg` calls in `expr`; `true` when there was at least one.
+fn resolve_cfg_expr(expr: &mut Expr, defines: &BTreeMap<String, String>) -> bool {
+    if let Some(key) = cfg_key(expr) {
+        let enabled = cfg_enabled(defines, key);
+        expr.kind = ExprKind::Literal(LiteralValue::Boolean(enabled));
+        return true;
+    }
+    let mut found = false;
+    match &mut expr.kind {
+        ExprKind::Literal(LiteralValue::List(items))
+        | ExprKind::New { args: items, .. }
+        | ExprKind::Tuple(items) => {
+            for item in items {
+                found |= resolve_cfg_expr(item, defines);
+            }
+        }
+        ExprKind::Literal(LiteralValue::Dict(pairs)) => {
+            for (key, value) in pairs {
+                found |= resolve_cfg_expr(key, defines);
+                found |= resolve_cfg_expr(value, defines);
+            }
+        }
+        ExprKind::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                found |= resolve_cfg_expr(value, defines);
+            }
+        }
+        ExprKind::Unary { expr, .. } => found |= resolve_cfg_expr(expr, defines),
+        ExprKind::Binary { left, right, .. } => {
+            found |= resolve_cfg_expr(left, defines);
+            found |= resolve_cfg_expr(right, defines);
+        }
+        ExprKind::FunctionCall { callee, args } => {
+            found |= resolve_cfg_expr(callee, defines);
+            for arg in args {
+                found |= resolve_cfg_expr(arg, defines);
+            }
+        }
+        ExprKind::PropertyAccess { target, .. } => found |= resolve_cfg_expr(target, defines),
+        ExprKind::IndexAccess { target, index } => {
+            found |= resolve_cfg_expr(target, defines);
+            found |= resolve_cfg_expr(index, defines);
+        }
+        ExprKind::Slice {
+            target,
+            start,
+            end,
+            step,
+        } => {
+            found |= resolve_cfg_expr(target, defines);
+            for bound in [start, end, step].into_iter().flatten() {
+                found |= resolve_cfg_expr(bound, defines);
+            }
+        }
+        ExprKind::Literal(_) | ExprKind::Variable(_) => {}
+    }
+    found
+}
+
+/// The key of a `cfg("KEY")` call. Other shapes are left to the analyzer.
+fn cfg_key(expr: &Expr) -> Option<&str> {
+    let ExprKind::FunctionCall { callee, args } = &expr.kind else {
+        return None;
+    };
+    match (&callee.kind, args.as_slice()) {
+        (ExprKind::Variable(name), [arg]) if name == "cfg" => match &arg.kind {
+            ExprKind::Literal(LiteralValue::String(key)) => Some(key),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eliminate_dead_functions, resolve_cfg};
+    use crate::ast::{ExprKind, LiteralValue, Stmt, StmtKind};
+    use crate::parser::Parser;
+
+    fn function_names(source: &str) -> (Vec<String>, Vec<String>) {
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+        let mut removed = eliminate_dead_functions(&mut program);
+        removed.sort();
+        let kept = program
+            .iter()
+            .filter_map(|stmt| match &stmt.kind {
+                StmtKind::FuncDeclaration(func) => Some(func.name.clone()),
+                _ => None,
+            })
+            .collect();
+        (kept, removed)
+    }
+
+    #[test]
+    fn unreachable_functions_are_dropped_transitively() {
+        let source = "fun leaf() : int\n    return 1\n\
+fun used() : int\n    return leaf()\n\
+fun unused() : int\n    return leaf()\n\
+class main\n    fun start()\n        print(used())\n";
+        let (kept, removed) = function_names(source);
+        assert_eq!(kept, vec!["leaf".to_string(), "used".to_string()]);
+        assert_eq!(removed, vec!["unused".to_string()]);
+    }
+
+    #[test]
+    fn functions_passed_as_values_or_by_name_are_kept() {
+        let source = "fun double(n: int) : int\n    return n * 2\n\
+fun on_save()\n    print(\"saved\")\n\
+fun apply(f, n: int)\n    return f(n)\n\
+class main\n    fun start()\n        print(apply(double, 2))\n        print(\"on_save\")\n";
+        let (kept, removed) = function_names(source);
+        assert_eq!(kept.len(), 3, "{kept:?}");
+        assert!(removed.is_empty(), "{removed:?}");
+    }
+
+    /// Prints and `if` structure of `class main`'s `start` after `resolve_cfg`.
+    fn start_after_cfg(source: &str, defines: &[(&str, &str)]) -> Vec<String> {
+        fn outline(body: &[Stmt], out: &mut Vec<String>) {
+            for stmt in body {
+                match &stmt.kind {
+                    StmtKind::Print(print) => match &print.args[0].kind {
+                        ExprKind::Literal(LiteralValue::String(s)) => out.push(s.clone()),
+                        other => panic!("unexpected print argument {other:?}"),
+                    },
+                    StmtKind::Conditional(cond) => {
+                        let head = match &cond.if_block.condition.kind {
+                            ExprKind::Literal(LiteralValue::Boolean(true)) => "if true",
+                            _ => "if",
+                        };
+                        out.push(head.to_string());
+                        outline(&cond.if_block.body, out);
+                        for elif in &cond.elif_blocks {
+                            out.push("elif".to_string());
+                            outline(&elif.body, out);
+                        }
+                        if let Some(body) = &cond.else_block {
+                            out.push("else".to_string());
+                            outline(body, out);
+                        }
+                    }
+                    other => panic!("unexpected statement {other:?}"),
+                }
+            }
+        }
+
+        let mut parser = Parser::new(source).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+        let defines = defines
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        resolve_cfg(&mut program, &defines);
+        let StmtKind::ClassDeclaration(class) = &program[0].kind else {
+            panic!("expected class main");
+        };
+        let mut out = Vec::new();
+        outline(&class.methods[0].body, &mut out);
+        out
+    }
+
+    #[test]
+    fn cfg_branch_is_removed_when_the_define_is_unset() {
+        let source = r#"
+class main
+    fun start()
+        if cfg("x")
+            print("x")
+        print("done")
+"#;
+        assert_eq!(start_after_cfg(source, &[]), vec!["done"]);
+        assert_eq!(start_after_cfg(source, &[("x", "false")]), vec!["done"]);
+        assert_eq!(
+            start_after_cfg(source, &[("x", "true")]),
+            vec!["if true", "x", "done"]
+        );
+    }
+
+    #[test]
+    fn cfg_chains_keep_only_the_selected_branch() {
+        let source = r#"
+class main
+    fun start()
+        if cfg("debug")
+            print("debug")
+        elif cfg("release") and not cfg("tiny")
+            print("release")
+        elif n > 1
+            print("many")
+        else
+            print("other")
+"#;
+        assert_eq!(
+            start_after_cfg(source, &[("release", "1")]),
+            vec!["if true", "release"]
+        );
+        assert_eq!(
+            start_after_cfg(source, &[("release", "1"), ("tiny", "1")]),
+            vec!["if", "many", "else", "other"]
+        );
+        assert_eq!(
+            start_after_cfg(source, &[("debug", "yes"), ("release", "1")]),
+            vec!["if true", "debug"]
+        );
+    }
+}
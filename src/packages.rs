@@ -1,9 +1,10 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // Opção B (git): o registry é um repositório git local em ~/.snask/registry (clone/pull do SnaskPackages).
@@ -14,7 +15,14 @@ const REGISTRY_HTTP_FALLBACK_URL: &str =
 const BASE_PKG_URL: &str =
     "https://raw.githubusercontent.com/rancidavi-dotcom/SnaskPackages/main/packages/";
 
-#[derive(Deserialize, Debug)]
+/// How long a fetched registry is reused before hitting git/HTTP again.
+/// `SNASK_REGISTRY_TTL` overrides it (in seconds).
+pub const DEFAULT_REGISTRY_TTL_SECS: u64 = 15 * 60;
+
+/// Set by `--refresh` and `snask update`: skip the cache for this process.
+static FORCE_REGISTRY_REFRESH: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Package {
     version: String,
     #[serde(default)]
@@ -35,7 +43,7 @@ impl Package {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Registry {
     pub packages: HashMap<String, Package>,
 }
@@ -223,7 +231,91 @@ fn read_registry_from_repo(repo: &PathBuf) -> Result<Registry, String> {
     Ok(Registry { packages })
 }
 
+/// `~/.snask/registry_cache.json`: the last fetched registry and when.
+#[derive(Serialize, Deserialize, Debug)]
+struct RegistryCache {
+    fetched_at: u64,
+    registry: Registry,
+}
+
+pub fn set_force_registry_refresh(refresh: bool) {
+    FORCE_REGISTRY_REFRESH.store(refresh, Ordering::Relaxed);
+}
+
+fn registry_ttl_secs() -> u64 {
+    std::env::var("SNASK_REGISTRY_TTL")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_REGISTRY_TTL_SECS)
+}
+
+fn read_registry_cache(path: &Path) -> Option<RegistryCache> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Returns the cached registry while it is younger than `ttl_secs` (unless
+/// `refresh`), otherwise calls `fetch` and caches the result. When `fetch`
+/// fails, a stale cache is used with a warning instead of failing.
+fn load_registry_cached(
+    cache_path: &Path,
+    ttl_secs: u64,
+    now: u64,
+    refresh: bool,
+    fetch: impl FnOnce() -> Result<Registry, String>,
+) -> Result<Registry, String> {
+    let cached = read_registry_cache(cache_path);
+    if let Some(cache) = &cached {
+        if !refresh && now.saturating_sub(cache.fetched_at) < ttl_secs {
+            return Ok(cached.unwrap().registry);
+        }
+    }
+
+    match fetch() {
+        Ok(registry) => {
+            let cache = RegistryCache {
+                fetched_at: now,
+                registry,
+            };
+            // The cache is only an optimization; failing to write it is fine.
+            if let Ok(json) = serde_json::to_vec(&cache) {
+                let _ = fs::write(cache_path, json);
+            }
+            Ok(cache.registry)
+        }
+        Err(e) => match cached {
+            Some(cache) => {
+                eprintln!(
+                    "⚠️  Could not update the registry; using the cached copy from {} min ago. ({})",
+                    now.saturating_sub(cache.fetched_at) / 60,
+                    e
+                );
+                Ok(cache.registry)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// The package registry, cached for `DEFAULT_REGISTRY_TTL_SECS` between
+/// commands. See `load_registry_cached`.
 pub fn fetch_registry() -> Result<Registry, String> {
+    let home = snask_home_dir()?;
+    fs::create_dir_all(&home).map_err(|e| format!("Failed to create {}: {}", home.display(), e))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    load_registry_cached(
+        &home.join("registry_cache.json"),
+        registry_ttl_secs(),
+        now,
+        FORCE_REGISTRY_REFRESH.load(Ordering::Relaxed),
+        fetch_registry_uncached,
+    )
+}
+
+fn fetch_registry_uncached() -> Result<Registry, String> {
     // Primeiro tenta o modo git (opção B). Se falhar, cai no HTTP como fallback.
     match ensure_registry_repo().and_then(|repo| read_registry_from_repo(&repo)) {
         Ok(r) => Ok(r),
@@ -254,6 +346,10 @@ pub fn is_package_installed(name: &str) -> bool {
 
 pub fn read_installed_package_sha256(name: &str) -> Result<String, String> {
     let packages_dir = get_packages_dir();
+    sha256_of_installed(&packages_dir, name)
+}
+
+fn sha256_of_installed(packages_dir: &Path, name: &str) -> Result<String, String> {
     let path = packages_dir.join(format!("{}.snask", name));
     let bytes = fs::read(&path)
         .map_err(|e| format!("Failed to read installed package {}: {}", path.display(), e))?;
@@ -412,24 +508,262 @@ pub fn list_packages() -> Result<(), String> {
     Ok(())
 }
 
-pub fn search_packages(query: &str) -> Result<(), String> {
-    println!("🔍 Pesquisando por '{}' no registry...", query);
+/// One match of `snask search`, as printed by `--json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub url: String,
+}
+
+/// Packages whose name or description contains `query`, sorted by name so
+/// `offset`/`limit` page through the same order on every run.
+pub fn search_registry(
+    registry: &Registry,
+    query: &str,
+    limit: Option<usize>,
+    offset: usize,
+) -> Vec<SearchResult> {
+    let mut names: Vec<&String> = registry
+        .packages
+        .iter()
+        .filter(|(name, package)| name.contains(query) || package.description.contains(query))
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|name| {
+            let package = &registry.packages[name];
+            SearchResult {
+                name: name.clone(),
+                version: package.version.clone(),
+                description: package.description.clone(),
+                url: package.url.clone(),
+            }
+        })
+        .collect()
+}
+
+pub fn search_packages(
+    query: &str,
+    json: bool,
+    limit: Option<usize>,
+    offset: usize,
+) -> Result<(), String> {
+    if !json {
+        println!("🔍 Pesquisando por '{}' no registry...", query);
+    }
 
     let registry: Registry = fetch_registry()?;
+    let results = search_registry(&registry, query, limit, offset);
 
-    let mut found = false;
-    for (name, package) in registry.packages {
-        if name.contains(query) || package.description.contains(query) {
-            println!("✨ {} (v{})", name, package.version);
-            println!("   Description: {}", package.description);
-            println!("   URL: {}", package.url);
-            println!("");
-            found = true;
-        }
+    if json {
+        let out = serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?;
+        println!("{}", out);
+        return Ok(());
     }
 
-    if !found {
+    for result in &results {
+        println!("✨ {} (v{})", result.name, result.version);
+        println!("   Description: {}", result.description);
+        println!("   URL: {}", result.url);
+        println!("");
+    }
+
+    if results.is_empty() {
         println!("Nenhum pacote encontrado para a busca: {}", query);
     }
     Ok(())
 }
+
+/// What `snask info` shows about a registry package.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackageInfo {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub url: String,
+    /// Hash of the installed `.snask` file, if the package is installed.
+    pub sha256: Option<String>,
+    pub installed: bool,
+}
+
+/// Looks `name` up in `registry` and checks `packages_dir` for an installed
+/// copy. Unknown names suggest the closest package.
+pub fn package_info(
+    registry: &Registry,
+    name: &str,
+    packages_dir: &Path,
+) -> Result<PackageInfo, String> {
+    let Some(package) = registry.packages.get(name) else {
+        let names: Vec<String> = registry.packages.keys().cloned().collect();
+        let mut msg = format!("Package '{}' not found in registry.", name);
+        if let Some((suggestion, _)) = crate::semantic_analyzer::suggest_name(name, &names) {
+            msg.push_str(&format!("\nhelp: did you mean '{}'?", suggestion));
+        } else {
+            msg.push_str("\nhelp: use `snask search <query>` to find packages.");
+        }
+        return Err(msg);
+    };
+    let installed = packages_dir.join(format!("{}.snask", name)).exists();
+    let sha256 = if installed {
+        Some(sha256_of_installed(packages_dir, name)?)
+    } else {
+        None
+    };
+    Ok(PackageInfo {
+        name: name.to_string(),
+        version: package.version.clone(),
+        description: package.description.clone(),
+        url: package.url.clone(),
+        sha256,
+        installed,
+    })
+}
+
+pub fn show_package_info(name: &str, json: bool) -> Result<(), String> {
+    let registry: Registry = fetch_registry()?;
+    let info = package_info(&registry, name, &get_packages_dir())?;
+
+    if json {
+        let out = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+        println!("{}", out);
+        return Ok(());
+    }
+
+    println!("📦 {} (v{})", info.name, info.version);
+    println!("   Description: {}", info.description);
+    println!("   URL: {}", info.url);
+    match &info.sha256 {
+        Some(sha) => {
+            println!("   Installed: yes");
+            println!("   SHA256: {}", sha);
+        }
+        None => println!("   Installed: no (use `snask install {}`)", info.name),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_registry_cached, package_info, read_registry_from_repo, search_registry};
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// A registry checkout with two packages in `index/` and one legacy entry.
+    fn fixture_registry(tag: &str) -> PathBuf {
+        let repo =
+            std::env::temp_dir().join(format!("snask_registry_{}_{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(repo.join("index/j")).unwrap();
+        fs::write(
+            repo.join("index/j/json_tools.json"),
+            r#"{"version": "1.2.0", "url": "json_tools.snask", "description": "JSON helpers"}"#,
+        )
+        .unwrap();
+        fs::write(
+            repo.join("index/j/json_schema.json"),
+            r#"{"version": "0.3.1", "description": "Validate json against a schema"}"#,
+        )
+        .unwrap();
+        fs::write(
+            repo.join("registry.json"),
+            r#"{"packages": {"http": {"version": "2.0.0", "description": "HTTP client"}}}"#,
+        )
+        .unwrap();
+        repo
+    }
+
+    #[test]
+    fn search_results_serialize_and_paginate() {
+        let repo = fixture_registry("search");
+        let registry = read_registry_from_repo(&repo).expect("fixture registry should load");
+        let _ = fs::remove_dir_all(&repo);
+
+        let all = search_registry(&registry, "json", None, 0);
+        let names: Vec<&str> = all.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["json_schema", "json_tools"]);
+
+        let value = serde_json::to_value(&all).unwrap();
+        assert_eq!(
+            value[1],
+            serde_json::json!({
+                "name": "json_tools",
+                "version": "1.2.0",
+                "description": "JSON helpers",
+                "url": "json_tools.snask",
+            })
+        );
+
+        let first = search_registry(&registry, "json", Some(1), 0);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].name, "json_schema");
+        let second = search_registry(&registry, "json", Some(1), 1);
+        assert_eq!(second[0].name, "json_tools");
+        assert!(search_registry(&registry, "json", None, 5).is_empty());
+    }
+
+    #[test]
+    fn info_reports_install_state_and_suggests_unknown_names() {
+        let repo = fixture_registry("info");
+        let registry = read_registry_from_repo(&repo).expect("fixture registry should load");
+        let packages_dir = repo.join("installed");
+        fs::create_dir_all(&packages_dir).unwrap();
+
+        let info = package_info(&registry, "http", &packages_dir).unwrap();
+        assert_eq!(info.version, "2.0.0");
+        assert!(!info.installed);
+        assert_eq!(info.sha256, None);
+
+        fs::write(packages_dir.join("http.snask"), "fun get(url)\n").unwrap();
+        let info = package_info(&registry, "http", &packages_dir).unwrap();
+        assert!(info.installed);
+        assert_eq!(info.sha256.as_deref().map(str::len), Some(64));
+        let value = serde_json::to_value(&info).unwrap();
+        assert_eq!(value["name"], "http");
+        assert_eq!(value["installed"], true);
+
+        let err = package_info(&registry, "json_tool", &packages_dir).unwrap_err();
+        let _ = fs::remove_dir_all(&repo);
+        assert!(err.contains("'json_tool' not found"), "{err}");
+        assert!(err.contains("did you mean 'json_tools'?"), "{err}");
+    }
+
+    #[test]
+    fn cached_registry_is_reused_until_the_ttl_expires() {
+        let repo = fixture_registry("cache");
+        let cache = repo.join("registry_cache.json");
+        let fetches = std::cell::Cell::new(0);
+        let fetch = || {
+            fetches.set(fetches.get() + 1);
+            read_registry_from_repo(&repo)
+        };
+
+        let registry = load_registry_cached(&cache, 900, 1_000, false, fetch).unwrap();
+        assert_eq!(registry.packages.len(), 3);
+        assert_eq!(fetches.get(), 1);
+
+        // Within the TTL: served from the cache.
+        load_registry_cached(&cache, 900, 1_899, false, fetch).unwrap();
+        assert_eq!(fetches.get(), 1);
+        // `--refresh` re-fetches even inside the window.
+        load_registry_cached(&cache, 900, 1_100, true, fetch).unwrap();
+        assert_eq!(fetches.get(), 2);
+        // Expired (fetched at 1_100, TTL 900): fetched again.
+        load_registry_cached(&cache, 900, 2_000, false, fetch).unwrap();
+        assert_eq!(fetches.get(), 3);
+
+        // Offline after expiry: the stale cache is used.
+        let offline = || Err("network unreachable".to_string());
+        let registry = load_registry_cached(&cache, 900, 9_000, false, offline).unwrap();
+        assert!(registry.packages.contains_key("http"));
+
+        let _ = fs::remove_dir_all(&repo);
+        let err = load_registry_cached(&cache, 900, 9_000, false, offline).unwrap_err();
+        assert_eq!(err, "network unreachable");
+    }
+}
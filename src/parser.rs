@@ -1,6 +1,7 @@
 use crate::ast::{
     BinaryOp, ConditionalStmt, ConstDecl, Expr, ExprKind, FuncDecl, IfBlock, LiteralValue,
-    Location, LoopStmt, MemoryStrategy, MutDecl, Program, Stmt, StmtKind, UnaryOp, VarDecl,
+    Location, LoopStmt, MemoryStrategy, MutDecl, PrintStmt, Program, Stmt, StmtKind, UnaryOp,
+    VarDecl,
 };
 use crate::span::{Position, Span};
 use crate::types::Type;
@@ -47,6 +48,13 @@ impl std::fmt::Display for ParseError {
     }
 }
 
+/// A piece of an `f"..."` literal: literal text or the raw source of a `{expr}`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum FStringPart {
+    Text(String),
+    Expr(String),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // Keywords
@@ -65,6 +73,9 @@ pub enum Token {
     Else(Location),
     While(Location),
     For(Location),
+    Try(Location),
+    Catch(Location),
+    Enum(Location),
     In(Location),
     Promote(Location),
     To(Location),
@@ -95,11 +106,13 @@ pub enum Token {
     Identifier(String, Location),
     Number(f64, Location),
     String(String, Location),
+    FString(Vec<FStringPart>, Location),
 
     // Operators
     Plus(Location),
     Minus(Location),
     Star(Location),
+    StarStar(Location),
     Slash(Location),
     DoubleSlash(Location),
     Percent(Location),
@@ -132,6 +145,7 @@ pub enum Token {
     RightBracket(Location),
     Comma(Location),
     Dot(Location),
+    QuestionDot(Location),
     Colon(Location),
     DoubleColon(Location),
     Semicolon(Location),
@@ -158,6 +172,9 @@ impl Token {
             | Token::Else(loc)
             | Token::While(loc)
             | Token::For(loc)
+            | Token::Try(loc)
+            | Token::Catch(loc)
+            | Token::Enum(loc)
             | Token::In(loc)
             | Token::Promote(loc)
             | Token::To(loc)
@@ -184,9 +201,11 @@ impl Token {
             | Token::Identifier(_, loc)
             | Token::Number(_, loc)
             | Token::String(_, loc)
+            | Token::FString(_, loc)
             | Token::Plus(loc)
             | Token::Minus(loc)
             | Token::Star(loc)
+            | Token::StarStar(loc)
             | Token::Slash(loc)
             | Token::DoubleSlash(loc)
             | Token::Percent(loc)
@@ -216,6 +235,7 @@ impl Token {
             | Token::RightBracket(loc)
             | Token::Comma(loc)
             | Token::Dot(loc)
+            | Token::QuestionDot(loc)
             | Token::Colon(loc)
             | Token::DoubleColon(loc)
             | Token::Semicolon(loc)
@@ -240,6 +260,9 @@ impl Token {
             Token::Else(_) => "'else'".to_string(),
             Token::While(_) => "'while'".to_string(),
             Token::For(_) => "'for'".to_string(),
+            Token::Try(_) => "'try'".to_string(),
+            Token::Catch(_) => "'catch'".to_string(),
+            Token::Enum(_) => "'enum'".to_string(),
             Token::In(_) => "'in'".to_string(),
             Token::Promote(_) => "'promote'".to_string(),
             Token::To(_) => "'to'".to_string(),
@@ -266,9 +289,11 @@ impl Token {
             Token::Identifier(name, _) => format!("identifier '{}'", name),
             Token::Number(n, _) => format!("number '{}'", n),
             Token::String(s, _) => format!("string \"{}\"", s),
+            Token::FString(_, _) => "f-string".to_string(),
             Token::Plus(_) => "'+'".to_string(),
             Token::Minus(_) => "'-'".to_string(),
             Token::Star(_) => "'*'".to_string(),
+            Token::StarStar(_) => "'**'".to_string(),
             Token::Slash(_) => "'/'".to_string(),
             Token::DoubleSlash(_) => "'//'".to_string(),
             Token::Percent(_) => "'%'".to_string(),
@@ -298,6 +323,7 @@ impl Token {
             Token::RightBracket(_) => "']'".to_string(),
             Token::Comma(_) => "','".to_string(),
             Token::Dot(_) => "'.'".to_string(),
+            Token::QuestionDot(_) => "'?.'".to_string(),
             Token::Colon(_) => "':'".to_string(),
             Token::DoubleColon(_) => "'::'".to_string(),
             Token::Semicolon(_) => "';'".to_string(),
@@ -306,10 +332,13 @@ impl Token {
     }
 }
 
+#[derive(Clone)]
 pub struct Tokenizer<'a> {
     chars: Peekable<Chars<'a>>,
     line: usize,
     column: usize,
+    /// Bytes consumed so far; the offset of the next character.
+    offset: usize,
     indent_stack: Vec<usize>,
     indent_unit: Option<usize>,
     pending_tokens: Vec<Token>,
@@ -322,6 +351,7 @@ impl<'a> Tokenizer<'a> {
             chars: input.chars().peekable(),
             line: 1,
             column: 1,
+            offset: 0,
             indent_stack: vec![0],
             indent_unit: None,
             pending_tokens: Vec::new(),
@@ -332,12 +362,13 @@ impl<'a> Tokenizer<'a> {
     fn advance(&mut self) -> Option<char> {
         let ch = self.chars.next();
         if let Some(c) = ch {
+            self.offset += c.len_utf8();
             if c == '\n' {
                 self.line += 1;
                 self.column = 1;
                 self.at_start_of_line = true;
             } else {
-                self.column += 1;
+                self.column += c.len_utf16();
             }
         }
         ch
@@ -360,6 +391,7 @@ impl<'a> Tokenizer<'a> {
         Location {
             line: self.line,
             column: self.column,
+            offset: self.offset,
         }
     }
 
@@ -464,7 +496,10 @@ impl<'a> Tokenizer<'a> {
             return Ok(Token::Newline(loc));
         }
 
-        let token = if ch.is_alphabetic() || ch == '_' {
+        let token = if ch == 'f' && self.peek() == Some(&'"') {
+            self.advance();
+            self.read_fstring(loc)?
+        } else if ch.is_alphabetic() || ch == '_' {
             self.read_identifier_or_keyword(ch, loc)
         } else if ch.is_digit(10) {
             self.read_number(ch, loc)
@@ -502,7 +537,9 @@ impl<'a> Tokenizer<'a> {
                     }
                 }
                 '*' => {
-                    if self.match_char('=') {
+                    if self.match_char('*') {
+                        Token::StarStar(loc)
+                    } else if self.match_char('=') {
                         Token::StarEqual(loc)
                     } else {
                         Token::Star(loc)
@@ -566,6 +603,16 @@ impl<'a> Tokenizer<'a> {
                         Token::Greater(loc)
                     }
                 }
+                '?' => {
+                    if self.match_char('.') {
+                        Token::QuestionDot(loc)
+                    } else {
+                        return Err(format!(
+                            "Unexpected character: {} at line {}, column {}",
+                            ch, loc.line, loc.column
+                        ));
+                    }
+                }
                 '"' => self.read_string(loc)?,
                 '@' => Token::At(loc),
                 _ => {
@@ -624,6 +671,9 @@ impl<'a> Tokenizer<'a> {
             "else" => Token::Else(loc),
             "while" => Token::While(loc),
             "for" => Token::For(loc),
+            "try" => Token::Try(loc),
+            "catch" => Token::Catch(loc),
+            "enum" => Token::Enum(loc),
             "in" => Token::In(loc),
             "promote" => Token::Promote(loc),
             "to" => Token::To(loc),
@@ -701,51 +751,7 @@ impl<'a> Tokenizer<'a> {
             }
             if c == '\\' {
                 self.advance();
-                let esc = self.advance().unwrap_or('\0');
-                match esc {
-                    '"' => s.push('"'),
-                    '\\' => s.push('\\'),
-                    'n' => s.push('\n'),
-                    'r' => s.push('\r'),
-                    't' => s.push('\t'),
-                    'b' => s.push('\x08'),
-                    'f' => s.push('\x0c'),
-                    'u' => {
-                        let mut hex = String::new();
-                        for _ in 0..4 {
-                            if let Some(h) = self.advance() {
-                                hex.push(h);
-                            } else {
-                                return Err(format!(
-                                    "Incomplete unicode escape (\\uXXXX) at line {}, column {}",
-                                    loc.line, loc.column
-                                ));
-                            }
-                        }
-                        if let Ok(code) = u32::from_str_radix(&hex, 16) {
-                            if let Some(ch) = char::from_u32(code) {
-                                s.push(ch);
-                            } else {
-                                return Err(format!(
-                                    "Invalid unicode escape (\\u{}) at line {}, column {}",
-                                    hex, loc.line, loc.column
-                                ));
-                            }
-                        } else {
-                            return Err(format!(
-                                "Invalid unicode escape (\\u{}) at line {}, column {}",
-                                hex, loc.line, loc.column
-                            ));
-                        }
-                    }
-                    '\0' => {
-                        return Err(format!(
-                            "Unterminated string (end of file) at line {}, column {}",
-                            loc.line, loc.column
-                        ));
-                    }
-                    other => s.push(other),
-                }
+                s.push(self.read_escape(&loc)?);
             } else {
                 s.push(self.advance().unwrap());
             }
@@ -759,6 +765,141 @@ impl<'a> Tokenizer<'a> {
         self.advance();
         Ok(Token::String(s, loc))
     }
+
+    /// Reads the character after a `\` inside a string literal.
+    fn read_escape(&mut self, loc: &Location) -> Result<char, String> {
+        let esc = self.advance().unwrap_or('\0');
+        match esc {
+            '"' => Ok('"'),
+            '\\' => Ok('\\'),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'b' => Ok('\x08'),
+            'f' => Ok('\x0c'),
+            'u' => {
+                let mut hex = String::new();
+                for _ in 0..4 {
+                    if let Some(h) = self.advance() {
+                        hex.push(h);
+                    } else {
+                        return Err(format!(
+                            "Incomplete unicode escape (\\uXXXX) at line {}, column {}",
+                            loc.line, loc.column
+                        ));
+                    }
+                }
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid unicode escape (\\u{}) at line {}, column {}",
+                            hex, loc.line, loc.column
+                        )
+                    })
+            }
+            '\0' => Err(format!(
+                "Unterminated string (end of file) at line {}, column {}",
+                loc.line, loc.column
+            )),
+            other => Ok(other),
+        }
+    }
+
+    /// Reads the body of `f"..."` (the `f"` is already consumed). Text keeps the
+    /// usual escapes plus `{{`/`}}`; each `{...}` is kept as raw source so the
+    /// parser can run the full expression grammar on it, nested strings included.
+    fn read_fstring(&mut self, loc: Location) -> Result<Token, String> {
+        let unterminated = |loc: &Location| {
+            format!(
+                "Unterminated f-string at line {}, column {} (hint: close it with '\"')",
+                loc.line, loc.column
+            )
+        };
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            let c = match self.peek().copied() {
+                Some('\n') | Some('\r') | None => return Err(unterminated(&loc)),
+                Some(c) => c,
+            };
+            self.advance();
+            match c {
+                '"' => break,
+                '\\' => text.push(self.read_escape(&loc)?),
+                '{' if self.match_char('{') => text.push('{'),
+                '}' if self.match_char('}') => text.push('}'),
+                '}' => {
+                    return Err(format!(
+                        "Single '}}' in f-string at line {}, column {} (hint: write '}}}}' for a literal brace)",
+                        loc.line, loc.column
+                    ));
+                }
+                '{' => {
+                    if !text.is_empty() {
+                        parts.push(FStringPart::Text(std::mem::take(&mut text)));
+                    }
+                    let expr = self.read_fstring_expr(&loc)?;
+                    if expr.trim().is_empty() {
+                        return Err(format!(
+                            "Empty expression '{{}}' in f-string at line {}, column {}",
+                            loc.line, loc.column
+                        ));
+                    }
+                    parts.push(FStringPart::Expr(expr));
+                }
+                other => text.push(other),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(FStringPart::Text(text));
+        }
+        Ok(Token::FString(parts, loc))
+    }
+
+    fn read_fstring_expr(&mut self, loc: &Location) -> Result<String, String> {
+        let mut expr = String::new();
+        let mut depth = 1;
+        let mut in_string = false;
+        loop {
+            let c = match self.peek().copied() {
+                Some('\n') | Some('\r') | None => {
+                    return Err(format!(
+                        "Unterminated '{{' in f-string at line {}, column {}",
+                        loc.line, loc.column
+                    ));
+                }
+                Some(c) => c,
+            };
+            self.advance();
+            if in_string {
+                if c == '\\' {
+                    expr.push(c);
+                    if let Some(next) = self.advance() {
+                        expr.push(next);
+                    }
+                    continue;
+                }
+                if c == '"' {
+                    in_string = false;
+                }
+            } else {
+                match c {
+                    '"' => in_string = true,
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(expr);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            expr.push(c);
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
@@ -768,6 +909,7 @@ enum Precedence {
     Or,         // or
     And,        // and
     Equality,   // == !=
+    Membership, // in
     BitOr,      // |
     BitXor,     // ^
     BitAnd,     // &
@@ -775,6 +917,7 @@ enum Precedence {
     Comparison, // < > <= >=
     Term,       // + -
     Factor,     // * /
+    Power,      // **
     Unary,      // - not
     Call,       // . ()
     Index,      // []
@@ -785,6 +928,9 @@ pub struct Parser<'a> {
     current_token: Token,
     peek_token: Token, // Adicionado para permitir o lookahead
     delimiter_stack: Vec<(Token, Span)>,
+    /// Set while parsing a slice bound, where `::` separates `start` from
+    /// `step` instead of naming a module member.
+    in_slice_bound: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -801,6 +947,7 @@ impl<'a> Parser<'a> {
             current_token,
             peek_token,
             delimiter_stack: Vec::new(),
+            in_slice_bound: false,
         })
     }
 
@@ -848,9 +995,19 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// `len` ASCII characters from `loc`.
     fn span_len(loc: &Location, len: usize) -> Span {
-        let start = Position::from_line_col(loc.line, loc.column);
-        let end = start.advance_cols(len.max(1));
+        Self::span_text(loc, len, len)
+    }
+
+    /// A token taking `bytes` bytes and `columns` columns of the source.
+    fn span_text(loc: &Location, bytes: usize, columns: usize) -> Span {
+        let start = Position::new(loc.line, loc.column, loc.offset);
+        let end = Position::new(
+            loc.line,
+            loc.column + columns.max(1),
+            loc.offset + bytes.max(1),
+        );
         Span::new(start, end)
     }
 
@@ -863,6 +1020,15 @@ impl<'a> Parser<'a> {
             Token::Identifier(s, _) => s.len().max(1),
             Token::Number(n, _) => format!("{}", n).len().max(1),
             Token::String(s, _) => (s.len() + 2).max(1),
+            Token::FString(parts, _) => {
+                3 + parts
+                    .iter()
+                    .map(|part| match part {
+                        FStringPart::Text(t) => t.len(),
+                        FStringPart::Expr(e) => e.len() + 2,
+                    })
+                    .sum::<usize>()
+            }
             Token::Let(_) => 3,
             Token::Mut(_) => 3,
             Token::Const(_) => 5,
@@ -878,6 +1044,9 @@ impl<'a> Parser<'a> {
             Token::Else(_) => 4,
             Token::While(_) => 5,
             Token::For(_) => 3,
+            Token::Try(_) => 3,
+            Token::Catch(_) => 5,
+            Token::Enum(_) => 4,
             Token::In(_) => 2,
             Token::Promote(_) => 7,
             Token::To(_) => 2,
@@ -895,7 +1064,7 @@ impl<'a> Parser<'a> {
             Token::And(_) => 3,
             Token::Or(_) => 2,
             Token::Not(_) => 3,
-            Token::DoubleSlash(_) => 2,
+            Token::DoubleSlash(_) | Token::StarStar(_) | Token::QuestionDot(_) => 2,
             Token::ShiftLeft(_) | Token::ShiftRight(_) => 2,
             Token::PlusEqual(_)
             | Token::MinusEqual(_)
@@ -911,9 +1080,28 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Columns (UTF-16 code units) taken by `tok`; differs from `token_len`
+    /// only for text that may hold non-ASCII characters.
+    fn token_columns(tok: &Token) -> usize {
+        match tok {
+            Token::Identifier(s, _) => s.encode_utf16().count().max(1),
+            Token::String(s, _) => s.encode_utf16().count() + 2,
+            Token::FString(parts, _) => {
+                3 + parts
+                    .iter()
+                    .map(|part| match part {
+                        FStringPart::Text(t) => t.encode_utf16().count(),
+                        FStringPart::Expr(e) => e.encode_utf16().count() + 2,
+                    })
+                    .sum::<usize>()
+            }
+            _ => Self::token_len(tok),
+        }
+    }
+
     fn token_span(tok: &Token) -> Span {
         let loc = tok.get_location().clone();
-        Self::span_len(&loc, Self::token_len(tok))
+        Self::span_text(&loc, Self::token_len(tok), Self::token_columns(tok))
     }
 
     fn skip_newlines(&mut self) -> ParseResult<()> {
@@ -1131,6 +1319,17 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_statement(&mut self) -> ParseResult<Stmt> {
+        // `type` is contextual so existing code can keep using it as a name.
+        if matches!(&self.current_token, Token::Identifier(s, _) if s == "type")
+            && matches!(self.peek_token, Token::Identifier(..))
+        {
+            return self.parse_type_alias();
+        }
+        if matches!(self.current_token, Token::Identifier(..))
+            && matches!(self.peek_token, Token::Comma(_))
+        {
+            return self.parse_tuple_assignment();
+        }
         if let Token::Identifier(_, loc) = self.current_token.clone() {
             let op_tok = match self.peek_token {
                 Token::Equal(_)
@@ -1214,7 +1413,7 @@ impl<'a> Parser<'a> {
 
         match self.current_token {
             Token::Newline(_) => {
-                self.consume_token(&Token::Newline(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Newline(Location::default()))?;
                 self.parse_statement()
             }
             Token::Let(_) => self.parse_var_declaration(),
@@ -1225,6 +1424,8 @@ impl<'a> Parser<'a> {
             Token::If(_) => self.parse_if_statement(),
             Token::While(_) => self.parse_while_statement(),
             Token::For(_) => self.parse_for_statement(),
+            Token::Try(_) => self.parse_try_statement(),
+            Token::Enum(_) => self.parse_enum_declaration(),
             Token::Fun(_) => self.parse_fun_declaration(),
             Token::Class(_) => self.parse_class_declaration(),
             Token::Return(_) => self.parse_return_statement(),
@@ -1233,11 +1434,11 @@ impl<'a> Parser<'a> {
             Token::From(_) => self.parse_from_import_statement(),
             Token::Promote(_) => {
                 let loc = self
-                    .consume_token(&Token::Promote(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::Promote(Location::default()))?
                     .get_location()
                     .clone();
                 let (target, _) = self.consume_identifier()?;
-                self.consume_token(&Token::To(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::To(Location::default()))?;
 
                 let mut to = MemoryStrategy::Default;
                 let (strat_name, strat_loc) = self.consume_identifier()?;
@@ -1263,7 +1464,7 @@ impl<'a> Parser<'a> {
             }
             Token::Scope(_) => {
                 let loc = self
-                    .consume_token(&Token::Scope(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::Scope(Location::default()))?
                     .get_location()
                     .clone();
                 let (name, _) = self.consume_identifier()?;
@@ -1276,15 +1477,12 @@ impl<'a> Parser<'a> {
             }
             Token::Zone(_) => {
                 let loc = self
-                    .consume_token(&Token::Zone(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::Zone(Location::default()))?
                     .get_location()
                     .clone();
                 let name = match self.current_token.clone() {
                     Token::String(s, _) => {
-                        self.consume_token(&Token::String(
-                            "".to_string(),
-                            Location { line: 0, column: 0 },
-                        ))?;
+                        self.consume_token(&Token::String("".to_string(), Location::default()))?;
                         s
                     }
                     _ => {
@@ -1308,11 +1506,11 @@ impl<'a> Parser<'a> {
             }
             Token::Entangle(_) => {
                 let loc = self
-                    .consume_token(&Token::Entangle(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::Entangle(Location::default()))?
                     .get_location()
                     .clone();
                 let (target, _) = self.consume_identifier()?;
-                self.consume_token(&Token::With(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::With(Location::default()))?;
                 let (anchor, _) = self.consume_identifier()?;
                 self.consume_end_of_statement()?;
                 Ok(Stmt::with_span(
@@ -1323,10 +1521,10 @@ impl<'a> Parser<'a> {
             }
             Token::At(_) => {
                 let loc = self
-                    .consume_token(&Token::At(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::At(Location::default()))?
                     .get_location()
                     .clone();
-                self.consume_token(&Token::Unsafe(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Unsafe(Location::default()))?;
 
                 if matches!(self.current_token, Token::Fun(_)) {
                     let mut stmt = self.parse_fun_declaration()?;
@@ -1411,13 +1609,15 @@ impl<'a> Parser<'a> {
                     };
 
                     let kind = match expr.clone().kind {
-                        ExprKind::PropertyAccess { target, property } => {
-                            StmtKind::PropertyAssignment(crate::ast::PropertyAssignment {
-                                target: *target,
-                                property,
-                                value,
-                            })
-                        }
+                        ExprKind::PropertyAccess {
+                            target,
+                            property,
+                            optional: false,
+                        } => StmtKind::PropertyAssignment(crate::ast::PropertyAssignment {
+                            target: *target,
+                            property,
+                            value,
+                        }),
                         ExprKind::IndexAccess { target, index } => {
                             StmtKind::IndexAssignment(crate::ast::IndexAssignment {
                                 target: *target,
@@ -1460,7 +1660,7 @@ impl<'a> Parser<'a> {
 
     fn parse_input_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Input(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Input(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
@@ -1482,15 +1682,12 @@ impl<'a> Parser<'a> {
 
     fn parse_import_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Import(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Import(Location::default()))?
             .get_location()
             .clone();
         let path = match self.current_token.clone() {
             Token::String(s, _) => {
-                self.consume_token(&Token::String(
-                    "".to_string(),
-                    Location { line: 0, column: 0 },
-                ))?;
+                self.consume_token(&Token::String("".to_string(), Location::default()))?;
                 s
             }
             _ => {
@@ -1506,26 +1703,52 @@ impl<'a> Parser<'a> {
             }
         };
 
+        let alias = self.parse_import_alias("SNASK-PARSE-IMPORT")?;
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc).merge(&Self::span1(&end_loc));
         Ok(Stmt {
-            kind: StmtKind::Import(path),
+            kind: StmtKind::Import { path, alias },
             loc: loc.clone(),
             span,
         })
     }
 
+    /// Parses an optional `as <name>` suffix for `import` / `from ... import`.
+    fn parse_import_alias(&mut self, code: &'static str) -> ParseResult<Option<String>> {
+        match self.current_token.clone() {
+            Token::Identifier(word, _) if word == "as" => {
+                self.advance()?;
+                match self.current_token.clone() {
+                    Token::Identifier(_, _) => {
+                        let (alias, _) = self.consume_identifier()?;
+                        Ok(Some(alias))
+                    }
+                    found => Err(ParseError::new(
+                        code,
+                        format!(
+                            "Expected an alias name after 'as', found {}.",
+                            found.friendly_name()
+                        ),
+                        Self::token_span(&found),
+                    )
+                    .with_help(
+                        "Write the alias as an identifier, e.g. `import \"utils/strings\" as strs`."
+                            .to_string(),
+                    )),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
     fn parse_import_c_om_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::ImportCOm(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::ImportCOm(Location::default()))?
             .get_location()
             .clone();
         let header = match self.current_token.clone() {
             Token::String(s, _) => {
-                self.consume_token(&Token::String(
-                    "".to_string(),
-                    Location { line: 0, column: 0 },
-                ))?;
+                self.consume_token(&Token::String("".to_string(), Location::default()))?;
                 s
             }
             _ => {
@@ -1569,7 +1792,7 @@ impl<'a> Parser<'a> {
 
     fn parse_from_import_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::From(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::From(Location::default()))?
             .get_location()
             .clone();
 
@@ -1580,7 +1803,7 @@ impl<'a> Parser<'a> {
 
         match self.current_token.clone() {
             Token::Slash(_) => {
-                self.consume_token(&Token::Slash(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Slash(Location::default()))?;
                 is_current_dir = true;
             }
             Token::Identifier(_, _) => {
@@ -1589,7 +1812,7 @@ impl<'a> Parser<'a> {
                     let (seg, _) = self.consume_identifier()?;
                     from_parts.push(seg);
                     if matches!(self.current_token, Token::Slash(_)) {
-                        self.consume_token(&Token::Slash(Location { line: 0, column: 0 }))?;
+                        self.consume_token(&Token::Slash(Location::default()))?;
                         continue;
                     }
                     break;
@@ -1608,9 +1831,10 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.consume_token(&Token::Import(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::Import(Location::default()))?;
 
         let (module, _) = self.consume_identifier()?;
+        let alias = self.parse_import_alias("SNASK-PARSE-FROM")?;
 
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc).merge(&Self::span1(&end_loc));
@@ -1619,6 +1843,7 @@ impl<'a> Parser<'a> {
                 from: from_parts,
                 is_current_dir,
                 module,
+                alias,
             },
             loc,
             span,
@@ -1627,7 +1852,7 @@ impl<'a> Parser<'a> {
 
     fn parse_block(&mut self) -> ParseResult<Vec<Stmt>> {
         if matches!(self.current_token, Token::Colon(_)) {
-            self.consume_token(&Token::Colon(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Colon(Location::default()))?;
         }
         // Se houver uma nova linha antes do bloco, consome.
         self.skip_newlines_only()?;
@@ -1635,7 +1860,7 @@ impl<'a> Parser<'a> {
         let mut stmts = Vec::new();
 
         if matches!(self.current_token, Token::LeftBrace(_)) {
-            self.consume_token(&Token::LeftBrace(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::LeftBrace(Location::default()))?;
             while !matches!(self.current_token, Token::RightBrace(_)) && !self.at_end() {
                 if matches!(self.current_token, Token::Newline(_))
                     || matches!(self.current_token, Token::Indent(_))
@@ -1647,21 +1872,21 @@ impl<'a> Parser<'a> {
                 stmts.push(self.parse_statement()?);
             }
             if !self.at_end() {
-                self.consume_token(&Token::RightBrace(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::RightBrace(Location::default()))?;
             }
         } else {
-            self.consume_token(&Token::Indent(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Indent(Location::default()))?;
             while !matches!(self.current_token, Token::Dedent(_)) && !self.at_end() {
                 // Ignora novas linhas vazias dentro do bloco
                 if let Token::Newline(_) = self.current_token {
-                    self.consume_token(&Token::Newline(Location { line: 0, column: 0 }))?;
+                    self.consume_token(&Token::Newline(Location::default()))?;
                     continue;
                 }
                 stmts.push(self.parse_statement()?);
             }
 
             if !self.at_end() {
-                self.consume_token(&Token::Dedent(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Dedent(Location::default()))?;
             }
         }
         Ok(stmts)
@@ -1669,7 +1894,7 @@ impl<'a> Parser<'a> {
 
     fn parse_class_declaration(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Class(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Class(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
@@ -1690,9 +1915,9 @@ impl<'a> Parser<'a> {
 
         let is_braced = matches!(self.current_token, Token::LeftBrace(_));
         if is_braced {
-            self.consume_token(&Token::LeftBrace(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::LeftBrace(Location::default()))?;
         } else {
-            self.consume_token(&Token::Indent(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Indent(Location::default()))?;
         }
 
         while (is_braced && !matches!(self.current_token, Token::RightBrace(_)))
@@ -1736,9 +1961,9 @@ impl<'a> Parser<'a> {
         }
 
         if is_braced {
-            self.consume_token(&Token::RightBrace(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::RightBrace(Location::default()))?;
         } else if !self.at_end() {
-            self.consume_token(&Token::Dedent(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Dedent(Location::default()))?;
         }
 
         let mut span = Self::span_len(&loc, 5);
@@ -1761,7 +1986,7 @@ impl<'a> Parser<'a> {
     }
     fn parse_if_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::If(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::If(Location::default()))?
             .get_location()
             .clone();
         let condition = self.parse_expression(Precedence::Assignment)?;
@@ -1776,7 +2001,7 @@ impl<'a> Parser<'a> {
         let mut elif_blocks = Vec::new();
 
         while let Token::Elif(_) = self.current_token {
-            self.consume_token(&Token::Elif(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Elif(Location::default()))?;
             let condition = self.parse_expression(Precedence::Assignment)?;
             let body = self.parse_block()?;
             span = span.merge(&condition.span);
@@ -1787,7 +2012,7 @@ impl<'a> Parser<'a> {
         }
 
         let else_block = if let Token::Else(_) = self.current_token {
-            self.consume_token(&Token::Else(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Else(Location::default()))?;
             let b = self.parse_block()?;
             if let Some(last) = b.last() {
                 span = span.merge(&last.span);
@@ -1810,7 +2035,7 @@ impl<'a> Parser<'a> {
 
     fn parse_while_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::While(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::While(Location::default()))?
             .get_location()
             .clone();
         let condition = self.parse_expression(Precedence::Assignment)?;
@@ -1819,8 +2044,13 @@ impl<'a> Parser<'a> {
         if let Some(last) = body.last() {
             span = span.merge(&last.span);
         }
+        let else_block = self.parse_loop_else(&mut span)?;
         Ok(Stmt::with_span(
-            StmtKind::Loop(LoopStmt::While { condition, body }),
+            StmtKind::Loop(LoopStmt::While {
+                condition,
+                body,
+                else_block,
+            }),
             loc,
             span,
         ))
@@ -1828,35 +2058,150 @@ impl<'a> Parser<'a> {
 
     fn parse_for_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::For(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::For(Location::default()))?
             .get_location()
             .clone();
         let (iterator, _) = self.consume_identifier()?;
-        self.consume_token(&Token::In(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::In(Location::default()))?;
         let iterable = self.parse_expression(Precedence::Assignment)?;
         let body = self.parse_block()?;
         let mut span = Self::span_len(&loc, 3).merge(&iterable.span);
         if let Some(last) = body.last() {
             span = span.merge(&last.span);
         }
+        let else_block = self.parse_loop_else(&mut span)?;
         Ok(Stmt::with_span(
             StmtKind::Loop(LoopStmt::For {
                 iterator,
                 iterable,
                 body,
+                else_block,
             }),
             loc,
             span,
         ))
     }
 
+    /// The optional `else` block right after a loop body.
+    fn parse_loop_else(&mut self, span: &mut Span) -> ParseResult<Option<Vec<Stmt>>> {
+        if !matches!(self.current_token, Token::Else(_)) {
+            return Ok(None);
+        }
+        self.consume_token(&Token::Else(Location::default()))?;
+        let block = self.parse_block()?;
+        if let Some(last) = block.last() {
+            *span = span.merge(&last.span);
+        }
+        Ok(Some(block))
+    }
+
+    fn parse_try_statement(&mut self) -> ParseResult<Stmt> {
+        let loc = self
+            .consume_token(&Token::Try(Location::default()))?
+            .get_location()
+            .clone();
+        let body = self.parse_block()?;
+        self.skip_newlines_only()?;
+        if !matches!(self.current_token, Token::Catch(_)) {
+            let found = self.current_token.clone();
+            return Err(ParseError::new(
+                "SNASK-PARSE-TRY",
+                format!(
+                    "Expected 'catch' after the 'try' block, found {}.",
+                    found.friendly_name()
+                ),
+                Self::token_span(&found),
+            )
+            .with_help("Add a handler: `catch err:` followed by an indented block.".to_string()));
+        }
+        self.consume_token(&Token::Catch(Location::default()))?;
+        let (err_name, _) = self.consume_identifier()?;
+        let handler = self.parse_block()?;
+        let mut span = Self::span_len(&loc, 3);
+        if let Some(last) = handler.last().or(body.last()) {
+            span = span.merge(&last.span);
+        }
+        Ok(Stmt::with_span(
+            StmtKind::TryCatch {
+                body,
+                err_name,
+                handler,
+            },
+            loc,
+            span,
+        ))
+    }
+
+    /// `enum Color: Red, Green, Blue` or `enum Color { Red, Green, Blue }`.
+    fn parse_enum_declaration(&mut self) -> ParseResult<Stmt> {
+        let loc = self
+            .consume_token(&Token::Enum(Location::default()))?
+            .get_location()
+            .clone();
+        let (name, name_loc) = self.consume_identifier()?;
+        let braced = matches!(self.current_token, Token::LeftBrace(_));
+        if braced {
+            self.consume_token(&Token::LeftBrace(Location::default()))?;
+            self.skip_newlines()?;
+        } else {
+            self.consume_token(&Token::Colon(Location::default()))?;
+        }
+
+        let mut variants: Vec<String> = Vec::new();
+        loop {
+            let (variant, variant_loc) = self.consume_identifier()?;
+            if variants.contains(&variant) {
+                return Err(ParseError::new(
+                    "SNASK-PARSE-ENUM",
+                    format!("Variant '{}' appears twice in enum '{}'.", variant, name),
+                    Self::span_len(&variant_loc, variant.len()),
+                ));
+            }
+            variants.push(variant);
+            if braced {
+                self.skip_newlines()?;
+            }
+            if !matches!(self.current_token, Token::Comma(_)) {
+                break;
+            }
+            self.consume_token(&Token::Comma(Location::default()))?;
+            if braced {
+                self.skip_newlines()?;
+                if matches!(self.current_token, Token::RightBrace(_)) {
+                    break;
+                }
+            }
+        }
+
+        let end_loc = if braced {
+            let close = self
+                .consume_token(&Token::RightBrace(Location::default()))?
+                .get_location()
+                .clone();
+            if matches!(self.current_token, Token::Semicolon(_) | Token::Newline(_)) {
+                self.consume_end_of_statement()?;
+            }
+            close
+        } else {
+            self.consume_end_of_statement()?
+        };
+        let span = Self::span_len(&loc, 4)
+            .merge(&Self::span_len(&name_loc, name.len()))
+            .merge(&Self::span1(&end_loc));
+        Ok(Stmt::with_span(
+            StmtKind::EnumDeclaration { name, variants },
+            loc,
+            span,
+        ))
+    }
+
     fn parse_fun_declaration(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Fun(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Fun(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
-        self.consume_token(&Token::LeftParen(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::LeftParen(Location::default()))?;
 
         let mut params = Vec::new();
         if !matches!(self.current_token, Token::RightParen(_)) {
@@ -1871,14 +2216,14 @@ impl<'a> Parser<'a> {
                 if !matches!(self.current_token, Token::Comma(_)) {
                     break;
                 }
-                self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Comma(Location::default()))?;
                 // Trailing comma support: if the next token is ')', we are done
                 if matches!(self.current_token, Token::RightParen(_)) {
                     break;
                 }
             }
         }
-        self.consume_token(&Token::RightParen(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::RightParen(Location::default()))?;
 
         let return_type: Option<Type> = self.parse_type_annotation()?;
         let body = self.parse_block()?;
@@ -1894,6 +2239,7 @@ impl<'a> Parser<'a> {
                 return_type,
                 body,
                 is_unsafe: false,
+                span,
             }),
             loc,
             span,
@@ -1902,10 +2248,11 @@ impl<'a> Parser<'a> {
 
     fn parse_return_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Return(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Return(Location::default()))?
             .get_location()
             .clone();
         let value = self.parse_expression(Precedence::Assignment)?;
+        let value = self.parse_tuple_tail(value)?;
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc)
             .merge(&value.span)
@@ -1913,9 +2260,80 @@ impl<'a> Parser<'a> {
         Ok(Stmt::with_span(StmtKind::Return(value), loc, span))
     }
 
+    /// Turns `first, second, ...` into a tuple expression; a lone expression is
+    /// returned unchanged.
+    fn parse_tuple_tail(&mut self, first: Expr) -> ParseResult<Expr> {
+        if !matches!(self.current_token, Token::Comma(_)) {
+            return Ok(first);
+        }
+        let loc = first.loc.clone();
+        let mut span = first.span;
+        let mut items = vec![first];
+        while matches!(self.current_token, Token::Comma(_)) {
+            self.consume_token(&Token::Comma(Location::default()))?;
+            let item = self.parse_expression(Precedence::Assignment)?;
+            span = span.merge(&item.span);
+            items.push(item);
+        }
+        Ok(Expr::with_span(ExprKind::Tuple(items), loc, span))
+    }
+
+    /// Parses the `, b, c` after the first name of a destructuring target.
+    fn parse_more_tuple_names(&mut self, first: String) -> ParseResult<Vec<String>> {
+        let mut names = vec![first];
+        while matches!(self.current_token, Token::Comma(_)) {
+            self.consume_token(&Token::Comma(Location::default()))?;
+            let (name, _) = self.consume_identifier()?;
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    fn parse_tuple_declaration(
+        &mut self,
+        loc: Location,
+        first: String,
+        mutable: bool,
+    ) -> ParseResult<Stmt> {
+        let names = self.parse_more_tuple_names(first)?;
+        self.consume_token(&Token::Equal(Location::default()))?;
+        let value = self.parse_expression(Precedence::Assignment)?;
+        let value = self.parse_tuple_tail(value)?;
+        let end_loc = self.consume_end_of_statement()?;
+        let span = Self::span1(&loc)
+            .merge(&value.span)
+            .merge(&Self::span1(&end_loc));
+        Ok(Stmt::with_span(
+            StmtKind::TupleDeclaration {
+                names,
+                mutable,
+                value,
+            },
+            loc,
+            span,
+        ))
+    }
+
+    fn parse_tuple_assignment(&mut self) -> ParseResult<Stmt> {
+        let (first, loc) = self.consume_identifier()?;
+        let names = self.parse_more_tuple_names(first)?;
+        self.consume_token(&Token::Equal(Location::default()))?;
+        let value = self.parse_expression(Precedence::Assignment)?;
+        let value = self.parse_tuple_tail(value)?;
+        let end_loc = self.consume_end_of_statement()?;
+        let span = Self::span1(&loc)
+            .merge(&value.span)
+            .merge(&Self::span1(&end_loc));
+        Ok(Stmt::with_span(
+            StmtKind::TupleAssignment { names, value },
+            loc,
+            span,
+        ))
+    }
+
     fn parse_type_annotation(&mut self) -> ParseResult<Option<Type>> {
         if matches!(self.current_token, Token::Colon(_)) {
-            self.consume_token(&Token::Colon(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Colon(Location::default()))?;
             let var_type = self.parse_type_name()?;
             Ok(Some(var_type))
         } else {
@@ -1928,15 +2346,27 @@ impl<'a> Parser<'a> {
             self.current_token = Token::Greater(loc);
             return Ok(());
         }
-        self.consume_token(&Token::Greater(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::Greater(Location::default()))?;
         Ok(())
     }
 
     fn parse_type_name(&mut self) -> ParseResult<Type> {
+        if matches!(self.current_token, Token::LeftParen(_)) {
+            return self.parse_tuple_type();
+        }
         let (type_name, loc) = match self.current_token.clone() {
             Token::Identifier(s, loc) => {
                 self.advance()?;
-                (s, loc)
+                // `ids::UserId` names a class or alias of an imported module.
+                let mut name = s;
+                while matches!(self.current_token, Token::DoubleColon(_))
+                    && matches!(self.peek_token, Token::Identifier(..))
+                {
+                    self.advance()?;
+                    let (part, _) = self.consume_identifier()?;
+                    name = format!("{name}::{part}");
+                }
+                (name, loc)
             }
             Token::List(loc) => {
                 self.advance()?;
@@ -1959,7 +2389,7 @@ impl<'a> Parser<'a> {
 
         let normalized = type_name.to_ascii_lowercase();
         if matches!(self.current_token, Token::Less(_)) {
-            self.consume_token(&Token::Less(Location { line: 0, column: 0 }))?;
+            self.consume_token(&Token::Less(Location::default()))?;
             match normalized.as_str() {
                 "list" => {
                     let inner = self.parse_type_name()?;
@@ -1968,7 +2398,7 @@ impl<'a> Parser<'a> {
                 }
                 "dict" => {
                     let key = self.parse_type_name()?;
-                    self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                    self.consume_token(&Token::Comma(Location::default()))?;
                     let value = self.parse_type_name()?;
                     self.consume_type_greater()?;
                     Ok(Type::DictOf(Box::new(key), Box::new(value)))
@@ -1976,23 +2406,100 @@ impl<'a> Parser<'a> {
                 _ => Err(ParseError::new(
                     "SNASK-PARSE-TYPE-GENERIC",
                     format!("Type '{}' does not accept generic parameters.", type_name),
-                    Span::single(Position::from_line_col(loc.line, loc.column)),
+                    loc.to_span(),
                 )
                 .with_help("Only list<T> and dict<K, V> are supported today.".to_string())),
             }
+        } else if let Ok(ty) = Type::from_str(normalized.as_str()) {
+            Ok(ty)
         } else {
-            Ok(Type::from_str(normalized.as_str()).unwrap_or(Type::User(type_name)))
+            Ok(Type::User(type_name))
+        }
+    }
+
+    fn parse_type_alias(&mut self) -> ParseResult<Stmt> {
+        let (_, loc) = self.consume_identifier()?;
+        let (name, name_loc) = self.consume_identifier()?;
+        let name_span = Self::span_len(&name_loc, name.len());
+        if Type::from_str(name.to_ascii_lowercase().as_str()).is_ok() {
+            return Err(ParseError::new(
+                "SNASK-PARSE-TYPE-ALIAS",
+                format!("'{}' is a built-in type and cannot be redefined.", name),
+                name_span,
+            ));
+        }
+        self.consume_token(&Token::Equal(Location::default()))?;
+        let target = self.parse_type_name()?;
+        if Self::type_mentions(&target, &name) {
+            return Err(ParseError::new(
+                "SNASK-PARSE-TYPE-ALIAS",
+                format!("Type alias '{}' refers to itself.", name),
+                name_span,
+            )
+            .with_help(
+                "An alias must name an existing type, e.g. `type UserId = int`.".to_string(),
+            ));
+        }
+        let end_loc = self.consume_end_of_statement()?;
+        let span = Self::span_len(&loc, 4)
+            .merge(&name_span)
+            .merge(&Self::span1(&end_loc));
+        Ok(Stmt::with_span(
+            StmtKind::TypeAlias { name, target },
+            loc,
+            span,
+        ))
+    }
+
+    fn type_mentions(ty: &Type, name: &str) -> bool {
+        match ty {
+            Type::User(n) => n == name,
+            Type::ListOf(inner) => Self::type_mentions(inner, name),
+            Type::DictOf(key, value) => {
+                Self::type_mentions(key, name) || Self::type_mentions(value, name)
+            }
+            Type::Function(params, ret) => {
+                params.iter().any(|p| Self::type_mentions(p, name))
+                    || Self::type_mentions(ret, name)
+            }
+            Type::Tuple(items) => items.iter().any(|t| Self::type_mentions(t, name)),
+            _ => false,
+        }
+    }
+
+    fn parse_tuple_type(&mut self) -> ParseResult<Type> {
+        let open = self
+            .consume_token(&Token::LeftParen(Location::default()))?
+            .get_location()
+            .clone();
+        let mut items = vec![self.parse_type_name()?];
+        while matches!(self.current_token, Token::Comma(_)) {
+            self.consume_token(&Token::Comma(Location::default()))?;
+            items.push(self.parse_type_name()?);
+        }
+        self.consume_token(&Token::RightParen(Location::default()))?;
+        if items.len() < 2 {
+            return Err(ParseError::new(
+                "SNASK-PARSE-TYPE",
+                "A tuple type needs at least two element types.".to_string(),
+                Self::span1(&open),
+            )
+            .with_help("Write the element type directly, or add more: `(int, str)`.".to_string()));
         }
+        Ok(Type::Tuple(items))
     }
 
     fn parse_var_declaration(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Let(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Let(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
+        if matches!(self.current_token, Token::Comma(_)) {
+            return self.parse_tuple_declaration(loc, name, false);
+        }
         let var_type = self.parse_type_annotation()?;
-        self.consume_token(&Token::Equal(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::Equal(Location::default()))?;
         let value = self.parse_expression(Precedence::Assignment)?;
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc)
@@ -2011,12 +2518,15 @@ impl<'a> Parser<'a> {
 
     fn parse_mut_declaration(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Mut(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Mut(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
+        if matches!(self.current_token, Token::Comma(_)) {
+            return self.parse_tuple_declaration(loc, name, true);
+        }
         let var_type = self.parse_type_annotation()?;
-        self.consume_token(&Token::Equal(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::Equal(Location::default()))?;
         let value = self.parse_expression(Precedence::Assignment)?;
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc)
@@ -2035,12 +2545,12 @@ impl<'a> Parser<'a> {
 
     fn parse_const_declaration(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Const(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Const(Location::default()))?
             .get_location()
             .clone();
         let (name, _) = self.consume_identifier()?;
         let var_type = self.parse_type_annotation()?;
-        self.consume_token(&Token::Equal(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::Equal(Location::default()))?;
         let value = self.parse_expression(Precedence::Assignment)?;
         let end_loc = self.consume_end_of_statement()?;
         let span = Self::span1(&loc)
@@ -2060,33 +2570,80 @@ impl<'a> Parser<'a> {
 
     fn parse_print_statement(&mut self) -> ParseResult<Stmt> {
         let loc = self
-            .consume_token(&Token::Print(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::Print(Location::default()))?
             .get_location()
             .clone();
-        self.consume_token(&Token::LeftParen(Location { line: 0, column: 0 }))?;
-        let mut expressions = Vec::new();
+        self.consume_token(&Token::LeftParen(Location::default()))?;
+        let mut print = PrintStmt {
+            args: Vec::new(),
+            sep: None,
+            end: None,
+        };
         if !matches!(self.current_token, Token::RightParen(_)) {
             loop {
-                expressions.push(self.parse_expression(Precedence::Assignment)?);
+                let is_option = matches!(self.peek_token, Token::Colon(_));
+                match self.current_token.clone() {
+                    Token::Identifier(name, name_loc) if is_option => {
+                        self.advance()?;
+                        self.consume_token(&Token::Colon(Location::default()))?;
+                        let value = self.parse_expression(Precedence::Assignment)?;
+                        let slot = match name.as_str() {
+                            "sep" => &mut print.sep,
+                            "end" => &mut print.end,
+                            _ => {
+                                return Err(ParseError::new(
+                                    "SNASK-PARSE-PRINT",
+                                    format!("Unknown print option '{}'.", name),
+                                    Self::span_len(&name_loc, name.len()),
+                                )
+                                .with_help("print accepts only `sep:` and `end:`.".to_string()));
+                            }
+                        };
+                        if slot.is_some() {
+                            return Err(ParseError::new(
+                                "SNASK-PARSE-PRINT",
+                                format!("print option '{}' was given more than once.", name),
+                                Self::span_len(&name_loc, name.len()),
+                            ));
+                        }
+                        *slot = Some(value);
+                    }
+                    found => {
+                        if print.sep.is_some() || print.end.is_some() {
+                            return Err(ParseError::new(
+                                "SNASK-PARSE-PRINT",
+                                "Positional print arguments must come before `sep:`/`end:`."
+                                    .to_string(),
+                                Self::token_span(&found),
+                            ));
+                        }
+                        print
+                            .args
+                            .push(self.parse_expression(Precedence::Assignment)?);
+                    }
+                }
                 if !matches!(self.current_token, Token::Comma(_)) {
                     break;
                 }
-                self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Comma(Location::default()))?;
             }
         }
-        self.consume_token(&Token::RightParen(Location { line: 0, column: 0 }))?;
+        self.consume_token(&Token::RightParen(Location::default()))?;
         let end_loc = self.consume_end_of_statement()?;
         let mut span = Self::span1(&loc).merge(&Self::span1(&end_loc));
-        for e in &expressions {
+        for e in print.exprs() {
             span = span.merge(&e.span);
         }
-        Ok(Stmt::with_span(StmtKind::Print(expressions), loc, span))
+        Ok(Stmt::with_span(StmtKind::Print(print), loc, span))
     }
 
     fn parse_expression(&mut self, precedence: Precedence) -> ParseResult<Expr> {
         let mut expr = self.parse_prefix()?;
 
         while precedence <= self.get_precedence(&self.current_token) {
+            if self.in_slice_bound && matches!(self.current_token, Token::DoubleColon(_)) {
+                break;
+            }
             expr = self.parse_infix(expr)?;
         }
 
@@ -2101,6 +2658,7 @@ impl<'a> Parser<'a> {
             Token::EqualEqual(_) | Token::TripleEqual(_) | Token::BangEqual(_) => {
                 Precedence::Equality
             }
+            Token::In(_) => Precedence::Membership,
             Token::Pipe(_) => Precedence::BitOr,
             Token::Caret(_) => Precedence::BitXor,
             Token::Ampersand(_) => Precedence::BitAnd,
@@ -2112,9 +2670,11 @@ impl<'a> Parser<'a> {
             Token::Star(_) | Token::Slash(_) | Token::DoubleSlash(_) | Token::Percent(_) => {
                 Precedence::Factor
             }
+            Token::StarStar(_) => Precedence::Power,
             Token::LeftParen(_) => Precedence::Call,
             Token::LeftBracket(_) => Precedence::Index,
-            Token::Dot(_) | Token::DoubleColon(_) => Precedence::Call, // Set Dot and DoubleColon precedence
+            // Set Dot and DoubleColon precedence
+            Token::Dot(_) | Token::QuestionDot(_) | Token::DoubleColon(_) => Precedence::Call,
             _ => Precedence::None,
         }
     }
@@ -2124,6 +2684,7 @@ impl<'a> Parser<'a> {
             Token::Plus(_) => Ok(BinaryOp::Add),
             Token::Minus(_) => Ok(BinaryOp::Subtract),
             Token::Star(_) => Ok(BinaryOp::Multiply),
+            Token::StarStar(_) => Ok(BinaryOp::Power),
             Token::Slash(_) => Ok(BinaryOp::Divide),
             Token::DoubleSlash(_) => Ok(BinaryOp::IntDivide),
             Token::Percent(_) => Ok(BinaryOp::Modulo),
@@ -2141,6 +2702,7 @@ impl<'a> Parser<'a> {
             Token::LessEqual(_) => Ok(BinaryOp::LessThanOrEquals),
             Token::Greater(_) => Ok(BinaryOp::GreaterThan),
             Token::GreaterEqual(_) => Ok(BinaryOp::GreaterThanOrEquals),
+            Token::In(_) => Ok(BinaryOp::In),
             _ => Err(ParseError::new(
                 "SNASK-PARSE-OP",
                 "Invalid binary operator.".to_string(),
@@ -2154,7 +2716,7 @@ impl<'a> Parser<'a> {
             return Ok(Expr::with_span(
                 ExprKind::Literal(LiteralValue::String(s.clone())),
                 loc.clone(),
-                Self::span_len(&loc, s.len().saturating_add(2)),
+                Self::span_text(&loc, s.len() + 2, s.encode_utf16().count() + 2),
             ));
         }
 
@@ -2245,21 +2807,91 @@ impl<'a> Parser<'a> {
         Ok(res)
     }
 
-    fn parse_prefix(&mut self) -> ParseResult<Expr> {
-        let loc = self.current_token.get_location().clone();
-        match self.current_token.clone() {
-            Token::Number(n, _) => {
-                self.consume_token(&Token::Number(0.0, loc.clone()))?;
-                let span = Self::span_len(&loc, format!("{}", n).len());
-                Ok(Expr::with_span(
-                    ExprKind::Literal(LiteralValue::Number(n)),
-                    loc,
-                    span,
-                ))
-            }
-            Token::String(s, _) => {
-                self.consume_token(&Token::String("".to_string(), loc.clone()))?;
-                self.parse_interpolated_string(s, loc)
+    /// Desugars `f"..."` into a `+` chain. The chain always starts from a
+    /// string so `f"{a + b}"` concatenates the sum instead of adding numbers.
+    fn parse_fstring(
+        &mut self,
+        parts: Vec<FStringPart>,
+        loc: Location,
+        span: Span,
+    ) -> ParseResult<Expr> {
+        let text = |t: String| {
+            Expr::with_span(
+                ExprKind::Literal(LiteralValue::String(t)),
+                loc.clone(),
+                span,
+            )
+        };
+        let mut exprs = Vec::new();
+        for part in parts {
+            match part {
+                FStringPart::Text(t) => exprs.push(text(t)),
+                FStringPart::Expr(src) => {
+                    let wrapped = format!("{};", src);
+                    let invalid = |detail: String| {
+                        ParseError::new(
+                            "SNASK-INTERPOLATION",
+                            format!("Invalid f-string expression '{}': {}", src.trim(), detail),
+                            span,
+                        )
+                    };
+                    let mut sub_p = Parser::new(&wrapped).map_err(|e| invalid(e.message))?;
+                    let expr = sub_p
+                        .parse_expression(Precedence::Assignment)
+                        .map_err(|e| invalid(e.message))?;
+                    if !matches!(sub_p.current_token, Token::Semicolon(_)) {
+                        return Err(invalid(format!(
+                            "unexpected {}",
+                            sub_p.current_token.friendly_name()
+                        )));
+                    }
+                    exprs.push(Expr::with_span(expr.kind, loc.clone(), span));
+                }
+            }
+        }
+
+        let starts_with_text = matches!(
+            exprs.first().map(|e| &e.kind),
+            Some(ExprKind::Literal(LiteralValue::String(_)))
+        );
+        if !starts_with_text {
+            exprs.insert(0, text(String::new()));
+        }
+        let mut res = exprs.remove(0);
+        for e in exprs {
+            res = Expr::with_span(
+                ExprKind::Binary {
+                    op: BinaryOp::Add,
+                    left: Box::new(res),
+                    right: Box::new(e),
+                },
+                loc.clone(),
+                span,
+            );
+        }
+        Ok(res)
+    }
+
+    fn parse_prefix(&mut self) -> ParseResult<Expr> {
+        let loc = self.current_token.get_location().clone();
+        match self.current_token.clone() {
+            Token::Number(n, _) => {
+                self.consume_token(&Token::Number(0.0, loc.clone()))?;
+                let span = Self::span_len(&loc, format!("{}", n).len());
+                Ok(Expr::with_span(
+                    ExprKind::Literal(LiteralValue::Number(n)),
+                    loc,
+                    span,
+                ))
+            }
+            Token::String(s, _) => {
+                self.consume_token(&Token::String("".to_string(), loc.clone()))?;
+                self.parse_interpolated_string(s, loc)
+            }
+            Token::FString(parts, _) => {
+                let token = self.consume_token(&Token::FString(Vec::new(), loc.clone()))?;
+                let span = Self::token_span(&token);
+                self.parse_fstring(parts, loc, span)
             }
             Token::True(_) => {
                 self.consume_token(&Token::True(loc.clone()))?;
@@ -2286,13 +2918,12 @@ impl<'a> Parser<'a> {
                 ))
             }
             Token::Identifier(s, _) => {
-                let len = s.len();
+                let span = Self::span_text(&loc, s.len(), s.encode_utf16().count());
                 self.consume_identifier()?;
-                Ok(Expr::with_span(
-                    ExprKind::Variable(s),
-                    loc.clone(),
-                    Self::span_len(&loc, len),
-                ))
+                if self.at_struct_literal(&s) {
+                    return self.parse_struct_literal(s, loc.clone(), span);
+                }
+                Ok(Expr::with_span(ExprKind::Variable(s), loc.clone(), span))
             }
             Token::SelfKw(_) => {
                 self.consume_token(&Token::SelfKw(loc.clone()))?;
@@ -2343,15 +2974,19 @@ impl<'a> Parser<'a> {
             }
             Token::LeftParen(_) => {
                 self.consume_token(&Token::LeftParen(loc))?;
-                let expr = self.parse_expression(Precedence::Assignment)?;
-                self.consume_token(&Token::RightParen(Location { line: 0, column: 0 }))?;
+                // `xs[(m::x)::2]`: inside parentheses `::` is a module path again.
+                let in_slice_bound = std::mem::replace(&mut self.in_slice_bound, false);
+                let expr = self.parse_expression(Precedence::Assignment);
+                self.in_slice_bound = in_slice_bound;
+                let expr = expr?;
+                self.consume_token(&Token::RightParen(Location::default()))?;
                 Ok(expr)
             }
             Token::LeftBracket(_) => self.parse_list_literal(),
             Token::LeftBrace(_) => self.parse_dict_literal(),
             Token::New(_) => {
                 let loc = self
-                    .consume_token(&Token::New(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::New(Location::default()))?
                     .get_location()
                     .clone();
 
@@ -2378,7 +3013,7 @@ impl<'a> Parser<'a> {
 
                 let (class_name, _) = self.consume_identifier()?;
 
-                self.consume_token(&Token::LeftParen(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::LeftParen(Location::default()))?;
                 let mut args = Vec::new();
                 if !matches!(self.current_token, Token::RightParen(_)) {
                     loop {
@@ -2386,11 +3021,11 @@ impl<'a> Parser<'a> {
                         if !matches!(self.current_token, Token::Comma(_)) {
                             break;
                         }
-                        self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                        self.consume_token(&Token::Comma(Location::default()))?;
                     }
                 }
                 let end_loc = self
-                    .consume_token(&Token::RightParen(Location { line: 0, column: 0 }))?
+                    .consume_token(&Token::RightParen(Location::default()))?
                     .get_location()
                     .clone();
                 let mut span = Self::span1(&loc).merge(&Self::span1(&end_loc));
@@ -2425,6 +3060,7 @@ impl<'a> Parser<'a> {
             Token::Plus(_)
             | Token::Minus(_)
             | Token::Star(_)
+            | Token::StarStar(_)
             | Token::Slash(_)
             | Token::DoubleSlash(_)
             | Token::Percent(_)
@@ -2441,7 +3077,8 @@ impl<'a> Parser<'a> {
             | Token::Less(_)
             | Token::LessEqual(_)
             | Token::Greater(_)
-            | Token::GreaterEqual(_) => {
+            | Token::GreaterEqual(_)
+            | Token::In(_) => {
                 let op = self.binary_op_from_token(&self.current_token)?;
                 let precedence = self.get_precedence(&self.current_token);
                 self.consume_token(&self.current_token.clone())?;
@@ -2459,24 +3096,22 @@ impl<'a> Parser<'a> {
             }
             Token::LeftParen(_) => self.parse_call_expression(left),
             Token::LeftBracket(_) => self.parse_index_access(left),
-            Token::Dot(_) | Token::DoubleColon(_) => {
+            Token::Dot(_) | Token::QuestionDot(_) | Token::DoubleColon(_) => {
                 let is_double_colon = matches!(self.current_token, Token::DoubleColon(_));
-                let dot_loc = if let Token::Dot(_) = self.current_token {
-                    self.consume_token(&Token::Dot(Location { line: 0, column: 0 }))?
-                        .get_location()
-                        .clone()
-                } else {
-                    self.consume_token(&Token::DoubleColon(Location { line: 0, column: 0 }))?
-                        .get_location()
-                        .clone()
-                };
+                // `a?.b` is nil when `a` is, instead of failing.
+                let optional = matches!(self.current_token, Token::QuestionDot(_));
+                let dot_token = self.current_token.clone();
+                let dot_loc = self.consume_token(&dot_token)?.get_location().clone();
                 let (property_name, _) = self.consume_identifier()?;
 
                 if is_double_colon {
                     // Trata como Namespace (combina os nomes)
                     if let ExprKind::Variable(base_name) = left.kind {
                         let combined_name = format!("{}::{}", base_name, property_name);
-                        let span = Self::span_len(&dot_loc, combined_name.len());
+                        // From the start of `base` to the end of `::property`.
+                        let span = left
+                            .span
+                            .merge(&Self::span_len(&dot_loc, 2 + property_name.len()));
                         let combined_expr =
                             Expr::with_span(ExprKind::Variable(combined_name), dot_loc, span);
                         if matches!(self.current_token, Token::LeftParen(_)) {
@@ -2487,33 +3122,23 @@ impl<'a> Parser<'a> {
                     }
                 }
 
+                let span = left
+                    .span
+                    .merge(&Self::span_len(&dot_loc, property_name.len()));
+                let access = Expr::with_span(
+                    ExprKind::PropertyAccess {
+                        target: Box::new(left),
+                        property: property_name,
+                        optional,
+                    },
+                    dot_loc,
+                    span,
+                );
                 if matches!(self.current_token, Token::LeftParen(_)) {
                     // It's a method call
-                    let span = left
-                        .span
-                        .merge(&Self::span_len(&dot_loc, property_name.len()));
-                    let callee = Expr::with_span(
-                        ExprKind::PropertyAccess {
-                            target: Box::new(left),
-                            property: property_name,
-                        },
-                        dot_loc,
-                        span,
-                    );
-                    self.parse_call_expression(callee)
+                    self.parse_call_expression(access)
                 } else {
-                    // It's a property access
-                    let span = left
-                        .span
-                        .merge(&Self::span_len(&dot_loc, property_name.len()));
-                    Ok(Expr::with_span(
-                        ExprKind::PropertyAccess {
-                            target: Box::new(left),
-                            property: property_name,
-                        },
-                        dot_loc,
-                        span,
-                    ))
+                    Ok(access)
                 }
             }
             _ => {
@@ -2529,7 +3154,7 @@ impl<'a> Parser<'a> {
 
     fn parse_call_expression(&mut self, callee: Expr) -> ParseResult<Expr> {
         let loc = self
-            .consume_token(&Token::LeftParen(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::LeftParen(Location::default()))?
             .get_location()
             .clone();
         let mut args = Vec::new();
@@ -2541,7 +3166,7 @@ impl<'a> Parser<'a> {
                 if !matches!(self.current_token, Token::Comma(_)) {
                     break;
                 }
-                self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Comma(Location::default()))?;
                 self.skip_newlines()?;
                 // Trailing comma support: if the next token is ')', we are done
                 if matches!(self.current_token, Token::RightParen(_)) {
@@ -2551,7 +3176,7 @@ impl<'a> Parser<'a> {
         }
         self.skip_newlines()?;
         let end_loc = self
-            .consume_token(&Token::RightParen(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::RightParen(Location::default()))?
             .get_location()
             .clone();
         let mut span = callee.span.merge(&Self::span1(&end_loc));
@@ -2571,7 +3196,7 @@ impl<'a> Parser<'a> {
 
     fn parse_list_literal(&mut self) -> ParseResult<Expr> {
         let loc = self
-            .consume_token(&Token::LeftBracket(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::LeftBracket(Location::default()))?
             .get_location()
             .clone();
         let mut elements = Vec::new();
@@ -2583,7 +3208,7 @@ impl<'a> Parser<'a> {
                 if !matches!(self.current_token, Token::Comma(_)) {
                     break;
                 }
-                self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Comma(Location::default()))?;
                 self.skip_newlines()?;
                 // Trailing comma support: if the next token is ']', we are done
                 if matches!(self.current_token, Token::RightBracket(_)) {
@@ -2593,7 +3218,7 @@ impl<'a> Parser<'a> {
         }
         self.skip_newlines()?;
         let end_loc = self
-            .consume_token(&Token::RightBracket(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::RightBracket(Location::default()))?
             .get_location()
             .clone();
         let mut span = Self::span1(&loc).merge(&Self::span1(&end_loc));
@@ -2609,7 +3234,7 @@ impl<'a> Parser<'a> {
 
     fn parse_dict_literal(&mut self) -> ParseResult<Expr> {
         let loc = self
-            .consume_token(&Token::LeftBrace(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::LeftBrace(Location::default()))?
             .get_location()
             .clone();
         let mut pairs = Vec::new();
@@ -2618,7 +3243,7 @@ impl<'a> Parser<'a> {
                 self.skip_newlines()?;
                 let key = self.parse_expression(Precedence::Assignment)?;
                 self.skip_newlines()?;
-                self.consume_token(&Token::Colon(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Colon(Location::default()))?;
                 self.skip_newlines()?;
                 let value = self.parse_expression(Precedence::Assignment)?;
                 pairs.push((key, value));
@@ -2626,7 +3251,7 @@ impl<'a> Parser<'a> {
                 if !matches!(self.current_token, Token::Comma(_)) {
                     break;
                 }
-                self.consume_token(&Token::Comma(Location { line: 0, column: 0 }))?;
+                self.consume_token(&Token::Comma(Location::default()))?;
                 self.skip_newlines()?;
                 // Trailing comma support: if the next token is '}', we are done
                 if matches!(self.current_token, Token::RightBrace(_)) {
@@ -2636,7 +3261,7 @@ impl<'a> Parser<'a> {
         }
         self.skip_newlines()?;
         let end_loc = self
-            .consume_token(&Token::RightBrace(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::RightBrace(Location::default()))?
             .get_location()
             .clone();
         let mut span = Self::span1(&loc).merge(&Self::span1(&end_loc));
@@ -2650,14 +3275,89 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    /// After a name, whether `{` opens a struct literal (`Point { x: 1 }`)
+    /// rather than a block: it has to be followed by `field:`, or be `{}`
+    /// after a capitalized name.
+    fn at_struct_literal(&self, name: &str) -> bool {
+        if !matches!(self.current_token, Token::LeftBrace(_)) {
+            return false;
+        }
+        let mut lookahead = self.tokenizer.clone();
+        let mut next = std::iter::once(Ok(self.peek_token.clone()))
+            .chain(std::iter::from_fn(move || Some(lookahead.next_token())))
+            .filter(|t| {
+                !matches!(
+                    t,
+                    Ok(Token::Newline(_) | Token::Indent(_) | Token::Dedent(_))
+                )
+            });
+        match (next.next(), next.next()) {
+            (Some(Ok(Token::Identifier(..))), Some(Ok(Token::Colon(_)))) => true,
+            (Some(Ok(Token::RightBrace(_))), _) => name.starts_with(char::is_uppercase),
+            _ => false,
+        }
+    }
+
+    /// `Point { x: 1, y: 2 }`, from the `{` after the class name.
+    fn parse_struct_literal(
+        &mut self,
+        class: String,
+        loc: Location,
+        span: Span,
+    ) -> ParseResult<Expr> {
+        self.consume_token(&Token::LeftBrace(Location::default()))?;
+        let mut fields: Vec<(String, Expr)> = Vec::new();
+        self.skip_newlines()?;
+        while !matches!(self.current_token, Token::RightBrace(_)) {
+            let (name, name_loc) = self.consume_identifier()?;
+            if fields.iter().any(|(given, _)| *given == name) {
+                return Err(ParseError::new(
+                    "SNASK-PARSE-EXPR",
+                    format!(
+                        "Field `{}` is given twice in this `{}` literal.",
+                        name, class
+                    ),
+                    Self::span_len(&name_loc, name.len()),
+                ));
+            }
+            self.skip_newlines()?;
+            self.consume_token(&Token::Colon(Location::default()))?;
+            self.skip_newlines()?;
+            let value = self.parse_expression(Precedence::Assignment)?;
+            fields.push((name, value));
+            self.skip_newlines()?;
+            if !matches!(self.current_token, Token::Comma(_)) {
+                break;
+            }
+            self.consume_token(&Token::Comma(Location::default()))?;
+            self.skip_newlines()?;
+        }
+        let end_loc = self
+            .consume_token(&Token::RightBrace(Location::default()))?
+            .get_location()
+            .clone();
+        Ok(Expr::with_span(
+            ExprKind::StructLiteral { class, fields },
+            loc,
+            span.merge(&Self::span1(&end_loc)),
+        ))
+    }
+
     fn parse_index_access(&mut self, target: Expr) -> ParseResult<Expr> {
         let loc = self
-            .consume_token(&Token::LeftBracket(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::LeftBracket(Location::default()))?
             .get_location()
             .clone();
-        let index = self.parse_expression(Precedence::Assignment)?;
+        let start = self.parse_slice_bound()?;
+        if matches!(self.current_token, Token::Colon(_) | Token::DoubleColon(_)) {
+            return self.parse_slice(target, start, loc);
+        }
+        let index = match start {
+            Some(index) => *index,
+            None => self.parse_expression(Precedence::Assignment)?,
+        };
         let end_loc = self
-            .consume_token(&Token::RightBracket(Location { line: 0, column: 0 }))?
+            .consume_token(&Token::RightBracket(Location::default()))?
             .get_location()
             .clone();
         let span = target.span.merge(&index.span).merge(&Self::span1(&end_loc));
@@ -2670,6 +3370,60 @@ impl<'a> Parser<'a> {
             span,
         ))
     }
+
+    /// The rest of `target[start:end:step]`, from the first `:`. The lexer
+    /// reads the `::` of `xs[::2]` as one token.
+    fn parse_slice(
+        &mut self,
+        target: Expr,
+        start: Option<Box<Expr>>,
+        loc: Location,
+    ) -> ParseResult<Expr> {
+        let (end, step) = if matches!(self.current_token, Token::DoubleColon(_)) {
+            self.consume_token(&Token::DoubleColon(Location::default()))?;
+            (None, self.parse_slice_bound()?)
+        } else {
+            self.consume_token(&Token::Colon(Location::default()))?;
+            let end = self.parse_slice_bound()?;
+            let step = if matches!(self.current_token, Token::Colon(_)) {
+                self.consume_token(&Token::Colon(Location::default()))?;
+                self.parse_slice_bound()?
+            } else {
+                None
+            };
+            (end, step)
+        };
+        let end_loc = self
+            .consume_token(&Token::RightBracket(Location::default()))?
+            .get_location()
+            .clone();
+        let span = target.span.merge(&Self::span1(&end_loc));
+        Ok(Expr::with_span(
+            ExprKind::Slice {
+                target: Box::new(target),
+                start,
+                end,
+                step,
+            },
+            loc,
+            span,
+        ))
+    }
+
+    /// One part of a slice, or `None` when it is left out (`xs[:2]`). A `::`
+    /// ends the bound, so `xs[i::2]` is a step and not a module path.
+    fn parse_slice_bound(&mut self) -> ParseResult<Option<Box<Expr>>> {
+        if matches!(
+            self.current_token,
+            Token::Colon(_) | Token::DoubleColon(_) | Token::RightBracket(_)
+        ) {
+            return Ok(None);
+        }
+        let in_slice_bound = std::mem::replace(&mut self.in_slice_bound, true);
+        let bound = self.parse_expression(Precedence::Assignment);
+        self.in_slice_bound = in_slice_bound;
+        Ok(Some(Box::new(bound?)))
+    }
 }
 
 pub fn parse_program(source: &str) -> ParseResult<Program> {
@@ -2677,6 +3431,12 @@ pub fn parse_program(source: &str) -> ParseResult<Program> {
     parser.parse_program()
 }
 
+/// Columns (UTF-16 code units) `tok` takes in the source, as the parser's
+/// spans count them.
+pub fn token_width(tok: &Token) -> usize {
+    Parser::token_columns(tok)
+}
+
 // Used by the LSP for semantic tokens / lightweight tooling.
 pub fn tokenize(source: &str) -> Result<Vec<Token>, String> {
     let mut tokenizer = Tokenizer::new(source);
@@ -2761,6 +3521,163 @@ class main
         assert_eq!(alias, "zlib");
     }
 
+    #[test]
+    fn parses_import_and_from_import_aliases() {
+        let src = r#"
+import "utils/strings" as strs
+from lib/text import fmt as f
+import "math"
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept import aliases");
+
+        let StmtKind::Import { path, alias } = &program[0].kind else {
+            panic!("expected import statement");
+        };
+        assert_eq!(path, "utils/strings");
+        assert_eq!(alias.as_deref(), Some("strs"));
+
+        let StmtKind::FromImport {
+            from,
+            module,
+            alias,
+            ..
+        } = &program[1].kind
+        else {
+            panic!("expected from-import statement");
+        };
+        assert_eq!(from, &vec!["lib".to_string(), "text".to_string()]);
+        assert_eq!(module, "fmt");
+        assert_eq!(alias.as_deref(), Some("f"));
+
+        let StmtKind::Import { alias, .. } = &program[2].kind else {
+            panic!("expected import statement");
+        };
+        assert!(alias.is_none());
+    }
+
+    #[test]
+    fn import_alias_requires_a_name() {
+        let mut p = Parser::new("import \"utils\" as 3\n").unwrap();
+        let err = p.parse_program().expect_err("alias must be an identifier");
+        assert_eq!(err.code, "SNASK-PARSE-IMPORT");
+    }
+
+    #[test]
+    fn parses_print_sep_and_end_options() {
+        let src = r#"
+class main
+    fun start()
+        print("a", end: "")
+        print("x", "y", sep: ", ")
+        print("plain")
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept print options");
+
+        let StmtKind::ClassDeclaration(class_decl) = &program[0].kind else {
+            panic!("expected class declaration");
+        };
+        let body = &class_decl.methods[0].body;
+        let string_lit = |e: &Option<Expr>| match e.as_ref().map(|e| &e.kind) {
+            Some(ExprKind::Literal(LiteralValue::String(s))) => Some(s.clone()),
+            _ => None,
+        };
+
+        let StmtKind::Print(first) = &body[0].kind else {
+            panic!("expected print");
+        };
+        assert_eq!(first.args.len(), 1);
+        assert_eq!(string_lit(&first.end).as_deref(), Some(""));
+        assert!(first.sep.is_none());
+
+        let StmtKind::Print(second) = &body[1].kind else {
+            panic!("expected print");
+        };
+        assert_eq!(second.args.len(), 2);
+        assert_eq!(string_lit(&second.sep).as_deref(), Some(", "));
+        assert!(second.end.is_none());
+
+        let StmtKind::Print(third) = &body[2].kind else {
+            panic!("expected print");
+        };
+        assert!(third.sep.is_none() && third.end.is_none());
+    }
+
+    #[test]
+    fn print_rejects_unknown_or_misplaced_options() {
+        let mut p = Parser::new("print(\"a\", flush: true)\n").unwrap();
+        let err = p.parse_program().expect_err("unknown option must fail");
+        assert_eq!(err.code, "SNASK-PARSE-PRINT");
+
+        let mut p = Parser::new("print(end: \"\", \"a\")\n").unwrap();
+        let err = p.parse_program().expect_err("positional after option must fail");
+        assert_eq!(err.code, "SNASK-PARSE-PRINT");
+    }
+
+    fn parse_single_expr(src: &str) -> Expr {
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("source should parse");
+        match &program[0].kind {
+            StmtKind::VarDeclaration(decl) => decl.value.clone(),
+            other => panic!("expected let declaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn fstring_interpolates_full_expressions_as_strings() {
+        let expr = parse_single_expr("let s = f\"{a + b}\"\n");
+        let ExprKind::Binary {
+            op: BinaryOp::Add,
+            left,
+            right,
+        } = expr.kind
+        else {
+            panic!("expected concatenation");
+        };
+        assert_eq!(
+            left.kind,
+            ExprKind::Literal(LiteralValue::String(String::new()))
+        );
+        assert!(matches!(
+            right.kind,
+            ExprKind::Binary {
+                op: BinaryOp::Add,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn fstring_escaped_braces_stay_literal() {
+        let expr = parse_single_expr("let s = f\"{{x}} = {x}\"\n");
+        let ExprKind::Binary { left, right, .. } = expr.kind else {
+            panic!("expected concatenation");
+        };
+        assert_eq!(
+            left.kind,
+            ExprKind::Literal(LiteralValue::String("{x} = ".to_string()))
+        );
+        assert_eq!(right.kind, ExprKind::Variable("x".to_string()));
+    }
+
+    #[test]
+    fn fstring_allows_nested_quotes_in_expressions() {
+        let expr = parse_single_expr("let s = f\"name: {user[\"name\"]}!\"\n");
+        let rendered = format!("{:?}", expr.kind);
+        assert!(rendered.contains("IndexAccess"));
+        assert!(rendered.contains("String(\"name\")"));
+        assert!(rendered.contains("String(\"!\")"));
+    }
+
+    #[test]
+    fn fstring_rejects_single_closing_brace() {
+        let err = Parser::new("let s = f\"oops }\"\n")
+            .and_then(|mut p| p.parse_program())
+            .expect_err("single '}' must fail");
+        assert_eq!(err.code, "SNASK-PARSE-TOKENIZE");
+    }
+
     #[test]
     fn parses_unsafe_zone_as_unsafe_block() {
         let src = r#"
@@ -2810,6 +3727,120 @@ class main
         assert_eq!(third.var_type, Some(Type::Usize));
     }
 
+    #[test]
+    fn bitwise_operators_bind_or_xor_and_shift_from_loosest() {
+        let src = "let a = 1 | 6 ^ 3 & 0xF0 >> 4\nlet b = ~x & 0b101\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept bitwise ops");
+        let binary = |expr: &Expr| match &expr.kind {
+            ExprKind::Binary { op, left, right } => (op.clone(), left.clone(), right.clone()),
+            other => panic!("expected binary expression, got {:?}", other),
+        };
+        let values: Vec<&Expr> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDeclaration(decl) => &decl.value,
+                other => panic!("expected let, got {:?}", other),
+            })
+            .collect();
+
+        let (op, _, xor) = binary(values[0]);
+        assert_eq!(op, BinaryOp::BitOr);
+        let (op, _, and) = binary(&xor);
+        assert_eq!(op, BinaryOp::BitXor);
+        let (op, _, shift) = binary(&and);
+        assert_eq!(op, BinaryOp::BitAnd);
+        let (op, hex, _) = binary(&shift);
+        assert_eq!(op, BinaryOp::ShiftRight);
+        assert!(matches!(hex.kind, ExprKind::Literal(LiteralValue::Number(n)) if n == 240.0));
+
+        let (op, not, mask) = binary(values[1]);
+        assert_eq!(op, BinaryOp::BitAnd);
+        assert!(matches!(
+            not.kind,
+            ExprKind::Unary {
+                op: UnaryOp::BitNot,
+                ..
+            }
+        ));
+        assert!(matches!(mask.kind, ExprKind::Literal(LiteralValue::Number(n)) if n == 5.0));
+    }
+
+    #[test]
+    fn optional_chaining_marks_each_question_dot_access() {
+        let src = "let a = p?.origin.x\nlet b = p?.origin?.moved(1)\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept `?.`");
+        let access = |expr: &Expr| match &expr.kind {
+            ExprKind::PropertyAccess {
+                target,
+                property,
+                optional,
+            } => (target.as_ref().clone(), property.clone(), *optional),
+            other => panic!("expected property access, got {:?}", other),
+        };
+        let values: Vec<&Expr> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDeclaration(decl) => &decl.value,
+                other => panic!("expected let, got {:?}", other),
+            })
+            .collect();
+
+        let (origin, x, optional) = access(values[0]);
+        assert_eq!((x.as_str(), optional), ("x", false));
+        let (_, origin, optional) = access(&origin);
+        assert_eq!((origin.as_str(), optional), ("origin", true));
+
+        let ExprKind::FunctionCall { callee, args } = &values[1].kind else {
+            panic!("expected a method call, got {:?}", values[1]);
+        };
+        assert_eq!(args.len(), 1);
+        let (origin, moved, optional) = access(callee);
+        assert_eq!((moved.as_str(), optional), ("moved", true));
+        assert!(access(&origin).2);
+
+        let err = Parser::new("p?.x = 1\n")
+            .unwrap()
+            .parse_program()
+            .expect_err("`?.` should not be assignable");
+        assert_eq!(err.code, "SNASK-PARSE-ASSIGN");
+        assert!(tokenize("p ? q").is_err());
+    }
+
+    #[test]
+    fn struct_literal_needs_a_name_before_the_fields() {
+        let src = r#"let p = Point { x: 1, y: 2 }
+let q = Point {
+    x: 3,
+    y: p.y,
+}
+if ready { print("go") }
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("struct literals should parse");
+        let literal = |stmt: &Stmt| {
+            let StmtKind::VarDeclaration(decl) = &stmt.kind else {
+                panic!("expected let, got {:?}", stmt);
+            };
+            let ExprKind::StructLiteral { class, fields } = &decl.value.kind else {
+                panic!("expected a struct literal, got {:?}", decl.value);
+            };
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            format!("{} {}", class, names.join(" "))
+        };
+        assert_eq!(literal(&program[0]), "Point x y");
+        assert_eq!(literal(&program[1]), "Point x y");
+        assert!(matches!(program[2].kind, StmtKind::Conditional(_)));
+
+        let err = Parser::new("let p = Point { x: 1, x: 2 }\n")
+            .unwrap()
+            .parse_program()
+            .expect_err("a field given twice should be rejected");
+        assert_eq!(err.code, "SNASK-PARSE-EXPR");
+        assert!(err.message.contains("`x` is given twice"));
+    }
+
     #[test]
     fn parse_program_recovering_caps_at_10_errors() {
         let mut src = String::new();
@@ -2820,4 +3851,448 @@ class main
         let (_prog, errs) = p.parse_program_recovering(10);
         assert_eq!(errs.len(), 10);
     }
+
+    #[test]
+    fn parses_try_catch_with_error_binding() {
+        let src = r#"
+class main
+    fun start()
+        try:
+            let text = sfs::read("missing.txt")
+            print(text)
+        catch err:
+            print(err)
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept try/catch");
+
+        let StmtKind::ClassDeclaration(class_decl) = &program[0].kind else {
+            panic!("expected class declaration");
+        };
+        let StmtKind::TryCatch {
+            body,
+            err_name,
+            handler,
+        } = &class_decl.methods[0].body[0].kind
+        else {
+            panic!("expected try/catch statement");
+        };
+        assert_eq!(body.len(), 2);
+        assert_eq!(err_name, "err");
+        assert_eq!(handler.len(), 1);
+    }
+
+    #[test]
+    fn try_without_catch_is_a_parse_error() {
+        let src = r#"
+class main
+    fun start()
+        try:
+            print(1)
+        print(2)
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let err = p.parse_program().expect_err("try needs a catch block");
+        assert_eq!(err.code, "SNASK-PARSE-TRY");
+    }
+
+    #[test]
+    fn parses_slices_with_optional_bounds_and_step() {
+        let src = "let a = [1, 2, 3, 4][1:3]\nlet b = xs[-2:]\n\
+            let c = s[::-1]\nlet d = xs[i]\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept slices");
+
+        let values: Vec<&ExprKind> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDeclaration(decl) => &decl.value.kind,
+                other => panic!("expected let, got {:?}", other),
+            })
+            .collect();
+        let ExprKind::Slice {
+            start, end, step, ..
+        } = values[0]
+        else {
+            panic!("expected slice, got {:?}", values[0]);
+        };
+        assert!(matches!(
+            start.as_deref().map(|e| &e.kind),
+            Some(ExprKind::Literal(LiteralValue::Number(n))) if *n == 1.0
+        ));
+        assert!(matches!(
+            end.as_deref().map(|e| &e.kind),
+            Some(ExprKind::Literal(LiteralValue::Number(n))) if *n == 3.0
+        ));
+        assert!(step.is_none());
+        assert!(matches!(
+            values[1],
+            ExprKind::Slice {
+                start: Some(_),
+                end: None,
+                step: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            values[2],
+            ExprKind::Slice {
+                start: None,
+                end: None,
+                step: Some(_),
+                ..
+            }
+        ));
+        assert!(matches!(values[3], ExprKind::IndexAccess { .. }));
+    }
+
+    #[test]
+    fn double_colon_in_a_slice_separates_start_and_step() {
+        let src = "let a = xs[1::2]\nlet b = xs[i::n]\nlet c = xs[(m::k)::2]\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept `::` slices");
+
+        let slices: Vec<(&Expr, &Expr)> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDeclaration(decl) => match &decl.value.kind {
+                    ExprKind::Slice {
+                        start: Some(start),
+                        end: None,
+                        step: Some(step),
+                        ..
+                    } => (start.as_ref(), step.as_ref()),
+                    other => panic!("expected start::step slice, got {:?}", other),
+                },
+                other => panic!("expected let, got {:?}", other),
+            })
+            .collect();
+        assert!(matches!(
+            (&slices[0].0.kind, &slices[0].1.kind),
+            (
+                ExprKind::Literal(LiteralValue::Number(a)),
+                ExprKind::Literal(LiteralValue::Number(b)),
+            ) if *a == 1.0 && *b == 2.0
+        ));
+        assert!(matches!(
+            (&slices[1].0.kind, &slices[1].1.kind),
+            (ExprKind::Variable(i), ExprKind::Variable(n)) if i == "i" && n == "n"
+        ));
+        assert!(matches!(
+            &slices[2].0.kind,
+            ExprKind::Variable(name) if name == "m::k"
+        ));
+    }
+
+    #[test]
+    fn in_binds_looser_than_comparison_and_tighter_than_and() {
+        let src = "let a = x in xs and k in d or 1 + 1 in ys\n\
+            let b = x in xs == true\nfor x in xs\n    print(x)\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept `in`");
+
+        let binary = |expr: &Expr| match &expr.kind {
+            ExprKind::Binary { op, left, right } => (op.clone(), left.clone(), right.clone()),
+            other => panic!("expected binary expression, got {:?}", other),
+        };
+        let StmtKind::VarDeclaration(a) = &program[0].kind else {
+            panic!("expected let");
+        };
+        let (op, and, last) = binary(&a.value);
+        assert_eq!(op, BinaryOp::Or);
+        let (op, first, second) = binary(&and);
+        assert_eq!(op, BinaryOp::And);
+        assert_eq!(binary(&first).0, BinaryOp::In);
+        assert_eq!(binary(&second).0, BinaryOp::In);
+        let (op, sum, _) = binary(&last);
+        assert_eq!(op, BinaryOp::In);
+        assert_eq!(binary(&sum).0, BinaryOp::Add);
+
+        let StmtKind::VarDeclaration(b) = &program[1].kind else {
+            panic!("expected let");
+        };
+        let (op, membership, _) = binary(&b.value);
+        assert_eq!(op, BinaryOp::Equals);
+        assert_eq!(binary(&membership).0, BinaryOp::In);
+        assert!(matches!(
+            program[2].kind,
+            StmtKind::Loop(LoopStmt::For { .. })
+        ));
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_multiply() {
+        let tokens = tokenize("a ** b * c *= d").expect("source should tokenize");
+        assert!(matches!(
+            tokens.as_slice(),
+            [
+                Token::Identifier(..),
+                Token::StarStar(_),
+                Token::Identifier(..),
+                Token::Star(_),
+                Token::Identifier(..),
+                Token::StarEqual(_),
+                Token::Identifier(..),
+                Token::Eof(_),
+            ]
+        ));
+
+        let src = "let a = 2 ** 3 ** 2\nlet b = 2 * 3 ** 2\nlet c = 2 ** 3 * 4\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept `**`");
+        let binary = |expr: &Expr| match &expr.kind {
+            ExprKind::Binary { op, left, right } => (op.clone(), left.clone(), right.clone()),
+            other => panic!("expected binary expression, got {:?}", other),
+        };
+        let values: Vec<&Expr> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDeclaration(decl) => &decl.value,
+                other => panic!("expected let, got {:?}", other),
+            })
+            .collect();
+
+        let (op, base, exponent) = binary(values[0]);
+        assert_eq!(op, BinaryOp::Power);
+        assert!(matches!(base.kind, ExprKind::Literal(LiteralValue::Number(n)) if n == 2.0));
+        assert_eq!(binary(&exponent).0, BinaryOp::Power);
+
+        let (op, _, right) = binary(values[1]);
+        assert_eq!(op, BinaryOp::Multiply);
+        assert_eq!(binary(&right).0, BinaryOp::Power);
+
+        let (op, left, _) = binary(values[2]);
+        assert_eq!(op, BinaryOp::Multiply);
+        assert_eq!(binary(&left).0, BinaryOp::Power);
+    }
+
+    #[test]
+    fn compound_index_assignment_reads_the_element_it_replaces() {
+        let src = "xs[0] = 1\nscores[\"bob\"] += 5\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p
+            .parse_program()
+            .expect("parser should accept index assignment");
+
+        let assignments: Vec<&crate::ast::IndexAssignment> = program
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::IndexAssignment(assign) => assign,
+                other => panic!("expected index assignment, got {:?}", other),
+            })
+            .collect();
+        assert!(matches!(
+            assignments[0].value.kind,
+            ExprKind::Literal(LiteralValue::Number(n)) if n == 1.0
+        ));
+        let ExprKind::Binary { op, left, .. } = &assignments[1].value.kind else {
+            panic!("expected `+=` to desugar, got {:?}", assignments[1].value);
+        };
+        assert_eq!(*op, BinaryOp::Add);
+        assert!(matches!(left.kind, ExprKind::IndexAccess { .. }));
+    }
+
+    #[test]
+    fn parses_else_blocks_after_while_and_for() {
+        let src = r#"
+class main
+    fun start()
+        mut i = 0
+        while i < 3
+            i = i + 1
+        else
+            print("done")
+            print(i)
+        for x in [1, 2]
+            print(x)
+        else:
+            print("empty or finished")
+        while i < 5
+            i = i + 1
+        print("no else")
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept loop else");
+
+        let StmtKind::ClassDeclaration(class_decl) = &program[0].kind else {
+            panic!("expected class declaration");
+        };
+        let body = &class_decl.methods[0].body;
+        let StmtKind::Loop(LoopStmt::While { else_block, .. }) = &body[1].kind else {
+            panic!("expected while loop");
+        };
+        assert_eq!(else_block.as_ref().map(Vec::len), Some(2));
+        let StmtKind::Loop(LoopStmt::For { else_block, .. }) = &body[2].kind else {
+            panic!("expected for loop");
+        };
+        assert_eq!(else_block.as_ref().map(Vec::len), Some(1));
+        let StmtKind::Loop(LoopStmt::While { else_block, .. }) = &body[3].kind else {
+            panic!("expected while loop");
+        };
+        assert_eq!(else_block, &None);
+        assert!(matches!(body[4].kind, StmtKind::Print(_)));
+    }
+
+    #[test]
+    fn parses_tuple_return_destructuring_and_swap() {
+        let src = r#"
+fun divmod(a: int, b: int): (int, int)
+    return a / b, a % b
+
+class main
+    fun start()
+        let q, r = divmod(7, 2)
+        mut a, b = q, r
+        a, b = b, a
+"#;
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept tuples");
+
+        let StmtKind::FuncDeclaration(divmod) = &program[0].kind else {
+            panic!("expected function declaration");
+        };
+        assert_eq!(divmod.return_type, Some(Type::Tuple(vec![Type::Int, Type::Int])));
+        let StmtKind::Return(ret) = &divmod.body[0].kind else {
+            panic!("expected return statement");
+        };
+        assert!(matches!(&ret.kind, ExprKind::Tuple(items) if items.len() == 2));
+
+        let StmtKind::ClassDeclaration(class_decl) = &program[1].kind else {
+            panic!("expected class declaration");
+        };
+        let body = &class_decl.methods[0].body;
+        let StmtKind::TupleDeclaration { names, mutable, .. } = &body[0].kind else {
+            panic!("expected tuple declaration");
+        };
+        assert_eq!(names, &["q", "r"]);
+        assert!(!mutable);
+        assert!(matches!(&body[1].kind, StmtKind::TupleDeclaration { mutable: true, .. }));
+
+        let StmtKind::TupleAssignment { names, value } = &body[2].kind else {
+            panic!("expected tuple assignment");
+        };
+        assert_eq!(names, &["a", "b"]);
+        let ExprKind::Tuple(items) = &value.kind else {
+            panic!("expected tuple on the right-hand side");
+        };
+        let rhs: Vec<_> = items
+            .iter()
+            .map(|item| match &item.kind {
+                ExprKind::Variable(name) => name.as_str(),
+                other => panic!("unexpected item {other:?}"),
+            })
+            .collect();
+        assert_eq!(rhs, ["b", "a"]);
+    }
+
+    #[test]
+    fn single_element_tuple_type_is_a_parse_error() {
+        let src = "fun one(): (int)
+    return 1
+";
+        let mut p = Parser::new(src).unwrap();
+        let err = p.parse_program().expect_err("(int) is not a tuple type");
+        assert_eq!(err.code, "SNASK-PARSE-TYPE");
+    }
+
+    #[test]
+    fn parses_enum_declarations_in_both_forms() {
+        let src = "enum Color: Red, Green, Blue\nenum Dir {\n    Up,\n    Down,\n}\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept enums");
+
+        let StmtKind::EnumDeclaration { name, variants } = &program[0].kind else {
+            panic!("expected enum declaration");
+        };
+        assert_eq!(name, "Color");
+        assert_eq!(variants, &["Red", "Green", "Blue"]);
+        let StmtKind::EnumDeclaration { name, variants } = &program[1].kind else {
+            panic!("expected braced enum declaration");
+        };
+        assert_eq!(name, "Dir");
+        assert_eq!(variants, &["Up", "Down"]);
+    }
+
+    #[test]
+    fn duplicate_enum_variant_is_a_parse_error() {
+        let mut p = Parser::new("enum Color: Red, Red\n").unwrap();
+        let err = p.parse_program().expect_err("variants must be unique");
+        assert_eq!(err.code, "SNASK-PARSE-ENUM");
+    }
+
+    #[test]
+    fn type_alias_and_qualified_type_names_are_left_for_the_analyzer() {
+        let src = "let id: UserId = 5\ntype UserId = int\nlet other: ids::UserId = 1\nlet type = 1\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("parser should accept type aliases");
+
+        let annotation = |stmt: &Stmt| match &stmt.kind {
+            StmtKind::VarDeclaration(decl) => decl.var_type.clone(),
+            other => panic!("expected let declaration, found {other:?}"),
+        };
+        assert_eq!(annotation(&program[0]), Some(Type::User("UserId".into())));
+        assert!(matches!(
+            &program[1].kind,
+            StmtKind::TypeAlias { name, target: Type::Int } if name == "UserId"
+        ));
+        assert_eq!(
+            annotation(&program[2]),
+            Some(Type::User("ids::UserId".into()))
+        );
+        assert!(matches!(&program[3].kind, StmtKind::VarDeclaration(d) if d.name == "type"));
+    }
+
+    #[test]
+    fn self_referential_or_builtin_type_alias_is_a_parse_error() {
+        for src in ["type Node = list<Node>\n", "type int = str\n"] {
+            let mut p = Parser::new(src).unwrap();
+            let err = p.parse_program().expect_err("alias should be rejected");
+            assert_eq!(err.code, "SNASK-PARSE-TYPE-ALIAS", "{src}");
+        }
+    }
+
+    #[test]
+    fn token_offsets_index_the_source_past_non_ascii_text() {
+        let src = "let café = \"é😀\"\nlet naïve = café\n";
+        let mut tokenizer = Tokenizer::new(src);
+        let mut seen = 0;
+        loop {
+            let token = tokenizer.next_token().expect("source should tokenize");
+            let rest = &src[token.get_location().offset..];
+            match &token {
+                Token::Eof(_) => break,
+                Token::Identifier(name, _) => assert!(rest.starts_with(name.as_str()), "{rest}"),
+                Token::String(text, _) => {
+                    assert!(rest.starts_with(&format!("\"{text}\"")), "{rest}")
+                }
+                Token::Let(_) => assert!(rest.starts_with("let"), "{rest}"),
+                Token::Equal(_) => assert!(rest.starts_with('='), "{rest}"),
+                _ => continue,
+            }
+            seen += 1;
+        }
+        assert_eq!(seen, 8);
+
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("source should parse");
+        let StmtKind::VarDeclaration(decl) = &program[1].kind else {
+            panic!("expected let declaration");
+        };
+        let span = decl.value.span;
+        assert_eq!(&src[span.start.offset..span.end.offset], "café");
+    }
+
+    #[test]
+    fn fstring_spans_count_columns_in_utf16_units() {
+        let src = "let x = 1\nlet s = f\"é😀{x}\"\n";
+        let mut p = Parser::new(src).unwrap();
+        let program = p.parse_program().expect("source should parse");
+        let StmtKind::VarDeclaration(decl) = &program[1].kind else {
+            panic!("expected let declaration");
+        };
+        let span = decl.value.span;
+        assert_eq!(&src[span.start.offset..span.end.offset], "f\"é😀{x}\"");
+        // `é` is one UTF-16 unit and `😀` two.
+        assert_eq!(span.end.column - span.start.column, 9);
+    }
 }
@@ -1,135 +1,309 @@
-use std::io::{self, Write};
+use crate::ast::{ExprKind, StmtKind};
+use crate::compiler::{build_source, BuildOptions, Verbosity};
+use crate::parser::{parse_program, Parser};
+use crate::semantic_analyzer::SemanticAnalyzer;
+use crate::types::Type;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::Command;
 
-/// REPL interativo para Snask
-pub struct Repl {
-    interpreter: crate::interpreter::Interpreter,
-    history: Vec<String>,
+/// File name shown in diagnostics for REPL input.
+pub const REPL_NAME: &str = "<repl>";
+
+/// Keywords that open an indented block; the REPL keeps reading lines until
+/// an empty one closes it.
+const BLOCK_KEYWORDS: &[&str] = &[
+    "fun", "class", "if", "elif", "else", "while", "for", "try", "catch", "enum", "unsafe",
+    "scope", "zone",
+];
+
+/// What a piece of input becomes in the synthesized program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// `fun`, `class`, `import`, ...: kept at the top level.
+    Declaration,
+    /// `let x = 1`, `print(x)`, loops...: kept inside `main::start`.
+    Statement,
+    /// A bare expression: printed once and not kept.
+    Expression,
+}
+
+/// The accumulated session. Every evaluation recompiles the whole session
+/// and runs it, printing only the output that the previous run did not.
+#[derive(Debug, Default)]
+pub struct ReplSession {
+    declarations: Vec<String>,
+    statements: Vec<String>,
+    printed: usize,
 }
 
-impl Repl {
+impl ReplSession {
     pub fn new() -> Self {
-        let mut interpreter = crate::interpreter::Interpreter::new();
-        crate::stdlib::register_stdlib(interpreter.get_globals_mut());
-        
-        Repl {
-            interpreter,
-            history: Vec::new(),
-        }
+        Self::default()
     }
 
-    pub fn run(&mut self) {
-        println!("╔═══════════════════════════════════════════════════════════╗");
-        println!("║          Snask REPL v0.2.0 - Interactive Shell           ║");
-        println!("╚═══════════════════════════════════════════════════════════╝");
-        println!();
-        println!("Type 'exit' or 'quit' to leave");
-        println!("Type 'help' to see available commands");
-        println!("Type 'clear' to clear history");
-        println!();
-
-        loop {
-            print!("snask> ");
-            io::stdout().flush().unwrap();
-
-            let mut input = String::new();
-            if io::stdin().read_line(&mut input).is_err() {
-                eprintln!("Failed to read input");
-                continue;
+    /// Classifies `input` by parsing it on its own.
+    pub fn classify(input: &str) -> Result<InputKind, String> {
+        let mut parser = Parser::new(input).map_err(|e| e.message)?;
+        let program = parser.parse_program().map_err(|e| e.message)?;
+        let kind = match program.last().map(|stmt| &stmt.kind) {
+            None => return Err("nothing to evaluate".to_string()),
+            Some(StmtKind::Expression(_) | StmtKind::FuncCall(_)) if program.len() == 1 => {
+                InputKind::Expression
             }
+            Some(
+                StmtKind::FuncDeclaration(_)
+                | StmtKind::ClassDeclaration(_)
+                | StmtKind::EnumDeclaration { .. }
+                | StmtKind::TypeAlias { .. }
+                | StmtKind::Import { .. }
+                | StmtKind::ImportCOm { .. }
+                | StmtKind::FromImport { .. },
+            ) => InputKind::Declaration,
+            Some(_) => InputKind::Statement,
+        };
+        Ok(kind)
+    }
 
-            let input = input.trim();
+    /// Whether `input` is a call to a function that returns nothing: a void
+    /// builtin such as `random_seed(7)`, or a session `fun f()` without a
+    /// return type that does not return a value. Such calls run as
+    /// statements instead of being wrapped in `print(...)`.
+    fn is_void_call(&self, input: &str) -> bool {
+        let Ok(program) = parse_program(input) else {
+            return false;
+        };
+        let [stmt] = program.as_slice() else {
+            return false;
+        };
+        let StmtKind::FuncCall(call) = &stmt.kind else {
+            return false;
+        };
+        let ExprKind::FunctionCall { callee, .. } = &call.kind else {
+            return false;
+        };
+        let ExprKind::Variable(name) = &callee.kind else {
+            return false;
+        };
+        let declared = self
+            .declarations
+            .iter()
+            .filter_map(|decl| parse_program(decl).ok())
+            .flatten()
+            .find_map(|stmt| match stmt.kind {
+                StmtKind::FuncDeclaration(func) if func.name == *name => Some(func),
+                _ => None,
+            });
+        if let Some(func) = declared {
+            return matches!(func.return_type, None | Some(Type::Void))
+                && !SemanticAnalyzer::body_guarantees_return(&func.body);
+        }
+        let analyzer = SemanticAnalyzer::new();
+        matches!(
+            analyzer.symbol_table.lookup(name).map(|s| &s.symbol_type),
+            Some(Type::Function(_, ret)) if **ret == Type::Void
+        )
+    }
 
-            if input.is_empty() {
-                continue;
-            }
+    /// The program for the session plus `input`: declarations at the top,
+    /// statements indented into `class main` / `fun start()`. Expressions are
+    /// wrapped in `print(...)`.
+    pub fn source_with(&self, input: &str, kind: InputKind) -> String {
+        let mut declarations = self.declarations.clone();
+        let mut statements = self.statements.clone();
+        match kind {
+            InputKind::Declaration => declarations.push(input.to_string()),
+            InputKind::Statement => statements.push(input.to_string()),
+            InputKind::Expression => statements.push(format!("print({})", input.trim())),
+        }
 
-            // Comandos especiais
-            match input {
-                "exit" | "quit" => {
-                    println!("Bye!");
-                    break;
-                }
-                "help" => {
-                    self.show_help();
-                    continue;
-                }
-                "clear" => {
-                    self.history.clear();
-                    println!("History cleared");
-                    continue;
-                }
-                "history" => {
-                    self.show_history();
-                    continue;
-                }
-                _ => {}
+        let mut source = String::new();
+        for decl in &declarations {
+            source.push_str(decl.trim_end());
+            source.push('\n');
+        }
+        source.push_str("class main\n    fun start()\n");
+        if statements.is_empty() {
+            source.push_str("        let __repl = 0\n");
+        }
+        for stmt in &statements {
+            for line in stmt.trim_end().lines() {
+                source.push_str("        ");
+                source.push_str(line);
+                source.push('\n');
             }
+        }
+        source
+    }
 
-            // Adicionar ao histórico
-            self.history.push(input.to_string());
+    /// Compiles and runs the session with `input` added. Returns the new
+    /// output; declarations and statements are kept only if the run worked.
+    pub fn eval(&mut self, input: &str) -> Result<String, String> {
+        let kind = match Self::classify(input)? {
+            InputKind::Expression if self.is_void_call(input) => InputKind::Statement,
+            kind => kind,
+        };
+        let source = self.source_with(input, kind);
+        let binary = std::env::temp_dir().join(format!("snask_repl_{}", std::process::id()));
+        let options = BuildOptions {
+            output_name: Some(binary.to_string_lossy().to_string()),
+            verbosity: Verbosity::Quiet,
+            ..Default::default()
+        };
+        build_source(REPL_NAME, &source, Path::new("."), options)?;
+        let output = Command::new(&binary).output();
+        let _ = std::fs::remove_file(&binary);
+        let output = output.map_err(|e| format!("repl: failed to run: {}", e))?;
 
-            // Tentar executar
-            self.execute(input);
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let fresh = stdout.get(self.printed..).unwrap_or_default().to_string();
+        if !output.status.success() {
+            return Err(format!(
+                "{}{}",
+                fresh,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        match kind {
+            InputKind::Declaration => self.declarations.push(input.to_string()),
+            InputKind::Statement => {
+                self.statements.push(input.to_string());
+                self.printed = stdout.len();
+            }
+            InputKind::Expression => {}
         }
+        Ok(fresh)
     }
+}
+
+/// Whether `line` opens an indented block (`fun f()`, `if x > 1`, ...).
+pub fn opens_block(line: &str) -> bool {
+    let first = line.split_whitespace().next().unwrap_or_default();
+    BLOCK_KEYWORDS.contains(&first)
+}
 
-    fn execute(&mut self, input: &str) {
-        // Adicionar ponto e vírgula se não tiver
-        let input_with_semicolon = if !input.ends_with(';') && !input.ends_with('}') {
-            format!("{};", input)
-        } else {
-            input.to_string()
+/// `snask repl`: reads input line by line and evaluates it. A line that opens
+/// a block continues until an empty line.
+pub fn run_repl() -> Result<(), String> {
+    println!("Snask REPL. Type `exit` to leave; an empty line ends a block.");
+    let mut session = ReplSession::new();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("snask> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
         };
+        let line = line.map_err(|e| e.to_string())?;
+        match line.trim() {
+            "" => continue,
+            "exit" | "quit" => return Ok(()),
+            _ => {}
+        }
 
-        // Parse e execute
-        match crate::parser::parse_program(&input_with_semicolon) {
-            Ok(program) => {
-                match self.interpreter.interpret(program) {
-                    crate::interpreter::InterpretResult::Ok => {
-                        // Sucesso silencioso
-                    }
-                    crate::interpreter::InterpretResult::RuntimeError(msg) => {
-                        eprintln!("❌ Runtime error: {}", msg);
+        let mut input = line.clone();
+        if opens_block(&line) {
+            loop {
+                print!("...    ");
+                io::stdout().flush().map_err(|e| e.to_string())?;
+                match lines.next() {
+                    Some(Ok(next)) if !next.trim().is_empty() => {
+                        input.push('\n');
+                        input.push_str(&next);
                     }
+                    _ => break,
                 }
             }
-            Err(e) => {
-                eprintln!("❌ Syntax error: {}", e.message);
-            }
         }
+        input.push('\n');
+
+        match session.eval(&input) {
+            Ok(output) => print!("{}", output),
+            Err(e) => eprintln!("{}", e.trim_end()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{opens_block, InputKind, ReplSession};
+    use crate::jit::shared_runtime_path;
+
+    #[test]
+    fn lets_are_kept_and_expressions_are_printed_once() {
+        let mut session = ReplSession::new();
+        assert_eq!(
+            ReplSession::classify("let x = 2\n"),
+            Ok(InputKind::Statement)
+        );
+        session.statements.push("let x = 2\n".to_string());
+        assert_eq!(
+            ReplSession::classify("fun triple(n: int) : int\n    return n * 3\n"),
+            Ok(InputKind::Declaration)
+        );
+        session
+            .declarations
+            .push("fun triple(n: int) : int\n    return n * 3\n".to_string());
+        assert_eq!(
+            ReplSession::classify("triple(x) + 1\n"),
+            Ok(InputKind::Expression)
+        );
+
+        let source = session.source_with("triple(x) + 1\n", InputKind::Expression);
+        assert_eq!(
+            source,
+            "fun triple(n: int) : int\n    return n * 3\n\
+class main\n    fun start()\n        let x = 2\n        print(triple(x) + 1)\n"
+        );
+        let mut parser = crate::parser::Parser::new(&source).expect("source should tokenize");
+        parser.parse_program().expect("session should parse");
     }
 
-    fn show_help(&self) {
-        println!("╔═══════════════════════════════════════════════════════════╗");
-        println!("║                    Comandos do REPL                       ║");
-        println!("╠═══════════════════════════════════════════════════════════╣");
-        println!("║  exit, quit    - Sair do REPL                             ║");
-        println!("║  help          - Mostrar esta ajuda                       ║");
-        println!("║  clear         - Limpar histórico                         ║");
-        println!("║  history       - Mostrar histórico de comandos            ║");
-        println!("╠═══════════════════════════════════════════════════════════╣");
-        println!("║                        Code Examples                      ║");
-        println!("╠═══════════════════════════════════════════════════════════╣");
-        println!("║  let x = 10                                               ║");
-        println!("║  print(\"Hello, Snask!\")                                   ║");
-        println!("║  fun add(a, b) {{ return a + b; }}                        ║");
-        println!("║  sqrt(16)                                                 ║");
-        println!("║  range(10)                                                ║");
-        println!("╚═══════════════════════════════════════════════════════════╝");
+    #[test]
+    fn void_calls_run_as_statements() {
+        let mut session = ReplSession::new();
+        session
+            .declarations
+            .push("fun greet()\n    print(\"hi\")\n".to_string());
+        session
+            .declarations
+            .push("fun twice(n: int) : int\n    return n * 2\n".to_string());
+        session
+            .declarations
+            .push("fun answer()\n    return 42\n".to_string());
+        assert!(session.is_void_call("greet()\n"));
+        assert!(session.is_void_call("random_seed(7)\n"));
+        assert!(!session.is_void_call("twice(3)\n"));
+        assert!(!session.is_void_call("answer()\n"));
+        assert!(!session.is_void_call("len(\"abc\")\n"));
+        assert!(!session.is_void_call("1 + 2\n"));
     }
 
-    fn show_history(&self) {
-        if self.history.is_empty() {
-            println!("(empty)");
+    #[test]
+    fn eval_prints_expressions_over_kept_statements() {
+        if !shared_runtime_path().is_file() {
+            eprintln!("skipping: no shared runtime (run `snask setup`)");
             return;
         }
+        let mut session = ReplSession::new();
+        assert_eq!(session.eval("let x = 2\n"), Ok(String::new()));
+        assert_eq!(
+            session.eval("fun triple(n: int) : int\n    return n * 3\n"),
+            Ok(String::new())
+        );
+        assert_eq!(session.eval("triple(x) + 1\n"), Ok("7\n".to_string()));
+        assert_eq!(session.eval("print(x)\n"), Ok("2\n".to_string()));
+        // The expression was not kept and the `print` statement already ran.
+        assert_eq!(session.eval("x * 10\n"), Ok("20\n".to_string()));
+    }
 
-        println!("╔═══════════════════════════════════════════════════════════╗");
-        println!("║                       Command History                     ║");
-        println!("╚═══════════════════════════════════════════════════════════╝");
-        
-        for (i, cmd) in self.history.iter().enumerate() {
-            println!("{:3}. {}", i + 1, cmd);
-        }
+    #[test]
+    fn block_openers_are_detected() {
+        assert!(opens_block("fun add(a, b)"));
+        assert!(opens_block("  if x > 1"));
+        assert!(!opens_block("let fun_count = 1"));
+        assert!(!opens_block("print(1)"));
     }
 }
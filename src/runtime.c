@@ -1,17 +1,36 @@
 // 🐍 Snask Modular Runtime Entry Point
 // Este arquivo unifica os módulos do novo runtime.
 
+// strptime/timegm (rt_datetime.c) are only declared with the GNU extensions;
+// this has to come before the first libc header of the unity build.
+#ifndef _GNU_SOURCE
+#define _GNU_SOURCE
+#endif
+
 #include "runtime/rt_base.h"
 #include "runtime/rt_gc.c"
+#include "runtime/rt_error.c"
 #include "runtime/rt_obj.c"
 #include "runtime/rt_io.c"
 #include "runtime/rt_sfs.c"
 #include "runtime/rt_json.c"
+// No sockets, dlopen or windowing under WebAssembly (`snask setup --target wasm32-*`).
+#ifndef SNASK_WASM
 #include "runtime/rt_http.c"
 #include "runtime/rt_gui.c"
+#endif
 #include "runtime/rt_sys.c"
+#include "runtime/rt_regex.c"
+#include "runtime/rt_datetime.c"
+#include "runtime/rt_csv.c"
+#include "runtime/rt_base64.c"
+#include "runtime/rt_hash.c"
+#include "runtime/rt_sb.c"
+#ifndef SNASK_WASM
 #include "runtime/rt_auth.c"
 #include "runtime/rt_blaze.c"
+#include "runtime/rt_test.c"
+#endif
 
 // Funções de compatibilidade ou stubs que ainda não foram modularizados
 // podem ser adicionados aqui ou em rt_base.c
@@ -0,0 +1,116 @@
+#include <stdlib.h>
+#include <string.h>
+#include "rt_base64.h"
+#include "rt_error.h"
+#include "rt_gc.h"
+
+// Base64 module (RFC 4648). The standard alphabet always pads with `=`; the
+// URL-safe one (`-` and `_`) leaves the padding out, as JWTs and URLs do, and
+// accepts input with or without it. Invalid input records a catchable error.
+
+static const char B64_STD[] = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+static const char B64_URL[] = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+static const char* b64_str_arg(const char* who, SnaskValue* v) {
+    if (!v || (int)v->tag != SNASK_STR || !v->ptr) {
+        snask_error_set("%s: expected a str", who);
+        return NULL;
+    }
+    return (const char*)v->ptr;
+}
+
+static void b64_encode_with(SnaskValue* out, const char* who, SnaskValue* text, const char* alphabet, bool pad) {
+    const char* src = b64_str_arg(who, text);
+    if (!src) { *out = MAKE_NIL(); return; }
+    const unsigned char* s = (const unsigned char*)src;
+    size_t len = strlen(src);
+    char* buf = (char*)malloc((len + 2) / 3 * 4 + 1);
+    size_t n = 0;
+    for (size_t i = 0; i < len; i += 3) {
+        size_t left = len - i;
+        unsigned int chunk = (unsigned int)s[i] << 16;
+        if (left > 1) chunk |= (unsigned int)s[i + 1] << 8;
+        if (left > 2) chunk |= s[i + 2];
+        buf[n++] = alphabet[(chunk >> 18) & 63];
+        buf[n++] = alphabet[(chunk >> 12) & 63];
+        if (left > 1) buf[n++] = alphabet[(chunk >> 6) & 63];
+        else if (pad) buf[n++] = '=';
+        if (left > 2) buf[n++] = alphabet[chunk & 63];
+        else if (pad) buf[n++] = '=';
+    }
+    buf[n] = '\0';
+    snask_gc_track_ptr(buf);
+    *out = MAKE_STR(buf);
+}
+
+static int b64_digit(const char* alphabet, char c) {
+    const char* p = c ? strchr(alphabet, c) : NULL;
+    return p ? (int)(p - alphabet) : -1;
+}
+
+static void b64_decode_with(SnaskValue* out, const char* who, SnaskValue* text, const char* alphabet, bool pad_required) {
+    const char* s = b64_str_arg(who, text);
+    if (!s) { *out = MAKE_NIL(); return; }
+    size_t len = strlen(s);
+    size_t data_len = len;
+    while (data_len > 0 && s[data_len - 1] == '=' && len - data_len < 2) data_len--;
+    size_t padding = len - data_len;
+
+    bool bad_length = data_len % 4 == 1
+        || (pad_required ? len % 4 != 0 : (padding > 0 && len % 4 != 0));
+    if (bad_length) {
+        snask_error_set("%s: invalid length %zu", who, len);
+        *out = MAKE_NIL();
+        return;
+    }
+
+    char* buf = (char*)malloc(data_len / 4 * 3 + 3);
+    size_t n = 0;
+    unsigned int acc = 0;
+    int bits = 0;
+    for (size_t i = 0; i < data_len; i++) {
+        int d = b64_digit(alphabet, s[i]);
+        if (d < 0) {
+            snask_error_set("%s: invalid character '%c' at offset %zu", who, s[i], i);
+            free(buf);
+            *out = MAKE_NIL();
+            return;
+        }
+        acc = (acc << 6) | (unsigned int)d;
+        bits += 6;
+        if (bits >= 8) {
+            bits -= 8;
+            char byte = (char)((acc >> bits) & 0xFF);
+            if (byte == '\0') {
+                snask_error_set("%s: decoded data contains a NUL byte", who);
+                free(buf);
+                *out = MAKE_NIL();
+                return;
+            }
+            buf[n++] = byte;
+        }
+    }
+    buf[n] = '\0';
+    snask_gc_track_ptr(buf);
+    *out = MAKE_STR(buf);
+}
+
+// base64_encode(text) -> standard alphabet, padded with `=`
+void base64_encode(SnaskValue* out, SnaskValue* text) {
+    b64_encode_with(out, "base64::encode", text, B64_STD, true);
+}
+
+// base64_decode(text) -> original text; the padding has to be there
+void base64_decode(SnaskValue* out, SnaskValue* text) {
+    b64_decode_with(out, "base64::decode", text, B64_STD, true);
+}
+
+// base64_encode_url(text) -> URL-safe alphabet, no padding
+void base64_encode_url(SnaskValue* out, SnaskValue* text) {
+    b64_encode_with(out, "base64::encode_url", text, B64_URL, false);
+}
+
+// base64_decode_url(text) -> original text; padding is optional
+void base64_decode_url(SnaskValue* out, SnaskValue* text) {
+    b64_decode_with(out, "base64::decode_url", text, B64_URL, false);
+}
@@ -0,0 +1,11 @@
+#ifndef RT_BASE64_H
+#define RT_BASE64_H
+
+#include "rt_base.h"
+
+void base64_encode(SnaskValue* out, SnaskValue* text);
+void base64_decode(SnaskValue* out, SnaskValue* text);
+void base64_encode_url(SnaskValue* out, SnaskValue* text);
+void base64_decode_url(SnaskValue* out, SnaskValue* text);
+
+#endif // RT_BASE64_H
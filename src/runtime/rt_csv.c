@@ -0,0 +1,208 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "rt_csv.h"
+#include "rt_error.h"
+#include "rt_gc.h"
+
+// CSV module following RFC 4180: fields may be quoted, quoted fields may hold
+// the delimiter, line breaks and `""` for a literal quote. Rows are lists of
+// strings. Malformed input records a catchable error and yields nil.
+
+typedef struct {
+    char* data;
+    size_t len;
+    size_t cap;
+} CsvBuf;
+
+static void csv_buf_put(CsvBuf* b, const char* s, size_t n) {
+    if (b->len + n + 1 > b->cap) {
+        size_t cap = b->cap ? b->cap : 64;
+        while (b->len + n + 1 > cap) cap *= 2;
+        b->data = (char*)realloc(b->data, cap);
+        b->cap = cap;
+    }
+    memcpy(b->data + b->len, s, n);
+    b->len += n;
+    b->data[b->len] = '\0';
+}
+
+static void csv_list_push(SnaskObject* list, int* cap, SnaskValue v) {
+    if (list->count >= *cap) {
+        int new_cap = (*cap == 0) ? 8 : *cap * 2;
+        list->names = (char**)realloc(list->names, (size_t)new_cap * sizeof(char*));
+        list->values = (SnaskValue*)realloc(list->values, (size_t)new_cap * sizeof(SnaskValue));
+        *cap = new_cap;
+    }
+    char idx_name[32];
+    snprintf(idx_name, sizeof(idx_name), "%d", list->count);
+    list->names[list->count] = snask_gc_strdup(idx_name);
+    list->values[list->count] = v;
+    list->count++;
+}
+
+static SnaskObject* csv_new_list(void) {
+    SnaskObject* list = (SnaskObject*)malloc(sizeof(SnaskObject));
+    list->count = 0; list->names = NULL; list->values = NULL;
+    return list;
+}
+
+// The single-byte delimiter in `v`, or 0 after recording an error for `who`.
+static char csv_delim_arg(const char* who, SnaskValue* v) {
+    const char* s = (v && (int)v->tag == SNASK_STR && v->ptr) ? (const char*)v->ptr : NULL;
+    if (!s || strlen(s) != 1 || s[0] == '"' || s[0] == '\r' || s[0] == '\n') {
+        snask_error_set("%s: delimiter must be a single character other than a quote or line break", who);
+        return 0;
+    }
+    return s[0];
+}
+
+static void csv_parse_with(SnaskValue* out, const char* who, SnaskValue* text, char delim) {
+    if (!text || (int)text->tag != SNASK_STR || !text->ptr) {
+        snask_error_set("%s: expected a str", who);
+        *out = MAKE_NIL();
+        return;
+    }
+    const char* s = (const char*)text->ptr;
+    SnaskObject* rows = csv_new_list();
+    int rows_cap = 0;
+    SnaskObject* row = NULL;
+    int row_cap = 0;
+    CsvBuf field = {0};
+    int line = 1;
+
+    size_t i = 0;
+    while (s[i] != '\0') {
+        if (!row) { row = csv_new_list(); row_cap = 0; }
+        field.len = 0;
+        csv_buf_put(&field, "", 0);
+        if (s[i] == '"') {
+            int start_line = line;
+            i++;
+            for (;;) {
+                if (s[i] == '\0') {
+                    snask_error_set("%s: unterminated quoted field starting on line %d", who, start_line);
+                    free(field.data);
+                    *out = MAKE_NIL();
+                    return;
+                }
+                if (s[i] == '"') {
+                    if (s[i + 1] != '"') { i++; break; }
+                    i++;
+                }
+                if (s[i] == '\n') line++;
+                csv_buf_put(&field, s + i, 1);
+                i++;
+            }
+            if (s[i] != delim && s[i] != '\r' && s[i] != '\n' && s[i] != '\0') {
+                snask_error_set("%s: unexpected '%c' after a closing quote on line %d", who, s[i], line);
+                free(field.data);
+                *out = MAKE_NIL();
+                return;
+            }
+        } else {
+            size_t start = i;
+            while (s[i] != '\0' && s[i] != delim && s[i] != '\r' && s[i] != '\n') i++;
+            csv_buf_put(&field, s + start, i - start);
+        }
+        csv_list_push(row, &row_cap, MAKE_STR(snask_gc_strdup(field.data)));
+
+        if (s[i] == delim) {
+            i++;
+            // A delimiter right before the end of a record leaves an empty last field.
+            if (s[i] == '\0' || s[i] == '\r' || s[i] == '\n') {
+                csv_list_push(row, &row_cap, MAKE_STR(snask_gc_strdup("")));
+            } else {
+                continue;
+            }
+        }
+        if (s[i] == '\r') i++;
+        if (s[i] == '\n') { i++; line++; }
+        csv_list_push(rows, &rows_cap, MAKE_OBJ(row));
+        row = NULL;
+    }
+    free(field.data);
+    *out = MAKE_OBJ(rows);
+}
+
+static bool csv_needs_quotes(const char* s, char delim) {
+    for (; *s; s++) {
+        if (*s == delim || *s == '"' || *s == '\r' || *s == '\n') return true;
+    }
+    return false;
+}
+
+static void csv_write_with(SnaskValue* out, const char* who, SnaskValue* rows, char delim) {
+    if (!rows || (int)rows->tag != SNASK_OBJ || !rows->ptr) {
+        snask_error_set("%s: expected a list of rows", who);
+        *out = MAKE_NIL();
+        return;
+    }
+    SnaskObject* list = (SnaskObject*)rows->ptr;
+    CsvBuf b = {0};
+    csv_buf_put(&b, "", 0);
+    for (int r = 0; r < list->count; r++) {
+        SnaskValue* rv = &list->values[r];
+        if ((int)rv->tag != SNASK_OBJ || !rv->ptr) {
+            snask_error_set("%s: row %d is not a list", who, r);
+            free(b.data);
+            *out = MAKE_NIL();
+            return;
+        }
+        SnaskObject* row = (SnaskObject*)rv->ptr;
+        for (int c = 0; c < row->count; c++) {
+            if (c > 0) csv_buf_put(&b, &delim, 1);
+            SnaskValue* v = &row->values[c];
+            char num[64];
+            const char* text;
+            switch ((int)v->tag) {
+                case SNASK_STR: text = v->ptr ? (const char*)v->ptr : ""; break;
+                case SNASK_NUM: snprintf(num, sizeof(num), "%.15g", v->num); text = num; break;
+                case SNASK_BOOL: text = v->num ? "true" : "false"; break;
+                case SNASK_NIL: text = ""; break;
+                default:
+                    snask_error_set("%s: field %d of row %d is not a str, number or bool", who, c, r);
+                    free(b.data);
+                    *out = MAKE_NIL();
+                    return;
+            }
+            if (!csv_needs_quotes(text, delim)) {
+                csv_buf_put(&b, text, strlen(text));
+                continue;
+            }
+            csv_buf_put(&b, "\"", 1);
+            for (const char* p = text; *p; p++) {
+                if (*p == '"') csv_buf_put(&b, "\"", 1);
+                csv_buf_put(&b, p, 1);
+            }
+            csv_buf_put(&b, "\"", 1);
+        }
+        csv_buf_put(&b, "\n", 1);
+    }
+    snask_gc_track_ptr(b.data);
+    *out = MAKE_STR(b.data);
+}
+
+// csv_parse(text) -> list of rows, each a list of str fields
+void csv_parse(SnaskValue* out, SnaskValue* text) {
+    csv_parse_with(out, "csv::parse", text, ',');
+}
+
+// csv_parse_delim(text, delim) -> like csv_parse with another separator ("\t", ";", ...)
+void csv_parse_delim(SnaskValue* out, SnaskValue* text, SnaskValue* delim) {
+    char d = csv_delim_arg("csv::parse_delim", delim);
+    if (!d) { *out = MAKE_NIL(); return; }
+    csv_parse_with(out, "csv::parse_delim", text, d);
+}
+
+// csv_write(rows) -> CSV text, one "\n"-terminated line per row
+void csv_write(SnaskValue* out, SnaskValue* rows) {
+    csv_write_with(out, "csv::write", rows, ',');
+}
+
+// csv_write_delim(rows, delim) -> like csv_write with another separator
+void csv_write_delim(SnaskValue* out, SnaskValue* rows, SnaskValue* delim) {
+    char d = csv_delim_arg("csv::write_delim", delim);
+    if (!d) { *out = MAKE_NIL(); return; }
+    csv_write_with(out, "csv::write_delim", rows, d);
+}
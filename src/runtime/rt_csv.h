@@ -0,0 +1,11 @@
+#ifndef RT_CSV_H
+#define RT_CSV_H
+
+#include "rt_base.h"
+
+void csv_parse(SnaskValue* out, SnaskValue* text);
+void csv_parse_delim(SnaskValue* out, SnaskValue* text, SnaskValue* delim);
+void csv_write(SnaskValue* out, SnaskValue* rows);
+void csv_write_delim(SnaskValue* out, SnaskValue* rows, SnaskValue* delim);
+
+#endif // RT_CSV_H
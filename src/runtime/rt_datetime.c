@@ -0,0 +1,52 @@
+#ifndef _GNU_SOURCE
+#define _GNU_SOURCE
+#endif
+#include <math.h>
+#include <string.h>
+#include <time.h>
+#include "rt_datetime.h"
+#include "rt_gc.h"
+
+// Datetime module. Everything is UTC (gmtime_r/timegm) so output does not
+// depend on the TZ of the machine running the program.
+
+// dt_now() -> seconds since the epoch, with sub-second precision
+void dt_now(SnaskValue* out) {
+    struct timespec ts;
+    if (clock_gettime(CLOCK_REALTIME, &ts) != 0) { *out = MAKE_NIL(); return; }
+    *out = MAKE_NUM((double)ts.tv_sec + (double)ts.tv_nsec / 1e9);
+}
+
+// dt_format(epoch, fmt) -> strftime(fmt) of the UTC time
+void dt_format(SnaskValue* out, SnaskValue* epoch, SnaskValue* fmt) {
+    if (!epoch || (int)epoch->tag != SNASK_NUM) { *out = MAKE_NIL(); return; }
+    if (!fmt || (int)fmt->tag != SNASK_STR || !fmt->ptr) { *out = MAKE_NIL(); return; }
+    time_t t = (time_t)floor(epoch->num);
+    struct tm tm;
+    if (!gmtime_r(&t, &tm)) { *out = MAKE_NIL(); return; }
+    char buf[512];
+    size_t n = strftime(buf, sizeof(buf), (const char*)fmt->ptr, &tm);
+    // strftime returns 0 both on overflow and for an empty result.
+    if (n == 0 && ((const char*)fmt->ptr)[0] != '\0') { *out = MAKE_NIL(); return; }
+    buf[n] = '\0';
+    *out = MAKE_STR(snask_gc_strdup(buf));
+}
+
+// dt_parse(text, fmt) -> epoch of the UTC time, nil unless all of text matches fmt
+void dt_parse(SnaskValue* out, SnaskValue* text, SnaskValue* fmt) {
+    if (!text || (int)text->tag != SNASK_STR || !text->ptr) { *out = MAKE_NIL(); return; }
+    if (!fmt || (int)fmt->tag != SNASK_STR || !fmt->ptr) { *out = MAKE_NIL(); return; }
+    struct tm tm;
+    memset(&tm, 0, sizeof(tm));
+    tm.tm_mday = 1;
+    const char* end = strptime((const char*)text->ptr, (const char*)fmt->ptr, &tm);
+    if (!end || *end != '\0') { *out = MAKE_NIL(); return; }
+    *out = MAKE_NUM((double)timegm(&tm));
+}
+
+// dt_add_days(epoch, n) -> epoch + n days (UTC has no DST jumps)
+void dt_add_days(SnaskValue* out, SnaskValue* epoch, SnaskValue* days) {
+    if (!epoch || (int)epoch->tag != SNASK_NUM) { *out = MAKE_NIL(); return; }
+    if (!days || (int)days->tag != SNASK_NUM) { *out = MAKE_NIL(); return; }
+    *out = MAKE_NUM(epoch->num + days->num * 86400.0);
+}
@@ -0,0 +1,11 @@
+#ifndef RT_DATETIME_H
+#define RT_DATETIME_H
+
+#include "rt_base.h"
+
+void dt_now(SnaskValue* out);
+void dt_format(SnaskValue* out, SnaskValue* epoch, SnaskValue* fmt);
+void dt_parse(SnaskValue* out, SnaskValue* text, SnaskValue* fmt);
+void dt_add_days(SnaskValue* out, SnaskValue* epoch, SnaskValue* days);
+
+#endif // RT_DATETIME_H
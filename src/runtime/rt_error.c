@@ -0,0 +1,90 @@
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "rt_error.h"
+#include "rt_gc.h"
+
+static _Thread_local char* snask_pending_error = NULL;
+static _Thread_local const char* snask_current_location = NULL;
+
+void snask_error_set(const char* fmt, ...) {
+    char buf[1024];
+    va_list ap;
+    va_start(ap, fmt);
+    vsnprintf(buf, sizeof(buf), fmt, ap);
+    va_end(ap);
+    free(snask_pending_error);
+    snask_pending_error = strdup(buf);
+}
+
+int snask_error_pending(void) {
+    return snask_pending_error != NULL;
+}
+
+void snask_error_clear(void) {
+    free(snask_pending_error);
+    snask_pending_error = NULL;
+}
+
+// Hands the pending message to Snask as a string and clears the slot.
+void snask_error_take(SnaskValue* out) {
+    if (!snask_pending_error) { *out = MAKE_STR(snask_gc_strdup("unknown error")); return; }
+    *out = MAKE_STR(snask_gc_strdup(snask_pending_error));
+    snask_error_clear();
+}
+
+void snask_assert_fail(SnaskValue* msg, const char* location) {
+    fflush(stdout);
+    if (msg && (int)msg->tag == SNASK_STR && msg->ptr) {
+        fprintf(stderr, "assertion failed: %s\n", (const char*)msg->ptr);
+    } else {
+        fprintf(stderr, "assertion failed\n");
+    }
+    fprintf(stderr, "  --> %s\n", location ? location : "<unknown>");
+    exit(1);
+}
+
+void snask_set_location(const char* location) {
+    snask_current_location = location;
+}
+
+// Unrecoverable runtime failure: prints the message with the location last
+// recorded by `snask_set_location` and exits with 1.
+void snask_panic(const char* fmt, ...) {
+    char buf[1024];
+    va_list ap;
+    va_start(ap, fmt);
+    vsnprintf(buf, sizeof(buf), fmt, ap);
+    va_end(ap);
+    fflush(stdout);
+    fprintf(stderr, "runtime error: %s\n", buf);
+    fprintf(stderr, "  --> %s\n", snask_current_location ? snask_current_location : "<unknown>");
+    exit(1);
+}
+
+// A failed operation: a catchable error when generated code runs it inside a
+// `try` (`recoverable`), elsewhere a panic at the location recorded for it.
+void snask_fail(int recoverable, const char* fmt, ...) {
+    char buf[1024];
+    va_list ap;
+    va_start(ap, fmt);
+    vsnprintf(buf, sizeof(buf), fmt, ap);
+    va_end(ap);
+    if (recoverable) {
+        snask_error_set("%s", buf);
+        return;
+    }
+    snask_panic("%s", buf);
+}
+
+// `/`, `//` or `%` with a zero divisor.
+void snask_division_by_zero(int recoverable) {
+    snask_fail(recoverable, "division by zero");
+}
+
+// A method call on a value whose class has no such method (or that is not an
+// object at all).
+void snask_no_method(int recoverable, const char* method) {
+    snask_fail(recoverable, "no method `%s` on this value", method);
+}
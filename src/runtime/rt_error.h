@@ -0,0 +1,30 @@
+#ifndef RT_ERROR_H
+#define RT_ERROR_H
+
+#include "rt_base.h"
+
+// Pending runtime error used by `try`/`catch`. Natives that fail record a
+// message here (besides returning nil); code inside a `try` block checks it
+// after every call and jumps to the handler.
+void snask_error_set(const char* fmt, ...);
+int snask_error_pending(void);
+void snask_error_clear(void);
+void snask_error_take(SnaskValue* out);
+
+// Failed `assert(cond, msg)`: prints msg and "file:line:col", exits with 1.
+void snask_assert_fail(SnaskValue* msg, const char* location);
+
+// Line table for runtime panics: generated code records the "file:line:col"
+// of the operation it is about to run, and `snask_panic` reports it.
+void snask_set_location(const char* location);
+void snask_panic(const char* fmt, ...);
+
+// Catchable inside a `try` (`recoverable`), a panic otherwise.
+void snask_fail(int recoverable, const char* fmt, ...);
+
+// A zero divisor: catchable inside a `try` (`recoverable`), a panic otherwise.
+void snask_division_by_zero(int recoverable);
+// A method call with no matching method on the receiver's class.
+void snask_no_method(int recoverable, const char* method);
+
+#endif // RT_ERROR_H
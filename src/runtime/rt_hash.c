@@ -0,0 +1,181 @@
+#include <stdint.h>
+#include <string.h>
+#include "rt_hash.h"
+#include "rt_error.h"
+#include "rt_gc.h"
+
+// Hash module: SHA-256 (FIPS 180-4), SHA-1 and MD5 (RFC 1321) implemented
+// here so no crypto library has to be linked. Digests come back as lowercase
+// hex. SHA-1 and MD5 are broken for security purposes; they are only meant
+// for checksums and interop with existing formats.
+
+// All three hashes pad the message the same way: a 0x80 byte, zeros up to 56
+// mod 64, then the bit length as 8 bytes (big-endian for SHA, little for MD5).
+typedef void (*HashBlockFn)(uint32_t* state, const unsigned char* block);
+
+static void hash_run(uint32_t* state, HashBlockFn block_fn, const unsigned char* data, size_t len, bool big_endian_len) {
+    size_t full = len / 64 * 64;
+    for (size_t i = 0; i < full; i += 64) block_fn(state, data + i);
+
+    unsigned char tail[128];
+    size_t rest = len - full;
+    memcpy(tail, data + full, rest);
+    tail[rest] = 0x80;
+    size_t tail_len = rest < 56 ? 64 : 128;
+    memset(tail + rest + 1, 0, tail_len - rest - 1);
+    uint64_t bits = (uint64_t)len * 8;
+    for (int i = 0; i < 8; i++) {
+        int shift = big_endian_len ? 56 - 8 * i : 8 * i;
+        tail[tail_len - 8 + i] = (unsigned char)(bits >> shift);
+    }
+    block_fn(state, tail);
+    if (tail_len == 128) block_fn(state, tail + 64);
+}
+
+static uint32_t rotl32(uint32_t x, int n) { return (x << n) | (x >> (32 - n)); }
+static uint32_t rotr32(uint32_t x, int n) { return (x >> n) | (x << (32 - n)); }
+
+static uint32_t load_be32(const unsigned char* p) {
+    return ((uint32_t)p[0] << 24) | ((uint32_t)p[1] << 16) | ((uint32_t)p[2] << 8) | p[3];
+}
+
+static uint32_t load_le32(const unsigned char* p) {
+    return ((uint32_t)p[3] << 24) | ((uint32_t)p[2] << 16) | ((uint32_t)p[1] << 8) | p[0];
+}
+
+static const uint32_t SHA256_K[64] = {
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+};
+
+static void sha256_block(uint32_t* h, const unsigned char* block) {
+    uint32_t w[64];
+    for (int i = 0; i < 16; i++) w[i] = load_be32(block + 4 * i);
+    for (int i = 16; i < 64; i++) {
+        uint32_t s0 = rotr32(w[i - 15], 7) ^ rotr32(w[i - 15], 18) ^ (w[i - 15] >> 3);
+        uint32_t s1 = rotr32(w[i - 2], 17) ^ rotr32(w[i - 2], 19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+    }
+    uint32_t a = h[0], b = h[1], c = h[2], d = h[3], e = h[4], f = h[5], g = h[6], k = h[7];
+    for (int i = 0; i < 64; i++) {
+        uint32_t t1 = k + (rotr32(e, 6) ^ rotr32(e, 11) ^ rotr32(e, 25)) + ((e & f) ^ (~e & g)) + SHA256_K[i] + w[i];
+        uint32_t t2 = (rotr32(a, 2) ^ rotr32(a, 13) ^ rotr32(a, 22)) + ((a & b) ^ (a & c) ^ (b & c));
+        k = g; g = f; f = e; e = d + t1;
+        d = c; c = b; b = a; a = t1 + t2;
+    }
+    h[0] += a; h[1] += b; h[2] += c; h[3] += d; h[4] += e; h[5] += f; h[6] += g; h[7] += k;
+}
+
+static void sha1_block(uint32_t* h, const unsigned char* block) {
+    uint32_t w[80];
+    for (int i = 0; i < 16; i++) w[i] = load_be32(block + 4 * i);
+    for (int i = 16; i < 80; i++) w[i] = rotl32(w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16], 1);
+    uint32_t a = h[0], b = h[1], c = h[2], d = h[3], e = h[4];
+    for (int i = 0; i < 80; i++) {
+        uint32_t f, k;
+        if (i < 20) { f = (b & c) | (~b & d); k = 0x5a827999; }
+        else if (i < 40) { f = b ^ c ^ d; k = 0x6ed9eba1; }
+        else if (i < 60) { f = (b & c) | (b & d) | (c & d); k = 0x8f1bbcdc; }
+        else { f = b ^ c ^ d; k = 0xca62c1d6; }
+        uint32_t t = rotl32(a, 5) + f + e + k + w[i];
+        e = d; d = c; c = rotl32(b, 30); b = a; a = t;
+    }
+    h[0] += a; h[1] += b; h[2] += c; h[3] += d; h[4] += e;
+}
+
+static const uint32_t MD5_K[64] = {
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+};
+
+static const int MD5_S[64] = {
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+};
+
+static void md5_block(uint32_t* h, const unsigned char* block) {
+    uint32_t m[16];
+    for (int i = 0; i < 16; i++) m[i] = load_le32(block + 4 * i);
+    uint32_t a = h[0], b = h[1], c = h[2], d = h[3];
+    for (int i = 0; i < 64; i++) {
+        uint32_t f;
+        int g;
+        if (i < 16) { f = (b & c) | (~b & d); g = i; }
+        else if (i < 32) { f = (d & b) | (~d & c); g = (5 * i + 1) % 16; }
+        else if (i < 48) { f = b ^ c ^ d; g = (3 * i + 5) % 16; }
+        else { f = c ^ (b | ~d); g = (7 * i) % 16; }
+        uint32_t t = d;
+        d = c; c = b;
+        b = b + rotl32(a + f + MD5_K[i] + m[g], MD5_S[i]);
+        a = t;
+    }
+    h[0] += a; h[1] += b; h[2] += c; h[3] += d;
+}
+
+// Writes `words` state words as hex, each one big- or little-endian.
+static void hash_to_hex(SnaskValue* out, const uint32_t* state, int words, bool big_endian) {
+    static const char digits[] = "0123456789abcdef";
+    char hex[65];
+    int n = 0;
+    for (int i = 0; i < words; i++) {
+        for (int j = 0; j < 4; j++) {
+            int shift = big_endian ? 24 - 8 * j : 8 * j;
+            unsigned char byte = (unsigned char)(state[i] >> shift);
+            hex[n++] = digits[byte >> 4];
+            hex[n++] = digits[byte & 15];
+        }
+    }
+    hex[n] = '\0';
+    *out = MAKE_STR(snask_gc_strdup(hex));
+}
+
+static const unsigned char* hash_str_arg(const char* who, SnaskValue* v) {
+    if (!v || (int)v->tag != SNASK_STR || !v->ptr) {
+        snask_error_set("%s: expected a str", who);
+        return NULL;
+    }
+    return (const unsigned char*)v->ptr;
+}
+
+// hash_sha256_hex(text) -> 64 hex digits
+void hash_sha256_hex(SnaskValue* out, SnaskValue* text) {
+    const unsigned char* s = hash_str_arg("hash::sha256_hex", text);
+    if (!s) { *out = MAKE_NIL(); return; }
+    uint32_t h[8] = {
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    };
+    hash_run(h, sha256_block, s, strlen((const char*)s), true);
+    hash_to_hex(out, h, 8, true);
+}
+
+// hash_sha1_hex(text) -> 40 hex digits
+void hash_sha1_hex(SnaskValue* out, SnaskValue* text) {
+    const unsigned char* s = hash_str_arg("hash::sha1_hex", text);
+    if (!s) { *out = MAKE_NIL(); return; }
+    uint32_t h[5] = { 0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0 };
+    hash_run(h, sha1_block, s, strlen((const char*)s), true);
+    hash_to_hex(out, h, 5, true);
+}
+
+// hash_md5_hex(text) -> 32 hex digits
+void hash_md5_hex(SnaskValue* out, SnaskValue* text) {
+    const unsigned char* s = hash_str_arg("hash::md5_hex", text);
+    if (!s) { *out = MAKE_NIL(); return; }
+    uint32_t h[4] = { 0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476 };
+    hash_run(h, md5_block, s, strlen((const char*)s), false);
+    hash_to_hex(out, h, 4, false);
+}
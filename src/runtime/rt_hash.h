@@ -0,0 +1,10 @@
+#ifndef RT_HASH_H
+#define RT_HASH_H
+
+#include "rt_base.h"
+
+void hash_sha256_hex(SnaskValue* out, SnaskValue* text);
+void hash_sha1_hex(SnaskValue* out, SnaskValue* text);
+void hash_md5_hex(SnaskValue* out, SnaskValue* text);
+
+#endif // RT_HASH_H
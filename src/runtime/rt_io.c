@@ -1,9 +1,12 @@
+#include <errno.h>
+#include <math.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 #include <unistd.h>
 #include <time.h>
 #include "rt_base.h"
+#include "rt_error.h"
 #include "rt_gc.h"
 #include "rt_io.h"
 #include "rt_obj.h"
@@ -21,6 +24,46 @@ void s_time(SnaskValue* out) {
     out->ptr = NULL;
 }
 
+// Nanoseconds on the monotonic clock. Doubles hold them exactly for the
+// first ~104 days of uptime, which is plenty for measuring durations.
+static double monotonic_ns(void) {
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (double)ts.tv_sec * 1e9 + (double)ts.tv_nsec;
+}
+
+// time_ns()/time_ms(): monotonic, so only differences between two calls
+// mean anything; they never jump when the wall clock is adjusted.
+void s_time_ns(SnaskValue* out) {
+    *out = MAKE_NUM(monotonic_ns());
+}
+
+void s_time_ms(SnaskValue* out) {
+    *out = MAKE_NUM(floor(monotonic_ns() / 1e6));
+}
+
+static void sleep_millis(const char* who, SnaskValue* out, SnaskValue* ms) {
+    *out = MAKE_NIL();
+    if (!ms || (int)ms->tag != SNASK_NUM || !(ms->num >= 0)) {
+        snask_error_set("%s: expected a non-negative number of milliseconds", who);
+        return;
+    }
+    struct timespec req;
+    req.tv_sec = (time_t)(ms->num / 1000);
+    req.tv_nsec = (long)((ms->num - (double)req.tv_sec * 1000) * 1e6);
+    if (req.tv_nsec > 999999999L) req.tv_nsec = 999999999L;
+    // A signal cuts nanosleep short; the rest of the wait is in `req`.
+    while (nanosleep(&req, &req) == -1 && errno == EINTR) {}
+}
+
+void s_sleep(SnaskValue* out, SnaskValue* ms) {
+    sleep_millis("sleep", out, ms);
+}
+
+void s_sleep_ms(SnaskValue* out, SnaskValue* ms) {
+    sleep_millis("sleep_ms", out, ms);
+}
+
 // Helper para converter SnaskValue para string temporária (buffer local ou literal)
 static const char* _val_to_str_tmp(SnaskValue* v, char* buf, size_t buf_size) {
     if (!v) return "nil";
@@ -52,3 +95,27 @@ void s_concat(SnaskValue* out, SnaskValue* s1, SnaskValue* s2) {
     strcat(res, str2);
     *out = MAKE_STR(res);
 }
+
+// Chains of `+` with strings (`a + b + c + d`) are lowered to one call that
+// sizes the result first and allocates it once.
+void s_concat_n(SnaskValue* out, SnaskValue* parts, int count) {
+    char buf[64];
+    size_t total = 0;
+    for (int i = 0; i < count; i++) {
+        total += strlen(_val_to_str_tmp(&parts[i], buf, sizeof(buf)));
+    }
+    char* res = (char*)malloc(total + 1);
+    if (!res) {
+        *out = MAKE_NIL();
+        return;
+    }
+    size_t len = 0;
+    for (int i = 0; i < count; i++) {
+        const char* part = _val_to_str_tmp(&parts[i], buf, sizeof(buf));
+        size_t n = strlen(part);
+        memcpy(res + len, part, n);
+        len += n;
+    }
+    res[len] = '\0';
+    *out = MAKE_STR(res);
+}
@@ -73,25 +73,53 @@ static void sb_append_json_escaped(StrBuf* sb, const char* s) {
 }
 
 // --- Internal Stringify ---
-static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int indent, int level);
+static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int indent, int level, bool sort_keys);
+
+// Lists are objects whose member names are their indexes ("0", "1", ...).
+static bool json_obj_is_list(SnaskObject* obj) {
+    for (int i = 0; i < obj->count; i++) {
+        char idx[32];
+        snprintf(idx, sizeof(idx), "%d", i);
+        if (!obj->names[i] || strcmp(obj->names[i], idx) != 0) return false;
+    }
+    return true;
+}
+
+static void json_stringify_object_into(StrBuf* sb, SnaskObject* obj, bool pretty, int indent, int level, bool sort_keys) {
+    // Member order: as stored, or by key when sorting (lists keep their order).
+    int* order = (int*)malloc((size_t)(obj->count > 0 ? obj->count : 1) * sizeof(int));
+    for (int i = 0; i < obj->count; i++) order[i] = i;
+    if (sort_keys && !json_obj_is_list(obj)) {
+        for (int i = 1; i < obj->count; i++) {
+            int cur = order[i];
+            const char* key = obj->names[cur] ? obj->names[cur] : "";
+            int j = i - 1;
+            while (j >= 0 && strcmp(obj->names[order[j]] ? obj->names[order[j]] : "", key) > 0) {
+                order[j + 1] = order[j];
+                j--;
+            }
+            order[j + 1] = cur;
+        }
+    }
 
-static void json_stringify_object_into(StrBuf* sb, SnaskObject* obj, bool pretty, int indent, int level) {
     sb_append_char(sb, '{');
     if (pretty && obj->count > 0) sb_append_char(sb, '\n');
-    for (int i = 0; i < obj->count; i++) {
+    for (int k = 0; k < obj->count; k++) {
+        int i = order[k];
         if (pretty) sb_append_indent(sb, level + 1, indent);
         sb_append_json_escaped(sb, obj->names[i] ? obj->names[i] : "");
         sb_append_char(sb, ':');
         if (pretty) sb_append_char(sb, ' ');
-        json_stringify_into(sb, &obj->values[i], pretty, indent, level + 1);
-        if (i < obj->count - 1) sb_append_char(sb, ',');
+        json_stringify_into(sb, &obj->values[i], pretty, indent, level + 1, sort_keys);
+        if (k < obj->count - 1) sb_append_char(sb, ',');
         if (pretty) sb_append_char(sb, '\n');
     }
     if (pretty && obj->count > 0) sb_append_indent(sb, level, indent);
     sb_append_char(sb, '}');
+    free(order);
 }
 
-static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int indent, int level) {
+static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int indent, int level, bool sort_keys) {
     int tag = (int)v->tag;
     if (tag == SNASK_NUM) {
         char tmp[64];
@@ -103,7 +131,7 @@ static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int inde
         sb_append_cstr(sb, v->num ? "true" : "false");
     } else if (tag == SNASK_OBJ) {
         SnaskObject* obj = (SnaskObject*)v->ptr;
-        json_stringify_object_into(sb, obj, pretty, indent, level);
+        json_stringify_object_into(sb, obj, pretty, indent, level, sort_keys);
     } else {
         sb_append_cstr(sb, "null");
     }
@@ -113,7 +141,7 @@ static void json_stringify_into(StrBuf* sb, SnaskValue* v, bool pretty, int inde
 void json_stringify(SnaskValue* out, SnaskValue* v) {
     StrBuf sb;
     sb_init(&sb);
-    json_stringify_into(&sb, v, false, 0, 0);
+    json_stringify_into(&sb, v, false, 0, 0, false);
     snask_gc_track_ptr(sb.data);
     *out = MAKE_STR(sb.data);
 }
@@ -121,7 +149,25 @@ void json_stringify(SnaskValue* out, SnaskValue* v) {
 void json_stringify_pretty(SnaskValue* out, SnaskValue* v) {
     StrBuf sb;
     sb_init(&sb);
-    json_stringify_into(&sb, v, true, 2, 0);
+    json_stringify_into(&sb, v, true, 2, 0, false);
+    snask_gc_track_ptr(sb.data);
+    *out = MAKE_STR(sb.data);
+}
+
+// json_stringify_opts(v, indent, sort_keys): `indent` spaces per level (0 is
+// compact, capped at 10) and, when `sort_keys` is truthy, object keys in
+// byte order at every level for reproducible output.
+void json_stringify_opts(SnaskValue* out, SnaskValue* v, SnaskValue* indent_val, SnaskValue* sort_val) {
+    int indent = 2;
+    if (indent_val && (int)indent_val->tag == SNASK_NUM) {
+        indent = (int)indent_val->num;
+        if (indent < 0) indent = 0;
+        if (indent > 10) indent = 10;
+    }
+    bool sort_keys = sort_val && ((int)sort_val->tag == SNASK_BOOL || (int)sort_val->tag == SNASK_NUM) && sort_val->num != 0.0;
+    StrBuf sb;
+    sb_init(&sb);
+    json_stringify_into(&sb, v, indent > 0, indent, 0, sort_keys);
     snask_gc_track_ptr(sb.data);
     *out = MAKE_STR(sb.data);
 }
@@ -6,6 +6,7 @@
 // Standard JSON
 void json_stringify(SnaskValue* out, SnaskValue* v);
 void json_stringify_pretty(SnaskValue* out, SnaskValue* v);
+void json_stringify_opts(SnaskValue* out, SnaskValue* v, SnaskValue* indent, SnaskValue* sort_keys);
 void json_parse(SnaskValue* out, SnaskValue* data);
 
 // SJSON / SNIF (Snask Interchange Format)
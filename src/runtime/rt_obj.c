@@ -2,13 +2,16 @@
 #include <string.h>
 #include <stdlib.h>
 #include <time.h>
+#ifndef SNASK_WASM
 #include <dlfcn.h>
 #include <sqlite3.h>
 #include <zlib.h>
+#endif
 #include "rt_base.h"
 #include "rt_obj.h"
 #include "rt_gc.h"
 #include "rt_json.h"
+#include "rt_error.h"
 
 // ABI version check — called by generated main() before anything else
 void s_check_abi(int expected_version) {
@@ -316,6 +319,14 @@ static int snask_name_is_index(const char* name, int idx) {
     return strcmp(name, buf) == 0;
 }
 
+// Lists and dicts share SnaskObject; a list has no names or only "0", "1", ...
+static bool snask_obj_is_list(const SnaskObject* obj) {
+    for (int i = 0; i < obj->count; i++) {
+        if (obj->names && obj->names[i] && !snask_name_is_index(obj->names[i], i)) return false;
+    }
+    return true;
+}
+
 void is_nil(SnaskValue* out, SnaskValue* val) {
     *out = MAKE_BOOL(val->tag == SNASK_NIL);
 }
@@ -327,6 +338,8 @@ void s_len(SnaskValue* out, SnaskValue* val) {
     else *out = MAKE_NUM(0);
 }
 
+// SQLite and zlib are host libraries; WebAssembly builds reject those imports.
+#ifndef SNASK_WASM
 static void* sqlite_handle_to_ptr(const char* h) {
     if (!h) return NULL;
     void* p = NULL;
@@ -591,6 +604,7 @@ void zlib_decompress(SnaskValue* out, SnaskValue* input) {
     text[dest_len] = '\0';
     *out = MAKE_STR(text);
 }
+#endif
 
 void json_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val) {
     if (obj_val->tag == SNASK_STR && obj_val->ptr && idx_val->tag == SNASK_NUM) {
@@ -620,6 +634,22 @@ void json_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val) {
     } else *out = MAKE_NIL();
 }
 
+// `target[index]` in compiled code: like json_get, but a number outside a list
+// or string is a panic instead of nil. Dicts keep giving nil for a missing key.
+void snask_index_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val) {
+    if (idx_val->tag == SNASK_NUM && obj_val->ptr &&
+        (obj_val->tag == SNASK_STR ||
+         (obj_val->tag == SNASK_OBJ && snask_obj_is_list((SnaskObject*)obj_val->ptr)))) {
+        int len = obj_val->tag == SNASK_STR ? (int)strlen((const char*)obj_val->ptr)
+                                            : ((SnaskObject*)obj_val->ptr)->count;
+        int idx = (int)idx_val->num;
+        if (idx < 0 || idx >= len) {
+            snask_panic("index %d out of bounds for length %d", idx, len);
+        }
+    }
+    json_get(out, obj_val, idx_val);
+}
+
 void snask_iter_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val) {
     if (!obj_val || !idx_val) { *out = MAKE_NIL(); return; }
     if (obj_val->tag == SNASK_STR) {
@@ -671,7 +701,60 @@ void json_set(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val, SnaskVa
     } else *out = MAKE_NIL();
 }
 
+// `target[index] = value` in compiled code: like json_set, but assigning to a
+// number outside a list or to something that is not a list or dict fails like
+// `snask_division_by_zero` instead of being ignored.
+void snask_index_set(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val, SnaskValue* val,
+                     int recoverable) {
+    if ((int)obj_val->tag != SNASK_OBJ || !obj_val->ptr) {
+        snask_fail(recoverable, "index assignment needs a list or dict");
+        *out = MAKE_NIL();
+        return;
+    }
+    if ((int)idx_val->tag == SNASK_NUM) {
+        int len = ((SnaskObject*)obj_val->ptr)->count;
+        int idx = (int)idx_val->num;
+        if (idx < 0 || idx >= len) {
+            snask_fail(recoverable, "index %d out of bounds for length %d", idx, len);
+            *out = MAKE_NIL();
+            return;
+        }
+    }
+    json_set(out, obj_val, idx_val, val);
+}
+
+// `item in container`: a substring of a str, a key of a dict or an element of
+// a list (compared like `==`). Any other container records a catchable error.
+void snask_in(SnaskValue* out, SnaskValue* item, SnaskValue* container) {
+    if ((int)container->tag == SNASK_STR && container->ptr) {
+        *out = MAKE_BOOL((int)item->tag == SNASK_STR && item->ptr &&
+                         strstr((const char*)container->ptr, (const char*)item->ptr) != NULL);
+        return;
+    }
+    if ((int)container->tag != SNASK_OBJ || !container->ptr) {
+        snask_error_set("'in' needs a list, dict or str on the right");
+        *out = MAKE_BOOL(false);
+        return;
+    }
+    SnaskObject* obj = (SnaskObject*)container->ptr;
+    bool is_list = snask_obj_is_list(obj);
+    for (int i = 0; i < obj->count; i++) {
+        if (is_list ? snask_value_eq_loose(item, &obj->values[i])
+                    : ((int)item->tag == SNASK_STR && obj->names[i] &&
+                       strcmp(obj->names[i], (const char*)item->ptr) == 0)) {
+            *out = MAKE_BOOL(true);
+            return;
+        }
+    }
+    *out = MAKE_BOOL(false);
+}
+
 void s_call_by_name(SnaskValue* out, SnaskValue* name_val, SnaskValue* arg1, SnaskValue* arg2, SnaskValue* arg3) {
+#ifdef SNASK_WASM
+    // No symbol lookup without a dynamic loader.
+    (void)name_val; (void)arg1; (void)arg2; (void)arg3;
+    *out = MAKE_NIL();
+#else
     if (name_val->tag != SNASK_STR) { *out = MAKE_NIL(); return; }
     char raw_name[512];
     strncpy(raw_name, (char*)name_val->ptr, 512);
@@ -699,4 +782,5 @@ void s_call_by_name(SnaskValue* out, SnaskValue* name_val, SnaskValue* arg1, Sna
     typedef void (*SnaskFn3)(SnaskValue*, SnaskValue*, SnaskValue*, SnaskValue*);
     SnaskFn3 f = (SnaskFn3)fp;
     f(out, arg1, arg2, arg3);
+#endif
 }
@@ -42,6 +42,10 @@ bool s_om_resource_release(SnaskValue* value, const char* expected_type);
 void s_free_obj(SnaskValue* obj_val);
 void s_get_member(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val);
 void s_set_member(SnaskValue* obj_val, SnaskValue* idx_val, SnaskValue* val);
+void snask_index_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val);
+void snask_index_set(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val, SnaskValue* val,
+                     int recoverable);
+void snask_in(SnaskValue* out, SnaskValue* item, SnaskValue* container);
 void snask_iter_get(SnaskValue* out, SnaskValue* obj_val, SnaskValue* idx_val);
 void zlib_compress(SnaskValue* out, SnaskValue* input);
 void zlib_decompress(SnaskValue* out, SnaskValue* input);
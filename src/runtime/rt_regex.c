@@ -0,0 +1,144 @@
+#include <regex.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "rt_regex.h"
+#include "rt_gc.h"
+
+// Regex module backed by POSIX extended regular expressions (libc <regex.h>),
+// so no extra library has to be linked. Invalid patterns yield nil.
+
+#define RX_MAX_GROUPS 10
+
+static bool rx_compile(regex_t* re, SnaskValue* pattern) {
+    if (!pattern || (int)pattern->tag != SNASK_STR || !pattern->ptr) return false;
+    return regcomp(re, (const char*)pattern->ptr, REG_EXTENDED) == 0;
+}
+
+static bool rx_is_str(SnaskValue* v) {
+    return v && (int)v->tag == SNASK_STR && v->ptr;
+}
+
+// With a capture group the first group is extracted, otherwise the whole match.
+static regmatch_t rx_pick(const regex_t* re, const regmatch_t* m) {
+    if (re->re_nsub > 0 && m[1].rm_so >= 0) return m[1];
+    return m[0];
+}
+
+static void rx_list_push(SnaskObject* arr, int* cap, SnaskValue v) {
+    if (arr->count >= *cap) {
+        int new_cap = (*cap == 0) ? 8 : *cap * 2;
+        arr->names = (char**)realloc(arr->names, (size_t)new_cap * sizeof(char*));
+        arr->values = (SnaskValue*)realloc(arr->values, (size_t)new_cap * sizeof(SnaskValue));
+        for (int i = *cap; i < new_cap; i++) {
+            arr->names[i] = NULL;
+            arr->values[i] = MAKE_NIL();
+        }
+        *cap = new_cap;
+    }
+    char idx_name[32];
+    snprintf(idx_name, sizeof(idx_name), "%d", arr->count);
+    arr->names[arr->count] = snask_gc_strdup(idx_name);
+    arr->values[arr->count] = v;
+    arr->count++;
+}
+
+// regex_match(pattern, text) -> bool (true if the pattern matches anywhere)
+void regex_match(SnaskValue* out, SnaskValue* pattern, SnaskValue* text) {
+    regex_t re;
+    if (!rx_is_str(text) || !rx_compile(&re, pattern)) { *out = MAKE_NIL(); return; }
+    int rc = regexec(&re, (const char*)text->ptr, 0, NULL, 0);
+    regfree(&re);
+    *out = MAKE_BOOL(rc == 0);
+}
+
+// regex_find(pattern, text) -> first match (or its first group), nil if none
+void regex_find(SnaskValue* out, SnaskValue* pattern, SnaskValue* text) {
+    regex_t re;
+    if (!rx_is_str(text) || !rx_compile(&re, pattern)) { *out = MAKE_NIL(); return; }
+    const char* src = (const char*)text->ptr;
+    regmatch_t m[RX_MAX_GROUPS];
+    if (regexec(&re, src, RX_MAX_GROUPS, m, 0) != 0) {
+        regfree(&re);
+        *out = MAKE_NIL();
+        return;
+    }
+    regmatch_t hit = rx_pick(&re, m);
+    regfree(&re);
+    *out = MAKE_STR(snask_gc_strndup(src + hit.rm_so, (size_t)(hit.rm_eo - hit.rm_so)));
+}
+
+// regex_find_all(pattern, text) -> list of every non-overlapping match
+void regex_find_all(SnaskValue* out, SnaskValue* pattern, SnaskValue* text) {
+    regex_t re;
+    if (!rx_is_str(text) || !rx_compile(&re, pattern)) { *out = MAKE_NIL(); return; }
+    const char* src = (const char*)text->ptr;
+    size_t len = strlen(src);
+
+    SnaskObject* arr = (SnaskObject*)malloc(sizeof(SnaskObject));
+    arr->count = 0; arr->names = NULL; arr->values = NULL;
+    int cap = 0;
+
+    size_t pos = 0;
+    regmatch_t m[RX_MAX_GROUPS];
+    while (pos <= len && regexec(&re, src + pos, RX_MAX_GROUPS, m, pos > 0 ? REG_NOTBOL : 0) == 0) {
+        regmatch_t hit = rx_pick(&re, m);
+        rx_list_push(arr, &cap, MAKE_STR(snask_gc_strndup(src + pos + hit.rm_so, (size_t)(hit.rm_eo - hit.rm_so))));
+        // Empty matches still have to make progress.
+        pos += (m[0].rm_eo > m[0].rm_so) ? (size_t)m[0].rm_eo : (size_t)m[0].rm_so + 1;
+    }
+    regfree(&re);
+    *out = MAKE_OBJ(arr);
+}
+
+// regex_replace(pattern, text, repl) -> text with every match replaced.
+// `\0`..`\9` in repl insert the whole match / a capture group, `\\` is a backslash.
+void regex_replace(SnaskValue* out, SnaskValue* pattern, SnaskValue* text, SnaskValue* repl) {
+    regex_t re;
+    if (!rx_is_str(text) || !rx_is_str(repl) || !rx_compile(&re, pattern)) { *out = MAKE_NIL(); return; }
+    const char* src = (const char*)text->ptr;
+    const char* rep = (const char*)repl->ptr;
+    size_t len = strlen(src);
+
+    size_t cap = len + 64, n = 0;
+    char* buf = (char*)malloc(cap);
+#define RX_PUT(p, k) do { \
+        size_t _k = (k); \
+        if (n + _k + 1 > cap) { while (n + _k + 1 > cap) cap *= 2; buf = (char*)realloc(buf, cap); } \
+        memcpy(buf + n, (p), _k); n += _k; \
+    } while (0)
+
+    size_t pos = 0;
+    regmatch_t m[RX_MAX_GROUPS];
+    while (pos <= len && regexec(&re, src + pos, RX_MAX_GROUPS, m, pos > 0 ? REG_NOTBOL : 0) == 0) {
+        RX_PUT(src + pos, (size_t)m[0].rm_so);
+        for (const char* r = rep; *r; r++) {
+            if (r[0] == '\\' && r[1] >= '0' && r[1] <= '9') {
+                int g = r[1] - '0';
+                if ((size_t)g <= re.re_nsub && m[g].rm_so >= 0) {
+                    RX_PUT(src + pos + m[g].rm_so, (size_t)(m[g].rm_eo - m[g].rm_so));
+                }
+                r++;
+            } else if (r[0] == '\\' && r[1] == '\\') {
+                RX_PUT("\\", 1);
+                r++;
+            } else {
+                RX_PUT(r, 1);
+            }
+        }
+        if (m[0].rm_eo > m[0].rm_so) {
+            pos += (size_t)m[0].rm_eo;
+        } else {
+            // Empty match: keep the next character and move past it.
+            pos += (size_t)m[0].rm_so;
+            if (pos < len) RX_PUT(src + pos, 1);
+            pos += 1;
+        }
+    }
+    if (pos < len) RX_PUT(src + pos, len - pos);
+#undef RX_PUT
+    buf[n] = '\0';
+    regfree(&re);
+    *out = MAKE_STR(snask_gc_strdup(buf));
+    free(buf);
+}
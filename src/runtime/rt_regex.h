@@ -0,0 +1,11 @@
+#ifndef RT_REGEX_H
+#define RT_REGEX_H
+
+#include "rt_base.h"
+
+void regex_match(SnaskValue* out, SnaskValue* pattern, SnaskValue* text);
+void regex_find(SnaskValue* out, SnaskValue* pattern, SnaskValue* text);
+void regex_find_all(SnaskValue* out, SnaskValue* pattern, SnaskValue* text);
+void regex_replace(SnaskValue* out, SnaskValue* pattern, SnaskValue* text, SnaskValue* repl);
+
+#endif // RT_REGEX_H
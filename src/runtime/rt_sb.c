@@ -0,0 +1,102 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "rt_sb.h"
+#include "rt_error.h"
+#include "rt_gc.h"
+
+// String builders: a growable buffer behind a "%p" handle string, like the
+// sfs/gui/sqlite handles. Appending doubles the capacity when it runs out, so
+// building a string of n bytes costs O(n) instead of the O(n^2) of repeated
+// `+`. Live builders are kept in a list so a freed or made-up handle is
+// reported as an error instead of being dereferenced.
+
+typedef struct SbBuf {
+    char* data;
+    size_t len;
+    size_t cap;
+    struct SbBuf* next;
+} SbBuf;
+
+static SbBuf* sb_live = NULL;
+
+static SbBuf* sb_from_handle(const char* who, SnaskValue* handle) {
+    void* p = NULL;
+    if (handle && (int)handle->tag == SNASK_STR && handle->ptr) {
+        sscanf((const char*)handle->ptr, "%p", &p);
+    }
+    for (SbBuf* b = sb_live; p && b; b = b->next) {
+        if (b == p) return b;
+    }
+    snask_error_set("%s: invalid or freed string builder", who);
+    return NULL;
+}
+
+// sb_create() -> handle of an empty builder
+void sb_create(SnaskValue* out) {
+    SbBuf* b = (SbBuf*)calloc(1, sizeof(SbBuf));
+    if (b) b->data = (char*)malloc(64);
+    if (!b || !b->data) {
+        free(b);
+        snask_error_set("sb::create: out of memory");
+        *out = MAKE_NIL();
+        return;
+    }
+    b->data[0] = '\0';
+    b->cap = 64;
+    b->next = sb_live;
+    sb_live = b;
+    char buf[64];
+    snprintf(buf, sizeof(buf), "%p", (void*)b);
+    *out = MAKE_STR(snask_gc_strdup(buf));
+}
+
+// sb_append(sb, text) -> true once text is at the end of the builder
+void sb_append(SnaskValue* out, SnaskValue* handle, SnaskValue* text) {
+    SbBuf* b = sb_from_handle("sb::append", handle);
+    if (!b) { *out = MAKE_BOOL(false); return; }
+    if (!text || (int)text->tag != SNASK_STR) {
+        snask_error_set("sb::append: expected a str");
+        *out = MAKE_BOOL(false);
+        return;
+    }
+    const char* s = text->ptr ? (const char*)text->ptr : "";
+    size_t n = strlen(s);
+    if (b->len + n + 1 > b->cap) {
+        size_t cap = b->cap;
+        while (b->len + n + 1 > cap) cap *= 2;
+        char* data = (char*)realloc(b->data, cap);
+        if (!data) {
+            snask_error_set("sb::append: out of memory");
+            *out = MAKE_BOOL(false);
+            return;
+        }
+        b->data = data;
+        b->cap = cap;
+    }
+    memcpy(b->data + b->len, s, n + 1);
+    b->len += n;
+    *out = MAKE_BOOL(true);
+}
+
+// sb_to_string(sb) -> copy of everything appended so far
+void sb_to_string(SnaskValue* out, SnaskValue* handle) {
+    SbBuf* b = sb_from_handle("sb::to_string", handle);
+    if (!b) { *out = MAKE_NIL(); return; }
+    char* s = (char*)snask_gc_malloc(b->len + 1);
+    if (!s) { *out = MAKE_NIL(); return; }
+    memcpy(s, b->data, b->len + 1);
+    *out = MAKE_STR(s);
+}
+
+// sb_free(sb) -> true once the builder's memory is released
+void sb_free(SnaskValue* out, SnaskValue* handle) {
+    SbBuf* b = sb_from_handle("sb::free", handle);
+    if (!b) { *out = MAKE_BOOL(false); return; }
+    for (SbBuf** link = &sb_live; *link; link = &(*link)->next) {
+        if (*link == b) { *link = b->next; break; }
+    }
+    free(b->data);
+    free(b);
+    *out = MAKE_BOOL(true);
+}
@@ -0,0 +1,11 @@
+#ifndef RT_SB_H
+#define RT_SB_H
+
+#include "rt_base.h"
+
+void sb_create(SnaskValue* out);
+void sb_append(SnaskValue* out, SnaskValue* handle, SnaskValue* text);
+void sb_to_string(SnaskValue* out, SnaskValue* handle);
+void sb_free(SnaskValue* out, SnaskValue* handle);
+
+#endif // RT_SB_H
@@ -8,11 +8,16 @@
 #include <dirent.h>
 #include "rt_sfs.h"
 #include "rt_gc.h"
+#include "rt_error.h"
 
 void sfs_read(SnaskValue* out, SnaskValue* path) {
     if ((int)path->tag != SNASK_STR) { *out = MAKE_NIL(); return; }
     FILE *f = fopen((char*)path->ptr, "rb");
-    if (!f) { *out = MAKE_NIL(); return; }
+    if (!f) {
+        snask_error_set("sfs::read: cannot open '%s': %s", (char*)path->ptr, strerror(errno));
+        *out = MAKE_NIL();
+        return;
+    }
     fseek(f, 0, SEEK_END); long sz = ftell(f); fseek(f, 0, SEEK_SET);
     char *s = (char*)malloc(sz + 1); 
     if (!s) { fclose(f); *out = MAKE_NIL(); return; }
@@ -47,7 +52,11 @@ void binfile_read_into(SnaskValue* out, SnaskValue* path, SnaskValue* dst, Snask
 void sfs_write(SnaskValue* out, SnaskValue* path, SnaskValue* content) {
     if ((int)path->tag != SNASK_STR || (int)content->tag != SNASK_STR) { *out = MAKE_BOOL(false); return; }
     FILE *f = fopen((char*)path->ptr, "w");
-    if (!f) { *out = MAKE_BOOL(false); return; }
+    if (!f) {
+        snask_error_set("sfs::write: cannot open '%s': %s", (char*)path->ptr, strerror(errno));
+        *out = MAKE_BOOL(false);
+        return;
+    }
     fprintf(f, "%s", (char*)content->ptr);
     fflush(f);
     fclose(f);
@@ -57,7 +66,11 @@ void sfs_write(SnaskValue* out, SnaskValue* path, SnaskValue* content) {
 void sfs_append(SnaskValue* out, SnaskValue* path, SnaskValue* content) {
     if ((int)path->tag != SNASK_STR || (int)content->tag != SNASK_STR) { *out = MAKE_BOOL(false); return; }
     FILE *f = fopen((char*)path->ptr, "a");
-    if (!f) { *out = MAKE_BOOL(false); return; }
+    if (!f) {
+        snask_error_set("sfs::append: cannot open '%s': %s", (char*)path->ptr, strerror(errno));
+        *out = MAKE_BOOL(false);
+        return;
+    }
     fprintf(f, "%s", (char*)content->ptr);
     fflush(f);
     fclose(f);
@@ -201,7 +214,11 @@ void sfs_mtime(SnaskValue* out, SnaskValue* path) {
 void sfs_listdir(SnaskValue* out, SnaskValue* path) {
     if ((int)path->tag != SNASK_STR) { *out = MAKE_NIL(); return; }
     DIR* d = opendir((const char*)path->ptr);
-    if (!d) { *out = MAKE_NIL(); return; }
+    if (!d) {
+        snask_error_set("sfs::listdir: cannot open '%s': %s", (const char*)path->ptr, strerror(errno));
+        *out = MAKE_NIL();
+        return;
+    }
 
     SnaskObject* arr = (SnaskObject*)malloc(sizeof(SnaskObject));
     arr->count = 0; arr->names = NULL; arr->values = NULL;
@@ -258,6 +275,91 @@ void sfs_bench_create_small_files(SnaskValue* out, SnaskValue* dir, SnaskValue*
     *out = MAKE_NUM((double)created);
 }
 
+// --- Streaming ---
+// Files opened with sfs_open are referred to by a "%p" handle string, like the
+// gui/sqlite handles. Open files are tracked so a stale or made-up handle is
+// reported as an error instead of being dereferenced.
+
+#define SFS_MAX_OPEN 64
+static FILE* sfs_open_files[SFS_MAX_OPEN];
+
+static FILE* sfs_file_from_handle(const char* who, SnaskValue* handle) {
+    void* p = NULL;
+    if (handle && (int)handle->tag == SNASK_STR && handle->ptr) {
+        sscanf((const char*)handle->ptr, "%p", &p);
+    }
+    for (int i = 0; p && i < SFS_MAX_OPEN; i++) {
+        if (sfs_open_files[i] == p) return (FILE*)p;
+    }
+    snask_error_set("%s: invalid or closed file handle", who);
+    return NULL;
+}
+
+// sfs_open(path, mode) -> handle; mode is "r", "w" or "a"
+void sfs_open(SnaskValue* out, SnaskValue* path, SnaskValue* mode) {
+    if (!path || (int)path->tag != SNASK_STR || !path->ptr) { *out = MAKE_NIL(); return; }
+    const char* m = (mode && (int)mode->tag == SNASK_STR && mode->ptr) ? (const char*)mode->ptr : "";
+    if (strcmp(m, "r") != 0 && strcmp(m, "w") != 0 && strcmp(m, "a") != 0) {
+        snask_error_set("sfs::open: mode must be \"r\", \"w\" or \"a\", got \"%s\"", m);
+        *out = MAKE_NIL();
+        return;
+    }
+    int slot = -1;
+    for (int i = 0; i < SFS_MAX_OPEN && slot < 0; i++) {
+        if (!sfs_open_files[i]) slot = i;
+    }
+    if (slot < 0) {
+        snask_error_set("sfs::open: too many open files (max %d)", SFS_MAX_OPEN);
+        *out = MAKE_NIL();
+        return;
+    }
+    FILE* f = fopen((const char*)path->ptr, m);
+    if (!f) {
+        snask_error_set("sfs::open: cannot open '%s': %s", (const char*)path->ptr, strerror(errno));
+        *out = MAKE_NIL();
+        return;
+    }
+    sfs_open_files[slot] = f;
+    char buf[64];
+    snprintf(buf, sizeof(buf), "%p", (void*)f);
+    *out = MAKE_STR(snask_gc_strdup(buf));
+}
+
+// sfs_read_line(handle) -> next line without its "\n"/"\r\n", nil at EOF
+void sfs_read_line(SnaskValue* out, SnaskValue* handle) {
+    FILE* f = sfs_file_from_handle("sfs::read_line", handle);
+    if (!f) { *out = MAKE_NIL(); return; }
+    char* line = NULL;
+    size_t cap = 0;
+    ssize_t n = getline(&line, &cap, f);
+    if (n < 0) {
+        free(line);
+        *out = MAKE_NIL();
+        return;
+    }
+    if (n > 0 && line[n - 1] == '\n') line[--n] = '\0';
+    if (n > 0 && line[n - 1] == '\r') line[--n] = '\0';
+    snask_gc_track_ptr(line);
+    *out = MAKE_STR(line);
+}
+
+// sfs_write_line(handle, text) -> writes text plus "\n"
+void sfs_write_line(SnaskValue* out, SnaskValue* handle, SnaskValue* text) {
+    FILE* f = sfs_file_from_handle("sfs::write_line", handle);
+    if (!f || !text || (int)text->tag != SNASK_STR || !text->ptr) { *out = MAKE_BOOL(false); return; }
+    *out = MAKE_BOOL(fputs((const char*)text->ptr, f) >= 0 && fputc('\n', f) != EOF);
+}
+
+// sfs_close(handle) -> true once the file is flushed and closed
+void sfs_close(SnaskValue* out, SnaskValue* handle) {
+    FILE* f = sfs_file_from_handle("sfs::close", handle);
+    if (!f) { *out = MAKE_BOOL(false); return; }
+    for (int i = 0; i < SFS_MAX_OPEN; i++) {
+        if (sfs_open_files[i] == f) sfs_open_files[i] = NULL;
+    }
+    *out = MAKE_BOOL(fclose(f) == 0);
+}
+
 void sfs_bench_count_entries(SnaskValue* out, SnaskValue* dir) {
     if ((int)dir->tag != SNASK_STR || !dir->ptr) { *out = MAKE_NUM(0); return; }
     DIR* d = opendir((const char*)dir->ptr);
@@ -21,6 +21,10 @@ void sfs_delete(SnaskValue* out, SnaskValue* path);
 void sfs_size(SnaskValue* out, SnaskValue* path);
 void sfs_mtime(SnaskValue* out, SnaskValue* path);
 void sfs_listdir(SnaskValue* out, SnaskValue* path);
+void sfs_open(SnaskValue* out, SnaskValue* path, SnaskValue* mode);
+void sfs_read_line(SnaskValue* out, SnaskValue* handle);
+void sfs_write_line(SnaskValue* out, SnaskValue* handle, SnaskValue* text);
+void sfs_close(SnaskValue* out, SnaskValue* handle);
 
 // Benchmarking functions
 void sfs_bench_create_small_files(SnaskValue* out, SnaskValue* dir, SnaskValue* n_files, SnaskValue* size_bytes);
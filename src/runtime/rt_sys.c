@@ -1,10 +1,15 @@
+#include <ctype.h>
+#include <math.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 #include <sys/utsname.h>
+#include <time.h>
 #include <unistd.h>
 #include "rt_base.h"
+#include "rt_error.h"
 #include "rt_gc.h"
+#include "rt_json.h"
 
 void num_to_str(SnaskValue* out, SnaskValue* n) {
     if (!n || (int)n->tag != SNASK_NUM) { *out = MAKE_NIL(); return; }
@@ -21,6 +26,567 @@ void str_to_num(SnaskValue* out, SnaskValue* s) {
     *out = MAKE_NUM(v);
 }
 
+// Strict number parsing for to_int/to_float: surrounding whitespace is fine,
+// anything else left over ("12abc") is not.
+static int parse_number(const char* s, double* out) {
+    while (isspace((unsigned char)*s)) s++;
+    if (*s == '\0') return 0;
+    char* end = NULL;
+    double v = strtod(s, &end);
+    if (end == s) return 0;
+    while (isspace((unsigned char)*end)) end++;
+    if (*end != '\0') return 0;
+    *out = v;
+    return 1;
+}
+
+static const char* type_name_of(SnaskValue* v) {
+    switch (v ? (int)v->tag : SNASK_NIL) {
+        case SNASK_NUM: return "number";
+        case SNASK_BOOL: return "bool";
+        case SNASK_STR: return "str";
+        case SNASK_OBJ: return "object";
+        case SNASK_NIL: return "nil";
+        default: return "resource";
+    }
+}
+
+// A list of `n` nil items named "0".."n-1", ready to be filled in.
+static SnaskObject* new_list(int n) {
+    SnaskObject* list = (SnaskObject*)malloc(sizeof(SnaskObject));
+    list->count = n;
+    list->names = (char**)malloc((size_t)(n > 0 ? n : 1) * sizeof(char*));
+    list->values = (SnaskValue*)malloc((size_t)(n > 0 ? n : 1) * sizeof(SnaskValue));
+    for (int i = 0; i < n; i++) {
+        char idx_name[32];
+        snprintf(idx_name, sizeof(idx_name), "%d", i);
+        list->names[i] = snask_gc_strdup(idx_name);
+        list->values[i] = MAKE_NIL();
+    }
+    return list;
+}
+
+// The list behind `v`, or NULL after recording a catchable error for `who`.
+static SnaskObject* list_arg(const char* who, SnaskValue* v) {
+    if (!v || (int)v->tag != SNASK_OBJ || !v->ptr) {
+        snask_error_set("%s: expected a list, got %s", who, type_name_of(v));
+        return NULL;
+    }
+    return (SnaskObject*)v->ptr;
+}
+
+// The dict behind `v`, or NULL after recording a catchable error for `who`.
+static SnaskObject* dict_arg(const char* who, SnaskValue* v) {
+    if (!v || (int)v->tag != SNASK_OBJ || !v->ptr) {
+        snask_error_set("%s: expected a dict, got %s", who, type_name_of(v));
+        return NULL;
+    }
+    return (SnaskObject*)v->ptr;
+}
+
+// Shared by to_float/to_int: numbers pass through, bools become 1/0 and
+// strings are parsed. Anything else records a catchable error for `who`.
+static int convert_number(const char* who, SnaskValue* v, double* out) {
+    int tag = v ? (int)v->tag : SNASK_NIL;
+    if (tag == SNASK_NUM || tag == SNASK_BOOL) {
+        *out = v->num;
+        return 1;
+    }
+    if (tag == SNASK_STR && v->ptr && parse_number((const char*)v->ptr, out)) return 1;
+    if (tag == SNASK_STR) {
+        snask_error_set("%s: cannot convert \"%s\" to a number", who, v->ptr ? (const char*)v->ptr : "");
+    } else {
+        snask_error_set("%s: cannot convert %s to a number", who, type_name_of(v));
+    }
+    return 0;
+}
+
+void s_to_float(SnaskValue* out, SnaskValue* v) {
+    double n = 0.0;
+    *out = convert_number("to_float", v, &n) ? MAKE_NUM(n) : MAKE_NIL();
+}
+
+// to_int(v): like to_float, then truncated toward zero (3.9 -> 3, -3.9 -> -3).
+void s_to_int(SnaskValue* out, SnaskValue* v) {
+    double n = 0.0;
+    if (!convert_number("to_int", v, &n)) { *out = MAKE_NIL(); return; }
+    if (!isfinite(n)) {
+        snask_error_set("to_int: %g has no integer value", n);
+        *out = MAKE_NIL();
+        return;
+    }
+    *out = MAKE_NUM(trunc(n));
+}
+
+// to_str(v): never fails; objects are rendered as JSON.
+void s_to_str(SnaskValue* out, SnaskValue* v) {
+    int tag = v ? (int)v->tag : SNASK_NIL;
+    switch (tag) {
+        case SNASK_STR: *out = MAKE_STR(v->ptr ? v->ptr : snask_gc_strdup("")); return;
+        case SNASK_NUM: num_to_str(out, v); return;
+        case SNASK_BOOL: *out = MAKE_STR(snask_gc_strdup(v->num != 0.0 ? "true" : "false")); return;
+        case SNASK_OBJ: json_stringify(out, v); return;
+        case SNASK_NIL: *out = MAKE_STR(snask_gc_strdup("nil")); return;
+        default: *out = MAKE_STR(snask_gc_strdup("<resource>")); return;
+    }
+}
+
+// to_bool(v): truthiness. nil, false, 0 and "" are false; everything else is true.
+void s_to_bool(SnaskValue* out, SnaskValue* v) {
+    int tag = v ? (int)v->tag : SNASK_NIL;
+    switch (tag) {
+        case SNASK_NIL: *out = MAKE_BOOL(0); return;
+        case SNASK_NUM:
+        case SNASK_BOOL: *out = MAKE_BOOL(v->num != 0.0); return;
+        case SNASK_STR: *out = MAKE_BOOL(v->ptr && ((const char*)v->ptr)[0] != '\0'); return;
+        default: *out = MAKE_BOOL(v->ptr != NULL); return;
+    }
+}
+
+// Numbers are doubles, so integers are exact only up to 2^53; the integer
+// math builtins reject arguments and results beyond that.
+#define SNASK_MAX_EXACT_INT 9007199254740992LL
+
+// Reads an integer argument for `who`, recording a catchable error otherwise.
+static int int_arg(const char* who, SnaskValue* v, long long* out) {
+    if (!v || (int)v->tag != SNASK_NUM) {
+        snask_error_set("%s: expected an int, got %s", who, type_name_of(v));
+        return 0;
+    }
+    if (v->num != trunc(v->num) || fabs(v->num) > (double)SNASK_MAX_EXACT_INT) {
+        snask_error_set("%s: %g is not an int", who, v->num);
+        return 0;
+    }
+    *out = (long long)v->num;
+    return 1;
+}
+
+static long long gcd_of(long long a, long long b) {
+    if (a < 0) a = -a;
+    if (b < 0) b = -b;
+    while (b != 0) {
+        long long t = a % b;
+        a = b;
+        b = t;
+    }
+    return a;
+}
+
+void s_gcd(SnaskValue* out, SnaskValue* a, SnaskValue* b) {
+    long long x = 0, y = 0;
+    if (!int_arg("gcd", a, &x) || !int_arg("gcd", b, &y)) { *out = MAKE_NIL(); return; }
+    *out = MAKE_NUM((double)gcd_of(x, y));
+}
+
+void s_lcm(SnaskValue* out, SnaskValue* a, SnaskValue* b) {
+    long long x = 0, y = 0;
+    if (!int_arg("lcm", a, &x) || !int_arg("lcm", b, &y)) { *out = MAKE_NIL(); return; }
+    if (x == 0 || y == 0) { *out = MAKE_NUM(0); return; }
+    long long g = gcd_of(x, y);
+    long long r = 0;
+    if (__builtin_mul_overflow(llabs(x / g), llabs(y), &r) || r > SNASK_MAX_EXACT_INT) {
+        snask_error_set("lcm: lcm(%lld, %lld) does not fit in an int", x, y);
+        *out = MAKE_NIL();
+        return;
+    }
+    *out = MAKE_NUM((double)r);
+}
+
+// factorial(n): 18! is the largest one below 2^53.
+void s_factorial(SnaskValue* out, SnaskValue* n) {
+    long long k = 0;
+    if (!int_arg("factorial", n, &k)) { *out = MAKE_NIL(); return; }
+    if (k < 0) {
+        snask_error_set("factorial: %lld is negative", k);
+        *out = MAKE_NIL();
+        return;
+    }
+    long long r = 1;
+    for (long long i = 2; i <= k; i++) {
+        if (__builtin_mul_overflow(r, i, &r) || r > SNASK_MAX_EXACT_INT) {
+            snask_error_set("factorial: %lld! does not fit in an int", k);
+            *out = MAKE_NIL();
+            return;
+        }
+    }
+    *out = MAKE_NUM((double)r);
+}
+
+// pow(base, exp), also what `base ** exp` compiles to.
+void s_pow(SnaskValue* out, SnaskValue* base, SnaskValue* exp) {
+    if (!base || (int)base->tag != SNASK_NUM || !exp || (int)exp->tag != SNASK_NUM) {
+        snask_error_set("pow: expected two numbers, got %s and %s", type_name_of(base), type_name_of(exp));
+        *out = MAKE_NIL();
+        return;
+    }
+    *out = MAKE_NUM(pow(base->num, exp->num));
+}
+
+// isqrt(n): floor(sqrt(n)), corrected for the rounding of sqrt on doubles.
+void s_isqrt(SnaskValue* out, SnaskValue* n) {
+    long long k = 0;
+    if (!int_arg("isqrt", n, &k)) { *out = MAKE_NIL(); return; }
+    if (k < 0) {
+        snask_error_set("isqrt: %lld is negative", k);
+        *out = MAKE_NIL();
+        return;
+    }
+    long long r = (long long)sqrt((double)k);
+    while (r * r > k) r--;
+    while ((r + 1) * (r + 1) <= k) r++;
+    *out = MAKE_NUM((double)r);
+}
+
+// Pseudo-random numbers come from one splitmix64 generator. random_seed(n)
+// makes the sequence reproducible; until it is called, the state is seeded
+// on first use from /dev/urandom, or the clock and pid where that is missing.
+static uint64_t rng_state;
+static int rng_seeded = 0;
+
+static uint64_t rng_next(void) {
+    if (!rng_seeded) {
+        FILE* f = fopen("/dev/urandom", "rb");
+        if (!f || fread(&rng_state, sizeof(rng_state), 1, f) != 1) {
+            rng_state = (uint64_t)time(NULL) ^ ((uint64_t)getpid() << 32);
+        }
+        if (f) fclose(f);
+        rng_seeded = 1;
+    }
+    uint64_t z = (rng_state += 0x9E3779B97F4A7C15ULL);
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// A double in [0, 1) with all 53 bits of the mantissa random.
+static double rng_unit(void) {
+    return (double)(rng_next() >> 11) * (1.0 / 9007199254740992.0);
+}
+
+void s_random_seed(SnaskValue* out, SnaskValue* n) {
+    long long seed = 0;
+    if (!int_arg("random_seed", n, &seed)) { *out = MAKE_NIL(); return; }
+    rng_state = (uint64_t)seed;
+    rng_seeded = 1;
+    *out = MAKE_NIL();
+}
+
+// random(): libc already has a `random`, hence the prefix.
+void s_random(SnaskValue* out) {
+    *out = MAKE_NUM(rng_unit());
+}
+
+// random_range(min, max): a float in [min, max).
+void s_random_range(SnaskValue* out, SnaskValue* min, SnaskValue* max) {
+    if (!min || !max || (int)min->tag != SNASK_NUM || (int)max->tag != SNASK_NUM) {
+        snask_error_set("random_range: expected two numbers");
+        *out = MAKE_NIL();
+        return;
+    }
+    if (min->num >= max->num) {
+        snask_error_set("random_range: min (%g) must be less than max (%g)", min->num, max->num);
+        *out = MAKE_NIL();
+        return;
+    }
+    double r = min->num + rng_unit() * (max->num - min->num);
+    // Rounding can land exactly on max when the range is huge.
+    *out = MAKE_NUM(r < max->num ? r : nextafter(max->num, min->num));
+}
+
+// Copies the items of a numeric list for the statistics builtins. Returns
+// the item count, or -1 after recording a catchable error for `who`.
+static int numeric_items(const char* who, SnaskValue* list, double** out) {
+    SnaskObject* obj = list_arg(who, list);
+    if (!obj) return -1;
+    double* items = (double*)malloc((size_t)(obj->count > 0 ? obj->count : 1) * sizeof(double));
+    for (int i = 0; i < obj->count; i++) {
+        if ((int)obj->values[i].tag != SNASK_NUM) {
+            snask_error_set("%s: item %d is %s, expected a number", who, i, type_name_of(&obj->values[i]));
+            free(items);
+            return -1;
+        }
+        items[i] = obj->values[i].num;
+    }
+    *out = items;
+    return obj->count;
+}
+
+// Like numeric_items, but an empty list is an error too: it has no mean,
+// median, standard deviation or mode.
+static int nonempty_numeric_items(const char* who, SnaskValue* list, double** out) {
+    int n = numeric_items(who, list, out);
+    if (n == 0) {
+        free(*out);
+        snask_error_set("%s: the list is empty", who);
+        return -1;
+    }
+    return n;
+}
+
+static int compare_doubles(const void* a, const void* b) {
+    double x = *(const double*)a, y = *(const double*)b;
+    return (x > y) - (x < y);
+}
+
+// sum(list): 0 for an empty list.
+void s_sum(SnaskValue* out, SnaskValue* list) {
+    double* items = NULL;
+    int n = numeric_items("sum", list, &items);
+    if (n < 0) { *out = MAKE_NIL(); return; }
+    double total = 0.0;
+    for (int i = 0; i < n; i++) total += items[i];
+    free(items);
+    *out = MAKE_NUM(total);
+}
+
+void s_mean(SnaskValue* out, SnaskValue* list) {
+    double* items = NULL;
+    int n = nonempty_numeric_items("mean", list, &items);
+    if (n < 0) { *out = MAKE_NIL(); return; }
+    double total = 0.0;
+    for (int i = 0; i < n; i++) total += items[i];
+    free(items);
+    *out = MAKE_NUM(total / n);
+}
+
+// median(list): the middle item, or the mean of the two middle ones.
+void s_median(SnaskValue* out, SnaskValue* list) {
+    double* items = NULL;
+    int n = nonempty_numeric_items("median", list, &items);
+    if (n < 0) { *out = MAKE_NIL(); return; }
+    qsort(items, (size_t)n, sizeof(double), compare_doubles);
+    double m = n % 2 ? items[n / 2] : (items[n / 2 - 1] + items[n / 2]) / 2.0;
+    free(items);
+    *out = MAKE_NUM(m);
+}
+
+// stddev(list): population standard deviation (divides by n).
+void s_stddev(SnaskValue* out, SnaskValue* list) {
+    double* items = NULL;
+    int n = nonempty_numeric_items("stddev", list, &items);
+    if (n < 0) { *out = MAKE_NIL(); return; }
+    double total = 0.0;
+    for (int i = 0; i < n; i++) total += items[i];
+    double avg = total / n;
+    double squares = 0.0;
+    for (int i = 0; i < n; i++) squares += (items[i] - avg) * (items[i] - avg);
+    free(items);
+    *out = MAKE_NUM(sqrt(squares / n));
+}
+
+// mode(list): the most frequent item; on a tie, the one that appears first.
+void s_mode(SnaskValue* out, SnaskValue* list) {
+    double* items = NULL;
+    int n = nonempty_numeric_items("mode", list, &items);
+    if (n < 0) { *out = MAKE_NIL(); return; }
+    double best = items[0];
+    int best_count = 0;
+    for (int i = 0; i < n; i++) {
+        int count = 0;
+        for (int j = 0; j < n; j++) {
+            if (items[j] == items[i]) count++;
+        }
+        if (count > best_count) {
+            best = items[i];
+            best_count = count;
+        }
+    }
+    free(items);
+    *out = MAKE_NUM(best);
+}
+
+// zip(a, b): [[a0, b0], [a1, b1], ...], as long as the shorter list.
+void s_zip(SnaskValue* out, SnaskValue* a, SnaskValue* b) {
+    SnaskObject* left = list_arg("zip", a);
+    SnaskObject* right = left ? list_arg("zip", b) : NULL;
+    if (!right) { *out = MAKE_NIL(); return; }
+    int n = left->count < right->count ? left->count : right->count;
+    SnaskObject* pairs = new_list(n);
+    for (int i = 0; i < n; i++) {
+        SnaskObject* pair = new_list(2);
+        pair->values[0] = left->values[i];
+        pair->values[1] = right->values[i];
+        pairs->values[i] = MAKE_OBJ(pair);
+    }
+    *out = MAKE_OBJ(pairs);
+}
+
+// enumerate(list): [[0, item0], [1, item1], ...].
+void s_enumerate(SnaskValue* out, SnaskValue* list) {
+    SnaskObject* items = list_arg("enumerate", list);
+    if (!items) { *out = MAKE_NIL(); return; }
+    SnaskObject* pairs = new_list(items->count);
+    for (int i = 0; i < items->count; i++) {
+        SnaskObject* pair = new_list(2);
+        pair->values[0] = MAKE_NUM((double)i);
+        pair->values[1] = items->values[i];
+        pairs->values[i] = MAKE_OBJ(pair);
+    }
+    *out = MAKE_OBJ(pairs);
+}
+
+// chunk(list, n): consecutive sublists of `n` items; the last one is shorter
+// when the length is not a multiple of `n`.
+void s_chunk(SnaskValue* out, SnaskValue* list, SnaskValue* size) {
+    SnaskObject* items = list_arg("chunk", list);
+    long long n = 0;
+    if (!items || !int_arg("chunk", size, &n)) { *out = MAKE_NIL(); return; }
+    if (n <= 0) {
+        snask_error_set("chunk: size must be positive, got %lld", n);
+        *out = MAKE_NIL();
+        return;
+    }
+    int chunks = (int)((items->count + n - 1) / n);
+    SnaskObject* result = new_list(chunks);
+    for (int c = 0; c < chunks; c++) {
+        int start = (int)(c * n);
+        int len = items->count - start < n ? items->count - start : (int)n;
+        SnaskObject* part = new_list(len);
+        for (int i = 0; i < len; i++) part->values[i] = items->values[start + i];
+        result->values[c] = MAKE_OBJ(part);
+    }
+    *out = MAKE_OBJ(result);
+}
+
+// Dict helpers. A dict keeps its keys in insertion order; these builtins
+// return them in that order.
+
+void s_dict_keys(SnaskValue* out, SnaskValue* d) {
+    SnaskObject* dict = dict_arg("dict_keys", d);
+    if (!dict) { *out = MAKE_NIL(); return; }
+    SnaskObject* keys = new_list(dict->count);
+    for (int i = 0; i < dict->count; i++) {
+        keys->values[i] = MAKE_STR(snask_gc_strdup(dict->names[i] ? dict->names[i] : ""));
+    }
+    *out = MAKE_OBJ(keys);
+}
+
+void s_dict_values(SnaskValue* out, SnaskValue* d) {
+    SnaskObject* dict = dict_arg("dict_values", d);
+    if (!dict) { *out = MAKE_NIL(); return; }
+    SnaskObject* values = new_list(dict->count);
+    for (int i = 0; i < dict->count; i++) values->values[i] = dict->values[i];
+    *out = MAKE_OBJ(values);
+}
+
+// dict_items(d): [[key, value], ...].
+void s_dict_items(SnaskValue* out, SnaskValue* d) {
+    SnaskObject* dict = dict_arg("dict_items", d);
+    if (!dict) { *out = MAKE_NIL(); return; }
+    SnaskObject* items = new_list(dict->count);
+    for (int i = 0; i < dict->count; i++) {
+        SnaskObject* pair = new_list(2);
+        pair->values[0] = MAKE_STR(snask_gc_strdup(dict->names[i] ? dict->names[i] : ""));
+        pair->values[1] = dict->values[i];
+        items->values[i] = MAKE_OBJ(pair);
+    }
+    *out = MAKE_OBJ(items);
+}
+
+// dict_merge(a, b): a new dict with the entries of `a`, then those of `b`.
+// A key in both keeps its place from `a` and takes the value from `b`.
+void s_dict_merge(SnaskValue* out, SnaskValue* a, SnaskValue* b) {
+    SnaskObject* left = dict_arg("dict_merge", a);
+    SnaskObject* right = left ? dict_arg("dict_merge", b) : NULL;
+    if (!right) { *out = MAKE_NIL(); return; }
+    int cap = left->count + right->count;
+    SnaskObject* merged = (SnaskObject*)malloc(sizeof(SnaskObject));
+    merged->count = 0;
+    merged->names = (char**)malloc((size_t)(cap > 0 ? cap : 1) * sizeof(char*));
+    merged->values = (SnaskValue*)malloc((size_t)(cap > 0 ? cap : 1) * sizeof(SnaskValue));
+    SnaskObject* sources[2] = { left, right };
+    for (int s = 0; s < 2; s++) {
+        for (int i = 0; i < sources[s]->count; i++) {
+            const char* key = sources[s]->names[i] ? sources[s]->names[i] : "";
+            int at = -1;
+            for (int j = 0; j < merged->count; j++) {
+                if (strcmp(merged->names[j], key) == 0) { at = j; break; }
+            }
+            if (at < 0) {
+                at = merged->count++;
+                merged->names[at] = snask_gc_strdup(key);
+            }
+            merged->values[at] = sources[s]->values[i];
+        }
+    }
+    *out = MAKE_OBJ(merged);
+}
+
+// Clamps a slice bound like Python's slice.indices(): negative values count
+// from the end, nil means "omitted".
+static long slice_bound(SnaskValue* v, long len, long step, int is_start) {
+    long lower = step > 0 ? 0 : -1;
+    long upper = step > 0 ? len : len - 1;
+    if (!v || (int)v->tag != SNASK_NUM) {
+        if (is_start) return step > 0 ? lower : upper;
+        return step > 0 ? upper : lower;
+    }
+    long i = (long)v->num;
+    if (i < 0) {
+        i += len;
+        if (i < lower) i = lower;
+    } else if (i > upper) {
+        i = upper;
+    }
+    return i;
+}
+
+// string_slice(str, start, end, step) -> string (byte indices, like substring)
+void string_slice(SnaskValue* out, SnaskValue* s, SnaskValue* start_v, SnaskValue* end_v, SnaskValue* step_v) {
+    if (!s || (int)s->tag != SNASK_STR || !s->ptr) { *out = MAKE_NIL(); return; }
+    long step = 1;
+    if (step_v && (int)step_v->tag == SNASK_NUM) step = (long)step_v->num;
+    if (step == 0) { *out = MAKE_NIL(); return; }
+
+    const char* src = (const char*)s->ptr;
+    long len = (long)strlen(src);
+    long start = slice_bound(start_v, len, step, 1);
+    long end = slice_bound(end_v, len, step, 0);
+
+    long count = 0;
+    if (step > 0 && start < end) count = (end - start + step - 1) / step;
+    if (step < 0 && start > end) count = (start - end - step - 1) / (-step);
+
+    char* dst = (char*)snask_gc_malloc((size_t)count + 1);
+    for (long k = 0, i = start; k < count; k++, i += step) dst[k] = src[i];
+    dst[count] = '\0';
+    *out = MAKE_STR(dst);
+}
+
+// target[start:end:step] -> a new str or list with the selected items. Bounds
+// clamp and count from the end like string_slice; omitted ones arrive as nil.
+void snask_slice(SnaskValue* out, SnaskValue* target, SnaskValue* start_v, SnaskValue* end_v, SnaskValue* step_v) {
+    long step = 1;
+    if (step_v && (int)step_v->tag == SNASK_NUM) step = (long)step_v->num;
+    if (step == 0) {
+        snask_error_set("slice: step cannot be zero");
+        *out = MAKE_NIL();
+        return;
+    }
+    if (target && (int)target->tag == SNASK_STR && target->ptr) {
+        string_slice(out, target, start_v, end_v, step_v);
+        return;
+    }
+    if (!target || (int)target->tag != SNASK_OBJ || !target->ptr) {
+        snask_error_set("slice: expected a list or str, got %s", type_name_of(target));
+        *out = MAKE_NIL();
+        return;
+    }
+
+    SnaskObject* src = (SnaskObject*)target->ptr;
+    long len = (long)src->count;
+    long start = slice_bound(start_v, len, step, 1);
+    long end = slice_bound(end_v, len, step, 0);
+
+    long count = 0;
+    if (step > 0 && start < end) count = (end - start + step - 1) / step;
+    if (step < 0 && start > end) count = (start - end - step - 1) / (-step);
+
+    SnaskObject* dst = new_list((int)count);
+    for (long k = 0, i = start; k < count; k++, i += step) dst->values[k] = src->values[i];
+    *out = MAKE_OBJ(dst);
+}
+
 void os_platform(SnaskValue* out) {
     struct utsname u;
     if (uname(&u) != 0) { *out = MAKE_NIL(); return; }
@@ -33,6 +599,24 @@ void os_arch(SnaskValue* out) {
     *out = MAKE_STR(snask_gc_strdup(u.machine));
 }
 
+static int g_argc = 0;
+static char** g_argv = NULL;
+
+// Called by generated main() with the process arguments, kept for args().
+void s_set_args(int argc, char** argv) {
+    g_argc = argc;
+    g_argv = argv;
+}
+
+// Program arguments without argv[0], as a list of strings.
+void s_args(SnaskValue* out) {
+    SnaskObject* list = new_list(g_argc > 1 ? g_argc - 1 : 0);
+    for (int i = 0; i < list->count; i++) {
+        list->values[i] = MAKE_STR(snask_gc_strdup(g_argv[i + 1]));
+    }
+    *out = MAKE_OBJ(list);
+}
+
 void os_cwd(SnaskValue* out) {
     char buf[4096];
     if (!getcwd(buf, 4096)) { *out = MAKE_NIL(); return; }
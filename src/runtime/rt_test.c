@@ -0,0 +1,94 @@
+#include <dlfcn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+#include "rt_test.h"
+#include "rt_error.h"
+
+static int snask_tests_run = 0;
+static int snask_tests_failed = 0;
+
+static const char** snask_cov_labels = NULL;
+static unsigned long long* snask_cov_counts = NULL;
+static int snask_cov_count = 0;
+
+void snask_cov_init(const char** labels, int count) {
+    snask_cov_labels = labels;
+    snask_cov_count = count;
+    snask_cov_counts = (unsigned long long*)calloc((size_t)count, sizeof(unsigned long long));
+    atexit(snask_cov_dump);
+}
+
+void snask_cov_hit(int id) {
+    if (snask_cov_counts && id >= 0 && id < snask_cov_count) snask_cov_counts[id]++;
+}
+
+void snask_cov_dump(void) {
+    if (!snask_cov_counts) return;
+    const char* path = getenv("SNASK_COVERAGE_FILE");
+    FILE* f = fopen(path && *path ? path : "snask.cov", "a");
+    if (f) {
+        for (int i = 0; i < snask_cov_count; i++) {
+            fprintf(f, "%llu\t%s\n", snask_cov_counts[i], snask_cov_labels[i]);
+        }
+        fclose(f);
+    }
+    // Written once per process; a test child starts again from zero.
+    memset(snask_cov_counts, 0, (size_t)snask_cov_count * sizeof(unsigned long long));
+}
+
+void snask_test_run(SnaskValue* out, SnaskValue* name) {
+    if (!name || (int)name->tag != SNASK_STR || !name->ptr) { *out = MAKE_BOOL(0); return; }
+    const char* test = (const char*)name->ptr;
+    char sym[512];
+    snprintf(sym, sizeof(sym), "f_%s", test);
+    snask_tests_run++;
+
+    // Test functions are exported with -rdynamic, like GUI/blaze handlers.
+    void* fp = dlsym(RTLD_DEFAULT, sym);
+    if (!fp) {
+        printf("test %s ... FAILED (function not found)\n", test);
+        snask_tests_failed++;
+        *out = MAKE_BOOL(0);
+        return;
+    }
+
+    fflush(stdout);
+    fflush(stderr);
+    pid_t pid = fork();
+    if (pid == 0) {
+        // The parent dumps its own hits; count only what this test runs.
+        if (snask_cov_counts) {
+            memset(snask_cov_counts, 0, (size_t)snask_cov_count * sizeof(unsigned long long));
+        }
+        typedef void (*SnaskFn0)(SnaskValue*);
+        SnaskValue ret = MAKE_NIL();
+        ((SnaskFn0)fp)(&ret);
+        fflush(stdout);
+        fflush(stderr);
+        snask_cov_dump();
+        _exit(snask_error_pending() ? 1 : 0);
+    }
+
+    int status = 0;
+    int ok = pid > 0 && waitpid(pid, &status, 0) == pid && WIFEXITED(status) &&
+             WEXITSTATUS(status) == 0;
+    if (ok) {
+        printf("test %s ... ok\n", test);
+    } else {
+        printf("test %s ... FAILED\n", test);
+        snask_tests_failed++;
+    }
+    *out = MAKE_BOOL(ok);
+}
+
+void snask_test_summary(SnaskValue* out) {
+    int passed = snask_tests_run - snask_tests_failed;
+    printf("\ntest result: %s. %d passed; %d failed\n",
+           snask_tests_failed ? "FAILED" : "ok", passed, snask_tests_failed);
+    fflush(stdout);
+    *out = MAKE_NIL();
+    exit(snask_tests_failed ? 1 : 0);
+}
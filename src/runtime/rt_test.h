@@ -0,0 +1,22 @@
+#ifndef RT_TEST_H
+#define RT_TEST_H
+
+#include "rt_base.h"
+
+// `snask test` driver. Each `test_*` function runs in a child process, so a
+// failed `assert` (which exits) only ends that test; the parent prints
+// "test <name> ... ok|FAILED" and keeps the tally.
+void snask_test_run(SnaskValue* out, SnaskValue* name);
+
+// Prints the pass/fail summary and exits with 1 if any test failed.
+void snask_test_summary(SnaskValue* out);
+
+// `snask test --coverage`. Instrumented binaries register one label per
+// function ("file\tline\tname") and bump its counter on every entry; the
+// counts are appended to $SNASK_COVERAGE_FILE (default "snask.cov") at exit
+// as "count\tlabel" lines.
+void snask_cov_init(const char** labels, int count);
+void snask_cov_hit(int id);
+void snask_cov_dump(void);
+
+#endif // RT_TEST_H
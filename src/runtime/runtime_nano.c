@@ -44,6 +44,21 @@ void s_eq(SnaskValue* out, SnaskValue* a, SnaskValue* b) {
     }
 }
 
+// Runtime errors: nano has no `try`, so a zero integer divisor always stops
+// the program at the location generated code recorded for it.
+static const char* snask_current_location = NULL;
+
+void snask_set_location(const char* location) { snask_current_location = location; }
+
+void snask_division_by_zero(int recoverable) {
+    (void)recoverable;
+    const char* loc = snask_current_location ? snask_current_location : "<unknown>";
+    fputs("runtime error: division by zero\n  --> ", stderr);
+    fputs(loc, stderr);
+    fputs("\n", stderr);
+    exit(1);
+}
+
 // Placeholder for memory (malloc)
 void* snask_gc_malloc(size_t n) { return malloc(n); }
 char* snask_gc_strdup(const char* s) { return strdup(s); }
@@ -2,6 +2,7 @@ use crate::ast::{
     BinaryOp, ClassDecl, ConditionalStmt, Expr, ExprKind, FuncDecl, LiteralValue, LoopStmt,
     Program, Stmt, StmtKind, UnaryOp, VarDecl,
 };
+use crate::builtins::{BuiltinSig, BUILTINS, TEST_DRIVER_BUILTINS};
 use crate::span::Span;
 use crate::types::Type;
 use std::collections::HashMap;
@@ -81,7 +82,7 @@ impl SemanticSymbolTable {
     }
 }
 
-fn display_type(ty: &Type) -> String {
+pub(crate) fn display_type(ty: &Type) -> String {
     match ty {
         Type::Int => "int".to_string(),
         Type::Float => "float".to_string(),
@@ -117,6 +118,170 @@ fn display_type(ty: &Type) -> String {
                 .join(", ");
             format!("fun({}) -> {}", params, display_type(ret))
         }
+        Type::Tuple(items) => {
+            let items = items.iter().map(display_type).collect::<Vec<_>>().join(", ");
+            format!("({})", items)
+        }
+    }
+}
+
+/// The type `ty` names once every alias in it is replaced by its target.
+/// `chain` holds the aliases being resolved; meeting one of them again
+/// returns the cycle.
+fn resolve_alias(
+    ty: &Type,
+    declared: &HashMap<String, Type>,
+    chain: &mut Vec<String>,
+) -> Result<Type, Vec<String>> {
+    Ok(match ty {
+        Type::User(name) => match declared.get(name) {
+            Some(target) => {
+                if let Some(start) = chain.iter().position(|seen| seen == name) {
+                    let mut cycle = chain[start..].to_vec();
+                    cycle.push(name.clone());
+                    return Err(cycle);
+                }
+                chain.push(name.clone());
+                let resolved = resolve_alias(target, declared, chain)?;
+                chain.pop();
+                resolved
+            }
+            None => ty.clone(),
+        },
+        Type::ListOf(inner) => Type::ListOf(Box::new(resolve_alias(inner, declared, chain)?)),
+        Type::DictOf(key, value) => Type::DictOf(
+            Box::new(resolve_alias(key, declared, chain)?),
+            Box::new(resolve_alias(value, declared, chain)?),
+        ),
+        Type::Function(params, ret) => Type::Function(
+            params
+                .iter()
+                .map(|p| resolve_alias(p, declared, chain))
+                .collect::<Result<_, _>>()?,
+            Box::new(resolve_alias(ret, declared, chain)?),
+        ),
+        Type::Tuple(items) => Type::Tuple(
+            items
+                .iter()
+                .map(|item| resolve_alias(item, declared, chain))
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => ty.clone(),
+    })
+}
+
+fn expand_aliases_in_type(ty: &mut Type, aliases: &HashMap<String, Type>) {
+    match ty {
+        Type::User(name) => {
+            if let Some(target) = aliases.get(name.as_str()).cloned() {
+                *ty = target;
+            }
+        }
+        Type::ListOf(inner) => expand_aliases_in_type(inner, aliases),
+        Type::DictOf(key, value) => {
+            expand_aliases_in_type(key, aliases);
+            expand_aliases_in_type(value, aliases);
+        }
+        Type::Function(params, ret) => {
+            for p in params {
+                expand_aliases_in_type(p, aliases);
+            }
+            expand_aliases_in_type(ret, aliases);
+        }
+        Type::Tuple(items) => {
+            for item in items {
+                expand_aliases_in_type(item, aliases);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn expand_aliases_in_opt_type(ty: &mut Option<Type>, aliases: &HashMap<String, Type>) {
+    if let Some(ty) = ty {
+        expand_aliases_in_type(ty, aliases);
+    }
+}
+
+fn expand_aliases_in_func(
+    func: &mut FuncDecl,
+    aliases: &HashMap<String, Type>,
+    nested: &mut Vec<(String, Span)>,
+) {
+    for (_, ty) in &mut func.params {
+        expand_aliases_in_type(ty, aliases);
+    }
+    expand_aliases_in_opt_type(&mut func.return_type, aliases);
+    expand_aliases_in_body(&mut func.body, aliases, nested);
+}
+
+/// Expands the statements of a block; aliases declared there are collected
+/// in `nested`.
+fn expand_aliases_in_body(
+    body: &mut [Stmt],
+    aliases: &HashMap<String, Type>,
+    nested: &mut Vec<(String, Span)>,
+) {
+    for stmt in body {
+        if let StmtKind::TypeAlias { name, .. } = &stmt.kind {
+            nested.push((name.clone(), stmt.span));
+        }
+        expand_aliases_in_stmt(stmt, aliases, nested);
+    }
+}
+
+fn expand_aliases_in_stmt(
+    stmt: &mut Stmt,
+    aliases: &HashMap<String, Type>,
+    nested: &mut Vec<(String, Span)>,
+) {
+    match &mut stmt.kind {
+        StmtKind::VarDeclaration(v) => expand_aliases_in_opt_type(&mut v.var_type, aliases),
+        StmtKind::MutDeclaration(v) => expand_aliases_in_opt_type(&mut v.var_type, aliases),
+        StmtKind::ConstDeclaration(v) => expand_aliases_in_opt_type(&mut v.var_type, aliases),
+        StmtKind::ListDeclaration(v) => expand_aliases_in_opt_type(&mut v.var_type, aliases),
+        StmtKind::DictDeclaration(v) => expand_aliases_in_opt_type(&mut v.var_type, aliases),
+        StmtKind::Input { var_type, .. }
+        | StmtKind::TypeAlias {
+            target: var_type, ..
+        } => expand_aliases_in_type(var_type, aliases),
+        StmtKind::FuncDeclaration(f) => expand_aliases_in_func(f, aliases, nested),
+        StmtKind::ClassDeclaration(c) => {
+            for p in &mut c.properties {
+                expand_aliases_in_opt_type(&mut p.var_type, aliases);
+            }
+            for m in &mut c.methods {
+                expand_aliases_in_func(m, aliases, nested);
+            }
+        }
+        StmtKind::Conditional(c) => {
+            expand_aliases_in_body(&mut c.if_block.body, aliases, nested);
+            for block in &mut c.elif_blocks {
+                expand_aliases_in_body(&mut block.body, aliases, nested);
+            }
+            if let Some(body) = &mut c.else_block {
+                expand_aliases_in_body(body, aliases, nested);
+            }
+        }
+        StmtKind::Loop(LoopStmt::While {
+            body, else_block, ..
+        })
+        | StmtKind::Loop(LoopStmt::For {
+            body, else_block, ..
+        }) => {
+            expand_aliases_in_body(body, aliases, nested);
+            if let Some(body) = else_block {
+                expand_aliases_in_body(body, aliases, nested);
+            }
+        }
+        StmtKind::UnsafeBlock(body)
+        | StmtKind::Scope { body, .. }
+        | StmtKind::Zone { body, .. } => expand_aliases_in_body(body, aliases, nested),
+        StmtKind::TryCatch { body, handler, .. } => {
+            expand_aliases_in_body(body, aliases, nested);
+            expand_aliases_in_body(handler, aliases, nested);
+        }
+        _ => {}
     }
 }
 
@@ -145,6 +310,7 @@ impl SemanticError {
 
     pub fn message(&self) -> String { self.kind.message() }
     pub fn code(&self) -> &'static str { self.kind.code() }
+    pub fn is_warning(&self) -> bool { self.kind.is_warning() }
 }
 
 pub struct SemanticAnalyzer {
@@ -152,17 +318,21 @@ pub struct SemanticAnalyzer {
     current_function_return_type: Option<Type>,
     current_class: Option<String>,
     classes: HashMap<String, ClassDecl>,
+    enums: HashMap<String, Vec<String>>,
     pub errors: Vec<SemanticError>,
+    /// Diagnostics that do not stop the build unless `--deny-warnings`.
+    pub warnings: Vec<SemanticError>,
     tiny_mode: bool,
     unsafe_depth: usize,
 }
 
 fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
     let mut prev: Vec<usize> = (0..=b.len()).collect();
     let mut cur = vec![0usize; b.len() + 1];
     for (i, ca) in a.chars().enumerate() {
         cur[0] = i + 1;
-        for (j, cb) in b.chars().enumerate() {
+        for (j, &cb) in b.iter().enumerate() {
             let cost = if ca == cb { 0 } else { 1 };
             cur[j + 1] = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + cost);
         }
@@ -171,15 +341,24 @@ fn levenshtein(a: &str, b: &str) -> usize {
     prev[b.len()]
 }
 
-fn suggest_name(target: &str, candidates: &[String]) -> Option<(String, u8)> {
-    let mut scored: Vec<(String, usize)> = candidates
+/// Closest candidate to `target`, if it is clearly closer than the rest.
+/// Short names only accept one edit, so `ab` never turns into `xy`.
+pub(crate) fn suggest_name(target: &str, candidates: &[String]) -> Option<(String, u8)> {
+    let max_d = (target.chars().count() / 3).clamp(1, 2);
+    let mut unique: Vec<&String> = candidates
         .iter()
+        .filter(|c| c.as_str() != target && !c.starts_with("__"))
+        .collect();
+    unique.sort();
+    unique.dedup();
+    let mut scored: Vec<(String, usize)> = unique
+        .into_iter()
         .map(|c| (c.clone(), levenshtein(target, c)))
         .collect();
     scored.sort_by_key(|(_, d)| *d);
     let (best, best_d) = scored.first()?.clone();
     let second_d = scored.get(1).map(|(_, d)| *d).unwrap_or(usize::MAX);
-    if best_d <= 2 && best_d + 1 < second_d {
+    if best_d <= max_d && best_d < second_d {
         let confidence = match best_d {
             0 => 100,
             1 => 95,
@@ -210,6 +389,12 @@ fn is_library_native(name: &str) -> bool {
         || name.starts_with("sjson_")
         || name.starts_with("snif_")
         || name.starts_with("string_")
+        || name.starts_with("regex_")
+        || name.starts_with("dt_")
+        || name.starts_with("csv_")
+        || name.starts_with("base64_")
+        || name.starts_with("hash_")
+        || name.starts_with("sb_")
 }
 
 fn library_native_help(name: &str) -> String {
@@ -241,6 +426,18 @@ fn library_native_help(name: &str) -> String {
         "snif"
     } else if name.starts_with("string_") {
         "string"
+    } else if name.starts_with("regex_") {
+        "regex"
+    } else if name.starts_with("dt_") {
+        "datetime"
+    } else if name.starts_with("csv_") {
+        "csv"
+    } else if name.starts_with("base64_") {
+        "base64"
+    } else if name.starts_with("hash_") {
+        "hash"
+    } else if name.starts_with("sb_") {
+        "sb"
     } else {
         "a library"
     };
@@ -251,38 +448,6 @@ fn library_native_help(name: &str) -> String {
     )
 }
 
-fn native_module_aliases(name: &str) -> Vec<String> {
-    let mappings = [
-        ("gui_", "gui"),
-        ("os_", "os"),
-        ("sfs_", "sfs"),
-        ("path_", "sfs"),
-        ("json_", "json"),
-        ("sjson_", "sjson"),
-        ("snif_", "snif"),
-        ("string_", "string"),
-        ("sqlite_", "sqlite"),
-        ("zlib_", "zlib"),
-        ("skia_", "snask_skia"),
-        ("blaze_", "blaze"),
-        ("auth_", "blaze_auth"),
-        ("thread_", "os"),
-        ("snaskgui_", "snaskgui"),
-    ];
-
-    if let Some(rest) = name.strip_prefix("s_http_") {
-        return vec![format!("requests::{}", rest)];
-    }
-
-    for (prefix, module) in mappings {
-        if let Some(rest) = name.strip_prefix(prefix) {
-            return vec![format!("{}::{}", module, rest)];
-        }
-    }
-
-    Vec::new()
-}
-
 impl SemanticAnalyzer {
     pub fn new() -> Self {
         let mut analyzer = SemanticAnalyzer {
@@ -290,7 +455,9 @@ impl SemanticAnalyzer {
             current_function_return_type: None,
             current_class: None,
             classes: HashMap::new(),
+            enums: HashMap::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             tiny_mode: false,
             unsafe_depth: 0,
         };
@@ -302,12 +469,42 @@ impl SemanticAnalyzer {
         self.tiny_mode = tiny;
     }
 
+    /// Makes the `snask test` driver calls visible to the generated
+    /// `main::start`.
+    pub fn set_test_mode(&mut self, test: bool) {
+        if test {
+            for builtin in TEST_DRIVER_BUILTINS {
+                self.define_builtin(builtin);
+            }
+        }
+    }
+
     fn mk_variable_not_found(&self, name: String, span: Span) -> SemanticError {
+        if let Some((enum_name, variant)) = name.rsplit_once("::") {
+            if let Some(variants) = self.enums.get(enum_name) {
+                let e = SemanticError::new(
+                    SemanticErrorKind::UnknownEnumVariant {
+                        enum_name: enum_name.to_string(),
+                        variant: variant.to_string(),
+                    },
+                    span,
+                );
+                return match suggest_name(variant, variants) {
+                    Some((best, _)) => {
+                        e.with_help(format!("Did you mean `{enum_name}::{best}`?"))
+                    }
+                    None => e.with_note(format!(
+                        "`{enum_name}` has the variants: {}",
+                        variants.join(", ")
+                    )),
+                };
+            }
+        }
         let candidates = self.symbol_table.visible_names();
         let mut e = SemanticError::new(SemanticErrorKind::VariableNotFound(name.clone()), span);
         if let Some((best, conf)) = suggest_name(&name, &candidates) {
             if conf >= 90 {
-                e = e.with_help(format!("Did you mean '{best}'?"));
+                e = e.with_help(format!("Did you mean `{best}`?"));
             } else {
                 e = e.with_note(format!("Possible match: '{best}' ({conf}%)"));
             }
@@ -320,7 +517,7 @@ impl SemanticAnalyzer {
         let mut e = SemanticError::new(SemanticErrorKind::FunctionNotFound(name.clone()), span);
         if let Some((best, conf)) = suggest_name(&name, &candidates) {
             if conf >= 90 {
-                e = e.with_help(format!("Did you mean '{best}'?"));
+                e = e.with_help(format!("Did you mean `{best}`?"));
             } else {
                 e = e.with_note(format!("Possible match: '{best}' ({conf}%)"));
             }
@@ -333,7 +530,7 @@ impl SemanticAnalyzer {
         let mut e = SemanticError::new(SemanticErrorKind::UnknownType(name.clone()), span);
         if let Some((best, conf)) = suggest_name(&name, &candidates) {
             if conf >= 90 {
-                e = e.with_help(format!("Did you mean type '{best}'?"));
+                e = e.with_help(format!("Did you mean type `{best}`?"));
             } else {
                 e = e.with_note(format!("Possible match: '{best}' ({conf}%)"));
             }
@@ -341,593 +538,33 @@ impl SemanticAnalyzer {
         e
     }
 
+    fn mk_property_not_found(&self, class_name: &str, property: &str, span: Span) -> SemanticError {
+        let candidates = self.class_member_names(class_name);
+        let e = SemanticError::new(
+            SemanticErrorKind::PropertyNotFound(property.to_string()),
+            span,
+        )
+        .with_note(format!(
+            "`{property}` is not a property or method of `{class_name}`"
+        ));
+        match suggest_name(property, &candidates) {
+            Some((best, _)) => e.with_help(format!("Did you mean `{best}`?")),
+            None => e,
+        }
+    }
+
     fn register_stdlib(&mut self) {
         self.define_module_as_any("math");
         self.define_module_as_any("string");
         self.define_module_as_any("collections");
 
-        // Runtime intrinsics — inline LLVM IR, no C dependency
-        self.define_builtin("__snask_type", vec![Type::Any], Type::Int, false);
-        self.define_builtin("__snask_get_str", vec![Type::Any], Type::String, false);
-        self.define_builtin("__snask_get_num", vec![Type::Any], Type::Float, false);
-        self.define_builtin("__snask_write", vec![Type::Int, Type::String, Type::Int], Type::Int, false);
-
-        // Print functions — implementação Snask pura via intrinsics (agora em src/stdlib/snask.snask)
-
-        self.define_builtin("abs", vec![Type::Float], Type::Float, false);
-        self.define_builtin("floor", vec![Type::Float], Type::Float, false);
-        self.define_builtin("ceil", vec![Type::Float], Type::Float, false);
-        self.define_builtin("round", vec![Type::Float], Type::Float, false);
-        self.define_builtin("pow", vec![Type::Float, Type::Float], Type::Float, false);
-        self.define_builtin("sqrt", vec![Type::Float], Type::Float, false);
-        self.define_builtin("min", vec![], Type::Any, true);
-        self.define_builtin("max", vec![], Type::Any, true);
-        self.define_builtin("sin", vec![Type::Float], Type::Float, false);
-        self.define_builtin("cos", vec![Type::Float], Type::Float, false);
-
         self.define_constant("PI", Type::Float);
         self.define_constant("E", Type::Float);
         self.define_constant("TAU", Type::Float);
 
-        self.define_builtin("len", vec![Type::Any], Type::Float, false);
-        self.define_builtin("upper", vec![Type::String], Type::String, false);
-        self.define_builtin("lower", vec![Type::String], Type::String, false);
-        self.define_builtin("trim", vec![Type::String], Type::String, false);
-        self.define_builtin("split", vec![Type::String, Type::String], Type::List, false);
-        self.define_builtin("join", vec![Type::List, Type::String], Type::String, false);
-        self.define_builtin(
-            "replace",
-            vec![Type::String, Type::String, Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin(
-            "contains",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin(
-            "starts_with",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin(
-            "ends_with",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin("chars", vec![Type::String], Type::List, false);
-        self.define_builtin(
-            "substring",
-            vec![Type::String, Type::Float, Type::Float],
-            Type::String,
-            false,
-        );
-        self.define_builtin("format", vec![Type::String], Type::String, true);
-
-        self.define_builtin("range", vec![Type::Float], Type::List, false);
-        self.define_builtin("sort", vec![Type::List], Type::List, false);
-        self.define_builtin("reverse", vec![Type::List], Type::List, false);
-        self.define_builtin("unique", vec![Type::List], Type::List, false);
-        self.define_builtin("flatten", vec![Type::List], Type::List, false);
-
-        self.define_builtin("is_nil", vec![Type::Any], Type::Bool, false);
-        self.define_builtin("is_str", vec![Type::Any], Type::Bool, false);
-        self.define_builtin("is_obj", vec![Type::Any], Type::Bool, false);
-
-        self.define_builtin("read_file", vec![Type::String], Type::String, false);
-        self.define_builtin(
-            "write_file",
-            vec![Type::String, Type::String],
-            Type::Void,
-            false,
-        );
-        self.define_builtin(
-            "append_file",
-            vec![Type::String, Type::String],
-            Type::Void,
-            false,
-        );
-        self.define_builtin("exists", vec![Type::String], Type::Bool, false);
-        self.define_builtin("delete", vec![Type::String], Type::Void, false);
-        self.define_builtin("read_dir", vec![Type::String], Type::List, false);
-        self.define_builtin("is_file", vec![Type::String], Type::Bool, false);
-        self.define_builtin("is_dir", vec![Type::String], Type::Bool, false);
-        self.define_builtin("create_dir", vec![Type::String], Type::Void, false);
-
-        self.define_builtin("http_get", vec![Type::String], Type::Dict, false);
-        self.define_builtin(
-            "http_post",
-            vec![Type::String, Type::String],
-            Type::Void,
-            false,
-        );
-
-        self.define_builtin("time", vec![], Type::Float, false);
-        self.define_builtin("sleep", vec![Type::Float], Type::Void, false);
-        self.define_builtin("exit", vec![Type::Float], Type::Void, false);
-        self.define_builtin(
-            "__s_call_by_name",
-            vec![Type::String, Type::Any, Type::Any, Type::Any],
-            Type::Any,
-            false,
-        );
-        self.define_builtin("args", vec![], Type::List, false);
-        self.define_builtin("env", vec![Type::String], Type::String, false);
-        self.define_builtin(
-            "set_env",
-            vec![Type::String, Type::String],
-            Type::Void,
-            false,
-        );
-        self.define_builtin("cwd", vec![], Type::String, false);
-        self.define_builtin("platform", vec![], Type::String, false);
-        self.define_builtin("arch", vec![], Type::String, false);
-
-        self.define_builtin("str_to_num", vec![Type::String], Type::Float, false);
-        self.define_builtin("num_to_str", vec![Type::Float], Type::String, false);
-        self.define_builtin("calc_eval", vec![Type::String], Type::Float, false);
-
-        self.define_builtin("wrapping_add", vec![Type::Any, Type::Any], Type::Any, false);
-        self.define_builtin("wrapping_sub", vec![Type::Any, Type::Any], Type::Any, false);
-        self.define_builtin("wrapping_mul", vec![Type::Any, Type::Any], Type::Any, false);
-        self.define_builtin(
-            "saturating_add",
-            vec![Type::Any, Type::Any],
-            Type::Any,
-            false,
-        );
-        self.register_systems_low_level_builtins();
-
-        // Core natives aliased
-        self.define_builtin_with_alias("os_cwd", vec![], Type::String, false);
-        self.define_builtin_with_alias("string_len", vec![Type::Any], Type::Float, false);
-
-        // JSON (via `import "json"`; module calls compile to `__json_*`)
-        self.define_builtin_with_alias("json_parse", vec![Type::String], Type::Any, false);
-        self.define_builtin_with_alias("json_stringify", vec![Type::Any], Type::String, false);
-        self.define_builtin_with_alias(
-            "json_stringify_pretty",
-            vec![Type::Any],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias("json_get", vec![Type::Any, Type::String], Type::Any, false);
-        self.define_builtin_with_alias(
-            "json_has",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("json_len", vec![Type::Any], Type::Float, false);
-        self.define_builtin_with_alias(
-            "json_index",
-            vec![Type::Any, Type::Float],
-            Type::Any,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "json_set",
-            vec![Type::Any, Type::String, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("json_keys", vec![Type::Any], Type::Any, false);
-        self.define_builtin_with_alias("json_parse_ex", vec![Type::String], Type::Any, false);
-
-        // SJSON (native helpers)
-        self.define_builtin_with_alias("sjson_type", vec![Type::Any], Type::String, false);
-        self.define_builtin_with_alias("sjson_new_object", vec![], Type::Any, false);
-        self.define_builtin_with_alias("sjson_new_array", vec![], Type::Any, false);
-        self.define_builtin_with_alias("sjson_arr_len", vec![Type::Any], Type::Float, false);
-        self.define_builtin_with_alias(
-            "sjson_arr_get",
-            vec![Type::Any, Type::Float],
-            Type::Any,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sjson_arr_set",
-            vec![Type::Any, Type::Float, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sjson_arr_push",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sjson_path_get",
-            vec![Type::Any, Type::String],
-            Type::Any,
-            false,
-        );
-
-        self.define_builtin("is_0", vec![Type::Any], Type::Bool, false);
-
-        // SNIF (library surface over SNIF runtime)
-        self.define_builtin_with_alias("snif_new_object", vec![], Type::Any, false);
-        self.define_builtin_with_alias("snif_new_array", vec![], Type::Any, false);
-        self.define_builtin_with_alias("snif_parse_ex", vec![Type::String], Type::Any, false);
-        self.define_builtin_with_alias("snif_type", vec![Type::Any], Type::String, false);
-        self.define_builtin_with_alias("snif_arr_len", vec![Type::Any], Type::Float, false);
-        self.define_builtin_with_alias(
-            "snif_arr_get",
-            vec![Type::Any, Type::Float],
-            Type::Any,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "snif_arr_set",
-            vec![Type::Any, Type::Float, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "snif_arr_push",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "snif_path_get",
-            vec![Type::Any, Type::String],
-            Type::Any,
-            false,
-        );
-
-        // Auth (used by blaze_auth)
-        self.define_builtin_with_alias("auth_random_hex", vec![Type::Float], Type::String, false);
-        self.define_builtin_with_alias("auth_now", vec![], Type::Float, false);
-        self.define_builtin_with_alias(
-            "auth_const_time_eq",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "auth_hash_password",
-            vec![Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "auth_verify_password",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("auth_session_id", vec![], Type::String, false);
-        self.define_builtin_with_alias("auth_csrf_token", vec![], Type::String, false);
-        self.define_builtin_with_alias(
-            "auth_cookie_kv",
-            vec![Type::String, Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "auth_cookie_session",
-            vec![Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "auth_cookie_delete",
-            vec![Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "auth_bearer_header",
-            vec![Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias("auth_ok", vec![], Type::Bool, false);
-        self.define_builtin_with_alias("auth_fail", vec![], Type::Bool, false);
-        self.define_builtin_with_alias("auth_version", vec![], Type::String, false);
-
-        // SFS (filesystem)
-        self.define_builtin_with_alias("sfs_read", vec![Type::String], Type::String, false);
-        self.define_builtin_with_alias("binfile_size", vec![Type::String], Type::Float, false);
-        self.define_builtin_with_alias(
-            "binfile_read_into",
-            vec![Type::String, Type::Ptr, Type::Float],
-            Type::Float,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_write",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_append",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_write_mb",
-            vec![Type::String, Type::Float],
-            Type::Float,
-            false,
-        );
-        self.define_builtin_with_alias("sfs_count_bytes", vec![Type::String], Type::Float, false);
-        self.define_builtin_with_alias("sfs_delete", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias("sfs_exists", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "sfs_copy",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_move",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("sfs_mkdir", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias("sfs_is_file", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias("sfs_is_dir", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias("sfs_listdir", vec![Type::String], Type::Any, false);
-        self.define_builtin_with_alias(
-            "sfs_bench_create_small_files",
-            vec![Type::String, Type::Float, Type::Float],
-            Type::Float,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_bench_count_entries",
-            vec![Type::String],
-            Type::Float,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "sfs_bench_delete_small_files",
-            vec![Type::String, Type::Float],
-            Type::Float,
-            false,
-        );
-        self.define_builtin_with_alias("sfs_size", vec![Type::String], Type::Float, false);
-        self.define_builtin_with_alias("sfs_mtime", vec![Type::String], Type::Float, false);
-        self.define_builtin_with_alias("sfs_rmdir", vec![Type::String], Type::Bool, false);
-
-        // Blaze (Web Server)
-        self.define_builtin_with_alias(
-            "blaze_run",
-            vec![Type::Float, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "blaze_qs_get",
-            vec![Type::String, Type::String],
-            Type::String,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "blaze_cookie_get",
-            vec![Type::String, Type::String],
-            Type::String,
-            false,
-        );
-
-        // OS
-        self.define_builtin_with_alias("os_platform", vec![], Type::String, false);
-        self.define_builtin_with_alias("os_arch", vec![], Type::String, false);
-        self.define_builtin_with_alias("os_getenv", vec![Type::String], Type::String, false);
-        self.define_builtin_with_alias(
-            "os_setenv",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("os_random_hex", vec![Type::Float], Type::String, false);
-
-        // Misc "s_*" core helpers (runtime)
-        self.define_builtin_with_alias("s_time", vec![], Type::Float, false);
-        self.define_builtin_with_alias("s_sleep", vec![Type::Float], Type::Void, false);
-
-        // Path helpers
-        self.define_builtin_with_alias("path_basename", vec![Type::String], Type::String, false);
-        self.define_builtin_with_alias("path_dirname", vec![Type::String], Type::String, false);
-        self.define_builtin_with_alias("path_extname", vec![Type::String], Type::String, false);
-        self.define_builtin_with_alias(
-            "path_join",
-            vec![Type::String, Type::String],
-            Type::String,
-            false,
-        );
-
-        // GUI (GTK runtime surface via `import "snask_gtk"`)
-        self.define_builtin_with_alias("gui_init", vec![], Type::Bool, false);
-        self.define_builtin_with_alias("gui_run", vec![], Type::Void, false);
-        self.define_builtin_with_alias("gui_quit", vec![], Type::Void, false);
-        self.define_builtin_with_alias(
-            "gui_window",
-            vec![Type::String, Type::Float, Type::Float],
-            Type::Any,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_set_title",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_set_resizable",
-            vec![Type::Any, Type::Bool],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_autosize", vec![Type::Any], Type::Bool, false);
-        self.define_builtin_with_alias("gui_vbox", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_hbox", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_scrolled", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_eventbox", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_flowbox", vec![], Type::Any, false);
-        self.define_builtin_with_alias(
-            "gui_flow_add",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_frame", vec![], Type::Any, false);
-        self.define_builtin_with_alias(
-            "gui_set_margin",
-            vec![Type::Any, Type::Float],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_icon",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_listbox", vec![], Type::Any, false);
-        self.define_builtin_with_alias(
-            "gui_list_add_text",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_on_select_ctx",
-            vec![Type::Any, Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_set_child",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_add", vec![Type::Any, Type::Any], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "gui_add_expand",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_label", vec![Type::String], Type::Any, false);
-        self.define_builtin_with_alias("gui_entry", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_textview", vec![], Type::Any, false);
-        self.define_builtin_with_alias(
-            "gui_set_placeholder",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_set_editable",
-            vec![Type::Any, Type::Bool],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_button", vec![Type::String], Type::Any, false);
-        self.define_builtin_with_alias(
-            "gui_set_enabled",
-            vec![Type::Any, Type::Bool],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_set_visible",
-            vec![Type::Any, Type::Bool],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_show_all", vec![Type::Any], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "gui_set_text",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_get_text", vec![Type::Any], Type::String, false);
-        self.define_builtin_with_alias(
-            "gui_on_click",
-            vec![Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_on_click_ctx",
-            vec![Type::Any, Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_on_tap_ctx",
-            vec![Type::Any, Type::Any, Type::Any],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("gui_separator_h", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_separator_v", vec![], Type::Any, false);
-        self.define_builtin_with_alias("gui_css", vec![Type::String], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "gui_add_class",
-            vec![Type::Any, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_msg_info",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "gui_msg_error",
-            vec![Type::String, Type::String],
-            Type::Bool,
-            false,
-        );
-
-        // snaskgui: framebuffer-first native surface for emulators/games.
-        self.define_builtin_with_alias("snaskgui_init", vec![], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "snaskgui_window",
-            vec![Type::String, Type::Float, Type::Float, Type::Float],
-            Type::Any,
-            false,
-        );
-        self.define_builtin_with_alias(
-            "snaskgui_present_rgba",
-            vec![Type::Any, Type::Ptr, Type::Float, Type::Float],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("snaskgui_poll", vec![Type::Any], Type::Bool, false);
-        self.define_builtin_with_alias(
-            "snaskgui_key_down",
-            vec![Type::Any, Type::Float],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("snaskgui_mouse_x", vec![Type::Any], Type::Float, false);
-        self.define_builtin_with_alias("snaskgui_mouse_y", vec![Type::Any], Type::Float, false);
-        self.define_builtin_with_alias(
-            "snaskgui_mouse_down",
-            vec![Type::Any, Type::Float],
-            Type::Bool,
-            false,
-        );
-        self.define_builtin_with_alias("snaskgui_should_close", vec![Type::Any], Type::Bool, false);
-        self.define_builtin_with_alias("snaskgui_delay", vec![Type::Float], Type::Void, false);
-        self.define_builtin_with_alias("snaskgui_close", vec![Type::Any], Type::Void, false);
+        for builtin in BUILTINS {
+            self.define_builtin(builtin);
+        }
     }
 
     fn define_module_as_any(&mut self, name: &str) {
@@ -939,137 +576,15 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn define_builtin(
-        &mut self,
-        name: &str,
-        params: Vec<Type>,
-        return_type: Type,
-        is_variadic: bool,
-    ) {
-        let symbol = SemanticSymbol {
-            name: name.to_string(),
-            symbol_type: Type::Function(params, Box::new(return_type)),
-            kind: SemanticSymbolKind::Function,
-            is_variadic,
-        };
-        self.symbol_table.define(symbol);
-    }
-
-    fn define_builtin_with_alias(
-        &mut self,
-        name: &str,
-        params: Vec<Type>,
-        return_type: Type,
-        is_variadic: bool,
-    ) {
-        self.define_builtin(name, params.clone(), return_type.clone(), is_variadic);
-        self.define_builtin(
-            &format!("__{}", name),
-            params.clone(),
-            return_type.clone(),
-            is_variadic,
-        );
-        for alias in native_module_aliases(name) {
-            self.define_builtin(&alias, params.clone(), return_type.clone(), is_variadic);
-        }
-    }
-
-    fn register_systems_low_level_builtins(&mut self) {
-        for (name, ret) in [
-            ("as_u8", Type::U8),
-            ("as_u16", Type::U16),
-            ("as_u32", Type::U32),
-            ("as_u64", Type::U64),
-            ("as_i8", Type::I8),
-            ("as_i16", Type::I16),
-            ("as_i32", Type::I32),
-            ("as_i64", Type::I64),
-            ("as_usize", Type::Usize),
-            ("as_isize", Type::Isize),
-            ("lo_u8", Type::U8),
-            ("hi_u8", Type::U8),
-            ("is_zero_u8", Type::Bool),
-            ("is_negative_u8", Type::Bool),
-            ("null_ptr", Type::Ptr),
-        ] {
-            let params = if name == "null_ptr" {
-                Vec::new()
-            } else {
-                vec![Type::Any]
-            };
-            self.define_builtin(name, params, ret, false);
-        }
-
-        for name in [
-            "make_u16",
-            "bit_set",
-            "bit_clear",
-            "bit_toggle",
-            "bit_write",
-            "flag_set",
-            "flag_clear",
-            "flag_write",
-            "wrapping_inc",
-            "wrapping_dec",
-        ] {
-            self.define_builtin(name, vec![Type::Any, Type::Any], Type::Any, false);
-        }
-
-        for name in ["bit_test", "flag_has"] {
-            self.define_builtin(name, vec![Type::Any, Type::Any], Type::Bool, false);
-        }
-
-        for name in [
-            "carry_add_u8",
-            "borrow_sub_u8",
-            "overflow_add_i8",
-            "overflow_sub_i8",
-        ] {
-            self.define_builtin(
+    fn define_builtin(&mut self, builtin: &BuiltinSig) {
+        for name in builtin.names() {
+            self.symbol_table.define(SemanticSymbol {
                 name,
-                vec![Type::Any, Type::Any, Type::Any],
-                Type::Bool,
-                false,
-            );
+                symbol_type: Type::Function(builtin.params.to_vec(), Box::new(builtin.ret.clone())),
+                kind: SemanticSymbolKind::Function,
+                is_variadic: builtin.variadic,
+            });
         }
-
-        self.define_builtin("mem_alloc", vec![Type::Any], Type::Ptr, false);
-        self.define_builtin("mem_alloc_zero", vec![Type::Any], Type::Ptr, false);
-        self.define_builtin("mem_free", vec![Type::Ptr], Type::Void, false);
-        self.define_builtin("ptr_add", vec![Type::Ptr, Type::Any], Type::Ptr, false);
-        self.define_builtin("mem_read_u8", vec![Type::Ptr, Type::Any], Type::U8, false);
-        self.define_builtin("mem_read_u16", vec![Type::Ptr, Type::Any], Type::U16, false);
-        self.define_builtin("mem_read_u32", vec![Type::Ptr, Type::Any], Type::U32, false);
-        self.define_builtin(
-            "mem_write_u8",
-            vec![Type::Ptr, Type::Any, Type::Any],
-            Type::Void,
-            false,
-        );
-        self.define_builtin(
-            "mem_write_u16",
-            vec![Type::Ptr, Type::Any, Type::Any],
-            Type::Void,
-            false,
-        );
-        self.define_builtin(
-            "mem_write_u32",
-            vec![Type::Ptr, Type::Any, Type::Any],
-            Type::Void,
-            false,
-        );
-        self.define_builtin(
-            "mem_fill_u8",
-            vec![Type::Ptr, Type::Any, Type::Any],
-            Type::Void,
-            false,
-        );
-        self.define_builtin(
-            "mem_copy",
-            vec![Type::Ptr, Type::Ptr, Type::Any],
-            Type::Void,
-            false,
-        );
     }
 
     fn define_constant(&mut self, name: &str, const_type: Type) {
@@ -1083,6 +598,7 @@ impl SemanticAnalyzer {
     }
 
     pub fn analyze(&mut self, program: &Program) {
+        self.register_enums(program);
         self.register_classes(program);
         self.register_functions(program);
         for statement in program {
@@ -1090,6 +606,88 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// Replaces every use of a `type Name = T` alias in the annotations of
+    /// `program` with its underlying type, so analysis and code generation
+    /// only see real types. Runs on the whole resolved program, so an alias
+    /// may be used before its declaration or from another module
+    /// (`ids::UserId`), and may name another alias. Duplicate, nested and
+    /// cyclic aliases are reported as errors.
+    pub fn expand_type_aliases(&mut self, program: &mut Program) {
+        let mut declared: HashMap<String, Type> = HashMap::new();
+        let mut order = Vec::new();
+        for statement in program.iter() {
+            if let StmtKind::TypeAlias { name, target } = &statement.kind {
+                if declared.contains_key(name) {
+                    self.errors.push(
+                        SemanticError::new(
+                            SemanticErrorKind::InvalidTypeAlias(name.clone()),
+                            statement.span,
+                        )
+                        .with_note(format!("`{name}` is already declared as a type alias")),
+                    );
+                    continue;
+                }
+                declared.insert(name.clone(), target.clone());
+                order.push((name.clone(), statement.span));
+            }
+        }
+
+        let mut aliases = HashMap::new();
+        for (name, span) in order {
+            let mut chain = vec![name.clone()];
+            let resolved = match resolve_alias(&declared[&name], &declared, &mut chain) {
+                Ok(ty) => ty,
+                Err(cycle) => {
+                    self.errors.push(
+                        SemanticError::new(SemanticErrorKind::InvalidTypeAlias(name.clone()), span)
+                            .with_note(format!("the aliases form a cycle: {}", cycle.join(" -> "))),
+                    );
+                    Type::Any
+                }
+            };
+            aliases.insert(name, resolved);
+        }
+
+        let mut nested = Vec::new();
+        for statement in program.iter_mut() {
+            expand_aliases_in_stmt(statement, &aliases, &mut nested);
+        }
+        for (name, span) in nested {
+            self.errors.push(
+                SemanticError::new(SemanticErrorKind::InvalidTypeAlias(name), span)
+                    .with_note("type aliases can only be declared at the top level".to_string()),
+            );
+        }
+    }
+
+    fn register_enums(&mut self, program: &Program) {
+        for statement in program {
+            if let StmtKind::EnumDeclaration { name, variants } = &statement.kind {
+                self.register_enum(name, variants, statement.span);
+            }
+        }
+    }
+
+    /// Each variant becomes an `int` constant named `Enum::Variant`.
+    fn register_enum(&mut self, name: &str, variants: &[String], span: Span) {
+        if self.enums.contains_key(name) {
+            self.errors.push(SemanticError::new(
+                SemanticErrorKind::VariableAlreadyDeclared(name.to_string()),
+                span,
+            ));
+            return;
+        }
+        self.enums.insert(name.to_string(), variants.to_vec());
+        for variant in variants {
+            self.symbol_table.define(SemanticSymbol {
+                name: format!("{name}::{variant}"),
+                symbol_type: Type::Int,
+                kind: SemanticSymbolKind::Constant,
+                is_variadic: false,
+            });
+        }
+    }
+
     fn register_classes(&mut self, program: &Program) {
         for statement in program {
             if let StmtKind::ClassDeclaration(class) = &statement.kind {
@@ -1160,6 +758,50 @@ impl SemanticAnalyzer {
         self.class_member_type_inner(class_name, member, &mut Vec::new())
     }
 
+    /// Properties and methods of `class_name` and its parents.
+    fn class_member_names(&self, class_name: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut visited: Vec<&str> = Vec::new();
+        let mut current = Some(class_name);
+        while let Some(name) = current {
+            let Some(class) = self.classes.get(name) else {
+                break;
+            };
+            if visited.contains(&name) {
+                break;
+            }
+            visited.push(name);
+            names.extend(class.properties.iter().map(|p| p.name.clone()));
+            names.extend(class.methods.iter().map(|m| m.name.clone()));
+            current = class.parent.as_deref();
+        }
+        names
+    }
+
+    /// Properties of `class_name` and its parents, the closest declaration
+    /// of each name.
+    fn class_properties(&self, class_name: &str) -> Vec<VarDecl> {
+        let mut properties: Vec<VarDecl> = Vec::new();
+        let mut visited: Vec<&str> = Vec::new();
+        let mut current = Some(class_name);
+        while let Some(name) = current {
+            let Some(class) = self.classes.get(name) else {
+                break;
+            };
+            if visited.contains(&name) {
+                break;
+            }
+            visited.push(name);
+            for prop in &class.properties {
+                if !properties.iter().any(|p| p.name == prop.name) {
+                    properties.push(prop.clone());
+                }
+            }
+            current = class.parent.as_deref();
+        }
+        properties
+    }
+
     fn is_subclass_of(&self, child: &str, parent: &str) -> bool {
         if child == parent {
             return true;
@@ -1232,10 +874,63 @@ impl SemanticAnalyzer {
                 }
                 self.validate_type_exists(ret, span);
             }
+            Type::Tuple(items) => {
+                for item in items {
+                    self.validate_type_exists(item, span);
+                }
+            }
             _ => {}
         }
     }
 
+    /// Warns about the first statement after one that always returns, in
+    /// `body` and in the blocks nested in it. Nested functions get their own
+    /// pass when they are analyzed.
+    fn warn_unreachable(&mut self, body: &[Stmt]) {
+        if let Some(pos) = body.iter().position(Self::stmt_guarantees_return) {
+            if let Some(next) = body.get(pos + 1) {
+                self.warnings.push(
+                    SemanticError::new(SemanticErrorKind::UnreachableCode, next.span)
+                        .with_help("remove it, or move it before the `return`".to_string()),
+                );
+            }
+        }
+        for stmt in body {
+            match &stmt.kind {
+                StmtKind::Conditional(cond) => {
+                    self.warn_unreachable(&cond.if_block.body);
+                    for elif in &cond.elif_blocks {
+                        self.warn_unreachable(&elif.body);
+                    }
+                    if let Some(else_body) = &cond.else_block {
+                        self.warn_unreachable(else_body);
+                    }
+                }
+                StmtKind::Loop(
+                    LoopStmt::While {
+                        body, else_block, ..
+                    }
+                    | LoopStmt::For {
+                        body, else_block, ..
+                    },
+                ) => {
+                    self.warn_unreachable(body);
+                    if let Some(else_body) = else_block {
+                        self.warn_unreachable(else_body);
+                    }
+                }
+                StmtKind::Scope { body, .. }
+                | StmtKind::Zone { body, .. }
+                | StmtKind::UnsafeBlock(body) => self.warn_unreachable(body),
+                StmtKind::TryCatch { body, handler, .. } => {
+                    self.warn_unreachable(body);
+                    self.warn_unreachable(handler);
+                }
+                _ => {}
+            }
+        }
+    }
+
     fn stmt_guarantees_return(stmt: &Stmt) -> bool {
         match &stmt.kind {
             StmtKind::Return(_) => true,
@@ -1255,19 +950,22 @@ impl SemanticAnalyzer {
             StmtKind::Scope { body, .. }
             | StmtKind::Zone { body, .. }
             | StmtKind::UnsafeBlock(body) => body.iter().any(Self::stmt_guarantees_return),
+            StmtKind::TryCatch { body, handler, .. } => {
+                Self::body_guarantees_return(body) && Self::body_guarantees_return(handler)
+            }
             _ => false,
         }
     }
 
-    fn body_guarantees_return(body: &[Stmt]) -> bool {
+    pub(crate) fn body_guarantees_return(body: &[Stmt]) -> bool {
         body.iter().any(Self::stmt_guarantees_return)
     }
 
     fn analyze_statement(&mut self, statement: &Stmt) {
         match &statement.kind {
-            StmtKind::Import(lib) => {
+            StmtKind::Import { path: lib, alias } => {
                 self.symbol_table.define(SemanticSymbol {
-                    name: lib.clone(),
+                    name: alias.clone().unwrap_or_else(|| lib.clone()),
                     symbol_type: Type::Any,
                     kind: SemanticSymbolKind::Immutable,
                     is_variadic: false,
@@ -1351,31 +1049,49 @@ impl SemanticAnalyzer {
                         Type::Any
                     }
                 };
-
-                if let Some(symbol) = self.symbol_table.lookup(&var_set.name) {
-                    if symbol.kind == SemanticSymbolKind::Constant
-                        || symbol.kind == SemanticSymbolKind::Immutable
-                    {
-                        self.errors.push(SemanticError::new(
-                            SemanticErrorKind::ImmutableAssignment(var_set.name.clone()),
-                            statement.span.clone(),
-                        ));
-                    }
-                    if !self.is_compatible(&symbol.symbol_type, &expr_type)
-                        && expr_type != Type::Any
-                    {
+                let value_span = var_set.value.span;
+                self.analyze_assignment(&var_set.name, expr_type, statement.span, value_span);
+            }
+            StmtKind::TupleDeclaration {
+                names,
+                mutable,
+                value,
+            } => {
+                let item_types = self.destructure_types(names.len(), value);
+                let kind = if *mutable {
+                    SemanticSymbolKind::Mutable
+                } else {
+                    SemanticSymbolKind::Immutable
+                };
+                for (name, symbol_type) in names.iter().zip(item_types) {
+                    let symbol = SemanticSymbol {
+                        name: name.clone(),
+                        symbol_type,
+                        kind: kind.clone(),
+                        is_variadic: false,
+                    };
+                    if !self.symbol_table.define(symbol) {
                         self.errors.push(SemanticError::new(
-                            SemanticErrorKind::TypeMismatch {
-                                expected: symbol.symbol_type.clone(),
-                                found: expr_type,
-                            },
-                            statement.span.clone(),
+                            SemanticErrorKind::VariableAlreadyDeclared(name.clone()),
+                            statement.span,
                         ));
                     }
-                } else {
-                    self.errors.push(
-                        self.mk_variable_not_found(var_set.name.clone(), statement.span.clone()),
-                    );
+                }
+            }
+            StmtKind::TupleAssignment { names, value } => {
+                let item_types = self.destructure_types(names.len(), value);
+                for (name, item_type) in names.iter().zip(item_types) {
+                    self.analyze_assignment(name, item_type, statement.span, value.span);
+                }
+            }
+            StmtKind::TypeAlias { target, .. } => {
+                self.validate_type_exists(target, &statement.span);
+            }
+            StmtKind::EnumDeclaration { name, variants } => {
+                // Top-level enums were registered before the first statement.
+                let registered = self.enums.get(name).is_some_and(|known| known == variants);
+                if !registered {
+                    self.register_enum(name, variants, statement.span);
                 }
             }
             StmtKind::PropertyAssignment(p) => {
@@ -1408,13 +1124,14 @@ impl SemanticAnalyzer {
                                         expected: expected_type,
                                         found: value_type,
                                     },
-                                    statement.span.clone(),
+                                    p.value.span.clone(),
                                 ));
                             }
                         } else {
-                            self.errors.push(SemanticError::new(
-                                SemanticErrorKind::PropertyNotFound(p.property.clone()),
-                                statement.span.clone(),
+                            self.errors.push(self.mk_property_not_found(
+                                &class_name,
+                                &p.property,
+                                statement.span,
                             ));
                         }
                     }
@@ -1459,7 +1176,7 @@ impl SemanticAnalyzer {
                         if !index_type.is_numeric() && index_type != Type::Any {
                             self.errors.push(SemanticError::new(
                                 SemanticErrorKind::InvalidIndexType(index_type),
-                                statement.span.clone(),
+                                i.index.span.clone(),
                             ));
                         }
                     }
@@ -1467,7 +1184,7 @@ impl SemanticAnalyzer {
                         if !index_type.is_numeric() && index_type != Type::Any {
                             self.errors.push(SemanticError::new(
                                 SemanticErrorKind::InvalidIndexType(index_type),
-                                statement.span.clone(),
+                                i.index.span.clone(),
                             ));
                         }
                         if !self.is_compatible(&element_type, &value_type)
@@ -1478,7 +1195,7 @@ impl SemanticAnalyzer {
                                     expected: (*element_type).clone(),
                                     found: value_type,
                                 },
-                                statement.span.clone(),
+                                i.value.span.clone(),
                             ));
                         }
                     }
@@ -1508,7 +1225,7 @@ impl SemanticAnalyzer {
                     other => {
                         self.errors.push(SemanticError::new(
                             SemanticErrorKind::IndexAccessOnNonIndexable(other),
-                            statement.span.clone(),
+                            i.target.span.clone(),
                         ));
                     }
                 }
@@ -1543,6 +1260,17 @@ impl SemanticAnalyzer {
                 let prev_return_type = self.current_function_return_type.clone();
                 self.current_function_return_type =
                     Some(func_decl.return_type.clone().unwrap_or(Type::Any));
+                // In a method `self` is the object; functions nested in the
+                // body don't get one.
+                let method_class = self.current_class.take();
+                if let Some(class_name) = &method_class {
+                    self.symbol_table.define(SemanticSymbol {
+                        name: "self".to_string(),
+                        symbol_type: Type::User(class_name.clone()),
+                        kind: SemanticSymbolKind::Immutable,
+                        is_variadic: false,
+                    });
+                }
 
                 for (param_name, param_type) in &func_decl.params {
                     let param_symbol = SemanticSymbol {
@@ -1561,6 +1289,7 @@ impl SemanticAnalyzer {
                 for stmt in &func_decl.body {
                     self.analyze_statement(stmt);
                 }
+                self.warn_unreachable(&func_decl.body);
 
                 if func_decl.is_unsafe {
                     self.unsafe_depth = self.unsafe_depth.saturating_sub(1);
@@ -1585,6 +1314,7 @@ impl SemanticAnalyzer {
                 }
 
                 self.current_function_return_type = prev_return_type;
+                self.current_class = method_class;
                 self.symbol_table.exit_scope();
             }
             StmtKind::Return(expr) => {
@@ -1731,12 +1461,30 @@ impl SemanticAnalyzer {
                         .push(self.mk_variable_not_found(set.name.clone(), statement.span.clone()));
                 }
             }
-            StmtKind::Print(expressions) => {
-                for expr in expressions {
+            StmtKind::Print(print) => {
+                for expr in &print.args {
                     if let Err(e) = self.type_check_expression(expr) {
                         self.errors.push(e);
                     }
                 }
+                for option in print.sep.iter().chain(print.end.iter()) {
+                    match self.type_check_expression(option) {
+                        Ok(option_type) => {
+                            if !self.is_compatible(&Type::String, &option_type)
+                                && option_type != Type::Any
+                            {
+                                self.errors.push(SemanticError::new(
+                                    SemanticErrorKind::TypeMismatch {
+                                        expected: Type::String,
+                                        found: option_type,
+                                    },
+                                    option.span.clone(),
+                                ));
+                            }
+                        }
+                        Err(e) => self.errors.push(e),
+                    }
+                }
             }
             StmtKind::Expression(expr) | StmtKind::FuncCall(expr) => {
                 if let Err(e) = self.type_check_expression(expr) {
@@ -1747,12 +1495,9 @@ impl SemanticAnalyzer {
                 self.symbol_table.enter_scope();
                 let prev_class = self.current_class.clone();
                 self.current_class = Some(class.name.clone());
-                self.symbol_table.define(SemanticSymbol {
-                    name: "self".to_string(),
-                    symbol_type: Type::User(class.name.clone()),
-                    kind: SemanticSymbolKind::Immutable,
-                    is_variadic: false,
-                });
+                for prop in &class.properties {
+                    self.analyze_class_property(prop, statement.span);
+                }
                 for method in &class.methods {
                     self.analyze_statement(&Stmt::with_span(
                         StmtKind::FuncDeclaration(method.clone()),
@@ -1785,10 +1530,98 @@ impl SemanticAnalyzer {
                 }
                 self.symbol_table.exit_scope();
             }
+            StmtKind::TryCatch {
+                body,
+                err_name,
+                handler,
+            } => {
+                self.symbol_table.enter_scope();
+                for s in body {
+                    self.analyze_statement(s);
+                }
+                self.symbol_table.exit_scope();
+
+                // The error binding only exists inside the handler.
+                self.symbol_table.enter_scope();
+                self.symbol_table.define(SemanticSymbol {
+                    name: err_name.clone(),
+                    symbol_type: Type::String,
+                    kind: SemanticSymbolKind::Immutable,
+                    is_variadic: false,
+                });
+                for s in handler {
+                    self.analyze_statement(s);
+                }
+                self.symbol_table.exit_scope();
+            }
             StmtKind::Entangle { .. } => {}
         }
     }
 
+    /// `span` covers the whole assignment; a type mismatch points at the
+    /// assigned value (`value_span`) instead.
+    fn analyze_assignment(&mut self, name: &str, expr_type: Type, span: Span, value_span: Span) {
+        if let Some(symbol) = self.symbol_table.lookup(name) {
+            if symbol.kind == SemanticSymbolKind::Constant
+                || symbol.kind == SemanticSymbolKind::Immutable
+            {
+                self.errors.push(SemanticError::new(
+                    SemanticErrorKind::ImmutableAssignment(name.to_string()),
+                    span,
+                ));
+            }
+            if !self.is_compatible(&symbol.symbol_type, &expr_type) && expr_type != Type::Any {
+                self.errors.push(SemanticError::new(
+                    SemanticErrorKind::TypeMismatch {
+                        expected: symbol.symbol_type.clone(),
+                        found: expr_type,
+                    },
+                    value_span,
+                ));
+            }
+        } else {
+            self.errors.push(self.mk_variable_not_found(name.to_string(), span));
+        }
+    }
+
+    /// Element types for destructuring `value` into `count` names. Tuples must
+    /// match exactly; lists and `any` values can only be checked at runtime.
+    fn destructure_types(&mut self, count: usize, value: &Expr) -> Vec<Type> {
+        let span = value.span;
+        let value_type = match self.type_check_expression(value) {
+            Ok(t) => t,
+            Err(e) => {
+                self.errors.push(e);
+                Type::Any
+            }
+        };
+        match value_type {
+            Type::Tuple(items) if items.len() == count => items,
+            Type::Tuple(items) => {
+                self.errors.push(SemanticError::new(
+                    SemanticErrorKind::TupleArityMismatch {
+                        expected: count,
+                        found: items.len(),
+                    },
+                    span,
+                ));
+                vec![Type::Any; count]
+            }
+            Type::ListOf(inner) => vec![*inner; count],
+            Type::List | Type::Any => vec![Type::Any; count],
+            other => {
+                self.errors.push(SemanticError::new(
+                    SemanticErrorKind::TypeMismatch {
+                        expected: Type::Tuple(vec![Type::Any; count]),
+                        found: other,
+                    },
+                    span,
+                ));
+                vec![Type::Any; count]
+            }
+        }
+    }
+
     fn analyze_var_decl(&mut self, decl: &VarDecl, kind: SemanticSymbolKind, span: Span) {
         if let Some(expected_type) = &decl.var_type {
             self.validate_type_exists(expected_type, &span);
@@ -1809,7 +1642,7 @@ impl SemanticAnalyzer {
                         expected: expected_type.clone(),
                         found: expr_type,
                     },
-                    span.clone(),
+                    decl.value.span.clone(),
                 ));
             }
             expected_type.clone()
@@ -1832,9 +1665,73 @@ impl SemanticAnalyzer {
         }
     }
 
-    fn analyze_conditional(&mut self, cond: &ConditionalStmt) {
-        if let Err(e) = self.check_condition(&cond.if_block.condition) {
-            self.errors.push(e);
+    /// Checks `new Class(args)` against the parameters of `init` (inherited
+    /// if the class has none). Without `init` the class takes no arguments.
+    fn constructor_type(
+        &mut self,
+        class: &str,
+        args: &[Expr],
+        span: Span,
+    ) -> Result<Type, SemanticError> {
+        let params = match self.class_member_type(class, "init") {
+            Some(Type::Function(params, _)) => params,
+            _ => Vec::new(),
+        };
+        if args.len() != params.len() {
+            return Err(SemanticError::new(
+                SemanticErrorKind::WrongNumberOfArguments {
+                    expected: params.len(),
+                    found: args.len(),
+                },
+                span,
+            )
+            .with_note(format!(
+                "`{class}` is constructed with the parameters of its `init` method"
+            )));
+        }
+        for (arg, expected_type) in args.iter().zip(params.iter()) {
+            let arg_type = self.type_check_expression(arg)?;
+            if !self.is_compatible(expected_type, &arg_type) {
+                return Err(SemanticError::new(
+                    SemanticErrorKind::TypeMismatch {
+                        expected: expected_type.clone(),
+                        found: arg_type,
+                    },
+                    arg.span,
+                ));
+            }
+        }
+        Ok(Type::User(class.to_string()))
+    }
+
+    /// Checks a property's initializer against its declared type.
+    fn analyze_class_property(&mut self, prop: &VarDecl, class_span: Span) {
+        if let Some(var_type) = &prop.var_type {
+            self.validate_type_exists(var_type, &class_span);
+        }
+        let value_type = match self.type_check_expression(&prop.value) {
+            Ok(t) => t,
+            Err(e) => {
+                self.errors.push(e);
+                return;
+            }
+        };
+        if let Some(var_type) = &prop.var_type {
+            if !self.is_compatible(var_type, &value_type) && value_type != Type::Any {
+                self.errors.push(SemanticError::new(
+                    SemanticErrorKind::TypeMismatch {
+                        expected: var_type.clone(),
+                        found: value_type,
+                    },
+                    prop.value.span,
+                ));
+            }
+        }
+    }
+
+    fn analyze_conditional(&mut self, cond: &ConditionalStmt) {
+        if let Err(e) = self.check_condition(&cond.if_block.condition) {
+            self.errors.push(e);
         }
         self.symbol_table.enter_scope();
         for stmt in &cond.if_block.body {
@@ -1865,7 +1762,9 @@ impl SemanticAnalyzer {
     fn analyze_loop(&mut self, loop_stmt: &LoopStmt) {
         self.symbol_table.enter_scope();
         match loop_stmt {
-            LoopStmt::While { condition, body } => {
+            LoopStmt::While {
+                condition, body, ..
+            } => {
                 if let Err(e) = self.check_condition(condition) {
                     self.errors.push(e);
                 }
@@ -1877,6 +1776,7 @@ impl SemanticAnalyzer {
                 iterator,
                 iterable,
                 body,
+                ..
             } => {
                 let iterable_type = match self.type_check_expression(iterable) {
                     Ok(t) => t,
@@ -1920,6 +1820,16 @@ impl SemanticAnalyzer {
             }
         }
         self.symbol_table.exit_scope();
+
+        // `else` runs after the loop, where its variable is no longer in scope.
+        let (LoopStmt::While { else_block, .. } | LoopStmt::For { else_block, .. }) = loop_stmt;
+        if let Some(else_body) = else_block {
+            self.symbol_table.enter_scope();
+            for stmt in else_body {
+                self.analyze_statement(stmt);
+            }
+            self.symbol_table.exit_scope();
+        }
     }
 
     fn check_condition(&mut self, expr: &Expr) -> Result<(), SemanticError> {
@@ -1993,12 +1903,68 @@ impl SemanticAnalyzer {
             return self.is_compatible(expected_key, found_key)
                 && self.is_compatible(expected_value, found_value);
         }
+        if let (Type::Tuple(expected_items), Type::Tuple(found_items)) = (expected, found) {
+            return expected_items.len() == found_items.len()
+                && expected_items
+                    .iter()
+                    .zip(found_items)
+                    .all(|(e, f)| self.is_compatible(e, f));
+        }
         if *expected == Type::Any || *found == Type::Any {
             return true;
         }
         false
     }
 
+    /// The message of `assert(cond, message)` is optional, which the generic
+    /// fixed-arity call check cannot express.
+    fn assert_call_type(&mut self, args: &[Expr], span: Span) -> Result<Type, SemanticError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(SemanticError::new(
+                SemanticErrorKind::WrongNumberOfArguments {
+                    expected: 2,
+                    found: args.len(),
+                },
+                span,
+            ));
+        }
+        self.check_condition(&args[0])?;
+        if let Some(message) = args.get(1) {
+            let found = self.type_check_expression(message)?;
+            if !self.is_compatible(&Type::String, &found) {
+                return Err(SemanticError::new(
+                    SemanticErrorKind::TypeMismatch {
+                        expected: Type::String,
+                        found,
+                    },
+                    message.span,
+                ));
+            }
+        }
+        Ok(Type::Void)
+    }
+
+    /// `cfg("KEY")` is resolved before analysis when building; `snask check`
+    /// still sees the call, so only its shape is checked here.
+    fn cfg_call_type(&mut self, args: &[Expr], span: Span) -> Result<Type, SemanticError> {
+        if args.len() != 1 {
+            return Err(SemanticError::new(
+                SemanticErrorKind::WrongNumberOfArguments {
+                    expected: 1,
+                    found: args.len(),
+                },
+                span,
+            ));
+        }
+        if !matches!(args[0].kind, ExprKind::Literal(LiteralValue::String(_))) {
+            return Err(SemanticError::new(
+                SemanticErrorKind::CfgKeyNotLiteral,
+                args[0].span,
+            ));
+        }
+        Ok(Type::Bool)
+    }
+
     fn systems_low_level_call_type(
         &mut self,
         name: &str,
@@ -2040,6 +2006,26 @@ impl SemanticAnalyzer {
             arg_types.push(self.type_check_expression(arg)?);
         }
 
+        // An integer where a pointer belongs would be dereferenced as an address.
+        let pointer_args: &[usize] = match name {
+            "mem_copy" => &[0, 1],
+            _ if is_raw_memory && !name.starts_with("mem_alloc") => &[0],
+            _ => &[],
+        };
+        for &i in pointer_args {
+            if let (Some(arg), Some(found)) = (args.get(i), arg_types.get(i)) {
+                if !self.is_compatible(&Type::Ptr, found) {
+                    return Err(SemanticError::new(
+                        SemanticErrorKind::TypeMismatch {
+                            expected: Type::Ptr,
+                            found: found.clone(),
+                        },
+                        arg.span.clone(),
+                    ));
+                }
+            }
+        }
+
         let ret = match name {
             "as_u8" => Some(Type::U8),
             "as_u16" => Some(Type::U16),
@@ -2167,6 +2153,22 @@ impl SemanticAnalyzer {
                             ))
                         }
                     }
+                    BinaryOp::Power => {
+                        if left_type == Type::Any || right_type == Type::Any {
+                            Ok(Type::Any)
+                        } else if left_type.is_numeric() && right_type.is_numeric() {
+                            Ok(Type::Float)
+                        } else {
+                            Err(SemanticError::new(
+                                SemanticErrorKind::InvalidOperation {
+                                    op: format!("{:?}", op),
+                                    type1: left_type,
+                                    type2: Some(right_type),
+                                },
+                                expression.span.clone(),
+                            ))
+                        }
+                    }
                     BinaryOp::BitAnd
                     | BinaryOp::BitOr
                     | BinaryOp::BitXor
@@ -2187,6 +2189,30 @@ impl SemanticAnalyzer {
                             ))
                         }
                     }
+                    BinaryOp::In => {
+                        // The left side must fit what the container holds:
+                        // an element of a list, a key of a dict, a str in a str.
+                        let item_type = match &right_type {
+                            Type::Any | Type::List | Type::Dict => Some(Type::Any),
+                            Type::String => Some(Type::String),
+                            Type::ListOf(element_type) => Some((**element_type).clone()),
+                            Type::DictOf(key_type, _) => Some((**key_type).clone()),
+                            _ => None,
+                        };
+                        match item_type {
+                            Some(item_type) if self.is_compatible(&item_type, &left_type) => {
+                                Ok(Type::Bool)
+                            }
+                            _ => Err(SemanticError::new(
+                                SemanticErrorKind::InvalidOperation {
+                                    op: format!("{:?}", op),
+                                    type1: left_type,
+                                    type2: Some(right_type),
+                                },
+                                expression.span.clone(),
+                            )),
+                        }
+                    }
                     _ => {
                         if self.is_compatible(&left_type, &right_type)
                             || self.is_compatible(&right_type, &left_type)
@@ -2268,6 +2294,48 @@ impl SemanticAnalyzer {
                     {
                         return Ok(ret);
                     }
+
+                    if name == "assert" {
+                        return self.assert_call_type(args, expression.span);
+                    }
+                    if name == "cfg" {
+                        return self.cfg_call_type(args, expression.span);
+                    }
+                }
+
+                // `Point(1, 2)` constructs like `new Point(1, 2)`.
+                if let ExprKind::Variable(name) = &callee.kind {
+                    if self.classes.contains_key(name) {
+                        return self.constructor_type(name, args, expression.span);
+                    }
+                }
+
+                // `a?.m(x)` is checked like `a.m(x)`, but may be nil.
+                if let ExprKind::PropertyAccess {
+                    target,
+                    property,
+                    optional: true,
+                } = &callee.kind
+                {
+                    let method = Expr::with_span(
+                        ExprKind::PropertyAccess {
+                            target: target.clone(),
+                            property: property.clone(),
+                            optional: false,
+                        },
+                        callee.loc.clone(),
+                        callee.span,
+                    );
+                    let call = Expr::with_span(
+                        ExprKind::FunctionCall {
+                            callee: Box::new(method),
+                            args: args.clone(),
+                        },
+                        expression.loc.clone(),
+                        expression.span,
+                    );
+                    self.type_check_expression(&call)?;
+                    return Ok(Type::Any);
                 }
 
                 let callee_symbol = if let ExprKind::Variable(name) = &callee.kind {
@@ -2324,23 +2392,26 @@ impl SemanticAnalyzer {
                     ))
                 }
             }
-            ExprKind::PropertyAccess { target, property } => {
+            ExprKind::PropertyAccess {
+                target,
+                property,
+                optional,
+            } => {
                 let target_type = self.type_check_expression(target)?;
-                match target_type {
+                let member_type = match target_type {
                     Type::Any => Ok(Type::Any),
                     Type::User(class_name) => self
                         .class_member_type(&class_name, property)
                         .ok_or_else(|| {
-                            SemanticError::new(
-                                SemanticErrorKind::PropertyNotFound(property.clone()),
-                                expression.span.clone(),
-                            )
+                            self.mk_property_not_found(&class_name, property, expression.span)
                         }),
                     _ => Err(SemanticError::new(
                         SemanticErrorKind::PropertyNotFound(property.clone()),
                         expression.span.clone(),
                     )),
-                }
+                }?;
+                // `a?.b` may be nil whatever `b` is.
+                Ok(if *optional { Type::Any } else { member_type })
             }
             ExprKind::IndexAccess { target, index } => {
                 let target_type = self.type_check_expression(target)?;
@@ -2352,7 +2423,7 @@ impl SemanticAnalyzer {
                         if !index_type.is_numeric() && index_type != Type::Any {
                             Err(SemanticError::new(
                                 SemanticErrorKind::InvalidIndexType(index_type),
-                                expression.span.clone(),
+                                index.span.clone(),
                             ))
                         } else {
                             Ok(Type::String)
@@ -2362,7 +2433,7 @@ impl SemanticAnalyzer {
                         if !index_type.is_numeric() && index_type != Type::Any {
                             Err(SemanticError::new(
                                 SemanticErrorKind::InvalidIndexType(index_type),
-                                expression.span.clone(),
+                                index.span.clone(),
                             ))
                         } else {
                             Ok(Type::Any)
@@ -2372,7 +2443,7 @@ impl SemanticAnalyzer {
                         if !index_type.is_numeric() && index_type != Type::Any {
                             Err(SemanticError::new(
                                 SemanticErrorKind::InvalidIndexType(index_type),
-                                expression.span.clone(),
+                                index.span.clone(),
                             ))
                         } else {
                             Ok(*element_type)
@@ -2386,7 +2457,7 @@ impl SemanticAnalyzer {
                                     expected: (*key_type).clone(),
                                     found: index_type,
                                 },
-                                expression.span.clone(),
+                                index.span.clone(),
                             ))
                         } else {
                             Ok(*value_type)
@@ -2398,67 +2469,113 @@ impl SemanticAnalyzer {
                     )),
                 }
             }
+            ExprKind::Slice {
+                target,
+                start,
+                end,
+                step,
+            } => {
+                let target_type = self.type_check_expression(target)?;
+                for bound in [start, end, step].into_iter().flatten() {
+                    let bound_type = self.type_check_expression(bound)?;
+                    if !bound_type.is_numeric() && bound_type != Type::Any {
+                        return Err(SemanticError::new(
+                            SemanticErrorKind::InvalidIndexType(bound_type),
+                            bound.span.clone(),
+                        ));
+                    }
+                }
+                // A slice has the type of what it was cut from.
+                match target_type {
+                    Type::Any | Type::String | Type::List | Type::ListOf(_) => Ok(target_type),
+                    other => Err(SemanticError::new(
+                        SemanticErrorKind::IndexAccessOnNonIndexable(other),
+                        expression.span.clone(),
+                    )),
+                }
+            }
             ExprKind::New { class, args, .. } => {
                 if !self.classes.contains_key(class) {
                     return Err(self.mk_unknown_type(class.clone(), expression.span.clone()));
                 }
-                let init_method = self
-                    .classes
-                    .get(class)
-                    .ok_or_else(|| self.mk_unknown_type(class.clone(), expression.span.clone()))?
-                    .methods
-                    .iter()
-                    .find(|m| m.name == "init")
-                    .cloned();
-
-                if let Some(init) = init_method {
-                    let expected_args = init.params.len();
-                    if args.len() != expected_args {
+                self.constructor_type(class, args, expression.span)
+            }
+            ExprKind::StructLiteral { class, fields } => {
+                if !self.classes.contains_key(class) {
+                    return Err(self.mk_unknown_type(class.clone(), expression.span.clone()));
+                }
+                let properties = self.class_properties(class);
+                for (name, value) in fields {
+                    let value_type = self.type_check_expression(value)?;
+                    let Some(property) = properties.iter().find(|p| p.name == *name) else {
+                        return Err(self.mk_property_not_found(class, name, value.span.clone()));
+                    };
+                    let expected = self.property_decl_type(property);
+                    if !self.is_compatible(&expected, &value_type) {
                         return Err(SemanticError::new(
-                            SemanticErrorKind::WrongNumberOfArguments {
-                                expected: expected_args,
-                                found: args.len(),
+                            SemanticErrorKind::TypeMismatch {
+                                expected,
+                                found: value_type,
                             },
-                            expression.span.clone(),
+                            value.span.clone(),
                         ));
                     }
-
-                    for (arg, (_, expected_type)) in args.iter().zip(init.params.iter()) {
-                        let arg_type = self.type_check_expression(arg)?;
-                        if !self.is_compatible(expected_type, &arg_type) {
-                            return Err(SemanticError::new(
-                                SemanticErrorKind::TypeMismatch {
-                                    expected: expected_type.clone(),
-                                    found: arg_type,
-                                },
-                                arg.span.clone(),
-                            ));
-                        }
-                    }
-                } else {
-                    for arg in args {
-                        let _ = self.type_check_expression(arg)?;
-                    }
                 }
-
+                let missing: Vec<String> = properties
+                    .iter()
+                    .filter(|p| !fields.iter().any(|(name, _)| *name == p.name))
+                    .map(|p| format!("`{}`", p.name))
+                    .collect();
+                if !missing.is_empty() {
+                    return Err(SemanticError::new(
+                        SemanticErrorKind::MissingStructFields {
+                            class: class.clone(),
+                            fields: missing.join(", "),
+                        },
+                        expression.span.clone(),
+                    ));
+                }
                 Ok(Type::User(class.clone()))
             }
+            ExprKind::Tuple(items) => {
+                let mut item_types = Vec::with_capacity(items.len());
+                for item in items {
+                    item_types.push(self.type_check_expression(item)?);
+                }
+                Ok(Type::Tuple(item_types))
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{SemanticAnalyzer, SemanticErrorKind};
+    use super::{SemanticAnalyzer, SemanticError, SemanticErrorKind, SemanticSymbolKind};
+    use crate::builtins::BUILTINS;
     use crate::parser::parse_program;
+    use crate::types::Type;
 
     fn analyze_source(src: &str) -> SemanticAnalyzer {
-        let program = parse_program(src).expect("source should parse");
+        let mut program = parse_program(src).expect("source should parse");
         let mut analyzer = SemanticAnalyzer::new();
+        analyzer.expand_type_aliases(&mut program);
         analyzer.analyze(&program);
         analyzer
     }
 
+    /// Analyzes `src` and returns its only error; `what` names the construct
+    /// that is expected to fail.
+    fn single_error(src: &str, what: &str) -> SemanticError {
+        let analyzer = analyze_source(src);
+        assert_eq!(
+            analyzer.errors.len(),
+            1,
+            "expected only {what} to fail, got: {:?}",
+            analyzer.errors
+        );
+        analyzer.errors.into_iter().next().unwrap()
+    }
+
     #[test]
     fn sqlite_cleanup_functions_are_not_public_snask_api() {
         let analyzer = analyze_source(
@@ -2920,6 +3037,27 @@ class main
         );
     }
 
+    #[test]
+    fn bitwise_ops_reject_floats() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let mask: int = 0xF0 & 0x0F
+        let bit: int = 1 << 4
+        let a = 1.5 & 2
+        let b = 1 << 0.5
+        let c = ~1.5
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 3, "{:?}", analyzer.errors);
+        assert!(analyzer
+            .errors
+            .iter()
+            .all(|e| matches!(e.kind, SemanticErrorKind::InvalidOperation { .. })));
+    }
+
     #[test]
     fn native_module_aliases_type_check() {
         let analyzer = analyze_source(
@@ -2996,6 +3134,30 @@ class main
         );
     }
 
+    #[test]
+    fn raw_memory_builtins_require_pointer_arguments() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        @unsafe:
+            let mem: ptr = mem_alloc(16)
+            mem_write_u8(mem, 0, 1)
+            let byte: u8 = mem_read_u8(ptr_add(mem, 1), 0)
+            mem_write_u8(4096, 0, 1)
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 1, "{:?}", analyzer.errors);
+        assert!(matches!(
+            analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::Ptr,
+                found: Type::Int
+            }
+        ));
+    }
+
     #[test]
     fn typed_function_without_return_is_reported() {
         let analyzer = analyze_source(
@@ -3041,4 +3203,1012 @@ class main
             analyzer.errors
         );
     }
+
+    #[test]
+    fn print_options_must_be_strings() {
+        let err = single_error(
+            r#"
+class main
+    fun start()
+        print("a", "b", sep: ", ", end: "")
+        print("a", end: 1)
+"#,
+            "the numeric `end:`",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn string_slice_type_checks_as_a_module_call() {
+        let err = single_error(
+            r#"
+import "string"
+
+class main
+    fun start()
+        let tail: str = string::slice("hello", -3, -1, 1)
+        let rev: str = string::slice("hello", 10, -10, -1)
+        let bad = string::slice(42, 0, 1, 1)
+"#,
+            "the numeric receiver",
+        );
+        assert!(matches!(
+            err.kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::Int
+            }
+        ));
+    }
+
+    #[test]
+    fn regex_module_type_checks_and_raw_natives_stay_restricted() {
+        let err = single_error(
+            r#"
+import "regex"
+
+class main
+    fun start()
+        let ok: bool = regex::match("[0-9]+", "a1 b22")
+        let first: str = regex::find("([a-z])[0-9]+", "a1 b22")
+        let all: list = regex::find_all("[0-9]+", "a1 b22")
+        let swapped: str = regex::replace("([a-z])([0-9]+)", "a1", "\\2\\1")
+        let raw = regex_match("a", "a")
+"#,
+            "the raw native call",
+        );
+        assert!(matches!(
+            err.kind,
+            SemanticErrorKind::RestrictedNativeFunction { .. }
+        ));
+    }
+
+    #[test]
+    fn datetime_module_type_checks() {
+        let err = single_error(
+            r#"
+import "datetime"
+
+class main
+    fun start()
+        let now: float = datetime::now()
+        let day: str = datetime::format(1700000000, "%Y-%m-%d")
+        let epoch: float = datetime::parse("2023-11-14", "%Y-%m-%d")
+        let next: float = datetime::add_days(epoch, 1)
+        let bad: str = datetime::format("today", "%Y")
+"#,
+            "the string epoch",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn csv_module_type_checks() {
+        let err = single_error(
+            r#"
+import "csv"
+
+class main
+    fun start()
+        let rows: list = csv::parse("a,b\n1,2\n")
+        let tabbed: list = csv::parse_delim("a\tb\n", "\t")
+        let text: str = csv::write(rows)
+        let piped: str = csv::write_delim(rows, "|")
+        let bad: str = csv::write("a,b")
+"#,
+            "the string rows",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn base64_module_type_checks() {
+        let err = single_error(
+            r#"
+import "base64"
+
+class main
+    fun start()
+        let encoded: str = base64::encode("hello")
+        let decoded: str = base64::decode(encoded)
+        let token: str = base64::encode_url("<<??>>")
+        let back: str = base64::decode_url(token)
+        let bad: str = base64::encode(42)
+"#,
+            "the number argument",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn hash_module_type_checks() {
+        let err = single_error(
+            r#"
+import "hash"
+
+class main
+    fun start()
+        let a: str = hash::sha256_hex("abc")
+        let b: str = hash::sha1_hex("abc")
+        let c: str = hash::md5_hex("abc")
+        let raw = hash_md5_hex("abc")
+"#,
+            "the raw native call",
+        );
+        assert!(matches!(
+            err.kind,
+            SemanticErrorKind::RestrictedNativeFunction { .. }
+        ));
+    }
+
+    #[test]
+    fn sb_module_type_checks() {
+        let err = single_error(
+            r#"
+import "sb"
+
+class main
+    fun start()
+        let b: str = sb::create()
+        let ok: bool = sb::append(b, "abc")
+        let s: str = sb::to_string(b)
+        let freed: bool = sb::free(b)
+        sb::append(b, 42)
+"#,
+            "the number argument",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn sfs_streaming_natives_type_check() {
+        let err = single_error(
+            r#"
+import "sfs"
+
+class main
+    fun start()
+        let h: str = sfs::open("app.log", "r")
+        let line: str = sfs::read_line(h)
+        let wrote: bool = sfs::write_line(h, line)
+        let closed: bool = sfs::close(h)
+        sfs::open("app.log")
+"#,
+            "the missing argument",
+        );
+        assert!(matches!(
+            err.kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 2,
+                found: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn cfg_takes_a_single_string_literal() {
+        let err = single_error(
+            r#"
+class main
+    fun start()
+        let key = "debug"
+        let on: bool = cfg("debug")
+        let dynamic: bool = cfg(key)
+"#,
+            "the variable key",
+        );
+        assert!(matches!(err.kind, SemanticErrorKind::CfgKeyNotLiteral));
+    }
+
+    #[test]
+    fn json_stringify_opts_takes_indent_and_sort_keys() {
+        let err = single_error(
+            r#"
+import "json"
+
+class main
+    fun start()
+        let sorted: str = json::stringify_opts({"b": 1, "a": 2}, 0, true)
+        let wide: str = json::stringify_opts({"b": 1}, 4, false)
+        json::stringify_opts({"b": 1}, 2)
+"#,
+            "the missing argument",
+        );
+        assert!(matches!(
+            err.kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 3,
+                found: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn catch_binding_is_a_string_scoped_to_the_handler() {
+        let err = single_error(
+            r#"
+import "sfs"
+
+class main
+    fun start()
+        try:
+            let text: str = sfs::read("missing.txt")
+            print(text)
+        catch err:
+            let message: str = err
+            print(message)
+        print(err)
+"#,
+            "the use of `err` after the handler",
+        );
+        assert!(matches!(
+            &err.kind,
+            SemanticErrorKind::VariableNotFound(name) if name == "err"
+        ));
+    }
+
+    #[test]
+    fn assert_takes_a_condition_and_an_optional_string_message() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let n = 3
+        assert(n > 2)
+        assert(n == 3, "n should be three")
+        assert(n > 2, 42)
+        assert()
+"#,
+        );
+
+        assert_eq!(
+            analyzer.errors.len(),
+            2,
+            "expected the numeric message and the empty call to fail, got: {:?}",
+            analyzer.errors
+        );
+        assert!(matches!(
+            analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                ..
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments { found: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn tuple_destructuring_checks_arity_and_element_types() {
+        let analyzer = analyze_source(
+            r#"
+fun divmod(a: int, b: int): (int, int)
+    return a / b, a % b
+
+class main
+    fun start()
+        let q, r = divmod(7, 2)
+        mut a, b = q, r
+        a, b = b, a
+        let s: str = q
+        let x, y, z = divmod(9, 4)
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { expected: Type::String, found: Type::Int }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::TupleArityMismatch { expected: 3, found: 2 }
+        ));
+    }
+
+    #[test]
+    fn enum_variants_are_int_constants_and_unknown_variants_error() {
+        let analyzer = analyze_source(
+            r#"
+enum Color: Red, Green, Blue
+
+class main
+    fun start()
+        let red: int = Color::Red
+        let same = Color::Green == Color::Blue
+        let nope = Color::Purple
+        Color::Red = 4
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::UnknownEnumVariant { enum_name, variant }
+                if enum_name == "Color" && variant == "Purple"
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::ImmutableAssignment(name) if name == "Color::Red"
+        ));
+    }
+
+    #[test]
+    fn type_alias_checks_like_its_underlying_type() {
+        let analyzer = analyze_source(
+            r#"
+type UserId = Id
+type Names = list<str>
+
+class main
+    fun start()
+        let id: UserId = 5
+        let plain: int = id
+        let names: Names = ["a", "b"]
+        let bad: UserId = "x"
+
+type Id = int
+type Missing = Nope
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { expected: Type::Int, found: Type::String }
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::UnknownType(name) if name == "Nope"
+        ));
+    }
+
+    #[test]
+    fn cyclic_duplicate_and_nested_type_aliases_are_reported() {
+        let analyzer = analyze_source(
+            r#"
+type A = list<B>
+type B = A
+type Count = int
+type Count = float
+
+class main
+    fun start()
+        type Local = int
+        let n: Count = 1
+"#,
+        );
+
+        let invalid: Vec<(&str, String)> = analyzer
+            .errors
+            .iter()
+            .filter_map(|e| match &e.kind {
+                SemanticErrorKind::InvalidTypeAlias(name) => {
+                    Some((name.as_str(), e.notes.join("; ")))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(invalid.len(), 4, "{:?}", analyzer.errors);
+        assert_eq!(invalid[0].0, "Count");
+        assert!(invalid[0].1.contains("already declared"), "{invalid:?}");
+        assert_eq!(invalid[1].0, "A");
+        assert!(invalid[1].1.contains("A -> B -> A"), "{invalid:?}");
+        assert_eq!(invalid[2].0, "B");
+        assert_eq!(invalid[3].0, "Local");
+        assert!(invalid[3].1.contains("top level"), "{invalid:?}");
+    }
+
+    #[test]
+    fn conversion_builtins_return_their_target_types() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let i: int = to_int("42")
+        let t: int = to_int(3.9)
+        let f: float = to_float("2.5")
+        let s: str = to_str(i)
+        let b: bool = to_bool("")
+        let wrong: str = to_int("7")
+        to_bool()
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { expected: Type::String, found: Type::Int }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments { expected: 1, found: 0 }
+        ));
+    }
+
+    #[test]
+    fn integer_math_builtins_return_int() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let g: int = gcd(12, 18)
+        let l: int = lcm(4, 6)
+        let f: int = factorial(5)
+        let r: int = isqrt(99)
+        let wrong: str = factorial(3)
+        gcd(1)
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::Int
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 2,
+                found: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn random_builtins_type_check() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        random_seed(42)
+        let x: float = random()
+        let d: float = random_range(1, 7)
+        let wrong: str = random()
+        random_seed("abc")
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::Float
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::TypeMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn timing_builtins_type_check() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let start: int = time_ns()
+        sleep_ms(5)
+        let elapsed: int = time_ns() - start
+        let ms: int = time_ms()
+        let wrong: str = time_ms()
+        sleep_ms()
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::Int
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 1,
+                found: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn loop_else_blocks_are_type_checked() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        mut i = 0
+        while i < 3
+            i = i + 1
+        else
+            let n: str = i
+        for x in [1, 2]
+            print(x)
+        else
+            print(x)
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { .. }
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::VariableNotFound(name) if name == "x"
+        ));
+    }
+
+    #[test]
+    fn slices_keep_the_type_of_their_target() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let xs: list<int> = [1, 2, 3, 4]
+        let middle: list<int> = xs[1:3]
+        let tail: str = "hello"[-3:]
+        let reversed: list = [1, 2][::-1]
+        let wrong: str = xs[:2]
+        let bad = xs["a":]
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { .. }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::InvalidIndexType(Type::String)
+        ));
+    }
+
+    #[test]
+    fn membership_checks_the_item_against_the_container() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let xs: list<int> = [1, 2, 3]
+        let ages: dict<str, int> = {"ana": 30}
+        let a: bool = 2 in xs
+        let b: bool = "ana" in ages and "sk" in "snask"
+        let c: bool = "two" in xs
+        let d = 1 in "snask"
+        let e = 1 in 5
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 3, "{:?}", analyzer.errors);
+        assert!(analyzer.errors.iter().all(|e| matches!(
+            &e.kind,
+            SemanticErrorKind::InvalidOperation { op, .. } if op == "In"
+        )));
+    }
+
+    #[test]
+    fn power_takes_numbers_and_returns_float() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let a: float = 2 ** 10
+        let b: float = 1.5 ** 2 ** 2
+        let c: str = 2 ** 3
+        let d = "2" ** 3
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch { .. }
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::InvalidOperation { op, .. } if op == "Power"
+        ));
+    }
+
+    #[test]
+    fn statistics_builtins_take_a_list_and_return_float() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let data = [2, 4, 4, 4, 5, 5, 7, 9]
+        let s: float = sum(data)
+        let m: float = mean(data)
+        let md: float = median(data)
+        let sd: float = stddev(data)
+        let mo: float = mode(data)
+        let wrong: str = mean(data)
+        sum()
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::Float
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 1,
+                found: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn list_utilities_return_lists() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let pairs: list = zip([1, 2, 3], ["a", "b"])
+        let indexed: list = enumerate(["x", "y"])
+        let parts: list = chunk([1, 2, 3, 4, 5], 2)
+        let wrong: str = chunk([1], 1)
+        zip([1])
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::String,
+                found: Type::List
+            }
+        ));
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 2,
+                found: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn dict_helpers_return_lists_and_a_merged_dict() {
+        let analyzer = analyze_source(
+            r#"
+class main
+    fun start()
+        let a = {"x": 1, "y": 2}
+        let keys: list = dict_keys(a)
+        let values: list = dict_values(a)
+        let items: list = dict_items(a)
+        let merged: dict = dict_merge(a, {"y": 20})
+        let wrong: list = dict_merge(a, a)
+        dict_keys("x")
+"#,
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::List,
+                found: Type::Dict
+            }
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::Dict,
+                found: Type::String
+            }
+        ));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_expression() {
+        let analyzer = analyze_source(
+            "class main\n    fun start()\n        let message = \"Hello\"\n        print(mesage)\n        let age: int = \"18\"\n",
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        let missing = &analyzer.errors[0];
+        assert!(matches!(&missing.kind, SemanticErrorKind::VariableNotFound(n) if n == "mesage"));
+        assert_eq!(
+            (missing.span.start.line, missing.span.start.column),
+            (4, 15)
+        );
+        assert_eq!(missing.span.end.column, 21);
+
+        let mismatch = &analyzer.errors[1];
+        assert!(matches!(mismatch.kind, SemanticErrorKind::TypeMismatch { .. }));
+        assert_eq!(
+            (mismatch.span.start.line, mismatch.span.start.column),
+            (5, 24)
+        );
+    }
+
+    #[test]
+    fn misspelled_names_suggest_the_closest_symbol() {
+        let analyzer = analyze_source(
+            "fun length(s: str) : int\n    return 0\nclass main\n    fun start()\n        print(lenght(\"abc\"))\n        print(qqqqqqqq)\n",
+        );
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert_eq!(
+            analyzer.errors[0].help.as_deref(),
+            Some("Did you mean `length`?")
+        );
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::VariableNotFound(n) if n == "qqqqqqqq"
+        ));
+        assert_eq!(analyzer.errors[1].help, None);
+        assert!(analyzer.errors[1].notes.is_empty());
+    }
+
+    #[test]
+    fn statements_after_return_are_reported_as_warnings() {
+        let analyzer = analyze_source(
+            "fun f(n: int) : int\n    if n > 0\n        return 1\n        print(\"a\")\n    return 0\n    print(\"b\")\nclass main\n    fun start()\n        print(f(1))\n",
+        );
+
+        assert!(analyzer.errors.is_empty(), "{:?}", analyzer.errors);
+        let lines: Vec<usize> = analyzer
+            .warnings
+            .iter()
+            .map(|w| {
+                assert!(w.is_warning());
+                w.span.start.line
+            })
+            .collect();
+        assert_eq!(lines, vec![6, 4]);
+    }
+
+    const SHAPES: &str = r#"
+class Point
+    let x: int = 0
+    fun moved(dx: int) : Point
+        let p: Point = new Point()
+        p.x = self.x + dx
+        return p
+
+class Line
+    let origin: Point = new Point()
+
+class main
+    fun start()
+        let line: Line = new Line()
+"#;
+
+    #[test]
+    fn chained_member_access_is_checked_against_the_class() {
+        let analyzer = analyze_source(&format!(
+            "{SHAPES}        let x: int = line.origin.moved(2).moved(3).x\n"
+        ));
+
+        assert!(analyzer.errors.is_empty(), "{:?}", analyzer.errors);
+    }
+
+    #[test]
+    fn unknown_members_and_bad_method_arguments_are_reported() {
+        let analyzer = analyze_source(&format!(
+            "{SHAPES}        print(line.origin.moed(1))\n        print(line.origin.moved(\"far\"))\n"
+        ));
+
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        let unknown = &analyzer.errors[0];
+        assert!(matches!(
+            &unknown.kind,
+            SemanticErrorKind::PropertyNotFound(name) if name == "moed"
+        ));
+        assert_eq!(unknown.help.as_deref(), Some("Did you mean `moved`?"));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::Int,
+                found: Type::String
+            }
+        ));
+    }
+
+    #[test]
+    fn optional_chaining_is_any_but_still_checks_members() {
+        let ok = analyze_source(&format!(
+            "{SHAPES}        let x: str = line?.origin?.x\n        let p: str = line?.origin.moved(1)\n"
+        ));
+        assert!(ok.errors.is_empty(), "{:?}", ok.errors);
+
+        let analyzer = analyze_source(&format!(
+            "{SHAPES}        print(line?.orign)\n        print(line.origin?.moved(\"far\"))\n"
+        ));
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::PropertyNotFound(name) if name == "orign"
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::Int,
+                found: Type::String
+            }
+        ));
+    }
+
+    const COUNTER: &str = r#"
+class Counter
+    let count: int = 0
+    fun step(by: int) : int
+        return self.count + by
+    fun helper() : int
+        return self.step(1)
+"#;
+
+    #[test]
+    fn self_calls_resolve_through_the_enclosing_class() {
+        let ok = analyze_source(&format!(
+            "{COUNTER}    fun twice() : int\n        return self.helper() + self.count\n"
+        ));
+        assert!(ok.errors.is_empty(), "{:?}", ok.errors);
+
+        let analyzer = analyze_source(&format!(
+            "{COUNTER}    fun bad()\n        print(self.helpr())\n        print(self.step())\n"
+        ));
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::PropertyNotFound(name) if name == "helpr"
+        ));
+        assert_eq!(
+            analyzer.errors[0].help.as_deref(),
+            Some("Did you mean `helper`?")
+        );
+        assert!(matches!(
+            analyzer.errors[1].kind,
+            SemanticErrorKind::WrongNumberOfArguments {
+                expected: 1,
+                found: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn self_is_only_bound_inside_methods() {
+        let analyzer = analyze_source(&format!(
+            "{COUNTER}    let start: int = self.count\nfun free() : int\n    return self.count\n"
+        ));
+        assert_eq!(analyzer.errors.len(), 2, "{:?}", analyzer.errors);
+        assert!(analyzer.errors.iter().all(
+            |e| matches!(&e.kind, SemanticErrorKind::VariableNotFound(name) if name == "self")
+        ));
+    }
+
+    #[test]
+    fn struct_literals_name_every_property_of_the_class() {
+        let ok = analyze_source(&format!(
+            "{SHAPES}        let l: Line = Line {{ origin: Point {{ x: 1 }} }}\n        let x: int = l.origin.x\n"
+        ));
+        assert!(ok.errors.is_empty(), "{:?}", ok.errors);
+
+        let analyzer = analyze_source(&format!(
+            "{SHAPES}        print(Point {{}})\n        print(Point {{ x: 1, y: 2 }})\n        print(Point {{ x: \"one\" }})\n"
+        ));
+        assert_eq!(analyzer.errors.len(), 3, "{:?}", analyzer.errors);
+        assert!(matches!(
+            &analyzer.errors[0].kind,
+            SemanticErrorKind::MissingStructFields { class, fields }
+                if class == "Point" && fields == "`x`"
+        ));
+        assert!(matches!(
+            &analyzer.errors[1].kind,
+            SemanticErrorKind::PropertyNotFound(name) if name == "y"
+        ));
+        assert!(matches!(
+            &analyzer.errors[2].kind,
+            SemanticErrorKind::TypeMismatch {
+                expected: Type::Int,
+                found: Type::String
+            }
+        ));
+    }
+
+    #[test]
+    fn property_initializers_must_match_their_declared_type() {
+        let analyzer = analyze_source(
+            "class Point\n    let x: int = \"zero\"\nclass main\n    fun start()\n        let p: Point = new Point()\n",
+        );
+
+        assert!(
+            analyzer.errors.iter().any(|e| matches!(
+                e.kind,
+                SemanticErrorKind::TypeMismatch {
+                    expected: Type::Int,
+                    ..
+                }
+            )),
+            "{:?}",
+            analyzer.errors
+        );
+    }
+
+    const POINT_WITH_INIT: &str = "class Point\n    let x: int = 0\n    let y: int = 0\n    fun init(x: int, y: int)\n        self.x = x\n        self.y = y\nclass main\n    fun start()\n";
+
+    #[test]
+    fn constructors_are_checked_against_init() {
+        let analyzer = analyze_source(&format!(
+            "{POINT_WITH_INIT}        let a: Point = new Point(1, 2)\n        let b: Point = Point(3, 4)\n        print(a.x + b.y)\n"
+        ));
+
+        assert!(analyzer.errors.is_empty(), "{:?}", analyzer.errors);
+    }
+
+    #[test]
+    fn constructor_argument_count_must_match_init() {
+        let analyzer = analyze_source(&format!(
+            "{POINT_WITH_INIT}        let a = new Point(1)\n        let b = Point(1, 2, 3)\n"
+        ));
+
+        let counts: Vec<(usize, usize)> = analyzer
+            .errors
+            .iter()
+            .filter_map(|e| match e.kind {
+                SemanticErrorKind::WrongNumberOfArguments { expected, found } => {
+                    Some((expected, found))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(counts, vec![(2, 1), (2, 3)], "{:?}", analyzer.errors);
+    }
+
+    #[test]
+    fn builtins_are_registered_from_the_shared_table() {
+        let analyzer = SemanticAnalyzer::new();
+        let mut expected = Vec::new();
+        for builtin in BUILTINS {
+            for name in builtin.names() {
+                let symbol = analyzer
+                    .symbol_table
+                    .lookup(&name)
+                    .unwrap_or_else(|| panic!("`{name}` should be registered"));
+                assert_eq!(
+                    symbol.symbol_type,
+                    Type::Function(builtin.params.to_vec(), Box::new(builtin.ret.clone())),
+                    "signature of `{name}`"
+                );
+                assert_eq!(symbol.is_variadic, builtin.variadic, "`{name}` variadic");
+                expected.push(name);
+            }
+        }
+        expected.sort();
+        expected.dedup();
+
+        let mut registered: Vec<String> = analyzer
+            .symbol_table
+            .visible_names()
+            .into_iter()
+            .filter(|name| {
+                analyzer.symbol_table.lookup(name).map(|s| &s.kind)
+                    == Some(&SemanticSymbolKind::Function)
+            })
+            .collect();
+        registered.sort();
+        assert_eq!(registered, expected);
+    }
 }
@@ -0,0 +1,182 @@
+//! Layout formatter for `.snask` sources.
+//!
+//! Only whitespace changes: blocks are re-indented with four spaces per
+//! level, trailing whitespace is dropped, runs of blank lines collapse to one
+//! and the file ends with a single `\n`. Tokens, comments and spacing inside a
+//! line are kept as written.
+
+use crate::parser::Parser;
+
+const INDENT_WIDTH: usize = 4;
+
+fn check_parses(source: &str) -> Result<(), String> {
+    Parser::new(source)
+        .and_then(|mut p| p.parse_program())
+        .map(|_| ())
+        .map_err(|e| e.message)
+}
+
+/// Indentation width the way the tokenizer counts it: a tab is four columns.
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .map(|c| if c == '\t' { INDENT_WIDTH } else { 1 })
+        .sum()
+}
+
+/// Brackets opened minus brackets closed on `line`, ignoring strings and
+/// `//` comments.
+fn bracket_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut chars = line.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '/' if !in_string && chars.peek() == Some(&'/') => break,
+            '(' | '[' | '{' if !in_string => delta += 1,
+            ')' | ']' | '}' if !in_string => delta -= 1,
+            _ => {}
+        }
+    }
+    delta
+}
+
+/// Block level of a line indented by `width` given the widths of the open
+/// blocks, without opening or closing any.
+fn level_within(stack: &[usize], width: usize) -> usize {
+    stack
+        .iter()
+        .filter(|w| **w <= width)
+        .count()
+        .saturating_sub(1)
+}
+
+struct Layout {
+    out: String,
+    /// Original indentation widths of the open blocks, outermost first.
+    stack: Vec<usize>,
+    /// A blank line is owed before the next line written.
+    blank: bool,
+}
+
+impl Layout {
+    fn push_line(&mut self, indent: usize, content: &str) {
+        if self.blank && !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.blank = false;
+        self.out.push_str(&" ".repeat(indent));
+        self.out.push_str(content);
+        self.out.push('\n');
+    }
+}
+
+/// Formats `source`. Sources that do not parse are returned as an error and
+/// never rewritten.
+pub fn format_snask(source: &str) -> Result<String, String> {
+    check_parses(source)?;
+
+    let mut layout = Layout {
+        out: String::new(),
+        stack: vec![0],
+        blank: false,
+    };
+    // Comment lines wait for the next statement: the tokenizer ignores their
+    // indentation, so they are placed by the code around them.
+    let mut comments: Vec<(usize, &str)> = Vec::new();
+    // Lines inside brackets opened on an earlier line move with that line.
+    let mut open_brackets = 0;
+    let mut shift: isize = 0;
+
+    for raw in source.lines() {
+        let line = raw.trim_end();
+        let content = line.trim_start();
+        let width = indent_width(line);
+
+        if open_brackets > 0 {
+            if content.is_empty() {
+                layout.out.push('\n');
+            } else {
+                let indent = (width as isize + shift).max(0) as usize;
+                layout.push_line(indent, content);
+            }
+            open_brackets += bracket_delta(content);
+            continue;
+        }
+        if content.is_empty() {
+            if comments.is_empty() {
+                layout.blank = true;
+            } else {
+                comments.push((0, ""));
+            }
+            continue;
+        }
+        if content.starts_with("//") {
+            comments.push((width, content));
+            continue;
+        }
+
+        // Comments indented past this line stay with the block above it.
+        let before: Vec<Option<usize>> = comments
+            .iter()
+            .map(|(w, text)| {
+                (*w > width && !text.is_empty()).then(|| level_within(&layout.stack, *w))
+            })
+            .collect();
+        if width > *layout.stack.last().unwrap_or(&0) {
+            layout.stack.push(width);
+        } else {
+            while layout.stack.len() > 1 && width < *layout.stack.last().unwrap_or(&0) {
+                layout.stack.pop();
+            }
+        }
+        let level = layout.stack.len() - 1;
+        for ((_, text), own) in comments.drain(..).zip(before) {
+            if text.is_empty() {
+                layout.blank = true;
+            } else {
+                layout.push_line(own.unwrap_or(level) * INDENT_WIDTH, text);
+            }
+        }
+
+        let indent = level * INDENT_WIDTH;
+        shift = indent as isize - width as isize;
+        layout.push_line(indent, content);
+        open_brackets = bracket_delta(content).max(0);
+    }
+    for (w, text) in comments {
+        if text.is_empty() {
+            layout.blank = true;
+        } else {
+            layout.push_line(level_within(&layout.stack, w) * INDENT_WIDTH, text);
+        }
+    }
+
+    check_parses(&layout.out)?;
+    Ok(layout.out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_snask;
+
+    #[test]
+    fn reindents_blocks_and_keeps_comments() {
+        let source = "\n\nfun add(a, b)\n  // sum\n  let total = a + b   \n  return total\n\n\n\n\
+// entry point\nclass main\n\tfun start()\n\t\tprint(add(1, 2))\n\t\t// done\n";
+        assert_eq!(
+            format_snask(source).unwrap(),
+            "fun add(a, b)\n    // sum\n    let total = a + b\n    return total\n\n\
+// entry point\nclass main\n    fun start()\n        print(add(1, 2))\n        // done\n"
+        );
+    }
+
+    #[test]
+    fn sources_that_do_not_parse_are_left_alone() {
+        assert!(format_snask("fun broken(\n").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! Conversion between SNIF values and JSON.
+//!
+//! Typed literals are already one-key objects in a parsed SNIF value
+//! (`@date"2026-01-01"` is `{"$date": "2026-01-01"}`), and JSON gets that same
+//! tagged object. Converting back yields the same value, so `format_snif`
+//! prints the literal again.
+
+use crate::snif_parser::SnifValue;
+use serde_json::{Map, Number, Value};
+
+/// Largest integer an `f64` holds exactly; whole numbers up to it are written
+/// as JSON integers.
+const MAX_EXACT_INT: f64 = 9_007_199_254_740_992.0;
+
+pub fn snif_to_json(v: &SnifValue) -> Result<Value, String> {
+    Ok(match v {
+        SnifValue::Null => Value::Null,
+        SnifValue::Bool(b) => Value::Bool(*b),
+        SnifValue::Number(n) if n.fract() == 0.0 && n.abs() <= MAX_EXACT_INT => {
+            Value::Number(Number::from(*n as i64))
+        }
+        SnifValue::Number(n) => Number::from_f64(*n)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{n} has no JSON representation"))?,
+        SnifValue::String(s) => Value::String(s.clone()),
+        SnifValue::Array(items) => {
+            Value::Array(items.iter().map(snif_to_json).collect::<Result<_, _>>()?)
+        }
+        SnifValue::Object(o) => Value::Object(
+            o.iter()
+                .map(|(k, item)| snif_to_json(item).map(|item| (k.clone(), item)))
+                .collect::<Result<Map<_, _>, _>>()?,
+        ),
+    })
+}
+
+pub fn json_to_snif(v: &Value) -> SnifValue {
+    match v {
+        Value::Null => SnifValue::Null,
+        Value::Bool(b) => SnifValue::Bool(*b),
+        Value::Number(n) => SnifValue::Number(n.as_f64().unwrap_or_default()),
+        Value::String(s) => SnifValue::String(s.clone()),
+        Value::Array(items) => SnifValue::Array(items.iter().map(json_to_snif).collect()),
+        Value::Object(o) => SnifValue::Object(
+            o.iter()
+                .map(|(k, item)| (k.clone(), json_to_snif(item)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_to_snif, snif_to_json};
+    use crate::snif_fmt::format_snif;
+    use crate::snif_parser::parse_snif;
+    use serde_json::json;
+
+    #[test]
+    fn typed_literals_become_tagged_objects() {
+        let v =
+            parse_snif(r#"{released: @date"2026-01-01", price: @dec"9.90", build: 3}"#).unwrap();
+        let j = snif_to_json(&v).unwrap();
+        assert_eq!(
+            j,
+            json!({"released": {"$date": "2026-01-01"}, "price": {"$dec": "9.90"}, "build": 3})
+        );
+        let back = format_snif(&json_to_snif(&j));
+        assert!(back.contains(r#"released: @date"2026-01-01""#), "{back}");
+        assert!(back.contains(r#"price: @dec"9.90""#), "{back}");
+    }
+
+    #[test]
+    fn plain_objects_round_trip_through_json() {
+        let src = r#"{
+    package: {name: "app", version: "0.1.0", entry: "main.snask"},
+    build: {opt_level: 2, ratio: 0.5, strip: true, target: null},
+    scripts: {test: "snask test", lint: ["snask", "check"]},
+}"#;
+        let v = parse_snif(src).unwrap();
+        let j = snif_to_json(&v).unwrap();
+        let back = json_to_snif(&j);
+        assert_eq!(back, v);
+        assert_eq!(format_snif(&back), format_snif(&v));
+        assert_eq!(snif_to_json(&back).unwrap(), j);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::snif_parser::SnifValue;
 use crate::sps::is_known_build_profile;
+use serde_json::{json, Value};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -72,9 +73,13 @@ pub fn validate_snask_manifest(v: &SnifValue) -> Vec<SnifSchemaError> {
         return errs;
     };
 
+    validate_workspace(root, &mut errs);
+
     // package
     let pkg = match root.get("package") {
         Some(v) => v,
+        // A workspace root does not need to be a package itself.
+        None if root.contains_key("workspace") => return errs,
         None => {
             errs.push(SnifSchemaError::new(
                 "$.package",
@@ -139,6 +144,17 @@ pub fn validate_snask_manifest(v: &SnifValue) -> Vec<SnifSchemaError> {
         )),
     }
 
+    if pkg_o.contains_key("maintainer") && get_str(pkg_o, "maintainer").is_none() {
+        errs.push(SnifSchemaError::new(
+            "$.package.maintainer",
+            "package.maintainer must be a string (e.g. 'Jane Doe <jane@example.com>').",
+        ));
+    }
+
+    if pkg_o.contains_key("lib") && !matches!(pkg_o.get("lib"), Some(SnifValue::Bool(_))) {
+        errs.push(SnifSchemaError::new("$.package.lib", "package.lib must be a boolean."));
+    }
+
     // dependencies
     if let Some(deps_v) = root.get("dependencies") {
         if let Some(deps) = as_obj(deps_v) {
@@ -215,13 +231,19 @@ pub fn validate_snask_manifest(v: &SnifValue) -> Vec<SnifSchemaError> {
                 ));
             }
 
+            // `llc -O` only takes 0..=3.
             if let Some(n) = get_num(build, "opt_level") {
-                if !(0.0..=3.0).contains(&n) {
+                if !(0.0..=3.0).contains(&n) || n.fract() != 0.0 {
                     errs.push(SnifSchemaError::new(
                         "$.build.opt_level",
-                        "opt_level must be in range 0..3.",
+                        "opt_level must be an integer in range 0..3.",
                     ));
                 }
+            } else if build.contains_key("opt_level") {
+                errs.push(SnifSchemaError::new(
+                    "$.build.opt_level",
+                    "opt_level must be a number.",
+                ));
             }
             if build.contains_key("debug") && get_bool(build, "debug").is_none() {
                 errs.push(SnifSchemaError::new(
@@ -259,19 +281,55 @@ pub fn validate_snask_manifest(v: &SnifValue) -> Vec<SnifSchemaError> {
     errs
 }
 
+fn validate_workspace(root: &BTreeMap<String, SnifValue>, errs: &mut Vec<SnifSchemaError>) {
+    let Some(ws_v) = root.get("workspace") else {
+        return;
+    };
+    let Some(ws) = as_obj(ws_v) else {
+        errs.push(SnifSchemaError::new(
+            "$.workspace",
+            "'workspace' must be an object.",
+        ));
+        return;
+    };
+    match ws.get("members") {
+        Some(SnifValue::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                if !matches!(item, SnifValue::String(_)) {
+                    errs.push(SnifSchemaError::new(
+                        format!("$.workspace.members[{i}]"),
+                        "Workspace member must be a string (directory path).",
+                    ));
+                }
+            }
+        }
+        Some(_) => errs.push(SnifSchemaError::new(
+            "$.workspace.members",
+            "workspace.members must be an array of strings.",
+        )),
+        None => errs.push(SnifSchemaError::new(
+            "$.workspace.members",
+            "Missing required key 'workspace.members'.",
+        )),
+    }
+}
+
 pub fn snask_manifest_schema_md() -> String {
     let md = r#"# snask.snif schema (v1)
 
 ## Top-level
-- `package` *(object, required)*
+- `package` *(object, required unless `workspace` is present)*
 - `dependencies` *(object, optional)*
 - `build` *(object, optional)*
 - `scripts` *(object, optional)*
+- `workspace` *(object, optional)*
 
 ## package
 - `name` *(string, required)*: `[a-zA-Z0-9_-]+`
 - `version` *(string, required)*: semver-ish `x.y.z`
 - `entry` *(string, required)*: ends with `.snask`
+- `maintainer` *(string, optional)*: `Name <email>` for the `.deb` control file
+- `lib` *(bool, optional)*: a library; `snask build` emits a module object and needs no `class main`
 
 ## dependencies
 Map: `name -> version|string | null`
@@ -285,14 +343,107 @@ Map: `name -> version|string | null`
 - `lto` *(string, optional)*: `off` | `thin`
 - `opt` *(string, optional)*: `O2` | `O3` | `Os` | `Oz`
 - `debug` *(bool, optional)*
+- `defines` *(object, optional)*: `KEY -> bool|string|number`, read by `cfg("KEY")`; `--define KEY=VAL` overrides an entry
 
 ## scripts
 Map: `name -> string`
+- `prebuild` / `postbuild` run around `snask build`, `prerun` / `postrun` around `snask run`; a failing hook stops the command (`--no-hooks` skips them)
+
+## workspace
+- `members` *(array of strings, required)*: member directories, each with its own `snask.snif`
+- A dependency named after another member resolves to that member's source, not the registry.
 
 "#;
     md.to_string()
 }
 
+/// JSON Schema (draft 2020-12) for `snask.snif`, matching
+/// `validate_snask_manifest`. Editors can validate manifests with it after
+/// `snask snif to-json`, or directly on JSON-compatible SNIF.
+pub fn snask_manifest_json_schema() -> Value {
+    let build = json!({
+        "type": "object",
+        "properties": {
+            "opt_level": { "type": "integer", "minimum": 0, "maximum": 3 },
+            "profile": {
+                "enum": [
+                    "humane", "systems", "baremetal", "dev", "release", "release-size", "tiny",
+                    "extreme"
+                ]
+            },
+            "strip": { "type": "boolean" },
+            "lto": { "enum": ["off", "thin"] },
+            "opt": { "enum": ["O2", "O3", "Os", "Oz"] },
+            "debug": { "type": "boolean" },
+            "features": {
+                "type": "object",
+                "additionalProperties": { "type": ["boolean", "string", "number"] }
+            },
+            "defines": {
+                "type": "object",
+                "additionalProperties": { "type": ["boolean", "string", "number"] }
+            }
+        }
+    });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "https://snask.dev/schema/snask.snif.json",
+        "title": "snask.snif",
+        "description": "Snask package manifest (v1)",
+        "type": "object",
+        "anyOf": [{ "required": ["package"] }, { "required": ["workspace"] }],
+        "properties": {
+            "package": {
+                "type": "object",
+                "required": ["name", "version", "entry"],
+                "properties": {
+                    "name": { "type": "string", "pattern": "^[a-zA-Z0-9_-]+$" },
+                    "version": { "type": "string", "pattern": "^[0-9]+\\.[0-9]+\\.[0-9]+$" },
+                    "entry": { "type": "string", "pattern": "\\.snask$" },
+                    "maintainer": { "type": "string" },
+                    "lib": { "type": "boolean" }
+                }
+            },
+            "dependencies": {
+                "type": "object",
+                "additionalProperties": { "type": ["string", "null"] }
+            },
+            "build": { "$ref": "#/$defs/build" },
+            "profile": {
+                "type": "object",
+                "properties": {
+                    "release": { "$ref": "#/$defs/build" },
+                    "dev": { "$ref": "#/$defs/build" }
+                }
+            },
+            "scripts": {
+                "type": "object",
+                "additionalProperties": { "type": "string" }
+            },
+            "app": {
+                "type": "object",
+                "required": ["id", "name"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "comment": { "type": "string" },
+                    "icon": { "type": "string" },
+                    "terminal": { "type": "boolean" },
+                    "categories": { "type": "string" }
+                }
+            },
+            "workspace": {
+                "type": "object",
+                "required": ["members"],
+                "properties": {
+                    "members": { "type": "array", "items": { "type": "string" } }
+                }
+            }
+        },
+        "$defs": { "build": build }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +466,33 @@ mod tests {
         assert!(errs.iter().any(|e| e.path == "$.build.opt_level"));
     }
 
+    #[test]
+    fn schema_opt_level_must_be_an_integer_from_0_to_3() {
+        let manifest = |opt: &str| {
+            format!(
+                "{{package:{{name:\"x\",version:\"0.1.0\",entry:\"main.snask\"}},build:{{opt_level:{opt}}}}}"
+            )
+        };
+        let ok = manifest("2");
+        assert!(validate_snask_manifest(&parse_snif(&ok).unwrap()).is_empty());
+
+        for bad in ["5", "1.5", "-1", "\"2\""] {
+            let src = manifest(bad);
+            let errs = validate_snask_manifest(&parse_snif(&src).unwrap());
+            assert_eq!(errs.len(), 1, "opt_level: {bad}");
+            assert_eq!(errs[0].path, "$.build.opt_level");
+        }
+
+        let src = manifest("5");
+        let errs = validate_snask_manifest(&parse_snif(&src).unwrap());
+        let rendered =
+            crate::snif_tooling::render_snif_schema_diagnostic("snask.snif", &src, &errs);
+        assert!(
+            rendered.contains("$.build.opt_level: opt_level must be an integer in range 0..3."),
+            "{rendered}"
+        );
+    }
+
     #[test]
     fn schema_build_profile_validation() {
         for profile in ["humane", "systems", "baremetal", "tiny"] {
@@ -335,4 +513,115 @@ mod tests {
         let errs = validate_snask_manifest(&v);
         assert!(errs.iter().any(|e| e.path == "$.build.profile"));
     }
+
+    #[test]
+    fn schema_workspace_root_needs_no_package() {
+        let v = parse_snif("{workspace:{members:[\"a\",\"b\"]}}").unwrap();
+        assert!(validate_snask_manifest(&v).is_empty());
+
+        let v = parse_snif("{workspace:{members:[\"a\",1]}}").unwrap();
+        let errs = validate_snask_manifest(&v);
+        assert!(errs.iter().any(|e| e.path == "$.workspace.members[1]"));
+    }
+
+    /// Checks `value` against the subset of JSON Schema the manifest schema
+    /// uses. `pattern` is skipped: the crate has no regex engine.
+    fn schema_errors(schema: &Value, root: &Value, value: &Value, path: &str) -> Vec<String> {
+        if let Some(target) = schema["$ref"].as_str() {
+            let name = target.trim_start_matches("#/$defs/");
+            return schema_errors(&root["$defs"][name], root, value, path);
+        }
+        let mut errs = Vec::new();
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        let allowed: Vec<&str> = match &schema["type"] {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![kind],
+        };
+        if !allowed.contains(&kind) && !(kind == "integer" && allowed.contains(&"number")) {
+            errs.push(format!("{path}: expected {allowed:?}, got {kind}"));
+            return errs;
+        }
+        if let Some(options) = schema["enum"].as_array() {
+            if !options.contains(value) {
+                errs.push(format!("{path}: not one of {options:?}"));
+            }
+        }
+        if let Some(n) = value.as_f64() {
+            if schema["minimum"].as_f64().is_some_and(|min| n < min)
+                || schema["maximum"].as_f64().is_some_and(|max| n > max)
+            {
+                errs.push(format!("{path}: out of range"));
+            }
+        }
+        if let Some(any) = schema["anyOf"].as_array() {
+            let matched = any
+                .iter()
+                .any(|s| schema_errors(s, root, value, path).is_empty());
+            if !matched {
+                errs.push(format!("{path}: matches none of anyOf"));
+            }
+        }
+        if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+            for (i, item) in items.iter().enumerate() {
+                let at = format!("{path}[{i}]");
+                errs.extend(schema_errors(item_schema, root, item, &at));
+            }
+        }
+        if let Value::Object(o) = value {
+            for key in schema["required"].as_array().into_iter().flatten() {
+                let key = key.as_str().unwrap();
+                if !o.contains_key(key) {
+                    errs.push(format!("{path}.{key}: missing"));
+                }
+            }
+            for (k, v) in o {
+                let sub = match schema["properties"].get(k) {
+                    Some(sub) => sub,
+                    None if schema["additionalProperties"].is_object() => {
+                        &schema["additionalProperties"]
+                    }
+                    None => continue,
+                };
+                errs.extend(schema_errors(sub, root, v, &format!("{path}.{k}")));
+            }
+        }
+        errs
+    }
+
+    #[test]
+    fn json_schema_accepts_a_manifest_and_rejects_a_missing_name() {
+        let schema = snask_manifest_json_schema();
+        let check = |src: &str| {
+            let v = crate::snif_json::snif_to_json(&parse_snif(src).unwrap()).unwrap();
+            schema_errors(&schema, &schema, &v, "$")
+        };
+
+        let good = r#"{
+            package: {name: "app", version: "0.1.0", entry: "main.snask", lib: false},
+            dependencies: {json: "0.1.0", http: null},
+            build: {opt_level: 2, profile: "release-size", lto: "thin"},
+            profile: {release: {opt_level: 3, strip: true}},
+            app: {id: "dev.snask.app", name: "App", terminal: false},
+            scripts: {test: "snask test"},
+        }"#;
+        assert_eq!(check(good), Vec::<String>::new());
+        assert!(validate_snask_manifest(&parse_snif(good).unwrap()).is_empty());
+
+        let errs = check(r#"{package: {version: "0.1.0", entry: "main.snask"}}"#);
+        assert_eq!(errs, vec!["$.package.name: missing".to_string()]);
+        let too_high =
+            r#"{package: {name: "a", version: "0.1.0", entry: "m.snask"}, build: {opt_level: 9}}"#;
+        assert!(!check(too_high).is_empty());
+        assert!(!check("{}").is_empty());
+        assert!(check(r#"{workspace: {members: ["a", "b"]}}"#).is_empty());
+    }
 }
@@ -1,7 +1,10 @@
 use crate::diagnostics::{Annotation, Diagnostic, DiagnosticBag};
 use crate::snif_fmt::format_snif;
+use crate::snif_json::{json_to_snif, snif_to_json};
 use crate::snif_parser::{parse_snif, SnifParseError, SnifValue};
-use crate::snif_schema::{snask_manifest_schema_md, validate_snask_manifest, SnifSchemaError};
+use crate::snif_schema::{
+    snask_manifest_json_schema, snask_manifest_schema_md, validate_snask_manifest, SnifSchemaError,
+};
 use crate::span::{Position, Span};
 use sha2::{Digest, Sha256};
 use std::fs;
@@ -116,3 +119,378 @@ pub fn validate_snask_snif(src: &str) -> Result<Vec<SnifSchemaError>, SnifParseE
 pub fn schema_md() -> String {
     snask_manifest_schema_md()
 }
+
+pub fn schema_json() -> String {
+    let schema = snask_manifest_json_schema();
+    serde_json::to_string_pretty(&schema).unwrap_or_default() + "\n"
+}
+
+/// One difference between two SNIF documents. Paths use the `$.key[0]` form
+/// of schema errors; values are rendered canonically on one line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnifChange {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        from: String,
+        to: String,
+    },
+    /// Same path, different kind of value (e.g. a string became an array).
+    KindChanged {
+        path: String,
+        from_kind: &'static str,
+        to_kind: &'static str,
+        from: String,
+        to: String,
+    },
+}
+
+fn snif_kind(v: &SnifValue) -> &'static str {
+    match v {
+        SnifValue::Null => "null",
+        SnifValue::Bool(_) => "bool",
+        SnifValue::Number(_) => "number",
+        SnifValue::String(_) => "string",
+        SnifValue::Array(_) => "array",
+        SnifValue::Object(o) if o.len() == 1 => match o.keys().next().map(String::as_str) {
+            Some("$date") => "@date",
+            Some("$dec") => "@dec",
+            Some("$bin") => "@bin",
+            Some("$enum") => "@enum",
+            _ => "object",
+        },
+        SnifValue::Object(_) => "object",
+    }
+}
+
+/// `format_snif` output folded onto one line.
+fn inline_snif(v: &SnifValue) -> String {
+    format_snif(v)
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Semantic diff of two parsed documents: objects are compared key by key and
+/// arrays index by index, so formatting and key order never show up.
+pub fn snif_diff(before: &SnifValue, after: &SnifValue) -> Vec<SnifChange> {
+    let mut out = Vec::new();
+    diff_values("$", before, after, &mut out);
+    out
+}
+
+fn diff_values(path: &str, before: &SnifValue, after: &SnifValue, out: &mut Vec<SnifChange>) {
+    let (from_kind, to_kind) = (snif_kind(before), snif_kind(after));
+    match (before, after) {
+        _ if from_kind != to_kind => out.push(SnifChange::KindChanged {
+            path: path.to_string(),
+            from_kind,
+            to_kind,
+            from: inline_snif(before),
+            to: inline_snif(after),
+        }),
+        (SnifValue::Object(a), SnifValue::Object(b)) if from_kind == "object" => {
+            for (k, v) in a {
+                let child = format!("{path}.{k}");
+                match b.get(k) {
+                    Some(w) => diff_values(&child, v, w, out),
+                    None => out.push(SnifChange::Removed {
+                        path: child,
+                        value: inline_snif(v),
+                    }),
+                }
+            }
+            for (k, w) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                out.push(SnifChange::Added {
+                    path: format!("{path}.{k}"),
+                    value: inline_snif(w),
+                });
+            }
+        }
+        (SnifValue::Array(a), SnifValue::Array(b)) => {
+            for (i, v) in a.iter().enumerate() {
+                let child = format!("{path}[{i}]");
+                match b.get(i) {
+                    Some(w) => diff_values(&child, v, w, out),
+                    None => out.push(SnifChange::Removed {
+                        path: child,
+                        value: inline_snif(v),
+                    }),
+                }
+            }
+            for (i, w) in b.iter().enumerate().skip(a.len()) {
+                out.push(SnifChange::Added {
+                    path: format!("{path}[{i}]"),
+                    value: inline_snif(w),
+                });
+            }
+        }
+        _ => {
+            let (from, to) = (inline_snif(before), inline_snif(after));
+            if from != to {
+                out.push(SnifChange::Changed {
+                    path: path.to_string(),
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+}
+
+pub fn render_snif_diff(changes: &[SnifChange]) -> String {
+    if changes.is_empty() {
+        return "No changes.\n".to_string();
+    }
+    let mut out = String::new();
+    for c in changes {
+        let line = match c {
+            SnifChange::Added { path, value } => format!("+ {path}: {value}"),
+            SnifChange::Removed { path, value } => format!("- {path}: {value}"),
+            SnifChange::Changed { path, from, to } => format!("~ {path}: {from} -> {to}"),
+            SnifChange::KindChanged {
+                path,
+                from_kind,
+                to_kind,
+                from,
+                to,
+            } => format!("~ {path}: {from_kind} became {to_kind}: {from} -> {to}"),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// `snask snif diff <a> <b>`.
+pub fn snif_diff_files(a: &Path, b: &Path) -> Result<String, String> {
+    let parse = |path: &Path| -> Result<SnifValue, String> {
+        let src = read_snif_file(path)?;
+        parse_snif(&src)
+            .map_err(|e| render_snif_parse_diagnostic(&path.display().to_string(), &src, &e))
+    };
+    let (before, after) = (parse(a)?, parse(b)?);
+    Ok(render_snif_diff(&snif_diff(&before, &after)))
+}
+
+/// `snask snif to-json <file>`.
+pub fn snif_file_to_json(path: &Path) -> Result<String, String> {
+    let src = read_snif_file(path)?;
+    let v = parse_snif(&src)
+        .map_err(|e| render_snif_parse_diagnostic(&path.display().to_string(), &src, &e))?;
+    let json = snif_to_json(&v).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::to_string_pretty(&json)
+        .map(|s| s + "\n")
+        .map_err(|e| e.to_string())
+}
+
+/// `snask snif from-json <file>`: the JSON document as canonical SNIF.
+pub fn json_file_to_snif(path: &Path) -> Result<String, String> {
+    let src = read_snif_file(path)?;
+    let json: serde_json::Value = serde_json::from_str(&src)
+        .map_err(|e| format!("Invalid JSON in {}: {}", path.display(), e))?;
+    Ok(format_snif(&json_to_snif(&json)))
+}
+
+/// Expands `${VAR}` and `${VAR:-default}` in every string value against the
+/// process environment. Only the manifest loader calls this: `snask snif fmt`
+/// works on the parsed source, so the literal `${...}` text is preserved.
+pub fn expand_env_vars(v: &SnifValue) -> Result<SnifValue, String> {
+    expand_env_vars_with(v, "$", &|name| std::env::var(name).ok())
+}
+
+fn expand_env_vars_with(
+    v: &SnifValue,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<SnifValue, String> {
+    Ok(match v {
+        SnifValue::String(s) => SnifValue::String(
+            expand_env_in_str(s, lookup).map_err(|e| format!("{path}: {e}"))?,
+        ),
+        SnifValue::Array(items) => SnifValue::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| expand_env_vars_with(item, &format!("{path}[{i}]"), lookup))
+                .collect::<Result<_, _>>()?,
+        ),
+        SnifValue::Object(o) => SnifValue::Object(
+            o.iter()
+                .map(|(k, item)| {
+                    expand_env_vars_with(item, &format!("{path}.{k}"), lookup)
+                        .map(|item| (k.clone(), item))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Expands one string. `$${` is an escape for a literal `${`; a `$` that is
+/// not followed by `{` is left alone so shell snippets like `$HOME` still work.
+pub fn expand_env_in_str(
+    s: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(escaped) = after.strip_prefix("${") {
+            out.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body_start) = after.strip_prefix('{') else {
+            out.push('$');
+            rest = after;
+            continue;
+        };
+        let close = body_start
+            .find('}')
+            .ok_or_else(|| format!("unterminated `${{` in \"{s}\""))?;
+        let body = &body_start[..close];
+        let (name, default) = match body.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (body, None),
+        };
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("invalid environment variable name `{name}`"));
+        }
+        // Like the shell, `:-` also falls back when the variable is empty.
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => out.push_str(default),
+            (Some(value), _) => out.push_str(&value),
+            (None, Some(default)) => out.push_str(default),
+            (None, None) => {
+                return Err(format!(
+                    "environment variable `{name}` is not set (use `${{{name}:-default}}` to make it optional)"
+                ))
+            }
+        }
+        rest = &body_start[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_env_in_str, format_snif_source, render_snif_diff, snif_diff, SnifChange};
+    use crate::snif_parser::parse_snif;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/snask".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn env_vars_expand_present_and_default_values() {
+        assert_eq!(
+            expand_env_in_str("${HOME}/bin", &lookup).unwrap(),
+            "/home/snask/bin"
+        );
+        assert_eq!(
+            expand_env_in_str("ci=${CI:-false} home=${HOME:-x}", &lookup).unwrap(),
+            "ci=false home=/home/snask"
+        );
+        assert_eq!(
+            expand_env_in_str("[${EMPTY}] ${EMPTY:-fallback}", &lookup).unwrap(),
+            "[] fallback"
+        );
+        assert_eq!(
+            expand_env_in_str("echo $HOME $${HOME}", &lookup).unwrap(),
+            "echo $HOME ${HOME}"
+        );
+    }
+
+    #[test]
+    fn env_vars_missing_without_default_is_an_error() {
+        let err = expand_env_in_str("${CI}", &lookup).unwrap_err();
+        assert!(err.contains("`CI` is not set"), "{err}");
+        assert!(expand_env_in_str("${HOME", &lookup).is_err());
+        assert!(expand_env_in_str("${1BAD}", &lookup).is_err());
+    }
+
+    #[test]
+    fn formatter_keeps_env_var_references_literal() {
+        let out = format_snif_source(r#"{ scripts: { run: "${HOME}/run ${CI:-0}" } }"#).unwrap();
+        assert!(out.contains("${HOME}/run ${CI:-0}"), "{out}");
+    }
+
+    #[test]
+    fn diff_ignores_formatting_and_key_order() {
+        let a = parse_snif(
+            "{package: {name: \"app\", version: \"0.1.0\"}, dependencies: {json: \"1.0.0\"}}",
+        )
+        .unwrap();
+        let b = parse_snif(
+            "// manifest\n{\n  dependencies: { json: '1.0.0' },\n  package: {\n    version: \"0.1.0\",\n    name: \"app\",\n  },\n}\n",
+        )
+        .unwrap();
+        assert!(snif_diff(&a, &b).is_empty());
+        assert_eq!(render_snif_diff(&[]), "No changes.\n");
+    }
+
+    #[test]
+    fn diff_reports_changed_added_removed_and_retyped_values() {
+        let a = parse_snif(
+            "{package: {name: \"app\", version: \"0.1.0\"}, build: {flags: \"-O2\"}, old: true}",
+        )
+        .unwrap();
+        let b = parse_snif(
+            "{package: {name: \"app\", version: \"0.2.0\"}, build: {flags: [\"-O2\", \"-g\"]}, new: 1}",
+        )
+        .unwrap();
+        let changes = snif_diff(&a, &b);
+        assert_eq!(
+            changes,
+            vec![
+                SnifChange::KindChanged {
+                    path: "$.build.flags".to_string(),
+                    from_kind: "string",
+                    to_kind: "array",
+                    from: "\"-O2\"".to_string(),
+                    to: "[\"-O2\", \"-g\"]".to_string(),
+                },
+                SnifChange::Removed {
+                    path: "$.old".to_string(),
+                    value: "true".to_string(),
+                },
+                SnifChange::Changed {
+                    path: "$.package.version".to_string(),
+                    from: "\"0.1.0\"".to_string(),
+                    to: "\"0.2.0\"".to_string(),
+                },
+                SnifChange::Added {
+                    path: "$.new".to_string(),
+                    value: "1".to_string(),
+                },
+            ]
+        );
+        let rendered = render_snif_diff(&changes);
+        assert!(
+            rendered.contains("~ $.build.flags: string became array: \"-O2\" -> [\"-O2\", \"-g\"]"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("~ $.package.version: \"0.1.0\" -> \"0.2.0\""));
+    }
+}
@@ -5,6 +5,8 @@ use std::fmt;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Position {
     pub line: usize,
+    /// 1-based, counted in UTF-16 code units like LSP positions: `é` takes
+    /// one column and `😀` two.
     pub column: usize,
     pub offset: usize, // Offset absoluto no arquivo
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -19,6 +20,15 @@ pub struct SpsManifest {
     pub profile: ProfileSection,
     #[serde(default)]
     pub app: Option<AppSection>,
+    #[serde(default)]
+    pub workspace: Option<WorkspaceSection>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct WorkspaceSection {
+    /// Member directories, relative to the workspace root.
+    #[serde(default)]
+    pub members: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -27,6 +37,12 @@ pub struct PackageSection {
     pub version: String,
     #[serde(default = "default_entry")]
     pub entry: String,
+    /// `Name <email>` written to the `.deb` control file by `snask dist --deb`.
+    #[serde(default)]
+    pub maintainer: Option<String>,
+    /// A library: `snask build` makes a module object and needs no `class main`.
+    #[serde(default)]
+    pub lib: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -43,6 +59,9 @@ pub struct BuildSection {
     pub opt_level: u8,
     #[serde(default)]
     pub features: BTreeMap<String, SnifFeatureValue>,
+    /// Values read by `cfg("KEY")`; `--define KEY=VAL` overrides them.
+    #[serde(default)]
+    pub defines: BTreeMap<String, String>,
     /// Optional build profile: humane|systems|baremetal|dev|release|release-size|tiny|extreme
     #[serde(default)]
     pub profile: Option<String>,
@@ -55,6 +74,9 @@ pub struct BuildSection {
     /// Optional size-oriented opt flag: O2|O3|Os|Oz
     #[serde(default)]
     pub opt: Option<String>,
+    /// Optional limit on nested imports; `--max-import-depth` overrides it.
+    #[serde(default)]
+    pub max_import_depth: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -162,6 +184,50 @@ pub fn is_known_build_profile(profile: &str) -> bool {
     )
 }
 
+/// Runs a `scripts` entry with `sh -lc` from `dir`. `args` are passed as the
+/// shell's positional parameters and appended to the command as `"$@"`, so
+/// they reach it unsplit and unexpanded.
+pub fn run_script(dir: &Path, name: &str, cmdline: &str, args: &[String]) -> Result<(), String> {
+    let cmdline = if args.is_empty() {
+        cmdline.to_string()
+    } else {
+        format!("{} \"$@\"", cmdline)
+    };
+    let status = Command::new("sh")
+        .arg("-lc")
+        .arg(cmdline)
+        .arg(name)
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("Script '{}' failed.", name));
+    }
+    Ok(())
+}
+
+/// Runs `action` between the `pre<stage>` and `post<stage>` scripts of `m`
+/// (`prebuild`/`postbuild` around `snask build`, `prerun`/`postrun` around
+/// `snask run`). A failing hook stops everything after it. `None` skips the
+/// hooks, as `--no-hooks` does.
+pub fn with_hooks<T>(
+    m: Option<&SpsManifest>,
+    dir: &Path,
+    stage: &str,
+    action: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let hook = |name: String| match m.and_then(|m| m.scripts.get(&name)) {
+        Some(cmdline) => run_script(dir, &name, cmdline, &[])
+            .map_err(|e| format!("{} (skip lifecycle scripts with --no-hooks)", e)),
+        None => Ok(()),
+    };
+    hook(format!("pre{}", stage))?;
+    let out = action()?;
+    hook(format!("post{}", stage))?;
+    Ok(out)
+}
+
 pub fn find_manifest(start_dir: &Path) -> Option<PathBuf> {
     // MVP: procura apenas no diretório atual (sem subir árvore)
     let snif = start_dir.join("snask.snif");
@@ -609,9 +675,13 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
         msg.push_str("\nHow to fix:\n- Run `snask init` to generate a valid `snask.snif` template.\n- Or compare your file against the example in `docs/SPS.md`.\n");
         msg
     })?;
+    let root = crate::snif_tooling::expand_env_vars(&root)?;
     let root_obj = snif_get_obj(&root, "root")?;
 
     let pkg_v = root_obj.get("package").ok_or_else(|| {
+        if root_obj.contains_key("workspace") {
+            return "This snask.snif is a workspace root (it has no package section).\n\nHow to fix:\n- Build every member: `snask build --workspace`\n- Or run the command inside a member directory.\n".to_string();
+        }
         "Missing required section: package\n\nHow to fix:\n- Add:\n  package: { name: \"app\", version: \"0.1.0\", entry: \"main.snask\", },\n".to_string()
     })?;
     let pkg = snif_get_obj(pkg_v, "package")?;
@@ -619,6 +689,16 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
         name: snif_get_str(pkg, "name", None)?,
         version: snif_get_str(pkg, "version", None)?,
         entry: snif_get_str(pkg, "entry", Some(default_entry()))?,
+        maintainer: match pkg.get("maintainer") {
+            None => None,
+            Some(SnifValue::String(s)) => Some(s.clone()),
+            Some(_) => return Err("Expected string for field: maintainer".to_string()),
+        },
+        lib: match pkg.get("lib") {
+            None => false,
+            Some(SnifValue::Bool(b)) => *b,
+            Some(_) => return Err("Expected bool for package.lib".to_string()),
+        },
     };
 
     let empty_build = SnifValue::Object(BTreeMap::new());
@@ -641,9 +721,32 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
             out
         }
     };
+    let defines = match build_obj.get("defines") {
+        None => BTreeMap::new(),
+        Some(d) => {
+            let d_obj = snif_get_obj(d, "build.defines")?;
+            let mut out = BTreeMap::new();
+            for (k, v) in d_obj {
+                let value = match v {
+                    SnifValue::Bool(b) => b.to_string(),
+                    SnifValue::String(s) => s.clone(),
+                    SnifValue::Number(n) => n.to_string(),
+                    _ => {
+                        return Err(format!(
+                            "Invalid value type in build.defines.{}. Must be a string, number, or boolean.",
+                            k
+                        ))
+                    }
+                };
+                out.insert(k.clone(), value);
+            }
+            out
+        }
+    };
     let build = BuildSection {
         opt_level: snif_get_u8(build_obj, "opt_level", default_opt_level())?,
         features,
+        defines,
         profile: match build_obj.get("profile") {
             Some(SnifValue::String(s)) => Some(s.clone()),
             Some(_) => return Err("Expected string in build.profile".to_string()),
@@ -664,6 +767,13 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
             Some(_) => return Err("Expected string in build.opt".to_string()),
             None => None,
         },
+        max_import_depth: match build_obj.get("max_import_depth") {
+            Some(SnifValue::Number(n)) if *n >= 1.0 && n.fract() == 0.0 => Some(*n as usize),
+            Some(_) => {
+                return Err("Expected a positive whole number in build.max_import_depth".to_string())
+            }
+            None => None,
+        },
     };
 
     let dependencies = snif_get_map(root_obj, "dependencies")?;
@@ -712,6 +822,8 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
         }
     };
 
+    let workspace = workspace_from_snif(root_obj)?;
+
     Ok(SpsManifest {
         package,
         dependencies,
@@ -719,14 +831,219 @@ fn manifest_from_snif(src: &str) -> Result<SpsManifest, String> {
         scripts,
         profile,
         app,
+        workspace,
+    })
+}
+
+fn workspace_from_snif(
+    root_obj: &std::collections::BTreeMap<String, SnifValue>,
+) -> Result<Option<WorkspaceSection>, String> {
+    let Some(v) = root_obj.get("workspace") else {
+        return Ok(None);
+    };
+    let o = snif_get_obj(v, "workspace")?;
+    let members = match o.get("members") {
+        None => Vec::new(),
+        Some(SnifValue::Array(items)) => {
+            let mut out = Vec::new();
+            for (i, item) in items.iter().enumerate() {
+                match item {
+                    SnifValue::String(s) => out.push(s.clone()),
+                    _ => return Err(format!("Expected string in workspace.members[{i}]")),
+                }
+            }
+            out
+        }
+        Some(_) => return Err("Expected array of strings in workspace.members".to_string()),
+    };
+    Ok(Some(WorkspaceSection { members }))
+}
+
+/// A loaded workspace: the root directory and every member manifest.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<WorkspaceMember>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    pub dir: PathBuf,
+    pub manifest: SpsManifest,
+}
+
+impl Workspace {
+    pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+        self.members.iter().find(|m| m.manifest.package.name == name)
+    }
+
+    pub fn entry_path(&self, member: &WorkspaceMember) -> PathBuf {
+        member.dir.join(&member.manifest.package.entry)
+    }
+
+    /// Dependencies of `member` that are other workspace members, mapped to
+    /// their entry file. These never go through the registry.
+    pub fn path_deps(&self, member: &WorkspaceMember) -> BTreeMap<String, PathBuf> {
+        member
+            .manifest
+            .dependencies
+            .keys()
+            .filter_map(|name| self.member(name).map(|dep| (name.clone(), self.entry_path(dep))))
+            .collect()
+    }
+
+    /// Registry, git and path dependencies of all members, merged. `path:`
+    /// specifiers are rebased onto the workspace root. Two members asking
+    /// for different versions of the same package is an error.
+    pub fn registry_deps(&self) -> Result<BTreeMap<String, String>, String> {
+        let mut out: BTreeMap<String, String> = BTreeMap::new();
+        for member in &self.members {
+            for (name, req) in &member.manifest.dependencies {
+                if self.member(name).is_some() {
+                    continue;
+                }
+                let rebased;
+                let req = match DepSource::parse(req) {
+                    DepSource::Path(rel) => {
+                        let dir = member.dir.join(rel);
+                        let dir = dir.strip_prefix(&self.root).unwrap_or(&dir);
+                        rebased = format!("path:{}", dir.display());
+                        &rebased
+                    }
+                    _ => req,
+                };
+                match out.get(name) {
+                    Some(prev) if prev != req && prev != "*" && req != "*" => {
+                        return Err(format!(
+                            "SPS: workspace members disagree on '{name}': '{prev}' vs '{req}' (in {member}).",
+                            member = member.manifest.package.name
+                        ));
+                    }
+                    Some(prev) if prev != "*" => {}
+                    _ => {
+                        out.insert(name.clone(), req.clone());
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Loads the `workspace` section of `dir/snask.snif` and every member
+/// manifest. The root does not need a `package` section.
+pub fn load_workspace_from(dir: &Path) -> Result<Workspace, String> {
+    let manifest_path = dir.join("snask.snif");
+    let src = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("SPS: failed to read {}: {}", manifest_path.display(), e))?;
+    let root = parse_snif(&src).map_err(|e| {
+        format!(
+            "SPS: failed to parse snask.snif:\n{}\n{}",
+            e.message,
+            render_snif_error(&src, e.line, e.col)
+        )
+    })?;
+    let root = crate::snif_tooling::expand_env_vars(&root)?;
+    let root_obj = snif_get_obj(&root, "root")?;
+    let section = workspace_from_snif(root_obj)?.ok_or_else(|| {
+        format!(
+            "SPS: {} has no workspace section.\n\nHow to fix:\n- Add:\n  workspace: {{ members: [\"pkg_a\", \"pkg_b\"], }},\n",
+            manifest_path.display()
+        )
+    })?;
+    if section.members.is_empty() {
+        return Err("SPS: workspace.members is empty.".to_string());
+    }
+
+    let mut members: Vec<WorkspaceMember> = Vec::new();
+    for rel in &section.members {
+        let member_dir = dir.join(rel);
+        if !member_dir.join("snask.snif").exists() {
+            return Err(format!(
+                "SPS: workspace member '{}' has no snask.snif ({}).",
+                rel,
+                member_dir.join("snask.snif").display()
+            ));
+        }
+        let (manifest, _) = load_manifest_from(&member_dir)
+            .map_err(|e| format!("SPS: in workspace member '{}':\n{}", rel, e))?;
+        if let Some(other) = members
+            .iter()
+            .find(|m| m.manifest.package.name == manifest.package.name)
+        {
+            return Err(format!(
+                "SPS: workspace members '{}' and '{}' are both named '{}'.",
+                other.dir.display(),
+                member_dir.display(),
+                manifest.package.name
+            ));
+        }
+        members.push(WorkspaceMember {
+            dir: member_dir,
+            manifest,
+        });
+    }
+
+    Ok(Workspace {
+        root: dir.to_path_buf(),
+        members,
     })
 }
 
+/// Resolves the registry dependencies of every member once and writes a
+/// single `snask.lock` at the workspace root. Member-to-member dependencies
+/// are recorded with their relative path.
+pub fn resolve_workspace_deps_and_lock(ws: &Workspace) -> Result<(), String> {
+    let mut locked = lock_deps(&ws.root, &ws.registry_deps()?)?;
+    for member in &ws.members {
+        for name in ws.path_deps(member).keys() {
+            let dep = ws.member(name).expect("path deps are workspace members");
+            let rel = dep.dir.strip_prefix(&ws.root).unwrap_or(&dep.dir);
+            locked.insert(
+                name.clone(),
+                LockedDep {
+                    version: dep.manifest.package.version.clone(),
+                    sha256: String::new(),
+                    url: None,
+                    path: Some(rel.to_string_lossy().to_string()),
+                    rev: None,
+                },
+            );
+        }
+    }
+
+    let name = ws
+        .root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "workspace".to_string());
+    let members = ws
+        .members
+        .iter()
+        .map(|m| LockPackage {
+            name: m.manifest.package.name.clone(),
+            version: m.manifest.package.version.clone(),
+        })
+        .collect();
+    write_lock(
+        &ws.root,
+        LockPackage {
+            name,
+            version: "0.0.0".to_string(),
+        },
+        members,
+        locked,
+    )
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Lockfile {
     pub package: LockPackage,
     #[serde(default)]
     pub registry: Option<LockRegistry>,
+    /// Workspace members sharing this lockfile (empty outside workspaces).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<LockPackage>,
     pub dependencies: BTreeMap<String, LockedDep>,
 }
 
@@ -745,9 +1062,17 @@ pub struct LockRegistry {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LockedDep {
     pub version: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub sha256: String,
     #[serde(default)]
     pub url: Option<String>,
+    /// Workspace member path (relative to the root) for member dependencies,
+    /// or the directory of a `path:` dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Commit a `git+` dependency resolved to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
 }
 
 pub fn lockfile_path(dir: &Path) -> PathBuf {
@@ -770,6 +1095,23 @@ pub fn write_lockfile(
     dir: &Path,
     manifest: &SpsManifest,
     deps: BTreeMap<String, LockedDep>,
+) -> Result<(), String> {
+    write_lock(
+        dir,
+        LockPackage {
+            name: manifest.package.name.clone(),
+            version: manifest.package.version.clone(),
+        },
+        Vec::new(),
+        deps,
+    )
+}
+
+fn write_lock(
+    dir: &Path,
+    package: LockPackage,
+    members: Vec<LockPackage>,
+    deps: BTreeMap<String, LockedDep>,
 ) -> Result<(), String> {
     // Best-effort: registra a revisão do registry local (git) para auditoria/debug.
     // Reprodutibilidade real é garantida por sha256 em cada dep.
@@ -779,11 +1121,9 @@ pub fn write_lockfile(
     });
 
     let lf = Lockfile {
-        package: LockPackage {
-            name: manifest.package.name.clone(),
-            version: manifest.package.version.clone(),
-        },
+        package,
         registry,
+        members,
         dependencies: deps,
     };
     let s = toml::to_string_pretty(&lf).map_err(|e| e.to_string())?;
@@ -844,16 +1184,62 @@ pub fn init_project(name: Option<String>) -> Result<(), String> {
     Ok(())
 }
 
-pub fn add_dependency(name: &str, version: Option<String>) -> Result<(), String> {
+/// `snask init --lib`: a library project whose entry is `<name>.snask`
+/// (no `class main`), plus the `package.json`/`README.md` that
+/// `snask lib publish` expects.
+pub fn init_library(name: Option<String>) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let lib_name = name.unwrap_or_else(|| {
+        cwd.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    });
+    let entry = init_library_in(&cwd, &lib_name)?;
+    println!("✅ SPS: created snask.snif, {}, package.json and README.md.", entry);
+    println!("📦 Check it with `snask check {}`, then `snask lib publish {}`.", entry, lib_name);
+    Ok(())
+}
+
+pub fn init_library_in(dir: &Path, name: &str) -> Result<String, String> {
+    let manifest_path = dir.join("snask.snif");
+    if manifest_path.exists() {
+        return Err("snask.snif already exists in this directory.".to_string());
+    }
+    crate::lib_tool::lib_init_in(
+        dir,
+        &crate::lib_tool::NewLibOpts {
+            name: name.to_string(),
+            description: format!("The {} Snask library.", name),
+            version: "0.1.0".to_string(),
+        },
+    )?;
+
+    let entry = format!("{}.snask", name);
+    let manifest = format!(
+        "{{\n  package: {{ name: \"{}\", version: \"0.1.0\", entry: \"{}\", lib: true, }},\n  dependencies: {{}},\n}}\n",
+        name, entry
+    );
+    fs::write(&manifest_path, manifest).map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+/// Adds `name` to `snask.snif`. `spec` is a registry version, `git+<url>#<ref>`
+/// or `path:<dir>` (see `DepSource`); registry packages are installed right
+/// away, the others while locking.
+pub fn add_dependency(name: &str, spec: Option<String>) -> Result<(), String> {
     let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
     let (mut m, manifest_path) = load_manifest_from(&cwd)?;
-    m.dependencies
-        .insert(name.to_string(), version.unwrap_or_else(|| "*".to_string()));
+    let spec = spec.unwrap_or_else(|| "*".to_string());
+    let from_registry = matches!(DepSource::parse(&spec), DepSource::Registry(_));
+    m.dependencies.insert(name.to_string(), spec);
     write_manifest(&manifest_path, &m)?;
 
     // instala imediatamente usando o módulo de pacotes do crate
-    let registry = crate::packages::fetch_registry()?;
-    let _ = crate::packages::install_package_with_registry(name, &registry)?;
+    if from_registry {
+        let registry = crate::packages::fetch_registry()?;
+        let _ = crate::packages::install_package_with_registry(name, &registry)?;
+    }
 
     // lock determinístico
     resolve_deps_and_lock(&cwd, &m)?;
@@ -875,15 +1261,187 @@ pub fn remove_dependency(name: &str) -> Result<(), String> {
 }
 
 pub fn resolve_deps_and_lock(dir: &std::path::Path, manifest: &SpsManifest) -> Result<(), String> {
+    let locked = lock_deps(dir, &manifest.dependencies)?;
+    write_lockfile(dir, manifest, locked)?;
+    Ok(())
+}
+
+/// Where a dependency comes from, parsed from its value in `snask.snif`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepSource {
+    /// A registry version requirement: `"1.2.0"` or `"*"`.
+    Registry(String),
+    /// `git+<url>` or `git+<url>#<ref>` (branch, tag or commit).
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+    /// `path:<dir>`, relative to the project: a library directory holding
+    /// `<name>.snask`, or the `.snask` file itself.
+    Path(PathBuf),
+}
+
+impl DepSource {
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Some(rest) = spec.strip_prefix("git+") {
+            let (url, reference) = match rest.rsplit_once('#') {
+                Some((url, r)) if !r.is_empty() => (url, Some(r.to_string())),
+                Some((url, _)) => (url, None),
+                None => (rest, None),
+            };
+            return DepSource::Git {
+                url: url.to_string(),
+                reference,
+            };
+        }
+        if let Some(path) = spec.strip_prefix("path:") {
+            return DepSource::Path(PathBuf::from(path));
+        }
+        DepSource::Registry(spec.to_string())
+    }
+}
+
+/// Installs (if needed) and pins every dependency in `deps`. Relative
+/// `path:` dependencies are resolved against `dir`.
+fn lock_deps(
+    dir: &Path,
+    deps: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, LockedDep>, String> {
+    let mut registry_deps = BTreeMap::new();
+    let mut locked = BTreeMap::new();
+    for (name, spec) in deps {
+        match DepSource::parse(spec) {
+            DepSource::Registry(req) => {
+                registry_deps.insert(name.clone(), req);
+            }
+            source => {
+                let packages_dir = crate::packages::get_packages_dir();
+                let dep = install_source_dep(name, &source, dir, &packages_dir)?;
+                locked.insert(name.clone(), dep);
+            }
+        }
+    }
+    locked.extend(lock_registry_deps(&registry_deps)?);
+    Ok(locked)
+}
+
+/// Copies a `git+` or `path:` dependency into `packages_dir` as
+/// `<name>.snask` and pins it by the sha256 of that file. Git sources are
+/// cloned next to `packages_dir` (in `git/<name>`) and also pin the commit.
+fn install_source_dep(
+    name: &str,
+    source: &DepSource,
+    base_dir: &Path,
+    packages_dir: &Path,
+) -> Result<LockedDep, String> {
+    match source {
+        DepSource::Registry(req) => Err(format!(
+            "SPS: '{name}' ({req}) is a registry dependency, not a git or path one."
+        )),
+        DepSource::Path(rel) => {
+            let src = base_dir.join(rel);
+            let sha256 = copy_library(name, &src, packages_dir)?;
+            Ok(LockedDep {
+                version: library_version(&src),
+                sha256,
+                url: None,
+                path: Some(rel.to_string_lossy().to_string()),
+                rev: None,
+            })
+        }
+        DepSource::Git { url, reference } => {
+            let checkout = packages_dir
+                .parent()
+                .unwrap_or(packages_dir)
+                .join("git")
+                .join(name);
+            if checkout.join(".git").exists() {
+                git_in(&checkout, &["remote", "set-url", "origin", url])?;
+                git_in(&checkout, &["fetch", "--tags", "origin"])?;
+            } else {
+                fs::create_dir_all(&checkout)
+                    .map_err(|e| format!("SPS: failed to create {}: {}", checkout.display(), e))?;
+                git_in(&checkout, &["clone", url, "."])?;
+            }
+            let target = reference.as_deref().unwrap_or("origin/HEAD");
+            git_in(&checkout, &["checkout", "--detach", target]).map_err(|e| {
+                format!("SPS: '{name}': could not check out '{target}' from {url}.\n{e}")
+            })?;
+            let rev = git_in(&checkout, &["rev-parse", "HEAD"])?;
+            let sha256 = copy_library(name, &checkout, packages_dir)?;
+            Ok(LockedDep {
+                version: library_version(&checkout),
+                sha256,
+                url: Some(url.clone()),
+                path: None,
+                rev: Some(rev),
+            })
+        }
+    }
+}
+
+/// Copies the library at `src` (a directory with `<name>.snask`, or the file
+/// itself) to `packages_dir/<name>.snask` and returns its sha256.
+fn copy_library(name: &str, src: &Path, packages_dir: &Path) -> Result<String, String> {
+    let file = if src.is_dir() {
+        src.join(format!("{name}.snask"))
+    } else {
+        src.to_path_buf()
+    };
+    let bytes = fs::read(&file).map_err(|e| {
+        format!(
+            "SPS: dependency '{name}': failed to read {} ({}).\n\nHow to fix:\n- Point it at a library directory containing `{name}.snask`, or at the `.snask` file itself.\n",
+            file.display(),
+            e
+        )
+    })?;
+    fs::create_dir_all(packages_dir)
+        .map_err(|e| format!("SPS: failed to create {}: {}", packages_dir.display(), e))?;
+    let dest = packages_dir.join(format!("{name}.snask"));
+    fs::write(&dest, &bytes)
+        .map_err(|e| format!("SPS: failed to write {}: {}", dest.display(), e))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// `version` from the library's `package.json`, or `0.0.0` without one.
+fn library_version(src: &Path) -> String {
+    fs::read(src.join("package.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+        .and_then(|pkg| pkg.get("version")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "0.0.0".to_string())
+}
+
+/// Runs git in `dir` and returns its trimmed stdout.
+fn git_in(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run git {:?}: {}", args, e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Installs (if needed) and pins every registry dependency in `deps`.
+fn lock_registry_deps(
+    deps: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, LockedDep>, String> {
+    let mut locked = BTreeMap::new();
+    if deps.is_empty() {
+        return Ok(locked);
+    }
     let registry = crate::packages::fetch_registry()?;
-    let mut locked = std::collections::BTreeMap::new();
-    for (name, _req) in &manifest.dependencies {
+    for (name, req) in deps {
         if let Some(pkg) = registry.packages.get(name) {
-            let req = manifest
-                .dependencies
-                .get(name)
-                .map(|s| s.as_str())
-                .unwrap_or("*");
+            let req = req.as_str();
             if req != "*" && req != pkg.version() {
                 return Err(format!(
                     "SPS: version constraint not satisfied for '{name}': requested '{req}', registry provides '{got}'.\n\nHow to fix:\n- Change the version in `snask.snif` (dependencies.{name})\n- Or run `snask update {name}` and then `snask build`\n",
@@ -912,6 +1470,8 @@ pub fn resolve_deps_and_lock(dir: &std::path::Path, manifest: &SpsManifest) -> R
                     version: ver,
                     sha256: sha,
                     url,
+                    path: None,
+                    rev: None,
                 },
             );
         } else {
@@ -925,12 +1485,13 @@ pub fn resolve_deps_and_lock(dir: &std::path::Path, manifest: &SpsManifest) -> R
                     version: ver,
                     sha256: sha,
                     url,
+                    path: None,
+                    rev: None,
                 },
             );
         }
     }
-    write_lockfile(dir, manifest, locked)?;
-    Ok(())
+    Ok(locked)
 }
 
 pub fn pin_from_lock(dir: &std::path::Path, manifest: &SpsManifest) -> Result<(), String> {
@@ -948,6 +1509,10 @@ pub fn pin_from_lock(dir: &std::path::Path, manifest: &SpsManifest) -> Result<()
         if !manifest.dependencies.contains_key(name) {
             continue;
         }
+        // membros do workspace e dependências git/path não vêm do registry
+        if dep.path.is_some() || dep.rev.is_some() {
+            continue;
+        }
 
         // checa constraint antes (manifest manda)
         if let Some(pkg) = registry.packages.get(name) {
@@ -992,8 +1557,11 @@ pub fn pin_from_lock(dir: &std::path::Path, manifest: &SpsManifest) -> Result<()
 #[cfg(test)]
 mod tests {
     use super::{
-        load_manifest_from, read_lockfile, write_lockfile, LockedDep, PackageSection, SpsManifest,
+        init_library_in, install_source_dep, load_manifest_from, load_workspace_from,
+        read_lockfile, resolve_workspace_deps_and_lock, write_lockfile, DepSource, LockedDep,
+        PackageSection, SpsManifest,
     };
+    use sha2::{Digest, Sha256};
     use std::collections::BTreeMap;
     use std::fs;
     use std::path::PathBuf;
@@ -1009,6 +1577,27 @@ mod tests {
         dir
     }
 
+    #[test]
+    fn scaffolded_library_type_checks() {
+        let dir = temp_project_dir("lib_init");
+        let entry = init_library_in(&dir, "mathx").expect("library should be scaffolded");
+        assert_eq!(entry, "mathx.snask");
+
+        let (m, _) = load_manifest_from(&dir).expect("manifest should load");
+        assert_eq!(m.package.entry, "mathx.snask");
+        assert!(m.package.lib);
+        assert!(dir.join("package.json").is_file());
+        assert!(dir.join("README.md").is_file());
+        let source = fs::read_to_string(dir.join(&entry)).unwrap();
+        assert!(!source.contains("class main"));
+
+        let checked = crate::compiler::check_source(&entry, &source, &dir, false, None);
+        let again = init_library_in(&dir, "mathx");
+        let _ = fs::remove_dir_all(&dir);
+        checked.expect("scaffolded library should type-check");
+        assert!(again.is_err());
+    }
+
     #[test]
     fn snif_manifest_loads_package_build_deps_scripts_and_app() {
         let dir = temp_project_dir("manifest");
@@ -1023,7 +1612,9 @@ mod tests {
     strip: true,
     lto: "thin",
     opt: "O3",
+    max_import_depth: 8,
     features: { gui: true, flavor: "dev", level: 2 },
+    defines: { debug: true, platform: "linux", level: 2 },
   },
   scripts: { run: "snask run" },
   app: { id: "dev.snask.demo", name: "Demo", terminal: false },
@@ -1050,6 +1641,19 @@ mod tests {
         assert_eq!(manifest.build.strip, Some(true));
         assert_eq!(manifest.build.lto.as_deref(), Some("thin"));
         assert_eq!(manifest.build.opt.as_deref(), Some("O3"));
+        assert_eq!(manifest.build.max_import_depth, Some(8));
+        assert_eq!(
+            manifest.build.defines.get("debug").map(String::as_str),
+            Some("true")
+        );
+        assert_eq!(
+            manifest.build.defines.get("platform").map(String::as_str),
+            Some("linux")
+        );
+        assert_eq!(
+            manifest.build.defines.get("level").map(String::as_str),
+            Some("2")
+        );
         assert_eq!(
             manifest.scripts.get("run").map(String::as_str),
             Some("snask run")
@@ -1077,20 +1681,117 @@ mod tests {
     }
 
     #[test]
-    fn lockfile_roundtrip_preserves_package_and_dependency_hashes() {
-        let dir = temp_project_dir("lock");
-        let manifest = SpsManifest {
+    fn snif_manifest_expands_env_vars_when_loading() {
+        let dir = temp_project_dir("env");
+        std::env::set_var("SNASK_SPS_TEST_OUT", "/tmp/out");
+        fs::write(
+            dir.join("snask.snif"),
+            r#"{
+  package: { name: "demo", version: "0.1.0" },
+  scripts: { deploy: "cp app ${SNASK_SPS_TEST_OUT}", ci: "echo ${SNASK_SPS_TEST_UNSET:-local}" },
+}"#,
+        )
+        .expect("manifest should be written");
+
+        let (manifest, _) = load_manifest_from(&dir).expect("manifest should load");
+        assert_eq!(
+            manifest.scripts.get("deploy").map(String::as_str),
+            Some("cp app /tmp/out")
+        );
+        assert_eq!(
+            manifest.scripts.get("ci").map(String::as_str),
+            Some("echo local")
+        );
+
+        fs::write(
+            dir.join("snask.snif"),
+            r#"{ package: { name: "demo", version: "0.1.0" }, scripts: { x: "${SNASK_SPS_TEST_UNSET}" } }"#,
+        )
+        .expect("manifest should be written");
+        let err = load_manifest_from(&dir).expect_err("missing env var should be rejected");
+        assert!(err.contains("$.scripts.x"), "{err}");
+        assert!(err.contains("SNASK_SPS_TEST_UNSET"), "{err}");
+    }
+
+    fn demo_manifest() -> SpsManifest {
+        SpsManifest {
             package: PackageSection {
                 name: "demo".to_string(),
                 version: "0.1.0".to_string(),
                 entry: "main.snask".to_string(),
+                maintainer: None,
+                lib: false,
             },
             dependencies: BTreeMap::new(),
             build: Default::default(),
             scripts: BTreeMap::new(),
             profile: Default::default(),
             app: None,
-        };
+            workspace: None,
+        }
+    }
+
+    #[test]
+    fn prebuild_hook_runs_first_and_a_failing_one_stops_the_build() {
+        let dir = temp_project_dir("hooks");
+        let mut manifest = demo_manifest();
+        for (hook, cmdline) in [
+            ("prebuild", "echo pre >> hooks.log"),
+            ("postbuild", "echo post >> hooks.log"),
+        ] {
+            manifest
+                .scripts
+                .insert(hook.to_string(), cmdline.to_string());
+        }
+
+        let built = with_hooks(Some(&manifest), &dir, "build", || {
+            let log = fs::read_to_string(dir.join("hooks.log")).unwrap_or_default();
+            fs::write(dir.join("hooks.log"), format!("{}build\n", log)).unwrap();
+            Ok(())
+        });
+        let log = fs::read_to_string(dir.join("hooks.log")).unwrap_or_default();
+        assert_eq!(built, Ok(()));
+        assert_eq!(log, "pre\nbuild\npost\n");
+
+        manifest
+            .scripts
+            .insert("prebuild".to_string(), "exit 3".to_string());
+        let mut compiled = false;
+        let err = with_hooks(Some(&manifest), &dir, "build", || {
+            compiled = true;
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.contains("Script 'prebuild' failed."), "{err}");
+        assert!(!compiled);
+
+        let mut compiled = false;
+        with_hooks(None, &dir, "build", || {
+            compiled = true;
+            Ok(())
+        })
+        .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert!(compiled, "no hooks run without a manifest");
+    }
+
+    #[test]
+    fn script_arguments_reach_the_command_verbatim() {
+        let dir = temp_project_dir("script_args");
+        let args = ["a b".to_string(), "$HOME;c".to_string()];
+        run_script(&dir, "dev", "printf '%s|' > args.txt", &args).expect("script should run");
+        run_script(&dir, "plain", "printf plain > plain.txt", &[]).expect("script should run");
+        let got = fs::read_to_string(dir.join("args.txt")).unwrap_or_default();
+        let plain = fs::read_to_string(dir.join("plain.txt")).unwrap_or_default();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(got, "a b|$HOME;c|");
+        assert_eq!(plain, "plain");
+    }
+
+    #[test]
+    fn lockfile_roundtrip_preserves_package_and_dependency_hashes() {
+        let dir = temp_project_dir("lock");
+        let manifest = demo_manifest();
         let mut deps = BTreeMap::new();
         deps.insert(
             "json".to_string(),
@@ -1098,6 +1799,8 @@ mod tests {
                 version: "1.2.3".to_string(),
                 sha256: "abc123".to_string(),
                 url: Some("https://example.test/json.snask".to_string()),
+                path: None,
+                rev: None,
             },
         );
 
@@ -1114,4 +1817,140 @@ mod tests {
         assert_eq!(dep.sha256, "abc123");
         assert_eq!(dep.url.as_deref(), Some("https://example.test/json.snask"));
     }
+
+    #[test]
+    fn path_dependencies_are_copied_and_locked_by_hash() {
+        assert_eq!(
+            DepSource::parse("git+https://example.test/mathx.git#v0.2"),
+            DepSource::Git {
+                url: "https://example.test/mathx.git".to_string(),
+                reference: Some("v0.2".to_string()),
+            }
+        );
+        assert_eq!(
+            DepSource::parse("1.2.0"),
+            DepSource::Registry("1.2.0".to_string())
+        );
+
+        let dir = temp_project_dir("pathdep");
+        let lib = dir.join("libs").join("mathx");
+        fs::create_dir_all(&lib).expect("library dir should be created");
+        fs::write(
+            lib.join("mathx.snask"),
+            "fun double(n: int) : int\n    return n * 2\n",
+        )
+        .expect("library should be written");
+        fs::write(
+            lib.join("package.json"),
+            r#"{"name": "mathx", "version": "0.2.0"}"#,
+        )
+        .expect("package.json should be written");
+
+        let source = DepSource::parse("path:libs/mathx");
+        assert_eq!(source, DepSource::Path(PathBuf::from("libs/mathx")));
+        let packages_dir = dir.join("packages");
+        let dep = install_source_dep("mathx", &source, &dir, &packages_dir)
+            .expect("path dependency should resolve");
+        let installed = fs::read(packages_dir.join("mathx.snask")).expect("library is installed");
+        assert_eq!(dep.sha256, format!("{:x}", Sha256::digest(&installed)));
+        assert_eq!(dep.version, "0.2.0");
+
+        let mut deps = BTreeMap::new();
+        deps.insert("mathx".to_string(), dep);
+        write_lockfile(&dir, &demo_manifest(), deps).expect("lockfile should be written");
+        let lock = read_lockfile(&dir).expect("lockfile should be read");
+        let locked = lock
+            .dependencies
+            .get("mathx")
+            .expect("mathx should be locked");
+        assert_eq!(locked.path.as_deref(), Some("libs/mathx"));
+        assert_eq!(locked.sha256.len(), 64);
+        assert_eq!(locked.rev, None);
+
+        let missing = install_source_dep(
+            "nope",
+            &DepSource::parse("path:libs/nope"),
+            &dir,
+            &packages_dir,
+        )
+        .expect_err("missing library should be reported");
+        assert!(missing.contains("nope.snask"), "{missing}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn write_member(root: &std::path::Path, dir: &str, manifest: &str) {
+        let member = root.join(dir);
+        fs::create_dir_all(&member).expect("member dir should be created");
+        fs::write(member.join("snask.snif"), manifest).expect("manifest should be written");
+        fs::write(member.join("main.snask"), "class main\n    fun start()\n        print(1)\n")
+            .expect("entry should be written");
+    }
+
+    fn two_member_workspace(name: &str) -> PathBuf {
+        let root = temp_project_dir(name);
+        fs::write(
+            root.join("snask.snif"),
+            r#"{ workspace: { members: ["app", "libs/util"] } }"#,
+        )
+        .expect("workspace manifest should be written");
+        write_member(
+            &root,
+            "app",
+            r#"{ package: { name: "app", version: "0.1.0" }, dependencies: { util: "*" } }"#,
+        );
+        write_member(
+            &root,
+            "libs/util",
+            r#"{ package: { name: "util", version: "0.3.0" } }"#,
+        );
+        root
+    }
+
+    #[test]
+    fn workspace_loads_members_and_resolves_path_deps() {
+        let root = two_member_workspace("ws");
+
+        let ws = load_workspace_from(&root).expect("workspace should load");
+        let names: Vec<&str> = ws
+            .members
+            .iter()
+            .map(|m| m.manifest.package.name.as_str())
+            .collect();
+        assert_eq!(names, ["app", "util"]);
+
+        let app = ws.member("app").unwrap();
+        let deps = ws.path_deps(app);
+        assert_eq!(
+            deps.get("util"),
+            Some(&root.join("libs/util").join("main.snask"))
+        );
+        assert!(ws.registry_deps().unwrap().is_empty());
+
+        // Only path deps: the shared lockfile is written without touching the registry.
+        resolve_workspace_deps_and_lock(&ws).expect("lockfile should be written");
+        let lock = read_lockfile(&root).expect("lockfile should be read");
+        assert_eq!(lock.members.len(), 2);
+        let util = lock.dependencies.get("util").expect("util should be locked");
+        assert_eq!(util.version, "0.3.0");
+        assert_eq!(util.path.as_deref(), Some("libs/util"));
+        assert!(!root.join("app").join("snask.lock").exists());
+
+        // The root has no package section, so a plain build points at --workspace.
+        let err = load_manifest_from(&root).expect_err("workspace root is not a package");
+        assert!(err.contains("--workspace"), "{err}");
+    }
+
+    #[test]
+    fn workspace_member_without_manifest_is_an_error() {
+        let root = two_member_workspace("ws_missing");
+        fs::write(
+            root.join("snask.snif"),
+            r#"{ workspace: { members: ["app", "ghost"] } }"#,
+        )
+        .expect("workspace manifest should be written");
+        fs::create_dir_all(root.join("ghost")).unwrap();
+
+        let err = load_workspace_from(&root).expect_err("member without snask.snif must fail");
+        assert!(err.contains("'ghost' has no snask.snif"), "{err}");
+    }
 }
@@ -0,0 +1,433 @@
+use crate::ast::{
+    ClassDecl, Expr, ExprKind, FuncDecl, LiteralValue, Location, Program, Stmt, StmtKind,
+};
+use crate::compiler::{build_file, resolve_entry_file, BuildOptions, Verbosity};
+use crate::sps;
+use std::process::Command;
+
+/// Prefix that marks a top-level function as a test.
+pub const TEST_PREFIX: &str = "test_";
+
+/// Environment variable the runtime reads to know where to append the
+/// coverage counters of each process.
+pub const COVERAGE_FILE_ENV: &str = "SNASK_COVERAGE_FILE";
+
+/// Entry counter of one instrumented function, summed over every process
+/// that wrote to the coverage file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCoverage {
+    pub file: String,
+    pub line: usize,
+    pub name: String,
+    pub hits: u64,
+}
+
+/// Replaces `class main` with a generated one whose `start()` runs every
+/// top-level `test_*` function through `snask_test_run` and finishes with
+/// `snask_test_summary`, which prints the totals and sets the exit status.
+/// Functions from imported modules (`mod::test_x`) are not collected.
+/// Returns the test names in source order.
+pub fn synthesize_test_driver(
+    program: &mut Program,
+    filter: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let tests: Vec<String> = program
+        .iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::FuncDeclaration(func)
+                if func.name.starts_with(TEST_PREFIX)
+                    && !func.name.contains("::")
+                    && func.params.is_empty() =>
+            {
+                Some(func.name.clone())
+            }
+            _ => None,
+        })
+        .filter(|name| filter.is_none_or(|f| name.contains(f)))
+        .collect();
+    if tests.is_empty() {
+        return Err(match filter {
+            Some(f) => format!("test: no `{TEST_PREFIX}*` function matches `{f}`"),
+            None => format!(
+                "test: no `{TEST_PREFIX}*` functions found\n\nhelp: a test is a top-level function without parameters, e.g. `fun {TEST_PREFIX}sum()`."
+            ),
+        });
+    }
+
+    let loc = Location::default();
+    let call = |callee: &str, args: Vec<Expr>| {
+        let callee = Box::new(Expr::new(
+            ExprKind::Variable(callee.to_string()),
+            loc.clone(),
+        ));
+        let expr = Expr::new(ExprKind::FunctionCall { callee, args }, loc.clone());
+        Stmt::new(StmtKind::FuncCall(expr), loc.clone())
+    };
+    let mut body: Vec<Stmt> = tests
+        .iter()
+        .map(|name| {
+            let value = LiteralValue::String(name.clone());
+            let arg = Expr::new(ExprKind::Literal(value), loc.clone());
+            call("snask_test_run", vec![arg])
+        })
+        .collect();
+    body.push(call("snask_test_summary", Vec::new()));
+
+    program.retain(
+        |stmt| !matches!(&stmt.kind, StmtKind::ClassDeclaration(class) if class.name == "main"),
+    );
+    program.push(Stmt::new(
+        StmtKind::ClassDeclaration(ClassDecl {
+            name: "main".to_string(),
+            parent: None,
+            properties: Vec::new(),
+            methods: vec![FuncDecl {
+                name: "start".to_string(),
+                params: Vec::new(),
+                return_type: None,
+                body,
+                is_unsafe: false,
+                span: loc.to_span(),
+            }],
+        }),
+        loc,
+    ));
+    Ok(tests)
+}
+
+/// `snask test`: builds the project (or `file`) with a generated test driver,
+/// runs it and fails when any test failed. With `coverage` the binary counts
+/// function entries and a per-file summary is printed after the results.
+pub fn run_tests(
+    file: Option<String>,
+    filter: Option<String>,
+    coverage: bool,
+    verbosity: Verbosity,
+) -> Result<(), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let binary = std::env::temp_dir().join(format!("snask_test_{}", std::process::id()));
+    let coverage_file = binary.with_extension("cov");
+    let mut options = BuildOptions {
+        output_name: Some(binary.to_string_lossy().to_string()),
+        verbosity,
+        test_mode: true,
+        test_filter: filter,
+        coverage,
+        ..Default::default()
+    };
+    let entry = if let Ok((m, _)) = sps::load_manifest_from(&cwd) {
+        sps::pin_from_lock(&cwd, &m)?;
+        sps::resolve_deps_and_lock(&cwd, &m)?;
+        options.features = m.build.features.clone();
+        options.defines = m.build.defines.clone();
+        file.unwrap_or_else(|| m.package.entry.clone())
+    } else {
+        resolve_entry_file(file)?
+    };
+
+    build_file(&entry, options)?;
+    let _ = std::fs::remove_file(&coverage_file);
+    let status = Command::new(&binary)
+        .env(COVERAGE_FILE_ENV, &coverage_file)
+        .status();
+    let _ = std::fs::remove_file(&binary);
+    let status = status.map_err(|e| format!("test: failed to run the test binary: {}", e))?;
+    if coverage {
+        let dump = std::fs::read_to_string(&coverage_file).unwrap_or_default();
+        let _ = std::fs::remove_file(&coverage_file);
+        print!("{}", render_coverage(&parse_coverage(&dump)));
+    }
+    if !status.success() {
+        return Err("test: some tests failed".to_string());
+    }
+    Ok(())
+}
+
+/// Merges the `count\tfile\tline\tname` lines written by every process,
+/// keeping the order in which functions first appear. The generated
+/// `main::start` driver is left out.
+pub fn parse_coverage(dump: &str) -> Vec<FunctionCoverage> {
+    let mut functions: Vec<FunctionCoverage> = Vec::new();
+    for line in dump.lines() {
+        let mut fields = line.splitn(4, '\t');
+        let (Some(hits), Some(file), Some(at), Some(name)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let (Ok(hits), Ok(at)) = (hits.parse::<u64>(), at.parse::<usize>()) else {
+            continue;
+        };
+        if name == "main::start" {
+            continue;
+        }
+        match functions
+            .iter_mut()
+            .find(|f| f.file == file && f.line == at && f.name == name)
+        {
+            Some(existing) => existing.hits += hits,
+            None => functions.push(FunctionCoverage {
+                file: file.to_string(),
+                line: at,
+                name: name.to_string(),
+                hits,
+            }),
+        }
+    }
+    functions
+}
+
+/// One line per file with the share of functions that ran at least once,
+/// followed by the functions that never ran.
+pub fn render_coverage(functions: &[FunctionCoverage]) -> String {
+    let mut files: Vec<&str> = Vec::new();
+    for f in functions {
+        if !files.contains(&f.file.as_str()) {
+            files.push(&f.file);
+        }
+    }
+    let mut out = String::from("\ncoverage (functions entered):\n");
+    for file in files {
+        let in_file: Vec<&FunctionCoverage> = functions.iter().filter(|f| f.file == file).collect();
+        let hit = in_file.iter().filter(|f| f.hits > 0).count();
+        let percent = hit as f64 * 100.0 / in_file.len() as f64;
+        out.push_str(&format!(
+            "  {}: {}/{} ({:.1}%)\n",
+            file,
+            hit,
+            in_file.len(),
+            percent
+        ));
+        for f in in_file.iter().filter(|f| f.hits == 0) {
+            out.push_str(&format!("    never run: {} (line {})\n", f.name, f.line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_coverage, render_coverage, synthesize_test_driver, COVERAGE_FILE_ENV};
+    use crate::ast::{ExprKind, LiteralValue, StmtKind};
+    use crate::compiler::{build_source, BuildOptions, Verbosity};
+    use crate::jit::shared_runtime_path;
+    use crate::parser::Parser;
+    use crate::semantic_analyzer::{SemanticAnalyzer, SemanticErrorKind};
+    use std::path::Path;
+    use std::process::Command;
+
+    const SOURCE: &str = "fun helper() : int\n    return 2\n\
+fun test_passes()\n    assert(helper() == 2)\n\
+fun test_fails()\n    assert(helper() == 3, \"two is not three\")\n\
+class main\n    fun start()\n        helper()\n";
+
+    fn driver_calls(program: &crate::ast::Program) -> Vec<String> {
+        let Some(StmtKind::ClassDeclaration(main)) = program.last().map(|s| &s.kind) else {
+            panic!("driver class should be appended");
+        };
+        main.methods[0]
+            .body
+            .iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::FuncCall(expr) => match &expr.kind {
+                    ExprKind::FunctionCall { callee, args } => {
+                        let ExprKind::Variable(name) = &callee.kind else {
+                            panic!("callee should be a name");
+                        };
+                        match args.first().map(|a| &a.kind) {
+                            Some(ExprKind::Literal(LiteralValue::String(test))) => {
+                                format!("{name}({test})")
+                            }
+                            _ => format!("{name}()"),
+                        }
+                    }
+                    other => panic!("unexpected call {other:?}"),
+                },
+                other => panic!("unexpected statement {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn driver_runs_passing_and_failing_tests_then_the_summary() {
+        let mut parser = Parser::new(SOURCE).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+
+        let tests = synthesize_test_driver(&mut program, None).expect("tests should be found");
+        assert_eq!(
+            tests,
+            vec!["test_passes".to_string(), "test_fails".to_string()]
+        );
+        assert_eq!(
+            driver_calls(&program),
+            vec![
+                "snask_test_run(test_passes)",
+                "snask_test_run(test_fails)",
+                "snask_test_summary()",
+            ]
+        );
+        let mains = program
+            .iter()
+            .filter(|s| matches!(&s.kind, StmtKind::ClassDeclaration(c) if c.name == "main"))
+            .count();
+        assert_eq!(mains, 1, "the user's class main is replaced");
+
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.set_test_mode(true);
+        analyzer.analyze(&program);
+        assert!(analyzer.errors.is_empty(), "{:?}", analyzer.errors);
+
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        let ir = generator.generate(program).expect("IR should be generated");
+        assert!(ir.contains("f_test_passes"), "{ir}");
+        assert!(ir.contains("f_test_fails"), "{ir}");
+        assert!(ir.contains("snask_test_run"), "{ir}");
+        assert!(ir.contains("snask_test_summary"), "{ir}");
+    }
+
+    #[test]
+    fn driver_calls_are_not_user_callable() {
+        let source = "class main\n    fun start()\n        snask_test_summary()\n";
+        let program = crate::parser::parse_program(source).expect("source should parse");
+        let mut analyzer = SemanticAnalyzer::new();
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.errors.iter().any(|e| matches!(
+                &e.kind,
+                SemanticErrorKind::FunctionNotFound(name) if name == "snask_test_summary"
+            )),
+            "{:?}",
+            analyzer.errors
+        );
+    }
+
+    #[test]
+    fn test_binary_reports_each_test_and_fails_when_one_fails() {
+        if !shared_runtime_path().is_file() {
+            eprintln!("skipping: no shared runtime (run `snask setup`)");
+            return;
+        }
+        let binary = std::env::temp_dir().join(format!("snask_test_run_{}", std::process::id()));
+        let options = BuildOptions {
+            output_name: Some(binary.to_string_lossy().to_string()),
+            verbosity: Verbosity::Quiet,
+            test_mode: true,
+            ..Default::default()
+        };
+        build_source("main.snask", SOURCE, Path::new("."), options).expect("test build");
+        let output = Command::new(&binary).output();
+        let _ = std::fs::remove_file(&binary);
+        let output = output.expect("test binary should run");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("test test_passes ... ok\n"), "{stdout}");
+        assert!(stdout.contains("test test_fails ... FAILED\n"), "{stdout}");
+        assert!(
+            stdout.contains("test result: FAILED. 1 passed; 1 failed"),
+            "{stdout}"
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("two is not three"), "{stderr}");
+        assert_eq!(output.status.code(), Some(1));
+    }
+
+    #[test]
+    fn filter_selects_tests_by_substring() {
+        let mut parser = Parser::new(SOURCE).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+
+        let tests = synthesize_test_driver(&mut program, Some("fail")).unwrap();
+        assert_eq!(tests, vec!["test_fails".to_string()]);
+
+        let err = synthesize_test_driver(&mut program, Some("nothing")).unwrap_err();
+        assert!(err.contains("nothing"), "{err}");
+    }
+
+    const COVERED: &str = "fun used() : int\n    return 1\n\
+fun unused() : int\n    return 2\n\
+class Counter\n    let n: int = 0\n    fun bump() : int\n        return self.n + 1\n\
+fun test_uses()\n    assert(used() == 1)\n    assert(Counter().bump() == 1)\n";
+
+    #[test]
+    fn coverage_labels_functions_and_methods_at_their_own_lines() {
+        let mut parser = Parser::new(COVERED).expect("source should tokenize");
+        let mut program = parser.parse_program().expect("source should parse");
+        synthesize_test_driver(&mut program, None).unwrap();
+
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        generator.set_coverage(true);
+        let ir = generator
+            .generate(program.clone())
+            .expect("IR should be generated");
+        assert!(ir.contains("main.snask\\091\\09used"), "{ir}");
+        assert!(ir.contains("main.snask\\093\\09unused"), "{ir}");
+        assert!(ir.contains("main.snask\\097\\09Counter::bump"), "{ir}");
+        assert!(!ir.contains("\\09test_uses"), "tests are not counted: {ir}");
+        assert!(ir.contains("call void @snask_cov_hit"), "{ir}");
+
+        let context = inkwell::context::Context::create();
+        let mut generator = crate::llvm_generator::LLVMGenerator::new(&context, "main.snask");
+        let ir = generator.generate(program).expect("IR should be generated");
+        assert!(
+            !ir.contains("call void @snask_cov_hit"),
+            "coverage is off by default"
+        );
+    }
+
+    #[test]
+    fn coverage_merges_the_counters_of_every_process() {
+        // What the runtime appends: the driver process, then the test child.
+        let dump = "0\tmain.snask\t1\tused\n0\tmain.snask\t3\tunused\n\
+1\tmain.snask\t0\tmain::start\n\
+1\tmain.snask\t1\tused\n0\tmain.snask\t3\tunused\n0\tmain.snask\t0\tmain::start\n";
+        let functions = parse_coverage(dump);
+        let hits: Vec<(&str, u64)> = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.hits))
+            .collect();
+        assert_eq!(hits, vec![("used", 1), ("unused", 0)]);
+
+        let report = render_coverage(&functions);
+        assert!(report.contains("main.snask: 1/2 (50.0%)"), "{report}");
+        assert!(report.contains("never run: unused (line 3)"), "{report}");
+    }
+
+    #[test]
+    fn coverage_binary_writes_the_counters_of_each_test() {
+        if !shared_runtime_path().is_file() {
+            eprintln!("skipping: no shared runtime (run `snask setup`)");
+            return;
+        }
+        let binary = std::env::temp_dir().join(format!("snask_test_cov_{}", std::process::id()));
+        let coverage_file = binary.with_extension("cov");
+        let options = BuildOptions {
+            output_name: Some(binary.to_string_lossy().to_string()),
+            verbosity: Verbosity::Quiet,
+            test_mode: true,
+            coverage: true,
+            ..Default::default()
+        };
+        build_source("main.snask", COVERED, Path::new("."), options).expect("coverage build");
+        let _ = std::fs::remove_file(&coverage_file);
+        let status = Command::new(&binary)
+            .env(COVERAGE_FILE_ENV, &coverage_file)
+            .status();
+        let dump = std::fs::read_to_string(&coverage_file).unwrap_or_default();
+        let _ = std::fs::remove_file(&binary);
+        let _ = std::fs::remove_file(&coverage_file);
+        assert!(status.expect("test binary should run").success());
+
+        let functions = parse_coverage(&dump);
+        let hits: Vec<(&str, usize, u64)> = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.line, f.hits))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![("used", 1, 1), ("unused", 3, 0), ("Counter::bump", 7, 1)],
+            "{dump}"
+        );
+    }
+}
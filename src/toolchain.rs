@@ -1,45 +1,35 @@
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::OnceLock;
 
+/// LLVM major versions the generated IR is known to work with, in the order
+/// they are probed. 18 is the version the code generator is built against.
+pub const LLVM_MAJORS: &[u32] = &[18, 19, 20, 17];
+
+static CLANG: OnceLock<Option<PathBuf>> = OnceLock::new();
+static LLC: OnceLock<Option<PathBuf>> = OnceLock::new();
+static LLVM_STRIP: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The clang to compile and link with. Resolved once per process; falls back
+/// to `clang-18` so error messages name something installable.
 pub fn clang() -> PathBuf {
-    resolve_llvm18_tool(
-        "SNASK_CLANG",
-        &[
-            "clang-18",
-            "/usr/bin/clang-18",
-            "/usr/lib/llvm18/bin/clang",
-            "/usr/lib/llvm-18/bin/clang",
-        ],
-        "clang",
-    )
-    .unwrap_or_else(|| PathBuf::from("clang-18"))
+    CLANG
+        .get_or_init(|| resolve_llvm_tool("SNASK_CLANG", "clang"))
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("clang-18"))
 }
 
+/// The llc to turn IR into objects, resolved like [`clang`].
 pub fn llc() -> PathBuf {
-    resolve_llvm18_tool(
-        "SNASK_LLC",
-        &[
-            "llc-18",
-            "/usr/bin/llc-18",
-            "/usr/lib/llvm18/bin/llc",
-            "/usr/lib/llvm-18/bin/llc",
-        ],
-        "llc",
-    )
-    .unwrap_or_else(|| PathBuf::from("llc-18"))
+    LLC.get_or_init(|| resolve_llvm_tool("SNASK_LLC", "llc"))
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("llc-18"))
 }
 
 pub fn llvm_strip() -> Option<PathBuf> {
-    resolve_llvm18_tool(
-        "SNASK_LLVM_STRIP",
-        &[
-            "llvm-strip-18",
-            "/usr/bin/llvm-strip-18",
-            "/usr/lib/llvm18/bin/llvm-strip",
-            "/usr/lib/llvm-18/bin/llvm-strip",
-        ],
-        "llvm-strip",
-    )
+    LLVM_STRIP
+        .get_or_init(|| resolve_llvm_tool("SNASK_LLVM_STRIP", "llvm-strip"))
+        .clone()
 }
 
 pub fn ld_lld() -> Option<PathBuf> {
@@ -68,24 +58,38 @@ pub fn command_exists(tool: &PathBuf) -> bool {
         .unwrap_or(false)
 }
 
-fn resolve_llvm18_tool(env_var: &str, versioned: &[&str], generic: &str) -> Option<PathBuf> {
-    if let Some(path) = resolve_env_tool(env_var) {
+fn resolve_llvm_tool(env_var: &str, generic: &str) -> Option<PathBuf> {
+    let overridden = std::env::var_os(env_var).map(PathBuf::from);
+    resolve_llvm_tool_from(overridden, generic)
+}
+
+/// Resolution order: the override (e.g. `$SNASK_LLC`) if it runs, then
+/// `<tool>-<major>` and the distro LLVM directories for each of
+/// [`LLVM_MAJORS`], then the unversioned `<tool>` if its version is one of them.
+fn resolve_llvm_tool_from(overridden: Option<PathBuf>, generic: &str) -> Option<PathBuf> {
+    if let Some(path) = overridden.filter(command_exists) {
         return Some(path);
     }
 
-    for candidate in versioned {
-        let path = PathBuf::from(candidate);
-        if command_exists(&path) {
-            return Some(path);
+    for major in LLVM_MAJORS {
+        for candidate in [
+            format!("{}-{}", generic, major),
+            format!("/usr/bin/{}-{}", generic, major),
+            format!("/usr/lib/llvm{}/bin/{}", major, generic),
+            format!("/usr/lib/llvm-{}/bin/{}", major, generic),
+        ] {
+            let path = PathBuf::from(candidate);
+            if command_exists(&path) {
+                return Some(path);
+            }
         }
     }
 
     let generic_path = PathBuf::from(generic);
-    if command_major_version(&generic_path) == Some(18) {
-        return Some(generic_path);
+    match command_major_version(&generic_path) {
+        Some(major) if LLVM_MAJORS.contains(&major) => Some(generic_path),
+        _ => None,
     }
-
-    None
 }
 
 fn resolve_tool(env_var: &str, candidates: &[&str]) -> Option<PathBuf> {
@@ -113,7 +117,8 @@ fn resolve_env_tool(env_var: &str) -> Option<PathBuf> {
     }
 }
 
-fn command_major_version(tool: &PathBuf) -> Option<u32> {
+/// The version `tool --version` reports, e.g. `18.1.3`.
+pub fn tool_version(tool: &PathBuf) -> Option<String> {
     let output = Command::new(tool).arg("--version").output().ok()?;
     if !output.status.success() {
         return None;
@@ -125,16 +130,50 @@ fn command_major_version(tool: &PathBuf) -> Option<u32> {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    for token in text.split(|c: char| !(c.is_ascii_digit() || c == '.')) {
-        if token.is_empty() {
-            continue;
-        }
-        if let Some(first) = token.split('.').next() {
-            if let Ok(major) = first.parse::<u32>() {
-                return Some(major);
-            }
-        }
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|token| token.trim_end_matches('.').to_string())
+}
+
+fn command_major_version(tool: &PathBuf) -> Option<u32> {
+    tool_version(tool)?.split('.').next()?.parse().ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{command_major_version, resolve_llvm_tool_from, tool_version};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    fn fake_tool(dir: &PathBuf, name: &str, version_line: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\necho '{}'\n", version_line)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
     }
 
-    None
+    #[test]
+    fn override_picks_a_custom_binary_name() {
+        let dir = std::env::temp_dir().join(format!("snask_toolchain_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let custom = fake_tool(&dir, "my-llc", "Homebrew LLVM version 19.1.7");
+        assert_eq!(
+            resolve_llvm_tool_from(Some(custom.clone()), "llc"),
+            Some(custom.clone())
+        );
+        assert_eq!(tool_version(&custom).as_deref(), Some("19.1.7"));
+        assert_eq!(command_major_version(&custom), Some(19));
+
+        // An override that does not run falls through to probing.
+        let missing = dir.join("no-such-llc");
+        assert_ne!(
+            resolve_llvm_tool_from(Some(missing.clone()), "llc"),
+            Some(missing)
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
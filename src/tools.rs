@@ -1,72 +1,389 @@
 use crate::toolchain;
 use indicatif::HumanBytes;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-pub fn doctor() -> Result<(), String> {
-    println!("🩺 Snask Doctor");
-    println!("----------------");
+/// Result of looking up a single tool or native library.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub found: bool,
+    pub path: Option<String>,
+    pub hint: Option<String>,
+    /// Version reported by `--version`, for the LLVM tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorPaths {
+    pub home: String,
+    pub snask_home: String,
+    pub runtime: String,
+    pub registry: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorToolchain {
+    pub clang: CheckResult,
+    pub llc: CheckResult,
+    pub gcc: CheckResult,
+    #[serde(rename = "llvm-strip")]
+    pub llvm_strip: CheckResult,
+    #[serde(rename = "ld.lld")]
+    pub ld_lld: CheckResult,
+    pub git: CheckResult,
+    #[serde(rename = "dpkg-deb", default, skip_serializing_if = "Option::is_none")]
+    pub dpkg_deb: Option<CheckResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appimagetool: Option<CheckResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorNativeDeps {
+    #[serde(rename = "gtk+-3.0", default, skip_serializing_if = "Option::is_none")]
+    pub gtk3: Option<CheckResult>,
+}
+
+/// State of something that lives under `~/.snask` (runtime, registry).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    pub found: bool,
+    pub path: String,
+    /// Set when `--fix` created it during this run.
+    pub fixed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DoctorStatus {
+    Ok,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorSummary {
+    pub status: DoctorStatus,
+    pub critical: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Everything `snask doctor` checks. Printed as text or, with `--json`, as-is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub os: String,
+    pub arch: String,
+    pub paths: DoctorPaths,
+    pub toolchain: DoctorToolchain,
+    pub native_deps: DoctorNativeDeps,
+    pub runtime: ComponentStatus,
+    pub registry: ComponentStatus,
+    pub summary: DoctorSummary,
+}
+
+const LLVM_HINT: &str = "sudo apt install clang-18 llvm-18 (or set SNASK_CLANG/SNASK_LLC)";
 
-    // Check OS
-    println!("OS: {}", std::env::consts::OS);
-    println!("Arch: {}", std::env::consts::ARCH);
+impl DoctorReport {
+    pub fn collect(home: &Path) -> Self {
+        let snask_home = home.join(".snask");
+        let runtime = runtime_path(home);
+        let registry = snask_home.join("registry");
 
-    // Check Tools
-    check_resolved_tool("clang", &toolchain::clang());
-    check_resolved_tool("llc", &toolchain::llc());
-    check_optional_tool("llvm-strip", toolchain::llvm_strip());
-    check_optional_tool("ld.lld", toolchain::ld_lld());
-    check_tool("git");
+        let mut clang = check_resolved_tool(&toolchain::clang());
+        let mut llc = check_resolved_tool(&toolchain::llc());
+        if !clang.found || !llc.found {
+            clang.hint = Some(LLVM_HINT.to_string());
+            llc.hint = Some(LLVM_HINT.to_string());
+        }
 
-    #[cfg(target_os = "linux")]
-    {
-        check_tool("dpkg-deb");
-        check_tool("appimagetool");
+        let linux = cfg!(target_os = "linux");
+        let gtk3 = linux.then(|| {
+            let found = has_pkg("gtk+-3.0");
+            CheckResult {
+                found,
+                path: None,
+                hint: (!found).then(|| "sudo apt install libgtk-3-dev".to_string()),
+                version: None,
+            }
+        });
+
+        let mut report = DoctorReport {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            paths: DoctorPaths {
+                home: home.display().to_string(),
+                snask_home: snask_home.display().to_string(),
+                runtime: runtime.display().to_string(),
+                registry: registry.display().to_string(),
+            },
+            toolchain: DoctorToolchain {
+                clang,
+                llc,
+                gcc: check_tool("gcc"),
+                llvm_strip: check_optional_tool(toolchain::llvm_strip()),
+                ld_lld: check_optional_tool(toolchain::ld_lld()),
+                git: check_tool("git"),
+                dpkg_deb: linux.then(|| check_tool("dpkg-deb")),
+                appimagetool: linux.then(|| check_tool("appimagetool")),
+            },
+            native_deps: DoctorNativeDeps { gtk3 },
+            runtime: ComponentStatus {
+                found: runtime.exists(),
+                path: runtime.display().to_string(),
+                fixed: false,
+                error: None,
+            },
+            registry: ComponentStatus {
+                found: registry_index(home).is_file(),
+                path: registry.display().to_string(),
+                fixed: false,
+                error: None,
+            },
+            summary: DoctorSummary {
+                status: DoctorStatus::Ok,
+                critical: Vec::new(),
+                warnings: Vec::new(),
+            },
+        };
+        report.summarize();
+        report
     }
 
-    // Check Runtime
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let runtime_path = format!("{}/.snask/lib/runtime.bc", home);
-    if Path::new(&runtime_path).exists() {
-        println!("✅ Runtime found at {}", runtime_path);
-    } else {
-        println!("❌ Runtime NOT found. Run `snask setup`.");
+    /// Recomputes `summary` from the individual checks.
+    pub fn summarize(&mut self) {
+        let mut critical = Vec::new();
+        let mut warnings = Vec::new();
+
+        let tc = &self.toolchain;
+        for (name, check) in [("clang", &tc.clang), ("llc", &tc.llc)] {
+            if !check.found {
+                critical.push(name.to_string());
+            }
+        }
+        if !self.runtime.found {
+            critical.push("runtime".to_string());
+        }
+
+        let optional = [
+            ("gcc", Some(&tc.gcc)),
+            ("llvm-strip", Some(&tc.llvm_strip)),
+            ("ld.lld", Some(&tc.ld_lld)),
+            ("git", Some(&tc.git)),
+            ("dpkg-deb", tc.dpkg_deb.as_ref()),
+            ("appimagetool", tc.appimagetool.as_ref()),
+            ("gtk+-3.0", self.native_deps.gtk3.as_ref()),
+        ];
+        for (name, check) in optional {
+            if matches!(check, Some(c) if !c.found) {
+                warnings.push(name.to_string());
+            }
+        }
+        if !self.registry.found {
+            warnings.push("registry".to_string());
+        }
+
+        let status = if !critical.is_empty() {
+            DoctorStatus::Critical
+        } else if !warnings.is_empty() {
+            DoctorStatus::Warning
+        } else {
+            DoctorStatus::Ok
+        };
+        self.summary = DoctorSummary {
+            status,
+            critical,
+            warnings,
+        };
     }
 
-    Ok(())
+    /// Repairs the runtime and the registry when possible. Never installs
+    /// system packages.
+    fn apply_fixes(&mut self, home: &Path) {
+        if !self.runtime.found && self.toolchain.clang.found {
+            println!("🔧 Runtime NOT found. Running `snask setup`...");
+            match repair_runtime(home, || build_runtime(None)) {
+                Ok(_) => {
+                    self.runtime.found = true;
+                    self.runtime.fixed = true;
+                }
+                Err(e) => self.runtime.error = Some(e),
+            }
+        }
+
+        if !self.registry.found {
+            println!("🔧 Registry NOT found. Fetching...");
+            // A cached registry would skip the clone this is meant to repair.
+            crate::packages::set_force_registry_refresh(true);
+            match crate::packages::fetch_registry() {
+                Ok(_) => {
+                    self.registry.found = registry_index(home).is_file();
+                    self.registry.fixed = self.registry.found;
+                }
+                Err(e) => self.registry.error = Some(e),
+            }
+        }
+
+        self.summarize();
+    }
+
+    fn print_text(&self) {
+        println!("🩺 Snask Doctor");
+        println!("----------------");
+
+        println!("OS: {}", self.os);
+        println!("Arch: {}", self.arch);
+
+        let tc = &self.toolchain;
+        print_check("clang", &tc.clang);
+        print_check("llc", &tc.llc);
+        if !tc.clang.found || !tc.llc.found {
+            println!("   hint: {}", LLVM_HINT);
+        }
+        print_check("gcc", &tc.gcc);
+        print_check("llvm-strip", &tc.llvm_strip);
+        print_check("ld.lld", &tc.ld_lld);
+        print_check("git", &tc.git);
+        if let Some(c) = &tc.dpkg_deb {
+            print_check("dpkg-deb", c);
+        }
+        if let Some(c) = &tc.appimagetool {
+            print_check("appimagetool", c);
+        }
+        if let Some(gtk) = &self.native_deps.gtk3 {
+            if !gtk.found {
+                println!("⚠️  gtk+-3.0: NOT FOUND (GUI disabled)");
+                if let Some(hint) = &gtk.hint {
+                    println!("   hint: {}", hint);
+                }
+            }
+        }
+
+        let rt = &self.runtime;
+        if rt.fixed {
+            println!("✅ Runtime installed at {}", rt.path);
+        } else if rt.found {
+            println!("✅ Runtime found at {}", rt.path);
+        } else if let Some(e) = &rt.error {
+            println!("❌ Runtime could not be built: {}", e);
+        } else {
+            println!("❌ Runtime NOT found. Run `snask setup`.");
+        }
+
+        let reg = &self.registry;
+        if reg.fixed {
+            println!("✅ Registry fetched.");
+        } else if reg.found {
+            println!("✅ Registry found at {}", reg.path);
+        } else if let Some(e) = &reg.error {
+            println!("⚠️  Registry could not be fetched: {}", e);
+        } else {
+            println!("⚠️  Registry NOT found. Run `snask doctor --fix` or `snask install <pkg>`.");
+        }
+    }
 }
 
-fn check_resolved_tool(label: &str, path: &PathBuf) {
-    if toolchain::command_exists(path) {
-        println!("✅ {}: {}", label, toolchain::tool_display(path));
+pub fn doctor(fix: bool, json: bool) -> Result<(), String> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
+    let mut report = DoctorReport::collect(&home);
+    if fix {
+        report.apply_fixes(&home);
+    }
+
+    if json {
+        let out = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+        println!("{}", out);
     } else {
-        println!("❌ {}: NOT FOUND", label);
+        report.print_text();
     }
+
+    if report.summary.status == DoctorStatus::Critical {
+        Err(format!(
+            "doctor found critical issue(s): {}",
+            report.summary.critical.join(", ")
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn runtime_path(home: &Path) -> PathBuf {
+    home.join(".snask").join("lib").join("runtime.bc")
 }
 
-fn check_optional_tool(label: &str, path: Option<PathBuf>) {
-    if let Some(path) = path {
-        println!("✅ {}: {}", label, toolchain::tool_display(&path));
+/// The index of the registry clone under `home`. The directory alone is not
+/// enough: an interrupted clone leaves it behind without the index.
+fn registry_index(home: &Path) -> PathBuf {
+    home.join(".snask").join("registry").join("registry.json")
+}
+
+/// Runs `setup` when the runtime under `home` is missing and checks that it
+/// actually produced it. Returns `Ok(false)` when nothing had to be done.
+fn repair_runtime(
+    home: &Path,
+    setup: impl FnOnce() -> Result<(), String>,
+) -> Result<bool, String> {
+    let runtime = runtime_path(home);
+    if runtime.exists() {
+        return Ok(false);
+    }
+    setup()?;
+    if runtime.exists() {
+        Ok(true)
     } else {
-        println!("❌ {}: NOT FOUND", label);
+        Err(format!(
+            "setup finished but {} is still missing",
+            runtime.display()
+        ))
+    }
+}
+
+fn print_check(label: &str, check: &CheckResult) {
+    match (check.found, &check.path) {
+        (true, Some(path)) => match &check.version {
+            Some(version) => println!("✅ {}: {} (LLVM {})", label, path, version),
+            None => println!("✅ {}: {}", label, path),
+        },
+        (true, None) => println!("✅ {}", label),
+        (false, _) => println!("❌ {}: NOT FOUND", label),
+    }
+}
+
+fn check_resolved_tool(path: &PathBuf) -> CheckResult {
+    let found = toolchain::command_exists(path);
+    CheckResult {
+        found,
+        path: found.then(|| toolchain::tool_display(path)),
+        hint: None,
+        version: found.then(|| toolchain::tool_version(path)).flatten(),
     }
 }
 
-fn check_tool(name: &str) {
+fn check_optional_tool(path: Option<PathBuf>) -> CheckResult {
+    CheckResult {
+        found: path.is_some(),
+        version: path.as_ref().and_then(toolchain::tool_version),
+        path: path.map(|p| toolchain::tool_display(&p)),
+        hint: None,
+    }
+}
+
+fn check_tool(name: &str) -> CheckResult {
     let status = Command::new("sh")
         .arg("-c")
         .arg(format!("command -v {}", name))
         .output();
 
     match status {
-        Ok(out) if out.status.success() => {
-            let path = String::from_utf8_lossy(&out.stdout).trim().to_string();
-            println!("✅ {}: {}", name, path);
-        }
-        _ => {
-            println!("❌ {}: NOT FOUND", name);
-        }
+        Ok(out) if out.status.success() => CheckResult {
+            found: true,
+            path: Some(String::from_utf8_lossy(&out.stdout).trim().to_string()),
+            hint: None,
+            version: None,
+        },
+        _ => CheckResult::default(),
     }
 }
 
@@ -82,7 +399,9 @@ pub fn cmd_size(path: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub fn run_setup(target: Option<String>) -> Result<(), String> {
+/// Compiles the runtime objects into `~/.snask/lib[/<target>]`. This is the
+/// non-interactive part of `snask setup`, also used by `snask doctor --fix`.
+pub fn build_runtime(target: Option<String>) -> Result<(), String> {
     println!("🔧 Setup Snask Toolchain");
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
     let snask_home = format!("{}/.snask", home);
@@ -114,7 +433,12 @@ pub fn run_setup(target: Option<String>) -> Result<(), String> {
 
     // 2. Check for Skia
     let mut extra_flags: Vec<String> = Vec::new();
-    if has_skia() {
+    let wasm = crate::compiler::is_wasm_target(target.as_deref());
+    if wasm {
+        // No GTK/Skia/dlopen in the browser or WASI; the runtime compiles without them.
+        println!("🕸️  WebAssembly target: building runtime without GUI/native extensions.");
+        extra_flags.extend(wasm_runtime_flags());
+    } else if has_skia() {
         println!("🎨 Skia detected! Enabling SNASK_SKIA in runtime.");
         extra_flags.push("-DSNASK_SKIA".to_string());
         // We could also add pkg-config --cflags skia here
@@ -125,7 +449,7 @@ pub fn run_setup(target: Option<String>) -> Result<(), String> {
         }
     }
 
-    if has_pkg("gtk+-3.0") {
+    if !wasm && has_pkg("gtk+-3.0") {
         println!("🖼️  GTK3 detected! Enabling SNASK_GUI_GTK in runtime.");
         extra_flags.push("-DSNASK_GUI_GTK".to_string());
         if let Ok(cflags) = get_pkg_cflags("gtk+-3.0") {
@@ -145,6 +469,21 @@ pub fn run_setup(target: Option<String>) -> Result<(), String> {
         extra_flags_refs,
     )?;
 
+    // 3b. Shared runtime for `snask run --jit` (host only; optional)
+    if target.is_none() && !wasm {
+        println!("📦 Compiling shared runtime for --jit (runtime.so)...");
+        let mut cmd = Command::new(toolchain::clang());
+        cmd.args(["-shared", "-fPIC", "-O2"])
+            .arg(&runtime_src)
+            .arg("-o")
+            .arg(format!("{}/runtime.so", lib_dir))
+            .args(&extra_flags)
+            .args(crate::compiler::get_runtime_linkargs_for(None, false));
+        if !cmd.status().is_ok_and(|s| s.success()) {
+            println!("⚠️  Failed to compile runtime.so; `snask run --jit` will fall back to AOT.");
+        }
+    }
+
     // 4. Compile Tiny Runtime
     println!("📦 Compiling tiny runtime (runtime_tiny.o, runtime_tiny.bc)...");
     let mut tiny_flags = extra_flags.clone();
@@ -192,6 +531,22 @@ pub fn run_setup(target: Option<String>) -> Result<(), String> {
     }
 
     println!("✅ Setup completed successfully.");
+    Ok(())
+}
+
+/// `-DSNASK_WASM` leaves the HTTP, GUI, auth, Blaze and test modules out of
+/// `runtime.c`; `WASI_SYSROOT` points clang at the WASI libc headers.
+fn wasm_runtime_flags() -> Vec<String> {
+    let mut flags = vec!["-DSNASK_WASM".to_string()];
+    if let Ok(sysroot) = std::env::var("WASI_SYSROOT") {
+        flags.push(format!("--sysroot={}", sysroot));
+    }
+    flags
+}
+
+pub fn run_setup(target: Option<String>) -> Result<(), String> {
+    build_runtime(target)?;
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
 
     // --- Neovim setup prompt ---
     print!("\n➜ Deseja configurar o suporte ao Neovim para Snask? (s/N): ");
@@ -340,10 +695,9 @@ fn compile_runtime(
     target: &Option<String>,
     extra_args: Vec<&str>,
 ) -> Result<(), String> {
-    let mut base_args = vec!["-c", src];
+    let mut base_args = vec!["-c".to_string(), src.to_string()];
     if let Some(t) = target {
-        base_args.push("--target=");
-        base_args.push(t);
+        base_args.push(format!("--target={}", t));
     }
 
     // .o
@@ -401,3 +755,135 @@ pub fn init_zenith_project(name: Option<String>) -> Result<(), String> {
     println!("Created project '{}'.", n);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        registry_index, repair_runtime, runtime_path, wasm_runtime_flags, DoctorReport,
+        DoctorStatus,
+    };
+    use crate::toolchain;
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn doctor_fix_builds_missing_runtime() {
+        let home = std::env::temp_dir().join(format!("snask_doctor_fix_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+
+        let runtime = runtime_path(&home);
+        let repaired = repair_runtime(&home, || {
+            fs::create_dir_all(runtime.parent().unwrap()).map_err(|e| e.to_string())?;
+            fs::write(&runtime, b"bc").map_err(|e| e.to_string())
+        })
+        .unwrap();
+        assert!(repaired);
+        assert!(runtime.exists());
+
+        // Already present: setup must not run again.
+        let again = repair_runtime(&home, || Err("setup should not run".to_string())).unwrap();
+        assert!(!again);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn doctor_fix_reports_setup_that_leaves_runtime_missing() {
+        let home = std::env::temp_dir().join(format!("snask_doctor_nofix_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+
+        let err = repair_runtime(&home, || Ok(())).unwrap_err();
+        assert!(err.contains("still missing"), "{err}");
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn doctor_json_report_round_trips_with_critical_status() {
+        let home = std::env::temp_dir().join(format!("snask_doctor_json_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+
+        // Empty HOME: no runtime, so the report is always critical.
+        let report = DoctorReport::collect(&home);
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["summary"]["status"], "critical");
+        assert_eq!(value["runtime"]["found"], false);
+        assert!(value["toolchain"]["clang"].is_object());
+
+        let back: DoctorReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.summary.status, DoctorStatus::Critical);
+        assert!(back.summary.critical.contains(&"runtime".to_string()));
+        assert_eq!(back, report);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn doctor_needs_the_registry_index_not_just_its_directory() {
+        let home = std::env::temp_dir().join(format!("snask_doctor_reg_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let index = registry_index(&home);
+        fs::create_dir_all(index.parent().unwrap()).unwrap();
+
+        // A half-finished clone: the directory exists, the index does not.
+        assert!(!DoctorReport::collect(&home).registry.found);
+
+        fs::write(&index, r#"{"packages": {}}"#).unwrap();
+        assert!(DoctorReport::collect(&home).registry.found);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn wasm_runtime_leaves_out_native_modules_and_compiles() {
+        let clang = toolchain::clang();
+        if !toolchain::command_exists(&clang) {
+            eprintln!("skipping: clang not found");
+            return;
+        }
+        let runtime = concat!(env!("CARGO_MANIFEST_DIR"), "/src/runtime.c");
+        let preprocessed = Command::new(&clang)
+            .args(["-E", "-DSNASK_WASM", runtime])
+            .output()
+            .unwrap();
+        assert!(preprocessed.status.success());
+        let source = String::from_utf8_lossy(&preprocessed.stdout);
+        let native_only = ["rt_http.c", "rt_gui.c", "rt_auth.c", "rt_blaze.c", "rt_test.c"];
+        for module in native_only {
+            assert!(!source.contains(module), "{module} is still included");
+        }
+        assert!(source.contains("rt_json.c"));
+
+        let dir = std::env::temp_dir().join(format!("snask_wasm_rt_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let probe = dir.join("probe.c");
+        fs::write(&probe, "#include <stdio.h>\nint x;\n").unwrap();
+        let compile = |src: &std::path::Path, out: &str| {
+            Command::new(&clang)
+                .args(["--target=wasm32-wasi", "-c"])
+                .arg(src)
+                .arg("-o")
+                .arg(dir.join(out))
+                .args(wasm_runtime_flags())
+                .output()
+                .unwrap()
+        };
+        if !compile(&probe, "probe.o").status.success() {
+            eprintln!("skipping wasm compile: no WASI sysroot (set WASI_SYSROOT)");
+            let _ = fs::remove_dir_all(&dir);
+            return;
+        }
+        let out = compile(std::path::Path::new(runtime), "runtime.o");
+        assert!(
+            out.status.success(),
+            "runtime does not compile for wasm32-wasi:\n{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
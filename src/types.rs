@@ -26,6 +26,7 @@ pub enum Type {
     Ptr,
     User(String),
     Function(Vec<Type>, Box<Type>), // param_types, return_type
+    Tuple(Vec<Type>),               // `(int, str)`, fixed arity
 }
 
 impl Type {
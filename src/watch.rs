@@ -0,0 +1,211 @@
+//! `snask build --watch` / `snask run --watch`: rebuild whenever the entry
+//! file, a module it imports or `snask.snif` changes.
+//!
+//! The parent directories of those files are watched rather than the files
+//! themselves, since editors often save by writing a new file and renaming
+//! it over the old one. Ctrl-C ends the session (and, for `run`, the
+//! program, which shares the terminal's process group).
+
+use crate::compiler;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Changes closer together than this trigger a single rebuild.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Collapses a burst of change events into one rebuild, which is due once no
+/// new event has arrived for `window`.
+#[derive(Debug)]
+pub struct Debounce {
+    window: Duration,
+    last_event: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(window: Duration) -> Self {
+        Debounce {
+            window,
+            last_event: None,
+        }
+    }
+
+    pub fn record(&mut self, at: Instant) {
+        self.last_event = Some(at);
+    }
+
+    /// Time left before the current burst counts as over; `None` when no
+    /// change is pending.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.last_event.map(|at| {
+            self.window
+                .saturating_sub(now.saturating_duration_since(at))
+        })
+    }
+
+    /// `true` once per burst, when it has been quiet for the whole window.
+    pub fn settled(&mut self, now: Instant) -> bool {
+        if self.remaining(now) == Some(Duration::ZERO) {
+            self.last_event = None;
+            return true;
+        }
+        false
+    }
+}
+
+/// The files a rebuild depends on: the entry, every module it imports and
+/// the manifest, if there is one. Paths are canonical where they exist.
+pub fn watched_files(entry: &Path, manifest: Option<&Path>) -> BTreeSet<PathBuf> {
+    compiler::source_files(entry)
+        .into_iter()
+        .chain(manifest.map(Path::to_path_buf))
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect()
+}
+
+/// Calls `cycle` right away and again after every settled change to the
+/// watched files. The file set is recomputed after each cycle, so new
+/// imports are picked up. Only returns when the watcher fails.
+pub fn watch(entry: &Path, manifest: Option<&Path>, mut cycle: impl FnMut()) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("watch: cannot start: {}", e))?;
+    let mut dirs: BTreeSet<PathBuf> = BTreeSet::new();
+    loop {
+        cycle();
+        let files = watched_files(entry, manifest);
+        let wanted: BTreeSet<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.parent())
+            .map(Path::to_path_buf)
+            .collect();
+        for dir in dirs.difference(&wanted) {
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in wanted.difference(&dirs) {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|e| format!("watch: cannot watch {}: {}", dir.display(), e))?;
+        }
+        dirs = wanted;
+        println!(
+            "👀 Watching {} file(s) for changes (Ctrl-C to stop)",
+            files.len()
+        );
+        wait_for_change(&rx, &files)?;
+        println!("🔁 Change detected, rebuilding...");
+    }
+}
+
+/// Blocks until one of `files` changed and [`DEBOUNCE`] passed without
+/// another change.
+fn wait_for_change(
+    rx: &mpsc::Receiver<notify::Result<Event>>,
+    files: &BTreeSet<PathBuf>,
+) -> Result<(), String> {
+    let stopped = || "watch: the file watcher stopped".to_string();
+    let mut debounce = Debounce::new(DEBOUNCE);
+    loop {
+        let received = match debounce.remaining(Instant::now()) {
+            None => rx.recv().map_err(|_| stopped())?,
+            Some(wait) => match rx.recv_timeout(wait) {
+                Ok(received) => received,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if debounce.settled(Instant::now()) {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err(stopped()),
+            },
+        };
+        // Errors from the watcher itself (e.g. an overflowed queue) are not
+        // changes; the next real event still triggers a rebuild.
+        if let Ok(event) = received {
+            if touches(&event, files) {
+                debounce.record(Instant::now());
+            }
+        }
+    }
+}
+
+fn touches(event: &Event, files: &BTreeSet<PathBuf>) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|path| files.contains(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{watched_files, Debounce};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn debounce_waits_for_a_quiet_window_after_the_last_change() {
+        let window = Duration::from_millis(200);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut debounce = Debounce::new(window);
+        assert_eq!(debounce.remaining(at(0)), None);
+        assert!(!debounce.settled(at(1000)));
+
+        debounce.record(at(0));
+        debounce.record(at(150));
+        assert!(!debounce.settled(at(300)));
+        assert_eq!(debounce.remaining(at(300)), Some(Duration::from_millis(50)));
+        assert!(debounce.settled(at(350)));
+        // One rebuild per burst.
+        assert!(!debounce.settled(at(400)));
+        assert_eq!(debounce.remaining(at(400)), None);
+    }
+
+    #[test]
+    fn watched_files_follow_imports_and_include_the_manifest() {
+        let dir = std::env::temp_dir().join(format!("snask_watch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("lib")).expect("temp dir should be created");
+        fs::write(
+            dir.join("main.snask"),
+            "import \"util\"\nimport \"json\"\nimport \"util\" as u\n\
+class main\n    fun start()\n        print(util::twice(2))\n",
+        )
+        .expect("entry should be written");
+        fs::write(
+            dir.join("util.snask"),
+            "from lib import helpers\nfun twice(n: int) : int\n    return n * 2\n",
+        )
+        .expect("module should be written");
+        fs::write(
+            dir.join("lib/helpers.snask"),
+            "fun one() : int\n    return 1\n",
+        )
+        .expect("nested module should be written");
+        fs::write(dir.join("unused.snask"), "fun unused()\n    print(1)\n")
+            .expect("unrelated file should be written");
+        fs::write(dir.join("snask.snif"), "{ package: { name: \"demo\" } }")
+            .expect("manifest should be written");
+
+        let files = watched_files(&dir.join("main.snask"), Some(&dir.join("snask.snif")));
+        let root = dir.canonicalize().expect("temp dir should resolve");
+        let _ = fs::remove_dir_all(&dir);
+
+        let names: Vec<PathBuf> = files
+            .iter()
+            .map(|path| path.strip_prefix(&root).unwrap_or(path).to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                PathBuf::from("lib/helpers.snask"),
+                PathBuf::from("main.snask"),
+                PathBuf::from("snask.snif"),
+                PathBuf::from("util.snask"),
+            ]
+        );
+    }
+}